@@ -11,7 +11,7 @@ class Account(BaseAccount):
     def __init__(
         self,
         public_key: str,
-        secret_key: str,
+        secret_key: Optional[str],
         window: int = 5000,
         proxy: Optional[dict] = None,
         debug: bool = False,
@@ -1,6 +1,5 @@
-from cryptography.hazmat.primitives.asymmetric import ed25519
-import base64
-from typing import Optional, Union
+from typing import Callable, Optional, Union
+from bpx import signing
 from bpx.models.objects import RequestConfiguration
 from time import time
 from bpx.exceptions import *
@@ -15,14 +14,17 @@ class BaseAccount:
 
     BPX_API_URL = "https://api.backpack.exchange/"
 
-    def __init__(self, public_key: str, secret_key: str, window: int, debug: bool):
+    def __init__(self, public_key: str, secret_key: Optional[str], window: int, debug: bool):
 
-        self.private_key = ed25519.Ed25519PrivateKey.from_private_bytes(
-            base64.b64decode(secret_key)
-        )
+        self.private_key = signing.load_private_key(secret_key) if secret_key is not None else None
         self.public_key = public_key
         self.window = window
         self.debug = debug
+        # Optional callable returning epoch ms; lets callers correct for local clock drift
+        self.timestamp_provider: Optional[Callable[[], int]] = None
+        # Optional callable returning the base64 signature of a signing string; replaces the
+        # local key, so the secret can live in a remote signer or hardware device instead
+        self.signer: Optional[Callable[[str], str]] = None
 
     def get_account(self, window: Optional[int] = None) -> RequestConfiguration:
         """
@@ -768,12 +770,20 @@ class BaseAccount:
         request_config = RequestConfiguration(url=url, headers=headers, data=params)
         return request_config
 
-    def _headers(self, params: dict, instruction: str, window: Optional[int]) -> dict:
+    def get_timestamp(self) -> int:
+        """
+        Returns the timestamp used for request signing, in milliseconds
+        """
+        if self.timestamp_provider is not None:
+            return self.timestamp_provider()
+        return int(time() * 1e3)
+
+    def _headers(self, params: signing.Params, instruction: str, window: Optional[int]) -> dict:
         """
         Returns headers for the given instruction and params
         """
         window = self.window if window is None else window
-        timestamp = int(time() * 1e3)
+        timestamp = self.get_timestamp()
         encoded_signature = self._sign(params, instruction, timestamp, window)
         headers = {
             "X-API-Key": self.public_key,
@@ -786,22 +796,15 @@ class BaseAccount:
             print(headers)
         return headers
 
-    def _sign(self, params: dict, instruction: str, timestamp: int, window: int):
+    def _sign(self, params: signing.Params, instruction: str, timestamp: int, window: int):
         """
         Returns encoded signature for given parameters, instruction, timestamp and window
         """
-        sign_str = f"instruction={instruction}"
-        sorted_params_list = []
-        for key, value in sorted(params.items()):
-            if isinstance(value, bool):
-                value = str(value).lower()
-            sorted_params_list.append(f"{key}={value}")
-        sorted_params = "&".join(sorted_params_list)
-        if sorted_params:
-            sign_str += "&" + sorted_params
-        sign_str += f"&timestamp={timestamp}&window={window}"
+        sign_str = signing.signing_string(instruction, params, timestamp, window)
         if self.debug:
             print(sign_str)
-        signature_bytes = self.private_key.sign(sign_str.encode())
-        encoded_signature = base64.b64encode(signature_bytes).decode()
-        return encoded_signature
+        if self.signer is not None:
+            return self.signer(sign_str)
+        if self.private_key is None:
+            raise ValueError("no secret key or signer configured")
+        return signing.sign(self.private_key, sign_str)
@@ -81,7 +81,7 @@ SelfTradePreventionType = Literal["RejectTaker", "RejectMaker", "RejectBoth", "A
 
 class BorrowLendSideEnum(str, Enum):
     BORROW = "Borrow"
-    LEND = "Bend"
+    LEND = "Lend"
 
     @classmethod
     def has_value(cls, value):
@@ -1,5 +1,5 @@
 import aiohttp
-from typing import Union, List, Dict, Any, Awaitable
+from typing import Optional, Union, List, Dict, Any, Awaitable
 from bpx.http_client.base.http_client import HttpClient
 import json
 import certifi
@@ -8,16 +8,37 @@ import ssl
 
 class AsyncHttpClient(HttpClient):
 
-    def __init__(self, proxy: str = ""):
+    def __init__(self, proxy: str = "", timeout: float = 10.0):
         self.proxy = proxy
+        self.timeout = timeout
+
+    def _session(self) -> "tuple[aiohttp.ClientSession, Optional[str]]":
+        """
+        Returns a session and the per-request proxy; SOCKS proxies need aiohttp-socks
+        and are applied through the connector instead
+        """
+        if self.proxy and self.proxy.startswith("socks"):
+            from aiohttp_socks import ProxyConnector
+
+            return aiohttp.ClientSession(connector=ProxyConnector.from_url(self.proxy)), None
+        return aiohttp.ClientSession(), self.proxy or None
+
+    def _timeout(self, timeout: Optional[float]) -> aiohttp.ClientTimeout:
+        return aiohttp.ClientTimeout(total=self.timeout if timeout is None else timeout)
 
     async def get(
-        self, url, headers=None, params=None
+        self, url, headers=None, params=None, timeout: Optional[float] = None
     ) -> Union[Dict[str, Any], List[Any], str]:
         ssl_context = ssl.create_default_context(cafile=certifi.where())
-        async with aiohttp.ClientSession() as session:
+        session, proxy = self._session()
+        async with session:
             async with session.get(
-                url, proxy=self.proxy, params=params, headers=headers, ssl=ssl_context
+                url,
+                proxy=proxy,
+                params=params,
+                headers=headers,
+                ssl=ssl_context,
+                timeout=self._timeout(timeout),
             ) as response:
 
                 try:
@@ -28,16 +49,18 @@ class AsyncHttpClient(HttpClient):
                     return await response.text()
 
     async def post(
-        self, url, headers=None, data=None
+        self, url, headers=None, data=None, timeout: Optional[float] = None
     ) -> Union[Dict[str, Any], List[Any], str]:
         ssl_context = ssl.create_default_context(cafile=certifi.where())
-        async with aiohttp.ClientSession() as session:
+        session, proxy = self._session()
+        async with session:
             async with session.post(
                 url,
-                proxy=self.proxy,
+                proxy=proxy,
                 headers=headers,
                 data=json.dumps(data),
                 ssl=ssl_context,
+                timeout=self._timeout(timeout),
             ) as response:
 
                 try:
@@ -48,16 +71,18 @@ class AsyncHttpClient(HttpClient):
                     return await response.text()
 
     async def delete(
-        self, url, headers=None, data=None
+        self, url, headers=None, data=None, timeout: Optional[float] = None
     ) -> Union[Dict[str, Any], List[Any], str]:
         ssl_context = ssl.create_default_context(cafile=certifi.where())
-        async with aiohttp.ClientSession() as session:
+        session, proxy = self._session()
+        async with session:
             async with session.delete(
                 url,
-                proxy=self.proxy,
+                proxy=proxy,
                 headers=headers,
                 data=json.dumps(data),
                 ssl=ssl_context,
+                timeout=self._timeout(timeout),
             ) as response:
 
                 try:
@@ -67,15 +92,17 @@ class AsyncHttpClient(HttpClient):
                 except aiohttp.client_exceptions.ContentTypeError:
                     return await response.text()
 
-    async def patch(self, url, headers=None, data=None):
+    async def patch(self, url, headers=None, data=None, timeout: Optional[float] = None):
         ssl_context = ssl.create_default_context(cafile=certifi.where())
-        async with aiohttp.ClientSession() as session:
+        session, proxy = self._session()
+        async with session:
             async with session.patch(
                 url,
-                proxy=self.proxy,
+                proxy=proxy,
                 headers=headers,
                 data=json.dumps(data),
                 ssl=ssl_context,
+                timeout=self._timeout(timeout),
             ) as response:
 
                 try:
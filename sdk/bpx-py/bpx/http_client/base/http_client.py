@@ -3,21 +3,21 @@ import abc
 
 class HttpClient(abc.ABC):
     @abc.abstractmethod
-    def get(self, url, headers=None, params=None):
+    def get(self, url, headers=None, params=None, timeout=None):
         """Perform a GET request."""
         pass
 
     @abc.abstractmethod
-    def post(self, url, headers=None, data=None):
+    def post(self, url, headers=None, data=None, timeout=None):
         """Perform a POST request."""
         pass
 
     @abc.abstractmethod
-    def delete(self, url, headers=None):
+    def delete(self, url, headers=None, timeout=None):
         """Perform a DELETE request"""
         pass
 
     @abc.abstractmethod
-    def patch(self, url, headers=None, data=None):
+    def patch(self, url, headers=None, data=None, timeout=None):
         """Perform a PATCH request"""
         pass
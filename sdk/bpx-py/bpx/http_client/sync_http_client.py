@@ -1,18 +1,20 @@
 import requests
-from typing import Dict, Any, List, Union
+from typing import Dict, Any, List, Optional, Union
 from bpx.http_client.base.http_client import HttpClient
 import json
 
 
 class SyncHttpClient(HttpClient):
-    def __init__(self, proxies: dict = None):
+    def __init__(self, proxies: dict = None, timeout: float = 10.0):
         self.proxies = proxies
+        self.timeout = timeout
 
     def get(
-        self, url, headers=None, params=None
+        self, url, headers=None, params=None, timeout: Optional[float] = None
     ) -> Union[Dict[str, Any], List[Any], str]:
         response = requests.get(
-            url=url, proxies=self.proxies, headers=headers, params=params
+            url=url, proxies=self.proxies, headers=headers, params=params,
+            timeout=self.timeout if timeout is None else timeout,
         )
 
         try:
@@ -21,10 +23,11 @@ class SyncHttpClient(HttpClient):
             return response.text
 
     def post(
-        self, url, headers=None, data=None
+        self, url, headers=None, data=None, timeout: Optional[float] = None
     ) -> Union[Dict[str, Any], List[Any], str]:
         response = requests.post(
-            url=url, proxies=self.proxies, headers=headers, json=data
+            url=url, proxies=self.proxies, headers=headers, json=data,
+            timeout=self.timeout if timeout is None else timeout,
         )
         try:
             return response.json()
@@ -32,10 +35,11 @@ class SyncHttpClient(HttpClient):
             return response.text
 
     def delete(
-        self, url, headers=None, data=None
+        self, url, headers=None, data=None, timeout: Optional[float] = None
     ) -> Union[Dict[str, Any], List[Any], str]:
         response = requests.delete(
-            url, proxies=self.proxies, headers=headers, json=data
+            url, proxies=self.proxies, headers=headers, json=data,
+            timeout=self.timeout if timeout is None else timeout,
         )
         try:
             return response.json()
@@ -43,9 +47,12 @@ class SyncHttpClient(HttpClient):
             return response.text
 
     def patch(
-        self, url, headers=None, data=None
+        self, url, headers=None, data=None, timeout: Optional[float] = None
     ) -> Union[Dict[str, Any], List[Any], str]:
-        response = requests.patch(url, proxies=self.proxies, headers=headers, json=data)
+        response = requests.patch(
+            url, proxies=self.proxies, headers=headers, json=data,
+            timeout=self.timeout if timeout is None else timeout,
+        )
         try:
             return response.json()
         except json.JSONDecodeError:
@@ -0,0 +1,168 @@
+"""
+Pure request-signing helpers for the Backpack API.
+
+Everything here is deterministic given its inputs (ed25519 signatures are
+deterministic), so signing strings and signatures can be pinned by fixtures.
+"""
+
+import base64
+import binascii
+from typing import Any, Dict, List, Mapping, Optional, Sequence, Tuple, Union
+
+from cryptography.hazmat.primitives.asymmetric import ed25519
+
+Params = Union[Mapping[str, Any], Sequence[Mapping[str, Any]]]
+
+# (HTTP method, path) -> signing instruction
+INSTRUCTIONS: Dict[Tuple[str, str], str] = {
+    ("GET", "api/v1/account"): "accountQuery",
+    ("PATCH", "api/v1/account"): "accountUpdate",
+    ("GET", "api/v1/account/limits/borrow"): "maxBorrowQuantity",
+    ("GET", "api/v1/account/limits/order"): "maxOrderQuantity",
+    ("GET", "api/v1/account/limits/withdrawal"): "maxWithdrawalQuantity",
+    ("GET", "api/v1/borrowLend/positions"): "borrowLendPositionQuery",
+    ("POST", "api/v1/borrowLend"): "borrowLendExecute",
+    ("GET", "api/v1/capital"): "balanceQuery",
+    ("GET", "api/v1/capital/collateral"): "collateralQuery",
+    ("GET", "wapi/v1/capital/deposits"): "depositQueryAll",
+    ("GET", "wapi/v1/capital/deposit/address"): "depositAddressQuery",
+    ("GET", "wapi/v1/capital/withdrawals"): "withdrawalQueryAll",
+    ("POST", "wapi/v1/capital/withdrawals"): "withdraw",
+    ("GET", "api/v1/position"): "positionQuery",
+    ("GET", "wapi/v1/history/borrowLend"): "borrowHistoryQueryAll",
+    ("GET", "wapi/v1/history/interest"): "interestHistoryQueryAll",
+    ("GET", "wapi/v1/history/fills"): "fillHistoryQueryAll",
+    ("GET", "wapi/v1/history/funding"): "fundingHistoryQueryAll",
+    ("GET", "wapi/v1/history/orders"): "orderHistoryQueryAll",
+    ("GET", "wapi/v1/history/pnl"): "pnlHistoryQueryAll",
+    ("GET", "wapi/v1/history/settlement"): "settlementHistoryQueryAll",
+    ("GET", "api/v1/order"): "orderQuery",
+    ("POST", "api/v1/order"): "orderExecute",
+    ("DELETE", "api/v1/order"): "orderCancel",
+    ("GET", "api/v1/orders"): "orderQueryAll",
+    ("POST", "api/v1/orders"): "orderExecute",  # batch: one instruction per order
+    ("DELETE", "api/v1/orders"): "orderCancelAll",
+    ("POST", "api/v1/rfq/quote"): "quoteSubmit",
+}
+
+
+class UnknownInstructionError(KeyError):
+    """
+    Raised for endpoints missing from the instruction table, instead of
+    sending a request the exchange would reject as unsigned
+    """
+
+
+def instruction_for(method: str, path: str) -> str:
+    """
+    Returns the signing instruction for an HTTP method and API path
+    """
+    key = (method.upper(), path.lstrip("/").split("?", 1)[0].rstrip("/"))
+    try:
+        return INSTRUCTIONS[key]
+    except KeyError:
+        raise UnknownInstructionError(f"no signing instruction for {key[0]} {key[1]}") from None
+
+
+def _encode_value(value: Any) -> str:
+    if isinstance(value, bool):
+        return str(value).lower()
+    return str(value)
+
+
+def _encode_params(params: Mapping[str, Any]) -> str:
+    return "&".join(
+        f"{key}={_encode_value(value)}" for key, value in sorted(params.items()) if value is not None
+    )
+
+
+def signing_string(instruction: str, params: Optional[Params], timestamp: int, window: int) -> str:
+    """
+    Returns the exact string to sign.
+
+    A mapping is signed as `instruction=X&k1=v1&...`; a sequence of mappings
+    (batch orders) repeats `instruction=X&...` per item, joined with `&`.
+    Keys are sorted, booleans lowercased and None values skipped; the
+    `timestamp` and `window` suffix is always appended.
+    """
+    if params is None or isinstance(params, Mapping):
+        items: List[Mapping[str, Any]] = [params or {}]
+    else:
+        items = list(params) or [{}]
+    parts: List[str] = []
+    for item in items:
+        part = f"instruction={instruction}"
+        encoded = _encode_params(item)
+        if encoded:
+            part += "&" + encoded
+        parts.append(part)
+    return "&".join(parts) + f"&timestamp={timestamp}&window={window}"
+
+
+class InvalidKeyError(ValueError):
+    """
+    Raised for a key that is not a base64-encoded 32-byte ed25519 key; the
+    message names the key but never includes its value
+    """
+
+
+def _decode_key(value: str, name: str) -> bytes:
+    try:
+        raw = base64.b64decode(value.strip(), validate=True)
+    except (binascii.Error, ValueError):
+        raise InvalidKeyError(f"{name} is not valid base64") from None
+    if len(raw) != 32:
+        raise InvalidKeyError(f"{name} must decode to 32 bytes, got {len(raw)}")
+    return raw
+
+
+def load_private_key(secret_key: str) -> ed25519.Ed25519PrivateKey:
+    """
+    Returns the ed25519 key for a base64-encoded 32-byte seed
+    """
+    return ed25519.Ed25519PrivateKey.from_private_bytes(_decode_key(secret_key, "api secret"))
+
+
+def public_key_b64(private_key: ed25519.Ed25519PrivateKey) -> str:
+    """
+    Returns the base64-encoded public key, the form Backpack expects as API key
+    """
+    return base64.b64encode(private_key.public_key().public_bytes_raw()).decode()
+
+
+def validate_keypair(public_key: str, secret_key: str) -> ed25519.Ed25519PrivateKey:
+    """
+    Returns the private key after checking that the API key is its public key
+    """
+    private_key = load_private_key(secret_key)
+    if _decode_key(public_key, "api key") != private_key.public_key().public_bytes_raw():
+        raise InvalidKeyError("api key is not the public key of api secret")
+    return private_key
+
+
+def sign(private_key: ed25519.Ed25519PrivateKey, message: str) -> str:
+    """
+    Returns the base64-encoded ed25519 signature of message
+    """
+    return base64.b64encode(private_key.sign(message.encode())).decode()
+
+
+def signature_headers(
+    *,
+    private_key: ed25519.Ed25519PrivateKey,
+    public_key: str,
+    instruction: str,
+    params: Optional[Params],
+    timestamp: int,
+    window: int,
+) -> Dict[str, str]:
+    """
+    Returns the authentication headers for a signed request
+    """
+    return {
+        "X-API-Key": public_key,
+        "X-Signature": sign(private_key, signing_string(instruction, params, timestamp, window)),
+        "X-Timestamp": str(timestamp),
+        "X-Window": str(window),
+        "Content-Type": "application/json; charset=utf-8",
+    }
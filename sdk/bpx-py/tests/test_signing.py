@@ -0,0 +1,191 @@
+import base64
+
+import pytest
+
+from bpx import signing
+from bpx.base.base_account import BaseAccount
+
+# RFC 8032 section 7.1, TEST 1 secret key (as a base64 seed, the format Backpack issues)
+SECRET_KEY = "nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A="
+PUBLIC_KEY = "11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo="
+TIMESTAMP = 1700000000000
+WINDOW = 5000
+
+GOLDEN = {
+    "empty_body": (
+        "balanceQuery",
+        None,
+        "instruction=balanceQuery&timestamp=1700000000000&window=5000",
+        "BT0fEucf+pN/4SqmbIL5fzaFq76mkKkvFnNT2FOoNFoSpBMQHiDoEnX7O45Cg8csHL7mPBqciUbMTF3+lpSOAQ==",
+    ),
+    "limit_order": (
+        "orderExecute",
+        {
+            "symbol": "SOL_USDC",
+            "side": "Bid",
+            "orderType": "Limit",
+            "price": "141.25",
+            "quantity": "0.5",
+            "timeInForce": "GTC",
+            "postOnly": True,
+            "clientId": 42,
+        },
+        "instruction=orderExecute&clientId=42&orderType=Limit&postOnly=true&price=141.25"
+        "&quantity=0.5&side=Bid&symbol=SOL_USDC&timeInForce=GTC&timestamp=1700000000000&window=5000",
+        "YvDBxFafhwcifuNNLO7ga+4VGeJM3JBiheIV4tGm4+qjaVFfFvuOzCgHN8b76ZUNy39YijON7Wpv9fOsM55IAQ==",
+    ),
+    "query_params": (
+        "fillHistoryQueryAll",
+        {"symbol": "SOL_USDC", "limit": 100, "offset": 0},
+        "instruction=fillHistoryQueryAll&limit=100&offset=0&symbol=SOL_USDC&timestamp=1700000000000&window=5000",
+        "g9/aGm8yOJgjaeZeRZPuytBbBwzoriTGmaTmGISYf1av5ZKy/P7qU0pO3kR1KQqM7rVJuoKFSa/hcAQ6GTa/AA==",
+    ),
+    "cancel": (
+        "orderCancel",
+        {"symbol": "SOL_USDC", "orderId": "111947294212554752"},
+        "instruction=orderCancel&orderId=111947294212554752&symbol=SOL_USDC&timestamp=1700000000000&window=5000",
+        "HxvZxI0dROsAQWV/zkVhl1Q3Mvp+8Jo3Y/ek4CCFkSMnDJPTIPzC4IDvvg3HYXCq60bAPZLTUY1fCaOxhqs6Bg==",
+    ),
+    "batch_orders": (
+        "orderExecute",
+        [
+            {"symbol": "SOL_USDC", "side": "Bid", "orderType": "Limit", "price": "140", "quantity": "1"},
+            {"symbol": "SOL_USDC", "side": "Ask", "orderType": "Limit", "price": "150", "quantity": "1", "reduceOnly": False},
+        ],
+        "instruction=orderExecute&orderType=Limit&price=140&quantity=1&side=Bid&symbol=SOL_USDC"
+        "&instruction=orderExecute&orderType=Limit&price=150&quantity=1&reduceOnly=false&side=Ask&symbol=SOL_USDC"
+        "&timestamp=1700000000000&window=5000",
+        "bImF+yDIt9qNWm3ZhWwnvIfXE/abivKYTBSi4FHdff9OeReVuW742LRiwWYaB97DUY060Huxwzi3qA/FDg28Bw==",
+    ),
+    "ws_subscribe": (
+        "subscribe",
+        {},
+        "instruction=subscribe&timestamp=1700000000000&window=5000",
+        "pJ9+IStoG6LwKDRJOZJX1r/etHlofXr5YcNQhi0oxKhKGkrJgKE+Fu9EET26F6KL+HmqfdQlq6up1nrzDH1CCg==",
+    ),
+}
+
+
+@pytest.fixture
+def private_key():
+    return signing.load_private_key(SECRET_KEY)
+
+
+def test_rfc8032_vector(private_key):
+    # TEST 1 from RFC 8032: empty message
+    expected = (
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+    )
+    assert base64.b64decode(signing.sign(private_key, "")).hex() == expected
+
+
+@pytest.mark.parametrize("name", sorted(GOLDEN))
+def test_golden_signing_strings(name):
+    instruction, params, expected, _signature = GOLDEN[name]
+    assert signing.signing_string(instruction, params, TIMESTAMP, WINDOW) == expected
+
+
+@pytest.mark.parametrize("name", sorted(GOLDEN))
+def test_golden_signatures(private_key, name):
+    instruction, params, expected, signature = GOLDEN[name]
+    assert signing.sign(private_key, expected) == signature
+
+
+def test_empty_batch_is_empty_body():
+    assert signing.signing_string("orderExecute", [], TIMESTAMP, WINDOW) == (
+        "instruction=orderExecute&timestamp=1700000000000&window=5000"
+    )
+
+
+def test_none_values_are_skipped():
+    assert signing.signing_string("orderQuery", {"symbol": "SOL_USDC", "orderId": None}, TIMESTAMP, WINDOW) == (
+        "instruction=orderQuery&symbol=SOL_USDC&timestamp=1700000000000&window=5000"
+    )
+
+
+@pytest.mark.parametrize(
+    "method,path,instruction",
+    [
+        ("GET", "api/v1/capital", "balanceQuery"),
+        ("get", "/api/v1/capital/", "balanceQuery"),
+        ("POST", "api/v1/order", "orderExecute"),
+        ("DELETE", "api/v1/order", "orderCancel"),
+        ("POST", "api/v1/orders", "orderExecute"),
+        ("DELETE", "api/v1/orders", "orderCancelAll"),
+        ("GET", "wapi/v1/history/fills?symbol=SOL_USDC", "fillHistoryQueryAll"),
+    ],
+)
+def test_instruction_table(method, path, instruction):
+    assert signing.instruction_for(method, path) == instruction
+
+
+def test_unknown_endpoint_is_rejected():
+    with pytest.raises(signing.UnknownInstructionError):
+        signing.instruction_for("GET", "api/v1/brandNewEndpoint")
+
+
+def test_public_key_matches_secret(private_key):
+    assert signing.public_key_b64(private_key) == PUBLIC_KEY
+    signing.validate_keypair(PUBLIC_KEY, SECRET_KEY)
+
+
+@pytest.mark.parametrize(
+    "public_key,secret_key,message",
+    [
+        (PUBLIC_KEY, "not base64!", "api secret is not valid base64"),
+        (PUBLIC_KEY, "YWJj", "api secret must decode to 32 bytes"),
+        ("YWJj", SECRET_KEY, "api key must decode to 32 bytes"),
+        (base64.b64encode(bytes(32)).decode(), SECRET_KEY, "api key is not the public key"),
+    ],
+)
+def test_invalid_keys_raise(public_key, secret_key, message):
+    with pytest.raises(signing.InvalidKeyError, match=message) as exc:
+        signing.validate_keypair(public_key, secret_key)
+    assert SECRET_KEY not in str(exc.value)
+
+
+def test_headers(private_key):
+    headers = signing.signature_headers(
+        private_key=private_key,
+        public_key=PUBLIC_KEY,
+        instruction="balanceQuery",
+        params=None,
+        timestamp=TIMESTAMP,
+        window=WINDOW,
+    )
+    assert headers["X-Signature"] == GOLDEN["empty_body"][3]
+    assert headers["X-Timestamp"] == str(TIMESTAMP)
+    assert headers["X-Window"] == str(WINDOW)
+    assert headers["X-API-Key"] == PUBLIC_KEY
+
+
+@pytest.mark.parametrize(
+    "call,method",
+    [
+        (lambda a: a.get_balances(), "GET"),
+        (lambda a: a.get_open_positions(), "GET"),
+        (lambda a: a.get_open_orders(symbol="SOL_USDC"), "GET"),
+        (lambda a: a.cancel_all_orders(symbol="SOL_USDC"), "DELETE"),
+        (lambda a: a.get_fill_history(symbol="SOL_USDC", limit=100, offset=0), "GET"),
+        (lambda a: a.get_order_history(symbol="SOL_USDC", limit=100, offset=0), "GET"),
+        (lambda a: a.get_funding_payments(symbol="SOL_USDC_PERP"), "GET"),
+        (lambda a: a.get_deposits(limit=100, offset=0, from_=1, to=2), "GET"),
+        (lambda a: a.get_withdrawals(limit=100, offset=0, from_=1, to=2), "GET"),
+        (lambda a: a.get_borrow_lend_positions(), "GET"),
+        (lambda a: a.get_borrow_history(symbol="SOL"), "GET"),
+        (lambda a: a.get_interest_history(symbol="SOL"), "GET"),
+        (lambda a: a.get_profit_and_loss_history(), "GET"),
+        (lambda a: a.get_settlements_history(), "GET"),
+    ],
+)
+def test_account_requests_match_table(call, method):
+    account = BaseAccount(public_key=PUBLIC_KEY, secret_key=SECRET_KEY, window=WINDOW, debug=False)
+    account.timestamp_provider = lambda: TIMESTAMP
+    config = call(account)
+    path = config.url[len(account.BPX_API_URL):]
+    params = config.params if config.params else config.data
+    expected = signing.sign(
+        account.private_key,
+        signing.signing_string(signing.instruction_for(method, path), params, TIMESTAMP, WINDOW),
+    )
+    assert config.headers["X-Signature"] == expected
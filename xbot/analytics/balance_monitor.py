@@ -0,0 +1,287 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Awaitable, Callable, Dict, Iterable, List, Mapping, Optional, Protocol, Set
+
+from xbot.connector.history import decimal_or_none, timestamp_ms
+from xbot.connector.interface import IConnector
+
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.backoff import AdaptiveInterval, BackoffConfig
+from ..core.metrics import METRICS
+from ..core.polling import PollResult, first_error, poll_all
+from ..core.state_cache import StateCache
+from ..core.symbology import SYMBOLOGY
+from ..utils.logging import get_logger
+
+_BALANCE_FIELDS = ("available", "locked", "staked")
+_SKIPPED_STATUS = ("fail", "cancel", "pending", "reject")
+
+
+def total_balances(margin: Mapping[str, Any]) -> Dict[str, Decimal]:
+    """Asset -> total quantity (available + locked + staked) from a connector `get_margin()` snapshot."""
+    raw = margin.get("balances")
+    if not isinstance(raw, Mapping):
+        return {}
+    totals: Dict[str, Decimal] = {}
+    for asset, entry in raw.items():
+        values = [entry.get(k) for k in _BALANCE_FIELDS] if isinstance(entry, Mapping) else [entry]
+        amounts = [d for d in (decimal_or_none(v) for v in values) if d is not None]
+        if amounts:
+            totals[str(asset).upper()] = sum(amounts, Decimal(0))
+    return totals
+
+
+class WithdrawalLedger(Protocol):
+    """Venue ids of the withdrawals the bot itself submitted (`execution.withdrawals.WithdrawalGuard`)."""
+
+    def submitted_ids(self) -> Set[str]:  # pragma: no cover - protocol
+        ...
+
+
+@dataclass(slots=True)
+class BalanceMonitorConfig:
+    interval_secs: float = 300.0
+    default_tolerance: Decimal = Decimal("0.01")  # unexplained change tolerated per asset, in asset units
+    tolerance: Dict[str, Decimal] = field(default_factory=dict)  # per-asset overrides
+    exclude: List[str] = field(default_factory=list)
+    page_size: int = 1000
+    request_timeout_secs: float = 30.0  # per history endpoint, paging included
+
+
+@dataclass(slots=True)
+class BalanceAnomaly:
+    asset: str
+    before: Decimal
+    after: Decimal
+    explained: Decimal  # net change accounted for by fills, fees, funding and transfers
+    from_ms: int
+    to_ms: int
+
+    @property
+    def residual(self) -> Decimal:
+        return self.after - self.before - self.explained
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "asset": self.asset,
+            "before": str(self.before),
+            "after": str(self.after),
+            "explained": str(self.explained),
+            "residual": str(self.residual),
+            "from_ms": self.from_ms,
+            "to_ms": self.to_ms,
+        }
+
+
+def _within(row: Mapping[str, Any], key: str, from_ms: int, to_ms: int) -> bool:
+    ts = timestamp_ms(row.get(key))
+    return ts is not None and from_ms <= ts < to_ms
+
+
+def _settled(row: Mapping[str, Any]) -> bool:
+    status = str(row.get("status") or "").lower()
+    return not any(marker in status for marker in _SKIPPED_STATUS)
+
+
+class BalanceMonitor:
+    """Flags balance changes between snapshots that no fill, fee, funding payment or transfer explains.
+
+    Every `interval_secs` the total balance per asset is snapshotted and the
+    change since the previous snapshot is compared with the venue's own
+    history for the same window:
+
+    - spot fills move the base and quote assets;
+    - fees are charged in their fee asset, perp fills included;
+    - funding payments settle in the perp's quote asset;
+    - deposits move their asset;
+    - withdrawals (plus their fees) move their asset, but only those the
+      attached `WithdrawalLedger` submitted. Any other withdrawal, one made
+      by hand or with a leaked key, is left unexplained and alerts.
+
+    A residual above the asset's tolerance raises an alert, CRITICAL when
+    balance went missing and WARNING when it appeared. Perp PnL settling into
+    the collateral asset is not itemised by the venue, so give that asset a
+    tolerance sized to it or exclude it.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        alerts: AlertManager,
+        config: Optional[BalanceMonitorConfig] = None,
+        backoff: Optional[BackoffConfig] = None,
+        state: Optional[StateCache] = None,
+        withdrawals: Optional[WithdrawalLedger] = None,
+    ) -> None:
+        for method in ("get_fill_history", "get_funding_payments", "get_deposits", "get_withdrawals"):
+            if not hasattr(connector, method):
+                raise ValueError(f"balance monitoring needs {method} on {connector.venue}")
+        self._connector = connector
+        self._alerts = alerts
+        self._config = config or BalanceMonitorConfig()
+        self._state = state
+        self._withdrawals = withdrawals
+        self._interval = AdaptiveInterval(connector.venue, "balances", self._config.interval_secs, backoff)
+        self._last: Optional[Dict[str, Decimal]] = None
+        self._last_ms = 0
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def attach_withdrawals(self, ledger: WithdrawalLedger) -> None:
+        self._withdrawals = ledger
+
+    async def snapshot(self) -> Dict[str, Decimal]:
+        margin = await self._connector.get_margin()
+        if self._state is not None:
+            self._state.mark_fresh(self._connector.venue, "margin")
+        return total_balances(margin)
+
+    def _mark_fresh(self, results: Iterable[PollResult]) -> None:
+        if self._state is None:
+            return
+        for result in results:
+            if result.ok:
+                self._state.mark_fresh(self._connector.venue, result.endpoint, result.finished_at)
+
+    async def _rows(self, fetch: Callable[..., Awaitable[List[Dict[str, Any]]]], **kwargs: Any) -> List[Dict[str, Any]]:
+        rows: List[Dict[str, Any]] = []
+        size = self._config.page_size
+        while True:
+            page = await fetch(limit=size, offset=len(rows), **kwargs)
+            rows.extend(page)
+            if len(page) < size:
+                return rows
+
+    async def explained(self, from_ms: int, to_ms: int) -> Dict[str, Decimal]:
+        """Net change per asset that the venue history accounts for in ``[from_ms, to_ms)``."""
+        flows: Dict[str, Decimal] = {}
+
+        def add(asset: Any, amount: Optional[Decimal]) -> None:
+            if asset and amount:
+                key = str(asset).upper()
+                flows[key] = flows.get(key, Decimal(0)) + amount
+
+        connector: Any = self._connector
+        venue = connector.venue
+        # Independent endpoints, fetched concurrently; any failure leaves the window for the next check
+        results = await poll_all(
+            {
+                "fill_history": lambda: self._rows(connector.get_fill_history, from_ms=from_ms, to_ms=to_ms),
+                # Funding payments take no time range; the latest page is filtered locally
+                "funding_payments": lambda: connector.get_funding_payments(limit=self._config.page_size),
+                "deposits": lambda: self._rows(connector.get_deposits, from_ms=from_ms, to_ms=to_ms),
+                "withdrawals": lambda: self._rows(connector.get_withdrawals, from_ms=from_ms, to_ms=to_ms),
+            },
+            timeout_secs=self._config.request_timeout_secs,
+        )
+        self._mark_fresh(results.values())
+        error = first_error(results)
+        if error is not None:
+            raise error
+        for fill in results["fill_history"].value:
+            instrument = SYMBOLOGY.instrument(venue, str(fill.get("symbol") or ""))
+            qty = decimal_or_none(fill.get("quantity")) or Decimal(0)
+            price = decimal_or_none(fill.get("price")) or Decimal(0)
+            sign = Decimal(1) if str(fill.get("side") or "").lower() in ("bid", "buy") else Decimal(-1)
+            if not instrument.derivative:
+                add(instrument.base, sign * qty)
+                add(instrument.quote, -sign * qty * price)
+            fee = decimal_or_none(fill.get("fee"))
+            add(fill.get("feeSymbol") or instrument.pnl_currency, -fee if fee else None)
+        for payment in results["funding_payments"].value:
+            if _within(payment, "intervalEndTimestamp", from_ms, to_ms):
+                settle = SYMBOLOGY.instrument(venue, str(payment.get("symbol") or "")).pnl_currency
+                add(settle, decimal_or_none(payment.get("quantity")))
+        for deposit in results["deposits"].value:
+            if _settled(deposit) and _within(deposit, "createdAt", from_ms, to_ms):
+                add(deposit.get("symbol"), decimal_or_none(deposit.get("quantity")))
+        submitted = self._withdrawals.submitted_ids() if self._withdrawals is not None else set()
+        for withdrawal in results["withdrawals"].value:
+            if str(withdrawal.get("id")) not in submitted:
+                continue
+            if _settled(withdrawal) and _within(withdrawal, "createdAt", from_ms, to_ms):
+                amount = (decimal_or_none(withdrawal.get("quantity")) or Decimal(0)) + (
+                    decimal_or_none(withdrawal.get("fee")) or Decimal(0)
+                )
+                add(withdrawal.get("symbol"), -amount)
+        return flows
+
+    def _tolerance(self, asset: str) -> Decimal:
+        return self._config.tolerance.get(asset, self._config.default_tolerance)
+
+    async def check(self) -> List[BalanceAnomaly]:
+        """Snapshot balances and return the unexplained changes since the previous snapshot."""
+        now_ms = int(time.time() * 1000)
+        current = await self.snapshot()
+        previous, from_ms = self._last, self._last_ms
+        if previous is None:
+            self._last, self._last_ms = current, now_ms
+            return []
+        flows = await self.explained(from_ms, now_ms)
+        # Advanced only once the window is explained, so a failed fetch widens the next window instead of losing it
+        self._last, self._last_ms = current, now_ms
+        anomalies: List[BalanceAnomaly] = []
+        venue = self._connector.venue
+        for asset in sorted(set(previous) | set(current) | set(flows)):
+            if asset in self._config.exclude:
+                continue
+            item = BalanceAnomaly(
+                asset=asset,
+                before=previous.get(asset, Decimal(0)),
+                after=current.get(asset, Decimal(0)),
+                explained=flows.get(asset, Decimal(0)),
+                from_ms=from_ms,
+                to_ms=now_ms,
+            )
+            METRICS.set("balance_residual", float(item.residual), venue=venue, asset=asset)
+            if abs(item.residual) > self._tolerance(asset):
+                anomalies.append(item)
+        for item in anomalies:
+            await self._report(item)
+        return anomalies
+
+    async def _report(self, item: BalanceAnomaly) -> None:
+        venue = self._connector.venue
+        METRICS.inc("balance_anomalies", venue=venue, asset=item.asset)
+        self._logger.warning("balance_anomaly", extra={"venue": venue, **item.to_dict()})
+        missing = item.residual < 0
+        await self._alerts.notify(
+            AlertLevel.CRITICAL if missing else AlertLevel.WARNING,
+            f"unexplained {item.asset} balance change",
+            f"{item.asset} went from {item.before} to {item.after}; fills, fees, funding and transfers explain "
+            f"{item.explained}, leaving {item.residual}",
+            venue=venue,
+            **item.to_dict(),
+        )
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="balance-monitor")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.check()
+            except Exception as exc:
+                self._logger.info("balance_check_error", extra={"venue": self._connector.venue, "error": str(exc)})
+                self._interval.failure(exc)
+            else:
+                self._interval.success()
+            await self._interval.sleep()
+
+
+__all__ = ["BalanceAnomaly", "BalanceMonitor", "BalanceMonitorConfig", "WithdrawalLedger", "total_balances"]
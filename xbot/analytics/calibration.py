@@ -0,0 +1,196 @@
+from __future__ import annotations
+
+import csv
+import json
+import statistics
+from dataclasses import dataclass, field
+from decimal import Decimal
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Mapping, Optional, Set, Tuple
+
+from .journal import _dec, strategy_tag
+
+# States that prove the venue accepted the order; submissions that only ever failed could not fill in either world
+_PLACED = {"open", "partially_filled", "filled", "cancelled"}
+
+
+@dataclass(slots=True)
+class OrderFills:
+    """Everything one order filled, real or simulated."""
+
+    key: str
+    symbol: str = ""
+    side: str = ""
+    qty: Decimal = Decimal(0)
+    notional: Decimal = Decimal(0)
+    first_ts: Optional[float] = None
+
+    def add(self, row: Mapping[str, Any]) -> None:
+        size = _dec(row.get("size"))
+        if size <= 0:
+            return
+        self.symbol = self.symbol or str(row.get("symbol") or "")
+        self.side = self.side or str(row.get("side") or "").lower()
+        self.qty += size
+        self.notional += size * _dec(row.get("price"))
+        ts = row.get("ts")
+        if ts is not None and (self.first_ts is None or float(ts) < self.first_ts):
+            self.first_ts = float(ts)
+
+    @property
+    def filled(self) -> bool:
+        return self.qty > 0
+
+    @property
+    def vwap(self) -> Optional[Decimal]:
+        return self.notional / self.qty if self.qty else None
+
+
+@dataclass(slots=True)
+class CalibrationStats:
+    orders: int = 0
+    real_filled: int = 0
+    sim_filled: int = 0
+    both_filled: int = 0
+    sim_only: int = 0  # the simulator filled what the venue did not: optimistic
+    real_only: int = 0  # the venue filled what the simulator did not: pessimistic
+    price_bias_bps: List[float] = field(default_factory=list)  # > 0: simulated price was better than real
+    qty_ratio: List[float] = field(default_factory=list)  # simulated / real filled quantity
+    delay_secs: List[float] = field(default_factory=list)  # simulated first fill - real first fill
+
+    def compare(self, real: OrderFills, sim: OrderFills, side: str = "") -> None:
+        self.orders += 1
+        self.real_filled += int(real.filled)
+        self.sim_filled += int(sim.filled)
+        if real.filled and sim.filled:
+            self.both_filled += 1
+            real_px, sim_px = real.vwap, sim.vwap
+            if real_px and sim_px:
+                # A buyer gains from a lower price, a seller from a higher one
+                edge = (real_px - sim_px) if (side or real.side or sim.side) == "buy" else (sim_px - real_px)
+                self.price_bias_bps.append(float(edge / real_px * 10_000))
+            self.qty_ratio.append(float(sim.qty / real.qty))
+            if real.first_ts is not None and sim.first_ts is not None:
+                self.delay_secs.append(sim.first_ts - real.first_ts)
+        elif sim.filled:
+            self.sim_only += 1
+        elif real.filled:
+            self.real_only += 1
+
+    @property
+    def real_fill_rate(self) -> float:
+        return self.real_filled / self.orders if self.orders else 0.0
+
+    @property
+    def sim_fill_rate(self) -> float:
+        return self.sim_filled / self.orders if self.orders else 0.0
+
+    def summary(self) -> Dict[str, Any]:
+        def mean(values: List[float]) -> Optional[float]:
+            return round(statistics.fmean(values), 4) if values else None
+
+        def median(values: List[float]) -> Optional[float]:
+            return round(statistics.median(values), 4) if values else None
+
+        return {
+            "orders": self.orders,
+            "real_fill_rate": round(self.real_fill_rate, 4),
+            "sim_fill_rate": round(self.sim_fill_rate, 4),
+            "fill_rate_error": round(self.sim_fill_rate - self.real_fill_rate, 4),
+            "both_filled": self.both_filled,
+            "sim_only": self.sim_only,
+            "real_only": self.real_only,
+            "price_bias_bps": mean(self.price_bias_bps),
+            "price_bias_bps_median": median(self.price_bias_bps),
+            "price_abs_error_bps": mean([abs(v) for v in self.price_bias_bps]),
+            "qty_ratio": mean(self.qty_ratio),
+            "fill_delay_secs": mean(self.delay_secs),
+        }
+
+
+@dataclass(slots=True)
+class CalibrationReport:
+    overall: CalibrationStats
+    by_symbol: Dict[str, CalibrationStats]
+    unmatched_sim: int  # simulated fills for orders the real session never placed
+
+    def format(self) -> str:
+        lines = [_line("all", self.overall.summary())]
+        for symbol in sorted(self.by_symbol):
+            lines.append(_line(symbol, self.by_symbol[symbol].summary()))
+        if self.unmatched_sim:
+            lines.append(f"{self.unmatched_sim} simulated orders have no real counterpart")
+        return "\n".join(lines)
+
+
+def _line(label: str, summary: Mapping[str, Any]) -> str:
+    return f"{label}: " + " ".join(f"{key}={value}" for key, value in summary.items() if value is not None)
+
+
+def _key(row: Mapping[str, Any], key: str) -> Optional[str]:
+    value = row.get(key)
+    return None if value in (None, "") else str(value)
+
+
+def calibrate(
+    order_events: Iterable[Mapping[str, Any]],
+    real_fills: Iterable[Mapping[str, Any]],
+    sim_fills: Iterable[Mapping[str, Any]],
+    *,
+    key: str = "client_order_index",
+    strategy: Optional[str] = None,
+) -> CalibrationReport:
+    """Compare a simulator's fills with what the venue did for the same orders.
+
+    The real session's `order_events` define the order universe. Every
+    order the venue accepted there is looked up in both fill sets by
+    `key`. When the simulator replays the session it must tag its fills
+    with the same key, usually `client_order_index` or `trace_id`. An order
+    without simulated fill rows counts as unfilled in the simulation.
+    `strategy` restricts the comparison to one strategy tag.
+    """
+    seen: Dict[str, Tuple[str, str]] = {}  # order key -> (symbol, side)
+    accepted: Set[str] = set()
+    for row in order_events:
+        order_key = _key(row, key)
+        if order_key is None or (strategy is not None and strategy_tag(row) != strategy):
+            continue
+        if str(row.get("state") or "") in _PLACED:
+            accepted.add(order_key)
+        symbol, side = seen.get(order_key, ("", ""))
+        info = row.get("info") or {}
+        if not side and isinstance(info, Mapping) and "is_ask" in info:
+            side = "sell" if info["is_ask"] else "buy"
+        seen[order_key] = (symbol or str(row.get("symbol") or ""), side)
+    placed = {order_key: seen[order_key] for order_key in seen if order_key in accepted}
+
+    def collect(rows: Iterable[Mapping[str, Any]]) -> Dict[str, OrderFills]:
+        out: Dict[str, OrderFills] = {}
+        for row in rows:
+            order_key = _key(row, key)
+            if order_key is not None:
+                out.setdefault(order_key, OrderFills(order_key)).add(row)
+        return out
+
+    real = collect(real_fills)
+    sim = collect(sim_fills)
+    overall = CalibrationStats()
+    by_symbol: Dict[str, CalibrationStats] = {}
+    for order_key, (symbol, side) in placed.items():
+        real_order = real.get(order_key, OrderFills(order_key, symbol=symbol))
+        sim_order = sim.get(order_key, OrderFills(order_key, symbol=symbol))
+        overall.compare(real_order, sim_order, side)
+        by_symbol.setdefault(symbol or real_order.symbol or "?", CalibrationStats()).compare(real_order, sim_order, side)
+    unmatched = sum(1 for order_key, fills in sim.items() if fills.filled and order_key not in placed)
+    return CalibrationReport(overall=overall, by_symbol=by_symbol, unmatched_sim=unmatched)
+
+
+def load_sim_fills(path: Path) -> List[Dict[str, Any]]:
+    """Simulated fills from CSV or JSONL: one row per fill with the order key, ts, symbol, side, price and size."""
+    with path.open(encoding="utf-8", newline="") as handle:
+        if path.suffix.lower() == ".csv":
+            return [dict(row) for row in csv.DictReader(handle)]
+        return [json.loads(line) for line in handle if line.strip()]
+
+
+__all__ = ["CalibrationReport", "CalibrationStats", "OrderFills", "calibrate", "load_sim_fills"]
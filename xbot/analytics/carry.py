@@ -0,0 +1,309 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Dict, List, Mapping, Optional, Sequence, Tuple
+
+from ..connector.interface import IConnector
+from ..execution.market_data_service import MarketDataService
+from ..execution.portfolio_executor import TargetPositionExecutor
+from ..utils.logging import get_logger
+from .equity import equity_from_margin
+from .funding_table import FundingTable
+
+FUNDING_PAIR = "funding_pair"
+LEND = "lend"
+
+
+@dataclass(slots=True)
+class CarryConfig:
+    interval_secs: float = 300.0
+    leverage: Decimal = Decimal(2)  # perp notional per unit of collateral on each leg's venue
+    reserve_pct: Decimal = Decimal("0.2")  # share of each venue's collateral never allocated
+    max_option_pct: Decimal = Decimal("0.5")  # cap per option, as a share of deployable collateral
+    min_apr: Decimal = Decimal("0.05")  # risk-adjusted return on capital below this is ignored
+    pair_haircut_apr: Decimal = Decimal("0.05")  # charged against funding spreads for basis and execution risk
+    lend_haircut_apr: Decimal = Decimal("0.005")
+    lend_assets: List[str] = field(default_factory=lambda: ["USDC"])  # stable assets, valued 1:1 with quote
+    venues: List[str] = field(default_factory=list)  # venues pairs may trade on; empty: those with an executor
+    apply: bool = False  # send pair targets to the executors; otherwise only plan and log
+
+
+@dataclass(slots=True)
+class CarryOption:
+    kind: str
+    key: str  # underlying for pairs, asset for lending
+    apr: Decimal  # gross annualized yield on notional
+    return_on_capital: Decimal  # risk-adjusted, annualized, per unit of collateral
+    capital_per_notional: Dict[str, Decimal]  # venue -> collateral tied up per unit notional
+    short_leg: Optional[Tuple[str, str]] = None  # (venue, venue symbol) receiving funding
+    long_leg: Optional[Tuple[str, str]] = None
+
+
+@dataclass(slots=True)
+class CarryAllocation:
+    option: CarryOption
+    notional: Decimal
+    capital: Dict[str, Decimal]
+
+    @property
+    def expected_income(self) -> Decimal:
+        """Annual income after haircuts."""
+        return self.option.return_on_capital * sum(self.capital.values(), Decimal(0))
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "kind": self.option.kind,
+            "key": self.option.key,
+            "apr": str(self.option.apr),
+            "return_on_capital": str(self.option.return_on_capital),
+            "notional": str(self.notional),
+            "capital": {venue: str(amount) for venue, amount in self.capital.items()},
+            "short_leg": self.option.short_leg,
+            "long_leg": self.option.long_leg,
+            "expected_income": str(self.expected_income),
+        }
+
+
+@dataclass(slots=True)
+class CarryPlan:
+    allocations: List[CarryAllocation]
+    collateral: Dict[str, Decimal]
+    idle: Dict[str, Decimal]
+    ts: float = field(default_factory=time.time)
+
+    @property
+    def expected_apr(self) -> Decimal:
+        total = sum(self.collateral.values(), Decimal(0))
+        income = sum((a.expected_income for a in self.allocations), Decimal(0))
+        return income / total if total else Decimal(0)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "ts": self.ts,
+            "allocations": [a.to_dict() for a in self.allocations],
+            "collateral": {venue: str(v) for venue, v in self.collateral.items()},
+            "idle": {venue: str(v) for venue, v in self.idle.items()},
+            "expected_apr": str(self.expected_apr),
+        }
+
+
+def plan_carry(
+    options: Sequence[CarryOption], collateral: Mapping[str, Decimal], config: CarryConfig
+) -> CarryPlan:
+    """Allocate collateral to the best return-on-capital options first.
+
+    Returns are linear in size, so filling options greedily by
+    risk-adjusted return on capital, each up to `max_option_pct` and within
+    every venue's free collateral after `reserve_pct`, gives the best plan.
+    """
+    free = {venue: max(Decimal(0), amount * (1 - config.reserve_pct)) for venue, amount in collateral.items()}
+    deployable = sum(free.values(), Decimal(0))
+    allocations: List[CarryAllocation] = []
+    for option in sorted(options, key=lambda o: o.return_on_capital, reverse=True):
+        if option.return_on_capital < config.min_apr:
+            break
+        if any(venue not in free for venue in option.capital_per_notional):
+            continue
+        capital_per_unit = sum(option.capital_per_notional.values(), Decimal(0))
+        if capital_per_unit <= 0:
+            continue
+        notional = deployable * config.max_option_pct / capital_per_unit
+        for venue, per in option.capital_per_notional.items():
+            notional = min(notional, free[venue] / per)
+        if notional <= 0:
+            continue
+        capital = {venue: notional * per for venue, per in option.capital_per_notional.items()}
+        for venue, amount in capital.items():
+            free[venue] -= amount
+        allocations.append(CarryAllocation(option=option, notional=notional, capital=capital))
+    return CarryPlan(allocations=allocations, collateral=dict(collateral), idle=free)
+
+
+class CarryOptimizer:
+    """Plans where idle collateral earns the most carry and targets the perp legs.
+
+    Options are funding capture pairs from the `FundingTable` (short the perp
+    paying the highest funding, long the one paying the least, on venues the
+    optimizer can trade) and lending on venues that publish borrow/lend
+    markets. Each is scored by annualized yield, less a haircut, per unit of
+    collateral it ties up. With `apply`, pair legs become targets of the
+    venues' `TargetPositionExecutor`s and legs dropped from the plan are
+    targeted back to flat. Lending is planned but not executed; moving funds
+    into lending stays an operator decision.
+    """
+
+    def __init__(
+        self,
+        *,
+        funding_table: Optional[FundingTable],
+        connectors: Sequence[IConnector],
+        executors: Optional[Mapping[str, TargetPositionExecutor]] = None,
+        market_data: Optional[Mapping[str, MarketDataService]] = None,
+        config: Optional[CarryConfig] = None,
+    ) -> None:
+        self._funding_table = funding_table
+        self._connectors = list(connectors)
+        self._executors = dict(executors or {})
+        self._market_data = dict(market_data or {})
+        self._config = config or CarryConfig()
+        self._plan: Optional[CarryPlan] = None
+        self._targeted: Dict[Tuple[str, str], Decimal] = {}  # (venue, canonical symbol) -> base qty
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def plan(self) -> Optional[CarryPlan]:
+        return self._plan
+
+    def _tradable(self) -> List[str]:
+        return [venue.lower() for venue in self._config.venues] or sorted(self._executors)
+
+    def pair_options(self) -> List[CarryOption]:
+        if self._funding_table is None:
+            return []
+        tradable = set(self._tradable())
+        per_leg = 1 / self._config.leverage
+        options: List[CarryOption] = []
+        for row in self._funding_table.rows():
+            if row.venue not in tradable:
+                continue
+            # Rows come sorted by annualized funding, highest first
+            legs = [r for r in self._funding_table.rows(row.underlying) if r.venue in tradable]
+            if legs[0] is not row or len(legs) < 2:
+                continue
+            short, long = legs[0], legs[-1]
+            spread = short.annualized - long.annualized
+            capital = {short.venue: per_leg} if short.venue == long.venue else {short.venue: per_leg, long.venue: per_leg}
+            options.append(
+                CarryOption(
+                    kind=FUNDING_PAIR,
+                    key=row.underlying,
+                    apr=spread,
+                    return_on_capital=(spread - self._config.pair_haircut_apr) / sum(capital.values()),
+                    capital_per_notional=capital,
+                    short_leg=(short.venue, short.symbol),
+                    long_leg=(long.venue, long.symbol),
+                )
+            )
+        return options
+
+    async def lend_options(self) -> List[CarryOption]:
+        assets = {asset.upper() for asset in self._config.lend_assets}
+        options: List[CarryOption] = []
+        for connector in self._connectors:
+            if not hasattr(connector, "get_borrow_lend_markets"):
+                continue
+            try:
+                markets = await connector.get_borrow_lend_markets()  # type: ignore[attr-defined]
+            except Exception as exc:
+                self._logger.info("carry_lend_markets_error", extra={"venue": connector.venue, "error": str(exc)})
+                continue
+            for market in markets:
+                if market.symbol.upper() not in assets or not market.open:
+                    continue
+                options.append(
+                    CarryOption(
+                        kind=LEND,
+                        key=market.symbol.upper(),
+                        apr=market.lend_rate,
+                        return_on_capital=market.lend_rate - self._config.lend_haircut_apr,
+                        capital_per_notional={connector.venue: Decimal(1)},
+                    )
+                )
+        return options
+
+    async def collateral(self) -> Dict[str, Decimal]:
+        out: Dict[str, Decimal] = {}
+        for connector in self._connectors:
+            try:
+                equity = equity_from_margin(await connector.get_margin())
+            except Exception as exc:
+                self._logger.info("carry_collateral_error", extra={"venue": connector.venue, "error": str(exc)})
+                continue
+            if equity is not None:
+                out[connector.venue] = equity
+        return out
+
+    async def refresh(self) -> CarryPlan:
+        options = self.pair_options() + await self.lend_options()
+        plan = plan_carry(options, await self.collateral(), self._config)
+        self._plan = plan
+        self._logger.info(
+            "carry_plan",
+            extra={
+                "allocations": [a.to_dict() for a in plan.allocations],
+                "expected_apr": str(plan.expected_apr),
+                "options": len(options),
+            },
+        )
+        if self._config.apply:
+            await self._apply(plan)
+        return plan
+
+    async def _mid(self, venue: str, symbol: str) -> Optional[Decimal]:
+        bid_i, ask_i, scale = await self._market_data[venue].get_top_of_book(symbol)
+        if not bid_i or not ask_i:
+            return None
+        return (Decimal(bid_i) + Decimal(ask_i)) / 2 / Decimal(scale)
+
+    async def _apply(self, plan: CarryPlan) -> None:
+        wanted: Dict[Tuple[str, str], Decimal] = {}
+        for allocation in plan.allocations:
+            option = allocation.option
+            if option.kind != FUNDING_PAIR or option.short_leg is None or option.long_leg is None:
+                continue
+            legs: List[Tuple[Tuple[str, str], Decimal]] = []
+            for (venue, venue_symbol), sign in ((option.short_leg, -1), (option.long_leg, 1)):
+                market_data = self._market_data.get(venue)
+                symbol = market_data.canonical_for(venue_symbol) if market_data is not None else None
+                mid = await self._mid(venue, symbol) if venue in self._executors and symbol is not None else None
+                if symbol is None or mid is None:
+                    break
+                legs.append(((venue, symbol), sign * allocation.notional / mid))
+            if len(legs) < 2:
+                # Never target one leg without its hedge
+                self._logger.info("carry_pair_untradable", extra={"underlying": option.key})
+                continue
+            for key, qty in legs:
+                wanted[key] = wanted.get(key, Decimal(0)) + qty
+        for key in set(self._targeted) - set(wanted):
+            wanted[key] = Decimal(0)
+        for (venue, symbol), qty in wanted.items():
+            await self._executors[venue].set_target(symbol, qty)
+        self._targeted = {key: qty for key, qty in wanted.items() if qty}
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="carry-optimizer")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.refresh()
+            except Exception as exc:
+                self._logger.info("carry_refresh_error", extra={"error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = [
+    "CarryAllocation",
+    "CarryConfig",
+    "CarryOptimizer",
+    "CarryOption",
+    "CarryPlan",
+    "FUNDING_PAIR",
+    "LEND",
+    "plan_carry",
+]
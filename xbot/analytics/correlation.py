@@ -0,0 +1,148 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import math
+import time
+from dataclasses import dataclass
+from typing import Dict, List, Mapping, Optional, Sequence
+
+from ..execution.candles import Candle, CandleService
+from ..execution.market_data_service import MarketDataService
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class CorrelationConfig:
+    interval_secs: float = 300.0
+    window: int = 100  # most recent candle-to-candle returns
+    min_periods: int = 30  # overlapping returns needed before a pair gets a coefficient
+    threshold: float = 0.7  # pairs at or above this (in absolute value) count as correlated exposure
+
+
+def _returns(candles: Sequence[Candle]) -> Dict[float, float]:
+    # Log return into each candle from the one before, keyed by the later candle's start
+    out: Dict[float, float] = {}
+    for prev, cur in zip(candles, candles[1:]):
+        if prev.close > 0 and cur.close > 0:
+            out[cur.start] = math.log(float(cur.close) / float(prev.close))
+    return out
+
+
+def _pearson(xs: Sequence[float], ys: Sequence[float]) -> Optional[float]:
+    n = len(xs)
+    if n < 2:
+        return None
+    mx, my = sum(xs) / n, sum(ys) / n
+    cov = sum((x - mx) * (y - my) for x, y in zip(xs, ys))
+    vx = sum((x - mx) ** 2 for x in xs)
+    vy = sum((y - my) ** 2 for y in ys)
+    if vx <= 0 or vy <= 0:
+        return None
+    return cov / math.sqrt(vx * vy)
+
+
+def correlation_matrix(
+    candles: Mapping[str, Sequence[Candle]], *, window: int = 100, min_periods: int = 30
+) -> Dict[str, Dict[str, Optional[float]]]:
+    """Pairwise Pearson correlation of candle log returns, over the periods both symbols traded.
+
+    Candles without trades are missing rather than flat, so two symbols are
+    compared only on the candle starts they share. A pair with fewer than
+    `min_periods` shared returns, or a flat series, gets None.
+    """
+    returns = {symbol: _returns(series) for symbol, series in candles.items()}
+    symbols = sorted(returns)
+    matrix: Dict[str, Dict[str, Optional[float]]] = {symbol: {} for symbol in symbols}
+    for i, a in enumerate(symbols):
+        matrix[a][a] = 1.0 if len(returns[a]) >= min_periods else None
+        for b in symbols[i + 1 :]:
+            shared = sorted(set(returns[a]) & set(returns[b]))[-window:]
+            rho = None
+            if len(shared) >= min_periods:
+                rho = _pearson([returns[a][t] for t in shared], [returns[b][t] for t in shared])
+            matrix[a][b] = matrix[b][a] = rho
+    return matrix
+
+
+class CorrelationService:
+    """Recomputes rolling return correlations across the symbols in the candle store.
+
+    Symbols are reported canonically when the venue's market data knows
+    them, the way positions are keyed, so the risk service can look up an
+    order's correlated positions directly.
+    """
+
+    def __init__(
+        self,
+        *,
+        candles: CandleService,
+        market_data: Optional[MarketDataService] = None,
+        config: Optional[CorrelationConfig] = None,
+    ) -> None:
+        self._candles = candles
+        self._market_data = market_data
+        self._config = config or CorrelationConfig()
+        self._matrix: Dict[str, Dict[str, Optional[float]]] = {}
+        self._updated: Optional[float] = None
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> CorrelationConfig:
+        return self._config
+
+    def _name(self, venue_symbol: str) -> str:
+        canonical = self._market_data.canonical_for(venue_symbol) if self._market_data is not None else None
+        return canonical or venue_symbol
+
+    def refresh(self) -> Dict[str, Dict[str, Optional[float]]]:
+        series = {self._name(symbol): self._candles.candles(symbol) for symbol in self._candles.symbols()}
+        self._matrix = correlation_matrix(series, window=self._config.window, min_periods=self._config.min_periods)
+        self._updated = time.time()
+        return self._matrix
+
+    def correlation(self, a: str, b: str) -> Optional[float]:
+        return self._matrix.get(a.upper(), {}).get(b.upper())
+
+    def correlated(self, symbol: str) -> Dict[str, float]:
+        """Symbols whose returns move with (or against) `symbol` at least `threshold`, itself included."""
+        row = self._matrix.get(symbol.upper(), {})
+        out = {other: rho for other, rho in row.items() if rho is not None and abs(rho) >= self._config.threshold}
+        out[symbol.upper()] = 1.0
+        return out
+
+    def snapshot(self) -> Dict[str, object]:
+        """Heatmap-ready view: ordered symbols and a square matrix, None where undefined."""
+        symbols: List[str] = sorted(self._matrix)
+        return {
+            "symbols": symbols,
+            "matrix": [[self._matrix[a].get(b) for b in symbols] for a in symbols],
+            "updated": self._updated,
+            "candle_secs": self._candles.interval_secs,
+            "window": self._config.window,
+            "threshold": self._config.threshold,
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="correlations")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                self.refresh()
+            except Exception as exc:
+                self._logger.info("correlation_refresh_error", extra={"error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = ["CorrelationConfig", "CorrelationService", "correlation_matrix"]
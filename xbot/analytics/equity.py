@@ -0,0 +1,297 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Any, Dict, List, Mapping, Optional, Sequence, Set
+
+from xbot.connector.history import decimal_or_none, timestamp_ms
+from xbot.connector.interface import IConnector
+from xbot.execution.fx import CurrencyConverter
+
+from ..storage.base import CAPITAL_FLOWS, EQUITY_SNAPSHOTS, IStorage, StorageWriter
+from ..utils.logging import get_logger
+
+TOTAL = "total"
+
+_EQUITY_KEYS = ("netEquity", "total_asset_value", "totalEquity", "equity", "netEquityAvailable", "collateral")
+
+
+def equity_from_margin(margin: Mapping[str, Any]) -> Optional[Decimal]:
+    """Extract account equity from a connector `get_margin()` snapshot."""
+    sources: List[Mapping[str, Any]] = [margin]
+    nested = margin.get("collateral")
+    if isinstance(nested, Mapping):
+        sources.insert(0, nested)
+    for source in sources:
+        for key in _EQUITY_KEYS:
+            value = source.get(key)
+            if value is None or isinstance(value, Mapping):
+                continue
+            try:
+                return Decimal(str(value))
+            except Exception:
+                continue
+    return None
+
+
+# Statuses of deposits/withdrawals that never moved (or have not yet moved) funds
+_UNSETTLED_STATUS = ("fail", "cancel", "pending", "reject")
+
+
+def _settled(row: Mapping[str, Any]) -> bool:
+    status = str(row.get("status") or "").lower()
+    return not any(marker in status for marker in _UNSETTLED_STATUS)
+
+
+def flow_ref(venue: str, kind: str, transfer_id: Any) -> str:
+    """Dedup key of a venue deposit/withdrawal in `capital_flows`."""
+    return f"{venue}:{kind}:{transfer_id}"
+
+
+@dataclass(slots=True)
+class EquityPoint:
+    ts: float
+    equity: Decimal
+    net_flows: Decimal
+
+    @property
+    def trading_equity(self) -> Decimal:
+        """Equity with deposits/withdrawals since the curve start backed out."""
+        return self.equity - self.net_flows
+
+
+class EquityTracker:
+    """Snapshots equity across connectors and serves a flow-adjusted equity curve.
+
+    Deposits and withdrawals are recorded in `capital_flows` (positive = deposit)
+    and subtracted cumulatively, so moving money in or out does not show up as
+    PnL or drawdown.
+
+    With a converter, each venue's equity is valued in the reporting currency
+    before it is summed; `currencies` names the asset a venue reports margin
+    equity in (the reporting currency when absent). A venue whose equity
+    cannot be converted is left out of that snapshot rather than summed raw.
+
+    Connectors with `get_deposits`/`get_withdrawals` have their settled
+    transfers from the last `flow_lookback_secs` recorded as flows before
+    every snapshot, deduplicated on the venue's transfer id, so a transfer
+    made outside the bot is backed out as well. Flows are valued through the
+    converter when there is one and taken as-is otherwise.
+    """
+
+    def __init__(
+        self,
+        *,
+        connectors: Sequence[IConnector],
+        writer: StorageWriter,
+        interval_secs: float = 60.0,
+        fx: Optional[CurrencyConverter] = None,
+        currencies: Optional[Mapping[str, str]] = None,
+        flow_lookback_secs: float = 86400.0,
+        flow_page_size: int = 100,
+    ) -> None:
+        self._connectors = list(connectors)
+        self._fx = fx
+        self._currencies = {venue.lower(): asset.upper() for venue, asset in (currencies or {}).items()}
+        self._writer = writer
+        self._interval = interval_secs
+        self._flow_lookback = flow_lookback_secs
+        self._flow_page = flow_page_size
+        self._flow_refs: Optional[Set[str]] = None
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def storage(self) -> IStorage:
+        return self._writer.storage
+
+    async def snapshot(self) -> Dict[str, Decimal]:
+        ts = time.time()
+        values: Dict[str, Decimal] = {}
+        for connector in self._connectors:
+            try:
+                equity = equity_from_margin(await connector.get_margin())
+            except Exception as exc:
+                self._logger.info("equity_snapshot_error", extra={"venue": connector.venue, "error": str(exc)})
+                continue
+            if equity is not None and self._fx is not None:
+                currency = self._currencies.get(connector.venue, self._fx.reporting_currency)
+                converted = self._fx.convert(equity, currency)
+                if converted is None:
+                    self._logger.info(
+                        "equity_conversion_missing",
+                        extra={"venue": connector.venue, "currency": currency, "target": self._fx.reporting_currency},
+                    )
+                    continue
+                equity = converted
+            if equity is not None:
+                values[connector.venue] = equity
+        if not values:
+            return values
+        values[TOTAL] = sum(values.values(), Decimal(0))
+        for venue, equity in values.items():
+            self._writer.enqueue(EQUITY_SNAPSHOTS, {"ts": ts, "venue": venue, "equity": str(equity)})
+        return values
+
+    def record_flow(
+        self,
+        *,
+        venue: str,
+        asset: str,
+        amount: Decimal,
+        kind: str,
+        ref: Optional[str] = None,
+        ts: Optional[float] = None,
+    ) -> bool:
+        """Record a deposit (amount > 0) or withdrawal (amount < 0) valued in the reporting currency.
+
+        A flow whose `ref` was already recorded is skipped; returns whether the row was written.
+        """
+        if ref is not None and self._flow_refs is not None:
+            if ref in self._flow_refs:
+                return False
+            self._flow_refs.add(ref)
+        row: Dict[str, Any] = {"venue": venue, "asset": asset, "amount": str(amount), "kind": kind, "ref": ref}
+        if ts is not None:
+            row["ts"] = ts
+        self._writer.enqueue(CAPITAL_FLOWS, row)
+        return True
+
+    def value_flow(self, asset: str, quantity: Decimal) -> Optional[Decimal]:
+        """`quantity` of `asset` in the reporting currency; None when the converter has no rate."""
+        return quantity if self._fx is None else self._fx.convert(quantity, asset)
+
+    async def _known_refs(self) -> Set[str]:
+        if self._flow_refs is None:
+            await self._writer.flush()  # flows recorded before the first sync are still buffered
+            rows = await self.storage.query(CAPITAL_FLOWS)
+            self._flow_refs = {str(row["ref"]) for row in rows if row.get("ref")}
+        return self._flow_refs
+
+    async def _history(self, fetch: Any, from_ms: int, to_ms: int) -> List[Dict[str, Any]]:
+        rows: List[Dict[str, Any]] = []
+        while True:
+            page = await fetch(limit=self._flow_page, offset=len(rows), from_ms=from_ms, to_ms=to_ms)
+            rows.extend(page)
+            if len(page) < self._flow_page:
+                return rows
+
+    async def sync_flows(self) -> int:
+        """Record settled venue deposits and withdrawals not yet in `capital_flows`; returns how many were added."""
+        try:
+            known = await self._known_refs()
+        except Exception as exc:
+            self._logger.info("capital_flow_sync_error", extra={"error": str(exc)})
+            return 0
+        to_ms = int(time.time() * 1000)
+        from_ms = to_ms - int(self._flow_lookback * 1000)
+        added = 0
+        for connector in self._connectors:
+            source: Any = connector
+            if not (hasattr(source, "get_deposits") and hasattr(source, "get_withdrawals")):
+                continue
+            for kind, fetch, sign in (
+                ("deposit", source.get_deposits, Decimal(1)),
+                ("withdrawal", source.get_withdrawals, Decimal(-1)),
+            ):
+                try:
+                    rows = await self._history(fetch, from_ms, to_ms)
+                except Exception as exc:
+                    self._logger.info("capital_flow_sync_error", extra={"venue": connector.venue, "kind": kind, "error": str(exc)})
+                    continue
+                for row in rows:
+                    transfer_id = row.get("id")
+                    if transfer_id is None or not _settled(row):
+                        continue
+                    ref = flow_ref(connector.venue, kind, transfer_id)
+                    if ref in known:
+                        continue
+                    asset = str(row.get("symbol") or "").upper()
+                    quantity = (decimal_or_none(row.get("quantity")) or Decimal(0)) + (
+                        (decimal_or_none(row.get("fee")) or Decimal(0)) if kind == "withdrawal" else Decimal(0)
+                    )
+                    amount = self.value_flow(asset, quantity)
+                    if amount is None:
+                        # Left unrecorded so the next sync retries once a rate is known
+                        self._logger.info("capital_flow_conversion_missing", extra={"venue": connector.venue, "asset": asset, "ref": ref})
+                        continue
+                    created = timestamp_ms(row.get("createdAt"))
+                    self.record_flow(
+                        venue=connector.venue,
+                        asset=asset,
+                        amount=sign * amount,
+                        kind=kind,
+                        ref=ref,
+                        ts=created / 1000 if created is not None else None,
+                    )
+                    added += 1
+        return added
+
+    async def equity_curve(
+        self,
+        start: Optional[float] = None,
+        end: Optional[float] = None,
+        *,
+        venue: str = TOTAL,
+    ) -> List[EquityPoint]:
+        await self._writer.flush()
+        snapshots = await self.storage.query(EQUITY_SNAPSHOTS, start=start, end=end, filters={"venue": venue})
+        flows = await self.storage.query(CAPITAL_FLOWS, start=start, end=end)
+        if venue != TOTAL:
+            flows = [f for f in flows if f.get("venue") == venue]
+        points: List[EquityPoint] = []
+        cumulative = Decimal(0)
+        idx = 0
+        for row in snapshots:
+            ts = float(row["ts"])
+            while idx < len(flows) and float(flows[idx]["ts"]) <= ts:
+                cumulative += Decimal(str(flows[idx].get("amount") or 0))
+                idx += 1
+            points.append(EquityPoint(ts=ts, equity=Decimal(str(row["equity"])), net_flows=cumulative))
+        return points
+
+    async def drawdown(self, start: Optional[float] = None, end: Optional[float] = None) -> Decimal:
+        """Current drawdown from the running peak of trading equity, as a fraction (0.1 = 10%)."""
+        curve = await self.equity_curve(start, end)
+        if not curve:
+            return Decimal(0)
+        peak = max(p.trading_equity for p in curve)
+        last = curve[-1].trading_equity
+        if peak <= 0:
+            return Decimal(0)
+        return max(Decimal(0), (peak - last) / peak)
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="equity-tracker")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            await self.sync_flows()
+            await self.snapshot()
+            await asyncio.sleep(self._interval)
+
+
+def curve_max_drawdown(curve: Sequence[EquityPoint]) -> Decimal:
+    """Largest peak-to-trough drop of trading equity, in quote units."""
+    peak: Optional[Decimal] = None
+    worst = Decimal(0)
+    for point in curve:
+        value = point.trading_equity
+        peak = value if peak is None else max(peak, value)
+        worst = max(worst, peak - value)
+    return worst
+
+
+__all__ = ["EquityTracker", "EquityPoint", "equity_from_margin", "curve_max_drawdown", "flow_ref", "TOTAL"]
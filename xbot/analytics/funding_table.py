@@ -0,0 +1,195 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field, replace
+from decimal import Decimal
+from typing import Any, Dict, List, Mapping, Optional, Protocol, Sequence
+
+from xbot.connector.history import CurrentFunding
+from xbot.execution.hedger import default_underlying
+
+from ..utils.logging import get_logger
+
+HOURS_PER_YEAR = Decimal(8760)
+
+
+class CurrentFundingSource(Protocol):
+    venue: str
+
+    async def get_current_funding(self, symbol: Optional[str] = None) -> List[CurrentFunding]:  # pragma: no cover
+        ...
+
+
+def annualize(rate: Decimal, interval_hours: float) -> Decimal:
+    """Per-interval rate -> simple annualized rate (rate x intervals per year)."""
+    if interval_hours <= 0:
+        raise ValueError("interval_hours must be positive")
+    return rate * HOURS_PER_YEAR / Decimal(str(interval_hours))
+
+
+@dataclass(slots=True)
+class FundingTableRow:
+    underlying: str
+    venue: str
+    symbol: str
+    rate: Decimal
+    interval_hours: float
+    annualized: Decimal
+    next_funding_ms: Optional[int]
+    spreads: Dict[str, Decimal] = field(default_factory=dict)  # other venue -> annualized(self) - annualized(other)
+
+    def time_to_next_secs(self, now: Optional[float] = None) -> Optional[float]:
+        if self.next_funding_ms is None:
+            return None
+        return max(0.0, self.next_funding_ms / 1000 - (time.time() if now is None else now))
+
+    def to_dict(self, now: Optional[float] = None) -> Dict[str, Any]:
+        return {
+            "underlying": self.underlying,
+            "venue": self.venue,
+            "symbol": self.symbol,
+            "rate": str(self.rate),
+            "interval_hours": self.interval_hours,
+            "annualized": str(self.annualized),
+            "next_funding_ms": self.next_funding_ms,
+            "time_to_next_secs": self.time_to_next_secs(now),
+            "spreads": {venue: str(spread) for venue, spread in self.spreads.items()},
+        }
+
+
+@dataclass(slots=True)
+class FundingTableConfig:
+    interval_secs: float = 60.0
+    underlyings: Dict[str, str] = field(default_factory=dict)  # venue symbol -> underlying overrides
+    interval_hours: Dict[str, float] = field(default_factory=dict)  # venue -> funding interval override
+    venues: List[str] = field(default_factory=list)  # restrict rows to these venues; empty keeps all
+
+
+class FundingTable:
+    """Live cross-venue table of annualized funding per underlying.
+
+    Each refresh polls every source's `get_current_funding()`. A source may
+    report several venues (Lighter's `funding-rates` also quotes other
+    exchanges); a venue reported by two sources keeps the first. Rows carry
+    the annualized rate, its spread versus every other venue quoting the same
+    underlying, and the next funding time, estimated at the next interval
+    boundary (UTC) when the venue does not publish it.
+    """
+
+    def __init__(self, *, sources: Sequence[CurrentFundingSource], config: Optional[FundingTableConfig] = None) -> None:
+        self._sources = list(sources)
+        self._config = config or FundingTableConfig()
+        self._rows: Dict[str, List[FundingTableRow]] = {}
+        self._updated: Optional[float] = None
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def updated(self) -> Optional[float]:
+        return self._updated
+
+    def underlying_for(self, symbol: str) -> str:
+        return self._config.underlyings.get(symbol.upper()) or default_underlying(symbol)
+
+    async def refresh(self) -> Dict[str, List[FundingTableRow]]:
+        now = time.time()
+        by_venue: Dict[tuple[str, str], FundingTableRow] = {}
+        allowed = {venue.lower() for venue in self._config.venues}
+        for source in self._sources:
+            try:
+                entries = await source.get_current_funding()
+            except Exception as exc:
+                self._logger.info("funding_table_source_error", extra={"venue": source.venue, "error": str(exc)})
+                continue
+            for entry in entries:
+                venue = entry.venue.lower()
+                if allowed and venue not in allowed:
+                    continue
+                key = (self.underlying_for(entry.symbol), venue)
+                if key in by_venue:
+                    continue
+                interval = self._config.interval_hours.get(venue, entry.interval_hours)
+                entry = replace(entry, interval_hours=interval)
+                by_venue[key] = FundingTableRow(
+                    underlying=key[0],
+                    venue=venue,
+                    symbol=entry.symbol,
+                    rate=entry.rate,
+                    interval_hours=interval,
+                    annualized=annualize(entry.rate, interval),
+                    next_funding_ms=entry.next_funding_at_ms(now),
+                )
+        table: Dict[str, List[FundingTableRow]] = {}
+        for row in by_venue.values():
+            table.setdefault(row.underlying, []).append(row)
+        for rows in table.values():
+            rows.sort(key=lambda r: r.annualized, reverse=True)
+            for row in rows:
+                row.spreads = {other.venue: row.annualized - other.annualized for other in rows if other is not row}
+        self._rows = table
+        self._updated = now
+        return table
+
+    def rows(self, underlying: Optional[str] = None) -> List[FundingTableRow]:
+        if underlying is not None:
+            return list(self._rows.get(underlying.upper(), []))
+        return [row for key in sorted(self._rows) for row in self._rows[key]]
+
+    def snapshot(self, underlying: Optional[str] = None) -> Dict[str, Any]:
+        now = time.time()
+        return {"updated": self._updated, "rows": [row.to_dict(now) for row in self.rows(underlying)]}
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="funding-table")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.refresh()
+            except Exception:
+                self._logger.exception("funding_table_refresh_failed")
+            await asyncio.sleep(self._config.interval_secs)
+
+
+def _fmt_countdown(seconds: Optional[float]) -> str:
+    if seconds is None:
+        return "-"
+    minutes, secs = divmod(int(seconds), 60)
+    hours, minutes = divmod(minutes, 60)
+    return f"{hours:d}h{minutes:02d}m{secs:02d}s"
+
+
+def render_table(snapshot: Mapping[str, Any]) -> str:
+    """Plain-text rendering of `FundingTable.snapshot()` for terminals and logs."""
+    lines = [f"{'UNDERLYING':<10} {'VENUE':<12} {'RATE':>11} {'INT':>4} {'APR %':>9} {'NEXT':>10}  SPREAD vs (APR %)"]
+    for row in snapshot.get("rows", []):
+        spreads = " ".join(
+            f"{venue}:{Decimal(spread) * 100:+.2f}" for venue, spread in sorted(row.get("spreads", {}).items())
+        )
+        lines.append(
+            f"{row['underlying']:<10} {row['venue']:<12} {Decimal(row['rate']):>11.6f} "
+            f"{row['interval_hours']:>3g}h {Decimal(row['annualized']) * 100:>9.2f} "
+            f"{_fmt_countdown(row.get('time_to_next_secs')):>10}  {spreads}"
+        )
+    return "\n".join(lines)
+
+
+__all__ = [
+    "CurrentFundingSource",
+    "FundingTable",
+    "FundingTableConfig",
+    "FundingTableRow",
+    "annualize",
+    "render_table",
+]
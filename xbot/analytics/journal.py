@@ -0,0 +1,211 @@
+from __future__ import annotations
+
+import csv
+from dataclasses import asdict, dataclass, fields
+from decimal import Decimal
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Mapping, Optional, Tuple
+
+from ..core.symbology import SYMBOLOGY, Instrument
+from ..storage.base import FILLS, FUNDING_PAYMENTS, IStorage
+
+
+@dataclass(slots=True)
+class TradeRecord:
+    venue: str
+    symbol: str
+    strategy: str
+    direction: str
+    entry_ts: float
+    exit_ts: float
+    entry_price: Decimal
+    exit_price: Decimal
+    size: Decimal
+    fees: Decimal
+    funding: Decimal
+    gross_pnl: Decimal
+    net_pnl: Decimal
+    holding_secs: float
+    currency: str = ""  # PnL, fee and funding currency: the settlement asset of derivatives
+
+
+def _dec(value: Any) -> Decimal:
+    if value in (None, ""):
+        return Decimal(0)
+    return Decimal(str(value))
+
+
+def strategy_tag(row: Mapping[str, Any]) -> str:
+    """Strategy label for a fill: explicit `strategy`, else the `trace_id` prefix."""
+    tag = row.get("strategy")
+    if tag:
+        return str(tag)
+    trace = str(row.get("trace_id") or "")
+    return trace.split(":", 1)[0] if trace else "manual"
+
+
+class _OpenTrade:
+    def __init__(self, instrument: Instrument, direction: int, ts: float) -> None:
+        self.instrument = instrument
+        self.direction = direction
+        self.entry_ts = ts
+        self.qty = Decimal(0)
+        self.entry_size = Decimal(0)
+        self.entry_value = Decimal(0)
+        self.exit_size = Decimal(0)
+        self.exit_value = Decimal(0)
+        self.fees = Decimal(0)
+        self.funding = Decimal(0)
+
+    @property
+    def entry_avg(self) -> Decimal:
+        return self.instrument.avg_price(self.entry_size, self.entry_value)
+
+    def close(self, key: Tuple[str, str, str], ts: float) -> TradeRecord:
+        venue, symbol, strategy = key
+        exit_avg = self.instrument.avg_price(self.exit_size, self.exit_value)
+        gross = self.instrument.pnl(self.exit_size * self.direction, self.entry_avg, exit_avg)
+        return TradeRecord(
+            venue=venue,
+            symbol=symbol,
+            strategy=strategy,
+            direction="long" if self.direction > 0 else "short",
+            entry_ts=self.entry_ts,
+            exit_ts=ts,
+            entry_price=self.entry_avg,
+            exit_price=exit_avg,
+            size=self.entry_size,
+            fees=self.fees,
+            funding=self.funding,
+            gross_pnl=gross,
+            net_pnl=gross - self.fees + self.funding,
+            holding_secs=ts - self.entry_ts,
+            currency=self.instrument.pnl_currency,
+        )
+
+
+def build_journal(
+    fills: Iterable[Mapping[str, Any]],
+    funding: Iterable[Mapping[str, Any]] = (),
+) -> List[TradeRecord]:
+    """Pair fills into round trips per (venue, symbol, strategy).
+
+    A trade opens when the tagged position leaves zero and closes when it returns
+    to zero; a fill that flips the position closes the trade and opens a new one
+    with the remainder, splitting its fee pro rata. Funding payments (positive =
+    received) are credited to whichever trade on that venue/symbol is open.
+    Sizes are contracts; PnL follows the symbology's contract multiplier and is
+    taken in coin, against harmonic average prices, for inverse contracts. The
+    contract is looked up by the fill's `venue_symbol`, the venue's own name for
+    the market, falling back to `symbol` for rows recorded without one.
+    """
+    events: List[Tuple[float, int, Mapping[str, Any]]] = []
+    for row in fills:
+        events.append((float(row.get("ts") or 0.0), 1, row))
+    for row in funding:
+        events.append((float(row.get("ts") or 0.0), 0, row))
+    events.sort(key=lambda e: (e[0], e[1]))
+
+    open_trades: Dict[Tuple[str, str, str], _OpenTrade] = {}
+    journal: List[TradeRecord] = []
+    for ts, kind, row in events:
+        venue = str(row.get("venue") or "")
+        symbol = str(row.get("symbol") or "")
+        if kind == 0:
+            for (v, s, _), trade in open_trades.items():
+                if v == venue and s == symbol:
+                    trade.funding += _dec(row.get("amount"))
+                    break
+            continue
+        key = (venue, symbol, strategy_tag(row))
+        size = _dec(row.get("size"))
+        if size <= 0:
+            continue
+        price = _dec(row.get("price"))
+        fee = _dec(row.get("fee"))
+        direction = -1 if str(row.get("side") or "").lower() in ("sell", "ask") else 1
+        remaining = size
+        trade = open_trades.get(key)
+        while remaining > 0:
+            if trade is None:
+                native = str(row.get("venue_symbol") or symbol)
+                trade = _OpenTrade(SYMBOLOGY.instrument(venue, native), direction, ts)
+                open_trades[key] = trade
+            share = fee * remaining / size
+            if trade.direction == direction:
+                trade.qty += remaining
+                trade.entry_size += remaining
+                trade.entry_value += trade.instrument.value(remaining, price)
+                trade.fees += share
+                remaining = Decimal(0)
+                continue
+            closed = min(remaining, trade.qty)
+            trade.qty -= closed
+            trade.exit_size += closed
+            trade.exit_value += trade.instrument.value(closed, price)
+            trade.fees += fee * closed / size
+            remaining -= closed
+            if trade.qty == 0:
+                journal.append(trade.close(key, ts))
+                open_trades.pop(key, None)
+                trade = None
+    journal.sort(key=lambda t: t.exit_ts)
+    return journal
+
+
+async def load_journal(
+    storage: IStorage,
+    *,
+    start: Optional[float] = None,
+    end: Optional[float] = None,
+    filters: Optional[Mapping[str, Any]] = None,
+) -> List[TradeRecord]:
+    """Build the journal from storage and keep trades whose exit falls in [start, end)."""
+    # Entries may predate `start`, so history is read from the beginning
+    fills = await storage.query(FILLS, end=end, filters=filters)
+    funding = await storage.query(FUNDING_PAYMENTS, end=end, filters=filters)
+    trades = build_journal(fills, funding)
+    if start is not None:
+        trades = [t for t in trades if t.exit_ts >= start]
+    return trades
+
+
+def _row(trade: TradeRecord) -> Dict[str, Any]:
+    return {k: (str(v) if isinstance(v, Decimal) else v) for k, v in asdict(trade).items()}
+
+
+def export_csv(trades: Iterable[TradeRecord], path: Path) -> int:
+    names = [f.name for f in fields(TradeRecord)]
+    count = 0
+    with path.open("w", encoding="utf-8", newline="") as handle:
+        writer = csv.DictWriter(handle, fieldnames=names)
+        writer.writeheader()
+        for trade in trades:
+            writer.writerow(_row(trade))
+            count += 1
+    return count
+
+
+def export_parquet(trades: Iterable[TradeRecord], path: Path) -> int:
+    try:
+        import pyarrow as pa  # type: ignore
+        import pyarrow.parquet as pq  # type: ignore
+    except Exception as exc:  # pragma: no cover - optional dependency
+        raise RuntimeError("pyarrow not available; install pyarrow for parquet export") from exc
+    rows = list(trades)
+    columns: Dict[str, List[Any]] = {f.name: [] for f in fields(TradeRecord)}
+    for trade in rows:
+        for key, value in asdict(trade).items():
+            columns[key].append(float(value) if isinstance(value, Decimal) else value)
+    pq.write_table(pa.table(columns), path)
+    return len(rows)
+
+
+__all__ = [
+    "TradeRecord",
+    "build_journal",
+    "load_journal",
+    "export_csv",
+    "export_parquet",
+    "strategy_tag",
+]
@@ -0,0 +1,140 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Dict, Iterable, List, Optional
+
+from ..storage.base import IStorage
+from ..utils.logging import get_logger
+from .journal import TradeRecord, load_journal
+
+
+@dataclass(slots=True)
+class KellyConfig:
+    fraction: Decimal = Decimal("0.5")  # share of the full Kelly bet; 0.5 is half Kelly
+    window: int = 50  # most recent round trips per strategy
+    min_trades: int = 20  # below this the multiplier stays at 1
+    baseline: Decimal = Decimal("0.01")  # share of equity a strategy's base size risks per trade
+    max_multiplier: Decimal = Decimal(2)
+    refresh_secs: float = 300.0
+
+
+@dataclass(slots=True)
+class KellyEstimate:
+    strategy: str
+    trades: int
+    win_rate: Decimal
+    payoff_ratio: Optional[Decimal]  # average win over average loss; None without losses
+    kelly: Decimal  # full-Kelly fraction of equity; negative without an edge
+    multiplier: Decimal  # applied to the strategy's base size
+
+
+def kelly_estimate(trades: Iterable[TradeRecord], *, strategy: str, config: KellyConfig) -> KellyEstimate:
+    """Win rate, payoff ratio and the capped fractional-Kelly multiplier over one strategy's recent trades.
+
+    `kelly = W - (1 - W) / R`. The multiplier is `fraction * kelly / baseline`
+    clamped to [0, max_multiplier]: a base size that risks `baseline` of equity
+    scaled to risk the fractional-Kelly share instead.
+    """
+    recent = sorted((t for t in trades if t.strategy == strategy), key=lambda t: t.exit_ts)[-config.window :]
+    wins = [t.net_pnl for t in recent if t.net_pnl > 0]
+    losses = [-t.net_pnl for t in recent if t.net_pnl <= 0]
+    count = len(recent)
+    win_rate = Decimal(len(wins)) / count if count else Decimal(0)
+    avg_win = sum(wins, Decimal(0)) / len(wins) if wins else Decimal(0)
+    avg_loss = sum(losses, Decimal(0)) / len(losses) if losses else Decimal(0)
+    payoff = avg_win / avg_loss if avg_loss > 0 else None
+    if payoff is None:
+        kelly = win_rate if wins else Decimal(0)
+    elif payoff == 0:
+        kelly = Decimal(-1)
+    else:
+        kelly = win_rate - (1 - win_rate) / payoff
+    if count < config.min_trades:
+        multiplier = Decimal(1)
+    else:
+        multiplier = min(config.max_multiplier, max(Decimal(0), config.fraction * kelly / config.baseline))
+    return KellyEstimate(
+        strategy=strategy,
+        trades=count,
+        win_rate=win_rate,
+        payoff_ratio=payoff,
+        kelly=kelly,
+        multiplier=multiplier,
+    )
+
+
+class KellyService:
+    """Keeps per-strategy fractional-Kelly multipliers current from the persisted trade journal.
+
+    Strategies read `multiplier(name)`; one without enough closed trades, or
+    not seen yet, gets 1 so it trades its base size.
+    """
+
+    def __init__(self, *, storage: IStorage, config: Optional[KellyConfig] = None) -> None:
+        self._storage = storage
+        self._config = config or KellyConfig()
+        self._estimates: Dict[str, KellyEstimate] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> KellyConfig:
+        return self._config
+
+    def estimate(self, strategy: str) -> Optional[KellyEstimate]:
+        return self._estimates.get(strategy)
+
+    def multiplier(self, strategy: str) -> Decimal:
+        estimate = self._estimates.get(strategy)
+        return estimate.multiplier if estimate is not None else Decimal(1)
+
+    async def refresh(self) -> List[KellyEstimate]:
+        trades = await load_journal(self._storage)
+        estimates = [
+            kelly_estimate(trades, strategy=name, config=self._config) for name in sorted({t.strategy for t in trades})
+        ]
+        self._estimates = {estimate.strategy: estimate for estimate in estimates}
+        for estimate in estimates:
+            self._logger.info(
+                "kelly_estimate",
+                extra={
+                    "strategy": estimate.strategy,
+                    "trades": estimate.trades,
+                    "win_rate": str(estimate.win_rate),
+                    "payoff_ratio": None if estimate.payoff_ratio is None else str(estimate.payoff_ratio),
+                    "kelly": str(estimate.kelly),
+                    "multiplier": str(estimate.multiplier),
+                },
+            )
+        return estimates
+
+    async def start(self) -> None:
+        if self._task is None:
+            await self._refresh_safely()
+            self._task = asyncio.create_task(self._run(), name="kelly")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _refresh_safely(self) -> None:
+        try:
+            await self.refresh()
+        except Exception as exc:
+            # Keep the last estimates; with none yet every strategy trades its base size
+            self._logger.info("kelly_refresh_error", extra={"error": str(exc)})
+
+    async def _run(self) -> None:
+        while True:
+            await asyncio.sleep(self._config.refresh_secs)
+            await self._refresh_safely()
+
+
+__all__ = ["KellyConfig", "KellyEstimate", "KellyService", "kelly_estimate"]
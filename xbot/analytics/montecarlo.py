@@ -0,0 +1,155 @@
+from __future__ import annotations
+
+import random
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Dict, List, Optional, Sequence, Tuple
+
+from .report import max_drawdown
+
+
+@dataclass(slots=True)
+class MonteCarloConfig:
+    simulations: int = 1000
+    method: str = "bootstrap"  # bootstrap: resample trades with replacement | shuffle: reorder only
+    slippage_bps: float = 0.0  # extra cost per trade, drawn uniformly from [0, slippage_bps] of its notional
+    fill_noise_bps: float = 0.0  # symmetric noise on each trade's PnL, stdev in bps of its notional
+    miss_rate: float = 0.0  # chance a trade never filled and drops out of the run
+    confidence: float = 0.90  # width of the reported interval
+    seed: Optional[int] = None
+
+
+@dataclass(slots=True)
+class Interval:
+    point: Decimal  # the backtest as it ran
+    low: Decimal
+    median: Decimal
+    high: Decimal
+
+    def format(self) -> str:
+        return f"{self.point:.2f} [{self.low:.2f} .. {self.high:.2f}]"
+
+
+@dataclass(slots=True)
+class MonteCarloReport:
+    simulations: int
+    confidence: float
+    pnl: Interval
+    max_drawdown: Interval
+    loss_probability: float  # share of simulations that ended below zero
+    extra: Dict[str, Any] = field(default_factory=dict)
+
+    def summary(self) -> Dict[str, Any]:
+        return {
+            "simulations": self.simulations,
+            "confidence": self.confidence,
+            "pnl": str(self.pnl.point),
+            "pnl_low": str(self.pnl.low),
+            "pnl_median": str(self.pnl.median),
+            "pnl_high": str(self.pnl.high),
+            "max_drawdown": str(self.max_drawdown.point),
+            "max_drawdown_median": str(self.max_drawdown.median),
+            "max_drawdown_high": str(self.max_drawdown.high),
+            "loss_probability": f"{self.loss_probability:.3f}",
+        }
+
+    def format(self) -> str:
+        return (
+            f"pnl {self.pnl.format()} | max_dd {self.max_drawdown.format()} | "
+            f"p(loss) {self.loss_probability:.1%} | {self.confidence:.0%} ci over {self.simulations} runs"
+        )
+
+
+def _quantile(ordered: Sequence[float], q: float) -> float:
+    if not ordered:
+        return 0.0
+    pos = q * (len(ordered) - 1)
+    lower = int(pos)
+    upper = min(lower + 1, len(ordered) - 1)
+    return ordered[lower] + (ordered[upper] - ordered[lower]) * (pos - lower)
+
+
+def _curve_stats(pnl: Sequence[float]) -> Tuple[float, float]:
+    equity = peak = worst = 0.0
+    for value in pnl:
+        equity += value
+        peak = max(peak, equity)
+        worst = max(worst, peak - equity)
+    return equity, worst
+
+
+def _dec(value: float) -> Decimal:
+    return Decimal(str(round(value, 8)))
+
+
+def simulate(
+    trade_pnl: Sequence[Decimal],
+    *,
+    notionals: Optional[Sequence[Decimal]] = None,
+    config: Optional[MonteCarloConfig] = None,
+) -> MonteCarloReport:
+    """Confidence intervals on PnL and max drawdown by resampling the trade sequence.
+
+    `trade_pnl` is one backtest's per-trade (or per-period) net PnL in
+    order. Every simulation rebuilds the sequence according to `method` and
+    optionally charges random slippage, adds fill noise and drops missed
+    trades; the perturbations scale with each trade's notional and are
+    skipped when `notionals` is not given. The point estimates are the
+    unperturbed run, so the interval shows how far luck in ordering and
+    execution could have moved them.
+    """
+    config = config or MonteCarloConfig()
+    if config.method not in ("bootstrap", "shuffle"):
+        raise ValueError(f"unknown monte carlo method {config.method!r}; use bootstrap or shuffle")
+    if notionals is not None and len(notionals) != len(trade_pnl):
+        raise ValueError("notionals must match trade_pnl one to one")
+    rng = random.Random(config.seed)
+    base = [float(value) for value in trade_pnl]
+    sizes = [abs(float(value)) for value in notionals] if notionals is not None else [0.0] * len(base)
+    indexes = list(range(len(base)))
+    finals: List[float] = []
+    drawdowns: List[float] = []
+    for _ in range(max(1, config.simulations)):
+        if config.method == "bootstrap":
+            picks = [rng.choice(indexes) for _ in indexes] if indexes else []
+        else:
+            picks = indexes[:]
+            rng.shuffle(picks)
+        run: List[float] = []
+        for i in picks:
+            if config.miss_rate and rng.random() < config.miss_rate:
+                continue
+            value = base[i]
+            if sizes[i]:
+                if config.slippage_bps:
+                    value -= rng.uniform(0.0, config.slippage_bps) * sizes[i] / 10_000
+                if config.fill_noise_bps:
+                    value += rng.gauss(0.0, config.fill_noise_bps) * sizes[i] / 10_000
+            run.append(value)
+        final, drawdown = _curve_stats(run)
+        finals.append(final)
+        drawdowns.append(drawdown)
+    finals.sort()
+    drawdowns.sort()
+    tail = (1 - config.confidence) / 2
+    point_pnl = sum((Decimal(str(value)) for value in trade_pnl), Decimal(0))
+    return MonteCarloReport(
+        simulations=len(finals),
+        confidence=config.confidence,
+        pnl=Interval(
+            point=point_pnl,
+            low=_dec(_quantile(finals, tail)),
+            median=_dec(_quantile(finals, 0.5)),
+            high=_dec(_quantile(finals, 1 - tail)),
+        ),
+        max_drawdown=Interval(
+            point=max_drawdown([Decimal(str(value)) for value in trade_pnl]),
+            low=_dec(_quantile(drawdowns, tail)),
+            median=_dec(_quantile(drawdowns, 0.5)),
+            high=_dec(_quantile(drawdowns, 1 - tail)),
+        ),
+        loss_probability=sum(1 for value in finals if value < 0) / len(finals),
+    )
+
+
+__all__ = ["Interval", "MonteCarloConfig", "MonteCarloReport", "simulate"]
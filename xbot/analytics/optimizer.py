@@ -0,0 +1,335 @@
+from __future__ import annotations
+
+import csv
+import functools
+import itertools
+import os
+import random
+from concurrent.futures import ProcessPoolExecutor
+from dataclasses import dataclass, field
+from decimal import Decimal
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Mapping, Optional, Sequence, Tuple, Union
+
+from .report import max_drawdown
+
+Params = Dict[str, Any]
+# A value list to pick from, or a (low, high) numeric range sampled uniformly (ints stay ints)
+Dimension = Union[Sequence[Any], Tuple[float, float]]
+
+
+@dataclass(slots=True)
+class RunResult:
+    params: Params
+    pnl: Decimal = Decimal(0)
+    max_drawdown: Decimal = Decimal(0)  # positive, same units as pnl
+    turnover: Decimal = Decimal(0)  # traded notional
+    trades: int = 0
+    error: Optional[str] = None
+    extra: Dict[str, Any] = field(default_factory=dict)
+    # The PnL sequence behind the totals, kept for Monte Carlo resampling; not exported
+    pnl_series: List[Decimal] = field(default_factory=list)
+    notionals: Optional[List[Decimal]] = None  # traded notional per entry of pnl_series
+
+    @property
+    def ok(self) -> bool:
+        return self.error is None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            **{f"param.{key}": value for key, value in self.params.items()},
+            "pnl": str(self.pnl),
+            "max_drawdown": str(self.max_drawdown),
+            "turnover": str(self.turnover),
+            "trades": self.trades,
+            "error": self.error or "",
+            **self.extra,
+        }
+
+
+Evaluator = Callable[[Params], RunResult]
+# Walk-forward evaluators also take the data window: evaluate(params, start=..., end=...) in epoch seconds
+WindowedEvaluator = Callable[..., RunResult]
+
+
+def result_from_pnl(
+    params: Params,
+    pnl_series: Iterable[Decimal],
+    *,
+    turnover: Decimal,
+    trades: int,
+    notionals: Optional[Iterable[Decimal]] = None,
+) -> RunResult:
+    """Build a RunResult from per-period (or per-trade) PnL, the way evaluators usually report a backtest.
+
+    Pass per-trade `notionals` to let Monte Carlo analysis perturb slippage and fills.
+    """
+    series = [Decimal(str(pnl)) for pnl in pnl_series]
+    return RunResult(
+        params=params,
+        pnl=sum(series, Decimal(0)),
+        max_drawdown=max_drawdown(series),
+        turnover=turnover,
+        trades=trades,
+        pnl_series=series,
+        notionals=[Decimal(str(value)) for value in notionals] if notionals is not None else None,
+    )
+
+
+def grid(space: Mapping[str, Sequence[Any]]) -> List[Params]:
+    """Every combination of the listed values, in a stable order."""
+    keys = list(space)
+    return [dict(zip(keys, values)) for values in itertools.product(*(list(space[key]) for key in keys))]
+
+
+def random_search(space: Mapping[str, Dimension], samples: int, *, seed: Optional[int] = None) -> List[Params]:
+    rng = random.Random(seed)
+    out: List[Params] = []
+    for _ in range(samples):
+        params: Params = {}
+        for key, dimension in space.items():
+            if isinstance(dimension, tuple) and len(dimension) == 2 and all(
+                isinstance(v, (int, float)) for v in dimension
+            ):
+                low, high = dimension
+                if isinstance(low, int) and isinstance(high, int):
+                    params[key] = rng.randint(low, high)
+                else:
+                    params[key] = rng.uniform(float(low), float(high))
+            else:
+                params[key] = rng.choice(list(dimension))
+        out.append(params)
+    return out
+
+
+def _safe_run(evaluate: Evaluator, params: Params) -> RunResult:
+    try:
+        return evaluate(params)
+    except Exception as exc:
+        return RunResult(params=params, error=f"{type(exc).__name__}: {exc}")
+
+
+def sweep(evaluate: Evaluator, param_sets: Sequence[Params], *, workers: Optional[int] = None) -> List[RunResult]:
+    """Run `evaluate` once per parameter set across a process pool.
+
+    `evaluate` must be an importable module-level function (it is pickled to
+    the workers). `workers=1` runs inline, which is handy under a debugger.
+    A run that raises is reported as a result with `error` set instead of
+    aborting the sweep. Results come back in the order of `param_sets`.
+    """
+    workers = workers or os.cpu_count() or 1
+    if workers == 1 or len(param_sets) <= 1:
+        return [_safe_run(evaluate, params) for params in param_sets]
+    with ProcessPoolExecutor(max_workers=workers) as pool:
+        return list(pool.map(_safe_run, itertools.repeat(evaluate), param_sets))
+
+
+def rank(results: Iterable[RunResult], by: str = "pnl") -> List[RunResult]:
+    """Best first: highest `by` metric (pnl by default), then smaller drawdown, then lower turnover; failures last."""
+    if by not in ("pnl", "max_drawdown", "turnover", "trades"):
+        raise ValueError(f"cannot rank by {by}")
+    # Drawdown and turnover are costs: lower ranks higher
+    sign = Decimal(1) if by in ("max_drawdown", "turnover") else Decimal(-1)
+
+    def key(result: RunResult) -> Tuple[int, Decimal, Decimal, Decimal]:
+        return (0 if result.ok else 1, sign * Decimal(getattr(result, by)), result.max_drawdown, result.turnover)
+
+    return sorted(results, key=key)
+
+
+def render_results(results: Sequence[RunResult], *, top: Optional[int] = None) -> str:
+    rows = list(results)[:top] if top else list(results)
+    if not rows:
+        return "no results"
+    keys = sorted({key for result in rows for key in result.params})
+    header = ["#", *keys, "pnl", "max_dd", "turnover", "trades"]
+    table = [header]
+    for index, result in enumerate(rows, 1):
+        metrics = (
+            [f"{result.pnl:.2f}", f"{result.max_drawdown:.2f}", f"{result.turnover:.2f}", str(result.trades)]
+            if result.ok
+            else ["error", result.error or "", "", ""]
+        )
+        table.append([str(index), *(_fmt(result.params.get(key)) for key in keys), *metrics])
+    widths = [max(len(row[i]) for row in table) for i in range(len(header))]
+    return "\n".join("  ".join(cell.rjust(width) for cell, width in zip(row, widths)) for row in table)
+
+
+def _fmt(value: Any) -> str:
+    return f"{value:.4g}" if isinstance(value, float) else str(value)
+
+
+@dataclass(slots=True, frozen=True)
+class Window:
+    start: float  # epoch seconds, inclusive
+    end: float  # exclusive
+
+
+def walk_forward_windows(
+    start: float,
+    end: float,
+    *,
+    train_secs: float,
+    test_secs: float,
+    step_secs: Optional[float] = None,
+    anchored: bool = False,
+) -> List[Tuple[Window, Window]]:
+    """(train, test) pairs rolling through [start, end).
+
+    Each test window directly follows its train window. The pair advances
+    by `step_secs` (default `test_secs`, so test windows tile the data
+    without overlap). Anchored windows keep training from `start` and only
+    grow. A trailing test window that would run past `end` is dropped.
+    """
+    if train_secs <= 0 or test_secs <= 0:
+        raise ValueError("train and test windows must be positive")
+    step = step_secs or test_secs
+    folds: List[Tuple[Window, Window]] = []
+    train_start = start
+    train_end = start + train_secs
+    while train_end + test_secs <= end:
+        folds.append((Window(train_start, train_end), Window(train_end, train_end + test_secs)))
+        train_end += step
+        if not anchored:
+            train_start += step
+    return folds
+
+
+@dataclass(slots=True)
+class Fold:
+    train: Window
+    test: Window
+    params: Params  # chosen on the train window
+    in_sample: RunResult
+    out_of_sample: RunResult
+
+
+@dataclass(slots=True)
+class WalkForwardReport:
+    folds: List[Fold]
+
+    @property
+    def oos_pnl(self) -> Decimal:
+        return sum((fold.out_of_sample.pnl for fold in self.folds), Decimal(0))
+
+    @property
+    def is_pnl(self) -> Decimal:
+        return sum((fold.in_sample.pnl for fold in self.folds), Decimal(0))
+
+    @property
+    def oos_max_drawdown(self) -> Decimal:
+        """Worst of the per-fold drawdowns and the drawdown across the chained test windows."""
+        within = max((fold.out_of_sample.max_drawdown for fold in self.folds), default=Decimal(0))
+        return max(within, max_drawdown([fold.out_of_sample.pnl for fold in self.folds]))
+
+    @property
+    def efficiency(self) -> Optional[Decimal]:
+        """Out-of-sample over in-sample PnL, per unit of window time; near 1 means the edge carried over."""
+        is_time = sum(fold.train.end - fold.train.start for fold in self.folds)
+        oos_time = sum(fold.test.end - fold.test.start for fold in self.folds)
+        if not self.folds or self.is_pnl <= 0 or oos_time <= 0:
+            return None
+        return (self.oos_pnl / Decimal(str(oos_time))) / (self.is_pnl / Decimal(str(is_time)))
+
+    @property
+    def oos_series(self) -> Tuple[List[Decimal], Optional[List[Decimal]]]:
+        """The test windows' PnL sequences chained in order, with notionals when every fold has them."""
+        series: List[Decimal] = []
+        notionals: Optional[List[Decimal]] = []
+        for fold in self.folds:
+            oos = fold.out_of_sample
+            series.extend(oos.pnl_series)
+            if notionals is not None and oos.notionals is not None:
+                notionals.extend(oos.notionals)
+            else:
+                notionals = None
+        return series, notionals
+
+    def summary(self) -> Dict[str, Any]:
+        oos = [fold.out_of_sample for fold in self.folds]
+        chosen = {tuple(sorted(fold.params.items())) for fold in self.folds}
+        efficiency = self.efficiency
+        return {
+            "folds": len(self.folds),
+            "oos_pnl": str(self.oos_pnl),
+            "is_pnl": str(self.is_pnl),
+            "oos_max_drawdown": str(self.oos_max_drawdown),
+            "oos_turnover": str(sum((r.turnover for r in oos), Decimal(0))),
+            "oos_trades": sum(r.trades for r in oos),
+            "profitable_folds": sum(1 for r in oos if r.ok and r.pnl > 0),
+            "failed_folds": sum(1 for r in oos if not r.ok),
+            "distinct_params": len(chosen),  # 1 = the same set won every fold
+            "efficiency": None if efficiency is None else f"{efficiency:.3f}",
+        }
+
+
+def walk_forward(
+    evaluate: WindowedEvaluator,
+    param_sets: Sequence[Params],
+    windows: Sequence[Tuple[Window, Window]],
+    *,
+    by: str = "pnl",
+    workers: Optional[int] = None,
+) -> WalkForwardReport:
+    """Pick the best parameters on each train window, then score only those on the following test window.
+
+    The out-of-sample numbers in the report come exclusively from data the
+    choice never saw, which is what separates a robust setting from an
+    overfit one.
+    """
+    folds: List[Fold] = []
+    for train, test in windows:
+        in_sample = sweep(functools.partial(evaluate, start=train.start, end=train.end), param_sets, workers=workers)
+        ranked = rank(in_sample, by=by)
+        best = ranked[0]
+        if not best.ok:
+            # Nothing ran on this window; carry the failure through as the out-of-sample result too
+            folds.append(Fold(train, test, best.params, best, RunResult(params=best.params, error=best.error)))
+            continue
+        oos = _safe_run(functools.partial(evaluate, start=test.start, end=test.end), best.params)
+        folds.append(Fold(train, test, best.params, best, oos))
+    return WalkForwardReport(folds)
+
+
+def render_walk_forward(report: WalkForwardReport) -> str:
+    lines = []
+    for index, fold in enumerate(report.folds, 1):
+        params = ", ".join(f"{key}={_fmt(value)}" for key, value in sorted(fold.params.items()))
+        oos = fold.out_of_sample
+        result = f"oos pnl {oos.pnl:.2f} dd {oos.max_drawdown:.2f}" if oos.ok else f"oos error {oos.error}"
+        lines.append(f"fold {index}: is pnl {fold.in_sample.pnl:.2f} | {result} | {params}")
+    lines.append(" ".join(f"{key}={value}" for key, value in report.summary().items()))
+    return "\n".join(lines)
+
+
+def export_results(results: Sequence[RunResult], path: Path) -> int:
+    rows = [result.to_dict() for result in results]
+    if not rows:
+        return 0
+    fields = list(dict.fromkeys(key for row in rows for key in row))
+    with path.open("w", newline="", encoding="utf-8") as handle:
+        writer = csv.DictWriter(handle, fieldnames=fields)
+        writer.writeheader()
+        writer.writerows(rows)
+    return len(rows)
+
+
+__all__ = [
+    "Dimension",
+    "Evaluator",
+    "Fold",
+    "RunResult",
+    "WalkForwardReport",
+    "Window",
+    "WindowedEvaluator",
+    "export_results",
+    "grid",
+    "random_search",
+    "rank",
+    "render_results",
+    "render_walk_forward",
+    "result_from_pnl",
+    "sweep",
+    "walk_forward",
+    "walk_forward_windows",
+]
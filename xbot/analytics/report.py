@@ -0,0 +1,227 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import math
+import time
+from dataclasses import dataclass
+from datetime import datetime, timedelta, timezone
+from decimal import Decimal
+from typing import Awaitable, Callable, List, Optional
+
+from ..core.alerts import AlertLevel, AlertManager
+from ..storage.base import IStorage
+from ..utils.logging import get_logger
+from .equity import EquityTracker, curve_max_drawdown
+from .journal import TradeRecord, load_journal
+from .tca import TcaReport, load_tca
+
+UnrealizedProvider = Callable[[], Awaitable[Decimal]]
+
+_DAY = 86400.0
+
+
+def _fmt_day(ts: float) -> str:
+    return datetime.fromtimestamp(ts, tz=timezone.utc).strftime("%Y-%m-%d")
+
+
+@dataclass(slots=True)
+class PerformanceReport:
+    period: str
+    start: float
+    end: float
+    trades: int
+    realized_pnl: Decimal
+    unrealized_pnl: Decimal
+    fees: Decimal
+    funding: Decimal
+    win_rate: float
+    max_drawdown: Decimal
+    sharpe: Optional[float]
+    tca: Optional[TcaReport] = None
+
+    @property
+    def total_pnl(self) -> Decimal:
+        return self.realized_pnl + self.unrealized_pnl
+
+    def format(self) -> str:
+        sharpe = f"{self.sharpe:.2f}" if self.sharpe is not None else "n/a"
+        lines = [
+            f"{self.period} report {_fmt_day(self.start)} -> {_fmt_day(self.end)}",
+            f"pnl total={self.total_pnl:.2f} realized={self.realized_pnl:.2f} unrealized={self.unrealized_pnl:.2f}",
+            f"fees={self.fees:.2f} funding={self.funding:.2f}",
+            f"trades={self.trades} win_rate={self.win_rate:.1%} max_dd={self.max_drawdown:.2f} sharpe={sharpe}",
+        ]
+        if self.tca is not None and self.tca.rows:
+            lines.append(self.tca.format())
+        return "\n".join(lines)
+
+
+def max_drawdown(pnl_series: List[Decimal]) -> Decimal:
+    """Largest peak-to-trough drop of the cumulative PnL curve (positive number)."""
+    peak = Decimal(0)
+    equity = Decimal(0)
+    worst = Decimal(0)
+    for pnl in pnl_series:
+        equity += pnl
+        peak = max(peak, equity)
+        worst = max(worst, peak - equity)
+    return worst
+
+
+def daily_pnl(trades: List[TradeRecord], start: float, end: float) -> List[Decimal]:
+    days = max(1, int(math.ceil((end - start) / _DAY)))
+    buckets = [Decimal(0)] * days
+    for trade in trades:
+        idx = min(days - 1, max(0, int((trade.exit_ts - start) // _DAY)))
+        buckets[idx] += trade.net_pnl
+    return buckets
+
+
+def sharpe_ratio(daily: List[Decimal], *, periods_per_year: int = 365) -> Optional[float]:
+    if len(daily) < 2:
+        return None
+    values = [float(v) for v in daily]
+    mean = sum(values) / len(values)
+    var = sum((v - mean) ** 2 for v in values) / (len(values) - 1)
+    if var <= 0:
+        return None
+    return mean / math.sqrt(var) * math.sqrt(periods_per_year)
+
+
+def summarize(
+    trades: List[TradeRecord],
+    *,
+    period: str,
+    start: float,
+    end: float,
+    unrealized: Decimal = Decimal(0),
+) -> PerformanceReport:
+    wins = sum(1 for t in trades if t.net_pnl > 0)
+    daily = daily_pnl(trades, start, end)
+    return PerformanceReport(
+        period=period,
+        start=start,
+        end=end,
+        trades=len(trades),
+        realized_pnl=sum((t.net_pnl for t in trades), Decimal(0)),
+        unrealized_pnl=unrealized,
+        fees=sum((t.fees for t in trades), Decimal(0)),
+        funding=sum((t.funding for t in trades), Decimal(0)),
+        win_rate=wins / len(trades) if trades else 0.0,
+        max_drawdown=max_drawdown([t.net_pnl for t in trades]),
+        sharpe=sharpe_ratio(daily),
+    )
+
+
+class PerformanceReporter:
+    """Builds daily/weekly performance reports from storage and pushes them to the alert sinks.
+
+    When an `EquityTracker` is supplied, max drawdown comes from its
+    deposit/withdrawal-adjusted equity curve instead of closed-trade PnL.
+    With `tca`, the daily report also carries transaction cost analysis
+    per strategy and symbol (`analytics.tca`).
+
+    Runs once per day at `hour_utc`; the weekly report goes out on `weekly_weekday`
+    (0 = Monday) alongside the daily one. Both cover the period that just ended.
+    """
+
+    def __init__(
+        self,
+        *,
+        storage: IStorage,
+        alerts: AlertManager,
+        unrealized: Optional[UnrealizedProvider] = None,
+        equity: Optional[EquityTracker] = None,
+        hour_utc: int = 0,
+        weekly_weekday: int = 0,
+        tca: bool = False,
+        tca_quote_age_secs: float = 10.0,
+    ) -> None:
+        self._storage = storage
+        self._alerts = alerts
+        self._unrealized = unrealized
+        self._equity = equity
+        self._hour = hour_utc
+        self._weekday = weekly_weekday
+        self._tca = tca
+        self._tca_quote_age = tca_quote_age_secs
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    async def build(self, period: str, start: float, end: float) -> PerformanceReport:
+        trades = await load_journal(self._storage, start=start, end=end)
+        unrealized = Decimal(0)
+        if self._unrealized is not None:
+            try:
+                unrealized = await self._unrealized()
+            except Exception as exc:
+                self._logger.info("report_unrealized_error", extra={"error": str(exc)})
+        report = summarize(trades, period=period, start=start, end=end, unrealized=unrealized)
+        if self._equity is not None:
+            # Prefer the flow-adjusted equity curve: it includes unrealized swings
+            curve = await self._equity.equity_curve(start, end)
+            if curve:
+                report.max_drawdown = curve_max_drawdown(curve)
+        if self._tca and period == "daily":
+            try:
+                report.tca = await load_tca(self._storage, start=start, end=end, max_quote_age_secs=self._tca_quote_age)
+            except Exception as exc:
+                self._logger.info("report_tca_error", extra={"error": str(exc)})
+        return report
+
+    async def publish(self, period: str, start: float, end: float) -> PerformanceReport:
+        report = await self.build(period, start, end)
+        await self._alerts.notify(
+            AlertLevel.INFO,
+            f"{period} performance",
+            report.format(),
+            realized_pnl=str(report.realized_pnl),
+            unrealized_pnl=str(report.unrealized_pnl),
+            trades=report.trades,
+        )
+        return report
+
+    def _next_run(self, now: datetime) -> datetime:
+        target = now.replace(hour=self._hour, minute=0, second=0, microsecond=0)
+        if target <= now:
+            target += timedelta(days=1)
+        return target
+
+    async def run_due(self, at: datetime) -> List[PerformanceReport]:
+        end = at.timestamp()
+        reports = [await self.publish("daily", end - _DAY, end)]
+        if at.weekday() == self._weekday:
+            reports.append(await self.publish("weekly", end - 7 * _DAY, end))
+        return reports
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="performance-reporter")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            due = self._next_run(datetime.now(timezone.utc))
+            await asyncio.sleep(max(0.0, due.timestamp() - time.time()))
+            try:
+                await self.run_due(due)
+            except Exception:
+                self._logger.exception("report_failed")
+
+
+__all__ = [
+    "PerformanceReport",
+    "PerformanceReporter",
+    "summarize",
+    "max_drawdown",
+    "sharpe_ratio",
+    "daily_pnl",
+]
@@ -0,0 +1,370 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import tempfile
+from dataclasses import dataclass, field
+from decimal import Decimal
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from xbot.connector.simulated import SimMarket, SimulatedConnector
+from xbot.core.alerts import Alert, AlertManager
+from xbot.core.cache import MarketCache
+from xbot.core.clock import WallClock
+from xbot.execution.circuit_breaker import DrawdownCircuitBreaker
+from xbot.execution.market_data_service import MarketDataService
+from xbot.execution.order_service import OrderService, OrderUpdatePayload, normalize_order_state
+from xbot.execution.position_service import PositionService, PositionSnapshot
+from xbot.execution.recovery import _position_qty
+from xbot.execution.risk_service import RiskLimits, RiskService, RiskViolationError
+from xbot.execution.router import ExecutionRouter
+from xbot.execution.tracking_limit import TrackingLimitEngine
+from xbot.storage.base import StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+from xbot.strategy.base import Strategy, StrategyConfig
+from xbot.strategy.market import MarketOrderStrategy
+from xbot.strategy.tracking_limit import TrackingLimitStrategy
+from ..utils.logging import get_logger
+from .equity import EquityTracker
+
+GAP = "gap"
+FUNDING_FLIP = "funding_flip"
+OUTAGE = "outage"
+WS_SILENCE = "ws_silence"
+SHOCKS = (GAP, FUNDING_FLIP, OUTAGE, WS_SILENCE)
+
+# Safeguards the runner watches, usable as `expect` keys
+DRAWDOWN_BREAKER = "drawdown_breaker"  # DrawdownCircuitBreaker tripped
+STALE_DATA_GATE = "stale_data_gate"  # RiskService refused opening orders on stale market data
+LIQUIDATED = "liquidated"  # equity fell to the maintenance margin
+
+
+@dataclass(slots=True)
+class Shock:
+    kind: str
+    at_step: int
+    steps: int = 1  # how long outages and silences last; gaps and flips persist
+    pct: Decimal = Decimal(0)  # gap: price move, -0.10 = 10% down
+    rate: Optional[Decimal] = None  # funding_flip: the new per-settlement rate
+
+    def active(self, step: int) -> bool:
+        return self.at_step <= step < self.at_step + self.steps
+
+
+@dataclass(slots=True)
+class Scenario:
+    name: str
+    shocks: List[Shock]
+    steps: int = 40
+    expect: Dict[str, bool] = field(default_factory=dict)  # safeguard -> should it fire
+
+
+@dataclass(slots=True)
+class StressConfig:
+    symbol: str = "SOL"
+    mode: str = "market"  # the strategy that builds the exposure: market | tracking_limit
+    side: str = "buy"
+    qty: Decimal = Decimal("150")
+    price: Decimal = Decimal("100")
+    collateral: Decimal = Decimal("10000")
+    spread_bps: Decimal = Decimal("2")
+    funding_rate: Decimal = Decimal("-0.0001")  # per settlement, longs pay when positive
+    funding_every_steps: int = 5
+    maintenance_margin: Decimal = Decimal("0.05")  # of exposure
+    initial_margin: Decimal = Decimal("0.10")  # of exposure, for margin usage
+    step_secs: float = 0.05  # wall-clock time per step; the risk stack runs on real time
+    stale_after_secs: float = 0.5
+    risk_limits: RiskLimits = field(default_factory=lambda: RiskLimits(max_drawdown_pct=Decimal("0.10")))
+
+
+@dataclass(slots=True)
+class StressResult:
+    scenario: str
+    pnl: Decimal
+    min_equity: Decimal
+    max_drawdown: Decimal  # fraction of the peak equity
+    max_margin_usage: Decimal  # initial margin over equity, 1 = fully used
+    funding_paid: Decimal
+    fired: Dict[str, Optional[int]]  # safeguard -> first step it fired, None if it never did
+    unreachable_steps: int
+    alerts: List[str]
+    expect: Dict[str, bool]
+
+    @property
+    def failures(self) -> List[str]:
+        return [
+            f"{name} {'did not fire' if expected else 'fired'}"
+            for name, expected in self.expect.items()
+            if (self.fired.get(name) is not None) != expected
+        ]
+
+    @property
+    def passed(self) -> bool:
+        return not self.failures
+
+    def format(self) -> str:
+        fired = ", ".join(f"{name}@{step}" for name, step in self.fired.items() if step is not None) or "none"
+        verdict = "PASS" if self.passed else "FAIL: " + "; ".join(self.failures)
+        return (
+            f"{self.scenario}: pnl {self.pnl:.2f} | min equity {self.min_equity:.2f} | "
+            f"max dd {self.max_drawdown:.2%} | max margin {self.max_margin_usage:.0%} | "
+            f"funding {self.funding_paid:.2f} | "
+            f"unreachable {self.unreachable_steps} steps | fired {fired} | {verdict}"
+        )
+
+
+def builtin_scenarios(side: str = "buy") -> List[Scenario]:
+    """The standard shocks, oriented against (or for) a position opened on `side`."""
+    against = Decimal(-1) if side == "buy" else Decimal(1)
+    return [
+        Scenario(
+            "gap_against_10pct",
+            [Shock(GAP, 10, pct=against * Decimal("0.10"))],
+            expect={DRAWDOWN_BREAKER: True, LIQUIDATED: False},
+        ),
+        Scenario(
+            "gap_favour_10pct",
+            [Shock(GAP, 10, pct=-against * Decimal("0.10"))],
+            expect={DRAWDOWN_BREAKER: False},
+        ),
+        Scenario(
+            # Longs pay a positive rate: the flip makes the position pay 1% per settlement
+            "funding_flip",
+            [Shock(FUNDING_FLIP, 5, rate=-against * Decimal("0.01"))],
+            expect={DRAWDOWN_BREAKER: True, LIQUIDATED: False},
+        ),
+        Scenario(
+            "venue_outage",
+            [Shock(OUTAGE, 10, steps=20), Shock(GAP, 15, pct=against * Decimal("0.05"))],
+            expect={STALE_DATA_GATE: True, DRAWDOWN_BREAKER: False},
+        ),
+        Scenario(
+            "ws_silence",
+            [Shock(WS_SILENCE, 10, steps=20)],
+            expect={STALE_DATA_GATE: True, DRAWDOWN_BREAKER: False},
+        ),
+    ]
+
+
+class _CollectingSink:
+    def __init__(self) -> None:
+        self.alerts: List[Alert] = []
+
+    async def send(self, alert: Alert) -> None:
+        self.alerts.append(alert)
+
+
+def _strategy(config: StressConfig, router: ExecutionRouter) -> Strategy:
+    strategy_cfg = StrategyConfig(
+        symbol=config.symbol, mode=config.mode, qty=float(config.qty), side=config.side, interval_secs=1.0
+    )
+    if config.mode == "market":
+        return MarketOrderStrategy(router=router, clock=WallClock(), config=strategy_cfg)
+    if config.mode == "tracking_limit":
+        return TrackingLimitStrategy(router=router, clock=WallClock(), config=strategy_cfg, wait_after_fill=3600.0)
+    raise ValueError(f"stress tests support market and tracking_limit strategies, not {config.mode}")
+
+
+class StressRunner:
+    """Replays synthetic shocks against the real risk stack on a simulated venue.
+
+    Each scenario builds a fresh `SimulatedConnector` and the services the
+    bot runs live (market data, positions, risk, orders, router, equity
+    tracker and drawdown breaker), then lets the configured strategy open
+    its exposure. Steps advance every `step_secs` of wall-clock time, since
+    staleness and drawdown checks read the real clock. Each step applies
+    the shocks due, feeds the book to the cache unless the WS is silent or
+    the venue down, settles funding, and runs the breaker. It then asks
+    `RiskService` whether a new opening order would pass; that is how the
+    stale-data gate and reduce-only mode are observed. The result records
+    PnL, drawdown, margin usage and which safeguards fired, and checks
+    them against the scenario's `expect`.
+    """
+
+    def __init__(self, config: Optional[StressConfig] = None) -> None:
+        self._config = config or StressConfig()
+        self._logger = get_logger(__name__)
+
+    async def run_all(self, scenarios: List[Scenario]) -> List[StressResult]:
+        return [await self.run(scenario) for scenario in scenarios]
+
+    async def run(self, scenario: Scenario) -> StressResult:
+        with tempfile.TemporaryDirectory(prefix="xbot-stress-") as root:
+            return await self._run(scenario, Path(root))
+
+    async def _run(self, scenario: Scenario, root: Path) -> StressResult:
+        cfg = self._config
+        venue_symbol = f"{cfg.symbol.upper()}_USD_PERP"
+        market = SimMarket(venue_symbol, mark=cfg.price, spread_bps=cfg.spread_bps, funding_rate=cfg.funding_rate)
+        connector = SimulatedConnector(markets=[market], collateral=cfg.collateral)
+        market_data = MarketDataService(
+            connector=connector, symbol_map={cfg.symbol: venue_symbol}, stale_after_secs=cfg.stale_after_secs
+        )
+        positions = PositionService()
+        risk = RiskService(market_data=market_data, position_service=positions, limits=cfg.risk_limits)
+        storage = JsonlStorage(root / "storage")
+        await storage.start()
+        writer = StorageWriter(storage)
+        orders = OrderService(
+            connector=connector,
+            market_data=market_data,
+            risk_service=risk,
+            tracking_engine=TrackingLimitEngine(market_data=market_data),
+            log_root=root / "orders",
+            storage=writer,
+        )
+        cache = MarketCache(source=connector.venue)
+        market_data.attach_cache(cache)
+        router = ExecutionRouter(
+            order_service=orders, position_service=positions, risk_service=risk, market_data=market_data, cache=cache
+        )
+
+        async def on_order_update(client_order_index: int, status: str, info: Dict[str, Any]) -> None:
+            state = normalize_order_state(status)
+            if state is None:
+                return
+            payload = OrderUpdatePayload(client_order_index, state, str(info.get("exchange_order_id") or ""), info)
+            with contextlib.suppress(Exception):
+                await orders.ingest_update(payload)
+
+        connector.attach_order_updates(on_order_update)
+        sink = _CollectingSink()
+        alerts = AlertManager([sink])
+        equity = EquityTracker(connectors=[connector], writer=writer)
+        breaker: Optional[DrawdownCircuitBreaker] = None
+        if cfg.risk_limits.max_drawdown_pct is not None:
+            breaker = DrawdownCircuitBreaker(equity=equity, risk=risk, alerts=alerts)
+        await cache.set_top(venue_symbol, float(market.bid), float(market.ask))
+        strategy = _strategy(cfg, router)
+        strategy_task = asyncio.create_task(strategy.start(), name=f"stress-{scenario.name}")
+        probe_size_i = await market_data.to_size_i(cfg.symbol, cfg.qty)
+        fired: Dict[str, Optional[int]] = {DRAWDOWN_BREAKER: None, STALE_DATA_GATE: None, LIQUIDATED: None}
+        unreachable = 0
+        peak = min_equity = connector.equity()
+        worst_dd = max_margin = Decimal(0)
+        try:
+            for step in range(scenario.steps):
+                active = [shock for shock in scenario.shocks if shock.active(step)]
+                for shock in scenario.shocks:
+                    if shock.at_step != step:
+                        continue
+                    if shock.kind == GAP:
+                        connector.set_mark(venue_symbol, market.mark * (1 + shock.pct))
+                    elif shock.kind == FUNDING_FLIP and shock.rate is not None:
+                        market.funding_rate = shock.rate
+                connector.down = any(shock.kind == OUTAGE for shock in active)
+                ws_silent = connector.down or any(shock.kind == WS_SILENCE for shock in active)
+                if step and cfg.funding_every_steps and step % cfg.funding_every_steps == 0:
+                    connector.settle_funding(venue_symbol)
+                if not ws_silent:
+                    await cache.set_top(venue_symbol, float(market.bid), float(market.ask))
+                    await connector.flush_updates()
+                if connector.down:
+                    unreachable += 1
+                else:
+                    await self._sync_positions(connector, market_data, positions)
+                if breaker is not None:
+                    await equity.snapshot()
+                    await breaker.check()
+                    if breaker.tripped and fired[DRAWDOWN_BREAKER] is None:
+                        fired[DRAWDOWN_BREAKER] = step
+                blocked = await self._probe(risk, cfg, probe_size_i)
+                if blocked is not None and "stale" in blocked and fired[STALE_DATA_GATE] is None:
+                    fired[STALE_DATA_GATE] = step
+                value = connector.equity()
+                exposure = connector.exposure()
+                peak = max(peak, value)
+                min_equity = min(min_equity, value)
+                if peak > 0:
+                    worst_dd = max(worst_dd, (peak - value) / peak)
+                if value > 0:
+                    max_margin = max(max_margin, exposure * cfg.initial_margin / value)
+                if exposure and value <= exposure * cfg.maintenance_margin:
+                    fired[LIQUIDATED] = step
+                    break
+                await asyncio.sleep(cfg.step_secs)
+        finally:
+            strategy_task.cancel()
+            with contextlib.suppress(asyncio.CancelledError, Exception):
+                await strategy_task
+            await writer.flush()
+        result = StressResult(
+            scenario=scenario.name,
+            pnl=connector.equity() - cfg.collateral,
+            min_equity=min_equity,
+            max_drawdown=worst_dd,
+            max_margin_usage=max_margin,
+            funding_paid=connector.funding_paid,
+            fired=fired,
+            unreachable_steps=unreachable,
+            alerts=[alert.title for alert in sink.alerts],
+            expect=dict(scenario.expect),
+        )
+        self._logger.info(
+            "stress_scenario",
+            extra={"scenario": scenario.name, "pnl": str(result.pnl), "fired": fired, "passed": result.passed},
+        )
+        return result
+
+    async def _sync_positions(
+        self, connector: SimulatedConnector, market_data: MarketDataService, positions: PositionService
+    ) -> None:
+        # What the reconciler does live: the venue's positions become the risk layer's view
+        await positions.reset()
+        for raw in await connector.get_positions():
+            symbol = market_data.canonical_for(str(raw.get("symbol")))
+            qty = _position_qty(raw)
+            if symbol is None or qty is None:
+                continue
+            notional = Decimal(str(raw.get("netExposureNotional") or 0))
+            await positions.ingest(
+                PositionSnapshot(symbol=symbol, base_qty=qty, quote_value=notional, notional=notional, raw=raw)
+            )
+
+    async def _probe(self, risk: RiskService, cfg: StressConfig, size_i: int) -> Optional[str]:
+        """Why a new opening order would be refused right now, or None when it would pass."""
+        try:
+            await risk.validate_order(symbol=cfg.symbol, size_i=size_i, is_ask=cfg.side == "sell", market=True)
+        except RiskViolationError as exc:
+            return str(exc)
+        except Exception as exc:
+            return f"unreachable: {exc}"
+        return None
+
+
+def scenario_from_dict(raw: Dict[str, Any]) -> Scenario:
+    shocks = []
+    for item in raw.get("shocks") or []:
+        kind = str(item["kind"]).lower()
+        if kind not in SHOCKS:
+            raise ValueError(f"unknown shock {kind!r}; use one of {', '.join(SHOCKS)}")
+        shocks.append(
+            Shock(
+                kind=kind,
+                at_step=int(item.get("at_step", 0)),
+                steps=int(item.get("steps", 1)),
+                pct=Decimal(str(item.get("pct", 0))),
+                rate=Decimal(str(item["rate"])) if item.get("rate") is not None else None,
+            )
+        )
+    return Scenario(
+        name=str(raw["name"]),
+        shocks=shocks,
+        steps=int(raw.get("steps", 40)),
+        expect={str(key): bool(value) for key, value in (raw.get("expect") or {}).items()},
+    )
+
+
+__all__ = [
+    "DRAWDOWN_BREAKER",
+    "LIQUIDATED",
+    "STALE_DATA_GATE",
+    "Scenario",
+    "Shock",
+    "StressConfig",
+    "StressResult",
+    "StressRunner",
+    "builtin_scenarios",
+    "scenario_from_dict",
+]
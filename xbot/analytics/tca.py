@@ -0,0 +1,231 @@
+from __future__ import annotations
+
+import bisect
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from decimal import Decimal
+from typing import Any, Dict, Iterable, List, Mapping, Optional, Tuple
+
+from ..storage.base import FILL_SNAPSHOTS, FILLS, MARKET_SNAPSHOTS, ORDER_EVENTS, IStorage
+from .journal import strategy_tag
+
+_BPS = Decimal(10_000)
+
+
+def _dec(value: Any) -> Optional[Decimal]:
+    if value in (None, ""):
+        return None
+    try:
+        return Decimal(str(value))
+    except Exception:
+        return None
+
+
+def _truthy(value: Any) -> bool:
+    if isinstance(value, str):
+        return value.strip().lower() in ("1", "true", "yes", "maker")
+    return bool(value)
+
+
+class _Quotes:
+    """Mid prices over time per (venue, symbol), for "what was the market when" lookups."""
+
+    def __init__(self, max_age_secs: float) -> None:
+        self._max_age = max_age_secs
+        self._series: Dict[Tuple[str, str], Tuple[List[float], List[Decimal]]] = {}
+
+    def add(self, venue: str, symbol: str, ts: float, bid: Any, ask: Any) -> None:
+        bid_d, ask_d = _dec(bid), _dec(ask)
+        if bid_d is None or ask_d is None or bid_d <= 0 or ask_d <= 0:
+            return
+        times, mids = self._series.setdefault((venue, symbol.upper()), ([], []))
+        at = bisect.bisect_right(times, ts)
+        times.insert(at, ts)
+        mids.insert(at, (bid_d + ask_d) / 2)
+
+    def mid(self, venue: str, symbol: str, ts: float) -> Optional[Decimal]:
+        """Latest mid at or before `ts`, if it is at most `max_age_secs` old."""
+        series = self._series.get((venue, symbol.upper()))
+        if series is None:
+            return None
+        times, mids = series
+        at = bisect.bisect_right(times, ts) - 1
+        if at < 0 or ts - times[at] > self._max_age:
+            return None
+        return mids[at]
+
+
+@dataclass(slots=True)
+class TcaRow:
+    strategy: str
+    symbol: str
+    fills: int = 0
+    orders: int = 0
+    notional: Decimal = Decimal(0)
+    maker_notional: Decimal = Decimal(0)
+    fees: Decimal = Decimal(0)
+    spread_notional: Decimal = Decimal(0)  # notional of the fills with a reference mid
+    spread_cost: Decimal = Decimal(0)  # sum of side * (price - mid) * size
+    shortfall_notional: Decimal = Decimal(0)  # notional of the orders with an arrival price
+    shortfall_cost: Decimal = Decimal(0)  # sum of side * (avg price - arrival mid) * filled size
+
+    @property
+    def maker_share(self) -> float:
+        return float(self.maker_notional / self.notional) if self.notional else 0.0
+
+    @property
+    def effective_spread_bps(self) -> Optional[Decimal]:
+        """Twice the notional-weighted distance from mid paid per fill; negative when we earned it."""
+        if not self.spread_notional:
+            return None
+        return 2 * self.spread_cost / self.spread_notional * _BPS
+
+    @property
+    def shortfall_bps(self) -> Optional[Decimal]:
+        """Notional-weighted cost of the filled part of each order against the mid when it was sent."""
+        if not self.shortfall_notional:
+            return None
+        return self.shortfall_cost / self.shortfall_notional * _BPS
+
+    def to_dict(self) -> Dict[str, Any]:
+        spread, shortfall = self.effective_spread_bps, self.shortfall_bps
+        return {
+            "strategy": self.strategy,
+            "symbol": self.symbol,
+            "fills": self.fills,
+            "orders": self.orders,
+            "notional": str(self.notional),
+            "maker_share": round(self.maker_share, 4),
+            "fees": str(self.fees),
+            "effective_spread_bps": str(round(spread, 2)) if spread is not None else None,
+            "shortfall_bps": str(round(shortfall, 2)) if shortfall is not None else None,
+            "shortfall_cost": str(self.shortfall_cost),
+        }
+
+
+@dataclass(slots=True)
+class TcaReport:
+    start: float
+    end: float
+    rows: List[TcaRow] = field(default_factory=list)
+
+    def format(self) -> str:
+        day = datetime.fromtimestamp(self.start, tz=timezone.utc).strftime("%Y-%m-%d")
+        lines = [f"tca {day}: {len(self.rows)} strategy/symbol pairs"]
+        for row in self.rows:
+            spread, shortfall = row.effective_spread_bps, row.shortfall_bps
+            lines.append(
+                f"{row.strategy} {row.symbol}: fills={row.fills} notional={row.notional:.2f}"
+                f" maker={row.maker_share:.0%}"
+                f" spread={f'{spread:.2f}bps' if spread is not None else 'n/a'}"
+                f" shortfall={f'{shortfall:.2f}bps' if shortfall is not None else 'n/a'}"
+                f" fees={row.fees:.2f}"
+            )
+        return "\n".join(lines)
+
+
+def analyze(
+    fills: Iterable[Mapping[str, Any]],
+    *,
+    market_snapshots: Iterable[Mapping[str, Any]] = (),
+    fill_snapshots: Iterable[Mapping[str, Any]] = (),
+    order_events: Iterable[Mapping[str, Any]] = (),
+    start: float = 0.0,
+    end: float = float("inf"),
+    max_quote_age_secs: float = 10.0,
+) -> TcaReport:
+    """Transaction costs per strategy and symbol for the fills in [start, end).
+
+    The reference mid of a fill is the latest `market_snapshots` touch no
+    older than `max_quote_age_secs`, else the book in its fill snapshot
+    (taken just after the fill, so it may already reflect it). An order's
+    arrival price is the mid when it was submitted (its first
+    `order_events` row), else the reference mid of its first fill. Side is
+    +1 for buys, so positive costs are paid and negative ones earned.
+    """
+    quotes = _Quotes(max_quote_age_secs)
+    after: Dict[Tuple[str, str], Decimal] = {}  # (venue, client_order_index) -> mid just after its fill
+    venue_symbols: Dict[Tuple[str, str], str] = {}  # (venue, canonical) -> venue symbol
+    for snap in fill_snapshots:
+        venue, coi = str(snap.get("venue") or ""), str(snap.get("client_order_index") or "")
+        if snap.get("venue_symbol"):
+            venue_symbols[(venue, str(snap.get("symbol") or "").upper())] = str(snap["venue_symbol"])
+        bid, ask = _dec(snap.get("bid")), _dec(snap.get("ask"))
+        if bid and ask:
+            after.setdefault((venue, coi), (bid + ask) / 2)
+    for snap in market_snapshots:
+        venue, symbol = str(snap.get("venue") or ""), str(snap.get("symbol") or "")
+        quotes.add(venue, symbol, float(snap["ts"]), snap.get("bid"), snap.get("ask"))
+    submitted: Dict[Tuple[str, str], float] = {}
+    for event in order_events:
+        key = (str(event.get("venue") or ""), str(event.get("client_order_index") or ""))
+        ts = float(event["ts"])
+        if key not in submitted or ts < submitted[key]:
+            submitted[key] = ts
+
+    def reference(venue: str, symbol: str, ts: float) -> Optional[Decimal]:
+        native = venue_symbols.get((venue, symbol.upper()), symbol)
+        return quotes.mid(venue, native, ts) or quotes.mid(venue, symbol, ts)
+
+    rows: Dict[Tuple[str, str], TcaRow] = {}
+    orders: Dict[Tuple[str, str], Dict[str, Any]] = {}
+    for fill in sorted(fills, key=lambda f: float(f["ts"])):
+        ts = float(fill["ts"])
+        price, size = _dec(fill.get("price")), _dec(fill.get("size"))
+        if not start <= ts < end or not price or not size:
+            continue
+        venue, symbol = str(fill.get("venue") or ""), str(fill.get("symbol") or "")
+        coi = str(fill.get("client_order_index") or "")
+        side = Decimal(-1) if str(fill.get("side")).lower() == "sell" else Decimal(1)
+        row = rows.setdefault((strategy_tag(fill), symbol), TcaRow(strategy=strategy_tag(fill), symbol=symbol))
+        notional = price * abs(size)
+        row.fills += 1
+        row.notional += notional
+        row.fees += _dec(fill.get("fee")) or Decimal(0)
+        if _truthy(fill.get("maker")):
+            row.maker_notional += notional
+        mid = reference(venue, symbol, ts) or after.get((venue, coi))
+        if mid:
+            row.spread_notional += notional
+            row.spread_cost += side * (price - mid) * abs(size)
+        order = orders.setdefault(
+            (venue, coi), {"row": row, "side": side, "size": Decimal(0), "value": Decimal(0), "mid": mid}
+        )
+        order["size"] += abs(size)
+        order["value"] += notional
+    for (venue, coi), order in orders.items():
+        row = order["row"]
+        row.orders += 1
+        sent = submitted.get((venue, coi))
+        arrival = reference(venue, row.symbol, sent) if sent is not None else None
+        arrival = arrival or order["mid"]
+        if not arrival:
+            continue
+        average = order["value"] / order["size"]
+        row.shortfall_notional += order["value"]
+        row.shortfall_cost += order["side"] * (average - arrival) * order["size"]
+    report = TcaReport(start=start, end=end)
+    report.rows = sorted(rows.values(), key=lambda r: (r.strategy, r.symbol))
+    return report
+
+
+async def load_tca(
+    storage: IStorage, *, start: float, end: float, max_quote_age_secs: float = 10.0, lookback_secs: float = 3600.0
+) -> TcaReport:
+    """`analyze` over stored rows; order events and quotes are read from `lookback_secs` before `start`."""
+    fills = await storage.query(FILLS, start=start, end=end)
+    snapshots = await storage.query(FILL_SNAPSHOTS, start=start, end=end)
+    quotes = await storage.query(MARKET_SNAPSHOTS, start=start - lookback_secs, end=end)
+    events = await storage.query(ORDER_EVENTS, start=start - lookback_secs, end=end, filters={"state": "submitting"})
+    return analyze(
+        fills,
+        market_snapshots=quotes,
+        fill_snapshots=snapshots,
+        order_events=events,
+        start=start,
+        end=end,
+        max_quote_age_secs=max_quote_age_secs,
+    )
+
+
+__all__ = ["TcaReport", "TcaRow", "analyze", "load_tca"]
@@ -0,0 +1,127 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Any, Dict, List, Optional, Protocol, Sequence
+
+from xbot.connector.history import Ticker
+
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+
+class TickerSource(Protocol):
+    venue: str
+
+    async def get_tickers(self) -> List[Ticker]:  # pragma: no cover
+        ...
+
+
+@dataclass(slots=True)
+class TickerStatsConfig:
+    interval_secs: float = 300.0
+    max_age_secs: float = 1800.0  # stats older than this are treated as missing
+
+
+class TickerStats:
+    """Cache of every source's 24h ticker statistics, for symbol screening.
+
+    Each refresh polls `get_tickers()` on every source and replaces that
+    venue's rows; a failed poll keeps the previous ones until they are
+    `max_age_secs` old. `liquid(venue, symbol, floor)` is the screening
+    check: True when the 24h quote volume reaches `floor`, and also when
+    the symbol has no fresh stats, so a gap in polling never blocks a
+    strategy on its own.
+    """
+
+    def __init__(self, *, sources: Sequence[TickerSource], config: Optional[TickerStatsConfig] = None) -> None:
+        self._sources = list(sources)
+        self._config = config or TickerStatsConfig()
+        self._tickers: Dict[str, Dict[str, Ticker]] = {}  # venue -> venue symbol -> ticker
+        self._updated: Dict[str, float] = {}  # venue -> last successful poll
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> TickerStatsConfig:
+        return self._config
+
+    async def refresh(self, now: Optional[float] = None) -> int:
+        """Poll every source; returns the number of tickers received."""
+        now = time.time() if now is None else now
+        received = 0
+        for source in self._sources:
+            try:
+                tickers = await source.get_tickers()
+            except Exception as exc:
+                self._logger.info("ticker_stats_source_error", extra={"venue": source.venue, "error": str(exc)})
+                continue
+            by_venue: Dict[str, Dict[str, Ticker]] = {}
+            for ticker in tickers:
+                by_venue.setdefault(ticker.venue.lower(), {})[ticker.symbol.upper()] = ticker
+            for venue, rows in by_venue.items():
+                self._tickers[venue] = rows
+                self._updated[venue] = now
+                METRICS.set("ticker_stats_symbols", len(rows), venue=venue)
+            received += len(tickers)
+        return received
+
+    def _fresh(self, venue: str, now: Optional[float]) -> bool:
+        updated = self._updated.get(venue)
+        current = time.time() if now is None else now
+        return updated is not None and current - updated <= self._config.max_age_secs
+
+    def get(self, venue: str, symbol: str, *, now: Optional[float] = None) -> Optional[Ticker]:
+        venue = venue.lower()
+        if not self._fresh(venue, now):
+            return None
+        return self._tickers.get(venue, {}).get(symbol.upper())
+
+    def quote_volume(self, venue: str, symbol: str, *, now: Optional[float] = None) -> Optional[Decimal]:
+        ticker = self.get(venue, symbol, now=now)
+        return ticker.quote_volume if ticker is not None else None
+
+    def liquid(self, venue: str, symbol: str, floor: Decimal, *, now: Optional[float] = None) -> bool:
+        volume = self.quote_volume(venue, symbol, now=now)
+        return volume is None or volume >= floor
+
+    def tickers(self, venue: Optional[str] = None, *, now: Optional[float] = None) -> List[Ticker]:
+        venues = [venue.lower()] if venue is not None else sorted(self._tickers)
+        found: List[Ticker] = []
+        for name in venues:
+            if self._fresh(name, now):
+                rows = self._tickers.get(name, {})
+                found.extend(rows[symbol] for symbol in sorted(rows))
+        return found
+
+    def snapshot(self, venue: Optional[str] = None) -> Dict[str, Any]:
+        return {
+            "updated": dict(self._updated),
+            "tickers": [ticker.to_dict() for ticker in self.tickers(venue)],
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="ticker-stats")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.refresh()
+            except Exception:
+                self._logger.exception("ticker_stats_refresh_failed")
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = ["TickerSource", "TickerStats", "TickerStatsConfig"]
@@ -0,0 +1,397 @@
+from __future__ import annotations
+
+import hmac
+import json
+from dataclasses import dataclass, field, fields, replace
+from decimal import Decimal, InvalidOperation
+from typing import Any, Awaitable, Callable, Dict, List, Optional
+
+from aiohttp import web
+
+from xbot.analytics.correlation import CorrelationService
+from xbot.analytics.funding_table import FundingTable
+from xbot.analytics.ticker_stats import TickerStats
+from xbot.connector.ws_pool import WsConnectionPool
+from xbot.core.approvals import FLATTEN_ALL, RAISE_RISK_LIMITS, WITHDRAW, ApprovalDenied, ApprovalGate, PendingApproval
+from xbot.core.audit import AUDIT, OPERATOR_ACTION
+from xbot.core.metrics import METRICS
+from xbot.execution.market_data_service import UnknownSymbolError
+from xbot.execution.risk_service import RiskLimits, RiskService, SymbolLists
+from xbot.execution.rejections import RejectionTracker
+from xbot.execution.router import ExecutionRouter
+from xbot.execution.screener import UniverseScreener
+from xbot.execution.watchlist import Watchlist
+from xbot.execution.withdrawals import WithdrawalGuard, WithdrawalRejected
+from xbot.utils.logging import get_logger
+
+Handler = Callable[[web.Request], Awaitable[web.StreamResponse]]
+
+_READS = ("GET", "HEAD", "OPTIONS")
+_MAX_AUDIT_BODY = 4096
+
+
+@dataclass(slots=True)
+class AdminApiConfig:
+    host: str = "127.0.0.1"
+    port: int = 8089
+    token: Optional[str] = None  # required for every endpoint except /health; acts as operator "admin"
+    operators: Dict[str, str] = field(default_factory=dict)  # operator name -> token, for two-person approvals
+
+    def tokens(self) -> Dict[str, str]:
+        tokens = dict(self.operators)
+        if self.token:
+            tokens.setdefault("admin", self.token)
+        return tokens
+
+
+def _json(payload: Any, status: int = 200) -> web.Response:
+    return web.Response(text=json.dumps(payload, default=str), status=status, content_type="application/json")
+
+
+def _operator(request: web.Request) -> str:
+    return str(request.get("operator") or "")
+
+
+def _approval(pending: PendingApproval) -> web.Response:
+    # 202 while parked for a second party, otherwise the action already ran
+    status = {"pending": 202, "failed": 502}.get(pending.status.value, 200)
+    return _json(pending.to_dict(), status=status)
+
+
+class AdminApi:
+    """Small authenticated HTTP surface for operators (health, metrics, withdrawals).
+
+    Binds to localhost by default; without a token only `/health` answers, so a
+    misconfigured deployment can never expose mutating endpoints.
+    """
+
+    def __init__(self, config: AdminApiConfig) -> None:
+        self._config = config
+        self._app = web.Application(middlewares=[self._auth])
+        self._runner: Optional[web.AppRunner] = None
+        self._logger = get_logger(__name__)
+        self.add_route("GET", "/health", self._health)
+        self.add_route("GET", "/metrics", self._metrics)
+
+    def add_route(self, method: str, path: str, handler: Handler) -> None:
+        self._app.router.add_route(method, path, handler)
+
+    @web.middleware
+    async def _auth(self, request: web.Request, handler: Handler) -> web.StreamResponse:
+        if request.path == "/health":
+            return await handler(request)
+        supplied = request.headers.get("Authorization", "").removeprefix("Bearer ").strip()
+        operator = next(
+            (
+                name
+                for name, token in self._config.tokens().items()
+                if token and hmac.compare_digest(supplied.encode(), token.encode())
+            ),
+            None,
+        )
+        if operator is None:
+            self._logger.warning("admin_unauthorized", extra={"path": request.path, "remote": request.remote})
+            self._audit(request, 401)
+            return _json({"error": "unauthorized"}, status=401)
+        request["operator"] = operator
+        # aiohttp caches the body, so handlers can still read it
+        body = await request.text() if request.method not in _READS and request.can_read_body else None
+        try:
+            response = await handler(request)
+        except web.HTTPException as exc:
+            self._audit(request, exc.status, body)
+            raise
+        self._audit(request, response.status, body)
+        return response
+
+    def _audit(self, request: web.Request, status: int, body: Optional[str] = None) -> None:
+        # Reads change nothing; every mutating call is on record, refused or not
+        if request.method in _READS:
+            return
+        AUDIT.record(
+            OPERATOR_ACTION,
+            method=request.method,
+            path=request.path,
+            operator=request.get("operator"),
+            query=dict(request.query) or None,
+            body=body[:_MAX_AUDIT_BODY] if body else None,
+            status=status,
+            remote=request.remote,
+        )
+
+    async def _health(self, request: web.Request) -> web.Response:
+        return _json({"ok": True})
+
+    async def _metrics(self, request: web.Request) -> web.Response:
+        return _json(METRICS.snapshot())
+
+    def mount_approvals(self, gate: ApprovalGate) -> None:
+        async def listing(request: web.Request) -> web.Response:
+            return _json([p.to_dict() for p in gate.approvals()])
+
+        async def approve(request: web.Request) -> web.Response:
+            try:
+                body = await request.json() if request.can_read_body else {}
+                code = body.get("code")
+            except (ValueError, AttributeError) as exc:
+                return _json({"error": f"invalid request: {exc}"}, status=400)
+            try:
+                pending = await gate.approve(
+                    request.match_info["approval_id"],
+                    operator=_operator(request),
+                    code=str(code) if code is not None else None,
+                )
+            except KeyError:
+                return _json({"error": "unknown approval"}, status=404)
+            except ApprovalDenied as exc:
+                return _json({"error": str(exc)}, status=403)
+            return _approval(pending)
+
+        async def reject(request: web.Request) -> web.Response:
+            try:
+                pending = await gate.reject(request.match_info["approval_id"], operator=_operator(request))
+            except KeyError:
+                return _json({"error": "unknown approval"}, status=404)
+            return _json(pending.to_dict())
+
+        self.add_route("GET", "/approvals", listing)
+        self.add_route("POST", "/approvals/{approval_id}/approve", approve)
+        self.add_route("POST", "/approvals/{approval_id}/reject", reject)
+
+    def mount_withdrawals(self, guard: WithdrawalGuard, gate: Optional[ApprovalGate] = None) -> None:
+        async def create(request: web.Request) -> web.Response:
+            try:
+                body = await request.json()
+                quantity = Decimal(str(body["quantity"]))
+                pending = await guard.request(
+                    symbol=str(body["symbol"]),
+                    blockchain=str(body["blockchain"]),
+                    address=str(body["address"]),
+                    quantity=quantity,
+                    requested_by=_operator(request),
+                )
+            except (KeyError, ValueError, InvalidOperation) as exc:
+                # WithdrawalRejected is a ValueError
+                return _json({"error": str(exc)}, status=400)
+            return _json(pending.to_dict(), status=202)
+
+        async def confirm(request: web.Request) -> web.Response:
+            withdrawal_id = request.match_info["withdrawal_id"]
+            if gate is not None and gate.required(WITHDRAW):
+                match = next((p for p in guard.withdrawals() if p.withdrawal_id == withdrawal_id), None)
+                if match is None:
+                    return _json({"error": "unknown withdrawal"}, status=404)
+
+                async def send() -> Dict[str, Any]:
+                    sent = await guard.confirm(withdrawal_id)
+                    if sent.error is not None:
+                        raise RuntimeError(sent.error)
+                    return sent.to_dict()
+
+                summary = f"withdraw {match.quantity} {match.symbol} to {match.address} on {match.blockchain}"
+                return _approval(await gate.submit(WITHDRAW, summary, send, requested_by=_operator(request)))
+            try:
+                pending = await guard.confirm(withdrawal_id)
+            except KeyError:
+                return _json({"error": "unknown withdrawal"}, status=404)
+            except WithdrawalRejected as exc:
+                return _json({"error": str(exc)}, status=409)
+            return _json(pending.to_dict(), status=200 if pending.error is None else 502)
+
+        async def cancel(request: web.Request) -> web.Response:
+            try:
+                pending = await guard.cancel(request.match_info["withdrawal_id"])
+            except KeyError:
+                return _json({"error": "unknown withdrawal"}, status=404)
+            return _json(pending.to_dict())
+
+        async def listing(request: web.Request) -> web.Response:
+            return _json([p.to_dict() for p in guard.withdrawals()])
+
+        self.add_route("GET", "/withdrawals", listing)
+        self.add_route("POST", "/withdrawals", create)
+        self.add_route("POST", "/withdrawals/{withdrawal_id}/confirm", confirm)
+        self.add_route("POST", "/withdrawals/{withdrawal_id}/cancel", cancel)
+
+    def mount_flatten(self, router: ExecutionRouter, gate: ApprovalGate) -> None:
+        async def flatten(request: web.Request) -> web.Response:
+            summary = f"flatten all positions and cancel all orders on {router.orders.venue}"
+            return _approval(await gate.submit(FLATTEN_ALL, summary, router.flatten_all, requested_by=_operator(request)))
+
+        self.add_route("POST", "/flatten", flatten)
+
+    def mount_risk_limits(self, risk: RiskService, gate: ApprovalGate, *, maxima: Optional[RiskLimits] = None) -> None:
+        """Expose limits for tightening at will; loosening past `maxima` (the configured limits) needs approval."""
+        ceiling = maxima or replace(risk.limits)
+        names = [f.name for f in fields(RiskLimits)]
+
+        def render(limits: RiskLimits) -> Dict[str, Optional[str]]:
+            return {name: None if getattr(limits, name) is None else str(getattr(limits, name)) for name in names}
+
+        async def show(request: web.Request) -> web.Response:
+            return _json({"limits": render(risk.limits), "maxima": render(ceiling)})
+
+        async def update(request: web.Request) -> web.Response:
+            try:
+                body = await request.json()
+                changes = {
+                    name: None if body[name] is None else Decimal(str(body[name])) for name in names if name in body
+                }
+            except (ValueError, TypeError, AttributeError, InvalidOperation) as exc:
+                return _json({"error": f"invalid request: {exc}"}, status=400)
+            if not changes:
+                return _json({"error": f"pass any of {', '.join(names)}"}, status=400)
+            # A cleared limit is unlimited, so it counts as raising one that was configured
+            raised = [
+                name
+                for name, value in changes.items()
+                if getattr(ceiling, name) is not None and (value is None or value > getattr(ceiling, name))
+            ]
+
+            async def apply() -> Dict[str, Optional[str]]:
+                # Merged when it runs, so changes made while an approval was pending are kept
+                limits = replace(risk.limits, **changes)
+                risk.set_limits(limits)
+                self._logger.warning("risk_limits_changed", extra={"limits": render(limits), "raised": raised})
+                return render(limits)
+
+            if not raised:
+                return _json(await apply())
+            summary = "raise risk limits beyond configured maxima: " + ", ".join(
+                f"{name}={changes[name]}" for name in raised
+            )
+            return _approval(await gate.submit(RAISE_RISK_LIMITS, summary, apply, requested_by=_operator(request)))
+
+        self.add_route("GET", "/risk/limits", show)
+        self.add_route("POST", "/risk/limits", update)
+
+    def mount_symbol_lists(
+        self, risk: RiskService, gate: ApprovalGate, *, configured: Optional[SymbolLists] = None
+    ) -> None:
+        """Replace the allow/deny lists; anything more permissive than the configured lists needs approval."""
+        baseline = configured or SymbolLists(allow=list(risk.symbol_lists.allow), deny=list(risk.symbol_lists.deny))
+
+        def render(lists: SymbolLists) -> Dict[str, List[str]]:
+            return {"allow": list(lists.allow), "deny": list(lists.deny)}
+
+        async def show(request: web.Request) -> web.Response:
+            return _json({"lists": render(risk.symbol_lists), "configured": render(baseline)})
+
+        async def update(request: web.Request) -> web.Response:
+            try:
+                body = await request.json()
+                changes = {name: [str(s) for s in body[name] or []] for name in ("allow", "deny") if name in body}
+            except (ValueError, TypeError, AttributeError) as exc:
+                return _json({"error": f"invalid request: {exc}"}, status=400)
+            if not changes:
+                return _json({"error": "pass allow and/or deny"}, status=400)
+            current = risk.symbol_lists
+            lists = SymbolLists(allow=changes.get("allow", current.allow), deny=changes.get("deny", current.deny))
+            # Loosening: a configured deny entry dropped, or the configured allow list widened or cleared
+            loosened = [f"-deny {entry}" for entry in baseline.deny if entry not in lists.deny]
+            if baseline.allow:
+                loosened += [f"+allow {entry}" for entry in lists.allow if entry not in baseline.allow]
+                if not lists.allow:
+                    loosened.append("allow cleared")
+
+            async def apply() -> Dict[str, List[str]]:
+                risk.set_symbol_lists(lists)
+                self._logger.warning("symbol_lists_changed", extra={**render(lists), "loosened": loosened})
+                return render(lists)
+
+            if not loosened:
+                return _json(await apply())
+            summary = "loosen symbol lists beyond the configured ones: " + ", ".join(loosened)
+            return _approval(await gate.submit(RAISE_RISK_LIMITS, summary, apply, requested_by=_operator(request)))
+
+        self.add_route("GET", "/risk/symbols", show)
+        self.add_route("POST", "/risk/symbols", update)
+
+    def mount_funding_table(self, table: FundingTable) -> None:
+        async def listing(request: web.Request) -> web.Response:
+            return _json(table.snapshot(request.query.get("underlying")))
+
+        self.add_route("GET", "/funding", listing)
+
+    def mount_ticker_stats(self, tickers: TickerStats) -> None:
+        async def listing(request: web.Request) -> web.Response:
+            return _json(tickers.snapshot(request.query.get("venue")))
+
+        self.add_route("GET", "/tickers", listing)
+
+    def mount_correlations(self, correlations: CorrelationService) -> None:
+        async def matrix(request: web.Request) -> web.Response:
+            return _json(correlations.snapshot())
+
+        self.add_route("GET", "/correlations", matrix)
+
+    def mount_ws_pool(self, pool: WsConnectionPool) -> None:
+        async def connections(request: web.Request) -> web.Response:
+            return _json(pool.health())
+
+        self.add_route("GET", "/ws/connections", connections)
+
+    def mount_watchlist(self, watchlist: Watchlist) -> None:
+        async def listing(request: web.Request) -> web.Response:
+            return _json(watchlist.symbols())
+
+        async def add(request: web.Request) -> web.Response:
+            try:
+                body = await request.json()
+                symbol = str(body["symbol"])
+                venue_symbol = body.get("venue_symbol")
+            except (KeyError, ValueError, TypeError, AttributeError) as exc:
+                return _json({"error": f"invalid request: {exc}"}, status=400)
+            try:
+                added = await watchlist.add(symbol, str(venue_symbol) if venue_symbol else None)
+            except UnknownSymbolError:
+                return _json({"error": f"unknown symbol {symbol}; pass venue_symbol"}, status=404)
+            except Exception as exc:
+                return _json({"error": str(exc)}, status=502)
+            return _json(watchlist.symbols(), status=201 if added else 200)
+
+        async def remove(request: web.Request) -> web.Response:
+            if not await watchlist.remove(request.match_info["symbol"]):
+                return _json({"error": "symbol not watched"}, status=404)
+            return _json(watchlist.symbols())
+
+        self.add_route("GET", "/watchlist", listing)
+        self.add_route("POST", "/watchlist", add)
+        self.add_route("DELETE", "/watchlist/{symbol}", remove)
+
+    def mount_rejections(self, tracker: RejectionTracker) -> None:
+        async def summary(request: web.Request) -> web.Response:
+            try:
+                limit = int(request.query.get("limit", 50))
+            except ValueError:
+                return _json({"error": "limit must be an integer"}, status=400)
+            return _json(tracker.snapshot(limit=limit, category=request.query.get("category")))
+
+        self.add_route("GET", "/rejections", summary)
+
+    def mount_screener(self, screener: UniverseScreener) -> None:
+        async def results(request: web.Request) -> web.Response:
+            return _json(screener.snapshot())
+
+        self.add_route("GET", "/universe", results)
+
+    async def start(self) -> None:
+        if self._runner is not None:
+            return
+        self._runner = web.AppRunner(self._app)
+        await self._runner.setup()
+        site = web.TCPSite(self._runner, self._config.host, self._config.port)
+        await site.start()
+        self._logger.info(
+            "admin_api_start",
+            extra={"host": self._config.host, "port": self._config.port, "operators": sorted(self._config.tokens())},
+        )
+
+    async def stop(self) -> None:
+        if self._runner is None:
+            return
+        await self._runner.cleanup()
+        self._runner = None
+
+
+__all__ = ["AdminApi", "AdminApiConfig"]
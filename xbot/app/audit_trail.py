@@ -0,0 +1,74 @@
+from __future__ import annotations
+
+import argparse
+import asyncio
+import json
+from datetime import datetime, timezone
+from typing import Any, List, Mapping
+
+from xbot.core.audit import trail
+from xbot.storage.base import AUDIT_LOG, ORDER_EVENTS
+from xbot.storage.factory import build_reader
+from .config import load_storage_config
+from .export_journal import _parse_date
+
+_SHOWN_FIRST = ("kind", "venue", "symbol", "command_id", "trace_id", "client_order_index")
+_HIDDEN = {"ts", "bot_id"}
+
+
+def format_row(row: Mapping[str, Any]) -> str:
+    stamp = datetime.fromtimestamp(float(row.get("ts") or 0.0), tz=timezone.utc).isoformat(timespec="milliseconds")
+    # Order events carry their state instead of an audit kind
+    kind = row.get("kind") or f"order_{row.get('state')}"
+    rest = {k: v for k, v in row.items() if k not in _HIDDEN and k not in _SHOWN_FIRST and v not in (None, "", {})}
+    ids = " ".join(f"{k}={row[k]}" for k in _SHOWN_FIRST[1:] if row.get(k) not in (None, ""))
+    details = " ".join(f"{k}={json.dumps(v, default=str) if isinstance(v, (dict, list)) else v}" for k, v in rest.items())
+    return f"{stamp} {kind:<18} {ids} {details}".rstrip()
+
+
+async def run(args: argparse.Namespace) -> List[Mapping[str, Any]]:
+    storage_cfg = load_storage_config(args.config_path)
+    if args.storage_root:
+        storage_cfg.root = args.storage_root
+    if args.bot_id:
+        storage_cfg.bot_id = args.bot_id
+    storage = build_reader(storage_cfg)
+    await storage.start()
+    try:
+        start, end = _parse_date(args.start), _parse_date(args.end)
+        filters = {"venue": args.venue} if args.venue else None
+        rows = await storage.query(AUDIT_LOG, start=start, end=end, filters=filters)
+        rows += await storage.query(ORDER_EVENTS, start=start, end=end, filters=filters)
+    finally:
+        await storage.stop()
+    return trail(rows, client_order_index=args.order, trace_id=args.trace, command_id=args.command)
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(description="reconstruct why an order was or was not sent from the audit log")
+    target = parser.add_mutually_exclusive_group(required=True)
+    target.add_argument("--order", type=int, help="client_order_index")
+    target.add_argument("--trace", help="trace_id")
+    target.add_argument("--command", help="command_id")
+    parser.add_argument("--config", dest="config_path", help="config file with a storage section")
+    parser.add_argument("--storage-root", help="override the jsonl storage directory")
+    parser.add_argument("--bot-id", help="read this bot's rows instead of the config's storage.bot_id")
+    parser.add_argument("--start", help="search from this date (UTC date or ISO timestamp)")
+    parser.add_argument("--end", help="search before this date (UTC date or ISO timestamp)")
+    parser.add_argument("--venue")
+    parser.add_argument("--json", action="store_true", help="print the rows as JSON lines")
+    return parser.parse_args()
+
+
+def main() -> None:
+    args = parse_args()
+    rows = asyncio.run(run(args))
+    if not rows:
+        print("no audit rows found")
+        return
+    for row in rows:
+        print(json.dumps(dict(row), default=str) if args.json else format_row(row))
+
+
+if __name__ == "__main__":
+    main()
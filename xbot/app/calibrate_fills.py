@@ -0,0 +1,69 @@
+from __future__ import annotations
+
+import argparse
+import asyncio
+import json
+from pathlib import Path
+
+from xbot.analytics.calibration import CalibrationReport, calibrate, load_sim_fills
+from xbot.storage.base import FILLS, ORDER_EVENTS
+from xbot.storage.factory import build_reader
+from .config import load_storage_config
+from .export_journal import _parse_date
+
+
+async def run(args: argparse.Namespace) -> CalibrationReport:
+    storage_cfg = load_storage_config(args.config_path)
+    if args.storage_root:
+        storage_cfg.root = args.storage_root
+    if args.bot_id:
+        storage_cfg.bot_id = args.bot_id
+    storage = build_reader(storage_cfg)
+    await storage.start()
+    try:
+        filters = {}
+        if args.venue:
+            filters["venue"] = args.venue
+        if args.symbol:
+            filters["symbol"] = args.symbol.upper()
+        start, end = _parse_date(args.start), _parse_date(args.end)
+        order_events = await storage.query(ORDER_EVENTS, start=start, end=end, filters=filters or None)
+        # Fills can land after the session window closes for orders placed inside it
+        real_fills = await storage.query(FILLS, start=start, filters=filters or None)
+    finally:
+        await storage.stop()
+    return calibrate(order_events, real_fills, load_sim_fills(Path(args.sim)), key=args.key, strategy=args.strategy)
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(description="compare simulated fills with the recorded real fills of a session")
+    parser.add_argument("--sim", required=True, help="simulated fills, CSV or JSONL with the order key, ts, side, price, size")
+    parser.add_argument("--config", dest="config_path", help="config file with a storage section")
+    parser.add_argument("--storage-root", help="override the jsonl storage directory")
+    parser.add_argument("--bot-id", help="read this bot's rows instead of the config's storage.bot_id")
+    parser.add_argument("--start", help="session start (UTC date or ISO timestamp)")
+    parser.add_argument("--end", help="session end (UTC date or ISO timestamp)")
+    parser.add_argument("--venue")
+    parser.add_argument("--symbol")
+    parser.add_argument("--strategy", help="strategy tag, e.g. the trace_id prefix")
+    parser.add_argument("--key", default="client_order_index", help="column matching simulated and real orders")
+    parser.add_argument("--json", action="store_true", help="print the summary as JSON")
+    return parser.parse_args()
+
+
+def main() -> None:
+    args = parse_args()
+    report = asyncio.run(run(args))
+    if args.json:
+        payload = {
+            "all": report.overall.summary(),
+            "by_symbol": {symbol: stats.summary() for symbol, stats in report.by_symbol.items()},
+            "unmatched_sim": report.unmatched_sim,
+        }
+        print(json.dumps(payload, indent=2, sort_keys=True))
+    else:
+        print(report.format())
+
+
+if __name__ == "__main__":
+    main()
@@ -1,13 +1,59 @@
 from __future__ import annotations
 
 import json
+import os
 from dataclasses import dataclass, field
 from decimal import Decimal
 from pathlib import Path
-from typing import Any, Dict, Optional
+from typing import Any, Dict, List, Optional
 
-from xbot.execution.risk_service import RiskLimits
+from xbot.analytics.balance_monitor import BalanceMonitorConfig
+from xbot.analytics.correlation import CorrelationConfig
+from xbot.analytics.carry import CarryConfig
+from xbot.analytics.funding_table import FundingTableConfig
+from xbot.analytics.kelly import KellyConfig
+from xbot.analytics.ticker_stats import TickerStatsConfig
+from xbot.app.admin_api import AdminApiConfig
+from xbot.connector.base import ConnectorConfig
+from xbot.connector.history import READ, TRADE, WITHDRAW
+from xbot.connector.profiles import MAINNET, EnvironmentProfile, resolve_profile
+from xbot.connector.signer import LOCAL, UNIX, SignerConfig
+from xbot.connector.ws_pool import WsPoolConfig
+from xbot.execution.arbitration import ArbitrationConfig
+from xbot.execution.candles import CandleConfig
+from xbot.execution.dust import DustConfig
+from xbot.execution.fx import FxConfig
+from xbot.execution.inventory_age import InventoryAgeConfig
+from xbot.execution.listings import ListingConfig
+from xbot.execution.maker_first import MakerFirstPolicy
+from xbot.execution.preflight import PreflightConfig
+from xbot.execution.reconciliation import ReconcileConfig
+from xbot.execution.risk_service import RiskLimits, RiskMode, SymbolLists
+from xbot.execution.screener import ScreenerConfig
+from xbot.execution.self_trade import REJECT, REPRICE, SelfTradeConfig
+from xbot.execution.signals import SignalConfig
+from xbot.execution.whales import WhaleConfig
+from xbot.execution.sizing import FIXED, VOLATILITY, SizingConfig
+from xbot.execution.startup_check import AccountExpectations, StartupCheckConfig
+from xbot.execution.trading_windows import TradingWindow, parse_time_of_day, parse_weekday
+from xbot.execution.venue_status import VenueStatusConfig
+from xbot.execution.withdrawals import AllowedAddress, WithdrawalConfig
+from xbot.strategy.cash_carry import CashCarryConfig
+from xbot.strategy.funding_rotation import RotationConfig
+from xbot.core.alerts import AlertConfig, AlertLevel
+from xbot.core.approvals import SECOND_OPERATOR, ApprovalConfig
+from xbot.core.backoff import BackoffConfig, PollingConfig
+from xbot.core.command_bus import BackpressurePolicy, CommandBusConfig
+from xbot.core.events import EventStreamConfig
 from xbot.core.heartbeat import HeartbeatConfig
+from xbot.core.rate_shaper import RateShaperConfig
+from xbot.core.shutdown import ShutdownConfig
+from xbot.storage.base import StorageConfig
+from xbot.strategy.breakout import BreakoutConfig
+from xbot.strategy.event_calendar import EventCalendarConfig, EventWindow, parse_events
+from xbot.strategy.guardrails import GuardrailConfig
+from xbot.strategy.mean_reversion import MeanReversionConfig
+from xbot.strategy.shadow import ShadowConfig
 
 try:
     import yaml  # type: ignore
@@ -15,6 +61,28 @@ except Exception:  # pragma: no cover
     yaml = None
 
 
+@dataclass(slots=True)
+class ReportConfig:
+    hour_utc: int = 0
+    weekly_weekday: int = 0
+    tca: bool = False  # transaction cost analysis in the daily report
+    tca_quote_age_secs: float = 10.0  # oldest market snapshot used as a fill's reference mid
+
+
+@dataclass(slots=True)
+class EquityConfig:
+    interval_secs: float = 60.0
+    drawdown_lookback_secs: float = 86400.0
+
+
+@dataclass(slots=True)
+class FundingConfig:
+    symbols: List[str] = field(default_factory=list)
+    interval_secs: float = 900.0
+    history_limit: int = 200
+    ewma_alpha: Decimal = Decimal("0.3")
+
+
 @dataclass(slots=True)
 class AppConfig:
     venue: str
@@ -26,9 +94,67 @@ class AppConfig:
     interval_secs: float = 10.0
     timeout_secs: float = 120.0
     reduce_only: int = 0
+    max_latency_ms: Optional[float] = None  # strategies wait while p95 WS or REST latency is above this
+    strategy_name: str = ""  # trace_id prefix of the strategy's orders; defaults to mode
+    guardrail_config: Optional[GuardrailConfig] = None
+    event_calendar_config: Optional[EventCalendarConfig] = None
+    mean_reversion_config: MeanReversionConfig = field(default_factory=MeanReversionConfig)
+    breakout_config: BreakoutConfig = field(default_factory=BreakoutConfig)
+    shadow_config: Optional[ShadowConfig] = None
+    candle_config: CandleConfig = field(default_factory=CandleConfig)
+    correlation_config: Optional[CorrelationConfig] = None
+    signal_config: Optional[SignalConfig] = None
+    whale_config: Optional[WhaleConfig] = None
+    sizing_config: Optional[SizingConfig] = None
+    strategy_sizing: str = FIXED
+    kelly_config: Optional[KellyConfig] = None  # scales the strategy's size by its fractional-Kelly multiplier
     symbol_map: Dict[str, str] = field(default_factory=dict)
+    connector_config: ConnectorConfig = field(default_factory=ConnectorConfig)
+    ws_pool_config: WsPoolConfig = field(default_factory=WsPoolConfig)
+    environment: str = MAINNET
+    read_only: bool = False  # market data only: no keys needed, nothing signed, no strategy
+    profile: Optional[EnvironmentProfile] = None
     risk_limits: RiskLimits = field(default_factory=RiskLimits)
+    symbol_lists: SymbolLists = field(default_factory=SymbolLists)
+    trading_windows: List[TradingWindow] = field(default_factory=list)
+    self_trade_config: Optional[SelfTradeConfig] = None
+    arbitration_config: Optional[ArbitrationConfig] = None
+    rate_shaper_config: Optional[RateShaperConfig] = None
+    stale_after_secs: Optional[float] = 30.0
+    funding_ttl_secs: float = 60.0
+    queue_tracking: bool = False
+    time_sync_interval_secs: float = 300.0
     heartbeat_config: Optional[HeartbeatConfig] = None
+    storage_config: Optional[StorageConfig] = None
+    alert_config: Optional[AlertConfig] = None
+    report_config: Optional[ReportConfig] = None
+    equity_config: Optional[EquityConfig] = None
+    shutdown_config: ShutdownConfig = field(default_factory=ShutdownConfig)
+    command_bus_config: CommandBusConfig = field(default_factory=CommandBusConfig)
+    withdrawal_config: WithdrawalConfig = field(default_factory=WithdrawalConfig)
+    admin_config: Optional[AdminApiConfig] = None
+    approval_config: ApprovalConfig = field(default_factory=ApprovalConfig)
+    dust_config: Optional[DustConfig] = None
+    inventory_age_config: Optional[InventoryAgeConfig] = None
+    funding_config: Optional[FundingConfig] = None
+    funding_table_config: Optional[FundingTableConfig] = None
+    ticker_stats_config: Optional[TickerStatsConfig] = None
+    carry_config: Optional[CarryConfig] = None
+    rotation_config: Optional[RotationConfig] = None
+    cash_carry_config: Optional[CashCarryConfig] = None
+    reconcile_config: Optional[ReconcileConfig] = None
+    balance_monitor_config: Optional[BalanceMonitorConfig] = None
+    listing_config: Optional[ListingConfig] = None
+    screener_config: Optional[ScreenerConfig] = None
+    venue_status_config: Optional[VenueStatusConfig] = None
+    event_stream_config: EventStreamConfig = field(default_factory=EventStreamConfig)
+    polling_config: PollingConfig = field(default_factory=PollingConfig)
+    preflight_config: Optional[PreflightConfig] = field(default_factory=PreflightConfig)  # None skips the key check
+    startup_check_config: Optional[StartupCheckConfig] = field(default_factory=StartupCheckConfig)  # None skips it
+    market_state_interval_secs: Optional[float] = 60.0  # order-book state refresh; None disables
+    fx_config: Optional[FxConfig] = None
+    audit_enabled: bool = True  # audit_log rows whenever storage is configured
+    instrument_overrides: Dict[str, Dict[str, Any]] = field(default_factory=dict)  # venue symbol -> Instrument fields
 
 
 def _read_yaml(path: Path) -> Dict[str, Any]:
@@ -43,6 +169,44 @@ def _read_json(path: Path) -> Dict[str, Any]:
         return json.load(handle)
 
 
+def _read_payload(config_path: str) -> Dict[str, Any]:
+    path = Path(config_path)
+    if not path.exists():
+        raise FileNotFoundError(config_path)
+    if path.suffix in {".yaml", ".yml"}:
+        return _read_yaml(path)
+    if path.suffix == ".json":
+        return _read_json(path)
+    raise ValueError(f"unsupported config extension: {path.suffix}")
+
+
+def _parse_storage(payload: Dict[str, Any]) -> Optional[StorageConfig]:
+    storage_cfg = payload.get("storage") or {}
+    if not storage_cfg:
+        return None
+    return StorageConfig(
+        backend=str(storage_cfg.get("backend", "jsonl")),
+        root=str(storage_cfg.get("root", "logs/storage")),
+        dsn=storage_cfg.get("dsn"),
+        bot_id=str(storage_cfg.get("bot_id", "default")),
+        timescale=bool(storage_cfg.get("timescale", True)),
+        batch_size=int(storage_cfg.get("batch_size", 500)),
+        flush_interval_secs=float(storage_cfg.get("flush_interval_secs", 1.0)),
+        max_buffered_rows=int(storage_cfg.get("max_buffered_rows", 100_000)),
+        snapshot_interval_secs=float(storage_cfg.get("snapshot_interval_secs", 0.0)),
+        fill_snapshot_depth=int(storage_cfg.get("fill_snapshot_depth", 0)),
+        fill_snapshot_trades=int(storage_cfg.get("fill_snapshot_trades", 20)),
+        funding_payments_interval_secs=float(storage_cfg.get("funding_payments_interval_secs", 300.0)),
+    )
+
+
+def load_storage_config(config_path: Optional[str]) -> StorageConfig:
+    """Storage section of a config file, defaulting to the JSONL backend."""
+    if not config_path:
+        return StorageConfig()
+    return _parse_storage(_read_payload(config_path)) or StorageConfig()
+
+
 def load_config(
     *,
     venue: str,
@@ -55,18 +219,12 @@ def load_config(
     timeout_secs: float = 120.0,
     reduce_only: int = 0,
     config_path: Optional[str] = None,
+    environment: Optional[str] = None,
+    read_only: bool = False,
 ) -> AppConfig:
     payload: Dict[str, Any] = {}
     if config_path:
-        path = Path(config_path)
-        if not path.exists():
-            raise FileNotFoundError(config_path)
-        if path.suffix in {".yaml", ".yml"}:
-            payload = _read_yaml(path)
-        elif path.suffix == ".json":
-            payload = _read_json(path)
-        else:
-            raise ValueError(f"unsupported config extension: {path.suffix}")
+        payload = _read_payload(config_path)
     cfg = AppConfig(
         venue=(payload.get("venue") or venue).lower(),
         symbol=payload.get("symbol") or symbol,
@@ -80,22 +238,653 @@ def load_config(
         symbol_map={k.upper(): v for k, v in (payload.get("symbol_map") or {}).items()},
     )
     cfg.symbol_map.setdefault(cfg.symbol.upper(), cfg.symbol)
+    if payload.get("max_latency_ms") is not None:
+        cfg.max_latency_ms = float(payload["max_latency_ms"])
+    cfg.strategy_name = str(payload.get("strategy_name") or "")
+    candles_cfg = payload.get("candles") or {}
+    cfg.candle_config = CandleConfig(
+        interval_secs=float(candles_cfg.get("interval_secs", 60.0)),
+        history=int(candles_cfg.get("history", 500)),
+    )
+    correlation_cfg = payload.get("correlation") or {}
+    if correlation_cfg.get("enabled", bool(correlation_cfg)):
+        cfg.correlation_config = CorrelationConfig(
+            interval_secs=float(correlation_cfg.get("interval_secs", 300.0)),
+            window=int(correlation_cfg.get("window", 100)),
+            min_periods=int(correlation_cfg.get("min_periods", 30)),
+            threshold=float(correlation_cfg.get("threshold", 0.7)),
+        )
+    signals_cfg = payload.get("signals") or {}
+    if signals_cfg.get("enabled", bool(signals_cfg)):
+        cfg.signal_config = SignalConfig(
+            window_secs=float(signals_cfg.get("window_secs", 30.0)),
+            vpin_buckets=int(signals_cfg.get("vpin_buckets", 50)),
+            bucket_volume={str(k): float(v) for k, v in (signals_cfg.get("bucket_volume") or {}).items()},
+            trades_per_bucket=int(signals_cfg.get("trades_per_bucket", 20)),
+            toxic_vpin=float(signals_cfg.get("toxic_vpin", 0.6)),
+            publish_secs=float(signals_cfg.get("publish_secs", 1.0)),
+        )
+    whales_cfg = payload.get("whales") or {}
+    if whales_cfg.get("enabled", bool(whales_cfg)):
+        defaults = WhaleConfig()
+        cfg.whale_config = WhaleConfig(
+            size_multiple=float(whales_cfg.get("size_multiple", defaults.size_multiple)),
+            min_size={str(k): float(v) for k, v in (whales_cfg.get("min_size") or {}).items()},
+            baseline_trades=int(whales_cfg.get("baseline_trades", defaults.baseline_trades)),
+            warmup_trades=int(whales_cfg.get("warmup_trades", defaults.warmup_trades)),
+            sweep_window_secs=float(whales_cfg.get("sweep_window_secs", defaults.sweep_window_secs)),
+            sweep_trades=int(whales_cfg.get("sweep_trades", defaults.sweep_trades)),
+            sweep_multiple=float(whales_cfg.get("sweep_multiple", defaults.sweep_multiple)),
+            cooldown_secs=float(whales_cfg.get("cooldown_secs", defaults.cooldown_secs)),
+            alert=bool(whales_cfg.get("alert", False)),
+        )
+    sizing_cfg = payload.get("sizing") or {}
+    if sizing_cfg.get("enabled", bool(sizing_cfg)):
+        max_size = sizing_cfg.get("max_size")
+        cfg.sizing_config = SizingConfig(
+            risk_fraction=Decimal(str(sizing_cfg.get("risk_fraction", "0.01"))),
+            atr_period=int(sizing_cfg.get("atr_period", 14)),
+            atr_multiple=Decimal(str(sizing_cfg.get("atr_multiple", "2"))),
+            max_size=Decimal(str(max_size)) if max_size is not None else None,
+        )
+        # Commands opt in per payload; the configured strategy opts in here
+        if sizing_cfg.get("strategy", False):
+            cfg.strategy_sizing = VOLATILITY
+    kelly_cfg = payload.get("kelly") or {}
+    if kelly_cfg.get("enabled", bool(kelly_cfg)):
+        # A volatility-sized base risks the sizer's fraction, which is then the natural baseline
+        baseline = cfg.sizing_config.risk_fraction if cfg.strategy_sizing == VOLATILITY and cfg.sizing_config else "0.01"
+        cfg.kelly_config = KellyConfig(
+            fraction=Decimal(str(kelly_cfg.get("fraction", "0.5"))),
+            window=int(kelly_cfg.get("window", 50)),
+            min_trades=int(kelly_cfg.get("min_trades", 20)),
+            baseline=Decimal(str(kelly_cfg.get("baseline", baseline))),
+            max_multiplier=Decimal(str(kelly_cfg.get("max_multiplier", "2"))),
+            refresh_secs=float(kelly_cfg.get("refresh_secs", 300.0)),
+        )
+    guardrails_cfg = payload.get("guardrails") or {}
+    if guardrails_cfg.get("enabled", bool(guardrails_cfg)):
+        max_daily_loss = guardrails_cfg.get("max_daily_loss")
+        max_trades = guardrails_cfg.get("max_trades_per_day")
+        cfg.guardrail_config = GuardrailConfig(
+            max_daily_loss=Decimal(str(max_daily_loss)) if max_daily_loss is not None else None,
+            max_trades_per_day=int(max_trades) if max_trades is not None else None,
+            reset_hour_utc=int(guardrails_cfg.get("reset_hour_utc", 0)),
+            loss_streak=int(guardrails_cfg["loss_streak"]) if guardrails_cfg.get("loss_streak") else None,
+            cooldown_secs=float(guardrails_cfg.get("cooldown_secs", 3600.0)),
+        )
+    if payload.get("mean_reversion"):
+        cfg.mean_reversion_config = _mean_reversion_config(payload["mean_reversion"])
+    if payload.get("breakout"):
+        cfg.breakout_config = _breakout_config(payload["breakout"])
+    shadow_cfg = payload.get("shadow") or {}
+    if shadow_cfg.get("enabled", bool(shadow_cfg)):
+        shadow_mode = str(shadow_cfg.get("mode") or cfg.mode).lower()
+        shadow_params: Any = None
+        if shadow_mode == "mean_reversion":
+            shadow_params = _mean_reversion_config(shadow_cfg.get("mean_reversion") or {})
+        elif shadow_mode == "breakout":
+            shadow_params = _breakout_config(shadow_cfg.get("breakout") or {})
+        defaults = ShadowConfig(mode=shadow_mode, name="")
+        cfg.shadow_config = ShadowConfig(
+            mode=shadow_mode,
+            name=str(shadow_cfg.get("strategy_name") or f"{cfg.strategy_name or cfg.mode}_shadow"),
+            qty=float(shadow_cfg["qty"]) if shadow_cfg.get("qty") else None,
+            interval_secs=float(shadow_cfg["interval_secs"]) if shadow_cfg.get("interval_secs") else None,
+            collateral=Decimal(str(shadow_cfg.get("collateral", defaults.collateral))),
+            mirror_secs=float(shadow_cfg.get("mirror_secs", defaults.mirror_secs)),
+            log_root=str(shadow_cfg.get("log_root", defaults.log_root)),
+            params=shadow_params,
+        )
+        if cfg.shadow_config.name == (cfg.strategy_name or cfg.mode):
+            raise ValueError("shadow.strategy_name must differ from the live strategy's name")
+    calendar_cfg = payload.get("event_calendar") or {}
+    if calendar_cfg.get("enabled", bool(calendar_cfg)):
+        defaults = EventCalendarConfig()
+        window_cfg = calendar_cfg.get("window") or {}
+        default_window = EventWindow(
+            before_secs=float(window_cfg.get("before_secs", defaults.default_window.before_secs)),
+            after_secs=float(window_cfg.get("after_secs", defaults.default_window.after_secs)),
+        )
+        cfg.event_calendar_config = EventCalendarConfig(
+            path=calendar_cfg.get("path"),
+            url=calendar_cfg.get("url"),
+            refresh_secs=float(calendar_cfg.get("refresh_secs", defaults.refresh_secs)),
+            interval_secs=float(calendar_cfg.get("interval_secs", defaults.interval_secs)),
+            default_window=default_window,
+            windows={
+                str(kind).lower(): EventWindow(
+                    before_secs=float(raw.get("before_secs", default_window.before_secs)),
+                    after_secs=float(raw.get("after_secs", default_window.after_secs)),
+                )
+                for kind, raw in (calendar_cfg.get("windows") or {}).items()
+            },
+            strategies=[str(name) for name in calendar_cfg.get("strategies") or []],
+            account_wide=bool(calendar_cfg.get("account_wide", False)),
+            events=parse_events(calendar_cfg.get("events") or []),
+        )
+        if not (cfg.event_calendar_config.path or cfg.event_calendar_config.url or cfg.event_calendar_config.events):
+            raise ValueError("event_calendar needs a path, a url or inline events")
     risk_cfg = payload.get("risk") or {}
     max_position = risk_cfg.get("max_position")
     max_notional = risk_cfg.get("max_notional")
+    max_drawdown_pct = risk_cfg.get("max_drawdown_pct")
+    max_impact_bps = risk_cfg.get("max_impact_bps")
+    max_correlated_notional = risk_cfg.get("max_correlated_notional")
     cfg.risk_limits = RiskLimits(
         max_position=None if max_position is None else Decimal(str(max_position)),
         max_notional=None if max_notional is None else Decimal(str(max_notional)),
+        max_drawdown_pct=None if max_drawdown_pct is None else Decimal(str(max_drawdown_pct)),
+        max_impact_bps=None if max_impact_bps is None else Decimal(str(max_impact_bps)),
+        max_correlated_notional=None if max_correlated_notional is None else Decimal(str(max_correlated_notional)),
+    )
+    cfg.symbol_lists = SymbolLists(
+        allow=[str(s) for s in risk_cfg.get("allow_symbols") or []],
+        deny=[str(s) for s in risk_cfg.get("deny_symbols") or []],
+    )
+    for index, window_cfg in enumerate(risk_cfg.get("no_trade_windows") or []):
+        every_hours = window_cfg.get("every_hours")
+        start, end = window_cfg.get("start"), window_cfg.get("end")
+        cfg.trading_windows.append(
+            TradingWindow(
+                name=str(window_cfg.get("name") or f"window_{index}"),
+                symbols=[str(s) for s in window_cfg.get("symbols") or []],
+                start=parse_time_of_day(start) if start is not None else None,
+                end=parse_time_of_day(end) if end is not None else None,
+                weekdays=[parse_weekday(day) for day in window_cfg.get("weekdays") or []],
+                every_hours=float(every_hours) if every_hours is not None else None,
+                before_secs=float(window_cfg.get("before_secs", 0.0)),
+                after_secs=float(window_cfg.get("after_secs", 0.0)),
+            )
+        )
+    self_trade_cfg = payload.get("self_trade") or {}
+    if self_trade_cfg.get("enabled", bool(self_trade_cfg)):
+        action = str(self_trade_cfg.get("action", REPRICE)).lower()
+        if action not in (REPRICE, REJECT):
+            raise ValueError(f"self_trade.action must be {REPRICE} or {REJECT}, got {action!r}")
+        cfg.self_trade_config = SelfTradeConfig(action=action)
+    arbitration_cfg = payload.get("arbitration") or {}
+    if arbitration_cfg.get("enabled", bool(arbitration_cfg)):
+        cfg.arbitration_config = ArbitrationConfig(
+            max_combined={
+                str(symbol).upper(): Decimal(str(cap)) for symbol, cap in (arbitration_cfg.get("max_combined") or {}).items()
+            },
+            priority=[str(name) for name in arbitration_cfg.get("priority") or []],
+        )
+    shaping_cfg = payload.get("rate_shaping") or {}
+    if shaping_cfg.get("enabled", bool(shaping_cfg)):
+        new_timeout = shaping_cfg.get("new_timeout_secs")
+        cfg.rate_shaper_config = RateShaperConfig(
+            global_per_sec=float(shaping_cfg["global_per_sec"]) if shaping_cfg.get("global_per_sec") else None,
+            venues={str(venue).lower(): float(rate) for venue, rate in (shaping_cfg.get("venues") or {}).items()},
+            burst_secs=float(shaping_cfg.get("burst_secs", 1.0)),
+            new_timeout_secs=float(new_timeout) if new_timeout is not None else None,
+        )
+    connector_cfg = payload.get("connector") or {}
+    cfg.connector_config = ConnectorConfig(
+        window_ms=int(connector_cfg.get("window_ms", 5000)),
+        request_timeout_secs=float(connector_cfg.get("request_timeout_secs", 10.0)),
+        market_data_timeout_secs=float(connector_cfg.get("market_data_timeout_secs", 3.0)),
+        proxy=connector_cfg.get("proxy"),
+        rest_url=connector_cfg.get("rest_url"),
+        ws_url=connector_cfg.get("ws_url"),
+        signer=_signer_config(connector_cfg.get("signer")),
+        order_retries=int(connector_cfg.get("order_retries", 1)),
+        order_lookup_delay_secs=float(connector_cfg.get("order_lookup_delay_secs", 1.0)),
+    )
+    pool_cfg = payload.get("ws_pool") or {}
+    cfg.ws_pool_config = WsPoolConfig(
+        max_streams_per_connection={
+            str(k).lower(): int(v) for k, v in (pool_cfg.get("max_streams_per_connection") or {}).items()
+        },
+        max_connections=int(pool_cfg.get("max_connections", 5)),
+        rebalance=bool(pool_cfg.get("rebalance", True)),
     )
+    # Environment profile: endpoints and symbols switch together; explicit connector.* URLs still win
+    cfg.environment = str(environment or payload.get("environment") or MAINNET).lower()
+    cfg.profile = resolve_profile(cfg.venue, cfg.environment, (payload.get("environments") or {}).get(cfg.environment))
+    cfg.connector_config.rest_url = cfg.connector_config.rest_url or cfg.profile.rest_url
+    cfg.connector_config.ws_url = cfg.connector_config.ws_url or cfg.profile.ws_url
+    if cfg.profile.symbol_map:
+        cfg.symbol_map.update(cfg.profile.symbol_map)
+    market_data_cfg = payload.get("market_data") or {}
+    if "stale_after_secs" in market_data_cfg:
+        stale_after = market_data_cfg["stale_after_secs"]
+        cfg.stale_after_secs = None if stale_after is None else float(stale_after)
+    if "funding_ttl_secs" in market_data_cfg:
+        cfg.funding_ttl_secs = float(market_data_cfg["funding_ttl_secs"])
+    cfg.queue_tracking = bool(market_data_cfg.get("queue_tracking", False))
+    if "market_state_interval_secs" in market_data_cfg:
+        interval = market_data_cfg["market_state_interval_secs"]
+        cfg.market_state_interval_secs = None if interval is None else float(interval)
+    cfg.instrument_overrides = {
+        str(native): _parse_instrument_override(raw or {}) for native, raw in (payload.get("instruments") or {}).items()
+    }
+    time_sync_cfg = payload.get("time_sync") or {}
+    cfg.time_sync_interval_secs = float(time_sync_cfg.get("interval_secs", 300.0))
     heartbeat_cfg = payload.get("heartbeat") or {}
     if heartbeat_cfg.get("url"):
         cfg.heartbeat_config = HeartbeatConfig(
             url=heartbeat_cfg["url"],
             interval_secs=float(heartbeat_cfg.get("interval_secs", heartbeat_cfg.get("interval", 30.0))),
             timeout_secs=float(heartbeat_cfg.get("timeout_secs", 5.0)),
+            poll_timeout_secs=float(heartbeat_cfg.get("poll_timeout_secs", 10.0)),
             bearer_token=heartbeat_cfg.get("token") or heartbeat_cfg.get("bearer_token"),
         )
+    cfg.storage_config = _parse_storage(payload)
+    alerts_cfg = payload.get("alerts") or {}
+    if alerts_cfg:
+        cfg.alert_config = AlertConfig(
+            webhook_url=alerts_cfg.get("webhook_url") or alerts_cfg.get("url"),
+            bearer_token=alerts_cfg.get("token") or alerts_cfg.get("bearer_token"),
+            timeout_secs=float(alerts_cfg.get("timeout_secs", 5.0)),
+            min_level=AlertLevel(str(alerts_cfg.get("min_level", "info")).lower()),
+        )
+    reports_cfg = payload.get("reports") or {}
+    if reports_cfg.get("enabled", bool(reports_cfg)):
+        cfg.report_config = ReportConfig(
+            hour_utc=int(reports_cfg.get("hour_utc", 0)),
+            weekly_weekday=int(reports_cfg.get("weekly_weekday", 0)),
+            tca=bool(reports_cfg.get("tca", False)),
+            tca_quote_age_secs=float(reports_cfg.get("tca_quote_age_secs", 10.0)),
+        )
+    equity_cfg = payload.get("equity") or {}
+    if equity_cfg or cfg.risk_limits.max_drawdown_pct is not None:
+        cfg.equity_config = EquityConfig(
+            interval_secs=float(equity_cfg.get("interval_secs", 60.0)),
+            drawdown_lookback_secs=float(equity_cfg.get("drawdown_lookback_secs", 86400.0)),
+        )
+    cfg.audit_enabled = bool((payload.get("audit") or {}).get("enabled", True))
+    fx_cfg = payload.get("fx") or {}
+    if fx_cfg.get("enabled", bool(fx_cfg)):
+        defaults = FxConfig()
+        pegs = fx_cfg.get("pegs")
+        cfg.fx_config = FxConfig(
+            reporting_currency=str(fx_cfg.get("reporting_currency", defaults.reporting_currency)).upper(),
+            pegs=defaults.pegs if pegs is None else {str(k).upper(): str(v).upper() for k, v in pegs.items()},
+            symbols=[str(s).upper() for s in fx_cfg.get("symbols") or []],
+            venue_currencies={str(k).lower(): str(v).upper() for k, v in (fx_cfg.get("venue_currencies") or {}).items()},
+            interval_secs=float(fx_cfg.get("interval_secs", defaults.interval_secs)),
+            max_age_secs=float(fx_cfg.get("max_age_secs", defaults.max_age_secs)),
+        )
+    shutdown_cfg = payload.get("shutdown") or {}
+    cfg.shutdown_config = ShutdownConfig(
+        cancel_open_orders=bool(shutdown_cfg.get("cancel_open_orders", True)),
+        step_timeout_secs=float(shutdown_cfg.get("step_timeout_secs", 10.0)),
+    )
+    commands_cfg = payload.get("commands") or {}
+    cfg.command_bus_config = CommandBusConfig(
+        capacity=int(commands_cfg.get("capacity", 256)),
+        policy=BackpressurePolicy(str(commands_cfg.get("policy", "block")).lower()),
+        dedupe_ttl_secs=float(commands_cfg.get("dedupe_ttl_secs", 600.0)),
+    )
+    withdrawals_cfg = payload.get("withdrawals") or {}
+    cfg.withdrawal_config = WithdrawalConfig(
+        enabled=bool(withdrawals_cfg.get("enabled", False)),
+        allowlist=[
+            AllowedAddress(
+                symbol=str(entry["symbol"]).upper(),
+                blockchain=str(entry["blockchain"]),
+                address=str(entry["address"]),
+                label=str(entry.get("label", "")),
+            )
+            for entry in withdrawals_cfg.get("allowlist") or []
+        ],
+        max_quantity={k.upper(): Decimal(str(v)) for k, v in (withdrawals_cfg.get("max_quantity") or {}).items()},
+        confirm_ttl_secs=float(withdrawals_cfg.get("confirm_ttl_secs", 300.0)),
+    )
+    admin_cfg = payload.get("admin") or {}
+    if admin_cfg.get("enabled", bool(admin_cfg)):
+        cfg.admin_config = AdminApiConfig(
+            host=str(admin_cfg.get("host", "127.0.0.1")),
+            port=int(admin_cfg.get("port", 8089)),
+            token=admin_cfg.get("token") or os.getenv(str(admin_cfg.get("token_env", "XBOT_ADMIN_TOKEN"))),
+            operators={
+                str(name): token
+                for name, entry in (admin_cfg.get("operators") or {}).items()
+                if (token := _operator_token(entry))
+            },
+        )
+    approvals_cfg = payload.get("approvals") or {}
+    defaults = ApprovalConfig()
+    cfg.approval_config = ApprovalConfig(
+        enabled=bool(approvals_cfg.get("enabled", True)),  # on unless turned off: the routes they gate move funds
+        mode=str(approvals_cfg.get("mode", defaults.mode)),
+        actions=[str(action) for action in approvals_cfg.get("actions", defaults.actions)],
+        ttl_secs=float(approvals_cfg.get("ttl_secs", defaults.ttl_secs)),
+    )
+    if cfg.admin_config and cfg.approval_config.enabled and cfg.approval_config.mode == SECOND_OPERATOR:
+        if len(cfg.admin_config.tokens()) < 2:
+            raise ValueError(
+                "approvals in second_operator mode need at least two admin operators with tokens;"
+                " set approvals.mode: confirmation_code, or approvals.enabled: false to run gated actions at once"
+            )
+    dust_cfg = payload.get("dust") or {}
+    if dust_cfg.get("enabled", bool(dust_cfg)):
+        cfg.dust_config = DustConfig(
+            quote=str(dust_cfg.get("quote", "USDC")).upper(),
+            max_notional=Decimal(str(dust_cfg.get("max_notional", "5"))),
+            interval_secs=float(dust_cfg.get("interval_secs", 3600.0)),
+            dry_run=bool(dust_cfg.get("dry_run", True)),
+            exclude=[str(asset).upper() for asset in dust_cfg.get("exclude") or []],
+        )
+    aging_cfg = payload.get("inventory_age") or {}
+    if aging_cfg.get("enabled", bool(aging_cfg)):
+        cfg.inventory_age_config = InventoryAgeConfig(
+            max_hold_secs={str(k): float(v) for k, v in (aging_cfg.get("max_hold_secs") or {}).items()},
+            interval_secs=float(aging_cfg.get("interval_secs", 30.0)),
+            tactic=str(aging_cfg.get("tactic", "maker_first")).lower(),
+            policy=MakerFirstPolicy.from_mapping(aging_cfg.get("policy") or {}),
+            retry_secs=float(aging_cfg.get("retry_secs", 60.0)),
+            dry_run=bool(aging_cfg.get("dry_run", False)),
+        )
+        if cfg.inventory_age_config.tactic not in ("maker_first", "market"):
+            raise ValueError("inventory_age.tactic must be maker_first or market")
+    funding_cfg = payload.get("funding") or {}
+    if funding_cfg.get("enabled", bool(funding_cfg)):
+        cfg.funding_config = FundingConfig(
+            symbols=[str(s).upper() for s in funding_cfg.get("symbols") or [cfg.symbol]],
+            interval_secs=float(funding_cfg.get("interval_secs", 900.0)),
+            history_limit=int(funding_cfg.get("history_limit", 200)),
+            ewma_alpha=Decimal(str(funding_cfg.get("ewma_alpha", "0.3"))),
+        )
+    table_cfg = payload.get("funding_table") or {}
+    if table_cfg.get("enabled", bool(table_cfg)):
+        cfg.funding_table_config = FundingTableConfig(
+            interval_secs=float(table_cfg.get("interval_secs", 60.0)),
+            underlyings={str(k).upper(): str(v).upper() for k, v in (table_cfg.get("underlyings") or {}).items()},
+            interval_hours={str(k).lower(): float(v) for k, v in (table_cfg.get("interval_hours") or {}).items()},
+            venues=[str(v).lower() for v in table_cfg.get("venues") or []],
+        )
+    tickers_cfg = payload.get("tickers") or {}
+    if tickers_cfg.get("enabled", bool(tickers_cfg)):
+        defaults = TickerStatsConfig()
+        cfg.ticker_stats_config = TickerStatsConfig(
+            interval_secs=float(tickers_cfg.get("interval_secs", defaults.interval_secs)),
+            max_age_secs=float(tickers_cfg.get("max_age_secs", defaults.max_age_secs)),
+        )
+    carry_cfg = payload.get("carry") or {}
+    if carry_cfg.get("enabled", bool(carry_cfg)):
+        defaults = CarryConfig()
+        cfg.carry_config = CarryConfig(
+            interval_secs=float(carry_cfg.get("interval_secs", defaults.interval_secs)),
+            leverage=Decimal(str(carry_cfg.get("leverage", defaults.leverage))),
+            reserve_pct=Decimal(str(carry_cfg.get("reserve_pct", defaults.reserve_pct))),
+            max_option_pct=Decimal(str(carry_cfg.get("max_option_pct", defaults.max_option_pct))),
+            min_apr=Decimal(str(carry_cfg.get("min_apr", defaults.min_apr))),
+            pair_haircut_apr=Decimal(str(carry_cfg.get("pair_haircut_apr", defaults.pair_haircut_apr))),
+            lend_haircut_apr=Decimal(str(carry_cfg.get("lend_haircut_apr", defaults.lend_haircut_apr))),
+            lend_assets=[str(asset).upper() for asset in carry_cfg.get("lend_assets") or defaults.lend_assets],
+            venues=[str(v).lower() for v in carry_cfg.get("venues") or []],
+            apply=bool(carry_cfg.get("apply", False)),
+        )
+        if cfg.carry_config.leverage <= 0:
+            raise ValueError("carry.leverage must be positive")
+    rotation_cfg = payload.get("funding_rotation") or {}
+    if rotation_cfg.get("enabled", bool(rotation_cfg)):
+        defaults = RotationConfig()
+        cfg.rotation_config = RotationConfig(
+            notional=Decimal(str(rotation_cfg.get("notional", defaults.notional))),
+            underlyings=[str(u).upper() for u in rotation_cfg.get("underlyings") or []],
+            venues=[str(v).lower() for v in rotation_cfg.get("venues") or []],
+            interval_secs=float(rotation_cfg.get("interval_secs", defaults.interval_secs)),
+            taker_fee_bps={
+                str(k).lower(): Decimal(str(v)) for k, v in (rotation_cfg.get("taker_fee_bps") or {}).items()
+            },
+            default_fee_bps=Decimal(str(rotation_cfg.get("default_fee_bps", defaults.default_fee_bps))),
+            slippage_bps=Decimal(str(rotation_cfg.get("slippage_bps", defaults.slippage_bps))),
+            horizon_hours=float(rotation_cfg.get("horizon_hours", defaults.horizon_hours)),
+            min_spread_apr=Decimal(str(rotation_cfg.get("min_spread_apr", defaults.min_spread_apr))),
+            hysteresis_apr=Decimal(str(rotation_cfg.get("hysteresis_apr", defaults.hysteresis_apr))),
+            exit_spread_apr=Decimal(str(rotation_cfg.get("exit_spread_apr", defaults.exit_spread_apr))),
+            min_hold_secs=float(rotation_cfg.get("min_hold_secs", defaults.min_hold_secs)),
+            flip=bool(rotation_cfg.get("flip", defaults.flip)),
+            min_volume_24h=Decimal(str(rotation_cfg.get("min_volume_24h", defaults.min_volume_24h))),
+            entry_window_secs=float(rotation_cfg.get("entry_window_secs", defaults.entry_window_secs)),
+            apply=bool(rotation_cfg.get("apply", False)),
+        )
+        if cfg.rotation_config.horizon_hours <= 0:
+            raise ValueError("funding_rotation.horizon_hours must be positive")
+        if cfg.funding_table_config is None:
+            raise ValueError("funding_rotation needs the funding_table section")
+        if cfg.rotation_config.min_volume_24h and cfg.ticker_stats_config is None:
+            raise ValueError("funding_rotation.min_volume_24h needs the tickers section")
+    cash_carry_cfg = payload.get("cash_carry") or {}
+    if cash_carry_cfg.get("enabled", bool(cash_carry_cfg)):
+        defaults = CashCarryConfig()
+        raw_symbols = cash_carry_cfg.get("symbols") or {}
+        if isinstance(raw_symbols, list):
+            raw_symbols = {symbol: None for symbol in raw_symbols}
+        target_bps, stop_bps = cash_carry_cfg.get("target_bps"), cash_carry_cfg.get("stop_bps")
+        cfg.cash_carry_config = CashCarryConfig(
+            symbols={str(k): (str(v) if v else None) for k, v in raw_symbols.items()},
+            notional=Decimal(str(cash_carry_cfg.get("notional", defaults.notional))),
+            entry_apr=Decimal(str(cash_carry_cfg.get("entry_apr", defaults.entry_apr))),
+            exit_apr=Decimal(str(cash_carry_cfg.get("exit_apr", defaults.exit_apr))),
+            converge_bps=Decimal(str(cash_carry_cfg.get("converge_bps", defaults.converge_bps))),
+            basis_horizon_hours=float(cash_carry_cfg.get("basis_horizon_hours", defaults.basis_horizon_hours)),
+            unwind_before_hours=float(cash_carry_cfg.get("unwind_before_hours", defaults.unwind_before_hours)),
+            entry_window_secs=float(cash_carry_cfg.get("entry_window_secs", defaults.entry_window_secs)),
+            expiries={str(k): str(v) for k, v in (cash_carry_cfg.get("expiries") or {}).items()},
+            interval_secs=float(cash_carry_cfg.get("interval_secs", defaults.interval_secs)),
+            order_timeout_secs=float(cash_carry_cfg.get("order_timeout_secs", defaults.order_timeout_secs)),
+            target_bps=Decimal(str(target_bps)) if target_bps is not None else None,
+            stop_bps=Decimal(str(stop_bps)) if stop_bps is not None else None,
+            close_on_basis=bool(cash_carry_cfg.get("close_on_basis", False)),
+            apply=bool(cash_carry_cfg.get("apply", False)),
+        )
+        if not cfg.cash_carry_config.symbols:
+            raise ValueError("cash_carry.symbols must list at least one perp or future")
+        if cfg.cash_carry_config.exit_apr >= cfg.cash_carry_config.entry_apr:
+            raise ValueError("cash_carry.exit_apr must be below entry_apr")
+    reconcile_cfg = payload.get("reconcile") or {}
+    if reconcile_cfg.get("enabled", bool(reconcile_cfg)):
+        cfg.reconcile_config = ReconcileConfig(
+            interval_secs=float(reconcile_cfg.get("interval_secs", 30.0)),
+            tolerance=Decimal(str(reconcile_cfg.get("tolerance", "0"))),
+            confirm_cycles=int(reconcile_cfg.get("confirm_cycles", 2)),
+            halt=bool(reconcile_cfg.get("halt", False)),
+            symbols=[str(s).upper() for s in reconcile_cfg.get("symbols") or []],
+        )
+    balances_cfg = payload.get("balance_monitor") or {}
+    if balances_cfg.get("enabled", bool(balances_cfg)):
+        cfg.balance_monitor_config = BalanceMonitorConfig(
+            interval_secs=float(balances_cfg.get("interval_secs", 300.0)),
+            default_tolerance=Decimal(str(balances_cfg.get("default_tolerance", "0.01"))),
+            tolerance={str(k).upper(): Decimal(str(v)) for k, v in (balances_cfg.get("tolerance") or {}).items()},
+            exclude=[str(asset).upper() for asset in balances_cfg.get("exclude") or []],
+            request_timeout_secs=float(balances_cfg.get("request_timeout_secs", 30.0)),
+        )
+    listings_cfg = payload.get("listings") or {}
+    if listings_cfg.get("enabled", bool(listings_cfg)):
+        cfg.listing_config = ListingConfig(
+            interval_secs=float(listings_cfg.get("interval_secs", 60.0)),
+            market_types=[str(t).upper() for t in listings_cfg.get("market_types", ["PERP"]) or []],
+            subscribe=bool(listings_cfg.get("subscribe", False)),
+        )
+    screener_cfg = payload.get("screener") or {}
+    if screener_cfg.get("enabled", bool(screener_cfg)):
+        defaults = ScreenerConfig()
+        max_spread = screener_cfg.get("max_spread_bps")
+        min_funding, max_funding = screener_cfg.get("min_funding_apr"), screener_cfg.get("max_funding_apr")
+        max_symbols = screener_cfg.get("max_symbols")
+        cfg.screener_config = ScreenerConfig(
+            interval_secs=float(screener_cfg.get("interval_secs", defaults.interval_secs)),
+            market_types=[str(t).upper() for t in screener_cfg.get("market_types", defaults.market_types) or []],
+            min_volume_24h=Decimal(str(screener_cfg.get("min_volume_24h", defaults.min_volume_24h))),
+            min_open_interest=Decimal(str(screener_cfg.get("min_open_interest", defaults.min_open_interest))),
+            max_spread_bps=Decimal(str(max_spread)) if max_spread is not None else None,
+            min_funding_apr=Decimal(str(min_funding)) if min_funding is not None else None,
+            max_funding_apr=Decimal(str(max_funding)) if max_funding is not None else None,
+            max_symbols=int(max_symbols) if max_symbols is not None else None,
+        )
+    preflight_cfg = payload.get("preflight") or {}
+    if preflight_cfg.get("enabled", True):
+        require = [str(scope).lower() for scope in preflight_cfg.get("require") or [READ, TRADE]]
+        if cfg.withdrawal_config.enabled and WITHDRAW not in require:
+            require.append(WITHDRAW)
+        cfg.preflight_config = PreflightConfig(
+            require=require,
+            ws_auth=bool(preflight_cfg.get("ws_auth", True)),
+            timeout_secs=float(preflight_cfg.get("timeout_secs", 10.0)),
+        )
+    else:
+        cfg.preflight_config = None
+    startup_cfg = payload.get("startup_check") or {}
+    if startup_cfg.get("enabled", True):
+        cfg.startup_check_config = _parse_startup_check(startup_cfg)
+    else:
+        cfg.startup_check_config = None
+    cfg.read_only = read_only or bool(payload.get("read_only", False))
+    if cfg.read_only:
+        _apply_read_only(cfg)
+    _apply_polling(cfg, payload.get("polling") or {})
     return cfg
 
 
-__all__ = ["AppConfig", "load_config"]
+def _mean_reversion_config(raw: Dict[str, Any]) -> MeanReversionConfig:
+    defaults = MeanReversionConfig()
+    stop_z = raw.get("stop_z", defaults.stop_z)
+    max_hold = raw.get("max_hold_secs", defaults.max_hold_secs)
+    config = MeanReversionConfig(
+        window=int(raw.get("window", defaults.window)),
+        entry_z=Decimal(str(raw.get("entry_z", defaults.entry_z))),
+        exit_z=Decimal(str(raw.get("exit_z", defaults.exit_z))),
+        stop_z=Decimal(str(stop_z)) if stop_z is not None else None,
+        max_hold_secs=float(max_hold) if max_hold is not None else None,
+        policy=MakerFirstPolicy.from_mapping(raw.get("policy") or {}),
+    )
+    if config.exit_z >= config.entry_z:
+        raise ValueError("mean_reversion.exit_z must be below entry_z")
+    return config
+
+
+def _breakout_config(raw: Dict[str, Any]) -> BreakoutConfig:
+    defaults = BreakoutConfig()
+    exit_channel = raw.get("exit_channel", defaults.exit_channel)
+    stop_atr = raw.get("stop_atr", defaults.stop_atr)
+    trail_atr = raw.get("trail_atr", defaults.trail_atr)
+    config = BreakoutConfig(
+        channel=int(raw.get("channel", defaults.channel)),
+        exit_channel=int(exit_channel) if exit_channel is not None else None,
+        atr_period=int(raw.get("atr_period", defaults.atr_period)),
+        stop_atr=Decimal(str(stop_atr)) if stop_atr is not None else None,
+        trail_atr=Decimal(str(trail_atr)) if trail_atr is not None else None,
+        allow_short=bool(raw.get("allow_short", defaults.allow_short)),
+    )
+    if config.exit_channel is not None and config.exit_channel >= config.channel:
+        raise ValueError("breakout.exit_channel must be shorter than channel")
+    return config
+
+
+def _signer_config(raw: Any) -> Optional[SignerConfig]:
+    if not raw:
+        return None
+    signer = SignerConfig(
+        kind=str(raw.get("kind", LOCAL)).lower(),
+        socket_path=raw.get("socket_path"),
+        public_key=raw.get("public_key"),
+        timeout_secs=float(raw.get("timeout_secs", 2.0)),
+    )
+    if signer.kind not in (LOCAL, UNIX):
+        raise ValueError(f"connector.signer.kind must be {LOCAL} or {UNIX}, got {signer.kind!r}")
+    if signer.kind == UNIX and not (signer.socket_path and signer.public_key):
+        raise ValueError("connector.signer.kind unix needs socket_path and public_key")
+    return signer
+
+
+def _apply_read_only(cfg: AppConfig) -> None:
+    """Drop every section that needs signed requests, so a keyless market-data run starts cleanly."""
+    if cfg.withdrawal_config.enabled:
+        raise ValueError("withdrawals need API keys and cannot be enabled with read_only")
+    if cfg.carry_config and cfg.carry_config.apply:
+        raise ValueError("carry.apply places orders and cannot be enabled with read_only")
+    if cfg.rotation_config and cfg.rotation_config.apply:
+        raise ValueError("funding_rotation.apply places orders and cannot be enabled with read_only")
+    if cfg.cash_carry_config and cfg.cash_carry_config.apply:
+        raise ValueError("cash_carry.apply places orders and cannot be enabled with read_only")
+    cfg.preflight_config = None
+    cfg.startup_check_config = None
+    cfg.heartbeat_config = None
+    cfg.equity_config = None
+    cfg.report_config = None
+    cfg.dust_config = None
+    cfg.inventory_age_config = None
+    cfg.reconcile_config = None
+    cfg.balance_monitor_config = None
+    cfg.carry_config = None
+
+
+def _operator_token(entry: Any) -> Optional[str]:
+    # An operator is either a literal token or a mapping naming it (token) or its env var (token_env)
+    if isinstance(entry, dict):
+        return entry.get("token") or (os.getenv(str(entry["token_env"])) if entry.get("token_env") else None)
+    return str(entry) if entry else None
+
+
+def _parse_instrument_override(raw: Dict[str, Any]) -> Dict[str, Any]:
+    changes: Dict[str, Any] = {}
+    if raw.get("multiplier") is not None:
+        changes["multiplier"] = Decimal(str(raw["multiplier"]))
+    for key in ("base", "quote", "settle"):
+        if raw.get(key):
+            changes[key] = str(raw[key]).upper()
+    if raw.get("kind"):
+        changes["kind"] = str(raw["kind"]).lower()
+    if raw.get("inverse") is not None:
+        changes["inverse"] = bool(raw["inverse"])
+    return changes
+
+
+def _parse_expectations(raw: Dict[str, Any]) -> AccountExpectations:
+    max_open_orders = raw.get("max_open_orders")
+    min_equity = raw.get("min_equity")
+    return AccountExpectations(
+        account_id=None if raw.get("account_id") is None else str(raw["account_id"]),
+        flat=bool(raw.get("flat", False)),
+        max_open_orders=None if max_open_orders is None else int(max_open_orders),
+        min_equity=None if min_equity is None else Decimal(str(min_equity)),
+        positions={str(k): Decimal(str(v)) for k, v in (raw.get("positions") or {}).items()},
+        position_tolerance=Decimal(str(raw.get("position_tolerance", "0"))),
+    )
+
+
+def _parse_startup_check(startup_cfg: Dict[str, Any]) -> StartupCheckConfig:
+    """Expectations come inline or from `expectations_file` (YAML/JSON, same keys); the file wins."""
+    raw: Dict[str, Any] = dict(startup_cfg.get("expectations") or {})
+    if startup_cfg.get("expectations_file"):
+        raw = _read_payload(str(startup_cfg["expectations_file"]))
+    venues = raw.pop("venues", None) or {}
+    return StartupCheckConfig(
+        expectations=_parse_expectations(raw) if raw else None,
+        venues={str(venue).lower(): _parse_expectations(spec or {}) for venue, spec in venues.items()},
+    )
+
+
+def _apply_polling(cfg: AppConfig, polling_cfg: Dict[str, Any]) -> None:
+    """Backoff settings plus per-exchange poll intervals, which win over each section's interval_secs."""
+    cfg.polling_config = PollingConfig(
+        backoff=BackoffConfig(
+            factor=float(polling_cfg.get("backoff_factor", 2.0)),
+            rate_limit_factor=float(polling_cfg.get("rate_limit_factor", 4.0)),
+            max_secs=float(polling_cfg.get("max_backoff_secs", 600.0)),
+        ),
+        intervals={
+            str(venue).lower(): {str(poll).lower(): float(secs) for poll, secs in (polls or {}).items()}
+            for venue, polls in (polling_cfg.get("intervals") or {}).items()
+        },
+    )
+    polling = cfg.polling_config
+    if cfg.heartbeat_config:
+        cfg.heartbeat_config.interval_secs = polling.interval(cfg.venue, "heartbeat", cfg.heartbeat_config.interval_secs)
+    if cfg.reconcile_config:
+        cfg.reconcile_config.interval_secs = polling.interval(cfg.venue, "reconcile", cfg.reconcile_config.interval_secs)
+    if cfg.balance_monitor_config:
+        cfg.balance_monitor_config.interval_secs = polling.interval(
+            cfg.venue, "balances", cfg.balance_monitor_config.interval_secs
+        )
+    if cfg.funding_config:
+        cfg.funding_config.interval_secs = polling.interval(cfg.venue, "funding", cfg.funding_config.interval_secs)
+
+
+__all__ = ["AppConfig", "EquityConfig", "FundingConfig", "ReportConfig", "load_config", "load_storage_config"]
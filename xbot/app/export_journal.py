@@ -0,0 +1,77 @@
+from __future__ import annotations
+
+import argparse
+import asyncio
+from datetime import datetime, timezone
+from pathlib import Path
+from typing import Optional
+
+from xbot.analytics.journal import export_csv, export_parquet, load_journal
+from xbot.storage.factory import build_reader
+from .config import load_storage_config
+
+
+def _parse_date(value: Optional[str]) -> Optional[float]:
+    """Accept YYYY-MM-DD or full ISO timestamps; naive values are treated as UTC."""
+    if not value:
+        return None
+    parsed = datetime.fromisoformat(value)
+    if parsed.tzinfo is None:
+        parsed = parsed.replace(tzinfo=timezone.utc)
+    return parsed.timestamp()
+
+
+async def run(args: argparse.Namespace) -> int:
+    storage_cfg = load_storage_config(args.config_path)
+    if args.storage_root:
+        storage_cfg.root = args.storage_root
+    if args.bot_id:
+        storage_cfg.bot_id = args.bot_id
+    storage = build_reader(storage_cfg)
+    await storage.start()
+    try:
+        filters = {}
+        if args.venue:
+            filters["venue"] = args.venue
+        if args.symbol:
+            filters["symbol"] = args.symbol.upper()
+        trades = await load_journal(
+            storage,
+            start=_parse_date(args.start),
+            end=_parse_date(args.end),
+            filters=filters or None,
+        )
+    finally:
+        await storage.stop()
+    if args.strategy:
+        trades = [t for t in trades if t.strategy == args.strategy]
+    out = Path(args.out)
+    out.parent.mkdir(parents=True, exist_ok=True)
+    if args.format == "parquet":
+        return export_parquet(trades, out)
+    return export_csv(trades, out)
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(description="export the per-trade journal from storage")
+    parser.add_argument("--config", dest="config_path", help="config file with a storage section")
+    parser.add_argument("--storage-root", help="override the jsonl storage directory")
+    parser.add_argument("--bot-id", help="read this bot's rows instead of the config's storage.bot_id")
+    parser.add_argument("--format", default="csv", choices=["csv", "parquet"])
+    parser.add_argument("--out", required=True, help="output file path")
+    parser.add_argument("--start", help="include trades exiting at/after this date (UTC)")
+    parser.add_argument("--end", help="include trades exiting before this date (UTC)")
+    parser.add_argument("--venue")
+    parser.add_argument("--symbol")
+    parser.add_argument("--strategy", help="strategy tag, e.g. the trace_id prefix")
+    return parser.parse_args()
+
+
+def main() -> None:
+    args = parse_args()
+    count = asyncio.run(run(args))
+    print(f"exported {count} trades to {args.out}")
+
+
+if __name__ == "__main__":
+    main()
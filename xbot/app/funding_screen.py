@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+import argparse
+import asyncio
+import json
+import os
+import sys
+import time
+import urllib.request
+from typing import Any, Dict, Optional
+
+from xbot.analytics.funding_table import render_table
+
+_CLEAR = "\x1b[2J\x1b[H"
+
+
+def _fetch(url: str, token: Optional[str], underlying: Optional[str]) -> Dict[str, Any]:
+    if underlying:
+        url = f"{url}?underlying={underlying.upper()}"
+    request = urllib.request.Request(url, headers={"Authorization": f"Bearer {token}"} if token else {})
+    with urllib.request.urlopen(request, timeout=10) as response:
+        return json.loads(response.read().decode())
+
+
+async def run(args: argparse.Namespace) -> None:
+    url = f"http://{args.host}:{args.port}/funding"
+    token = os.getenv(args.token_env)
+    while True:
+        try:
+            snapshot = await asyncio.to_thread(_fetch, url, token, args.underlying)
+            updated = snapshot.get("updated")
+            header = time.strftime("%Y-%m-%d %H:%M:%S UTC", time.gmtime(updated)) if updated else "not refreshed yet"
+            body = f"funding table, updated {header}\n\n{render_table(snapshot)}"
+        except Exception as exc:
+            body = f"funding table unavailable at {url}: {exc}"
+        if args.once:
+            print(body)
+            return
+        sys.stdout.write(_CLEAR + body + "\n")
+        sys.stdout.flush()
+        await asyncio.sleep(args.refresh)
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(description="live cross-venue funding table from the admin API")
+    parser.add_argument("--host", default="127.0.0.1")
+    parser.add_argument("--port", type=int, default=8089)
+    parser.add_argument("--token-env", default="XBOT_ADMIN_TOKEN", help="env var holding the admin token")
+    parser.add_argument("--underlying", help="show a single underlying, e.g. BTC")
+    parser.add_argument("--refresh", type=float, default=5.0, help="seconds between redraws")
+    parser.add_argument("--once", action="store_true", help="print one snapshot and exit")
+    return parser.parse_args()
+
+
+def main() -> None:
+    try:
+        asyncio.run(run(parse_args()))
+    except KeyboardInterrupt:
+        pass
+
+
+if __name__ == "__main__":
+    main()
@@ -2,57 +2,251 @@ from __future__ import annotations
 
 import argparse
 import asyncio
-from typing import Dict
-import os
-from pathlib import Path
+import dataclasses
+from decimal import Decimal
+from typing import Dict, List
+import sys
 
 from xbot.connector.factory import build_connector
+from xbot.connector.profiles import startup_banner
+from xbot.analytics.balance_monitor import BalanceMonitor
+from xbot.analytics.carry import CarryOptimizer
+from xbot.analytics.correlation import CorrelationService
+from xbot.analytics.equity import EquityTracker
+from xbot.analytics.funding_table import FundingTable
+from xbot.analytics.kelly import KellyService
+from xbot.analytics.report import PerformanceReporter
+from xbot.analytics.ticker_stats import TickerStats
+from xbot.core.alerts import AlertLevel, build_alert_manager
+from xbot.core.approvals import ApprovalGate
+from xbot.core.audit import AUDIT
 from xbot.core.clock import WallClock
+from xbot.core.command_bus import CommandBus
+from xbot.core.events import EventStream
+from xbot.core.metrics import METRICS
 from xbot.core.lifecycle import LifecycleController
 from xbot.core.heartbeat import HeartbeatService
+from xbot.core.rate_shaper import RateShaper
+from xbot.core.time_sync import TimeSyncService, server_time_sources
+from xbot.core.symbology import SYMBOLOGY
+from xbot.core.shutdown import PHASE_INTAKE, PHASE_ORDERS, PHASE_STORAGE, PHASE_TRANSPORT, ShutdownCoordinator
+from xbot.execution.circuit_breaker import DrawdownCircuitBreaker
+from xbot.execution.commands import RouterCommandHandler
+from xbot.execution.dust import DustSweeper
+from xbot.execution.inventory_age import InventoryAgeMonitor
+from xbot.execution.funding import EwmaFundingPredictor, FundingService
+from xbot.execution.fx import CurrencyConverter, FxService
+from xbot.execution.listings import ListingDetector
 from xbot.execution.market_data_service import MarketDataService
+from xbot.execution.market_state import MarketStateMonitor
 from xbot.execution.order_service import OrderService
+from xbot.execution.portfolio_executor import TargetPositionExecutor
 from xbot.execution.position_service import PositionService
-from xbot.execution.risk_service import RiskService
+from xbot.execution.preflight import PermissionPreflight
+from xbot.execution.order_expiry import OrderExpiryService
+from xbot.execution.order_progress import OrderProgressChannel
+from xbot.execution.queue_position import QueuePositionTracker
+from xbot.execution.reconciliation import PositionReconciler
+from xbot.execution.rejections import RejectionTracker
+from xbot.execution.startup_check import AccountStartupCheck
+from xbot.execution.recovery import RecoveryService
+from xbot.execution.risk_service import RiskMode, RiskService
+from xbot.execution.screener import UniverseScreener
+from xbot.execution.self_trade import SelfTradeGuard
 from xbot.execution.tracking_limit import TrackingLimitEngine
+from xbot.execution.venue_status import VenueStatusMonitor
+from xbot.execution.watchlist import Watchlist
+from xbot.execution.candles import CandleService
+from xbot.execution.arbitration import PositionArbiter
+from xbot.execution.decisions import LIVE, DecisionLog
+from xbot.execution.signals import SignalService
+from xbot.execution.whales import WhaleDetector
+from xbot.execution.sizing import VolatilitySizer
+from xbot.execution.withdrawals import WithdrawalGuard
 from xbot.execution.router import ExecutionRouter
-from xbot.strategy.base import StrategyConfig
+from xbot.storage.base import StorageWriter
+from xbot.storage.factory import build_writer
+from xbot.storage.recorder import FillSnapshotRecorder, FundingPaymentRecorder, MarketSnapshotRecorder
+from xbot.strategy.base import Strategy, StrategyConfig
 from xbot.strategy.market import MarketOrderStrategy
 from xbot.strategy.tracking_limit import TrackingLimitStrategy
 from xbot.strategy.diagnostic import DiagnosticStrategy
+from xbot.strategy.breakout import BreakoutConfig, BreakoutStrategy
+from xbot.strategy.event_calendar import EventCalendar, EventHaltService
+from xbot.strategy.guardrails import SessionGuardrails
+from xbot.strategy.mean_reversion import MeanReversionConfig, MeanReversionStrategy
+from xbot.strategy.shadow import ShadowRunner
+from xbot.strategy.funding_rotation import FundingRotationStrategy
+from xbot.strategy.cash_carry import CashAndCarryStrategy
 from xbot.utils.logging import get_logger, setup_logging
+from .admin_api import AdminApi
 from .config import AppConfig, load_config
 from xbot.core.cache import MarketCache
-from xbot.connector.backpack_ws import BackpackWsClient
+from xbot.connector.backpack_ws import BackpackWsClient, BackpackWsError
+from xbot.connector.ws_pool import WsConnectionPool
 
 
 STRATEGY_REGISTRY: Dict[str, str] = {
     "market": "market",
     "tracking_limit": "tracking_limit",
     "diagnostic": "diagnostic",
+    "mean_reversion": "mean_reversion",
+    "breakout": "breakout",
 }
+CANDLE_MODES = {"mean_reversion", "breakout"}  # strategies that read the candle store
 
 
-async def run(cfg: AppConfig, log_level: str) -> None:
+def build_strategy(
+    mode: str,
+    *,
+    router: ExecutionRouter,
+    clock: WallClock,
+    config: StrategyConfig,
+    candles: CandleService | None = None,
+    sizer: VolatilitySizer | None = None,
+    kelly: KellyService | None = None,
+    params: object = None,
+) -> Strategy:
+    """The strategy for `mode`; `params` is the mode's own config (mean reversion, breakout)."""
+    if mode == "tracking_limit":
+        return TrackingLimitStrategy(router=router, clock=clock, config=config, sizer=sizer, kelly=kelly)
+    if mode == "market":
+        return MarketOrderStrategy(router=router, clock=clock, config=config, sizer=sizer, kelly=kelly)
+    if mode == "diagnostic":
+        return DiagnosticStrategy(router=router, clock=clock, config=config)
+    if mode == "mean_reversion" and candles:
+        return MeanReversionStrategy(
+            router=router,
+            clock=clock,
+            config=config,
+            candles=candles,
+            params=params if isinstance(params, MeanReversionConfig) else None,
+            sizer=sizer,
+            kelly=kelly,
+        )
+    if mode == "breakout" and candles:
+        return BreakoutStrategy(
+            router=router,
+            clock=clock,
+            config=config,
+            candles=candles,
+            params=params if isinstance(params, BreakoutConfig) else None,
+            sizer=sizer,
+            kelly=kelly,
+        )
+    raise ValueError(f"unsupported mode: {mode}")
+
+
+async def run(cfg: AppConfig, log_level: str) -> int:
     setup_logging(log_level)
     logger = get_logger(__name__)
-    connector = build_connector(cfg.venue)
-    market_data = MarketDataService(connector=connector, symbol_map=cfg.symbol_map)
+    if cfg.profile is not None:
+        print(startup_banner(cfg.profile), file=sys.stderr, flush=True)
+        logger.info(
+            "environment_profile",
+            extra={"venue": cfg.venue, "environment": cfg.environment, "rest_url": cfg.profile.rest_url, "ws_url": cfg.profile.ws_url},
+        )
+    METRICS.set("bot_environment", 1, venue=cfg.venue, environment=cfg.environment)
+    for native, changes in cfg.instrument_overrides.items():
+        SYMBOLOGY.override(cfg.venue, native, **changes)
+    connector = build_connector(cfg.venue, cfg.connector_config)
+    market_data = MarketDataService(
+        connector=connector,
+        symbol_map=cfg.symbol_map,
+        stale_after_secs=cfg.stale_after_secs,
+        funding_ttl_secs=cfg.funding_ttl_secs,
+    )
     position_service = PositionService()
-    risk_service = RiskService(market_data=market_data, position_service=position_service, limits=cfg.risk_limits)
+    fx: FxService | None = None
+    if cfg.fx_config:
+        for symbol in cfg.fx_config.symbols:
+            if symbol not in market_data.symbols():
+                market_data.register_symbol(symbol, cfg.symbol_map.get(symbol, symbol))
+        fx = FxService(
+            converter=CurrencyConverter(
+                cfg.fx_config.reporting_currency, pegs=cfg.fx_config.pegs, max_age_secs=cfg.fx_config.max_age_secs
+            ),
+            market_data=[market_data],
+            symbols=cfg.fx_config.symbols,
+            interval_secs=cfg.fx_config.interval_secs,
+        )
+    risk_service = RiskService(
+        market_data=market_data,
+        position_service=position_service,
+        limits=cfg.risk_limits,
+        fx=fx.converter if fx else None,
+        symbols=cfg.symbol_lists,
+        windows=cfg.trading_windows,
+    )
     tracking_engine = TrackingLimitEngine(
         market_data=market_data,
         default_interval_secs=cfg.interval_secs,
         default_timeout_secs=cfg.timeout_secs,
     )
+    events = EventStream(config=cfg.event_stream_config)
+    storage: StorageWriter | None = build_writer(cfg.storage_config) if cfg.storage_config else None
+    if storage and cfg.audit_enabled:
+        AUDIT.attach(storage)
     order_service = OrderService(
         connector=connector,
         market_data=market_data,
         risk_service=risk_service,
         tracking_engine=tracking_engine,
+        storage=storage,
+        events=events,
     )
     # Shared market cache and optional WS client (for Backpack)
-    cache = MarketCache()
+    cache = MarketCache(events=events, source=cfg.venue)
+    market_data.attach_cache(cache)
+    expiry = OrderExpiryService(order_service=order_service, events=events)
+    order_service.attach_expiry(expiry)
+    progress = OrderProgressChannel()
+    order_service.attach_progress(progress)
+    if cfg.self_trade_config:
+        # One guard per process; register every account's OrderService with it
+        SelfTradeGuard(cfg.self_trade_config).register(order_service)
+    if cfg.arbitration_config:
+        PositionArbiter(cfg.arbitration_config).register(order_service)
+    if cfg.rate_shaper_config:
+        order_service.attach_rate_shaper(RateShaper(cfg.rate_shaper_config))
+    queue_tracker: QueuePositionTracker | None = None
+    if cfg.queue_tracking:
+        queue_tracker = QueuePositionTracker(market_data=market_data, cache=cache, events=events)
+        order_service.attach_queue_tracker(queue_tracker)
+    recorder: MarketSnapshotRecorder | None = None
+    if storage and cfg.storage_config and cfg.storage_config.snapshot_interval_secs > 0:
+        recorder = MarketSnapshotRecorder(
+            cache=cache,
+            writer=storage,
+            venue=cfg.venue,
+            interval_secs=cfg.storage_config.snapshot_interval_secs,
+        )
+    funding_payments: FundingPaymentRecorder | None = None
+    if (
+        storage
+        and cfg.storage_config
+        and cfg.storage_config.funding_payments_interval_secs > 0
+        and not cfg.read_only
+        and hasattr(connector, "get_funding_payments")
+    ):
+        # The journal and reports credit funding to trades from this table
+        funding_payments = FundingPaymentRecorder(
+            fetch=connector.get_funding_payments,  # type: ignore[attr-defined]
+            writer=storage,
+            venue=cfg.venue,
+            canonical=market_data.canonical_for,
+            interval_secs=cfg.storage_config.funding_payments_interval_secs,
+        )
+    order_service.attach_rejections(RejectionTracker())
+    if storage and cfg.storage_config and cfg.storage_config.fill_snapshot_depth > 0:
+        order_service.attach_fill_snapshots(
+            FillSnapshotRecorder(
+                cache=cache,
+                writer=storage,
+                depth=cfg.storage_config.fill_snapshot_depth,
+                trades=cfg.storage_config.fill_snapshot_trades,
+            )
+        )
 
     router = ExecutionRouter(
         order_service=order_service,
@@ -61,15 +255,37 @@ async def run(cfg: AppConfig, log_level: str) -> None:
         market_data=market_data,
         cache=cache,
     )
+    candles: CandleService | None = None
+    if cfg.sizing_config or cfg.correlation_config or CANDLE_MODES & {cfg.mode, cfg.shadow_config.mode if cfg.shadow_config else ""}:
+        candles = CandleService(events=events, config=cfg.candle_config)
+    sizer: VolatilitySizer | None = None
+    if cfg.sizing_config and candles:
+        sizer = VolatilitySizer(router=router, candles=candles, config=cfg.sizing_config)
+    signals = SignalService(events=events, config=cfg.signal_config) if cfg.signal_config else None
+    correlations: CorrelationService | None = None
+    if cfg.correlation_config and candles:
+        correlations = CorrelationService(candles=candles, market_data=market_data, config=cfg.correlation_config)
+        risk_service.attach_correlations(correlations)
+    # External commands (operator tooling, signal relays) enter through the bus
+    commands = CommandBus(cfg.command_bus_config)
+    commands.register(cfg.venue, RouterCommandHandler(router, progress=progress, sizer=sizer))
+    # Symbols streamed for market data; the admin API can change it at runtime
+    watchlist = Watchlist(
+        market_data=market_data,
+        events=events,
+        venue=cfg.venue,
+        symbols=[cfg.symbol] if cfg.symbol.upper() in market_data.symbols() else [],
+    )
     # Configure optional WS background task if venue supports it
     background_tasks = []
+    ws_auth: BackpackWsClient | None = None  # signs a private subscribe during the startup key check
+    ws_pool: WsConnectionPool | None = None
     if cfg.venue == "backpack":
         try:
             # Subscribe to the venue symbol for public streams
             venue_symbol = market_data.resolve_symbol(cfg.symbol)
         except Exception:
             venue_symbol = cfg.symbol
-        key_file = Path(os.getenv("BACKPACK_KEY_FILE", str(Path.cwd() / "Backpack_key.txt")))
         # Wire WS order updates into OrderService
         from xbot.execution.order_service import OrderUpdatePayload
 
@@ -80,16 +296,42 @@ async def run(cfg: AppConfig, log_level: str) -> None:
                 # Ingest failures should not crash WS task
                 pass
 
-        ws_client = BackpackWsClient(symbols=[venue_symbol], key_file=key_file, cache=cache, on_order_update=on_order_update)
+        async def on_ws_error(error: BackpackWsError) -> None:
+            # `alerts` is built below; the WS only starts once the session runs
+            await alerts.notify(
+                AlertLevel.CRITICAL if error.auth else AlertLevel.WARNING,
+                "backpack ws auth rejected" if error.auth else "backpack ws request rejected",
+                str(error),
+                venue="backpack",
+                code=error.code,
+                streams=error.streams,
+            )
+
+        def ws_shard(index: int, symbols: List[str]) -> BackpackWsClient:
+            # The primary connection also signs in for the account streams
+            # Same key file and connector config as the REST client
+            return connector.ws_client(  # type: ignore[attr-defined]
+                symbols=symbols,
+                cache=cache,
+                on_order_update=on_order_update,
+                on_error=on_ws_error,
+                private=index == 0 and not cfg.read_only,
+                market_feed="market" if index == 0 else f"market.{index}",
+            )
+
+        ws_pool = WsConnectionPool(venue="backpack", factory=ws_shard, symbols=[venue_symbol], config=cfg.ws_pool_config)
+        watchlist.attach_feed(ws_pool)
+        ws_auth = ws_pool.primary  # type: ignore[assignment]
 
         async def ws_task() -> None:
-            await ws_client.start()
+            assert ws_pool is not None
+            await ws_pool.start()
             # Keep the task alive until cancelled
             try:
                 while True:
                     await asyncio.sleep(3600)
             finally:
-                await ws_client.stop()
+                await ws_pool.stop()
 
         background_tasks.append(ws_task)
     elif cfg.venue == "lighter":
@@ -117,7 +359,7 @@ async def run(cfg: AppConfig, log_level: str) -> None:
             except Exception:
                 market_index = None
             try:
-                acct_getter = getattr(connector, "get_account_index", None)
+                acct_getter = None if cfg.read_only else getattr(connector, "get_account_index", None)
                 if acct_getter is not None:
                     account_index = acct_getter()  # type: ignore[call-arg]
             except Exception:
@@ -136,6 +378,8 @@ async def run(cfg: AppConfig, log_level: str) -> None:
                 key_file=key_file,
                 base_url=getattr(connector, "base_url", "https://mainnet.zklighter.elliot.ai"),
                 on_order_update=on_order_update,
+                ws_url=cfg.connector_config.ws_url,
+                proxy=cfg.connector_config.proxy,
             )
             await ws_client.start()
             try:
@@ -147,6 +391,52 @@ async def run(cfg: AppConfig, log_level: str) -> None:
         background_tasks.append(ws_task)
 
     lifecycle = LifecycleController(connector=connector, background_tasks=background_tasks)
+    alerts = build_alert_manager(cfg.alert_config)
+    whales = WhaleDetector(events=events, config=cfg.whale_config, alerts=alerts) if cfg.whale_config else None
+    equity: EquityTracker | None = None
+    breaker: DrawdownCircuitBreaker | None = None
+    if storage and cfg.equity_config:
+        equity = EquityTracker(
+            connectors=[connector],
+            writer=storage,
+            interval_secs=cfg.equity_config.interval_secs,
+            fx=fx.converter if fx else None,
+            currencies=cfg.fx_config.venue_currencies if cfg.fx_config else None,
+        )
+        # Always on: it reads max_drawdown_pct from the live limits, which POST /risk/limits can set
+        breaker = DrawdownCircuitBreaker(
+            equity=equity,
+            risk=risk_service,
+            alerts=alerts,
+            lookback_secs=cfg.equity_config.drawdown_lookback_secs,
+            interval_secs=cfg.equity_config.interval_secs,
+        )
+    reporter: PerformanceReporter | None = None
+    if storage and cfg.report_config:
+
+        async def unrealized_pnl() -> Decimal:
+            total = Decimal(0)
+            for raw in await connector.get_positions():
+                value = raw.get("pnlUnrealized") or raw.get("unrealized_pnl")
+                if value is not None:
+                    total += Decimal(str(value))
+            return total
+
+        reporter = PerformanceReporter(
+            storage=storage.storage,
+            alerts=alerts,
+            unrealized=unrealized_pnl,
+            equity=equity,
+            hour_utc=cfg.report_config.hour_utc,
+            weekly_weekday=cfg.report_config.weekly_weekday,
+            tca=cfg.report_config.tca,
+            tca_quote_age_secs=cfg.report_config.tca_quote_age_secs,
+        )
+    kelly: KellyService | None = None
+    if cfg.kelly_config:
+        if not storage:
+            raise ValueError("kelly sizing reads the trade journal and needs a storage section")
+        kelly = KellyService(storage=storage.storage, config=cfg.kelly_config)
     clock = WallClock()
     heartbeat: HeartbeatService | None = None
     strategy_cfg = StrategyConfig(
@@ -158,35 +448,444 @@ async def run(cfg: AppConfig, log_level: str) -> None:
         price_offset_ticks=cfg.price_offset_ticks,
         interval_secs=cfg.interval_secs,
         timeout_secs=cfg.timeout_secs,
+        max_latency_ms=cfg.max_latency_ms,
+        name=cfg.strategy_name,
+        sizing=cfg.strategy_sizing,
+    )
+    strategy = build_strategy(
+        cfg.mode,
+        router=router,
+        clock=clock,
+        config=strategy_cfg,
+        candles=candles,
+        sizer=sizer,
+        kelly=kelly,
+        params=cfg.mean_reversion_config if cfg.mode == "mean_reversion" else cfg.breakout_config,
+    )
+    shadow: ShadowRunner | None = None
+    if cfg.shadow_config:
+        shadow_cfg = cfg.shadow_config
+        # The live router's orders are recorded too, so both decision streams land in one table
+        decisions = DecisionLog(writer=storage)
+        router.attach_decisions(decisions, LIVE)
+        shadow_strategy_cfg = dataclasses.replace(
+            strategy_cfg,
+            mode=shadow_cfg.mode,
+            name=shadow_cfg.name,
+            qty=shadow_cfg.qty if shadow_cfg.qty is not None else strategy_cfg.qty,
+            interval_secs=shadow_cfg.interval_secs or strategy_cfg.interval_secs,
+        )
+        shadow = ShadowRunner(
+            market_data=market_data,
+            cache=cache,
+            symbol=cfg.symbol,
+            venue=cfg.venue,
+            limits=cfg.risk_limits,
+            decisions=decisions,
+            config=shadow_cfg,
+            factory=lambda paper: build_strategy(
+                shadow_cfg.mode,
+                router=paper,
+                clock=clock,
+                config=shadow_strategy_cfg,
+                candles=candles,
+                sizer=sizer,
+                params=shadow_cfg.params,
+            ),
+        )
+    guardrails: SessionGuardrails | None = None
+    if cfg.guardrail_config:
+        guardrails = SessionGuardrails(router=router, events=events, alerts=alerts, writer=storage)
+        guardrails.register(strategy, cfg.guardrail_config)
+    event_halt: EventHaltService | None = None
+    if cfg.event_calendar_config:
+        event_halt = EventHaltService(
+            calendar=EventCalendar(cfg.event_calendar_config),
+            config=cfg.event_calendar_config,
+            risk=risk_service,
+            alerts=alerts,
+        )
+        event_halt.register(strategy)
+
+    funding: FundingService | None = None
+    if cfg.funding_config:
+        if not hasattr(connector, "get_funding_rates"):
+            raise ValueError(f"funding rates are not supported on {cfg.venue}")
+        funding = FundingService(
+            connector=connector,  # type: ignore[arg-type]
+            market_data=market_data,
+            symbols=cfg.funding_config.symbols,
+            predictor=EwmaFundingPredictor(cfg.funding_config.ewma_alpha),
+            writer=storage,
+            history_limit=cfg.funding_config.history_limit,
+            interval_secs=cfg.funding_config.interval_secs,
+            backoff=cfg.polling_config.backoff,
+        )
+    funding_table: FundingTable | None = None
+    if cfg.funding_table_config:
+        if not hasattr(connector, "get_current_funding"):
+            raise ValueError(f"current funding is not supported on {cfg.venue}")
+        funding_table = FundingTable(sources=[connector], config=cfg.funding_table_config)  # type: ignore[list-item]
+    ticker_stats: TickerStats | None = None
+    if cfg.ticker_stats_config:
+        if not hasattr(connector, "get_tickers"):
+            raise ValueError(f"ticker stats are not supported on {cfg.venue}")
+        ticker_stats = TickerStats(sources=[connector], config=cfg.ticker_stats_config)  # type: ignore[list-item]
+    carry_executor: TargetPositionExecutor | None = None
+    carry: CarryOptimizer | None = None
+    if cfg.carry_config:
+        if cfg.carry_config.apply:
+            carry_executor = TargetPositionExecutor(
+                order_service=order_service,
+                position_service=position_service,
+                market_data=market_data,
+                trace_prefix="carry",
+            )
+        carry = CarryOptimizer(
+            funding_table=funding_table,
+            connectors=[connector],
+            executors={cfg.venue: carry_executor} if carry_executor else None,
+            market_data={cfg.venue: market_data},
+            config=cfg.carry_config,
+        )
+    rotation_executor: TargetPositionExecutor | None = None
+    rotation: FundingRotationStrategy | None = None
+    if cfg.rotation_config and funding_table:
+        if cfg.rotation_config.apply:
+            rotation_executor = TargetPositionExecutor(
+                order_service=order_service,
+                position_service=position_service,
+                market_data=market_data,
+                trace_prefix="rotation",
+            )
+        rotation = FundingRotationStrategy(
+            funding_table=funding_table,
+            executors={cfg.venue: rotation_executor} if rotation_executor else None,
+            market_data={cfg.venue: market_data},
+            config=cfg.rotation_config,
+            funding={cfg.venue: funding} if funding else None,
+            tickers=ticker_stats,
+        )
+    cash_carry_executor: TargetPositionExecutor | None = None
+    cash_carry: CashAndCarryStrategy | None = None
+    if cfg.cash_carry_config:
+        if cfg.cash_carry_config.apply:
+            cash_carry_executor = TargetPositionExecutor(
+                order_service=order_service,
+                position_service=position_service,
+                market_data=market_data,
+                trace_prefix="cash_carry_hedge",
+            )
+        cash_carry = CashAndCarryStrategy(
+            connector=connector,
+            order_service=order_service,
+            market_data=market_data,
+            executor=cash_carry_executor,
+            config=cfg.cash_carry_config,
+            alerts=alerts,
+        )
+    dust = DustSweeper(connector=connector, router=router, config=cfg.dust_config) if cfg.dust_config else None
+    inventory_age = (
+        InventoryAgeMonitor(router=router, config=cfg.inventory_age_config, alerts=alerts)
+        if cfg.inventory_age_config
+        else None
+    )
+    balance_monitor = (
+        BalanceMonitor(
+            connector=connector,
+            alerts=alerts,
+            config=cfg.balance_monitor_config,
+            backoff=cfg.polling_config.backoff,
+            state=events.state,
+        )
+        if cfg.balance_monitor_config
+        else None
+    )
+    listings = (
+        ListingDetector(
+            connector=connector,
+            market_data=market_data,
+            events=events,
+            alerts=alerts,
+            watchlist=watchlist,
+            config=cfg.listing_config,
+        )
+        if cfg.listing_config
+        else None
     )
-    if cfg.mode == "tracking_limit":
-        strategy = TrackingLimitStrategy(router=router, clock=clock, config=strategy_cfg)
-    elif cfg.mode == "market":
-        strategy = MarketOrderStrategy(router=router, clock=clock, config=strategy_cfg)
-    elif cfg.mode == "diagnostic":
-        strategy = DiagnosticStrategy(router=router, clock=clock, config=strategy_cfg)
-    else:
-        raise ValueError(f"unsupported mode: {cfg.mode}")
-
-    await lifecycle.start()
-    try:
+    screener = (
+        UniverseScreener(connector=connector, market_data=market_data, watchlist=watchlist, config=cfg.screener_config)
+        if cfg.screener_config
+        else None
+    )
+    market_state = (
+        MarketStateMonitor(
+            connector=connector,
+            market_data=market_data,
+            events=events,
+            alerts=alerts,
+            interval_secs=cfg.market_state_interval_secs,
+        )
+        if cfg.market_state_interval_secs and hasattr(connector, "refresh_markets")
+        else None
+    )
+    venue_status = (
+        VenueStatusMonitor(
+            connector=connector,
+            risk=risk_service,
+            events=events,
+            alerts=alerts,
+            config=cfg.venue_status_config,
+        )
+        if cfg.venue_status_config
+        else None
+    )
+    reconciler: PositionReconciler | None = None
+    if cfg.reconcile_config:
+        reconciler = PositionReconciler(
+            connector=connector,
+            market_data=market_data,
+            positions=position_service,
+            events=events,
+            risk=risk_service,
+            alerts=alerts,
+            config=cfg.reconcile_config,
+            backoff=cfg.polling_config.backoff,
+        )
+    admin: AdminApi | None = None
+    if cfg.admin_config:
+        admin = AdminApi(cfg.admin_config)
+        approvals = ApprovalGate(cfg.approval_config, alerts=alerts)
+        admin.mount_approvals(approvals)
+        admin.mount_flatten(router, approvals)
+        admin.mount_risk_limits(risk_service, approvals, maxima=cfg.risk_limits)
+        admin.mount_symbol_lists(risk_service, approvals, configured=cfg.symbol_lists)
+        if cfg.withdrawal_config.enabled:
+            if not hasattr(connector, "request_withdrawal"):
+                raise ValueError(f"withdrawals are not supported on {cfg.venue}")
+            withdrawal_guard = WithdrawalGuard(
+                connector=connector, config=cfg.withdrawal_config, alerts=alerts, equity=equity  # type: ignore[arg-type]
+            )
+            admin.mount_withdrawals(withdrawal_guard, approvals)
+            if balance_monitor:
+                balance_monitor.attach_withdrawals(withdrawal_guard)
+        if funding_table:
+            admin.mount_funding_table(funding_table)
+        if ticker_stats:
+            admin.mount_ticker_stats(ticker_stats)
+        admin.mount_watchlist(watchlist)
+        if order_service.rejections:
+            admin.mount_rejections(order_service.rejections)
+        if screener:
+            admin.mount_screener(screener)
+        if ws_pool:
+            admin.mount_ws_pool(ws_pool)
+        if correlations:
+            admin.mount_correlations(correlations)
+
+    recovery: RecoveryService | None = None
+    if not cfg.read_only:
+        recovery = RecoveryService(
+            connector=connector,
+            order_service=order_service,
+            position_service=position_service,
+            market_data=market_data,
+        )
+        # Restored orders go back to whoever sent them, by trace_id prefix
+        for executor in (carry_executor, rotation_executor, cash_carry_executor):
+            if executor:
+                recovery.register_reattach(f"{executor.trace_prefix}:", executor.adopt)
+        if cash_carry:
+            recovery.register_reattach("cash_carry:", cash_carry.adopt)
+        recovery.register_reattach(f"{strategy.name}:", strategy.adopt)
+        # Closed legs too: a group resumes from how its orders ended while the bot was down
+        recovery.register_reattach("oco:", router.oco.adopt, closed=True)
+        recovery.register_reattach("iceberg:", router.iceberg.adopt, closed=True)
+
+    time_sync = TimeSyncService(sources=server_time_sources([connector]), interval_secs=cfg.time_sync_interval_secs)
+    shutdown = ShutdownCoordinator(cfg.shutdown_config)
+    shutdown.install_signal_handlers()
+
+    async def stop_intake() -> dict:
+        # Every new order is rejected from here on; cancels still go through
+        risk_service.set_mode(RiskMode.HALTED, "shutdown")
+        logger.info("strategy_stop", extra={"venue": cfg.venue})
+        return {"discarded_commands": await commands.close()}
+
+    async def stop_heartbeat() -> None:
+        if heartbeat:
+            await heartbeat.stop()
+
+    shutdown.register("intake", stop_intake, phase=PHASE_INTAKE)
+    if admin:
+        shutdown.register("admin_api", admin.stop, phase=PHASE_INTAKE)
+    if cfg.shutdown_config.cancel_open_orders:
+        shutdown.register("cancel_open_orders", order_service.cancel_all, phase=PHASE_ORDERS)
+    shutdown.register("heartbeat", stop_heartbeat)
+    shutdown.register("time_sync", time_sync.stop)
+    for name, service in (
+        ("recorder", recorder),
+        ("funding_payments", funding_payments),
+        ("reporter", reporter),
+        ("breaker", breaker),
+        ("candles", candles),
+        ("signals", signals),
+        ("whales", whales),
+        ("kelly", kelly),
+        ("correlations", correlations),
+        ("guardrails", guardrails),
+        ("event_halt", event_halt),
+        ("shadow", shadow),
+        ("equity", equity),
+        ("fx", fx),
+        ("dust", dust),
+        ("inventory_age", inventory_age),
+        ("funding", funding),
+        ("funding_table", funding_table),
+        ("ticker_stats", ticker_stats),
+        ("carry", carry),
+        ("carry_executor", carry_executor),
+        ("funding_rotation", rotation),
+        ("rotation_executor", rotation_executor),
+        ("cash_carry", cash_carry),
+        ("cash_carry_executor", cash_carry_executor),
+        ("queue_tracker", queue_tracker),
+        ("order_expiry", expiry),
+        ("oco", router.oco),
+        ("iceberg", router.iceberg),
+        ("reconciler", reconciler),
+        ("recovery", recovery),
+        ("balance_monitor", balance_monitor),
+        ("listings", listings),
+        ("screener", screener),
+        ("market_state", market_state),
+        ("venue_status", venue_status),
+    ):
+        if service:
+            shutdown.register(name, service.stop)
+    shutdown.register("transport", lifecycle.stop, phase=PHASE_TRANSPORT)
+    if storage:
+        shutdown.register("storage", storage.stop, phase=PHASE_STORAGE)
+    shutdown.register("alerts", alerts.aclose, phase=PHASE_STORAGE)
+
+    async def session() -> None:
+        nonlocal heartbeat
+        if storage:
+            await storage.start()
+        if candles:
+            # Subscribed before the feeds connect so the first trades are bucketed
+            await candles.start()
+        if signals:
+            await signals.start()
+        if whales:
+            await whales.start()
+        await lifecycle.start()
+        if queue_tracker:
+            await queue_tracker.start()
+        await expiry.start()
+        # Sync before any signed request so drift cannot cause timestamp/window rejections
+        await time_sync.start()
+        if cfg.preflight_config:
+            # Fail fast on a key that cannot read, trade (or withdraw) before anything acts on the account
+            preflight = PermissionPreflight(connector=connector, ws=ws_auth, alerts=alerts, config=cfg.preflight_config)
+            await preflight.run(market_data.resolve_symbol(cfg.symbol))
+        if cfg.startup_check_config:
+            # Record which account this is and refuse to start if it is not the one we expect
+            startup_check = AccountStartupCheck(
+                connectors=[connector], writer=storage, alerts=alerts, config=cfg.startup_check_config
+            )
+            await startup_check.run()
+        if recorder:
+            await recorder.start()
+        if funding_payments:
+            await funding_payments.start()
+        if fx:
+            # Rates first: equity snapshots and notional checks value in the reporting currency
+            await fx.start()
+        if equity:
+            await equity.start()
+        if breaker:
+            await breaker.start()
+        if reporter:
+            await reporter.start()
+        if funding:
+            await funding.start()
+        if funding_table:
+            await funding_table.start()
+        if ticker_stats:
+            await ticker_stats.start()
+        if cfg.read_only:
+            # Nothing can be signed: no account state to restore and nothing may trade
+            risk_service.set_mode(RiskMode.HALTED, "read_only")
+        elif recovery:
+            # Restore in-flight orders/positions before any strategy can act on stale state
+            await recovery.run()
+            await router.oco.start()
+            await router.iceberg.start()
+        if reconciler:
+            # Seeds its virtual book from the recovered venue positions
+            await reconciler.start()
+        if balance_monitor:
+            await balance_monitor.start()
+        if listings:
+            await listings.start()
+        if screener:
+            await screener.start()
+        if market_state:
+            await market_state.start()
+        if venue_status:
+            await venue_status.start()
+        await commands.start()
+        if admin:
+            await admin.start()
+        if dust:
+            await dust.start()
+        if inventory_age:
+            await inventory_age.start()
         if cfg.heartbeat_config:
             heartbeat = HeartbeatService(
                 connector=connector,
                 router=router,
                 clock=clock,
-                strategy_name=strategy_cfg.mode,
+                strategy_name=strategy.name,
                 venue=cfg.venue,
                 config=cfg.heartbeat_config,
+                backoff=cfg.polling_config.backoff,
+                state=events.state,
             )
             await heartbeat.start()
+        if guardrails:
+            await guardrails.start()
+        if event_halt:
+            await event_halt.start()
+        if shadow:
+            await shadow.start()
+        if kelly:
+            await kelly.start()
+        if correlations:
+            await correlations.start()
+        if carry_executor:
+            await carry_executor.start()
+        if carry:
+            await carry.start()
+        if rotation_executor:
+            await rotation_executor.start()
+        if rotation:
+            await rotation.start()
+        if cash_carry_executor:
+            await cash_carry_executor.start()
+        if cash_carry:
+            await cash_carry.start()
+        if cfg.read_only:
+            # Feeds, tables, dashboard and admin API keep running until shutdown is requested
+            logger.info("read_only_start", extra={"venue": cfg.venue, "symbol": cfg.symbol})
+            await asyncio.Event().wait()
+            return
         logger.info("strategy_start", extra={"venue": cfg.venue, "mode": cfg.mode, "symbol": cfg.symbol})
         await strategy.start()
-    finally:
-        logger.info("strategy_stop", extra={"venue": cfg.venue})
-        if heartbeat:
-            await heartbeat.stop()
-        await lifecycle.stop()
+
+    summary = await shutdown.run_until_shutdown(session())
+    return summary.exit_code
 
 
 def parse_args() -> argparse.Namespace:
@@ -194,15 +893,20 @@ def parse_args() -> argparse.Namespace:
     parser.add_argument("--venue", required=True, help="venue identifier, e.g. backpack")
     parser.add_argument("--symbol", required=True, help="canonical symbol, e.g. SOL")
     parser.add_argument("--mode", default="market", choices=sorted(STRATEGY_REGISTRY.keys()))
-    parser.add_argument("--qty", type=float, required=True, help="order quantity in base units")
+    parser.add_argument("--qty", type=float, help="order quantity in base units; not needed with --read-only")
     parser.add_argument("--side", default="buy", choices=["buy", "sell"])
     parser.add_argument("--price-offset-ticks", type=int, default=0)
     parser.add_argument("--interval-secs", type=float, default=10.0)
     parser.add_argument("--timeout-secs", type=float, default=120.0)
     parser.add_argument("--reduce-only", type=int, default=0)
     parser.add_argument("--config", dest="config_path")
+    parser.add_argument("--env", dest="environment", help="environment profile, e.g. mainnet or testnet")
+    parser.add_argument("--read-only", action="store_true", help="stream market data without API keys; no trading")
     parser.add_argument("--log-level", default="INFO")
-    return parser.parse_args()
+    args = parser.parse_args()
+    if args.qty is None and not args.read_only:
+        parser.error("--qty is required unless --read-only is set")
+    return args
 
 
 def main() -> None:
@@ -210,7 +914,7 @@ def main() -> None:
     cfg = load_config(
         venue=args.venue,
         symbol=args.symbol,
-        qty=args.qty,
+        qty=args.qty or 0.0,
         side=args.side,
         mode=args.mode,
         price_offset_ticks=args.price_offset_ticks,
@@ -218,8 +922,10 @@ def main() -> None:
         timeout_secs=args.timeout_secs,
         reduce_only=args.reduce_only,
         config_path=args.config_path,
+        environment=args.environment,
+        read_only=args.read_only,
     )
-    asyncio.run(run(cfg, args.log_level))
+    sys.exit(asyncio.run(run(cfg, args.log_level)))
 
 
 if __name__ == "__main__":
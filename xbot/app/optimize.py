@@ -0,0 +1,146 @@
+from __future__ import annotations
+
+import argparse
+import importlib
+import sys
+from datetime import date, datetime, timezone
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+from xbot.analytics.montecarlo import MonteCarloConfig, simulate
+from xbot.analytics.optimizer import (
+    Dimension,
+    Evaluator,
+    Window,
+    export_results,
+    grid,
+    random_search,
+    rank,
+    render_results,
+    render_walk_forward,
+    sweep,
+    walk_forward,
+    walk_forward_windows,
+)
+from .config import _read_payload
+
+
+def load_evaluator(spec: str) -> Evaluator:
+    """`package.module:function` -> the evaluator it names."""
+    module_name, _, attr = spec.partition(":")
+    if not module_name or not attr:
+        raise ValueError(f"evaluator must look like package.module:function, got {spec!r}")
+    return getattr(importlib.import_module(module_name), attr)
+
+
+def parameter_sets(space: Dict[str, Any]) -> List[Dict[str, Any]]:
+    params = space.get("params") or {}
+    if not params:
+        raise ValueError("the space file needs a params section")
+    method = str(space.get("method", "grid")).lower()
+    if method == "grid":
+        return grid({key: list(values) for key, values in params.items()})
+    if method == "random":
+        # {min, max} is a uniform range; a list is a set of choices
+        dimensions: Dict[str, Dimension] = {
+            key: (value["min"], value["max"]) if isinstance(value, dict) else list(value) for key, value in params.items()
+        }
+        return random_search(dimensions, int(space.get("samples", 100)), seed=space.get("seed"))
+    raise ValueError(f"unknown search method {method!r}; use grid or random")
+
+
+def _epoch(value: Any) -> float:
+    """Epoch seconds from a number, an ISO date/timestamp string or a YAML date; naive values are UTC."""
+    if isinstance(value, (int, float)):
+        return float(value)
+    if isinstance(value, date) and not isinstance(value, datetime):
+        value = datetime(value.year, value.month, value.day)
+    parsed = value if isinstance(value, datetime) else datetime.fromisoformat(str(value))
+    if parsed.tzinfo is None:
+        parsed = parsed.replace(tzinfo=timezone.utc)
+    return parsed.timestamp()
+
+
+def windows(spec: Dict[str, Any]) -> List[Tuple[Window, Window]]:
+    day = 86400.0
+    step_days = spec.get("step_days")
+    return walk_forward_windows(
+        _epoch(spec["start"]),
+        _epoch(spec["end"]),
+        train_secs=float(spec["train_days"]) * day,
+        test_secs=float(spec["test_days"]) * day,
+        step_secs=None if step_days is None else float(step_days) * day,
+        anchored=bool(spec.get("anchored", False)),
+    )
+
+
+def monte_carlo_config(spec: Dict[str, Any]) -> MonteCarloConfig:
+    return MonteCarloConfig(
+        simulations=int(spec.get("simulations", 1000)),
+        method=str(spec.get("method", "bootstrap")).lower(),
+        slippage_bps=float(spec.get("slippage_bps", 0.0)),
+        fill_noise_bps=float(spec.get("fill_noise_bps", 0.0)),
+        miss_rate=float(spec.get("miss_rate", 0.0)),
+        confidence=float(spec.get("confidence", 0.90)),
+        seed=spec.get("seed"),
+    )
+
+
+def _robustness(label: str, series: List[Any], notionals: Optional[List[Any]], config: MonteCarloConfig) -> str:
+    if not series:
+        return f"{label}: no pnl series to resample"
+    return f"{label}: {simulate(series, notionals=notionals, config=config).format()}"
+
+
+def run(args: argparse.Namespace) -> int:
+    evaluate = load_evaluator(args.evaluator)
+    space = _read_payload(args.space)
+    param_sets = parameter_sets(space)
+    mc = monte_carlo_config(space["monte_carlo"]) if space.get("monte_carlo") else None
+    if space.get("walk_forward"):
+        folds = windows(space["walk_forward"])
+        if not folds:
+            raise ValueError("walk_forward range is shorter than one train + test window")
+        print(f"walk-forward: {len(folds)} folds x {len(param_sets)} parameter sets", file=sys.stderr)
+        report = walk_forward(evaluate, param_sets, folds, by=args.rank_by, workers=args.workers)
+        print(render_walk_forward(report))
+        if mc is not None:
+            print(_robustness("out-of-sample", *report.oos_series, mc))
+        if args.out:
+            out = Path(args.out)
+            out.parent.mkdir(parents=True, exist_ok=True)
+            export_results([fold.out_of_sample for fold in report.folds], out)
+        return 0 if any(fold.out_of_sample.ok for fold in report.folds) else 1
+    print(f"running {len(param_sets)} parameter sets", file=sys.stderr)
+    results = rank(sweep(evaluate, param_sets, workers=args.workers), by=args.rank_by)
+    print(render_results(results, top=args.top))
+    if mc is not None:
+        shown = [result for result in (results[: args.top] if args.top else results) if result.ok]
+        for index, result in enumerate(shown, 1):
+            print(_robustness(f"#{index}", result.pnl_series, result.notionals, mc))
+    if args.out:
+        out = Path(args.out)
+        out.parent.mkdir(parents=True, exist_ok=True)
+        export_results(results, out)
+    return 0 if any(result.ok for result in results) else 1
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(description="sweep strategy parameters through a backtest evaluator")
+    parser.add_argument("--evaluator", required=True, help="package.module:function running one backtest")
+    parser.add_argument(
+        "--space", required=True, help="YAML/JSON parameter space (method, params, samples, seed, walk_forward, monte_carlo)"
+    )
+    parser.add_argument("--workers", type=int, help="parallel processes, default one per CPU")
+    parser.add_argument("--rank-by", default="pnl", choices=["pnl", "max_drawdown", "turnover", "trades"])
+    parser.add_argument("--top", type=int, default=20, help="rows to print")
+    parser.add_argument("--out", help="write every result (walk-forward: each fold's test run) to this CSV file")
+    return parser.parse_args()
+
+
+def main() -> None:
+    sys.exit(run(parse_args()))
+
+
+if __name__ == "__main__":
+    main()
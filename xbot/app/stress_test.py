@@ -0,0 +1,104 @@
+from __future__ import annotations
+
+import argparse
+import asyncio
+import json
+import sys
+from decimal import Decimal
+from typing import Any, Dict, List
+
+from xbot.analytics.stress import Scenario, StressConfig, StressRunner, builtin_scenarios, scenario_from_dict
+from xbot.execution.risk_service import RiskLimits
+from xbot.utils.logging import setup_logging
+from .config import _read_payload
+
+
+def stress_config(stack: Dict[str, Any]) -> StressConfig:
+    """The `stack` section of a scenario file over the StressConfig defaults."""
+    base = StressConfig()
+    drawdown = stack.get("max_drawdown_pct", base.risk_limits.max_drawdown_pct)
+
+    def dec(key: str, default: Decimal) -> Decimal:
+        return Decimal(str(stack[key])) if stack.get(key) is not None else default
+
+    return StressConfig(
+        symbol=str(stack.get("symbol", base.symbol)).upper(),
+        mode=str(stack.get("mode", base.mode)),
+        side=str(stack.get("side", base.side)).lower(),
+        qty=dec("qty", base.qty),
+        price=dec("price", base.price),
+        collateral=dec("collateral", base.collateral),
+        spread_bps=dec("spread_bps", base.spread_bps),
+        funding_rate=dec("funding_rate", base.funding_rate),
+        funding_every_steps=int(stack.get("funding_every_steps", base.funding_every_steps)),
+        maintenance_margin=dec("maintenance_margin", base.maintenance_margin),
+        initial_margin=dec("initial_margin", base.initial_margin),
+        step_secs=float(stack.get("step_secs", base.step_secs)),
+        stale_after_secs=float(stack.get("stale_after_secs", base.stale_after_secs)),
+        risk_limits=RiskLimits(
+            max_position=dec("max_position", Decimal(0)) or None,
+            max_notional=dec("max_notional", Decimal(0)) or None,
+            max_drawdown_pct=Decimal(str(drawdown)) if drawdown is not None else None,
+        ),
+    )
+
+
+async def run(args: argparse.Namespace) -> int:
+    payload = _read_payload(args.scenarios) if args.scenarios else {}
+    config = stress_config(payload.get("stack") or {})
+    scenarios: List[Scenario] = (
+        [scenario_from_dict(raw) for raw in payload["scenarios"]]
+        if payload.get("scenarios")
+        else builtin_scenarios(config.side)
+    )
+    if args.only:
+        wanted = set(args.only)
+        scenarios = [scenario for scenario in scenarios if scenario.name in wanted]
+        if not scenarios:
+            raise ValueError(f"no scenario named {', '.join(sorted(wanted))}")
+    results = await StressRunner(config).run_all(scenarios)
+    if args.json:
+        print(
+            json.dumps(
+                [
+                    {
+                        "scenario": result.scenario,
+                        "pnl": str(result.pnl),
+                        "min_equity": str(result.min_equity),
+                        "max_drawdown": str(result.max_drawdown),
+                        "max_margin_usage": str(result.max_margin_usage),
+                        "funding_paid": str(result.funding_paid),
+                        "fired": result.fired,
+                        "unreachable_steps": result.unreachable_steps,
+                        "alerts": result.alerts,
+                        "passed": result.passed,
+                        "failures": result.failures,
+                    }
+                    for result in results
+                ],
+                indent=2,
+            )
+        )
+    else:
+        for result in results:
+            print(result.format())
+    return 0 if all(result.passed for result in results) else 1
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(description="replay synthetic shocks against the risk stack on a simulated venue")
+    parser.add_argument("--scenarios", help="YAML/JSON with optional stack and scenarios sections; builtins otherwise")
+    parser.add_argument("--only", nargs="+", help="run just these scenario names")
+    parser.add_argument("--json", action="store_true", help="print results as JSON")
+    parser.add_argument("--log-level", default="WARNING")
+    return parser.parse_args()
+
+
+def main() -> None:
+    args = parse_args()
+    setup_logging(args.log_level)
+    sys.exit(asyncio.run(run(args)))
+
+
+if __name__ == "__main__":
+    main()
@@ -3,68 +3,28 @@ from __future__ import annotations
 import argparse
 import asyncio
 from pathlib import Path
-from typing import Tuple
 
+from xbot.connector.backpack import BackpackConnector
 from xbot.core.cache import MarketCache
-from xbot.core.clock import Clock
-from xbot.core.eventbus import EventBus
-from sdk.backpack.auth import load_keys, ws_signature_tuple
-from sdk.backpack.ws import connect_and_subscribe
 
 
 async def run(symbol: str, key_file: Path, seconds: int = 20) -> None:
+    # One Backpack module: the WS client shares the connector's key file and config
+    connector = BackpackConnector(key_path=key_file)
     cache = MarketCache()
-    bus = EventBus()
-    keys = load_keys(key_file)
-    sig: Tuple[str, str, str, str] = ws_signature_tuple(keys)
-    print("[Connector] Initialized: Backpack (WS pending)")
-
-    async def ws_task():
-        async for msg in connect_and_subscribe(
-            public_streams=[f"depth.{symbol}"],
-            private_streams=["account.orderUpdate", "account.positionUpdate"],
-            signature=sig,
-        ):
-            stream = msg.get("stream")
-            data = msg.get("data")
-            if not stream or data is None:
-                continue
-            if stream.startswith("depth."):
-                bid = data.get("b")
-                ask = data.get("a")
-                top_b = float(bid[0][0]) if bid else None
-                top_a = float(ask[0][0]) if ask else None
-                await cache.set_top(symbol, top_b, top_a)
-            elif stream == "account.positionUpdate":
-                q = float(data.get("q") or data.get("quantity") or 0.0)
-                await cache.set_position(symbol, q)
-                bus.emit("position_update", {"symbol": symbol, "position": q})
-            elif stream == "account.orderUpdate":
-                bus.emit("order_update", data)
-
-    clock = Clock(interval=1.0)
-
-    class Printer:
-        def __init__(self) -> None:
-            self.t = 0
-
-        async def on_tick(self, ts: float) -> None:
-            self.t += 1
-            if self.t == 1:
-                print("[Connector] WS connected ok")
-                print("[Connector] Cache sync complete (orderbook/trades/positions)")
-
-    clock.add_iterator(Printer())
-
-    task = asyncio.create_task(ws_task())
+    client = connector.ws_client(symbols=[symbol], cache=cache)
+    print(f"[Connector] Initialized: Backpack ({'signed' if connector.credentials() else 'public only'})")
+    await client.start()
     try:
-        await clock.start(max_ticks=seconds)
+        for _ in range(seconds):
+            await asyncio.sleep(1.0)
+            top = cache.orderbooks.get(symbol)
+            if top is not None:
+                print(f"[Connector] {symbol} bid={top[0]} ask={top[1]} feed={cache.connection_state('market')}")
+        positions = await cache.snapshot_positions()
+        print(f"[Connector] Cache sync complete (positions={positions})")
     finally:
-        task.cancel()
-        try:
-            await task
-        except BaseException:
-            pass
+        await client.stop()
 
 
 def main() -> None:
@@ -78,4 +38,3 @@ def main() -> None:
 
 if __name__ == "__main__":
     main()
-
@@ -1,11 +1,42 @@
 from __future__ import annotations
 
+import asyncio
 import sys
+from dataclasses import dataclass, field
 from decimal import Decimal
 from pathlib import Path
-from typing import Any, Dict, List, Optional, Tuple
+from typing import TYPE_CHECKING, Any, Dict, List, Optional, Tuple
 
-from .base import BaseConnector
+from xbot.core.cache import MarketCache
+from xbot.core.metrics import METRICS
+from xbot.core.orderbook import OrderBook, levels_from_pairs
+from xbot.core.symbology import FUTURE, PERP, SPOT, SYMBOLOGY, Instrument
+from xbot.core.time_sync import clock_offset
+from xbot.utils.logging import get_logger
+from xbot.utils.secret import Secret, register_secret
+
+from .base import BaseConnector, ConnectorConfig
+from .borrow_lend import BORROW, LEND, BorrowLendMarket, BorrowLendPosition, parse_market, parse_position
+from .history import (
+    READ,
+    TRADE,
+    WITHDRAW,
+    CurrentFunding,
+    FundingRate,
+    HistoricalOrder,
+    HistoryPage,
+    KeyPermissions,
+    OrderHistory,
+    Ticker,
+    VenueStatus,
+    decimal_or_none,
+    paginate,
+    timestamp_ms,
+)
+from .signer import LOCAL, LocalSigner, Signer, remote_signer
+
+if TYPE_CHECKING:
+    from .backpack_ws import BackpackWsClient
 
 # Ensure vendored SDK (sdk/bpx-py) is importable without installation
 _repo_root = Path(__file__).resolve().parents[2]
@@ -16,7 +47,9 @@ if str(_sdk_path) not in sys.path:
 try:
     from bpx.async_.public import Public  # type: ignore
     from bpx.async_.account import Account  # type: ignore
+    from bpx.http_client.async_http_client import AsyncHttpClient  # type: ignore
     from bpx.constants.enums import OrderTypeEnum, TimeInForceEnum
+    from bpx import signing  # type: ignore
 except Exception as exc:  # pragma: no cover
     raise ImportError(
         "Backpack SDK not found. Ensure sdk/bpx-py is present."
@@ -34,51 +67,265 @@ def _format_int(value: int, decimals: int) -> str:
     return str(Decimal(value) / scale)
 
 
+class CredentialsError(ValueError):
+    """A key file that exists but cannot be used; the message names the file, never the key."""
+
+
+@dataclass(slots=True, frozen=True)
+class BackpackCredentials:
+    api_key: str  # ED25519 public key, base64; identifies the account
+    api_secret: Secret  # ED25519 seed, base64; redacted from repr and logs
+    signing_key: Any = field(default=None, repr=False, compare=False)  # parsed once from api_secret
+
+
+def load_credentials(path: Path) -> Optional[BackpackCredentials]:
+    """`api key:` / `api secret:` lines of a Backpack key file; None when the file or either key is missing.
+
+    Shared by the REST connector and the WebSocket client, so both sign with
+    the same keys from the same file. Keys are validated here, once: an
+    unreadable file, a malformed key or an API key that is not the secret's
+    public key raises `CredentialsError`, so a bad key fails at startup
+    instead of on the first signed request.
+    """
+    if not path.exists():
+        return None
+    try:
+        text = path.read_text(encoding="utf-8")
+    except (OSError, UnicodeDecodeError) as exc:
+        raise CredentialsError(f"cannot read key file {path}: {exc}") from exc
+    lines = dict(line.split(":", 1) for line in text.splitlines() if ":" in line)
+    api_key = (lines.get("api key") or lines.get("api_key") or lines.get("apiKey") or "").strip()
+    api_secret = (lines.get("api secret") or lines.get("api_secret") or lines.get("apiSecret") or "").strip()
+    if not api_key or not api_secret:
+        return None
+    secret = Secret(api_secret)
+    del api_secret, lines, text
+    try:
+        signing_key = signing.validate_keypair(api_key, secret.reveal())
+    except signing.InvalidKeyError as exc:
+        raise CredentialsError(f"invalid key in {path}: {exc}") from None
+    return BackpackCredentials(api_key=api_key, api_secret=secret, signing_key=signing_key)
+
+
+def load_signer(path: Path, config: ConnectorConfig) -> Optional[Signer]:
+    """The configured signer: an external one, else a `LocalSigner` on the key file (None without keys)."""
+    if config.signer is not None and config.signer.kind != LOCAL:
+        return remote_signer(config.signer)
+    credentials = load_credentials(path)
+    if credentials is None:
+        return None
+    return LocalSigner(credentials.api_key, credentials.signing_key)
+
+
+async def subscribe_signature(signer: Signer, *, timestamp_ms: int, window_ms: int) -> List[str]:
+    """`signature` field of a private WS subscribe: [api key, signature, timestamp, window]."""
+    message = signing.signing_string("subscribe", None, timestamp_ms, window_ms)
+    return [signer.public_key, await signer.sign(message), str(timestamp_ms), str(window_ms)]
+
+
+class DeferredSignature(str):
+    """Stand-in `X-Signature` the SDK's synchronous signing hook returns for `message`."""
+
+    message: str
+
+    def __new__(cls, message: str) -> "DeferredSignature":
+        placeholder = super().__new__(cls, "")
+        placeholder.message = message
+        return placeholder
+
+
+class SigningHttpClient(AsyncHttpClient):
+    """Fills in deferred `X-Signature` headers with an async `Signer` right before each request.
+
+    The SDK builds and signs headers synchronously; with `Account.signer` set to
+    `DeferredSignature` it only records the signing string, so a remote signer
+    is awaited here instead of blocking the event loop.
+    """
+
+    def __init__(self, signer: Signer, *, timeout: float) -> None:
+        super().__init__(timeout=timeout)
+        self._signer = signer
+
+    async def _signed(self, headers: Optional[Dict[str, Any]]) -> Optional[Dict[str, Any]]:
+        signature = (headers or {}).get("X-Signature")
+        if isinstance(signature, DeferredSignature) and headers is not None:
+            headers = {**headers, "X-Signature": await self._signer.sign(signature.message)}
+        return headers
+
+    async def get(self, url, headers=None, params=None, timeout: Optional[float] = None):  # type: ignore[no-untyped-def]
+        return await super().get(url, headers=await self._signed(headers), params=params, timeout=timeout)
+
+    async def post(self, url, headers=None, data=None, timeout: Optional[float] = None):  # type: ignore[no-untyped-def]
+        return await super().post(url, headers=await self._signed(headers), data=data, timeout=timeout)
+
+    async def delete(self, url, headers=None, data=None, timeout: Optional[float] = None):  # type: ignore[no-untyped-def]
+        return await super().delete(url, headers=await self._signed(headers), data=data, timeout=timeout)
+
+    async def patch(self, url, headers=None, data=None, timeout: Optional[float] = None):  # type: ignore[no-untyped-def]
+        return await super().patch(url, headers=await self._signed(headers), data=data, timeout=timeout)
+
+
+# Error codes / message fragments that mean the key itself was refused, not the request
+_DENIED_CODES = frozenset({"UNAUTHORIZED", "FORBIDDEN", "INVALID_SIGNATURE", "ACCESS_DENIED"})
+_DENIED_MARKERS = ("unauthorized", "forbidden", "permission", "not allowed", "signature", "api key")
+
+
+def _denied(resp: Any) -> Optional[str]:
+    """The venue error when a signed call was refused for the key's sake, else None."""
+    if not isinstance(resp, dict) or not ("code" in resp or "message" in resp):
+        return None
+    code = str(resp.get("code") or "").upper()
+    message = str(resp.get("message") or "")
+    if code in _DENIED_CODES or any(marker in message.lower() for marker in _DENIED_MARKERS):
+        return f"{code or 'error'}: {message}".strip()
+    return None
+
+
+def _ambiguous(exc: BaseException) -> bool:
+    """Errors after which a POST may or may not have reached the venue."""
+    import aiohttp
+
+    return isinstance(exc, (asyncio.TimeoutError, aiohttp.ClientConnectionError, aiohttp.ClientPayloadError))
+
+
+def _rows(resp: Any, what: str) -> List[Dict[str, Any]]:
+    """Normalize a history/list response; error payloads raise instead of reading as empty."""
+    if isinstance(resp, dict) and "data" in resp:
+        resp = resp["data"]
+    if isinstance(resp, list):
+        return [row for row in resp if isinstance(row, dict)]
+    if isinstance(resp, dict) and ("code" in resp or "message" in resp):
+        raise RuntimeError(f"{what} failed: {resp}")
+    return []
+
+
+# marketType -> (kind, settled in the base asset)
+_MARKET_KINDS = {"SPOT": (SPOT, False), "PERP": (PERP, False), "IPERP": (PERP, True), "DATED": (FUTURE, False)}
+
+
+def instrument_from_market(entry: Dict[str, Any], venue: str = "backpack") -> Instrument:
+    """Instrument from a `GET /api/v1/markets` entry, using its own base/quote/marketType fields."""
+    kind, inverse = _MARKET_KINDS.get(str(entry.get("marketType") or "SPOT").upper(), (SPOT, False))
+    base = str(entry.get("baseSymbol") or "").upper()
+    quote = str(entry.get("quoteSymbol") or "").upper()
+    settle = None if kind == SPOT else (base if inverse else quote)
+    return Instrument(venue=venue, native=str(entry["symbol"]), base=base, quote=quote, settle=settle, kind=kind)
+
+
 class BackpackConnector(BaseConnector):
     base_url = "https://api.backpack.exchange"
+    _HISTORY_PAGE_SIZE = 1000  # Backpack's maximum history page
+    _RECENT_ORDERS = 100  # history searched for a timed-out order, newest first
 
-    def __init__(self, *, key_path: Path) -> None:
-        super().__init__("backpack")
+    def __init__(self, *, key_path: Path, config: Optional[ConnectorConfig] = None) -> None:
+        self._config = config or ConnectorConfig()
+        super().__init__("backpack", timeout=self._config.request_timeout_secs, proxy=self._config.proxy)
+        if self._config.rest_url:
+            self.base_url = self._config.rest_url.rstrip("/")
         self._key_path = key_path
-        self._public = Public()
+        # Separate HTTP clients: the SDK default client is shared module-wide
+        self._public = Public(
+            proxy=self._config.proxy,
+            http_client=AsyncHttpClient(timeout=self._config.market_data_timeout_secs),
+        )
+        self._public.BASE_URL = self.base_url + "/"
         self._account: Optional[Account] = None
         self._markets: Dict[str, Dict[str, Any]] = {}
+        self._logger = get_logger(__name__)
 
-    def _load_keys(self) -> Tuple[str, str]:
-        # Keys not needed for public REST; keep for forward-compat
-        if not self._key_path.exists():
-            return "", ""
-        content = self._key_path.read_text(encoding="utf-8")
-        lines = dict(
-            line.split(":", 1)
-            for line in content.splitlines()
-            if ":" in line
-        )
-        api_key = lines.get("api key") or lines.get("api_key") or lines.get("apiKey") or ""
-        api_secret = lines.get("api secret") or lines.get("api_secret") or lines.get("apiSecret") or ""
-        return api_key.strip(), api_secret.strip()
+    @property
+    def key_path(self) -> Path:
+        return self._key_path
+
+    @property
+    def config(self) -> ConnectorConfig:
+        return self._config
+
+    def credentials(self) -> Optional[BackpackCredentials]:
+        # Keys are not needed for public REST; read per call so a rotated key file is picked up.
+        # Raises CredentialsError for a key file that exists but is unusable.
+        return load_credentials(self._key_path)
+
+    def signer(self) -> Optional[Signer]:
+        return load_signer(self._key_path, self._config)
+
+    def ws_client(self, *, cache: MarketCache, **kwargs: Any) -> "BackpackWsClient":
+        """WebSocket client on this connector's key file and config (URLs, proxy, signature window)."""
+        from .backpack_ws import BackpackWsClient
+
+        return BackpackWsClient(key_file=self._key_path, cache=cache, config=self._config, **kwargs)
 
     async def start(self) -> None:
-        # Initialize account client if keys present
-        pub, sec = self._load_keys()
-        if pub and sec:
-            self._account = Account(public_key=pub, secret_key=sec)
+        # Initialize account client if keys (or an external signer) are present
+        signer = self.signer()
+        if signer is not None:
+            self._account = Account(
+                public_key=signer.public_key,
+                secret_key=None,
+                window=self._config.window_ms,
+                proxy=self._config.proxy,
+                http_client=SigningHttpClient(signer, timeout=self._config.request_timeout_secs),
+            )
+            self._account.BPX_API_URL = self.base_url + "/"
+            self._account.timestamp_provider = clock_offset(self.venue).timestamp_ms
+            self._account.signer = DeferredSignature
+
+        await self.refresh_markets()
+        await super().start()
+
+    async def stop(self) -> None:
+        await super().stop()
+
+    async def get_server_time_ms(self) -> Optional[int]:
+        raw = await self._public.get_time()
+        if isinstance(raw, dict):
+            raw = raw.get("serverTime") or raw.get("time")
+        try:
+            return int(str(raw).strip().strip('"'))
+        except (TypeError, ValueError):
+            return None
 
+    async def get_exchange_status(self) -> VenueStatus:
+        raw = await self._public.get_status()
+        if not isinstance(raw, dict):
+            raw = {"status": raw}
+        status = str(raw.get("status") or "")
+        return VenueStatus(
+            venue=self.venue, maintenance=status.lower() == "maintenance", message=str(raw.get("message") or status)
+        )
+
+    async def refresh_markets(self) -> List[str]:
+        """Reload the market list, filters and order-book states; returns every visible symbol."""
         markets = await self._public.get_markets()
         # API may return dict or list; normalize to list of dicts
         if isinstance(markets, dict) and "data" in markets:
             markets = markets["data"]
         self._markets = {entry["symbol"]: entry for entry in markets if entry.get("visible", True)}
-        await super().start()
+        SYMBOLOGY.load(self.venue, (instrument_from_market(entry, self.venue) for entry in self._markets.values()))
+        return list(self._markets)
 
-    async def stop(self) -> None:
-        await super().stop()
+    def market_symbols(self) -> List[str]:
+        return list(self._markets)
+
+    def market_info(self, symbol: str) -> Optional[Dict[str, Any]]:
+        return self._markets.get(symbol)
 
     def _get_market_info(self, symbol: str) -> Dict[str, Any]:
         if symbol not in self._markets:
             raise ValueError(f"unknown market {symbol}")
         return self._markets[symbol]
 
+    def spot_market(self, asset: str, quote: str) -> Optional[str]:
+        """Spot market symbol trading `asset` against `quote`, if listed."""
+        for symbol, info in self._markets.items():
+            if (
+                str(info.get("marketType") or "SPOT").upper() == "SPOT"
+                and str(info.get("baseSymbol") or "").upper() == asset.upper()
+                and str(info.get("quoteSymbol") or "").upper() == quote.upper()
+            ):
+                return symbol
+        return None
+
     async def get_price_size_decimals(self, symbol: str) -> Tuple[int, int]:
         info = self._get_market_info(symbol)
         price_tick = info["filters"]["price"]["tickSize"]
@@ -108,6 +355,79 @@ class BackpackConnector(BaseConnector):
         ask = int(Decimal(str(asks_sorted[0][0])) * scale) if asks_sorted else None
         return bid, ask, scale
 
+    async def get_order_book(self, symbol: str) -> OrderBook:
+        book = await self._public.get_depth(symbol)
+        if not isinstance(book, dict) or "bids" not in book:
+            raise RuntimeError(f"depth failed: {book}")
+        return OrderBook.from_levels(symbol, levels_from_pairs(book.get("bids")), levels_from_pairs(book.get("asks")))
+
+    async def get_funding_rates(self, symbol: str, limit: int = 100) -> List[FundingRate]:
+        """Past funding rates for a perp market, oldest first."""
+        resp = await self._public.get_funding_interval_rates(symbol, limit=limit)
+        rates: List[FundingRate] = []
+        for row in _rows(resp, "funding rates"):
+            rate = decimal_or_none(row.get("fundingRate"))
+            ts_ms = timestamp_ms(row.get("intervalEndTimestamp"))
+            if rate is not None and ts_ms is not None:
+                rates.append(FundingRate(symbol=str(row.get("symbol") or symbol), rate=rate, ts_ms=ts_ms))
+        return sorted(rates, key=lambda r: r.ts_ms)
+
+    async def get_current_funding(self, symbol: Optional[str] = None) -> List[CurrentFunding]:
+        """Current-interval funding for every perp market (or one), from the mark price feed."""
+        resp = await self._public.get_all_mark_prices(symbol)
+        rows: List[CurrentFunding] = []
+        for row in _rows(resp, "mark prices"):
+            market = str(row.get("symbol") or "")
+            rate = decimal_or_none(row.get("fundingRate"))
+            if not market or rate is None:
+                continue
+            interval_ms = decimal_or_none((self._markets.get(market) or {}).get("fundingInterval"))
+            rows.append(
+                CurrentFunding(
+                    venue=self.venue,
+                    symbol=market,
+                    rate=rate,
+                    interval_hours=float(interval_ms) / 3_600_000 if interval_ms else 1.0,
+                    next_funding_ms=timestamp_ms(row.get("nextFundingTimestamp")),
+                )
+            )
+        return rows
+
+    async def get_tickers(self) -> List[Ticker]:
+        """24h statistics for every market, from `GET /api/v1/tickers`."""
+        resp = await self._public.get_tickers()
+        tickers: List[Ticker] = []
+        for row in _rows(resp, "tickers"):
+            market = str(row.get("symbol") or "")
+            if not market:
+                continue
+            trades = decimal_or_none(row.get("trades"))
+            tickers.append(
+                Ticker(
+                    venue=self.venue,
+                    symbol=market,
+                    last=decimal_or_none(row.get("lastPrice")),
+                    high=decimal_or_none(row.get("high")),
+                    low=decimal_or_none(row.get("low")),
+                    volume=decimal_or_none(row.get("volume")) or Decimal(0),
+                    quote_volume=decimal_or_none(row.get("quoteVolume")) or Decimal(0),
+                    price_change=decimal_or_none(row.get("priceChange")),
+                    price_change_pct=decimal_or_none(row.get("priceChangePercent")),
+                    trades=int(trades) if trades is not None else None,
+                )
+            )
+        return tickers
+
+    async def get_open_interest(self, symbol: Optional[str] = None) -> Dict[str, Decimal]:
+        """Open interest in base units per perp market (or one), from `GET /api/v1/openInterest`."""
+        resp = await self._public.get_open_interest(symbol or "")
+        found: Dict[str, Decimal] = {}
+        for row in _rows(resp, "open interest"):
+            market, value = str(row.get("symbol") or ""), decimal_or_none(row.get("openInterest"))
+            if market and value is not None:
+                found[market] = value
+        return found
+
     async def submit_limit_order(
         self,
         *,
@@ -125,7 +445,8 @@ class BackpackConnector(BaseConnector):
         qty = _format_int(base_amount, size_dec)
         px = _format_int(price, price_dec)
         side = "Ask" if is_ask else "Bid"
-        resp = await self._account.execute_order(
+        return await self._place(
+            "limit order",
             symbol=symbol,
             side=side,
             order_type=OrderTypeEnum.LIMIT,
@@ -136,9 +457,6 @@ class BackpackConnector(BaseConnector):
             post_only=post_only,
             reduce_only=bool(reduce_only),
         )
-        if isinstance(resp, dict) and resp.get("id"):
-            return str(resp["id"])
-        raise RuntimeError(f"limit order failed: {resp}")
 
     async def submit_market_order(
         self,
@@ -154,7 +472,8 @@ class BackpackConnector(BaseConnector):
         _, size_dec = await self.get_price_size_decimals(symbol)
         qty = _format_int(size_i, size_dec)
         side = "Ask" if is_ask else "Bid"
-        resp = await self._account.execute_order(
+        return await self._place(
+            "market order",
             symbol=symbol,
             side=side,
             order_type=OrderTypeEnum.MARKET,
@@ -162,9 +481,66 @@ class BackpackConnector(BaseConnector):
             client_id=client_order_index,
             reduce_only=bool(reduce_only),
         )
+
+    async def _place(self, what: str, **order: Any) -> str:
+        """POST the order; after a timeout, resend only once the venue confirms it has no order under the client id.
+
+        The client id is the idempotency key: a request that timed out may
+        still have been accepted, and resending it blindly would trade twice.
+        """
+        account = self._require_account("order submission")
+        symbol, client_id = order["symbol"], order["client_id"]
+        attempts, attempt = 1 + max(0, self._config.order_retries), 1
+        while True:
+            try:
+                resp = await account.execute_order(**order)
+            except Exception as exc:
+                if not _ambiguous(exc):
+                    raise
+                landed = await self._landed(symbol, client_id, exc)
+                if landed is not None:
+                    return landed
+                if attempt >= attempts:
+                    raise
+                attempt += 1
+                METRICS.inc("order_resubmits", venue=self.venue)
+                self._logger.info("order_resubmit", extra={"symbol": symbol, "client_id": client_id, "attempt": attempt})
+                continue
+            if isinstance(resp, dict) and resp.get("id"):
+                return str(resp["id"])
+            raise RuntimeError(f"{what} failed: {resp}")
+
+    async def _landed(self, symbol: str, client_id: int, error: Exception) -> Optional[str]:
+        """Exchange id of the order a timed-out POST placed; None when the venue has no record of it.
+
+        An order the venue cannot be asked about is treated as unknown: the
+        original error is raised rather than risking a second order.
+        """
+        await asyncio.sleep(self._config.order_lookup_delay_secs)
+        try:
+            found = await self.find_order(symbol, client_id)
+        except Exception as exc:
+            self._logger.warning(
+                "order_lookup_failed", extra={"symbol": symbol, "client_id": client_id, "error": str(exc)}
+            )
+            raise error
+        if found is None:
+            return None
+        METRICS.inc("orders_landed_after_timeout", venue=self.venue)
+        self._logger.warning(
+            "order_landed_after_timeout",
+            extra={"symbol": symbol, "client_id": client_id, "order_id": found.order_id, "status": found.status},
+        )
+        return found.order_id
+
+    async def find_order(self, symbol: str, client_order_index: int) -> Optional[HistoricalOrder]:
+        """The order placed under `client_order_index`, open or recently closed; None when the venue has none."""
+        account = self._require_account("order query")
+        resp = await account.get_open_order(symbol=symbol, client_id=client_order_index)
         if isinstance(resp, dict) and resp.get("id"):
-            return str(resp["id"])
-        raise RuntimeError(f"market order failed: {resp}")
+            return self._historical_order(resp)
+        history = await self.get_order_history(symbol, limit=self._RECENT_ORDERS)
+        return history.find(client_order_index=client_order_index)
 
     async def cancel_by_client_id(self, symbol: str, client_order_index: int) -> Dict[str, Any]:
         if not self._account:
@@ -205,5 +581,227 @@ class BackpackConnector(BaseConnector):
             "collateral": collateral,
         }
 
+    async def check_permissions(self, symbol: str) -> KeyPermissions:
+        """Probe what the API key may do, without side effects.
+
+        - read: the account query;
+        - trade: a post-only limit order for zero quantity on `symbol`, which
+          order validation always rejects; only an auth refusal counts as denied;
+        - withdraw: the withdrawal-limit query for the market's quote asset.
+        """
+        account = self._require_account("permission check")
+        info = self._get_market_info(symbol)
+        tick = str(info["filters"]["price"]["tickSize"])
+        quote = str(info.get("quoteSymbol") or "USDC")
+        probes = {
+            READ: lambda: account.get_account(),
+            TRADE: lambda: account.execute_order(
+                symbol=symbol,
+                side="Bid",
+                order_type=OrderTypeEnum.LIMIT,
+                time_in_force=TimeInForceEnum.GTC,
+                quantity="0",
+                price=tick,
+                post_only=True,
+            ),
+            WITHDRAW: lambda: account.get_max_withdrawal_quantity(symbol=quote),
+        }
+        permissions = KeyPermissions(venue=self.venue)
+        for scope, probe in probes.items():
+            resp = await probe()
+            if scope == TRADE and isinstance(resp, dict) and resp.get("id"):
+                # Cannot happen for a zero quantity; never leave an order behind if it does
+                await account.cancel_order(symbol=symbol, order_id=str(resp["id"]))
+            error = _denied(resp)
+            permissions.granted[scope] = error is None
+            if error is not None:
+                permissions.errors[scope] = error
+        return permissions
+
+    def account_id(self) -> Optional[str]:
+        """The API public key, which identifies the account (it is not a secret)."""
+        signer = self.signer()
+        return signer.public_key if signer is not None else None
+
+    async def get_open_orders(self, symbol: Optional[str] = None) -> List[Dict[str, Any]]:
+        account = self._require_account("open orders")
+        return _rows(await account.get_open_orders(symbol=symbol), "open orders")
+
+    def _require_account(self, action: str) -> Account:
+        if not self._account:
+            raise RuntimeError(f"account keys not configured for {action}")
+        return self._account
+
+    async def get_fill_history(
+        self,
+        symbol: Optional[str] = None,
+        *,
+        limit: int = 100,
+        offset: int = 0,
+        from_ms: Optional[int] = None,
+        to_ms: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
+        account = self._require_account("fill history")
+        resp = await account.get_fill_history(symbol=symbol, limit=limit, offset=offset, from_=from_ms, to=to_ms)
+        return _rows(resp, "fill history")
+
+    async def get_order_history(
+        self, symbol: Optional[str] = None, cursor: Optional[str] = None, limit: Optional[int] = None
+    ) -> OrderHistory:
+        """Closed and open orders; the cursor is the Backpack offset."""
+        account = self._require_account("order history")
+
+        async def fetch_page(page_cursor: Optional[str], size: int) -> HistoryPage:
+            offset = int(page_cursor or 0)
+            resp = await account.get_order_history(symbol=symbol, limit=size, offset=offset)
+            rows = [self._historical_order(row) for row in _rows(resp, "order history")]
+            return rows, (str(offset + len(rows)) if len(rows) >= size else None)
+
+        return await paginate(fetch_page, cursor=cursor, limit=limit, page_size=self._HISTORY_PAGE_SIZE)
+
+    def _historical_order(self, row: Dict[str, Any]) -> HistoricalOrder:
+        client_id = row.get("clientId")
+        return HistoricalOrder(
+            venue=self.venue,
+            symbol=str(row.get("symbol") or ""),
+            order_id=str(row.get("id") or ""),
+            client_order_index=int(client_id) if client_id is not None else None,
+            is_ask=str(row.get("side") or "").lower() == "ask",
+            order_type=str(row.get("orderType") or ""),
+            price=decimal_or_none(row.get("price")),
+            quantity=decimal_or_none(row.get("quantity")) or Decimal(0),
+            filled_quantity=decimal_or_none(row.get("executedQuantity")) or Decimal(0),
+            status=str(row.get("status") or ""),
+            created_ms=timestamp_ms(row.get("createdAt")),
+            raw=row,
+        )
+
+    async def get_funding_payments(
+        self,
+        symbol: Optional[str] = None,
+        *,
+        limit: int = 100,
+        offset: int = 0,
+    ) -> List[Dict[str, Any]]:
+        account = self._require_account("funding payments")
+        resp = await account.get_funding_payments(symbol=symbol, limit=limit, offset=offset)
+        return _rows(resp, "funding payments")
+
+    async def get_deposits(
+        self,
+        *,
+        limit: int = 100,
+        offset: int = 0,
+        from_ms: Optional[int] = None,
+        to_ms: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
+        account = self._require_account("deposit history")
+        resp = await account.get_deposits(limit=limit, offset=offset, from_=from_ms, to=to_ms)
+        return _rows(resp, "deposit history")
+
+    async def get_withdrawals(
+        self,
+        *,
+        limit: int = 100,
+        offset: int = 0,
+        from_ms: Optional[int] = None,
+        to_ms: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
+        account = self._require_account("withdrawal history")
+        resp = await account.get_withdrawals(limit=limit, offset=offset, from_=from_ms, to=to_ms)
+        return _rows(resp, "withdrawal history")
+
+    async def get_deposit_address(self, blockchain: str) -> str:
+        account = self._require_account("deposit address")
+        resp = await account.get_deposit_address(blockchain=blockchain)
+        address = resp.get("address") if isinstance(resp, dict) else None
+        if not address:
+            raise RuntimeError(f"deposit address {blockchain} failed: {resp}")
+        return str(address)
+
+    async def request_withdrawal(
+        self,
+        *,
+        symbol: str,
+        blockchain: str,
+        address: str,
+        quantity: Decimal,
+        client_id: Optional[int] = None,
+        two_factor_token: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """Submit a withdrawal as-is; callers go through `execution.withdrawals.WithdrawalGuard`."""
+        if quantity <= 0:
+            raise ValueError(f"withdrawal quantity must be positive, got {quantity}")
+        account = self._require_account("withdrawal")
+        resp = await account.withdrawal(
+            address=address,
+            blockchain=blockchain,
+            quantity=str(quantity),
+            symbol=symbol,
+            two_factor_token=two_factor_token,
+            client_id=client_id,
+        )
+        if not isinstance(resp, dict) or "code" in resp:
+            raise RuntimeError(f"withdrawal {symbol} failed: {resp}")
+        return resp
+
+    async def get_borrow_lend_markets(self) -> List[BorrowLendMarket]:
+        resp = await self._public.get_borrow_lend_markets()
+        return [parse_market(row) for row in _rows(resp, "borrow/lend markets")]
+
+    async def get_borrow_lend_positions(self) -> List[BorrowLendPosition]:
+        account = self._require_account("borrow/lend positions")
+        resp = await account.get_borrow_lend_positions()
+        return [parse_position(row) for row in _rows(resp, "borrow/lend positions")]
+
+    async def borrow(self, symbol: str, quantity: Decimal) -> Dict[str, Any]:
+        return await self._borrow_lend(BORROW, symbol, quantity)
+
+    async def repay(self, symbol: str, quantity: Decimal) -> Dict[str, Any]:
+        # Lending against a borrow position repays it
+        return await self._borrow_lend(LEND, symbol, quantity)
+
+    async def lend(self, symbol: str, quantity: Decimal) -> Dict[str, Any]:
+        return await self._borrow_lend(LEND, symbol, quantity)
+
+    async def redeem(self, symbol: str, quantity: Decimal) -> Dict[str, Any]:
+        # Borrowing against a lend position redeems it
+        return await self._borrow_lend(BORROW, symbol, quantity)
+
+    async def _borrow_lend(self, side: str, symbol: str, quantity: Decimal) -> Dict[str, Any]:
+        if quantity <= 0:
+            raise ValueError(f"borrow/lend quantity must be positive, got {quantity}")
+        account = self._require_account("borrow/lend")
+        resp = await account.execute_borrow_lend(quantity=str(quantity), side=side, symbol=symbol)
+        if isinstance(resp, dict) and "code" in resp:
+            raise RuntimeError(f"borrow/lend {side} {symbol} failed: {resp}")
+        return resp if isinstance(resp, dict) else {"raw": resp}
+
+    async def get_borrow_lend_history(
+        self,
+        symbol: Optional[str] = None,
+        *,
+        limit: int = 100,
+        offset: int = 0,
+    ) -> List[Dict[str, Any]]:
+        account = self._require_account("borrow/lend history")
+        resp = await account.get_borrow_history(symbol=symbol, limit=limit, offset=offset)
+        return _rows(resp, "borrow/lend history")
+
+    async def get_settlement_history(self, *, limit: int = 100, offset: int = 0) -> List[Dict[str, Any]]:
+        account = self._require_account("settlement history")
+        resp = await account.get_settlements_history(limit=limit, offset=offset)
+        return _rows(resp, "settlement history")
+
 
-__all__ = ["BackpackConnector"]
+__all__ = [
+    "BackpackConnector",
+    "BackpackCredentials",
+    "CredentialsError",
+    "instrument_from_market",
+    "load_credentials",
+    "load_signer",
+    "subscribe_signature",
+    "DeferredSignature",
+    "SigningHttpClient",
+]
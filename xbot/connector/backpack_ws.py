@@ -1,21 +1,76 @@
 from __future__ import annotations
 
 import asyncio
-import base64
 import contextlib
+import itertools
 import json
-import time
 from pathlib import Path
 from typing import Iterable, List, Optional, Callable, Awaitable, Dict, Any
 
 import websockets
-from cryptography.hazmat.primitives.asymmetric import ed25519
+
+try:
+    import orjson  # type: ignore
+
+    _loads: Callable[[Any], Any] = orjson.loads
+except Exception:  # pragma: no cover
+    _loads = json.loads
 
 from xbot.core.cache import MarketCache
+from xbot.connector.backpack import load_signer, subscribe_signature
+from xbot.connector.signer import Signer, SignerError
+from xbot.connector.base import ConnectorConfig
+from xbot.connector.proxy import ws_connect_kwargs
+from xbot.core.events import ConnectionState
+from xbot.core.feed_status import FeedStatus
+from xbot.core.latency import LATENCY, WS
+from xbot.core.metrics import METRICS
+from xbot.core.symbols import SYMBOLS
+from xbot.core.time_sync import clock_offset, get_timestamp
 from xbot.execution.order_service import OrderUpdatePayload
 from xbot.execution.models import OrderState
 from xbot.utils.logging import get_logger
 
+# Stream kinds, resolved once per stream name instead of prefix-matched per message
+_DEPTH, _TRADE, _POSITION, _ORDER = "depth", "trade", "position", "order"
+_ACCOUNT_ROUTES = {
+    "account.orderUpdate": (_ORDER, ""),
+    "account.positionUpdate": (_POSITION, ""),
+}
+_AUTH_MARKERS = ("signature", "auth", "api key", "apikey", "expired", "window")
+_MAX_PENDING_REQUESTS = 256
+
+
+class BackpackWsError(RuntimeError):
+    """An error frame the venue sent back for a (un)subscribe request."""
+
+    def __init__(self, code: Optional[int], message: str, *, method: str = "", streams: Iterable[str] = ()) -> None:
+        self.code = code
+        self.message = message
+        self.method = method
+        self.streams = list(streams)
+        super().__init__(f"backpack ws {method or 'request'} failed ({code}): {message}")
+
+    @property
+    def auth(self) -> bool:
+        """Rejected signature or keys, or a rejected private subscribe: no private events until fixed."""
+        text = self.message.lower()
+        return any(marker in text for marker in _AUTH_MARKERS) or any(s.startswith("account.") for s in self.streams)
+
+    @classmethod
+    def from_frame(cls, msg: Dict[str, Any], request: Optional[tuple[str, List[str]]] = None) -> "BackpackWsError":
+        error = msg.get("error")
+        if isinstance(error, dict):
+            code, message = error.get("code"), str(error.get("message") or error)
+        else:
+            code, message = None, str(error)
+        try:
+            code = None if code is None else int(code)
+        except (TypeError, ValueError):
+            code = None
+        method, streams = request or ("", [])
+        return cls(code, message, method=method, streams=streams)
+
 
 class BackpackWsClient:
     """Backpack WebSocket client implemented using websockets and ED25519 auth.
@@ -23,9 +78,18 @@ class BackpackWsClient:
     - Public streams: depth.<symbol>, trade.<symbol>
     - Private streams: account.orderUpdate, account.positionUpdate (if keys present)
     - Auto reconnect with backoff; graceful shutdown via stop()
+    - Symbols can be added/removed while connected (add_symbols/remove_symbols);
+      (un)subscribe requests carry at most STREAMS_PER_REQUEST streams each
+    - Every request carries an id; an error frame for it (invalid signature,
+      unknown stream) is raised to `on_error` as a BackpackWsError, and a
+      rejected private subscribe marks the account feed disconnected
+    - As one shard of a `WsConnectionPool`, `private=False` leaves the
+      account streams to the primary connection and `market_feed` names the
+      connection's market feed in the cache
     """
 
     WS_URL = "wss://ws.backpack.exchange"
+    STREAMS_PER_REQUEST = 200
 
     def __init__(
         self,
@@ -37,9 +101,18 @@ class BackpackWsClient:
         ping_interval: float = 55.0,
         ping_timeout: float = 10.0,
         on_order_update: Optional[Callable[[OrderUpdatePayload], Awaitable[None]]] = None,
+        stale_after_secs: Optional[float] = 15.0,
+        config: Optional[ConnectorConfig] = None,
+        on_error: Optional[Callable[[BackpackWsError], Awaitable[None]]] = None,
+        private: bool = True,
+        market_feed: str = "market",
     ) -> None:
-        self._symbols = list(symbols)
+        self._symbols = list(dict.fromkeys(symbols))
+        self._routes: Dict[str, tuple[str, str]] = {}
+        self._route(self._symbols)
+        self._ws: Any = None  # live connection, so symbol changes can be (un)subscribed in place
         self._key_file = key_file
+        self._signer: Optional[Signer] = None
         self._cache = cache
         self._reconnect_delay = reconnect_delay
         self._ping_interval = ping_interval
@@ -48,12 +121,74 @@ class BackpackWsClient:
         self._running = asyncio.Event()
         self._task: Optional[asyncio.Task] = None
         self._on_order_update = on_order_update
+        # Same config struct as the REST connector: URL override, proxy and signature window
+        config = config or ConnectorConfig()
+        self._config = config
+        self._ws_url = config.ws_url or self.WS_URL
+        self._proxy = config.proxy
+        self._window_ms = config.window_ms
+        self._private = private
+        self._market_status = FeedStatus(cache, feed=market_feed, stale_after_secs=stale_after_secs)
+        self._account_status = FeedStatus(cache, feed="account")
+        self._clock = clock_offset("backpack")
+        self._on_error = on_error
+        self._request_ids = itertools.count(1)
+        self._requests: Dict[int, tuple[str, List[str]]] = {}  # id -> (method, streams), until acked or rejected
+        self.last_error: Optional[BackpackWsError] = None
 
     async def start(self) -> None:
         if self._task is not None:
             return
+        if self._private:
+            # Validate the keys once, before connecting; a bad key file raises CredentialsError here
+            self._load_signer()
         self._running.set()
         self._task = asyncio.create_task(self._run(), name="backpack-ws")
+        await self._market_status.start()
+
+    @property
+    def symbols(self) -> List[str]:
+        return list(self._symbols)
+
+    @property
+    def stream_count(self) -> int:
+        """Streams this connection subscribes, counting the private ones it would sign in for."""
+        return len(self._public_streams(self._symbols)) + (len(_ACCOUNT_ROUTES) if self._private else 0)
+
+    @property
+    def market_state(self) -> Optional[ConnectionState]:
+        return self._market_status.state
+
+    @staticmethod
+    def _public_streams(symbols: Iterable[str]) -> List[str]:
+        symbols = list(symbols)
+        return [f"depth.{s}" for s in symbols] + [f"trade.{s}" for s in symbols]
+
+    def _route(self, symbols: Iterable[str]) -> None:
+        for symbol in symbols:
+            symbol = SYMBOLS.intern(symbol)
+            self._routes[f"depth.{symbol}"] = (_DEPTH, symbol)
+            self._routes[f"trade.{symbol}"] = (_TRADE, symbol)
+
+    async def add_symbols(self, symbols: Iterable[str]) -> List[str]:
+        """Start streaming more venue symbols; returns those that were new."""
+        added = [s for s in dict.fromkeys(symbols) if s not in self._symbols]
+        self._symbols.extend(added)
+        self._route(added)
+        if added and self._ws is not None:
+            await self._send("SUBSCRIBE", self._ws, self._public_streams(added))
+        return added
+
+    async def remove_symbols(self, symbols: Iterable[str]) -> List[str]:
+        """Stop streaming venue symbols; returns those that were streamed."""
+        removed = [s for s in dict.fromkeys(symbols) if s in self._symbols]
+        self._symbols = [s for s in self._symbols if s not in removed]
+        if removed and self._ws is not None:
+            await self._send("UNSUBSCRIBE", self._ws, self._public_streams(removed))
+        # Routes go last so messages still in flight before the unsubscribe land normally
+        for stream in self._public_streams(removed):
+            self._routes.pop(stream, None)
+        return removed
 
     async def stop(self) -> None:
         if self._task is None:
@@ -63,53 +198,79 @@ class BackpackWsClient:
         with contextlib.suppress(asyncio.CancelledError):
             await self._task
         self._task = None
+        await self._market_status.stop()
+        await self._account_status.stop()
 
-    def _load_keys(self) -> tuple[str | None, str | None]:
-        if not self._key_file.exists():
-            return None, None
-        try:
-            content = self._key_file.read_text(encoding="utf-8")
-            lines = dict(
-                line.split(":", 1)
-                for line in content.splitlines()
-                if ":" in line
-            )
-            pub = (lines.get("api key") or lines.get("api_key") or lines.get("apiKey") or "").strip()
-            sec = (lines.get("api secret") or lines.get("api_secret") or lines.get("apiSecret") or "").strip()
-            return (pub or None), (sec or None)
-        except Exception:
-            return None, None
+    def _load_signer(self) -> Optional[Signer]:
+        if self._signer is None:
+            self._signer = load_signer(self._key_file, self._config)
+        return self._signer
 
-    def _signature_tuple(self) -> Optional[list[str]]:
-        pub, sec = self._load_keys()
-        if not pub or not sec:
+    async def _signature_tuple(self) -> Optional[list[str]]:
+        signer = self._load_signer()
+        if signer is None:
             return None
-        ts = int(time.time() * 1000)
-        window = 5000
-        message = f"instruction=subscribe&timestamp={ts}&window={window}"
         try:
-            priv = ed25519.Ed25519PrivateKey.from_private_bytes(base64.b64decode(sec))
-            sig = priv.sign(message.encode("utf-8"))
-            sig_b64 = base64.b64encode(sig).decode()
-            return [pub, sig_b64, str(ts), str(window)]
-        except Exception as exc:
+            return await subscribe_signature(signer, timestamp_ms=get_timestamp("backpack"), window_ms=self._window_ms)
+        except SignerError as exc:
+            # A remote signer can be briefly unreachable; connect public-only and sign again on reconnect
             self._logger.info("ws_sign_error", extra={"venue": "backpack", "error": str(exc)})
             return None
 
+    async def check_auth(self, timeout_secs: float = 3.0) -> Optional[BackpackWsError]:
+        """Sign a private subscribe on a throwaway connection; returns the venue's rejection, if any.
+
+        A success ack is not guaranteed, so silence for `timeout_secs` counts as accepted.
+        """
+        streams = ["account.orderUpdate"]
+        signature = await self._signature_tuple()
+        if not signature:
+            return BackpackWsError(None, f"no usable api keys in {self._key_file}", method="SUBSCRIBE", streams=streams)
+        request_id = next(self._request_ids)
+        async with websockets.connect(
+            self._ws_url,
+            **await ws_connect_kwargs(self._ws_url, self._proxy),
+            ping_interval=None,
+        ) as ws:
+            payload = {"method": "SUBSCRIBE", "params": streams, "id": request_id, "signature": signature}
+            await ws.send(json.dumps(payload))
+            try:
+                async with asyncio.timeout(timeout_secs):
+                    async for raw in ws:
+                        msg = _loads(raw)
+                        if not isinstance(msg, dict) or msg.get("id") not in (request_id, None):
+                            continue
+                        if msg.get("error") is not None:
+                            return BackpackWsError.from_frame(msg, ("SUBSCRIBE", streams))
+                        if "result" in msg:
+                            return None
+            except TimeoutError:
+                pass
+        return None
+
     async def _subscribe(self, ws, streams: List[str], signature: Optional[list[str]] = None) -> None:
-        if not streams:
-            return
-        payload = {"method": "SUBSCRIBE", "params": streams}
-        if signature:
-            payload["signature"] = signature
-        await ws.send(json.dumps(payload))
+        await self._send("SUBSCRIBE", ws, streams, signature)
+
+    async def _send(self, method: str, ws, streams: List[str], signature: Optional[list[str]] = None) -> None:
+        for start in range(0, len(streams), self.STREAMS_PER_REQUEST):
+            chunk = streams[start : start + self.STREAMS_PER_REQUEST]
+            request_id = next(self._request_ids)
+            payload: Dict[str, Any] = {"method": method, "params": chunk, "id": request_id}
+            if signature:
+                payload["signature"] = signature
+            self._requests[request_id] = (method, chunk)
+            if len(self._requests) > _MAX_PENDING_REQUESTS:
+                # The venue does not always ack; forget the oldest instead of growing forever
+                self._requests.pop(next(iter(self._requests)))
+            await ws.send(json.dumps(payload))
 
     async def _run(self) -> None:
-        public_streams: List[str] = [f"depth.{s}" for s in self._symbols] + [f"trade.{s}" for s in self._symbols]
         private_streams: List[str] = ["account.orderUpdate", "account.positionUpdate"]
 
         while self._running.is_set():
-            signature = self._signature_tuple()
+            # Rebuilt per connection so symbols changed at runtime survive reconnects
+            public_streams = self._public_streams(self._symbols)
+            signature = await self._signature_tuple() if self._private else None
             has_private = bool(signature)
             try:
                 self._logger.info(
@@ -121,61 +282,120 @@ class BackpackWsClient:
                     },
                 )
                 async with websockets.connect(
-                    self.WS_URL,
+                    self._ws_url,
+                    **await ws_connect_kwargs(self._ws_url, self._proxy),
                     ping_interval=self._ping_interval,
                     ping_timeout=self._ping_timeout,
                     max_size=2 ** 22,
                 ) as ws:
+                    self._ws = ws
                     await self._subscribe(ws, public_streams)
                     if has_private:
                         await self._subscribe(ws, private_streams, signature=signature)
                     self._logger.info("ws_connected", extra={"venue": "backpack", "has_private": has_private})
+                    await self._market_status.connected()
+                    if has_private:
+                        await self._account_status.connected()
                     async for raw in ws:
-                        try:
-                            msg = json.loads(raw)
-                        except Exception:
-                            continue
-                        await self._handle_message(msg)
+                        await self._on_raw(raw)
+                self._ws = None
+                await self._feeds_down(reason="closed")
             except asyncio.CancelledError:
                 break
             except Exception as exc:
+                self._ws = None
                 self._logger.info("ws_error", extra={"venue": "backpack", "error": str(exc)})
+                await self._feeds_down(error=str(exc))
                 await asyncio.sleep(self._reconnect_delay)
 
+    async def _feeds_down(self, **info: object) -> None:
+        await self._market_status.disconnected(**info)
+        await self._account_status.disconnected(**info)
+
+    async def _on_raw(self, raw: Any) -> None:
+        try:
+            msg = _loads(raw)
+        except Exception:
+            return
+        await self._handle_message(msg)
+
     async def _handle_message(self, msg: dict) -> None:
         stream = msg.get("stream")
         data = msg.get("data")
         if not stream or data is None:
+            if "error" in msg or "result" in msg:
+                await self._on_response(msg)
             return
+        route = self._routes.get(stream) or _ACCOUNT_ROUTES.get(stream)
+        if route is None:
+            return
+        kind, symbol = route
+        if kind is _DEPTH or kind is _TRADE:
+            await self._market_status.on_message()
+        else:
+            await self._account_status.on_message()
         try:
-            if stream.startswith("depth."):
-                symbol = stream.split(".", 1)[1]
-                bid = data.get("b") or data.get("bids")
-                ask = data.get("a") or data.get("asks")
-                top_b = float(bid[0][0]) if bid else None
-                top_a = float(ask[0][0]) if ask else None
-                await self._cache.set_top(symbol, top_b, top_a)
-            elif stream.startswith("trade."):
-                symbol = stream.split(".", 1)[1]
-                trade = {
-                    "p": data.get("p") or data.get("price"),
-                    "q": data.get("q") or data.get("size"),
-                    "t": data.get("t") or data.get("ts"),
-                    "m": data.get("m") or data.get("is_maker"),
-                }
-                await self._cache.add_trade(symbol, trade)
-            elif stream.startswith("account.positionUpdate"):
-                symbol = data.get("s") or data.get("symbol")
+            event_us = data.get("E")
+            if event_us:
+                # Backpack event times are microseconds
+                LATENCY.record_event_time("backpack", WS, event_us / 1000, self._clock.timestamp_ms())
+            if kind is _DEPTH:
+                # Only the first level of each side is needed for the top of book
+                bid = data.get("b")
+                ask = data.get("a")
+                await self._cache.set_top(symbol, float(bid[0][0]) if bid else None, float(ask[0][0]) if ask else None)
+            elif kind is _TRADE:
+                await self._cache.add_trade(
+                    symbol,
+                    {
+                        "p": data.get("p") or data.get("price"),
+                        "q": data.get("q") or data.get("size"),
+                        "t": data.get("t") or data.get("ts"),
+                        # Buyer is the maker; keep an explicit False, it marks a buy aggressor
+                        "m": data["m"] if "m" in data else data.get("is_maker"),
+                    },
+                )
+            elif kind is _POSITION:
+                position_symbol = data.get("s") or data.get("symbol")
                 q = float(data.get("q") or data.get("quantity") or 0.0)
-                if symbol:
-                    await self._cache.set_position(symbol, q)
-            elif stream.startswith("account.orderUpdate"):
+                if position_symbol:
+                    await self._cache.set_position(position_symbol, q)
+            else:
                 self._logger.info("order_update", extra={"venue": "backpack", "data": data})
                 # Ingest into order service when client order id is present
                 await self._ingest_order_update(data)
         except Exception as exc:
             self._logger.info("ws_handle_error", extra={"venue": "backpack", "error": str(exc)})
 
+    async def _on_response(self, msg: Dict[str, Any]) -> None:
+        request_id = msg.get("id")
+        request = self._requests.pop(request_id, None) if isinstance(request_id, int) else None
+        if msg.get("error") is None:
+            if request is not None:
+                self._logger.debug("ws_request_ok", extra={"venue": "backpack", "method": request[0], "id": request_id})
+            return
+        error = BackpackWsError.from_frame(msg, request)
+        self.last_error = error
+        METRICS.inc("ws_request_errors", venue="backpack", code=str(error.code), auth=str(error.auth).lower())
+        self._logger.warning(
+            "ws_request_error",
+            extra={
+                "venue": "backpack",
+                "id": request_id,
+                "method": error.method,
+                "code": error.code,
+                "error": error.message,
+                "streams": error.streams,
+            },
+        )
+        if error.auth:
+            await self._account_status.disconnected(error=error.message, code=error.code, auth=True)
+        if self._on_error is not None:
+            try:
+                await self._on_error(error)
+            except Exception as exc:
+                self._logger.info("ws_error_callback_failed", extra={"venue": "backpack", "error": str(exc)})
+
     async def _ingest_order_update(self, data: Dict[str, Any]) -> None:
         if not self._on_order_update:
             return
@@ -224,4 +444,4 @@ class BackpackWsClient:
             self._logger.info("ws_ingest_error", extra={"venue": "backpack", "error": str(exc)})
 
 
-__all__ = ["BackpackWsClient"]
+__all__ = ["BackpackWsClient", "BackpackWsError"]
@@ -1,11 +1,27 @@
 from __future__ import annotations
 
 import abc
+from dataclasses import dataclass
 from typing import Any, Dict, List, Optional, Tuple
 
 import httpx
 
+from .history import OrderHistory
 from .interface import IConnector
+from .signer import SignerConfig
+
+
+@dataclass(slots=True)
+class ConnectorConfig:
+    window_ms: int = 5000  # signature validity window for venues that use one
+    request_timeout_secs: float = 10.0  # signed/trading REST calls
+    market_data_timeout_secs: float = 3.0  # public market-data REST calls
+    proxy: Optional[str] = None  # http://, https:// or socks5:// URL applied to REST and WS
+    rest_url: Optional[str] = None  # override the venue REST base URL (testnet/staging)
+    ws_url: Optional[str] = None  # override the venue WebSocket URL
+    signer: Optional[SignerConfig] = None  # where signatures come from; None signs with the key file
+    order_retries: int = 1  # resends of an order whose POST timed out and that the venue has no record of
+    order_lookup_delay_secs: float = 1.0  # wait before asking the venue whether a timed-out order landed
 
 
 class BaseConnector(IConnector, abc.ABC):
@@ -13,14 +29,15 @@ class BaseConnector(IConnector, abc.ABC):
 
     base_url: str
 
-    def __init__(self, venue: str, *, timeout: float = 10.0) -> None:
+    def __init__(self, venue: str, *, timeout: float = 10.0, proxy: Optional[str] = None) -> None:
         self.venue = venue
         self._timeout = timeout
+        self._proxy = proxy
         self._client: Optional[httpx.AsyncClient] = None
 
     async def start(self) -> None:
         if self._client is None:
-            self._client = httpx.AsyncClient(base_url=self.base_url, timeout=self._timeout)
+            self._client = httpx.AsyncClient(base_url=self.base_url, timeout=self._timeout, proxy=self._proxy)
 
     async def stop(self) -> None:
         if self._client is not None:
@@ -81,5 +98,14 @@ class BaseConnector(IConnector, abc.ABC):
     async def get_margin(self) -> Dict[str, Any]:  # pragma: no cover
         return {}
 
+    async def get_order_history(
+        self, symbol: Optional[str] = None, cursor: Optional[str] = None, limit: Optional[int] = None
+    ) -> OrderHistory:  # pragma: no cover - venue specific
+        raise NotImplementedError
+
+    async def get_server_time_ms(self) -> Optional[int]:
+        """Exchange clock in epoch milliseconds, or None when the venue has no time endpoint."""
+        return None
+
 
-__all__ = ["BaseConnector"]
+__all__ = ["BaseConnector", "ConnectorConfig"]
@@ -0,0 +1,77 @@
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Dict
+
+from .history import decimal_or_none
+
+BORROW = "Borrow"
+LEND = "Lend"
+
+
+@dataclass(slots=True)
+class BorrowLendMarket:
+    symbol: str  # asset, e.g. "USDC"
+    borrow_rate: Decimal  # annualized, 0.05 == 5% APR
+    lend_rate: Decimal  # annualized
+    utilization: Decimal
+    total_borrowed: Decimal
+    total_lent: Decimal
+    state: str = ""
+    raw: Dict[str, Any] = field(default_factory=dict)
+
+    @property
+    def open(self) -> bool:
+        return not self.state or self.state.lower() == "open"
+
+
+@dataclass(slots=True)
+class BorrowLendPosition:
+    symbol: str
+    net_quantity: Decimal  # > 0 lent, < 0 borrowed
+    net_exposure_notional: Decimal
+    cumulative_interest: Decimal
+    raw: Dict[str, Any] = field(default_factory=dict)
+
+    @property
+    def is_lending(self) -> bool:
+        return self.net_quantity > 0
+
+    @property
+    def is_borrowing(self) -> bool:
+        return self.net_quantity < 0
+
+
+def _dec(row: Dict[str, Any], *keys: str) -> Decimal:
+    for key in keys:
+        value = decimal_or_none(row.get(key))
+        if value is not None:
+            return value
+    return Decimal(0)
+
+
+def parse_market(row: Dict[str, Any]) -> BorrowLendMarket:
+    return BorrowLendMarket(
+        symbol=str(row.get("symbol") or ""),
+        borrow_rate=_dec(row, "borrowInterestRate"),
+        lend_rate=_dec(row, "lendInterestRate"),
+        utilization=_dec(row, "utilization"),
+        total_borrowed=_dec(row, "borrowedQuantity", "totalBorrowed"),
+        total_lent=_dec(row, "lentQuantity", "totalLent"),
+        state=str(row.get("state") or ""),
+        raw=row,
+    )
+
+
+def parse_position(row: Dict[str, Any]) -> BorrowLendPosition:
+    return BorrowLendPosition(
+        symbol=str(row.get("symbol") or ""),
+        net_quantity=_dec(row, "netQuantity"),
+        net_exposure_notional=_dec(row, "netExposureNotional"),
+        cumulative_interest=_dec(row, "cumulativeInterest"),
+        raw=row,
+    )
+
+
+__all__ = ["BorrowLendMarket", "BorrowLendPosition", "parse_market", "parse_position", "BORROW", "LEND"]
@@ -2,7 +2,9 @@ from __future__ import annotations
 
 import os
 from pathlib import Path
+from typing import Optional
 
+from .base import ConnectorConfig
 from .interface import IConnector
 
 
@@ -11,16 +13,16 @@ def _default_key_path(filename: str) -> Path:
     return base / filename
 
 
-def build_connector(venue: str) -> IConnector:
+def build_connector(venue: str, config: Optional[ConnectorConfig] = None) -> IConnector:
     normalized = venue.lower()
     if normalized == "backpack":
         key_file = Path(os.getenv("BACKPACK_KEY_FILE", _default_key_path("Backpack_key.txt")))
         from .backpack import BackpackConnector
-        return BackpackConnector(key_path=key_file)
+        return BackpackConnector(key_path=key_file, config=config)
     if normalized == "lighter":
         key_file = Path(os.getenv("LIGHTER_KEY_FILE", _default_key_path("Lighter_key.txt")))
         from .lighter import LighterConnector
-        return LighterConnector(key_path=key_file)
+        return LighterConnector(key_path=key_file, config=config)
     # Keep factory structure for parallelism; other venues can be added here.
     raise ValueError(f"unsupported venue {venue}")
 
@@ -0,0 +1,195 @@
+from __future__ import annotations
+
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from decimal import Decimal
+from typing import Any, Awaitable, Callable, Dict, Iterable, List, Optional, Tuple
+
+HistoryPage = Tuple[List["HistoricalOrder"], Optional[str]]
+FetchPage = Callable[[Optional[str], int], Awaitable[HistoryPage]]
+
+
+@dataclass(slots=True)
+class HistoricalOrder:
+    venue: str
+    symbol: str  # venue symbol
+    order_id: str
+    client_order_index: Optional[int]
+    is_ask: bool
+    order_type: str
+    price: Optional[Decimal]
+    quantity: Decimal
+    filled_quantity: Decimal
+    status: str  # venue status string, unnormalized
+    created_ms: Optional[int] = None
+    raw: Dict[str, Any] = field(default_factory=dict)
+
+
+@dataclass(slots=True)
+class OrderHistory:
+    orders: List[HistoricalOrder] = field(default_factory=list)
+    next_cursor: Optional[str] = None  # None once the history is exhausted
+
+    def find(self, *, client_order_index: Optional[int] = None, order_id: Optional[str] = None) -> Optional[HistoricalOrder]:
+        for entry in self.orders:
+            if order_id is not None and entry.order_id == order_id:
+                return entry
+            if client_order_index is not None and entry.client_order_index == client_order_index:
+                return entry
+        return None
+
+
+@dataclass(slots=True)
+class FundingRate:
+    symbol: str  # venue symbol
+    rate: Decimal  # per funding interval, 0.0001 == 1bp
+    ts_ms: int  # interval end
+
+
+@dataclass(slots=True)
+class CurrentFunding:
+    venue: str  # exchange the rate is quoted on (aggregators report several)
+    symbol: str  # venue symbol
+    rate: Decimal  # current interval
+    interval_hours: float
+    next_funding_ms: Optional[int] = None  # None when the venue does not publish it
+
+    def next_funding_at_ms(self, now: Optional[float] = None) -> int:
+        """Published next funding time, else the next interval boundary (UTC)."""
+        if self.next_funding_ms is not None:
+            return self.next_funding_ms
+        period = self.interval_hours * 3600
+        current = time.time() if now is None else now
+        return int((current // period + 1) * period * 1000)
+
+    def secs_to_next(self, now: Optional[float] = None) -> float:
+        current = time.time() if now is None else now
+        return max(0.0, self.next_funding_at_ms(current) / 1000 - current)
+
+
+@dataclass(slots=True)
+class Ticker:
+    """Rolling 24h statistics of one market."""
+
+    venue: str
+    symbol: str  # venue symbol
+    last: Optional[Decimal]
+    high: Optional[Decimal]
+    low: Optional[Decimal]
+    volume: Decimal  # base
+    quote_volume: Decimal
+    price_change: Optional[Decimal] = None
+    price_change_pct: Optional[Decimal] = None  # 0.01 == 1%
+    trades: Optional[int] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        def text(value: Optional[Decimal]) -> Optional[str]:
+            return str(value) if value is not None else None
+
+        return {
+            "venue": self.venue,
+            "symbol": self.symbol,
+            "last": text(self.last),
+            "high": text(self.high),
+            "low": text(self.low),
+            "volume": str(self.volume),
+            "quote_volume": str(self.quote_volume),
+            "price_change": text(self.price_change),
+            "price_change_pct": text(self.price_change_pct),
+            "trades": self.trades,
+        }
+
+
+async def paginate(
+    fetch_page: FetchPage,
+    *,
+    cursor: Optional[str] = None,
+    limit: Optional[int] = None,
+    page_size: int = 100,
+    max_pages: int = 1000,
+) -> OrderHistory:
+    """Follow venue cursors until the history is exhausted or `limit` orders are collected.
+
+    `fetch_page(cursor, size)` returns one page and the cursor of the next one
+    (None at the end). The last request is sized to what `limit` still allows, so
+    `next_cursor` resumes right after the last returned order.
+    """
+    history = OrderHistory(next_cursor=cursor)
+    for _ in range(max_pages):
+        size = page_size if limit is None else min(page_size, limit - len(history.orders))
+        if size <= 0:
+            break
+        rows, next_cursor = await fetch_page(history.next_cursor, size)
+        history.orders.extend(rows)
+        history.next_cursor = next_cursor
+        if next_cursor is None or not rows:
+            history.next_cursor = None
+            break
+    return history
+
+
+@dataclass(slots=True)
+class VenueStatus:
+    venue: str
+    maintenance: bool  # in maintenance now, or scheduled to start within the caller's lead time
+    message: str = ""
+    starts_ms: Optional[int] = None  # scheduled window, when announced
+    ends_ms: Optional[int] = None
+
+
+READ, TRADE, WITHDRAW = "read", "trade", "withdraw"
+
+
+@dataclass(slots=True)
+class KeyPermissions:
+    venue: str
+    granted: Dict[str, bool] = field(default_factory=dict)  # scope -> probe passed
+    errors: Dict[str, str] = field(default_factory=dict)  # scope -> venue error for denied scopes
+
+    def missing(self, required: Iterable[str]) -> List[str]:
+        return [scope for scope in required if not self.granted.get(scope, False)]
+
+
+def decimal_or_none(value: Any) -> Optional[Decimal]:
+    if value is None or value == "":
+        return None
+    try:
+        return Decimal(str(value))
+    except Exception:
+        return None
+
+
+def timestamp_ms(value: Any) -> Optional[int]:
+    """Epoch seconds/milliseconds or an ISO-8601 string, as epoch milliseconds."""
+    if value is None or value == "":
+        return None
+    try:
+        number = float(value)
+    except (TypeError, ValueError):
+        try:
+            parsed = datetime.fromisoformat(str(value).replace("Z", "+00:00"))
+        except ValueError:
+            return None
+        if parsed.tzinfo is None:
+            parsed = parsed.replace(tzinfo=timezone.utc)  # venues report UTC
+        return int(parsed.timestamp() * 1000)
+    return int(number if number > 1e12 else number * 1000)
+
+
+__all__ = [
+    "CurrentFunding",
+    "FundingRate",
+    "HistoricalOrder",
+    "KeyPermissions",
+    "OrderHistory",
+    "FetchPage",
+    "READ",
+    "TRADE",
+    "Ticker",
+    "VenueStatus",
+    "WITHDRAW",
+    "paginate",
+    "decimal_or_none",
+    "timestamp_ms",
+]
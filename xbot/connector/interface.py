@@ -1,6 +1,9 @@
 from __future__ import annotations
 
-from typing import Any, Dict, List, Optional, Protocol, Tuple
+from typing import TYPE_CHECKING, Any, Dict, List, Optional, Protocol, Tuple
+
+if TYPE_CHECKING:
+    from .history import OrderHistory
 
 
 class IConnector(Protocol):
@@ -62,5 +65,10 @@ class IConnector(Protocol):
     async def get_margin(self) -> Dict[str, Any]:
         """Return the current margin snapshot when supported by the venue."""
 
+    async def get_order_history(
+        self, symbol: Optional[str] = None, cursor: Optional[str] = None, limit: Optional[int] = None
+    ) -> "OrderHistory":
+        """Return past orders, closed ones included, following venue pagination from `cursor`."""
+
 
 __all__ = ["IConnector"]
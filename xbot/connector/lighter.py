@@ -2,13 +2,17 @@ from __future__ import annotations
 
 import sys
 import asyncio
-from dataclasses import dataclass
+from dataclasses import dataclass, replace
 from decimal import Decimal
 from pathlib import Path
 from typing import Any, Dict, List, Optional, Tuple
 
-from .base import BaseConnector
+from .base import BaseConnector, ConnectorConfig
+from .history import CurrentFunding, HistoricalOrder, HistoryPage, OrderHistory, decimal_or_none, paginate, timestamp_ms
+from xbot.core.orderbook import OrderBook, aggregate_orders
+from xbot.core.symbology import PERP, SYMBOLOGY, Instrument, parse_native
 from xbot.utils.logging import get_logger
+from xbot.utils.secret import register_secret
 
 
 # Prefer vendored SDK: sdk/lighter-python (fallback to sdk/lighter)
@@ -36,8 +40,11 @@ class _MarketInfo:
 class LighterConnector(BaseConnector):
     base_url = "https://mainnet.zklighter.elliot.ai"
 
-    def __init__(self, *, key_path: Path) -> None:
-        super().__init__("lighter")
+    def __init__(self, *, key_path: Path, config: Optional[ConnectorConfig] = None) -> None:
+        config = config or ConnectorConfig()
+        super().__init__("lighter", timeout=config.request_timeout_secs, proxy=config.proxy)
+        if config.rest_url:
+            self.base_url = config.rest_url.rstrip("/")
         self._key_path = key_path
         self._markets: Dict[str, _MarketInfo] = {}
         self._sdk_available = False
@@ -69,9 +76,13 @@ class LighterConnector(BaseConnector):
             import lighter  # type: ignore  # noqa: F401
             from lighter import ApiClient, Configuration  # type: ignore
 
-            self._api_client = ApiClient(configuration=Configuration(host=self.base_url))
+            configuration = Configuration(host=self.base_url)
+            if self._proxy:
+                configuration.proxy = self._proxy
+            self._api_client = ApiClient(configuration=configuration)
             order_api = lighter.OrderApi(self._api_client)  # type: ignore[attr-defined]
             ob = await order_api.order_books()
+            instruments: List[Instrument] = []
             for m in getattr(ob, "order_books", []):
                 symbol = getattr(m, "symbol", None)
                 if not symbol:
@@ -83,9 +94,14 @@ class LighterConnector(BaseConnector):
                     size_decimals=getattr(m, "supported_size_decimals", 0),
                 )
                 self._markets[symbol] = mi
-                base = symbol.split("_")[0].split("-")[0]
+                # Lighter lists USDC-margined perps by base asset alone
+                instrument = parse_native(symbol, venue=self.venue)
+                base = instrument.base
+                quote = instrument.quote or "USDC"
+                instruments.append(replace(instrument, quote=quote, settle=instrument.settle or quote, kind=PERP))
                 if base and base != symbol and base not in self._markets:
                     self._markets[base] = mi
+            SYMBOLOGY.load(self.venue, instruments)
             self._sdk_available = True
             self._logger.info(
                 "lighter_markets_built",
@@ -138,6 +154,10 @@ class LighterConnector(BaseConnector):
         except Exception:
             return None
 
+    def account_id(self) -> Optional[str]:
+        index = self.get_account_index()
+        return None if index is None else str(index)
+
     async def get_price_size_decimals(self, symbol: str) -> Tuple[int, int]:
         info = self._get_market_info(symbol)
         return info.price_decimals, info.size_decimals
@@ -170,6 +190,18 @@ class LighterConnector(BaseConnector):
             pass
         return best_bid, best_ask, price_scale
 
+    async def get_order_book(self, symbol: str, depth: int = 100) -> OrderBook:
+        """Resting orders from `order_book_orders`, summed per price level."""
+        if not self._sdk_available:
+            raise RuntimeError("Lighter SDK not available; cannot query order book")
+        import lighter  # type: ignore
+        info = self._get_market_info(symbol)
+        order_api = lighter.OrderApi(self._api_client)  # type: ignore[attr-defined]
+        obo = await order_api.order_book_orders(info.market_id, depth)
+        return OrderBook.from_levels(
+            symbol, aggregate_orders(getattr(obo, "bids", None) or []), aggregate_orders(getattr(obo, "asks", None) or [])
+        )
+
     async def submit_limit_order(
         self,
         *,
@@ -400,6 +432,23 @@ class LighterConnector(BaseConnector):
             "tx": getattr(tx_hash, "tx_hash", None) or getattr(tx_hash, "hash", None) or str(tx_hash)
         }
 
+    async def amend_order(self, *, symbol: str, order_id: str, base_amount: int, price: int) -> Dict[str, Any]:
+        """Change a resting order's size and price in place (one signed modify tx)."""
+        await self._ensure_signer()
+        info = self._get_market_info(symbol)
+        _, tx_hash, err = await self._signer.modify_order(  # type: ignore[attr-defined]
+            market_index=info.market_id,
+            order_index=int(order_id),
+            base_amount=int(base_amount),
+            price=int(price),
+            trigger_price=0,
+        )
+        if err is not None:
+            return {"error": str(err)}
+        return {
+            "tx": getattr(tx_hash, "tx_hash", None) or getattr(tx_hash, "hash", None) or str(tx_hash)
+        }
+
     async def get_order(self, symbol: str, client_order_index: int) -> Dict[str, Any]:
         return {
             "client_order_index": client_order_index,
@@ -414,6 +463,80 @@ class LighterConnector(BaseConnector):
     async def get_margin(self) -> Dict[str, Any]:
         return {}
 
+    # Funding intervals of the exchanges `funding-rates` aggregates; unknown ones default to 8h
+    _FUNDING_INTERVAL_HOURS = {"lighter": 1.0, "hyperliquid": 1.0, "binance": 8.0, "bybit": 8.0}
+
+    async def get_current_funding(self, symbol: Optional[str] = None) -> List[CurrentFunding]:
+        """Current funding from Lighter's `funding-rates`, which also quotes other exchanges.
+
+        `symbol` keeps only that market's rows (one per exchange quoting it).
+        """
+        import lighter  # type: ignore
+
+        resp = await lighter.FundingApi(self._api_client).funding_rates()  # type: ignore[attr-defined]
+        rows: List[CurrentFunding] = []
+        for entry in getattr(resp, "funding_rates", []) or []:
+            exchange = str(getattr(entry, "exchange", "") or self.venue).lower()
+            rate = decimal_or_none(getattr(entry, "rate", None))
+            if rate is None or (symbol is not None and str(getattr(entry, "symbol", "")) != symbol):
+                continue
+            rows.append(
+                CurrentFunding(
+                    venue=exchange,
+                    symbol=str(getattr(entry, "symbol", "")),
+                    rate=rate,
+                    interval_hours=self._FUNDING_INTERVAL_HOURS.get(exchange, 8.0),
+                )
+            )
+        return rows
+
+    async def get_order_history(
+        self, symbol: Optional[str] = None, cursor: Optional[str] = None, limit: Optional[int] = None
+    ) -> OrderHistory:
+        """Inactive (filled/cancelled) orders from `accountInactiveOrders`, following `next_cursor`."""
+        await self._ensure_signer()
+        import lighter  # type: ignore
+
+        acct_idx = self.get_account_index()
+        if acct_idx is None:
+            raise RuntimeError("lighter account_index not configured for order history")
+        market = self._get_market_info(symbol) if symbol else None
+        order_api = lighter.OrderApi(self._api_client)  # type: ignore[attr-defined]
+        token, err = self._signer.create_auth_token_with_expiry(int(__import__("time").time() + 600))
+        if err is not None:
+            raise RuntimeError(f"lighter auth token error: {err}")
+
+        async def fetch_page(page_cursor: Optional[str], size: int) -> HistoryPage:
+            kwargs: Dict[str, Any] = {"account_index": acct_idx, "limit": size, "auth": token}
+            if market is not None:
+                kwargs["market_id"] = market.market_id
+            if page_cursor:
+                kwargs["cursor"] = page_cursor
+            resp = await order_api.account_inactive_orders(**kwargs)
+            rows = [self._historical_order(o) for o in (getattr(resp, "orders", []) or [])]
+            return rows, getattr(resp, "next_cursor", None) or None
+
+        # accountInactiveOrders caps pages at 100
+        return await paginate(fetch_page, cursor=cursor, limit=limit, page_size=100)
+
+    def _historical_order(self, order: Any) -> HistoricalOrder:
+        market_index = getattr(order, "market_index", None)
+        symbol = next((m.symbol for m in self._markets.values() if m.market_id == market_index), str(market_index))
+        return HistoricalOrder(
+            venue=self.venue,
+            symbol=symbol,
+            order_id=str(getattr(order, "order_index", "")),
+            client_order_index=getattr(order, "client_order_index", None),
+            is_ask=bool(getattr(order, "is_ask", False)),
+            order_type=str(getattr(order, "type", "")),
+            price=decimal_or_none(getattr(order, "price", None)),
+            quantity=decimal_or_none(getattr(order, "initial_base_amount", None)) or Decimal(0),
+            filled_quantity=decimal_or_none(getattr(order, "filled_base_amount", None)) or Decimal(0),
+            status=str(getattr(order, "status", "")),
+            created_ms=timestamp_ms(getattr(order, "timestamp", None)),
+            raw=order.to_dict() if hasattr(order, "to_dict") else {},
+        )
+
     async def _ensure_signer(self) -> None:
         if not self._sdk_available:
             raise RuntimeError("Lighter SDK not available")
@@ -425,6 +548,7 @@ class LighterConnector(BaseConnector):
             priv = self._key_get(
                 keys, "api_key_private_key", "apikeyprivatekey", "private_key", "privatekey"
             ) or ""
+            register_secret(priv)
             acct = self._key_get(keys, "account_index", "accountindex") or "0"
             api_k = self._key_get(keys, "api_key_index", "apikeyindex") or "0"
             acct_idx = int(acct)
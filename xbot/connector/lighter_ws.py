@@ -10,7 +10,10 @@ from typing import Optional, Dict, Any, Callable, Awaitable
 import websockets
 
 from xbot.core.cache import MarketCache
+from xbot.connector.proxy import ws_connect_kwargs
+from xbot.core.feed_status import FeedStatus
 from xbot.utils.logging import get_logger
+from xbot.utils.secret import register_secret
 from xbot.execution.order_service import OrderUpdatePayload
 from xbot.execution.models import OrderState
 
@@ -33,6 +36,9 @@ class LighterWsClient:
         ping_interval: float = 55.0,
         ping_timeout: float = 10.0,
         on_order_update: Optional[Callable[[OrderUpdatePayload], Awaitable[None]]] = None,
+        stale_after_secs: Optional[float] = 15.0,
+        ws_url: Optional[str] = None,
+        proxy: Optional[str] = None,
     ) -> None:
         self._market_index = market_index
         self._venue_symbol = venue_symbol
@@ -44,12 +50,14 @@ class LighterWsClient:
         self._ping_interval = ping_interval
         self._ping_timeout = ping_timeout
         self._on_order_update = on_order_update
+        self._ws_url = ws_url or self.WS_URL
+        self._proxy = proxy
+        self._market_status = FeedStatus(cache, feed="market", stale_after_secs=stale_after_secs)
+        self._account_status = FeedStatus(cache, feed="account")
         self._logger = get_logger(__name__)
         self._running = asyncio.Event()
         self._task: Optional[asyncio.Task] = None
 
-        self._bids: Dict[float, float] = {}
-        self._asks: Dict[float, float] = {}
         self._ob_offset: Optional[int] = None
 
     async def start(self) -> None:
@@ -57,6 +65,7 @@ class LighterWsClient:
             return
         self._running.set()
         self._task = asyncio.create_task(self._run(), name="lighter-ws")
+        await self._market_status.start()
 
     async def stop(self) -> None:
         if self._task is None:
@@ -66,6 +75,8 @@ class LighterWsClient:
         with contextlib.suppress(asyncio.CancelledError):
             await self._task
         self._task = None
+        await self._market_status.stop()
+        await self._account_status.stop()
 
     def _load_keys(self) -> Dict[str, str]:
         try:
@@ -90,6 +101,7 @@ class LighterWsClient:
                 return None
 
             priv = _kg("api_key_private_key", "apikeyprivatekey", "private_key", "privatekey") or ""
+            register_secret(priv)
             if not priv:
                 self._logger.info(
                     "ws_auth_missing_priv",
@@ -158,18 +170,15 @@ class LighterWsClient:
                     payload["auth"] = auth_token
                 await ws.send(json.dumps(payload))
 
-    def _apply_ob_updates(self, side: str, updates: list[dict]) -> None:
-        book = self._bids if side == "bids" else self._asks
+    @staticmethod
+    def _ob_levels(updates: list[dict]) -> list[tuple[float, float]]:
+        levels = []
         for u in updates or []:
             try:
-                px = float(u.get("price"))
-                sz = float(u.get("size"))
-                if sz <= 0:
-                    book.pop(px, None)
-                else:
-                    book[px] = sz
+                levels.append((float(u.get("price")), float(u.get("size"))))
             except Exception:
                 continue
+        return levels
 
     async def _run(self) -> None:
         backoff = self._reconnect_delay
@@ -194,7 +203,8 @@ class LighterWsClient:
                     },
                 )
                 async with websockets.connect(
-                    self.WS_URL,
+                    self._ws_url,
+                    **await ws_connect_kwargs(self._ws_url, self._proxy),
                     ping_interval=self._ping_interval,
                     ping_timeout=self._ping_timeout,
                     max_size=2 ** 22,
@@ -203,8 +213,9 @@ class LighterWsClient:
                     self._logger.info(
                         "ws_connected", extra={"venue": "lighter", "has_private": has_private}
                     )
-                    self._bids.clear()
-                    self._asks.clear()
+                    await self._market_status.connected()
+                    if has_private:
+                        await self._account_status.connected()
                     self._ob_offset = None
                     async for raw in ws:
                         try:
@@ -212,25 +223,33 @@ class LighterWsClient:
                         except Exception:
                             continue
                         await self._handle_message(msg)
+                await self._feeds_down(reason="closed")
                 backoff = self._reconnect_delay
             except asyncio.CancelledError:
                 break
             except Exception as exc:
                 self._logger.info("ws_error", extra={"venue": "lighter", "error": str(exc)})
+                await self._feeds_down(error=str(exc))
                 await asyncio.sleep(backoff)
                 backoff = min(backoff * 2, 30.0)
 
+    async def _feeds_down(self, **info: object) -> None:
+        await self._market_status.disconnected(**info)
+        await self._account_status.disconnected(**info)
+
     async def _handle_message(self, msg: Dict[str, Any]) -> None:
         et = msg.get("type") or msg.get("event")
+        if isinstance(et, str) and "account" in et:
+            await self._account_status.on_message()
+        elif isinstance(et, str) and ("order_book" in et or "trade" in et):
+            await self._market_status.on_message()
         try:
             if et == "subscribed/order_book":
                 ob = msg.get("order_book") or {}
                 self._ob_offset = ob.get("offset")
-                self._bids.clear()
-                self._asks.clear()
-                self._apply_ob_updates("bids", ob.get("bids") or [])
-                self._apply_ob_updates("asks", ob.get("asks") or [])
-                await self._publish_top()
+                await self._cache.apply_book(
+                    self._venue_symbol, self._ob_levels(ob.get("bids")), self._ob_levels(ob.get("asks")), reset=True
+                )
             elif et == "update/order_book":
                 ob = msg.get("order_book") or {}
                 new_offset = ob.get("offset")
@@ -241,10 +260,10 @@ class LighterWsClient:
                         raise RuntimeError(
                             f"order_book offset gap: have {self._ob_offset}, got {new_offset}"
                         )
-                self._apply_ob_updates("bids", ob.get("bids") or [])
-                self._apply_ob_updates("asks", ob.get("asks") or [])
+                await self._cache.apply_book(
+                    self._venue_symbol, self._ob_levels(ob.get("bids")), self._ob_levels(ob.get("asks"))
+                )
                 self._ob_offset = new_offset if isinstance(new_offset, int) else self._ob_offset
-                await self._publish_top()
             elif et and (et.startswith("trade") or et == "update/trade"):
                 if isinstance(msg.get("trades"), list):
                     for tr in msg["trades"][:10]:
@@ -273,11 +292,6 @@ class LighterWsClient:
         except Exception as exc:
             self._logger.info("ws_handle_error", extra={"venue": "lighter", "error": str(exc)})
 
-    async def _publish_top(self) -> None:
-        top_b = max(self._bids.keys()) if self._bids else None
-        top_a = min(self._asks.keys()) if self._asks else None
-        await self._cache.set_top(self._venue_symbol, top_b, top_a)
-
     async def _handle_account_msg(self, msg: Dict[str, Any]) -> None:
         # Orders can arrive under various shapes: order_updates, orders,
         # nested in data/account, dict-of-lists, or dict keyed by market_index.
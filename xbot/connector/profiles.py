@@ -0,0 +1,77 @@
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from typing import Any, Dict, Mapping, Optional, Tuple
+
+MAINNET = "mainnet"
+TESTNET = "testnet"
+
+
+@dataclass(slots=True)
+class EnvironmentProfile:
+    venue: str
+    name: str
+    rest_url: Optional[str] = None
+    ws_url: Optional[str] = None
+    symbol_map: Dict[str, str] = field(default_factory=dict)
+
+    @property
+    def is_mainnet(self) -> bool:
+        return self.name == MAINNET
+
+
+# Built-in endpoints; `environments.<name>` in the config file extends or overrides these.
+_BUILTIN: Dict[Tuple[str, str], EnvironmentProfile] = {
+    ("backpack", MAINNET): EnvironmentProfile(
+        venue="backpack",
+        name=MAINNET,
+        rest_url="https://api.backpack.exchange",
+        ws_url="wss://ws.backpack.exchange",
+    ),
+    ("lighter", MAINNET): EnvironmentProfile(
+        venue="lighter",
+        name=MAINNET,
+        rest_url="https://mainnet.zklighter.elliot.ai",
+        ws_url="wss://mainnet.zklighter.elliot.ai/stream",
+    ),
+    ("lighter", TESTNET): EnvironmentProfile(
+        venue="lighter",
+        name=TESTNET,
+        rest_url="https://testnet.zklighter.elliot.ai",
+        ws_url="wss://testnet.zklighter.elliot.ai/stream",
+    ),
+}
+
+
+def resolve_profile(venue: str, name: str, overrides: Optional[Mapping[str, Any]] = None) -> EnvironmentProfile:
+    """Built-in profile for (venue, name) merged with config overrides.
+
+    Non-mainnet profiles must end up with both URLs; silently falling back to
+    mainnet endpoints is exactly the accident profiles exist to prevent.
+    """
+    venue = venue.lower()
+    name = name.lower()
+    base = _BUILTIN.get((venue, name))
+    overrides = overrides or {}
+    profile = EnvironmentProfile(
+        venue=venue,
+        name=name,
+        rest_url=overrides.get("rest_url") or (base.rest_url if base else None),
+        ws_url=overrides.get("ws_url") or (base.ws_url if base else None),
+        symbol_map={k.upper(): v for k, v in (overrides.get("symbol_map") or (base.symbol_map if base else {})).items()},
+    )
+    if not profile.is_mainnet and (not profile.rest_url or not profile.ws_url):
+        raise ValueError(
+            f"environment '{name}' for {venue} has no endpoints; set environments.{name}.rest_url and ws_url"
+        )
+    return profile
+
+
+def startup_banner(profile: EnvironmentProfile) -> str:
+    label = f" {profile.venue.upper()} {profile.name.upper()} "
+    bar = "=" * (len(label) + 8)
+    warning = "LIVE FUNDS - mainnet sizing applies" if profile.is_mainnet else "sandbox endpoints - not real funds"
+    return "\n".join([bar, "====" + label + "====", f"  {warning}", f"  rest={profile.rest_url} ws={profile.ws_url}", bar])
+
+
+__all__ = ["EnvironmentProfile", "resolve_profile", "startup_banner", "MAINNET", "TESTNET"]
@@ -0,0 +1,29 @@
+from __future__ import annotations
+
+from typing import Any, Dict, Optional
+from urllib.parse import urlparse
+
+
+async def ws_connect_kwargs(url: str, proxy: Optional[str]) -> Dict[str, Any]:
+    """Extra `websockets.connect` kwargs for tunnelling through an HTTP or SOCKS5 proxy.
+
+    The tunnel is opened with python-socks (works across websockets versions);
+    no proxy means no extra kwargs.
+    """
+    if not proxy:
+        return {}
+    try:
+        from python_socks.async_.asyncio import Proxy  # type: ignore
+    except Exception as exc:  # pragma: no cover - optional dependency
+        raise RuntimeError("WebSocket proxy support requires python-socks (pip install python-socks)") from exc
+    target = urlparse(url)
+    secure = target.scheme == "wss"
+    port = target.port or (443 if secure else 80)
+    sock = await Proxy.from_url(proxy).connect(dest_host=target.hostname, dest_port=port)
+    kwargs: Dict[str, Any] = {"sock": sock}
+    if secure:
+        kwargs["server_hostname"] = target.hostname
+    return kwargs
+
+
+__all__ = ["ws_connect_kwargs"]
@@ -0,0 +1,144 @@
+from __future__ import annotations
+
+import asyncio
+import base64
+import contextlib
+import json
+from dataclasses import dataclass
+from typing import Any, Optional, Protocol
+
+from cryptography.exceptions import InvalidSignature
+from cryptography.hazmat.primitives.asymmetric import ed25519
+
+LOCAL = "local"
+UNIX = "unix"
+
+
+class SignerError(RuntimeError):
+    """A signature could not be produced, or the one returned does not verify."""
+
+
+class Signer(Protocol):
+    """Produces ED25519 signatures for one API key, wherever its secret lives."""
+
+    @property
+    def public_key(self) -> str:
+        """The base64 public key, sent as the API key."""
+        ...
+
+    async def sign(self, message: str) -> str:
+        """Base64 signature of `message`."""
+        ...
+
+
+@dataclass(slots=True)
+class SignerConfig:
+    kind: str = LOCAL  # local: the key file; unix: an external signing service
+    socket_path: Optional[str] = None  # unix: the service's socket
+    public_key: Optional[str] = None  # unix: the API key the service signs for
+    timeout_secs: float = 2.0
+
+
+class LocalSigner:
+    """Signs with a key loaded into this process."""
+
+    def __init__(self, public_key: str, private_key: ed25519.Ed25519PrivateKey) -> None:
+        self._public_key = public_key
+        self._private_key = private_key
+
+    @property
+    def public_key(self) -> str:
+        return self._public_key
+
+    async def sign(self, message: str) -> str:
+        return base64.b64encode(self._private_key.sign(message.encode())).decode()
+
+    def __repr__(self) -> str:
+        return f"LocalSigner({self._public_key})"
+
+
+class UnixSocketSigner:
+    """Asks a signing service on a Unix socket, so the secret never reaches this host's disk or memory.
+
+    One non-blocking connection per signature. The request is a JSON line
+    `{"public_key": ..., "message": <base64>}` and the reply a JSON line
+    `{"signature": <base64>}` or `{"error": ...}`. The service can hold the
+    key itself or front an HSM or YubiKey. Every returned signature is
+    verified against `public_key` before use, so a misconfigured service
+    fails here rather than as a venue auth error.
+    """
+
+    def __init__(self, *, socket_path: str, public_key: str, timeout_secs: float = 2.0) -> None:
+        self._socket_path = socket_path
+        self._public_key = public_key
+        self._timeout = timeout_secs
+        try:
+            raw = base64.b64decode(public_key, validate=True)
+            self._verifier = ed25519.Ed25519PublicKey.from_public_bytes(raw)
+        except ValueError as exc:
+            raise SignerError(f"signer public key is not a base64 ED25519 key: {exc}") from None
+
+    @property
+    def public_key(self) -> str:
+        return self._public_key
+
+    async def _exchange(self, payload: dict[str, Any]) -> bytes:
+        reader, writer = await asyncio.open_unix_connection(self._socket_path)
+        try:
+            writer.write(json.dumps(payload).encode() + b"\n")
+            await writer.drain()
+            return await reader.readline()
+        finally:
+            writer.close()
+            with contextlib.suppress(OSError):
+                await writer.wait_closed()
+
+    async def _request(self, payload: dict[str, Any]) -> dict[str, Any]:
+        try:
+            reply = await asyncio.wait_for(self._exchange(payload), timeout=self._timeout)
+        except asyncio.TimeoutError:
+            raise SignerError(f"signer at {self._socket_path} timed out after {self._timeout}s") from None
+        except OSError as exc:
+            raise SignerError(f"signer at {self._socket_path} unreachable: {exc}") from exc
+        try:
+            return json.loads(reply)
+        except ValueError:
+            raise SignerError(f"signer at {self._socket_path} sent an unreadable reply") from None
+
+    async def sign(self, message: str) -> str:
+        encoded = message.encode()
+        reply = await self._request({"public_key": self._public_key, "message": base64.b64encode(encoded).decode()})
+        if reply.get("error") is not None:
+            raise SignerError(f"signer at {self._socket_path} refused: {reply['error']}")
+        signature = str(reply.get("signature") or "")
+        try:
+            self._verifier.verify(base64.b64decode(signature, validate=True), encoded)
+        except (InvalidSignature, ValueError):
+            raise SignerError(f"signer at {self._socket_path} returned a signature that does not verify") from None
+        return signature
+
+    def __repr__(self) -> str:
+        return f"UnixSocketSigner({self._socket_path}, {self._public_key})"
+
+
+def remote_signer(config: SignerConfig) -> Signer:
+    """The configured external signer; `local` signers are built from the key file by the connector."""
+    if config.kind == UNIX:
+        if not config.socket_path or not config.public_key:
+            raise ValueError("signer.kind unix needs socket_path and public_key")
+        return UnixSocketSigner(
+            socket_path=config.socket_path, public_key=config.public_key, timeout_secs=config.timeout_secs
+        )
+    raise ValueError(f"unknown signer kind {config.kind!r}")
+
+
+__all__ = [
+    "LOCAL",
+    "LocalSigner",
+    "Signer",
+    "SignerConfig",
+    "SignerError",
+    "UNIX",
+    "UnixSocketSigner",
+    "remote_signer",
+]
@@ -0,0 +1,378 @@
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from decimal import ROUND_DOWN, Decimal
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Tuple
+
+from xbot.core.orderbook import OrderBook
+
+from .history import CurrentFunding, OrderHistory
+
+OrderUpdateCallback = Callable[[int, str, Dict[str, Any]], Awaitable[None]]
+
+
+class SimulatedOutageError(ConnectionError):
+    """Raised by every call while the simulated venue is down."""
+
+
+@dataclass(slots=True)
+class SimMarket:
+    symbol: str  # venue symbol
+    mark: Decimal
+    spread_bps: Decimal = Decimal("2")
+    price_decimals: int = 2
+    size_decimals: int = 3
+    min_size_i: int = 1
+    depth: Decimal = Decimal("1000")  # base units quoted on each of five levels per side
+    funding_rate: Decimal = Decimal("0.0001")  # per settlement; longs pay when positive
+    funding_interval_hours: float = 8.0
+
+    def _scaled(self, value: Decimal) -> int:
+        return int((value * Decimal(10) ** self.price_decimals).to_integral_value(rounding=ROUND_DOWN))
+
+    @property
+    def bid(self) -> Decimal:
+        return self.mark * (1 - self.spread_bps / Decimal(20_000))
+
+    @property
+    def ask(self) -> Decimal:
+        return self.mark * (1 + self.spread_bps / Decimal(20_000))
+
+
+@dataclass(slots=True)
+class SimPosition:
+    qty: Decimal = Decimal(0)  # signed base quantity
+    entry: Decimal = Decimal(0)  # average entry price
+
+
+@dataclass(slots=True)
+class _RestingOrder:
+    symbol: str
+    client_order_index: int
+    order_id: str
+    price: Decimal
+    size: Decimal
+    is_ask: bool
+    reduce_only: int = 0
+    status: str = "new"
+    filled: Decimal = field(default=Decimal(0))
+
+
+class SimulatedConnector:
+    """In-memory venue for stress tests and dry runs; no network.
+
+    Market orders fill at the touch, limit orders fill when marketable on
+    submit or once `set_mark` moves the book through them (at their limit
+    price). Fills, fees and funding settle into one USD collateral
+    balance, and `get_positions`/`get_margin` answer in Backpack's schema
+    so recovery, equity and reconciliation code read them unchanged. Every
+    fill is queued for `on_order_update(client_order_index, status, info)`
+    and delivered by `flush_updates()`, the way a WS feed would report it.
+    `down = True` makes every venue call raise `SimulatedOutageError`;
+    `set_mark` and `settle_funding` still work, since the market moves on
+    while the bot cannot see it.
+    """
+
+    def __init__(
+        self,
+        *,
+        markets: List[SimMarket],
+        collateral: Decimal,
+        venue: str = "sim",
+        taker_fee_bps: Decimal = Decimal("4"),
+        maker_fee_bps: Decimal = Decimal("1"),
+        on_order_update: Optional[OrderUpdateCallback] = None,
+    ) -> None:
+        self.venue = venue
+        self.markets: Dict[str, SimMarket] = {market.symbol: market for market in markets}
+        self.collateral = collateral
+        self.down = False
+        self.funding_paid = Decimal(0)
+        self.fees_paid = Decimal(0)
+        self._taker_fee = taker_fee_bps / Decimal(10_000)
+        self._maker_fee = maker_fee_bps / Decimal(10_000)
+        self._on_order_update = on_order_update
+        self._positions: Dict[str, SimPosition] = {}
+        self._orders: Dict[int, _RestingOrder] = {}
+        # Fills are queued and delivered by `flush_updates`, so an update never re-enters the call that caused it
+        self._updates: List[Tuple[int, str, Dict[str, Any]]] = []
+        self._next_id = 1
+
+    def attach_order_updates(self, callback: OrderUpdateCallback) -> None:
+        self._on_order_update = callback
+
+    def _check(self) -> None:
+        if self.down:
+            raise SimulatedOutageError(f"{self.venue} is unreachable (simulated outage)")
+
+    def _market(self, symbol: str) -> SimMarket:
+        if symbol not in self.markets:
+            raise ValueError(f"unknown simulated market {symbol}")
+        return self.markets[symbol]
+
+    async def start(self) -> None:
+        return None
+
+    async def stop(self) -> None:
+        return None
+
+    # --- market data -------------------------------------------------------------
+
+    async def get_price_size_decimals(self, symbol: str) -> Tuple[int, int]:
+        self._check()
+        market = self._market(symbol)
+        return market.price_decimals, market.size_decimals
+
+    async def get_min_size_i(self, symbol: str) -> int:
+        self._check()
+        return self._market(symbol).min_size_i
+
+    async def get_top_of_book(self, symbol: str) -> Tuple[Optional[int], Optional[int], int]:
+        self._check()
+        market = self._market(symbol)
+        return market._scaled(market.bid), market._scaled(market.ask), 10 ** market.price_decimals
+
+    async def get_order_book(self, symbol: str) -> OrderBook:
+        self._check()
+        market = self._market(symbol)
+        step = market.mark * market.spread_bps / Decimal(20_000)
+        bids = [(float(market.bid - step * i), float(market.depth)) for i in range(5)]
+        asks = [(float(market.ask + step * i), float(market.depth)) for i in range(5)]
+        return OrderBook.from_levels(symbol, bids, asks)
+
+    async def get_current_funding(self, symbol: Optional[str] = None) -> List[CurrentFunding]:
+        self._check()
+        return [
+            CurrentFunding(
+                venue=self.venue, symbol=market.symbol, rate=market.funding_rate, interval_hours=market.funding_interval_hours
+            )
+            for market in self.markets.values()
+            if symbol is None or market.symbol == symbol
+        ]
+
+    def set_mark(self, symbol: str, price: Decimal) -> List[int]:
+        """Move the market (works during an outage) and fill resting orders it crosses; returns their indexes."""
+        market = self._market(symbol)
+        market.mark = price
+        crossed = [
+            order
+            for order in self._orders.values()
+            if order.symbol == symbol
+            and order.status in ("new", "partially_filled")
+            and (market.bid >= order.price if order.is_ask else market.ask <= order.price)
+        ]
+        for order in crossed:
+            self._fill(order, order.price, order.size - order.filled, maker=True)
+        return [order.client_order_index for order in crossed]
+
+    def settle_funding(self, symbol: str) -> Decimal:
+        """Charge one funding interval on the open position; returns what the account paid (negative = received)."""
+        market = self._market(symbol)
+        position = self._positions.get(symbol)
+        if position is None or not position.qty:
+            return Decimal(0)
+        payment = position.qty * market.mark * market.funding_rate
+        self.collateral -= payment
+        self.funding_paid += payment
+        return payment
+
+    # --- orders ------------------------------------------------------------------
+
+    def _size(self, symbol: str, size_i: int) -> Decimal:
+        return Decimal(size_i) / Decimal(10) ** self._market(symbol).size_decimals
+
+    def _price(self, symbol: str, price_i: int) -> Decimal:
+        return Decimal(price_i) / Decimal(10) ** self._market(symbol).price_decimals
+
+    def _clip_reduce_only(self, symbol: str, size: Decimal, is_ask: bool) -> Decimal:
+        held = self._positions.get(symbol, SimPosition()).qty
+        reducible = held if is_ask else -held
+        return max(Decimal(0), min(size, reducible))
+
+    def _apply_fill(self, symbol: str, size: Decimal, price: Decimal, is_ask: bool, *, maker: bool) -> Decimal:
+        position = self._positions.setdefault(symbol, SimPosition())
+        signed = -size if is_ask else size
+        if position.qty and (position.qty > 0) != (signed > 0):
+            closed = min(abs(signed), abs(position.qty))
+            direction = Decimal(1) if position.qty > 0 else Decimal(-1)
+            self.collateral += closed * (price - position.entry) * direction
+        new_qty = position.qty + signed
+        if not new_qty:
+            position.entry = Decimal(0)
+        elif not position.qty or (position.qty > 0) != (new_qty > 0):
+            position.entry = price
+        elif (position.qty > 0) == (signed > 0):
+            position.entry = (position.entry * abs(position.qty) + price * size) / abs(new_qty)
+        position.qty = new_qty
+        fee = size * price * (self._maker_fee if maker else self._taker_fee)
+        self.collateral -= fee
+        self.fees_paid += fee
+        return fee
+
+    def _fill(self, order: _RestingOrder, price: Decimal, size: Decimal, *, maker: bool) -> None:
+        if order.reduce_only:
+            size = self._clip_reduce_only(order.symbol, size, order.is_ask)
+        if size <= 0:
+            order.status = "cancelled"
+            self._notify(order, {})
+            return
+        fee = self._apply_fill(order.symbol, size, price, order.is_ask, maker=maker)
+        order.filled += size
+        order.status = "filled" if order.filled >= order.size else "partially_filled"
+        self._notify(order, {"l": str(size), "L": str(price), "n": str(fee), "m": maker, "z": str(order.filled)})
+
+    def _notify(self, order: _RestingOrder, info: Dict[str, Any]) -> None:
+        self._updates.append((order.client_order_index, order.status, {"exchange_order_id": order.order_id, **info}))
+
+    async def flush_updates(self) -> int:
+        """Deliver queued order updates to `on_order_update`; returns how many went out."""
+        queue, self._updates = self._updates, []
+        if self._on_order_update is not None:
+            for client_order_index, status, info in queue:
+                await self._on_order_update(client_order_index, status, info)
+        return len(queue)
+
+    def _new_order(
+        self, symbol: str, client_order_index: int, price: Decimal, size: Decimal, is_ask: bool, reduce_only: int
+    ) -> _RestingOrder:
+        order = _RestingOrder(
+            symbol=symbol,
+            client_order_index=client_order_index,
+            order_id=f"sim-{self._next_id}",
+            price=price,
+            size=size,
+            is_ask=is_ask,
+            reduce_only=reduce_only,
+        )
+        self._next_id += 1
+        self._orders[client_order_index] = order
+        return order
+
+    async def submit_limit_order(
+        self,
+        *,
+        symbol: str,
+        client_order_index: int,
+        base_amount: int,
+        price: int,
+        is_ask: bool,
+        post_only: bool = False,
+        reduce_only: int = 0,
+    ) -> str:
+        self._check()
+        market = self._market(symbol)
+        limit = self._price(symbol, price)
+        order = self._new_order(symbol, client_order_index, limit, self._size(symbol, base_amount), is_ask, reduce_only)
+        marketable = market.bid >= limit if is_ask else market.ask <= limit
+        if marketable and post_only:
+            order.status = "cancelled"
+            self._notify(order, {"reason": "post_only_would_take"})
+        elif marketable:
+            self._fill(order, market.bid if is_ask else market.ask, order.size, maker=False)
+        return order.order_id
+
+    async def submit_market_order(
+        self,
+        *,
+        symbol: str,
+        client_order_index: int,
+        size_i: int,
+        is_ask: bool,
+        reduce_only: int = 0,
+    ) -> str:
+        self._check()
+        market = self._market(symbol)
+        price = market.bid if is_ask else market.ask
+        order = self._new_order(symbol, client_order_index, price, self._size(symbol, size_i), is_ask, reduce_only)
+        self._fill(order, price, order.size, maker=False)
+        return order.order_id
+
+    async def cancel_by_client_id(self, symbol: str, client_order_index: int) -> Dict[str, Any]:
+        self._check()
+        order = self._orders.get(client_order_index)
+        if order is None or order.status not in ("new", "partially_filled"):
+            return {"status": "not_found"}
+        order.status = "cancelled"
+        return {"status": "cancelled", "id": order.order_id}
+
+    async def cancel_by_order_id(self, symbol: str, order_id: str) -> Dict[str, Any]:
+        self._check()
+        for order in self._orders.values():
+            if order.order_id == order_id:
+                return await self.cancel_by_client_id(symbol, order.client_order_index)
+        return {"status": "not_found"}
+
+    async def get_order(self, symbol: str, client_order_index: int) -> Dict[str, Any]:
+        self._check()
+        order = self._orders.get(client_order_index)
+        if order is None:
+            return {}
+        return {
+            "id": order.order_id,
+            "clientId": order.client_order_index,
+            "status": order.status,
+            "price": str(order.price),
+            "quantity": str(order.size),
+            "executedQuantity": str(order.filled),
+        }
+
+    async def get_open_orders(self, symbol: Optional[str] = None) -> List[Dict[str, Any]]:
+        self._check()
+        return [
+            await self.get_order(order.symbol, order.client_order_index)
+            for order in self._orders.values()
+            if order.status in ("new", "partially_filled") and (symbol is None or order.symbol == symbol)
+        ]
+
+    async def get_order_history(
+        self, symbol: Optional[str] = None, cursor: Optional[str] = None, limit: Optional[int] = None
+    ) -> OrderHistory:
+        self._check()
+        return OrderHistory()
+
+    # --- account -----------------------------------------------------------------
+
+    def unrealized_pnl(self) -> Decimal:
+        total = Decimal(0)
+        for symbol, position in self._positions.items():
+            if position.qty:
+                total += position.qty * (self.markets[symbol].mark - position.entry)
+        return total
+
+    def equity(self) -> Decimal:
+        return self.collateral + self.unrealized_pnl()
+
+    def exposure(self) -> Decimal:
+        return sum(
+            (abs(position.qty) * self.markets[symbol].mark for symbol, position in self._positions.items()),
+            Decimal(0),
+        )
+
+    async def get_positions(self) -> List[Dict[str, Any]]:
+        self._check()
+        rows = []
+        for symbol, position in self._positions.items():
+            if not position.qty:
+                continue
+            mark = self.markets[symbol].mark
+            rows.append(
+                {
+                    "symbol": symbol,
+                    "netQuantity": str(position.qty),
+                    "entryPrice": str(position.entry),
+                    "markPrice": str(mark),
+                    "netExposureNotional": str(abs(position.qty) * mark),
+                    "pnlUnrealized": str(position.qty * (mark - position.entry)),
+                }
+            )
+        return rows
+
+    async def get_margin(self) -> Dict[str, Any]:
+        self._check()
+        return {"collateral": {"netEquity": str(self.equity()), "assetsValue": str(self.collateral)}}
+
+    def account_id(self) -> str:
+        return f"{self.venue}-account"
+
+
+__all__ = ["SimMarket", "SimPosition", "SimulatedConnector", "SimulatedOutageError"]
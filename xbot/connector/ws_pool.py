@@ -0,0 +1,233 @@
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from typing import Any, Callable, Dict, Iterable, List, Optional, Protocol
+
+from ..core.events import ConnectionState
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+# Streams one connection may carry; the Backpack figure is the request cap the client has always used
+VENUE_STREAM_LIMITS: Dict[str, int] = {"backpack": 200}
+DEFAULT_STREAM_LIMIT = 200
+
+
+@dataclass(slots=True)
+class WsPoolConfig:
+    max_streams_per_connection: Dict[str, int] = field(default_factory=dict)  # venue -> override of the venue limit
+    max_connections: int = 5
+    rebalance: bool = True  # fold shards back together when removals leave room
+
+    def stream_limit(self, venue: str) -> int:
+        return self.max_streams_per_connection.get(venue, VENUE_STREAM_LIMITS.get(venue, DEFAULT_STREAM_LIMIT))
+
+
+class ShardClient(Protocol):
+    """One WebSocket connection of the pool (e.g. `BackpackWsClient`)."""
+
+    @property
+    def symbols(self) -> List[str]:
+        ...
+
+    @property
+    def stream_count(self) -> int:
+        ...
+
+    @property
+    def market_state(self) -> Optional[ConnectionState]:
+        ...
+
+    async def start(self) -> None:
+        ...
+
+    async def stop(self) -> None:
+        ...
+
+    async def add_symbols(self, symbols: Iterable[str]) -> List[str]:
+        ...
+
+    async def remove_symbols(self, symbols: Iterable[str]) -> List[str]:
+        ...
+
+
+# (shard index, initial venue symbols) -> client; shard 0 is the primary and carries the private streams
+ShardFactory = Callable[[int, List[str]], ShardClient]
+
+
+class WsConnectionPool:
+    """Spreads one venue's market-data streams over as many connections as its limits require.
+
+    Each connection carries at most the venue's stream limit. The primary
+    connection (shard 0) also carries the private account streams and keeps
+    the cache's `market` feed; the others report as `market.<shard>`. Added
+    symbols go to the least loaded shard with room, and a new connection is
+    opened when none has room, up to `max_connections`. Symbols that do not
+    fit are refused and logged. After a removal, with `rebalance`, the
+    smallest extra shard is folded into the others. Its symbols are
+    subscribed on their new connection before they are unsubscribed from
+    the old one, so the stream never pauses.
+
+    The pool exposes `add_symbols`/`remove_symbols`, so a `Watchlist` can use
+    it as a feed.
+    """
+
+    def __init__(
+        self,
+        *,
+        venue: str,
+        factory: ShardFactory,
+        symbols: Iterable[str] = (),
+        streams_per_symbol: int = 2,
+        config: Optional[WsPoolConfig] = None,
+    ) -> None:
+        self._venue = venue
+        self._factory = factory
+        self._streams_per_symbol = streams_per_symbol
+        self._config = config or WsPoolConfig()
+        self._limit = self._config.stream_limit(venue)
+        self._shards: Dict[int, ShardClient] = {}
+        self._pending = list(dict.fromkeys(symbols))
+        self._started = False
+        self._logger = get_logger(__name__)
+        if self._limit < streams_per_symbol:
+            raise ValueError(f"{venue} stream limit {self._limit} cannot hold a single symbol")
+
+    @property
+    def primary(self) -> ShardClient:
+        """Shard 0, created on first use; it signs in for the private streams."""
+        return self._shards[0] if 0 in self._shards else self._open(0, [])
+
+    @property
+    def symbols(self) -> List[str]:
+        streamed = [symbol for index in sorted(self._shards) for symbol in self._shards[index].symbols]
+        return streamed + [symbol for symbol in self._pending if symbol not in streamed]
+
+    def _open(self, index: int, symbols: List[str]) -> ShardClient:
+        client = self._factory(index, symbols)
+        self._shards[index] = client
+        METRICS.set("ws_pool_connections", len(self._shards), venue=self._venue)
+        return client
+
+    def _room(self, client: ShardClient) -> int:
+        return (self._limit - client.stream_count) // self._streams_per_symbol
+
+    def _next_index(self) -> Optional[int]:
+        if len(self._shards) >= self._config.max_connections:
+            return None
+        return next(i for i in range(len(self._shards) + 1) if i not in self._shards)
+
+    async def start(self) -> None:
+        if self._started:
+            return
+        self._started = True
+        pending, self._pending = self._pending, []
+        if 0 not in self._shards:
+            self._open(0, [])
+        await self._place(pending)
+        for index in sorted(self._shards):
+            await self._shards[index].start()
+
+    async def stop(self) -> None:
+        for index in sorted(self._shards, reverse=True):
+            await self._shards[index].stop()
+        self._started = False
+
+    async def _place(self, symbols: List[str]) -> List[str]:
+        placed: List[str] = []
+        for symbol in symbols:
+            candidates = [(self._room(client), -index) for index, client in self._shards.items()]
+            best = max(candidates, default=(0, 0))
+            if best[0] > 0:
+                placed += await self._shards[-best[1]].add_symbols([symbol])
+                continue
+            index = self._next_index()
+            if index is None:
+                self._logger.info(
+                    "ws_pool_full",
+                    extra={"venue": self._venue, "symbol": symbol, "connections": len(self._shards), "limit": self._limit},
+                )
+                METRICS.inc("ws_pool_refused", venue=self._venue)
+                continue
+            client = self._open(index, [symbol])
+            if self._started:
+                await client.start()
+            self._logger.info("ws_pool_shard_opened", extra={"venue": self._venue, "shard": index})
+            placed.append(symbol)
+        return placed
+
+    async def add_symbols(self, symbols: Iterable[str]) -> List[str]:
+        """Stream more venue symbols; returns those that were new and found room."""
+        current = set(self.symbols)
+        added = [s for s in dict.fromkeys(symbols) if s not in current]
+        if not self._started:
+            self._pending.extend(added)
+            return added
+        return await self._place(added)
+
+    async def remove_symbols(self, symbols: Iterable[str]) -> List[str]:
+        """Stop streaming venue symbols; returns those that were streamed."""
+        wanted = list(dict.fromkeys(symbols))
+        removed = [s for s in wanted if s in self._pending]
+        self._pending = [s for s in self._pending if s not in removed]
+        if not self._started:
+            return removed
+        for client in self._shards.values():
+            removed += await client.remove_symbols([s for s in wanted if s in client.symbols])
+        await self._close_empty()
+        if self._config.rebalance:
+            await self.rebalance()
+        return removed
+
+    async def _close_empty(self) -> None:
+        for index in [i for i, client in self._shards.items() if i != 0 and not client.symbols]:
+            await self._shards.pop(index).stop()
+            self._logger.info("ws_pool_shard_closed", extra={"venue": self._venue, "shard": index})
+        METRICS.set("ws_pool_connections", len(self._shards), venue=self._venue)
+
+    async def rebalance(self) -> int:
+        """Fold extra shards into the others while their symbols fit; returns the symbols moved."""
+        moved = 0
+        while len(self._shards) > 1:
+            index = min((i for i in self._shards if i != 0), key=lambda i: (len(self._shards[i].symbols), -i))
+            source = self._shards[index]
+            symbols = source.symbols
+            others = [client for i, client in self._shards.items() if i != index]
+            if len(symbols) > sum(max(0, self._room(client)) for client in others):
+                break
+            for symbol in symbols:
+                target = max(others, key=self._room)
+                # Make before break: the new subscription is live before the old one goes
+                await target.add_symbols([symbol])
+                await source.remove_symbols([symbol])
+                moved += 1
+            await self._close_empty()
+        if moved:
+            self._logger.info("ws_pool_rebalanced", extra={"venue": self._venue, "moved": moved, "connections": len(self._shards)})
+        return moved
+
+    def health(self) -> List[Dict[str, Any]]:
+        """Per-connection view: shard, market feed state, symbols and streams against the limit."""
+        out: List[Dict[str, Any]] = []
+        for index in sorted(self._shards):
+            client = self._shards[index]
+            state = client.market_state
+            out.append(
+                {
+                    "shard": index,
+                    "state": state.value if state is not None else None,
+                    "symbols": len(client.symbols),
+                    "streams": client.stream_count,
+                    "limit": self._limit,
+                }
+            )
+        return out
+
+
+__all__ = [
+    "DEFAULT_STREAM_LIMIT",
+    "ShardClient",
+    "ShardFactory",
+    "VENUE_STREAM_LIMITS",
+    "WsConnectionPool",
+    "WsPoolConfig",
+]
@@ -0,0 +1,150 @@
+from __future__ import annotations
+
+import time
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import Any, Dict, List, Optional, Protocol
+
+import httpx
+
+from .metrics import METRICS
+from ..utils.logging import get_logger
+
+
+class AlertLevel(str, Enum):
+    INFO = "info"
+    WARNING = "warning"
+    CRITICAL = "critical"
+
+
+_LEVEL_ORDER = {AlertLevel.INFO: 0, AlertLevel.WARNING: 1, AlertLevel.CRITICAL: 2}
+
+
+@dataclass(slots=True)
+class Alert:
+    level: AlertLevel
+    title: str
+    body: str = ""
+    fields: Dict[str, Any] = field(default_factory=dict)
+    ts: float = field(default_factory=time.time)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "level": self.level.value,
+            "title": self.title,
+            "body": self.body,
+            "fields": self.fields,
+            "ts": self.ts,
+        }
+
+
+@dataclass(slots=True)
+class AlertConfig:
+    webhook_url: Optional[str] = None
+    bearer_token: Optional[str] = None
+    timeout_secs: float = 5.0
+    min_level: AlertLevel = AlertLevel.INFO
+
+
+class AlertSink(Protocol):
+    async def send(self, alert: Alert) -> None:  # pragma: no cover - protocol
+        ...
+
+
+class AlertDeliveryError(RuntimeError):
+    """A sink reached its endpoint but the alert was not accepted."""
+
+
+class LogAlertSink:
+    """Always-on sink writing alerts into the structured log."""
+
+    def __init__(self) -> None:
+        self._logger = get_logger("xbot.alerts")
+
+    async def send(self, alert: Alert) -> None:
+        log = self._logger.warning if alert.level != AlertLevel.INFO else self._logger.info
+        log("alert", extra=alert.to_dict())
+
+
+class WebhookAlertSink:
+    """Posts alerts as JSON to an HTTP endpoint (Slack/Discord relays, ops tooling)."""
+
+    def __init__(self, *, url: str, bearer_token: Optional[str] = None, timeout_secs: float = 5.0) -> None:
+        self._url = url
+        self._token = bearer_token
+        self._client = httpx.AsyncClient(timeout=timeout_secs)
+
+    async def send(self, alert: Alert) -> None:
+        headers = {"Content-Type": "application/json"}
+        if self._token:
+            headers["Authorization"] = f"Bearer {self._token}"
+        payload = alert.to_dict()
+        payload["text"] = f"[{alert.level.value.upper()}] {alert.title}\n{alert.body}".rstrip()
+        resp = await self._client.post(self._url, json=payload, headers=headers)
+        if not 200 <= resp.status_code < 300:
+            raise AlertDeliveryError(f"webhook returned HTTP {resp.status_code}")
+
+    async def aclose(self) -> None:
+        await self._client.aclose()
+
+
+class AlertManager:
+    """Fans alerts out to every sink; a failing sink never affects the others or the caller.
+
+    Failures are logged and counted in `alert_failures` per sink, so a dead
+    webhook shows up on the metrics endpoint rather than only in the log.
+    """
+
+    def __init__(self, sinks: Optional[List[AlertSink]] = None, *, min_level: AlertLevel = AlertLevel.INFO) -> None:
+        self._sinks: List[AlertSink] = sinks if sinks is not None else [LogAlertSink()]
+        self._min_level = min_level
+        self._logger = get_logger(__name__)
+
+    def add_sink(self, sink: AlertSink) -> None:
+        self._sinks.append(sink)
+
+    async def send(self, alert: Alert) -> None:
+        if _LEVEL_ORDER[alert.level] < _LEVEL_ORDER[self._min_level]:
+            return
+        for sink in list(self._sinks):
+            try:
+                await sink.send(alert)
+            except Exception as exc:
+                METRICS.inc("alert_failures", sink=type(sink).__name__, level=alert.level.value)
+                self._logger.warning(
+                    "alert_sink_error", extra={"sink": type(sink).__name__, "title": alert.title, "error": str(exc)}
+                )
+
+    async def notify(self, level: AlertLevel, title: str, body: str = "", **fields: Any) -> None:
+        await self.send(Alert(level=level, title=title, body=body, fields=fields))
+
+    async def aclose(self) -> None:
+        for sink in self._sinks:
+            closer = getattr(sink, "aclose", None)
+            if closer is not None:
+                try:
+                    await closer()
+                except Exception:
+                    pass
+
+
+def build_alert_manager(config: Optional[AlertConfig]) -> AlertManager:
+    manager = AlertManager(min_level=config.min_level if config else AlertLevel.INFO)
+    if config and config.webhook_url:
+        manager.add_sink(
+            WebhookAlertSink(url=config.webhook_url, bearer_token=config.bearer_token, timeout_secs=config.timeout_secs)
+        )
+    return manager
+
+
+__all__ = [
+    "Alert",
+    "AlertLevel",
+    "AlertConfig",
+    "AlertDeliveryError",
+    "AlertSink",
+    "AlertManager",
+    "LogAlertSink",
+    "WebhookAlertSink",
+    "build_alert_manager",
+]
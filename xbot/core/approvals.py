@@ -0,0 +1,218 @@
+from __future__ import annotations
+
+import asyncio
+import hmac
+import secrets
+import time
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import Any, Awaitable, Callable, Dict, List, Optional
+
+from .alerts import AlertLevel, AlertManager
+from .audit import AUDIT, APPROVAL_DENIED, APPROVAL_EXECUTED, APPROVAL_REJECTED, APPROVAL_REQUESTED
+from ..utils.logging import get_logger
+
+WITHDRAW = "withdraw"
+FLATTEN_ALL = "flatten_all"
+RAISE_RISK_LIMITS = "raise_risk_limits"
+
+SECOND_OPERATOR = "second_operator"
+CONFIRMATION_CODE = "confirmation_code"
+
+
+@dataclass(slots=True)
+class ApprovalConfig:
+    enabled: bool = True
+    # second_operator: another operator's token must approve; confirmation_code: the code sent to the alert sinks
+    mode: str = SECOND_OPERATOR
+    actions: List[str] = field(default_factory=lambda: [WITHDRAW, FLATTEN_ALL, RAISE_RISK_LIMITS])
+    ttl_secs: float = 300.0
+
+
+class ApprovalStatus(str, Enum):
+    PENDING = "pending"
+    APPROVED = "approved"  # running
+    EXECUTED = "executed"
+    FAILED = "failed"
+    REJECTED = "rejected"
+    EXPIRED = "expired"
+
+
+class ApprovalDenied(ValueError):
+    pass
+
+
+@dataclass(slots=True)
+class PendingApproval:
+    approval_id: str
+    action: str
+    summary: str
+    requested_by: str
+    execute: Callable[[], Awaitable[Any]] = field(repr=False)
+    code: str = field(default="", repr=False)
+    created_at: float = field(default_factory=time.time)
+    expires_at: float = 0.0
+    status: ApprovalStatus = ApprovalStatus.PENDING
+    decided_by: str = ""
+    result: Any = None
+    error: Optional[str] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "approval_id": self.approval_id,
+            "action": self.action,
+            "summary": self.summary,
+            "requested_by": self.requested_by,
+            "created_at": self.created_at,
+            "expires_at": self.expires_at,
+            "status": self.status.value,
+            "decided_by": self.decided_by,
+            "result": self.result,
+            "error": self.error,
+        }
+
+
+class ApprovalGate:
+    """Holds dangerous operator actions until a second party signs off.
+
+    `submit` runs an action straight away when it is not gated and parks it
+    otherwise. In `second_operator` mode only an operator other than the one
+    who asked can `approve`. In `confirmation_code` mode the approval must
+    carry a one-time code that only goes out through the alert sinks, so it
+    proves access to that channel rather than to the admin token. Parked
+    actions expire after `ttl_secs` and live in memory only.
+    """
+
+    def __init__(self, config: Optional[ApprovalConfig] = None, *, alerts: Optional[AlertManager] = None) -> None:
+        self._config = config or ApprovalConfig()
+        if self._config.mode not in (SECOND_OPERATOR, CONFIRMATION_CODE):
+            raise ValueError(f"unknown approval mode {self._config.mode}")
+        self._alerts = alerts
+        self._pending: Dict[str, PendingApproval] = {}
+        self._lock = asyncio.Lock()
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> ApprovalConfig:
+        return self._config
+
+    def required(self, action: str) -> bool:
+        return self._config.enabled and action in self._config.actions
+
+    async def submit(
+        self, action: str, summary: str, execute: Callable[[], Awaitable[Any]], *, requested_by: str
+    ) -> PendingApproval:
+        """Run `execute` now if `action` is not gated, else park it for approval."""
+        now = time.time()
+        pending = PendingApproval(
+            approval_id=secrets.token_hex(8),
+            action=action,
+            summary=summary,
+            requested_by=requested_by,
+            execute=execute,
+            created_at=now,
+            expires_at=now + self._config.ttl_secs,
+        )
+        if not self.required(action):
+            await self._run(pending, decided_by=requested_by)
+            return pending
+        if self._config.mode == CONFIRMATION_CODE:
+            pending.code = f"{secrets.randbelow(10**6):06d}"
+        async with self._lock:
+            self._pending[pending.approval_id] = pending
+        self._logger.warning("approval_requested", extra=pending.to_dict())
+        AUDIT.record(APPROVAL_REQUESTED, **_audited(pending))
+        await self._notify(pending)
+        return pending
+
+    async def approve(self, approval_id: str, *, operator: str, code: Optional[str] = None) -> PendingApproval:
+        async with self._lock:
+            pending = self._pending.get(approval_id)
+            if pending is None:
+                raise KeyError(approval_id)
+            self._expire(pending)
+            if pending.status != ApprovalStatus.PENDING:
+                raise ApprovalDenied(f"approval {approval_id} is {pending.status.value}")
+            if self._config.mode == SECOND_OPERATOR and operator == pending.requested_by:
+                self._denied(pending, operator, "requester cannot approve their own action")
+            if self._config.mode == CONFIRMATION_CODE and not hmac.compare_digest(
+                (code or "").encode(), pending.code.encode()
+            ):
+                self._denied(pending, operator, "wrong confirmation code")
+            # Claimed under the lock so two approvals cannot both execute
+            pending.status = ApprovalStatus.APPROVED
+        await self._run(pending, decided_by=operator)
+        return pending
+
+    async def reject(self, approval_id: str, *, operator: str) -> PendingApproval:
+        async with self._lock:
+            pending = self._pending.get(approval_id)
+            if pending is None:
+                raise KeyError(approval_id)
+            self._expire(pending)
+            if pending.status == ApprovalStatus.PENDING:
+                pending.status = ApprovalStatus.REJECTED
+                pending.decided_by = operator
+                self._logger.info("approval_rejected", extra=pending.to_dict())
+                AUDIT.record(APPROVAL_REJECTED, **_audited(pending))
+            return pending
+
+    def approvals(self) -> List[PendingApproval]:
+        for pending in self._pending.values():
+            self._expire(pending)
+        return sorted(self._pending.values(), key=lambda p: p.created_at, reverse=True)
+
+    def _denied(self, pending: PendingApproval, operator: str, reason: str) -> None:
+        self._logger.warning(
+            "approval_denied", extra={"approval_id": pending.approval_id, "operator": operator, "reason": reason}
+        )
+        AUDIT.record(APPROVAL_DENIED, operator=operator, reason=reason, **_audited(pending))
+        raise ApprovalDenied(reason)
+
+    async def _run(self, pending: PendingApproval, *, decided_by: str) -> None:
+        pending.decided_by = decided_by
+        try:
+            pending.result = await pending.execute()
+            pending.status = ApprovalStatus.EXECUTED
+        except Exception as exc:
+            pending.status = ApprovalStatus.FAILED
+            pending.error = str(exc)
+        level = "warning" if pending.status == ApprovalStatus.FAILED else "info"
+        getattr(self._logger, level)("approval_executed", extra=pending.to_dict())
+        AUDIT.record(APPROVAL_EXECUTED, **_audited(pending))
+
+    def _expire(self, pending: PendingApproval) -> None:
+        if pending.status == ApprovalStatus.PENDING and time.time() > pending.expires_at:
+            pending.status = ApprovalStatus.EXPIRED
+
+    async def _notify(self, pending: PendingApproval) -> None:
+        if self._alerts is None:
+            return
+        if self._config.mode == CONFIRMATION_CODE:
+            how = f"confirm with code {pending.code}"
+        else:
+            how = f"needs approval by an operator other than {pending.requested_by}"
+        body = f"{pending.summary}; {how} before it expires in {int(self._config.ttl_secs)}s"
+        await self._alerts.notify(
+            AlertLevel.WARNING, "Admin action awaiting approval", body, approval_id=pending.approval_id, action=pending.action
+        )
+
+
+def _audited(pending: PendingApproval) -> Dict[str, Any]:
+    row = pending.to_dict()
+    row.pop("result", None)
+    return row
+
+
+__all__ = [
+    "ApprovalConfig",
+    "ApprovalDenied",
+    "ApprovalGate",
+    "ApprovalStatus",
+    "CONFIRMATION_CODE",
+    "FLATTEN_ALL",
+    "PendingApproval",
+    "RAISE_RISK_LIMITS",
+    "SECOND_OPERATOR",
+    "WITHDRAW",
+]
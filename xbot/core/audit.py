@@ -0,0 +1,120 @@
+from __future__ import annotations
+
+import time
+from typing import Any, Dict, Iterable, List, Mapping, Optional, Set
+
+from ..storage.base import AUDIT_LOG, StorageWriter
+
+# Row kinds, in the order they usually happen for one order
+COMMAND_RECEIVED = "command_received"
+COMMAND_DROPPED = "command_dropped"
+COMMAND_HANDLED = "command_handled"
+COMMAND_FAILED = "command_failed"
+RISK_ACCEPTED = "risk_accepted"
+RISK_REJECTED = "risk_rejected"
+ORDER_REPLAYED = "order_replayed"
+ORDER_SENT = "order_sent"
+CANCEL_SENT = "cancel_sent"
+EXCHANGE_RESPONSE = "exchange_response"
+OPERATOR_ACTION = "operator_action"
+APPROVAL_REQUESTED = "approval_requested"
+APPROVAL_DENIED = "approval_denied"
+APPROVAL_REJECTED = "approval_rejected"
+APPROVAL_EXECUTED = "approval_executed"
+
+_LINKS = ("command_id", "trace_id", "client_order_index")
+
+
+class AuditLog:
+    """Append-only record of what the bot was asked to do and what it decided.
+
+    Commands, risk decisions, orders sent, venue responses and operator
+    actions each become one `audit_log` row carrying whichever of
+    `command_id`, `trace_id` and `client_order_index` are known at that
+    point, which is what `trail` follows to put one order's story back
+    together. Recording is a no-op until a writer is attached, and never
+    blocks or raises.
+    """
+
+    def __init__(self) -> None:
+        self._writer: Optional[StorageWriter] = None
+
+    def attach(self, writer: Optional[StorageWriter]) -> None:
+        self._writer = writer
+
+    @property
+    def enabled(self) -> bool:
+        return self._writer is not None
+
+    def record(self, kind: str, *, venue: str = "", **fields: Any) -> None:
+        if self._writer is None:
+            return
+        row: Dict[str, Any] = {"ts": time.time(), "kind": kind, "venue": venue}
+        row.update({key: value for key, value in fields.items() if value is not None})
+        self._writer.enqueue(AUDIT_LOG, row)
+
+
+AUDIT = AuditLog()
+
+
+def trail(
+    rows: Iterable[Mapping[str, Any]],
+    *,
+    client_order_index: Optional[int] = None,
+    trace_id: Optional[str] = None,
+    command_id: Optional[str] = None,
+) -> List[Mapping[str, Any]]:
+    """Rows linked to an order, trace or command, oldest first.
+
+    Links are followed transitively: starting from an order index this finds
+    its trace, the command that carried that trace, and every row either one
+    touched, including orders the command placed and was refused. Order
+    events from `order_events` can be passed in alongside audit rows.
+    """
+    rows = list(rows)
+    keys: Dict[str, Set[str]] = {name: set() for name in _LINKS}
+    for name, value in (("command_id", command_id), ("trace_id", trace_id), ("client_order_index", client_order_index)):
+        if value is not None:
+            keys[name].add(str(value))
+    keys["trace_id"].update(keys["command_id"])
+    keys["command_id"].update(keys["trace_id"])
+    matched: Dict[int, Mapping[str, Any]] = {}
+    grew = True
+    while grew:
+        grew = False
+        for idx, row in enumerate(rows):
+            if idx in matched:
+                continue
+            if not any(row.get(name) is not None and str(row[name]) in keys[name] for name in _LINKS):
+                continue
+            matched[idx] = row
+            grew = True
+            for name in _LINKS:
+                if row.get(name) is not None:
+                    keys[name].add(str(row[name]))
+            # Command ids double as the trace of the orders they place
+            keys["trace_id"].update(keys["command_id"])
+            keys["command_id"].update(keys["trace_id"])
+    return sorted(matched.values(), key=lambda row: float(row.get("ts") or 0.0))
+
+
+__all__ = [
+    "APPROVAL_DENIED",
+    "APPROVAL_EXECUTED",
+    "APPROVAL_REJECTED",
+    "APPROVAL_REQUESTED",
+    "AUDIT",
+    "AuditLog",
+    "CANCEL_SENT",
+    "COMMAND_DROPPED",
+    "COMMAND_FAILED",
+    "COMMAND_HANDLED",
+    "COMMAND_RECEIVED",
+    "EXCHANGE_RESPONSE",
+    "OPERATOR_ACTION",
+    "ORDER_REPLAYED",
+    "ORDER_SENT",
+    "RISK_ACCEPTED",
+    "RISK_REJECTED",
+    "trail",
+]
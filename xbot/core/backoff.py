@@ -0,0 +1,119 @@
+from __future__ import annotations
+
+import asyncio
+from dataclasses import dataclass, field
+from typing import Dict, Optional
+
+from .metrics import METRICS
+from ..utils.logging import get_logger
+
+_RATE_LIMIT_MARKERS = ("429", "rate limit", "ratelimit", "too many requests")
+
+
+@dataclass(slots=True)
+class BackoffConfig:
+    factor: float = 2.0  # interval multiplier per failed cycle
+    rate_limit_factor: float = 4.0  # multiplier when the venue rate limited us
+    max_secs: float = 600.0
+
+
+@dataclass(slots=True)
+class PollingConfig:
+    backoff: BackoffConfig = field(default_factory=BackoffConfig)
+    intervals: Dict[str, Dict[str, float]] = field(default_factory=dict)  # venue -> poll name -> seconds
+
+    def interval(self, venue: str, poll: str, default: float) -> float:
+        return self.intervals.get(venue.lower(), {}).get(poll, default)
+
+
+def is_rate_limited(exc: BaseException) -> bool:
+    response = getattr(exc, "response", None)
+    if getattr(response, "status_code", None) == 429:
+        return True
+    text = str(exc).lower()
+    return any(marker in text for marker in _RATE_LIMIT_MARKERS)
+
+
+def retry_after_secs(exc: BaseException) -> Optional[float]:
+    headers = getattr(getattr(exc, "response", None), "headers", None)
+    if not headers:
+        return None
+    try:
+        return float(headers.get("Retry-After"))
+    except (TypeError, ValueError):
+        return None
+
+
+class AdaptiveInterval:
+    """Poll cadence that stretches while a REST loop is failing.
+
+    `failure(exc)` multiplies the current interval by `factor` (or
+    `rate_limit_factor` on a 429 / rate-limit error, honouring Retry-After)
+    up to `max_secs`; the first `success()` drops straight back to the
+    configured `base_secs`. The current value is published as the
+    `poll_interval_secs{venue,poll}` gauge.
+    """
+
+    def __init__(self, venue: str, poll: str, base_secs: float, config: Optional[BackoffConfig] = None) -> None:
+        self.venue = venue
+        self.poll = poll
+        self.base_secs = base_secs
+        self._config = config or BackoffConfig()
+        self._current = base_secs
+        self.failures = 0
+        self._logger = get_logger(__name__)
+
+    @property
+    def current(self) -> float:
+        return self._current
+
+    @property
+    def backing_off(self) -> bool:
+        return self.failures > 0
+
+    def success(self) -> float:
+        if self.failures:
+            self._logger.info(
+                "poll_resumed", extra={"venue": self.venue, "poll": self.poll, "failures": self.failures}
+            )
+        self.failures = 0
+        self._set(self.base_secs)
+        return self._current
+
+    def failure(self, exc: Optional[BaseException] = None) -> float:
+        self.failures += 1
+        limited = exc is not None and is_rate_limited(exc)
+        factor = self._config.rate_limit_factor if limited else self._config.factor
+        target = self._current * factor
+        retry_after = retry_after_secs(exc) if exc is not None else None
+        if retry_after is not None:
+            target = max(target, retry_after)
+        ceiling = max(self.base_secs, self._config.max_secs)
+        self._set(min(ceiling, target))
+        self._logger.info(
+            "poll_backoff",
+            extra={
+                "venue": self.venue,
+                "poll": self.poll,
+                "failures": self.failures,
+                "rate_limited": limited,
+                "interval_secs": self._current,
+            },
+        )
+        return self._current
+
+    async def sleep(self) -> None:
+        await asyncio.sleep(self._current)
+
+    def _set(self, value: float) -> None:
+        self._current = value
+        METRICS.set("poll_interval_secs", value, venue=self.venue, poll=self.poll)
+
+
+__all__ = [
+    "AdaptiveInterval",
+    "BackoffConfig",
+    "PollingConfig",
+    "is_rate_limited",
+    "retry_after_secs",
+]
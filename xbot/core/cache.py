@@ -4,7 +4,10 @@ import asyncio
 import time
 from collections import defaultdict, deque
 from dataclasses import dataclass
-from typing import Deque, Dict, Tuple, Optional
+from typing import Deque, Dict, Iterable, Tuple, Optional
+
+from .events import ConnectionState, EventKind, EventStream
+from .orderbook import Level, OrderBook
 
 
 @dataclass
@@ -15,9 +18,14 @@ class PositionInfo:
 
 
 class MarketCache:
-    def __init__(self) -> None:
+    def __init__(self, *, events: Optional[EventStream] = None, source: str = "cache", metrics_depth: int = 5) -> None:
         self._lock = asyncio.Lock()
+        self.events = events
+        self.source = source
+        self.metrics_depth = metrics_depth  # levels per side behind the depth-weighted book metrics
+        self.connections: Dict[str, Tuple[ConnectionState, float]] = {}
         self.orderbooks: Dict[str, Tuple[float | None, float | None, float]] = {}
+        self.books: Dict[str, OrderBook] = {}  # full depth, for feeds that maintain one
         self.trades: Dict[str, Deque[dict]] = defaultdict(lambda: deque(maxlen=100))
         self.positions: Dict[str, PositionInfo] = {}
         self.balances: Dict[str, Tuple[float, float, float]] = {}
@@ -25,19 +33,55 @@ class MarketCache:
     async def set_top(self, symbol: str, bid: float | None, ask: float | None) -> None:
         async with self._lock:
             self.orderbooks[symbol] = (bid, ask, time.time())
+        self._emit(EventKind.MARKET_DATA, {"bid": bid, "ask": ask}, symbol)
+
+    async def set_book(self, symbol: str, bids: Dict[float, float], asks: Dict[float, float]) -> None:
+        """Store a copy of a locally maintained book and publish its top like `set_top`."""
+        book = OrderBook(symbol=symbol, bids=dict(bids), asks=dict(asks), metrics_depth=self.metrics_depth)
+        async with self._lock:
+            self.books[symbol] = book
+        await self._publish_book(book)
+
+    async def apply_book(
+        self, symbol: str, bids: Iterable[Level], asks: Iterable[Level], *, reset: bool = False
+    ) -> OrderBook:
+        """Apply a book delta (absolute sizes, zero removes) in place; `reset` starts from an empty book.
+
+        Unlike `set_book` nothing is copied, and the derived metrics are only
+        recomputed when the delta reaches the first `metrics_depth` levels.
+        """
+        async with self._lock:
+            book = self.books.get(symbol)
+            if book is None:
+                book = self.books[symbol] = OrderBook(symbol=symbol, metrics_depth=self.metrics_depth)
+            elif reset:
+                book.clear()
+            book.apply("bids", bids)
+            book.apply("asks", asks)
+        await self._publish_book(book)
+        return book
+
+    async def _publish_book(self, book: OrderBook) -> None:
+        # Top of book plus the derived fields, on the same MARKET_DATA event
+        async with self._lock:
+            self.orderbooks[book.symbol] = (book.best_bid, book.best_ask, time.time())
+        self._emit(EventKind.MARKET_DATA, {"bid": book.best_bid, "ask": book.best_ask, **book.metrics().to_dict()}, book.symbol)
 
     async def add_trade(self, symbol: str, trade: dict) -> None:
         async with self._lock:
             self.trades[symbol].append(trade)
+        self._emit(EventKind.TRADE, trade, symbol)
 
     async def set_position(self, symbol: str, pos: float) -> None:
         async with self._lock:
             self.positions[symbol] = PositionInfo(symbol=symbol, position=pos, ts=time.time())
+        self._emit(EventKind.POSITION_UPDATE, {"position": pos}, symbol)
 
     async def set_balance(self, asset: str, total: float, available: Optional[float] = None) -> None:
         async with self._lock:
             avail = available if available is not None else total
             self.balances[asset.upper()] = (total, avail, time.time())
+        self._emit(EventKind.BALANCE_UPDATE, {"asset": asset.upper(), "total": total, "available": avail})
 
     async def set_balances(self, payload: Dict[str, Tuple[float, float] | float]) -> None:
         # payload: {"USDC": (total, available)} or {"USDC": total}
@@ -49,6 +93,25 @@ class MarketCache:
                 else:
                     total, available = float(v), float(v)
                 self.balances[k.upper()] = (total, available, ts)
+        for k in payload:
+            total, available, _ts = self.balances[k.upper()]
+            self._emit(EventKind.BALANCE_UPDATE, {"asset": k.upper(), "total": total, "available": available})
+
+    async def set_connection(self, feed: str, state: ConnectionState, **info: object) -> None:
+        async with self._lock:
+            self.connections[feed] = (state, time.time())
+        self._emit(EventKind.CONNECTION_STATUS, {"feed": feed, "state": state.value, **info})
+
+    def connection_state(self, feed: str) -> Optional[ConnectionState]:
+        entry = self.connections.get(feed)
+        return entry[0] if entry else None
+
+    def is_live(self, feed: str = "market") -> bool:
+        return self.connection_state(feed) in (ConnectionState.CONNECTED, ConnectionState.RESUBSCRIBED)
+
+    def _emit(self, kind: EventKind, data: dict, symbol: Optional[str] = None) -> None:
+        if self.events is not None:
+            self.events.emit(kind, self.source, data, symbol=symbol)
 
     async def snapshot_positions(self) -> Dict[str, dict]:
         async with self._lock:
@@ -0,0 +1,300 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+import uuid
+from dataclasses import dataclass, field
+from enum import Enum
+from collections import OrderedDict
+from typing import Any, Awaitable, Callable, Dict, List, Optional
+
+from ..utils.logging import get_logger
+from .audit import AUDIT, COMMAND_DROPPED, COMMAND_FAILED, COMMAND_HANDLED, COMMAND_RECEIVED
+from .metrics import METRICS, MetricsRegistry
+
+
+@dataclass(slots=True)
+class TradingCommand:
+    venue: str
+    kind: str
+    payload: Dict[str, Any] = field(default_factory=dict)
+    policy: Optional[Any] = None  # execution policy for order kinds, e.g. execution.maker_first.MakerFirstPolicy
+    command_id: str = field(default_factory=lambda: uuid.uuid4().hex)
+    ts: float = field(default_factory=time.time)
+    time_in_force_ms: Optional[int] = None  # submit_limit only: cancel whatever is still resting after this long
+
+
+@dataclass(slots=True)
+class TradingResult:
+    """Outcome reported for a command after it was handled, e.g. a resting order expiring."""
+
+    command_id: Optional[str]
+    venue: str
+    symbol: str
+    status: str  # expired
+    client_order_index: int
+    size_i: int
+    filled_i: int
+    ts: float = field(default_factory=time.time)
+
+    @property
+    def remaining_i(self) -> int:
+        return max(0, self.size_i - self.filled_i)
+
+    @property
+    def partial(self) -> bool:
+        return 0 < self.filled_i < self.size_i
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "command_id": self.command_id,
+            "venue": self.venue,
+            "symbol": self.symbol,
+            "status": self.status,
+            "client_order_index": self.client_order_index,
+            "size_i": self.size_i,
+            "filled_i": self.filled_i,
+            "remaining_i": self.remaining_i,
+            "partial": self.partial,
+            "ts": self.ts,
+        }
+
+
+CommandHandler = Callable[[TradingCommand], Awaitable[Any]]
+
+
+def _audit(kind: str, command: TradingCommand, **fields: Any) -> None:
+    AUDIT.record(
+        kind,
+        venue=command.venue,
+        command_id=command.command_id,
+        command_kind=command.kind,
+        symbol=command.payload.get("symbol"),
+        **fields,
+    )
+
+
+class BackpressurePolicy(str, Enum):
+    BLOCK = "block"  # publisher waits for room
+    DROP_NEWEST = "drop_newest"  # reject the incoming command
+    DROP_OLDEST = "drop_oldest"  # evict the oldest queued command
+
+
+class CommandBusClosed(RuntimeError):
+    pass
+
+
+class UnroutableCommand(KeyError):
+    pass
+
+
+@dataclass(slots=True)
+class CommandBusConfig:
+    capacity: int = 256
+    policy: BackpressurePolicy = BackpressurePolicy.BLOCK
+    dedupe_ttl_secs: float = 600.0  # how long an accepted command_id suppresses replays; 0 disables
+
+
+class _Route:
+    __slots__ = ("venue", "queue", "handler", "policy", "task")
+
+    def __init__(self, venue: str, handler: CommandHandler, capacity: int, policy: BackpressurePolicy) -> None:
+        self.venue = venue
+        self.queue: asyncio.Queue[TradingCommand] = asyncio.Queue(maxsize=capacity)
+        self.handler = handler
+        self.policy = policy
+        self.task: Optional[asyncio.Task] = None
+
+
+class CommandBus:
+    """Routes trading commands to one bounded queue per venue.
+
+    Each venue has a single consumer task, so commands for a venue are handled
+    in publish order and a slow venue never delays the others. What happens
+    when a queue is full is an explicit `BackpressurePolicy`; every drop is
+    counted in metrics (`command_bus_dropped{venue,reason}`) rather than lost
+    silently.
+
+    A `command_id` accepted within the last `dedupe_ttl_secs` is dropped as a
+    duplicate, so a relay that replays commands after reconnecting cannot
+    trade twice. Ids of commands that were evicted, discarded or failed are
+    forgotten, leaving them free to retry.
+    """
+
+    def __init__(self, config: Optional[CommandBusConfig] = None, *, metrics: Optional[MetricsRegistry] = None) -> None:
+        self._config = config or CommandBusConfig()
+        self._metrics = metrics or METRICS
+        self._routes: Dict[str, _Route] = {}
+        self._seen: "OrderedDict[str, float]" = OrderedDict()  # command_id -> accepted at (monotonic)
+        self._closed = False
+        self._started = False
+        self._logger = get_logger(__name__)
+
+    @property
+    def closed(self) -> bool:
+        return self._closed
+
+    def venues(self) -> List[str]:
+        return list(self._routes)
+
+    def register(
+        self,
+        venue: str,
+        handler: CommandHandler,
+        *,
+        capacity: Optional[int] = None,
+        policy: Optional[BackpressurePolicy] = None,
+    ) -> None:
+        venue = venue.lower()
+        if venue in self._routes:
+            raise ValueError(f"route already registered for venue {venue}")
+        route = _Route(venue, handler, capacity or self._config.capacity, policy or self._config.policy)
+        self._routes[venue] = route
+        if self._started:
+            route.task = asyncio.create_task(self._consume(route), name=f"command-bus-{venue}")
+
+    async def publish(self, command: TradingCommand) -> bool:
+        """Queue a command for its venue; returns False if it was dropped by policy."""
+        _audit(COMMAND_RECEIVED, command, payload=command.payload, policy=command.policy)
+        route = self._route_for(command)
+        if self._duplicate(route, command):
+            return False
+        if route.policy is BackpressurePolicy.BLOCK:
+            self._remember(command)  # before waiting, so a replay published meanwhile is caught
+            try:
+                await route.queue.put(command)
+            except BaseException:
+                self._forget(command)
+                raise
+            self._accepted(route)
+            return True
+        return self._offer(route, command)
+
+    def try_publish(self, command: TradingCommand) -> bool:
+        """Non-blocking publish; a full BLOCK route counts as a drop."""
+        _audit(COMMAND_RECEIVED, command, payload=command.payload, policy=command.policy)
+        route = self._route_for(command)
+        if self._duplicate(route, command):
+            return False
+        return self._offer(route, command)
+
+    def _route_for(self, command: TradingCommand) -> _Route:
+        if self._closed:
+            _audit(COMMAND_DROPPED, command, reason="closed")
+            raise CommandBusClosed("command bus is closed")
+        route = self._routes.get(command.venue.lower())
+        if route is None:
+            _audit(COMMAND_DROPPED, command, reason="unroutable")
+            self._metrics.inc("command_bus_dropped", venue=command.venue, reason="unroutable")
+            raise UnroutableCommand(command.venue)
+        return route
+
+    def _offer(self, route: _Route, command: TradingCommand) -> bool:
+        if route.queue.full():
+            if route.policy is BackpressurePolicy.DROP_OLDEST:
+                evicted = route.queue.get_nowait()
+                route.queue.task_done()
+                self._forget(evicted)
+                self._dropped(route, evicted, "evicted")
+            else:
+                self._dropped(route, command, "full")
+                return False
+        route.queue.put_nowait(command)
+        self._remember(command)
+        self._accepted(route)
+        return True
+
+    def _duplicate(self, route: _Route, command: TradingCommand) -> bool:
+        ttl = self._config.dedupe_ttl_secs
+        if ttl <= 0:
+            return False
+        now = time.monotonic()
+        while self._seen:
+            seen_at = next(iter(self._seen.values()))
+            if now - seen_at < ttl:
+                break
+            self._seen.popitem(last=False)
+        if command.command_id not in self._seen:
+            return False
+        self._dropped(route, command, "duplicate")
+        return True
+
+    def _remember(self, command: TradingCommand) -> None:
+        if self._config.dedupe_ttl_secs > 0:
+            self._seen[command.command_id] = time.monotonic()
+            self._seen.move_to_end(command.command_id)
+
+    def _forget(self, command: TradingCommand) -> None:
+        self._seen.pop(command.command_id, None)
+
+    def _accepted(self, route: _Route) -> None:
+        self._metrics.inc("command_bus_published", venue=route.venue)
+        self._metrics.set("command_bus_depth", route.queue.qsize(), venue=route.venue)
+
+    def _dropped(self, route: _Route, command: TradingCommand, reason: str) -> None:
+        _audit(COMMAND_DROPPED, command, reason=reason)
+        self._metrics.inc("command_bus_dropped", venue=route.venue, reason=reason)
+        self._logger.warning(
+            "command_dropped",
+            extra={"venue": route.venue, "kind": command.kind, "command_id": command.command_id, "reason": reason},
+        )
+
+    async def start(self) -> None:
+        self._closed = False
+        self._started = True
+        for route in self._routes.values():
+            if route.task is None:
+                route.task = asyncio.create_task(self._consume(route), name=f"command-bus-{route.venue}")
+
+    async def close(self) -> int:
+        """Stop accepting commands and stop the consumers; returns how many queued commands were discarded."""
+        self._closed = True
+        self._started = False
+        discarded = 0
+        for route in self._routes.values():
+            if route.task is not None:
+                route.task.cancel()
+                with contextlib.suppress(asyncio.CancelledError):
+                    await route.task
+                route.task = None
+            while not route.queue.empty():
+                command = route.queue.get_nowait()
+                self._forget(command)
+                self._dropped(route, command, "shutdown")
+                discarded += 1
+        return discarded
+
+    async def _consume(self, route: _Route) -> None:
+        while True:
+            command = await route.queue.get()
+            lag = max(0.0, time.time() - command.ts)
+            self._metrics.set("command_bus_depth", route.queue.qsize(), venue=route.venue)
+            self._metrics.set("command_bus_lag_secs", lag, venue=route.venue)
+            self._metrics.max("command_bus_lag_secs_max", lag, venue=route.venue)
+            try:
+                await route.handler(command)
+                self._metrics.inc("command_bus_handled", venue=route.venue)
+                _audit(COMMAND_HANDLED, command)
+            except Exception as exc:
+                _audit(COMMAND_FAILED, command, error=str(exc))
+                self._forget(command)
+                self._metrics.inc("command_bus_failed", venue=route.venue)
+                self._logger.info(
+                    "command_failed",
+                    extra={"venue": route.venue, "kind": command.kind, "command_id": command.command_id, "error": str(exc)},
+                )
+            finally:
+                route.queue.task_done()
+
+
+__all__ = [
+    "TradingCommand",
+    "TradingResult",
+    "CommandHandler",
+    "CommandBus",
+    "CommandBusConfig",
+    "CommandBusClosed",
+    "UnroutableCommand",
+    "BackpressurePolicy",
+]
@@ -0,0 +1,228 @@
+from __future__ import annotations
+
+import asyncio
+import itertools
+import time
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import Any, AsyncIterator, Dict, FrozenSet, Iterable, List, Optional
+
+from .metrics import METRICS, MetricsRegistry
+from .state_cache import StateCache
+
+
+class EventKind(str, Enum):
+    MARKET_DATA = "market_data"
+    TRADE = "trade"
+    ORDER_UPDATE = "order_update"
+    FILL = "fill"
+    POSITION_UPDATE = "position_update"
+    BALANCE_UPDATE = "balance_update"
+    CONNECTION_STATUS = "connection_status"
+    COMMAND_RESULT = "command_result"
+    WATCHLIST = "watchlist"  # symbols added to / removed from the runtime watchlist
+    NEW_LISTING = "new_listing"  # market listed by the venue while running
+    MARKET_STATE = "market_state"  # order book went post-only, reduce-only, closed or back to open
+    VENUE_STATUS = "venue_status"  # venue maintenance started or ended
+    SIGNAL = "signal"  # derived order-flow signals (imbalance, aggressor ratio, toxicity) per symbol
+    WHALE_ACTIVITY = "whale_activity"  # unusually large print or burst of aggressive prints
+
+
+class ConnectionState(str, Enum):
+    CONNECTED = "connected"
+    DISCONNECTED = "disconnected"
+    RESUBSCRIBED = "resubscribed"  # reconnected and subscriptions restored
+    DEGRADED = "degraded"  # socket up but no data within the staleness window
+
+
+class EventsLagged(Exception):
+    """Raised once by a `report_lag` subscription after it dropped events; the next read resumes with newer ones."""
+
+    def __init__(self, subscriber: str, missed: int) -> None:
+        super().__init__(f"subscriber {subscriber} lagged and missed {missed} events")
+        self.subscriber = subscriber
+        self.missed = missed
+
+
+@dataclass(slots=True)
+class EventStreamConfig:
+    capacity: int = 1024  # default queue size per subscription
+    capacities: Dict[str, int] = field(default_factory=dict)  # per subscriber name
+
+
+@dataclass(slots=True)
+class Event:
+    """One update from any source.
+
+    `source` is the venue (or component) that produced it; `seq` increases
+    monotonically per source, so consumers can detect gaps and order events
+    from one source without relying on wall-clock timestamps.
+    """
+
+    kind: EventKind
+    source: str
+    data: Dict[str, Any] = field(default_factory=dict)
+    symbol: Optional[str] = None
+    ts: float = field(default_factory=time.time)
+    seq: int = 0
+    snapshot: bool = False  # replayed from the StateCache on subscribe, not a new update
+
+
+class Subscription:
+    """Ordered, bounded view of the event stream; iterate with `async for`.
+
+    A full queue drops its oldest event. With `report_lag` the next read
+    raises `EventsLagged` with the number missed, so the consumer can resync
+    its state from the source instead of continuing on a silent gap.
+    """
+
+    def __init__(
+        self,
+        stream: "EventStream",
+        *,
+        kinds: Optional[FrozenSet[EventKind]],
+        sources: Optional[FrozenSet[str]],
+        symbols: Optional[FrozenSet[str]],
+        maxsize: int,
+        name: str,
+        report_lag: bool = False,
+    ) -> None:
+        self._stream = stream
+        self._kinds = kinds
+        self._sources = sources
+        self._symbols = symbols
+        self._queue: asyncio.Queue[Event] = asyncio.Queue(maxsize=maxsize)
+        self.name = name
+        self.maxsize = maxsize
+        self.dropped = 0
+        self._report_lag = report_lag
+        self._missed = 0  # dropped since the last EventsLagged
+
+    def matches(self, event: Event) -> bool:
+        if self._kinds is not None and event.kind not in self._kinds:
+            return False
+        if self._sources is not None and event.source not in self._sources:
+            return False
+        if self._symbols is not None and event.symbol not in self._symbols:
+            return False
+        return True
+
+    def _offer(self, event: Event) -> bool:
+        if self._queue.full():
+            # Slow consumer: keep the newest state, count the loss
+            self._queue.get_nowait()
+            self.dropped += 1
+            self._missed += 1
+            self._queue.put_nowait(event)
+            return False
+        self._queue.put_nowait(event)
+        return True
+
+    def _check_lag(self) -> None:
+        if self._report_lag and self._missed:
+            missed, self._missed = self._missed, 0
+            self._stream._metrics.inc("event_stream_lagged", subscriber=self.name)
+            raise EventsLagged(self.name, missed)
+
+    async def get(self) -> Event:
+        self._check_lag()
+        return await self._queue.get()
+
+    def get_nowait(self) -> Optional[Event]:
+        self._check_lag()
+        try:
+            return self._queue.get_nowait()
+        except asyncio.QueueEmpty:
+            return None
+
+    def close(self) -> None:
+        self._stream.unsubscribe(self)
+
+    def __aiter__(self) -> AsyncIterator[Event]:
+        return self
+
+    async def __anext__(self) -> Event:
+        return await self.get()
+
+
+class EventStream:
+    """Single fan-out point for market, order, position, balance and connection updates."""
+
+    def __init__(self, *, metrics: Optional[MetricsRegistry] = None, config: Optional[EventStreamConfig] = None) -> None:
+        self._config = config or EventStreamConfig()
+        self.state = StateCache()
+        self._subs: List[Subscription] = []
+        self._seq: Dict[str, itertools.count] = {}
+        self._metrics = metrics or METRICS
+        self._names = itertools.count(1)
+
+    def subscribe(
+        self,
+        kinds: Optional[Iterable[EventKind]] = None,
+        *,
+        sources: Optional[Iterable[str]] = None,
+        symbols: Optional[Iterable[str]] = None,
+        maxsize: Optional[int] = None,
+        name: Optional[str] = None,
+        report_lag: bool = False,
+        snapshot: bool = False,
+    ) -> Subscription:
+        """`maxsize` defaults to the configured capacity for `name`, then the stream-wide capacity.
+
+        With `snapshot`, the current state (positions, balances, open orders,
+        tops of book, feed connections) matching the filters is queued first.
+        """
+        name = name or f"sub-{next(self._names)}"
+        if maxsize is None:
+            maxsize = self._config.capacities.get(name, self._config.capacity)
+        sub = Subscription(
+            self,
+            kinds=frozenset(kinds) if kinds is not None else None,
+            sources=frozenset(sources) if sources is not None else None,
+            symbols=frozenset(symbols) if symbols is not None else None,
+            maxsize=maxsize,
+            name=name,
+            report_lag=report_lag,
+        )
+        if snapshot:
+            for event in self.state.snapshot():
+                if sub.matches(event):
+                    sub._offer(event)
+        self._subs.append(sub)
+        return sub
+
+    def unsubscribe(self, sub: Subscription) -> None:
+        if sub in self._subs:
+            self._subs.remove(sub)
+
+    def publish(self, event: Event) -> Event:
+        counter = self._seq.get(event.source)
+        if counter is None:
+            counter = self._seq[event.source] = itertools.count(1)
+        event.seq = next(counter)
+        self.state.update(event)
+        for sub in list(self._subs):
+            if sub.matches(event) and not sub._offer(event):
+                self._metrics.inc("event_stream_dropped", subscriber=sub.name, kind=event.kind.value)
+        return event
+
+    def emit(
+        self,
+        kind: EventKind,
+        source: str,
+        data: Optional[Dict[str, Any]] = None,
+        *,
+        symbol: Optional[str] = None,
+    ) -> Event:
+        return self.publish(Event(kind=kind, source=source, data=data or {}, symbol=symbol))
+
+
+__all__ = [
+    "ConnectionState",
+    "Event",
+    "EventKind",
+    "EventStream",
+    "EventStreamConfig",
+    "EventsLagged",
+    "Subscription",
+]
@@ -0,0 +1,85 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from typing import Optional
+
+from ..utils.logging import get_logger
+from .cache import MarketCache
+from .events import ConnectionState
+
+
+class FeedStatus:
+    """Tracks one WebSocket feed and publishes its `ConnectionState` transitions.
+
+    The first successful subscribe reports CONNECTED and later ones RESUBSCRIBED.
+    With `stale_after_secs` set, a watchdog marks the feed DEGRADED when no
+    message arrives within the window, and CONNECTED again on the next message.
+    Quiet feeds (e.g. private order streams) should leave it unset.
+    """
+
+    def __init__(self, cache: MarketCache, *, feed: str, stale_after_secs: Optional[float] = None) -> None:
+        self._cache = cache
+        self._feed = feed
+        self._stale_after = stale_after_secs
+        self._state: Optional[ConnectionState] = None
+        self._ever_connected = False
+        self._last_msg = 0.0
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def state(self) -> Optional[ConnectionState]:
+        return self._state
+
+    async def _set(self, state: ConnectionState, **info: object) -> None:
+        if state == self._state:
+            return
+        self._state = state
+        self._logger.info("feed_status", extra={"venue": self._cache.source, "feed": self._feed, "state": state.value, **info})
+        await self._cache.set_connection(self._feed, state, **info)
+
+    async def connected(self, **info: object) -> None:
+        self._last_msg = time.time()
+        state = ConnectionState.RESUBSCRIBED if self._ever_connected else ConnectionState.CONNECTED
+        self._ever_connected = True
+        self._state = None  # always announce a (re)subscribe
+        await self._set(state, **info)
+
+    async def disconnected(self, **info: object) -> None:
+        if self._state is not None:
+            await self._set(ConnectionState.DISCONNECTED, **info)
+
+    async def on_message(self) -> None:
+        self._last_msg = time.time()
+        if self._state == ConnectionState.DEGRADED:
+            await self._set(ConnectionState.CONNECTED, recovered=True)
+
+    async def check(self) -> None:
+        if self._stale_after is None or self._state not in (ConnectionState.CONNECTED, ConnectionState.RESUBSCRIBED):
+            return
+        silence = time.time() - self._last_msg
+        if silence > self._stale_after:
+            await self._set(ConnectionState.DEGRADED, silence_secs=round(silence, 1))
+
+    async def start(self) -> None:
+        if self._task is None and self._stale_after is not None:
+            self._task = asyncio.create_task(self._run(), name=f"feed-status-{self._feed}")
+
+    async def stop(self) -> None:
+        if self._task is not None:
+            self._task.cancel()
+            with contextlib.suppress(asyncio.CancelledError):
+                await self._task
+            self._task = None
+        await self.disconnected(reason="stopped")
+
+    async def _run(self) -> None:
+        assert self._stale_after is not None
+        while True:
+            await asyncio.sleep(max(0.5, self._stale_after / 2))
+            await self.check()
+
+
+__all__ = ["FeedStatus"]
@@ -8,7 +8,10 @@ from typing import Optional
 import httpx
 
 from xbot.connector.interface import IConnector
+from .backoff import AdaptiveInterval, BackoffConfig
 from .clock import WallClock
+from .polling import first_error, poll_all
+from .state_cache import StateCache
 from ..execution.router import ExecutionRouter
 
 
@@ -17,6 +20,7 @@ class HeartbeatConfig:
     url: str
     interval_secs: float = 30.0
     timeout_secs: float = 5.0
+    poll_timeout_secs: float = 10.0  # per venue call (positions, margin), which run concurrently
     bearer_token: Optional[str] = None
 
 
@@ -30,6 +34,8 @@ class HeartbeatService:
         strategy_name: str,
         venue: str,
         config: HeartbeatConfig,
+        backoff: Optional[BackoffConfig] = None,
+        state: Optional[StateCache] = None,
     ) -> None:
         self._connector = connector
         self._router = router
@@ -37,7 +43,9 @@ class HeartbeatService:
         self._strategy = strategy_name
         self._venue = venue
         self._config = config
+        self._state = state
         self._client = httpx.AsyncClient(timeout=config.timeout_secs)
+        self._interval = AdaptiveInterval(venue, "heartbeat", config.interval_secs, backoff)
         self._task: Optional[asyncio.Task] = None
         self._running = asyncio.Event()
 
@@ -59,18 +67,25 @@ class HeartbeatService:
 
     async def _run(self) -> None:
         while self._running.is_set():
-            await self._emit_once()
-            await asyncio.sleep(self._config.interval_secs)
+            error = await self._emit_once()
+            if error is None:
+                self._interval.success()
+            else:
+                self._interval.failure(error)
+            await self._interval.sleep()
 
-    async def _emit_once(self) -> None:
-        try:
-            positions = await self._connector.get_positions()
-        except Exception:
-            positions = []
-        try:
-            margin = await self._connector.get_margin()
-        except Exception:
-            margin = {}
+    async def _emit_once(self) -> Optional[BaseException]:
+        """Post one heartbeat; returns the venue error, if any, so the poll can back off."""
+        results = await poll_all(
+            {"positions": self._connector.get_positions, "margin": self._connector.get_margin},
+            timeout_secs=self._config.poll_timeout_secs,
+        )
+        if self._state is not None:
+            for result in results.values():
+                if result.ok:
+                    self._state.mark_fresh(self._venue, result.endpoint, result.finished_at)
+        positions = results["positions"].value if results["positions"].ok else []
+        margin = results["margin"].value if results["margin"].ok else {}
         payload = {
             "ts": int(self._clock.now()),
             "strategy": self._strategy,
@@ -86,6 +101,7 @@ class HeartbeatService:
         except Exception:
             # Heartbeat failures should not break trading; rely on logs
             pass
+        return first_error(results)
 
 
 __all__ = ["HeartbeatService", "HeartbeatConfig"]
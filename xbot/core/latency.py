@@ -0,0 +1,123 @@
+from __future__ import annotations
+
+import threading
+import time
+from collections import deque
+from contextlib import contextmanager
+from dataclasses import dataclass
+from typing import Deque, Dict, Iterator, Optional, Tuple
+
+from .metrics import METRICS
+
+WS = "ws"  # one-way: venue event time to local receipt, clock offset corrected
+REST = "rest"  # round trip of a REST call
+
+
+@dataclass(slots=True)
+class LatencyStats:
+    count: int
+    p50: float
+    p95: float
+    p99: float
+    max: float
+
+    def quantile(self, q: float) -> float:
+        return {0.5: self.p50, 0.95: self.p95, 0.99: self.p99, 1.0: self.max}[q]
+
+    def to_dict(self) -> Dict[str, float]:
+        return {"count": self.count, "p50": self.p50, "p95": self.p95, "p99": self.p99, "max": self.max}
+
+
+def _percentile(ordered: list[float], q: float) -> float:
+    # Nearest rank, so every result is an observed sample
+    index = min(len(ordered) - 1, max(0, int(round(q * len(ordered))) - 1))
+    return ordered[index]
+
+
+class LatencyTracker:
+    """Rolling latency samples in milliseconds per (venue, kind).
+
+    Keeps the last `window` samples of each series. Percentiles are computed
+    on demand and published as `latency_ms{venue,kind,quantile}` gauges
+    every `publish_every` samples, so a hot WS feed does not sort on every
+    message.
+    """
+
+    def __init__(self, *, window: int = 1000, publish_every: int = 50) -> None:
+        self._window = window
+        self._publish_every = publish_every
+        self._samples: Dict[Tuple[str, str], Deque[float]] = {}
+        self._pending: Dict[Tuple[str, str], int] = {}
+        self._lock = threading.Lock()
+
+    def record(self, venue: str, kind: str, latency_ms: float) -> None:
+        key = (venue, kind)
+        with self._lock:
+            series = self._samples.get(key)
+            if series is None:
+                series = self._samples[key] = deque(maxlen=self._window)
+            series.append(max(0.0, float(latency_ms)))  # clock error can make one-way times negative
+            self._pending[key] = self._pending.get(key, 0) + 1
+            publish = self._pending[key] >= self._publish_every
+            if publish:
+                self._pending[key] = 0
+        if publish:
+            self.publish(venue, kind)
+
+    def record_event_time(self, venue: str, kind: str, event_ms: float, now_ms: float) -> None:
+        self.record(venue, kind, now_ms - event_ms)
+
+    @contextmanager
+    def timed(self, venue: str, kind: str = REST) -> Iterator[None]:
+        """Record the wall time of the block, including when it raises."""
+        start = time.perf_counter()
+        try:
+            yield
+        finally:
+            self.record(venue, kind, (time.perf_counter() - start) * 1000)
+
+    def stats(self, venue: str, kind: str) -> Optional[LatencyStats]:
+        with self._lock:
+            series = self._samples.get((venue, kind))
+            ordered = sorted(series) if series else []
+        if not ordered:
+            return None
+        return LatencyStats(
+            count=len(ordered),
+            p50=_percentile(ordered, 0.5),
+            p95=_percentile(ordered, 0.95),
+            p99=_percentile(ordered, 0.99),
+            max=ordered[-1],
+        )
+
+    def publish(self, venue: str, kind: str) -> Optional[LatencyStats]:
+        stats = self.stats(venue, kind)
+        if stats is not None:
+            for quantile, value in (("p50", stats.p50), ("p95", stats.p95), ("p99", stats.p99)):
+                METRICS.set("latency_ms", value, venue=venue, kind=kind, quantile=quantile)
+        return stats
+
+    def exceeds(self, venue: str, kind: str, limit_ms: float, *, quantile: float = 0.95) -> bool:
+        """True when the series' `quantile` is above `limit_ms`; no samples never exceeds."""
+        stats = self.stats(venue, kind)
+        return stats is not None and stats.quantile(quantile) > limit_ms
+
+    def snapshot(self) -> Dict[str, Dict[str, Dict[str, float]]]:
+        with self._lock:
+            keys = list(self._samples)
+        out: Dict[str, Dict[str, Dict[str, float]]] = {}
+        for venue, kind in keys:
+            stats = self.stats(venue, kind)
+            if stats is not None:
+                out.setdefault(venue, {})[kind] = stats.to_dict()
+        return out
+
+    def reset(self) -> None:
+        with self._lock:
+            self._samples.clear()
+            self._pending.clear()
+
+
+LATENCY = LatencyTracker()
+
+__all__ = ["LATENCY", "LatencyStats", "LatencyTracker", "REST", "WS"]
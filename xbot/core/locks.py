@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+import asyncio
+from typing import Dict, Optional
+
+
+class ReentrantLock:
+    """asyncio lock the owning task may re-acquire.
+
+    Composite operations (cancel-replace, a tracking-limit reprice) hold it
+    around primitives that take the same lock themselves.
+    """
+
+    def __init__(self) -> None:
+        self._lock = asyncio.Lock()
+        self._owner: Optional[asyncio.Task] = None
+        self._depth = 0
+
+    @property
+    def locked(self) -> bool:
+        return self._lock.locked()
+
+    def owned(self) -> bool:
+        return self._owner is not None and self._owner is asyncio.current_task()
+
+    async def acquire(self) -> None:
+        if self.owned():
+            self._depth += 1
+            return
+        await self._lock.acquire()
+        self._owner = asyncio.current_task()
+        self._depth = 1
+
+    def release(self) -> None:
+        if not self.owned():
+            raise RuntimeError("lock released by a task that does not hold it")
+        self._depth -= 1
+        if self._depth == 0:
+            self._owner = None
+            self._lock.release()
+
+    async def __aenter__(self) -> "ReentrantLock":
+        await self.acquire()
+        return self
+
+    async def __aexit__(self, *exc: object) -> None:
+        self.release()
+
+
+class KeyedLocks:
+    """One `ReentrantLock` per key, created on first use."""
+
+    def __init__(self) -> None:
+        self._locks: Dict[str, ReentrantLock] = {}
+
+    def __call__(self, key: str) -> ReentrantLock:
+        lock = self._locks.get(key)
+        if lock is None:
+            lock = self._locks[key] = ReentrantLock()
+        return lock
+
+
+__all__ = ["KeyedLocks", "ReentrantLock"]
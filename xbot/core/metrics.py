@@ -0,0 +1,61 @@
+from __future__ import annotations
+
+import threading
+from typing import Dict, Tuple
+
+LabelKey = Tuple[Tuple[str, str], ...]
+
+
+def _key(labels: Dict[str, object]) -> LabelKey:
+    return tuple(sorted((k, str(v)) for k, v in labels.items()))
+
+
+class MetricsRegistry:
+    """In-process counters and gauges keyed by name and labels.
+
+    Deliberately minimal: values are plain floats and `snapshot()` returns a
+    JSON-serialisable dict, which the heartbeat/admin surfaces can expose.
+    """
+
+    def __init__(self) -> None:
+        self._counters: Dict[str, Dict[LabelKey, float]] = {}
+        self._gauges: Dict[str, Dict[LabelKey, float]] = {}
+        self._lock = threading.Lock()
+
+    def inc(self, name: str, value: float = 1.0, **labels: object) -> None:
+        with self._lock:
+            series = self._counters.setdefault(name, {})
+            key = _key(labels)
+            series[key] = series.get(key, 0.0) + value
+
+    def set(self, name: str, value: float, **labels: object) -> None:
+        with self._lock:
+            self._gauges.setdefault(name, {})[_key(labels)] = float(value)
+
+    def max(self, name: str, value: float, **labels: object) -> None:
+        """Gauge that only moves up (high-water marks)."""
+        with self._lock:
+            series = self._gauges.setdefault(name, {})
+            key = _key(labels)
+            series[key] = max(series.get(key, float("-inf")), float(value))
+
+    def get(self, name: str, **labels: object) -> float:
+        key = _key(labels)
+        with self._lock:
+            if name in self._counters:
+                return self._counters[name].get(key, 0.0)
+            return self._gauges.get(name, {}).get(key, 0.0)
+
+    def snapshot(self) -> Dict[str, list]:
+        with self._lock:
+            out: Dict[str, list] = {}
+            for store in (self._counters, self._gauges):
+                for name, series in store.items():
+                    out[name] = [{"labels": dict(key), "value": value} for key, value in series.items()]
+            return out
+
+
+METRICS = MetricsRegistry()
+
+
+__all__ = ["MetricsRegistry", "METRICS"]
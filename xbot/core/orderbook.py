@@ -0,0 +1,246 @@
+from __future__ import annotations
+
+import bisect
+import time
+from dataclasses import dataclass, field
+from typing import Dict, Iterable, List, Mapping, Optional, Tuple
+
+Level = Tuple[float, float]  # (price, size)
+
+
+@dataclass(slots=True)
+class BookMetrics:
+    """Derived prices and imbalances of one book; None where a side is empty."""
+
+    symbol: str
+    depth: int  # levels per side behind the depth-weighted fields
+    mid: Optional[float]
+    microprice: Optional[float]  # touch prices weighted by the opposite touch size
+    imbalance: Optional[float]  # (bid size - ask size) / total at the touch, in [-1, 1]
+    weighted_mid: Optional[float]  # side VWAPs over `depth` levels weighted by the opposite side's size
+    depth_imbalance: Optional[float]  # `imbalance` over `depth` levels
+    bid_depth: float
+    ask_depth: float
+    ts: float
+    bid_size: Optional[float] = None  # at the touch
+    ask_size: Optional[float] = None
+
+    def to_dict(self) -> Dict[str, Optional[float]]:
+        return {
+            "bid_size": self.bid_size,
+            "ask_size": self.ask_size,
+            "mid": self.mid,
+            "microprice": self.microprice,
+            "imbalance": self.imbalance,
+            "weighted_mid": self.weighted_mid,
+            "depth_imbalance": self.depth_imbalance,
+        }
+
+
+def _weighted(bid: float, bid_size: float, ask: float, ask_size: float) -> Tuple[Optional[float], Optional[float]]:
+    total = bid_size + ask_size
+    if total <= 0:
+        return None, None
+    return (bid * ask_size + ask * bid_size) / total, (bid_size - ask_size) / total
+
+
+@dataclass(slots=True)
+class OrderBook:
+    """Aggregated price levels for one symbol, in venue units (not scaled ints).
+
+    Sorted price indexes are kept next to the level dicts so the touch and
+    the first levels are read without sorting, and `metrics()` is only
+    recomputed after a delta that reaches the first `metrics_depth` levels.
+    Change levels through `apply` to keep them in step.
+    """
+
+    symbol: str
+    bids: Dict[float, float] = field(default_factory=dict)
+    asks: Dict[float, float] = field(default_factory=dict)
+    ts: float = field(default_factory=time.time)
+    metrics_depth: int = 5
+    _bid_prices: List[float] = field(default_factory=list, init=False, repr=False, compare=False)  # ascending
+    _ask_prices: List[float] = field(default_factory=list, init=False, repr=False, compare=False)  # ascending
+    _metrics: Optional[BookMetrics] = field(default=None, init=False, repr=False, compare=False)
+
+    def __post_init__(self) -> None:
+        self._bid_prices = sorted(self.bids)
+        self._ask_prices = sorted(self.asks)
+
+    @classmethod
+    def from_levels(cls, symbol: str, bids: Iterable[Level], asks: Iterable[Level], ts: Optional[float] = None) -> "OrderBook":
+        book = cls(symbol=symbol)
+        book.apply("bids", bids)
+        book.apply("asks", asks)
+        if ts is not None:
+            book.ts = ts
+        return book
+
+    def apply(self, side: str, levels: Iterable[Level]) -> None:
+        """Set absolute sizes per price; a size of zero removes the level."""
+        is_bid = side == "bids"
+        target, prices = (self.bids, self._bid_prices) if is_bid else (self.asks, self._ask_prices)
+        for price, size in levels:
+            # Rank from the touch, 0 being the best level, before the change
+            at = bisect.bisect_left(prices, price)
+            rank = len(prices) - 1 - at if is_bid else at
+            present = price in target
+            if size <= 0:
+                if not present:
+                    continue
+                target.pop(price)
+                del prices[at]
+            else:
+                if not present:
+                    prices.insert(at, price)
+                    rank += 1 if is_bid else 0
+                target[price] = size
+            if rank < self.metrics_depth:
+                self._metrics = None
+        self.ts = time.time()
+
+    def clear(self) -> None:
+        self.bids.clear()
+        self.asks.clear()
+        self._bid_prices.clear()
+        self._ask_prices.clear()
+        self._metrics = None
+        self.ts = time.time()
+
+    def levels(self, side: str, depth: Optional[int] = None) -> List[Level]:
+        """Best-first levels of one side."""
+        if side == "bids":
+            prices = self._bid_prices[::-1] if depth is None else self._bid_prices[: -depth - 1 : -1]
+            return [(price, self.bids[price]) for price in prices]
+        prices = self._ask_prices if depth is None else self._ask_prices[:depth]
+        return [(price, self.asks[price]) for price in prices]
+
+    def metrics(self) -> BookMetrics:
+        """Microprice, depth-weighted mid and imbalances, cached until a delta reaches the first levels."""
+        if self._metrics is None:
+            self._metrics = self._compute_metrics()
+        return self._metrics
+
+    def _compute_metrics(self) -> BookMetrics:
+        bids = self.levels("bids", self.metrics_depth)
+        asks = self.levels("asks", self.metrics_depth)
+        bid_depth = sum(size for _, size in bids)
+        ask_depth = sum(size for _, size in asks)
+        mid = microprice = imbalance = weighted_mid = depth_imbalance = None
+        if bids and asks:
+            mid = (bids[0][0] + asks[0][0]) / 2
+            microprice, imbalance = _weighted(bids[0][0], bids[0][1], asks[0][0], asks[0][1])
+            bid_vwap = sum(price * size for price, size in bids) / bid_depth
+            ask_vwap = sum(price * size for price, size in asks) / ask_depth
+            weighted_mid, depth_imbalance = _weighted(bid_vwap, bid_depth, ask_vwap, ask_depth)
+        return BookMetrics(
+            symbol=self.symbol,
+            depth=self.metrics_depth,
+            mid=mid,
+            microprice=microprice,
+            imbalance=imbalance,
+            weighted_mid=weighted_mid,
+            depth_imbalance=depth_imbalance,
+            bid_depth=bid_depth,
+            ask_depth=ask_depth,
+            ts=self.ts,
+            bid_size=bids[0][1] if bids else None,
+            ask_size=asks[0][1] if asks else None,
+        )
+
+    @property
+    def best_bid(self) -> Optional[float]:
+        return self._bid_prices[-1] if self._bid_prices else None
+
+    @property
+    def best_ask(self) -> Optional[float]:
+        return self._ask_prices[0] if self._ask_prices else None
+
+    @property
+    def mid(self) -> Optional[float]:
+        if self.best_bid is None or self.best_ask is None:
+            return None
+        return (self.best_bid + self.best_ask) / 2
+
+
+@dataclass(slots=True)
+class SlippageEstimate:
+    symbol: str
+    is_ask: bool
+    size: float
+    filled: float  # size the visible book can absorb
+    avg_price: Optional[float]  # over the filled part
+    reference_price: Optional[float]  # mid, or the touch when one side is empty
+    impact_bps: Optional[float]  # adverse distance of avg_price from the reference
+
+    @property
+    def complete(self) -> bool:
+        return self.filled >= self.size
+
+
+def estimate_slippage(book: OrderBook, *, is_ask: bool, size: float) -> SlippageEstimate:
+    """Walk the opposite side of `book` for a market order of `size` base units."""
+    levels = book.levels("bids" if is_ask else "asks")
+    remaining = size
+    cost = 0.0
+    for price, available in levels:
+        take = min(remaining, available)
+        cost += take * price
+        remaining -= take
+        if remaining <= 0:
+            break
+    filled = size - max(remaining, 0.0)
+    avg = cost / filled if filled > 0 else None
+    reference = book.mid or (levels[0][0] if levels else None)
+    impact = None
+    if avg is not None and reference:
+        impact = (reference - avg if is_ask else avg - reference) / reference * 10_000
+    return SlippageEstimate(
+        symbol=book.symbol,
+        is_ask=is_ask,
+        size=size,
+        filled=filled,
+        avg_price=avg,
+        reference_price=reference,
+        impact_bps=impact,
+    )
+
+
+def levels_from_pairs(rows: Iterable[object]) -> List[Level]:
+    """[[price, size], ...] as strings or numbers -> levels; malformed rows are skipped."""
+    levels: List[Level] = []
+    for row in rows or []:
+        try:
+            price, size = row[0], row[1]  # type: ignore[index]
+            levels.append((float(price), float(size)))
+        except Exception:
+            continue
+    return levels
+
+
+def _field(row: object, key: str) -> object:
+    return row.get(key) if isinstance(row, Mapping) else getattr(row, key, None)
+
+
+def aggregate_orders(rows: Iterable[object], price_attr: str = "price", size_attr: str = "remaining_base_amount") -> List[Level]:
+    """Per-order rows (e.g. Lighter `order_book_orders`) -> levels summed by price."""
+    totals: Dict[float, float] = {}
+    for row in rows or []:
+        try:
+            price = float(_field(row, price_attr))  # type: ignore[arg-type]
+            size = float(_field(row, size_attr))  # type: ignore[arg-type]
+        except Exception:
+            continue
+        totals[price] = totals.get(price, 0.0) + size
+    return list(totals.items())
+
+
+__all__ = [
+    "BookMetrics",
+    "Level",
+    "OrderBook",
+    "SlippageEstimate",
+    "aggregate_orders",
+    "estimate_slippage",
+    "levels_from_pairs",
+]
@@ -0,0 +1,54 @@
+from __future__ import annotations
+
+import asyncio
+import time
+from dataclasses import dataclass
+from typing import Any, Awaitable, Callable, Dict, Mapping, Optional
+
+PollCall = Callable[[], Awaitable[Any]]
+
+
+@dataclass(slots=True)
+class PollResult:
+    endpoint: str
+    value: Any = None
+    error: Optional[BaseException] = None
+    elapsed_ms: float = 0.0
+    finished_at: float = 0.0
+
+    @property
+    def ok(self) -> bool:
+        return self.error is None
+
+
+async def _poll_one(endpoint: str, call: PollCall, timeout_secs: Optional[float]) -> PollResult:
+    start = time.perf_counter()
+    result = PollResult(endpoint)
+    try:
+        result.value = await asyncio.wait_for(call(), timeout_secs)
+    except asyncio.TimeoutError:
+        result.error = TimeoutError(f"{endpoint} timed out after {timeout_secs}s")
+    except Exception as exc:
+        result.error = exc
+    result.elapsed_ms = (time.perf_counter() - start) * 1000
+    result.finished_at = time.time()
+    return result
+
+
+async def poll_all(calls: Mapping[str, PollCall], *, timeout_secs: Optional[float] = None) -> Dict[str, PollResult]:
+    """Run independent REST calls concurrently and wait for all of them.
+
+    Each call gets its own `timeout_secs`, so a slow endpoint costs at most
+    that long and never holds up the others' results; a failure or timeout
+    is returned in its `PollResult` instead of raising. Results keep the
+    order of `calls`.
+    """
+    results = await asyncio.gather(*(_poll_one(endpoint, call, timeout_secs) for endpoint, call in calls.items()))
+    return {result.endpoint: result for result in results}
+
+
+def first_error(results: Mapping[str, PollResult]) -> Optional[BaseException]:
+    return next((result.error for result in results.values() if result.error is not None), None)
+
+
+__all__ = ["PollCall", "PollResult", "first_error", "poll_all"]
@@ -0,0 +1,148 @@
+from __future__ import annotations
+
+import asyncio
+import itertools
+import time
+from dataclasses import dataclass, field
+from enum import IntEnum
+from typing import Callable, Dict, List, Optional
+
+from .metrics import METRICS
+
+
+class MessagePriority(IntEnum):
+    """Lower goes first."""
+
+    CANCEL = 0
+    REDUCE = 1  # orders that only shrink a position
+    NEW = 2  # new quotes and opening orders
+
+
+class RateShapedError(Exception):
+    """Raised when a low-priority message waited longer than its budget and was dropped."""
+
+
+@dataclass(slots=True)
+class RateShaperConfig:
+    global_per_sec: Optional[float] = None  # across every venue in the process
+    venues: Dict[str, float] = field(default_factory=dict)  # venue -> messages per second
+    burst_secs: float = 1.0  # bucket depth, in seconds' worth of the rate
+    new_timeout_secs: Optional[float] = None  # drop NEW messages queued longer than this; None waits
+
+
+class _Bucket:
+    def __init__(self, rate: float, burst_secs: float, now: float) -> None:
+        self.rate = rate
+        self.capacity = max(1.0, rate * burst_secs)
+        self.tokens = self.capacity
+        self.ts = now
+
+    def refill(self, now: float) -> None:
+        self.tokens = min(self.capacity, self.tokens + (now - self.ts) * self.rate)
+        self.ts = now
+
+    def wait_secs(self) -> float:
+        return 0.0 if self.tokens >= 1 else (1 - self.tokens) / self.rate
+
+
+@dataclass(slots=True)
+class _Waiter:
+    priority: MessagePriority
+    seq: int
+    venue: str
+    future: asyncio.Future
+
+
+class RateShaper:
+    """Token buckets on outbound order traffic, served by priority.
+
+    Every message takes a token from the venue's bucket (when the venue has
+    a rate) and from the global one (when set). While tokens last nothing
+    waits. Once a bucket runs dry, messages queue and are released as
+    tokens come back, cancels first, then risk-reducing orders, then new
+    orders, in arrival order within a class. A venue that is out of tokens
+    does not hold up another venue's messages, but the global bucket is
+    served strictly by priority. With `new_timeout_secs`, a new order still
+    queued after that long raises `RateShapedError` instead of going out
+    stale. Buckets start full, so a burst up to `burst_secs` of the rate
+    passes at once.
+    """
+
+    def __init__(self, config: RateShaperConfig, *, clock: Callable[[], float] = time.monotonic) -> None:
+        self._config = config
+        self._clock = clock
+        now = clock()
+        self._global = _Bucket(config.global_per_sec, config.burst_secs, now) if config.global_per_sec else None
+        self._venues = {v.lower(): _Bucket(rate, config.burst_secs, now) for v, rate in config.venues.items() if rate}
+        self._waiters: List[_Waiter] = []
+        self._seq = itertools.count()
+        self._timer: Optional[asyncio.TimerHandle] = None
+
+    @property
+    def config(self) -> RateShaperConfig:
+        return self._config
+
+    def queued(self, venue: Optional[str] = None) -> int:
+        return sum(1 for w in self._waiters if venue is None or w.venue == venue.lower())
+
+    async def acquire(self, venue: str, priority: MessagePriority) -> float:
+        """Wait for permission to send one message to `venue`; returns the seconds waited."""
+        venue = venue.lower()
+        started = self._clock()
+        loop = asyncio.get_running_loop()
+        waiter = _Waiter(priority, next(self._seq), venue, loop.create_future())
+        self._waiters.append(waiter)
+        self._waiters.sort(key=lambda w: (w.priority, w.seq))
+        self._dispatch()
+        timeout = self._config.new_timeout_secs if priority == MessagePriority.NEW else None
+        try:
+            await asyncio.wait_for(asyncio.shield(waiter.future), timeout)
+        except asyncio.TimeoutError:
+            if not waiter.future.done():
+                self._waiters.remove(waiter)
+                waiter.future.cancel()
+                METRICS.inc("outbound_dropped", venue=venue, priority=priority.name.lower())
+                raise RateShapedError(f"{venue} message dropped after {timeout}s in the outbound queue") from None
+        except asyncio.CancelledError:
+            if waiter in self._waiters:
+                self._waiters.remove(waiter)
+            raise
+        waited = self._clock() - started
+        METRICS.inc("outbound_messages", venue=venue, priority=priority.name.lower())
+        if waited > 0:
+            METRICS.max("outbound_wait_ms_max", waited * 1000, venue=venue, priority=priority.name.lower())
+        return waited
+
+    def _dispatch(self) -> None:
+        if self._timer is not None:
+            self._timer.cancel()
+            self._timer = None
+        now = self._clock()
+        buckets = [b for b in (self._global, *self._venues.values()) if b is not None]
+        for bucket in buckets:
+            bucket.refill(now)
+        blocked: set[str] = set()
+        for waiter in list(self._waiters):
+            if self._global is not None and self._global.tokens < 1:
+                break
+            bucket = self._venues.get(waiter.venue)
+            if waiter.venue in blocked or (bucket is not None and bucket.tokens < 1):
+                blocked.add(waiter.venue)
+                continue
+            if bucket is not None:
+                bucket.tokens -= 1
+            if self._global is not None:
+                self._global.tokens -= 1
+            self._waiters.remove(waiter)
+            if not waiter.future.done():
+                waiter.future.set_result(None)
+        METRICS.set("outbound_queued", len(self._waiters))
+        if not self._waiters:
+            return
+        waits = [self._global.wait_secs()] if self._global is not None else []
+        waits += [bucket.wait_secs() for venue, bucket in self._venues.items() if venue in blocked]
+        delay = max(min(w for w in waits if w > 0), 0.001) if any(w > 0 for w in waits) else 0.001
+        self._timer = asyncio.get_running_loop().call_later(delay, self._dispatch)
+
+
+__all__ = ["MessagePriority", "RateShapedError", "RateShaper", "RateShaperConfig"]
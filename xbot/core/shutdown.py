@@ -0,0 +1,137 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import signal
+import time
+from dataclasses import dataclass, field
+from typing import Any, Awaitable, Callable, Dict, List, Optional
+
+from ..utils.logging import get_logger
+
+StepFn = Callable[[], Awaitable[Any]]
+
+# Steps run in phase order; inside a phase they run in registration order.
+PHASE_INTAKE = 0  # stop accepting new commands/orders
+PHASE_ORDERS = 1  # cancel resting orders (optional)
+PHASE_SERVICES = 2  # stop strategies' helpers, recorders, reporters
+PHASE_TRANSPORT = 3  # close WebSockets and HTTP clients
+PHASE_STORAGE = 4  # flush write queues last so late cancel acks are persisted
+
+
+@dataclass(slots=True)
+class ShutdownConfig:
+    cancel_open_orders: bool = True
+    step_timeout_secs: float = 10.0
+
+
+@dataclass(slots=True)
+class ShutdownSummary:
+    reason: str
+    duration_secs: float = 0.0
+    steps: Dict[str, Any] = field(default_factory=dict)
+    errors: List[str] = field(default_factory=list)
+
+    @property
+    def exit_code(self) -> int:
+        return 1 if self.errors else 0
+
+
+class ShutdownCoordinator:
+    """Turns SIGINT/SIGTERM (or normal completion) into an ordered, bounded teardown."""
+
+    def __init__(self, config: Optional[ShutdownConfig] = None) -> None:
+        self._config = config or ShutdownConfig()
+        self._steps: List[tuple[int, str, StepFn]] = []
+        self._requested = asyncio.Event()
+        self._reason = "completed"
+        self._summary: Optional[ShutdownSummary] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> ShutdownConfig:
+        return self._config
+
+    @property
+    def requested(self) -> bool:
+        return self._requested.is_set()
+
+    def register(self, name: str, step: StepFn, *, phase: int = PHASE_SERVICES) -> None:
+        self._steps.append((phase, name, step))
+
+    def request(self, reason: str) -> None:
+        if not self._requested.is_set():
+            self._reason = reason
+            self._logger.info("shutdown_requested", extra={"reason": reason})
+            self._requested.set()
+
+    def install_signal_handlers(self) -> None:
+        loop = asyncio.get_running_loop()
+        for sig in (signal.SIGINT, signal.SIGTERM):
+            try:
+                loop.add_signal_handler(sig, self.request, sig.name)
+            except (NotImplementedError, RuntimeError):
+                # Windows event loops lack add_signal_handler; fall back to the sync API
+                signal.signal(sig, lambda signum, _frame: loop.call_soon_threadsafe(self.request, signal.Signals(signum).name))
+
+    async def run_until_shutdown(self, main: Awaitable[Any]) -> ShutdownSummary:
+        """Run `main` until it finishes or a shutdown is requested, then tear everything down."""
+        task = asyncio.ensure_future(main)
+        waiter = asyncio.ensure_future(self._requested.wait())
+        try:
+            await asyncio.wait({task, waiter}, return_when=asyncio.FIRST_COMPLETED)
+        finally:
+            waiter.cancel()
+        errors: List[str] = []
+        if not task.done():
+            task.cancel()
+            with contextlib.suppress(asyncio.CancelledError, Exception):
+                await task
+        elif task.exception() is not None:
+            self._reason = "error"
+            errors.append(f"main: {task.exception()!r}")
+        summary = await self.shutdown()
+        summary.errors[:0] = errors
+        self._log_summary(summary)
+        return summary
+
+    async def shutdown(self) -> ShutdownSummary:
+        if self._summary is not None:
+            return self._summary
+        started = time.monotonic()
+        summary = ShutdownSummary(reason=self._reason)
+        for phase, name, step in sorted(self._steps, key=lambda s: s[0]):
+            try:
+                summary.steps[name] = await asyncio.wait_for(step(), timeout=self._config.step_timeout_secs)
+            except asyncio.TimeoutError:
+                summary.errors.append(f"{name}: timed out after {self._config.step_timeout_secs}s")
+            except Exception as exc:
+                summary.errors.append(f"{name}: {exc!r}")
+        summary.duration_secs = time.monotonic() - started
+        self._summary = summary
+        return summary
+
+    def _log_summary(self, summary: ShutdownSummary) -> None:
+        steps = {k: v for k, v in summary.steps.items() if v is not None}
+        self._logger.info(
+            "shutdown_complete",
+            extra={
+                "reason": summary.reason,
+                "duration_secs": round(summary.duration_secs, 3),
+                "steps": steps,
+                "errors": summary.errors,
+                "exit_code": summary.exit_code,
+            },
+        )
+
+
+__all__ = [
+    "ShutdownCoordinator",
+    "ShutdownConfig",
+    "ShutdownSummary",
+    "PHASE_INTAKE",
+    "PHASE_ORDERS",
+    "PHASE_SERVICES",
+    "PHASE_STORAGE",
+    "PHASE_TRANSPORT",
+]
@@ -0,0 +1,93 @@
+from __future__ import annotations
+
+import dataclasses
+import time
+from typing import TYPE_CHECKING, Dict, List, Optional, Tuple
+
+if TYPE_CHECKING:
+    from .events import Event
+
+# Mirrors execution.models.FINAL_STATES; core does not import the execution layer
+_FINAL_ORDER_STATES = frozenset({"filled", "cancelled", "failed"})
+
+StateKey = Tuple[str, str, str]
+
+# EventKind value -> what identifies one piece of that state ("symbol" is Event.symbol, else a data field)
+_KEY_FIELDS = {
+    "position_update": "symbol",
+    "market_data": "symbol",
+    "balance_update": "asset",
+    "order_update": "client_order_index",
+    "connection_status": "feed",
+    "signal": "symbol",
+}
+
+
+class StateCache:
+    """Latest event per piece of state, so a new subscriber starts from the current picture.
+
+    Kept up to date by `EventStream.publish`: one entry per position
+    (source, symbol), balance (source, asset), live order (source, client
+    order index), top of book (source, symbol), order-flow signals (source,
+    symbol) and feed connection (source, feed). Orders leave the cache when
+    they reach a final state. Trades, fills and other one-off events are not
+    state and are never replayed.
+
+    It also records when each (source, endpoint) was last refreshed: every
+    cached event kind counts as an endpoint, and REST pollers call
+    `mark_fresh` for the calls they make (`positions`, `margin`, ...), so
+    consumers can tell how old each part of the picture is.
+    """
+
+    def __init__(self) -> None:
+        self._latest: Dict[StateKey, "Event"] = {}
+        self._fresh: Dict[Tuple[str, str], float] = {}
+
+    @staticmethod
+    def key(event: "Event") -> Optional[StateKey]:
+        kind = event.kind.value
+        field = _KEY_FIELDS.get(kind)
+        if field is None:
+            return None
+        part = event.symbol if field == "symbol" else event.data.get(field)
+        return None if part is None else (kind, event.source, str(part))
+
+    def update(self, event: "Event") -> None:
+        key = self.key(event)
+        if key is None:
+            return
+        self._fresh[(event.source, key[0])] = event.ts
+        if key[0] == "order_update" and event.data.get("state") in _FINAL_ORDER_STATES:
+            self._latest.pop(key, None)
+            return
+        # Re-insert so iteration order follows the latest update
+        self._latest.pop(key, None)
+        self._latest[key] = event
+
+    def snapshot(self) -> List["Event"]:
+        """Copies of the cached events, oldest update first, flagged with `snapshot=True`."""
+        return [dataclasses.replace(event, snapshot=True) for event in self._latest.values()]
+
+    def mark_fresh(self, source: str, endpoint: str, ts: Optional[float] = None) -> None:
+        self._fresh[(source, endpoint)] = time.time() if ts is None else ts
+
+    def fresh_at(self, source: str, endpoint: str) -> Optional[float]:
+        return self._fresh.get((source, endpoint))
+
+    def age(self, source: str, endpoint: str, now: Optional[float] = None) -> Optional[float]:
+        """Seconds since `endpoint` was last refreshed for `source`; None if it never was."""
+        ts = self._fresh.get((source, endpoint))
+        return None if ts is None else (time.time() if now is None else now) - ts
+
+    def freshness(self) -> Dict[str, Dict[str, float]]:
+        """source -> endpoint -> last refresh time (epoch seconds)."""
+        out: Dict[str, Dict[str, float]] = {}
+        for (source, endpoint), ts in self._fresh.items():
+            out.setdefault(source, {})[endpoint] = ts
+        return out
+
+    def __len__(self) -> int:
+        return len(self._latest)
+
+
+__all__ = ["StateCache"]
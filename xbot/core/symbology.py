@@ -0,0 +1,208 @@
+from __future__ import annotations
+
+import dataclasses
+import re
+import threading
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Any, Dict, Iterable, List, Optional
+
+SPOT = "spot"
+PERP = "perp"
+FUTURE = "future"
+
+_SEPARATORS = re.compile(r"[_\-/:]")
+_PERP_SUFFIXES = {"PERP", "SWAP"}
+
+
+@dataclass(slots=True, frozen=True)
+class Instrument:
+    """One tradable market with its currencies spelled out, never inferred from other markets."""
+
+    venue: str
+    native: str  # the venue's own spelling, e.g. SOL_USDC_PERP
+    base: str
+    quote: str
+    settle: Optional[str] = None  # margin/PnL currency of derivatives; None for spot
+    kind: str = SPOT  # spot | perp | future
+    multiplier: Decimal = Decimal(1)  # base units per contract
+    expiry: Optional[str] = None  # futures only, as the venue labels it
+
+    @property
+    def inverse(self) -> bool:
+        """Coin-margined: settled in the base asset rather than the quote."""
+        return self.settle is not None and self.settle == self.base
+
+    @property
+    def derivative(self) -> bool:
+        return self.kind != SPOT
+
+    @property
+    def id(self) -> str:
+        """Venue-independent instrument id: `SOL/USDC`, `SOL/USDC:USDC` (linear perp), `BTC/USD:BTC` (inverse)."""
+        if self.kind == SPOT:
+            return f"{self.base}/{self.quote}"
+        suffix = f"-{self.expiry}" if self.kind == FUTURE and self.expiry else ""
+        return f"{self.base}/{self.quote}:{self.settle or self.quote}{suffix}"
+
+    @property
+    def pnl_currency(self) -> str:
+        """Where fees, funding and realized PnL land: the settlement currency of derivatives, the quote of spot."""
+        return self.settle or self.quote
+
+    def notional(self, qty: Decimal, price: Decimal) -> Decimal:
+        """Quote-currency notional of `qty` contracts; an inverse contract's face value does not move with price."""
+        if self.inverse:
+            return abs(qty) * self.multiplier
+        return abs(qty) * self.multiplier * price
+
+    def value(self, qty: Decimal, price: Decimal) -> Decimal:
+        """Worth of `qty` contracts in the PnL currency: quote for linear contracts, base coins for inverse ones."""
+        if self.inverse:
+            return qty * self.multiplier / price if price else Decimal(0)
+        return qty * self.multiplier * price
+
+    def avg_price(self, qty: Decimal, value: Decimal) -> Decimal:
+        """Average price at which `qty` contracts were worth `value`; harmonic for inverse contracts."""
+        if not qty or not value:
+            return Decimal(0)
+        if self.inverse:
+            return qty * self.multiplier / value
+        return value / (qty * self.multiplier)
+
+    def pnl(self, qty: Decimal, entry: Decimal, exit: Decimal) -> Decimal:
+        """PnL in `pnl_currency` of a signed `qty` (positive long) carried from `entry` to `exit`."""
+        if self.inverse:
+            if not entry or not exit:
+                return Decimal(0)
+            return qty * self.multiplier * (1 / entry - 1 / exit)
+        return qty * self.multiplier * (exit - entry)
+
+
+def parse_native(symbol: str, *, venue: str = "") -> Instrument:
+    """Best-effort instrument from a symbol alone, for markets no venue metadata described.
+
+    Each part is taken literally: `SOL_USDC_PERP` is a USDC-settled SOL
+    perp and `USDT/USDC` is USDT quoted in USDC. No quote currency is ever
+    substituted for another. A bare `SOL` has an empty quote, which callers
+    must treat as unknown.
+    """
+    parts = [part for part in _SEPARATORS.split(symbol.strip().upper()) if part]
+    if not parts:
+        raise ValueError(f"cannot parse an instrument from {symbol!r}")
+    kind = SPOT
+    if len(parts) > 1 and parts[-1] in _PERP_SUFFIXES:
+        kind = PERP
+        parts = parts[:-1]
+    base = parts[0]
+    quote = parts[1] if len(parts) > 1 else ""
+    settle = (parts[2] if len(parts) > 2 else quote) if kind != SPOT else None
+    return Instrument(venue=venue, native=symbol, base=base, quote=quote, settle=settle or None, kind=kind)
+
+
+def base_asset(symbol: str) -> str:
+    """`SOL`, `SOL_USDC_PERP`, `SOL-PERP` and `SOL/USDC` all have base `SOL`."""
+    return parse_native(symbol).base
+
+
+class Symbology:
+    """Per-venue instrument tables, filled from each venue's market metadata.
+
+    Connectors register an `Instrument` for every market they load, so the
+    base, quote, settlement currency and contract multiplier come from the
+    venue and not from how the symbol happens to be spelled. `instrument()`
+    falls back to `parse_native` for markets that were never loaded (e.g.
+    a historical fill on a since-delisted market).
+    """
+
+    def __init__(self) -> None:
+        self._by_native: Dict[str, Dict[str, Instrument]] = {}
+        self._by_id: Dict[str, Dict[str, Instrument]] = {}
+        self._overrides: Dict[str, Dict[str, Dict[str, Any]]] = {}
+        self._lock = threading.Lock()
+
+    def override(self, venue: str, native: str, **changes: Any) -> None:
+        """Correct what a venue's metadata leaves out, e.g. a contract multiplier; survives market reloads.
+
+        Takes `Instrument` field names, plus `inverse=True` as shorthand for
+        settling in the base asset.
+        """
+        with self._lock:
+            self._overrides.setdefault(venue.lower(), {})[native] = dict(changes)
+        found = self.get(venue, native)
+        if found is not None:
+            self.register(found)
+
+    def _apply(self, instrument: Instrument) -> Instrument:
+        changes = dict(self._overrides.get(instrument.venue.lower(), {}).get(instrument.native) or {})
+        if not changes:
+            return instrument
+        inverse = changes.pop("inverse", None)
+        updated = dataclasses.replace(instrument, **changes)
+        if inverse is not None:
+            settle = updated.base if inverse else (changes.get("settle") or updated.quote)
+            updated = dataclasses.replace(updated, settle=settle if updated.derivative else None)
+        return updated
+
+    def register(self, instrument: Instrument) -> None:
+        instrument = self._apply(instrument)
+        venue = instrument.venue.lower()
+        with self._lock:
+            previous = self._by_native.get(venue, {}).get(instrument.native)
+            if previous is not None and previous.id != instrument.id:
+                self._by_id.get(venue, {}).pop(previous.id, None)
+            self._by_native.setdefault(venue, {})[instrument.native] = instrument
+            self._by_id.setdefault(venue, {})[instrument.id] = instrument
+
+    def load(self, venue: str, instruments: Iterable[Instrument], *, replace: bool = True) -> int:
+        """Register a venue's market list; with `replace`, markets missing from it are dropped."""
+        rows = list(instruments)
+        with self._lock:
+            if replace:
+                self._by_native[venue.lower()] = {}
+                self._by_id[venue.lower()] = {}
+        for instrument in rows:
+            self.register(instrument)
+        return len(rows)
+
+    def get(self, venue: str, native: str) -> Optional[Instrument]:
+        return self._by_native.get(venue.lower(), {}).get(native)
+
+    def instrument(self, venue: str, native: str) -> Instrument:
+        return self.get(venue, native) or self._apply(parse_native(native, venue=venue.lower()))
+
+    def by_id(self, venue: str, instrument_id: str) -> Optional[Instrument]:
+        return self._by_id.get(venue.lower(), {}).get(instrument_id)
+
+    def native(self, venue: str, instrument_id: str) -> Optional[str]:
+        """The venue's symbol for a canonical instrument id, e.g. `SOL/USDC:USDC` -> `SOL_USDC_PERP`."""
+        found = self.by_id(venue, instrument_id)
+        return found.native if found else None
+
+    def find(
+        self, venue: str, *, base: str, quote: Optional[str] = None, kind: Optional[str] = None
+    ) -> List[Instrument]:
+        return [
+            instrument
+            for instrument in self._by_native.get(venue.lower(), {}).values()
+            if instrument.base == base.upper()
+            and (quote is None or instrument.quote == quote.upper())
+            and (kind is None or instrument.kind == kind)
+        ]
+
+    def instruments(self, venue: str) -> List[Instrument]:
+        return list(self._by_native.get(venue.lower(), {}).values())
+
+
+SYMBOLOGY = Symbology()
+
+__all__ = [
+    "FUTURE",
+    "Instrument",
+    "PERP",
+    "SPOT",
+    "SYMBOLOGY",
+    "Symbology",
+    "base_asset",
+    "parse_native",
+]
@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+import sys
+import threading
+from typing import Dict, List, NewType, Optional
+
+SymbolId = NewType("SymbolId", int)
+
+
+def normalize_symbol(symbol: str) -> str:
+    return symbol.strip().upper()
+
+
+class SymbolRegistry:
+    """Process-wide symbol names and small integer ids.
+
+    `intern(symbol)` returns the one shared string object for a normalized
+    symbol. Hot-path dicts keyed by it (cache books, routing tables) then hit
+    on identity and never rehash a freshly built string; CPython caches a
+    string's hash on the object. `id(symbol)` hands out dense `SymbolId`s for
+    structures that want list indexing instead of hashing. Ids are never
+    reused, so they stay valid for the process lifetime.
+    """
+
+    def __init__(self) -> None:
+        self._ids: Dict[str, SymbolId] = {}
+        self._names: List[str] = []
+        self._lock = threading.Lock()
+
+    def intern(self, symbol: str) -> str:
+        return self._names[self.id(symbol)]
+
+    def id(self, symbol: str) -> SymbolId:
+        found = self._ids.get(symbol)
+        if found is not None:
+            return found
+        key = normalize_symbol(symbol)
+        with self._lock:
+            found = self._ids.get(key)
+            if found is None:
+                key = sys.intern(key)
+                found = SymbolId(len(self._names))
+                self._names.append(key)
+                self._ids[key] = found
+            # Also remember the caller's spelling so the next lookup skips normalizing
+            self._ids.setdefault(symbol, found)
+            return found
+
+    def get(self, symbol: str) -> Optional[SymbolId]:
+        """Id of an already registered symbol, without registering it."""
+        found = self._ids.get(symbol)
+        return found if found is not None else self._ids.get(normalize_symbol(symbol))
+
+    def name(self, symbol_id: SymbolId) -> str:
+        return self._names[symbol_id]
+
+    def __len__(self) -> int:
+        return len(self._names)
+
+
+SYMBOLS = SymbolRegistry()
+
+__all__ = ["SYMBOLS", "SymbolId", "SymbolRegistry", "normalize_symbol"]
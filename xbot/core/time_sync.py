@@ -0,0 +1,142 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import statistics
+import time
+from typing import Awaitable, Callable, Dict, List, Optional, Sequence
+
+from ..utils.logging import get_logger
+
+ServerTimeFn = Callable[[], Awaitable[Optional[int]]]
+
+
+class ClockOffset:
+    """Server-minus-local clock offset for one venue, applied to signing timestamps."""
+
+    def __init__(self, venue: str) -> None:
+        self.venue = venue
+        self.offset_ms = 0
+        self.rtt_ms: Optional[float] = None
+        self.updated_at: Optional[float] = None
+
+    def update(self, offset_ms: int, rtt_ms: float) -> None:
+        self.offset_ms = offset_ms
+        self.rtt_ms = rtt_ms
+        self.updated_at = time.time()
+
+    def now(self) -> float:
+        return time.time() + self.offset_ms / 1000.0
+
+    def timestamp_ms(self) -> int:
+        return int(time.time() * 1000) + self.offset_ms
+
+
+_OFFSETS: Dict[str, ClockOffset] = {}
+
+
+def clock_offset(venue: str) -> ClockOffset:
+    """Process-wide offset for `venue`; REST and WS signers share the same instance."""
+    key = venue.lower()
+    offset = _OFFSETS.get(key)
+    if offset is None:
+        offset = _OFFSETS[key] = ClockOffset(key)
+    return offset
+
+
+def get_timestamp(venue: str) -> int:
+    return clock_offset(venue).timestamp_ms()
+
+
+class TimeSyncService:
+    """Periodically estimates each venue's clock offset from its server-time endpoint.
+
+    Each round takes a few samples and keeps the median offset of the fastest
+    half (lowest round trip), using the midpoint of the request as the local
+    reference. Drift above `warn_drift_ms` is logged so a misconfigured VPS
+    clock shows up before signatures start failing.
+    """
+
+    def __init__(
+        self,
+        *,
+        sources: Dict[str, ServerTimeFn],
+        interval_secs: float = 300.0,
+        samples: int = 5,
+        warn_drift_ms: int = 500,
+    ) -> None:
+        self._sources = dict(sources)
+        self._interval = interval_secs
+        self._samples = max(1, samples)
+        self._warn_drift_ms = warn_drift_ms
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    async def _measure(self, fetch: ServerTimeFn) -> Optional[tuple[int, float]]:
+        readings: List[tuple[float, int]] = []
+        for _ in range(self._samples):
+            t0 = time.time()
+            server_ms = await fetch()
+            t1 = time.time()
+            if server_ms is None:
+                return None
+            local_mid_ms = (t0 + t1) * 500.0
+            readings.append(((t1 - t0) * 1000.0, int(server_ms - local_mid_ms)))
+        readings.sort(key=lambda r: r[0])
+        best = readings[: max(1, len(readings) // 2)]
+        return int(statistics.median(o for _rtt, o in best)), best[0][0]
+
+    async def sync_once(self) -> Dict[str, int]:
+        results: Dict[str, int] = {}
+        for venue, fetch in self._sources.items():
+            try:
+                measured = await self._measure(fetch)
+            except Exception as exc:
+                self._logger.info("time_sync_error", extra={"venue": venue, "error": str(exc)})
+                continue
+            if measured is None:
+                continue
+            offset_ms, rtt_ms = measured
+            clock_offset(venue).update(offset_ms, rtt_ms)
+            results[venue] = offset_ms
+            log = self._logger.warning if abs(offset_ms) >= self._warn_drift_ms else self._logger.info
+            log("time_sync", extra={"venue": venue, "offset_ms": offset_ms, "rtt_ms": round(rtt_ms, 1)})
+        return results
+
+    async def start(self) -> None:
+        if self._task is None and self._sources:
+            await self.sync_once()
+            self._task = asyncio.create_task(self._run(), name="time-sync")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            await asyncio.sleep(self._interval)
+            await self.sync_once()
+
+
+def server_time_sources(connectors: Sequence[object]) -> Dict[str, ServerTimeFn]:
+    """Collect `get_server_time_ms` from connectors that expose a server clock."""
+    sources: Dict[str, ServerTimeFn] = {}
+    for connector in connectors:
+        fetch = getattr(connector, "get_server_time_ms", None)
+        venue = getattr(connector, "venue", None)
+        if fetch is not None and venue:
+            sources[str(venue)] = fetch
+    return sources
+
+
+__all__ = [
+    "ClockOffset",
+    "TimeSyncService",
+    "clock_offset",
+    "get_timestamp",
+    "server_time_sources",
+]
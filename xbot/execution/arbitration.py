@@ -0,0 +1,179 @@
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Dict, List, Optional, Tuple, TYPE_CHECKING
+
+from .models import FINAL_STATES, Order
+from .order_expiry import ordered_size_i
+from .risk_service import RiskViolationError
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .order_service import OrderService
+
+MANUAL = "manual"  # orders whose trace_id names no strategy (operator commands, tooling)
+
+
+class ArbitrationError(RiskViolationError):
+    """Raised when an opening order would take the strategies' combined exposure past its cap."""
+
+
+@dataclass(slots=True)
+class ArbitrationConfig:
+    max_combined: Dict[str, Decimal] = field(default_factory=dict)  # symbol -> base cap per direction; "*" for the rest
+    priority: List[str] = field(default_factory=list)  # strategy names, highest first; unlisted ones rank last
+
+    def cap(self, symbol: str) -> Optional[Decimal]:
+        return self.max_combined.get(symbol.upper(), self.max_combined.get("*"))
+
+    def rank(self, strategy: str) -> int:
+        return self.priority.index(strategy) if strategy in self.priority else len(self.priority)
+
+
+def strategy_of(order: Order) -> str:
+    """Strategy that sent an order: the `trace_id` prefix before `:`."""
+    trace = order.trace_id or ""
+    return trace.split(":", 1)[0] if ":" in trace else MANUAL
+
+
+async def _filled_i(service: "OrderService", order: Order) -> int:
+    """Filled size in size steps, from the base quantity OrderService counted from the order's fills."""
+    return await service.market_data.to_size_i(order.symbol, order.filled)
+
+
+@dataclass(slots=True)
+class _Exposure:
+    filled_i: int = 0  # signed, + long
+    pending_long_i: int = 0
+    pending_short_i: int = 0
+    pending: List[Tuple["OrderService", Order]] = field(default_factory=list)
+
+    def worst(self, long: bool) -> int:
+        """Exposure in one direction if every resting order on that side filled."""
+        if long:
+            return max(self.filled_i + self.pending_long_i, 0)
+        return max(-self.filled_i + self.pending_short_i, 0)
+
+
+class PositionArbiter:
+    """Keeps strategies from piling into the same directional trade on a symbol.
+
+    Every strategy's exposure is built from the orders its trace_id tags
+    in the registered `OrderService`s: filled size plus what its resting
+    orders would add. An opening order is allowed while the combined
+    exposure of all strategies in its direction, counting resting orders
+    as filled, stays within `max_combined` for the symbol. When it would
+    not, resting opening orders of lower-priority strategies in the same
+    direction are cancelled to make room, lowest first; if that is not
+    enough the order is refused with `ArbitrationError`. Filled positions
+    are never closed by the arbiter. Orders that reduce their strategy's
+    own exposure always pass. Exposure covers orders placed since the
+    process started.
+    """
+
+    def __init__(self, config: ArbitrationConfig) -> None:
+        self._config = config
+        self._services: List["OrderService"] = []
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> ArbitrationConfig:
+        return self._config
+
+    def register(self, service: "OrderService") -> None:
+        if service not in self._services:
+            self._services.append(service)
+            service.attach_arbiter(self)
+
+    async def exposures(self, symbol: str) -> Dict[str, _Exposure]:
+        found: Dict[str, _Exposure] = {}
+        for service in self._services:
+            for order in await service.orders(symbol):
+                exposure = found.setdefault(strategy_of(order), _Exposure())
+                filled = await _filled_i(service, order)
+                exposure.filled_i += -filled if order.is_ask else filled
+                if order.state in FINAL_STATES:
+                    continue
+                remaining = max(ordered_size_i(order) - filled, 0)
+                if order.is_ask:
+                    exposure.pending_short_i += remaining
+                else:
+                    exposure.pending_long_i += remaining
+                exposure.pending.append((service, order))
+        return found
+
+    async def check(
+        self, *, service: "OrderService", symbol: str, is_ask: bool, size_i: int, trace_id: Optional[str]
+    ) -> None:
+        cap = self._config.cap(symbol)
+        if cap is None:
+            return
+        _, size_decimals = await service.market_data.get_price_size_decimals(symbol)
+        cap_i = int(cap * Decimal(10) ** size_decimals)
+        strategy = trace_id.split(":", 1)[0] if trace_id and ":" in trace_id else MANUAL
+        exposures = await self.exposures(symbol)
+        own = exposures.setdefault(strategy, _Exposure())
+        long = not is_ask
+        opening = long and own.filled_i >= 0 or not long and own.filled_i <= 0
+        if not opening and size_i <= abs(own.filled_i):
+            return
+        combined = sum(e.worst(long) for e in exposures.values())
+        before = own.worst(long)
+        if long:
+            own.pending_long_i += size_i
+        else:
+            own.pending_short_i += size_i
+        excess = combined - before + own.worst(long) - cap_i
+        if excess <= 0:
+            return
+        extra = {"symbol": symbol, "strategy": strategy, "is_ask": is_ask, "size_i": size_i, "cap_i": cap_i}
+        excess = await self._yield(symbol, strategy, long, exposures, excess, extra)
+        if excess <= 0:
+            return
+        METRICS.inc("arbitration_rejected", symbol=symbol, strategy=strategy)
+        self._logger.info("arbitration_rejected", extra={**extra, "excess_i": excess})
+        side = "sell" if is_ask else "buy"
+        raise ArbitrationError(
+            f"{strategy} {side} on {symbol} would take combined exposure past {cap}; {excess} size units over"
+        )
+
+    async def _yield(
+        self, symbol: str, strategy: str, long: bool, exposures: Dict[str, _Exposure], excess: int, extra: Dict
+    ) -> int:
+        """Cancel lower-priority resting opening orders until `excess` is gone; returns what is left."""
+        rank = self._config.rank(strategy)
+        losers = sorted(
+            (name for name in exposures if name != strategy and self._config.rank(name) > rank),
+            key=self._config.rank,
+            reverse=True,
+        )
+        for name in losers:
+            exposure = exposures[name]
+            for service, order in exposure.pending:
+                if excess <= 0:
+                    return excess
+                if order.is_ask == long:
+                    continue
+                before = exposure.worst(long)
+                remaining = max(ordered_size_i(order) - await _filled_i(service, order), 0)
+                try:
+                    await service.cancel(symbol, order.client_order_index)
+                except Exception as exc:
+                    self._logger.info("arbitration_cancel_error", extra={**extra, "error": str(exc)})
+                    continue
+                if long:
+                    exposure.pending_long_i -= remaining
+                else:
+                    exposure.pending_short_i -= remaining
+                excess -= before - exposure.worst(long)
+                METRICS.inc("arbitration_yielded", symbol=symbol, strategy=name)
+                self._logger.info(
+                    "arbitration_yielded",
+                    extra={**extra, "yielded_by": name, "client_order_index": order.client_order_index},
+                )
+        return excess
+
+
+__all__ = ["ArbitrationConfig", "ArbitrationError", "MANUAL", "PositionArbiter", "strategy_of"]
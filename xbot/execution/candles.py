@@ -0,0 +1,209 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from collections import defaultdict, deque
+from dataclasses import dataclass, replace
+from decimal import Decimal, InvalidOperation
+from typing import Deque, Dict, List, Optional, Sequence, Tuple, TYPE_CHECKING
+
+from ..core.events import EventKind, EventStream
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .indicators import Indicators
+
+
+@dataclass(slots=True)
+class CandleConfig:
+    interval_secs: float = 60.0
+    history: int = 500  # closed candles kept per symbol
+
+
+@dataclass(slots=True)
+class Candle:
+    start: float
+    open: Decimal
+    high: Decimal
+    low: Decimal
+    close: Decimal
+    volume: Decimal = Decimal(0)
+    trades: int = 0
+
+    def add(self, price: Decimal, qty: Decimal) -> None:
+        self.high = max(self.high, price)
+        self.low = min(self.low, price)
+        self.close = price
+        self.volume += qty
+        self.trades += 1
+
+
+def resample(candles: Sequence[Candle], interval_secs: float) -> List[Candle]:
+    """Candles, oldest first, merged into buckets of `interval_secs` aligned like the originals.
+
+    A bucket takes the first open, the last close, the extremes and the
+    summed volume and trade count of the candles starting inside it. The
+    last bucket may still be forming; callers decide whether it counts.
+    """
+    merged: List[Candle] = []
+    for candle in candles:
+        start = candle.start - candle.start % interval_secs
+        last = merged[-1] if merged else None
+        if last is None or last.start != start:
+            merged.append(replace(candle, start=start))
+            continue
+        last.high = max(last.high, candle.high)
+        last.low = min(last.low, candle.low)
+        last.close = candle.close
+        last.volume += candle.volume
+        last.trades += candle.trades
+    return merged
+
+
+def average_true_range(candles: List[Candle], period: int = 14) -> Optional[Decimal]:
+    """Wilder's ATR over closed candles, oldest first; None until `period + 1` candles exist."""
+    if period <= 0 or len(candles) < period + 1:
+        return None
+    ranges = [
+        max(cur.high - cur.low, abs(cur.high - prev.close), abs(cur.low - prev.close))
+        for prev, cur in zip(candles, candles[1:])
+    ]
+    atr = sum(ranges[:period], Decimal(0)) / period
+    for value in ranges[period:]:
+        atr = (atr * (period - 1) + value) / period
+    return atr
+
+
+class CandleService:
+    """Builds time-bucketed OHLCV candles per symbol from the `TRADE` event stream.
+
+    Buckets follow local receipt time, since venue trade stamps differ in
+    unit and meaning. A bucket with no trades produces no candle, so a quiet
+    market yields fewer, not flat, candles. Symbols are the venue symbols
+    the feeds publish trades under.
+
+    `candles(symbol, interval_secs=...)` serves coarser timeframes built
+    from the base candles, e.g. 5m from 1m. The interval must be a multiple
+    of the base one. Each derived series is cached until the symbol's base
+    candles change, so strategies sharing a timeframe resample it once.
+    """
+
+    def __init__(self, *, events: Optional[EventStream] = None, config: Optional[CandleConfig] = None) -> None:
+        self._events = events
+        self._config = config or CandleConfig()
+        self._closed: Dict[str, Deque[Candle]] = defaultdict(lambda: deque(maxlen=self._config.history))
+        self._current: Dict[str, Candle] = {}
+        self._versions: Dict[str, int] = defaultdict(int)  # bumped whenever a symbol's candles change
+        self._resampled: Dict[Tuple[str, float], Tuple[Tuple[int, bool], List[Candle]]] = {}
+        self._indicators: Dict[Tuple[str, Optional[float]], "Indicators"] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def interval_secs(self) -> float:
+        return self._config.interval_secs
+
+    def symbols(self) -> List[str]:
+        return sorted(set(self._closed) | set(self._current))
+
+    def on_trade(self, symbol: str, price: Decimal, qty: Decimal = Decimal(0), ts: Optional[float] = None) -> None:
+        ts = time.time() if ts is None else ts
+        start = ts - ts % self._config.interval_secs
+        self._versions[symbol] += 1
+        current = self._current.get(symbol)
+        if current is not None and current.start < start:
+            self._closed[symbol].append(current)
+            current = None
+        if current is None:
+            self._current[symbol] = Candle(start=start, open=price, high=price, low=price, close=price, volume=qty, trades=1)
+            return
+        current.add(price, qty)
+
+    def candles(
+        self,
+        symbol: str,
+        limit: Optional[int] = None,
+        *,
+        now: Optional[float] = None,
+        interval_secs: Optional[float] = None,
+    ) -> List[Candle]:
+        """Closed candles, oldest first; the forming one is included once its interval has passed.
+
+        With `interval_secs`, candles of that timeframe resampled from the
+        base ones; a derived candle is closed once its own interval has
+        passed. When the base history is full, the oldest derived candle is
+        dropped, since part of it may have been evicted.
+        """
+        now = time.time() if now is None else now
+        closed = list(self._closed.get(symbol, ()))
+        current = self._current.get(symbol)
+        forming_done = current is not None and now >= current.start + self._config.interval_secs
+        if current is not None and forming_done:
+            closed.append(current)
+        if interval_secs is not None and interval_secs != self._config.interval_secs:
+            closed = self._derived(symbol, closed, interval_secs, forming_done, now)
+        return closed[-limit:] if limit else closed
+
+    def _derived(
+        self, symbol: str, base: List[Candle], interval_secs: float, forming_done: bool, now: float
+    ) -> List[Candle]:
+        ratio = interval_secs / self._config.interval_secs
+        if ratio < 1 or ratio != int(ratio):
+            base_secs = self._config.interval_secs
+            raise ValueError(f"interval_secs {interval_secs} is not a multiple of the base {base_secs}")
+        key, version = (symbol, interval_secs), (self._versions[symbol], forming_done)
+        cached = self._resampled.get(key)
+        if cached is None or cached[0] != version:
+            merged = resample(base, interval_secs)
+            if merged and len(self._closed.get(symbol, ())) == self._config.history:
+                merged = merged[1:]
+            cached = self._resampled[key] = (version, merged)
+        derived = cached[1]
+        if derived and now < derived[-1].start + interval_secs:
+            return derived[:-1]
+        return list(derived)
+
+    def atr(self, symbol: str, period: int = 14, *, interval_secs: Optional[float] = None) -> Optional[Decimal]:
+        return average_true_range(self.candles(symbol, interval_secs=interval_secs), period)
+
+    def indicators(self, symbol: str, interval_secs: Optional[float] = None) -> "Indicators":
+        """The shared incremental `Indicators` handle for a symbol and timeframe."""
+        from .indicators import Indicators  # indicators imports Candle from here
+
+        key = (symbol, interval_secs)
+        if key not in self._indicators:
+            self._indicators[key] = Indicators(self, symbol, interval_secs)
+        return self._indicators[key]
+
+    async def start(self) -> None:
+        if self._task is None and self._events is not None:
+            self._task = asyncio.create_task(self._run(), name="candles")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        assert self._events is not None
+        sub = self._events.subscribe(kinds=[EventKind.TRADE], name="candles")
+        try:
+            async for event in sub:
+                if event.symbol is None or event.snapshot:
+                    continue
+                try:
+                    price = Decimal(str(event.data["p"]))
+                    qty = Decimal(str(event.data.get("q") or 0))
+                except (KeyError, InvalidOperation) as exc:
+                    self._logger.info("candle_trade_error", extra={"symbol": event.symbol, "error": str(exc)})
+                    continue
+                self.on_trade(event.symbol, price, qty, event.ts)
+        finally:
+            sub.close()
+
+
+__all__ = ["Candle", "CandleConfig", "CandleService", "average_true_range", "resample"]
@@ -0,0 +1,93 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from decimal import Decimal
+from typing import Optional
+
+from ..analytics.equity import EquityTracker
+from ..core.alerts import AlertLevel, AlertManager
+from ..utils.logging import get_logger
+from .risk_service import RiskMode, RiskService
+
+
+class DrawdownCircuitBreaker:
+    """Switches the risk layer to reduce-only once equity drawdown exceeds a limit.
+
+    Drawdown is measured on the flow-adjusted equity curve over the trailing
+    `lookback_secs`, against `risk.limits.max_drawdown_pct` as it stands at each
+    check, so a limit changed at runtime applies; with no limit nothing trips.
+    The breaker latches: resuming normal trading is an explicit operator
+    decision via `RiskService.set_mode`. Once the mode is back to normal the
+    breaker re-arms, measuring drawdown from that moment so the loss it
+    already acted on does not trip it again.
+    """
+
+    def __init__(
+        self,
+        *,
+        equity: EquityTracker,
+        risk: RiskService,
+        alerts: AlertManager,
+        lookback_secs: float = 86400.0,
+        interval_secs: float = 60.0,
+        trip_mode: RiskMode = RiskMode.REDUCE_ONLY,
+    ) -> None:
+        self._equity = equity
+        self._risk = risk
+        self._alerts = alerts
+        self._lookback = lookback_secs
+        self._interval = interval_secs
+        self._trip_mode = trip_mode
+        self._tripped = False
+        self._armed_at = 0.0  # drawdown before this is not counted: the breaker already acted on it
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def tripped(self) -> bool:
+        return self._tripped
+
+    async def check(self) -> Decimal:
+        now = time.time()
+        if self._tripped and self._risk.mode == RiskMode.NORMAL:
+            self.reset(now)
+        drawdown = await self._equity.drawdown(start=max(now - self._lookback, self._armed_at))
+        limit = self._risk.limits.max_drawdown_pct
+        if limit is not None and drawdown >= limit and not self._tripped:
+            self._tripped = True
+            reason = f"drawdown {drawdown:.2%} >= {limit:.2%}"
+            self._risk.set_mode(self._trip_mode, reason)
+            self._logger.warning("drawdown_breaker_tripped", extra={"drawdown": str(drawdown), "limit": str(limit)})
+            await self._alerts.notify(AlertLevel.CRITICAL, "drawdown circuit breaker tripped", reason, mode=self._trip_mode.value)
+        return drawdown
+
+    def reset(self, now: Optional[float] = None) -> None:
+        """Re-arm the breaker, counting drawdown only from `now`."""
+        self._tripped = False
+        self._armed_at = time.time() if now is None else now
+        self._logger.info("drawdown_breaker_rearmed", extra={"since": self._armed_at})
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="drawdown-breaker")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.check()
+            except Exception:
+                self._logger.exception("drawdown_check_failed")
+            await asyncio.sleep(self._interval)
+
+
+__all__ = ["DrawdownCircuitBreaker"]
@@ -0,0 +1,127 @@
+from __future__ import annotations
+
+from typing import Any, Awaitable, Callable, Dict, Optional
+
+from ..core.command_bus import TradingCommand
+from .maker_first import MAKER_FIRST, MakerFirstPolicy
+from .order_progress import OrderProgressChannel
+from .router import ExecutionRouter
+from .sizing import VOLATILITY, VolatilitySizer
+from ..utils.idgen import client_order_index_for
+
+_ORDER_KINDS = ("submit_limit", "submit_market")
+_PLACING_KINDS = (*_ORDER_KINDS, "tracking_limit", MAKER_FIRST)
+_PRICE_KEYS = ("price", "price_i", "post_only")
+
+
+def maker_first_policy(policy: Any) -> Optional[MakerFirstPolicy]:
+    """A `MakerFirstPolicy`, or a mapping with `tactic: maker_first` (plus policy fields)."""
+    if isinstance(policy, MakerFirstPolicy):
+        return policy
+    if isinstance(policy, dict) and policy.get("tactic") == MAKER_FIRST:
+        return MakerFirstPolicy.from_mapping(policy)
+    return None
+
+
+class RouterCommandHandler:
+    """Executes `TradingCommand`s for one venue against its `ExecutionRouter`.
+
+    `command.kind` selects the router call and `command.payload` supplies its
+    keyword arguments, e.g. `TradingCommand(venue="backpack", kind="submit_market",
+    payload={"symbol": "SOL", "is_ask": False, "size": "0.1"})`. A maker-first
+    `command.policy` turns `submit_limit`/`submit_market` into the maker-first
+    tactic; the limit price in the payload is then ignored.
+
+    Plain `submit_limit`/`submit_market` commands without a
+    `client_order_index` get one derived from `command_id`, so handling the
+    same command twice returns the first order instead of placing another.
+    Maker-first and tracking-limit commands derive each order's index from
+    `command_id` the same way (their `client_order_key`), so a second run
+    replays the orders the first one placed.
+    A `time_in_force_ms` on a plain `submit_limit` cancels what is still
+    resting once it runs out.
+
+    Orders placed by a command are traced with its `command_id` unless the
+    payload names a `trace_id`, so `progress.stream(command_id)` follows them.
+
+    A placing command with `"sizing": "volatility"` and no size in its
+    payload is sized by the `VolatilitySizer` instead.
+    """
+
+    def __init__(
+        self,
+        router: ExecutionRouter,
+        *,
+        progress: Optional[OrderProgressChannel] = None,
+        sizer: Optional[VolatilitySizer] = None,
+    ) -> None:
+        self._router = router
+        self._progress = progress
+        self._sizer = sizer
+        self._dispatch: Dict[str, Callable[..., Awaitable[Any]]] = {
+            "submit_limit": router.submit_limit,
+            "submit_market": router.submit_market,
+            "tracking_limit": router.tracking_limit,
+            MAKER_FIRST: router.maker_first,
+            "cancel": router.cancel,
+            "cancel_all": router.orders.cancel_all,
+        }
+
+    async def __call__(self, command: TradingCommand) -> Any:
+        payload = dict(command.payload)
+        if command.kind not in _PLACING_KINDS:
+            return await self._handle(command, payload)
+        payload.setdefault("trace_id", command.command_id)
+        try:
+            return await self._handle(command, payload)
+        finally:
+            if self._progress is not None:
+                self._progress.complete(str(payload["trace_id"]))
+
+    async def _size(self, command: TradingCommand, payload: Dict[str, Any]) -> None:
+        sizing = payload.pop("sizing", None)
+        if sizing is None:
+            return
+        if sizing != VOLATILITY:
+            raise ValueError(f"unsupported sizing: {sizing}")
+        if self._sizer is None:
+            raise ValueError("volatility sizing is not configured")
+        if any(payload.get(key) is not None for key in ("size", "size_i", "base_amount_i")):
+            raise ValueError("sizing and an explicit size are mutually exclusive")
+        size = await self._sizer.size(str(payload["symbol"]))
+        if command.kind == "tracking_limit":
+            payload["base_amount_i"] = await self._router.market_data.to_size_i(str(payload["symbol"]), size)
+        else:
+            payload["size"] = size
+
+    async def _handle(self, command: TradingCommand, payload: Dict[str, Any]) -> Any:
+        if command.kind in _PLACING_KINDS:
+            await self._size(command, payload)
+        tif = payload.pop("time_in_force_ms", None)
+        if command.time_in_force_ms is not None:
+            tif = command.time_in_force_ms
+        raw_policy = command.policy if command.policy is not None else command.payload.get("policy")
+        policy = maker_first_policy(raw_policy)
+        if policy is None and command.kind == MAKER_FIRST and isinstance(raw_policy, dict):
+            policy = MakerFirstPolicy.from_mapping(raw_policy)
+        maker_first = command.kind == MAKER_FIRST or (policy is not None and command.kind in _ORDER_KINDS)
+        if tif and (maker_first or command.kind != "submit_limit"):
+            raise ValueError(f"time_in_force_ms applies to plain submit_limit, not {command.kind}")
+        if maker_first:
+            payload = {k: v for k, v in payload.items() if k not in _PRICE_KEYS and k != "policy"}
+            payload.setdefault("client_order_key", command.command_id)
+            return await self._router.maker_first(**payload, policy=policy)
+        fn = self._dispatch.get(command.kind)
+        if fn is None:
+            raise ValueError(f"unsupported command kind: {command.kind}")
+        if command.kind in _ORDER_KINDS and payload.get("client_order_index") is None:
+            payload["client_order_index"] = client_order_index_for(command.command_id)
+        if command.kind == "tracking_limit":
+            payload.setdefault("client_order_key", command.command_id)
+        result = await fn(**payload)
+        if tif:
+            self._router.orders.expire_after(result, int(tif), command_id=command.command_id)
+        return result
+
+
+__all__ = ["RouterCommandHandler", "maker_first_policy"]
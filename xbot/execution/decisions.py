@@ -0,0 +1,52 @@
+from __future__ import annotations
+
+import time
+from collections import deque
+from typing import Any, Deque, Dict, List, Mapping, Optional
+
+from ..storage.base import STRATEGY_DECISIONS, StorageWriter
+
+LIVE = "live"
+
+# Order arguments worth comparing between variants; prices and sizes stay in venue integers
+_FIELDS = ("symbol", "client_order_index", "is_ask", "size_i", "size", "base_amount_i", "price_i", "reduce_only", "post_only", "trace_id")
+
+
+class DecisionLog:
+    """Every order a strategy asks its router for, tagged with the variant that asked.
+
+    A live strategy and a shadow candidate trading the same symbol write
+    to the one `strategy_decisions` table, `variant` telling them apart,
+    so the two streams can be lined up by time before a candidate is
+    promoted. A row is stamped with the time the decision was made and
+    written once the call returns, whether or not the risk checks or the
+    venue accepted it; `result` is `sent` or the error. The most recent
+    rows are also kept in memory.
+    """
+
+    def __init__(self, *, writer: Optional[StorageWriter] = None, history: int = 500) -> None:
+        self._writer = writer
+        self._recent: Deque[Dict[str, Any]] = deque(maxlen=history)
+
+    def record(
+        self, variant: str, action: str, args: Mapping[str, Any], result: str, *, ts: Optional[float] = None
+    ) -> Dict[str, Any]:
+        ts = time.time() if ts is None else ts
+        row: Dict[str, Any] = {"ts": ts, "variant": variant, "action": action, "result": result}
+        for key in _FIELDS:
+            value = args.get(key)
+            if value is not None:
+                row[key] = str(value) if key == "size" else value
+        trace_id = str(args.get("trace_id") or "")
+        row["strategy"] = trace_id.split(":", 1)[0] if ":" in trace_id else ""
+        self._recent.append(row)
+        if self._writer is not None:
+            self._writer.enqueue(STRATEGY_DECISIONS, row)
+        return row
+
+    def recent(self, variant: Optional[str] = None, limit: int = 100) -> List[Dict[str, Any]]:
+        rows = [row for row in self._recent if variant is None or row["variant"] == variant]
+        return rows[-limit:]
+
+
+__all__ = ["DecisionLog", "LIVE"]
@@ -0,0 +1,160 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+from dataclasses import dataclass, field
+from decimal import ROUND_DOWN, Decimal
+from typing import Any, Dict, List, Mapping, Optional
+
+from xbot.connector.interface import IConnector
+
+from .risk_service import RiskMode
+from .router import ExecutionRouter
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class DustConfig:
+    quote: str = "USDC"
+    max_notional: Decimal = Decimal("5")  # balances worth less than this (in quote) count as dust
+    interval_secs: float = 3600.0
+    dry_run: bool = True  # report candidates only
+    exclude: List[str] = field(default_factory=list)
+
+
+@dataclass(slots=True)
+class DustCandidate:
+    asset: str
+    quantity: Decimal
+    market: Optional[str] = None
+    notional: Optional[Decimal] = None
+    action: str = "skipped"  # converted | dry_run | skipped | failed
+    reason: str = ""
+    order_id: Optional[str] = None
+    client_order_index: Optional[int] = None
+
+
+def available_balances(margin: Mapping[str, Any]) -> Dict[str, Decimal]:
+    """Asset -> available quantity from a connector `get_margin()` snapshot."""
+    raw = margin.get("balances")
+    if not isinstance(raw, Mapping):
+        return {}
+    balances: Dict[str, Decimal] = {}
+    for asset, entry in raw.items():
+        value = entry.get("available") if isinstance(entry, Mapping) else entry
+        try:
+            balances[str(asset).upper()] = Decimal(str(value))
+        except Exception:
+            continue
+    return balances
+
+
+class DustSweeper:
+    """Periodically sells small non-quote balances into the quote currency.
+
+    Conversion needs a spot market per asset, resolved through the connector's
+    `spot_market(asset, quote)`. Connectors without it, and assets without a
+    market, are reported but left untouched. Sales go through `router` as
+    reduce-only market orders traced `dust:<asset>`, so they are risk-checked,
+    audited and recorded like any other order. Sweeps pause unless risk is
+    NORMAL.
+    """
+
+    def __init__(self, *, connector: IConnector, router: ExecutionRouter, config: DustConfig) -> None:
+        self._connector = connector
+        self._router = router
+        self._config = config
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    async def sweep(self) -> List[DustCandidate]:
+        risk = self._router.risk
+        if risk.mode != RiskMode.NORMAL:
+            self._logger.info("dust_sweep_paused", extra={"venue": self._connector.venue, "mode": risk.mode.value})
+            return []
+        quote = self._config.quote.upper()
+        excluded = {asset.upper() for asset in self._config.exclude}
+        balances = available_balances(await self._connector.get_margin())
+        candidates: List[DustCandidate] = []
+        for asset, quantity in sorted(balances.items()):
+            if asset == quote or asset in excluded or quantity <= 0:
+                continue
+            candidate = await self._evaluate(asset, quantity, quote)
+            if candidate is not None:
+                candidates.append(candidate)
+                self._logger.info(
+                    "dust_candidate",
+                    extra={
+                        "venue": self._connector.venue,
+                        "asset": candidate.asset,
+                        "quantity": str(candidate.quantity),
+                        "market": candidate.market,
+                        "notional": None if candidate.notional is None else str(candidate.notional),
+                        "action": candidate.action,
+                        "reason": candidate.reason,
+                    },
+                )
+        return candidates
+
+    async def _evaluate(self, asset: str, quantity: Decimal, quote: str) -> Optional[DustCandidate]:
+        resolver = getattr(self._connector, "spot_market", None)
+        market = resolver(asset, quote) if resolver is not None else None
+        if market is None:
+            return DustCandidate(asset=asset, quantity=quantity, reason="no_spot_market")
+        bid, _ask, scale = await self._connector.get_top_of_book(market)
+        if not bid:
+            return DustCandidate(asset=asset, quantity=quantity, market=market, reason="no_bid")
+        notional = quantity * Decimal(bid) / Decimal(scale)
+        if notional >= self._config.max_notional:
+            return None
+        candidate = DustCandidate(asset=asset, quantity=quantity, market=market, notional=notional)
+        _, size_decimals = await self._connector.get_price_size_decimals(market)
+        size_i = int((quantity * (Decimal(10) ** size_decimals)).to_integral_value(rounding=ROUND_DOWN))
+        if size_i <= 0 or size_i < await self._connector.get_min_size_i(market):
+            candidate.reason = "below_min_size"
+            return candidate
+        if self._config.dry_run:
+            candidate.action = "dry_run"
+            return candidate
+        try:
+            order = await self._router.submit_market(
+                symbol=self._canonical(market), is_ask=True, size_i=size_i, reduce_only=1, trace_id=f"dust:{asset}"
+            )
+            candidate.order_id = order.exchange_order_id
+            candidate.client_order_index = order.client_order_index
+            candidate.action = "converted"
+        except Exception as exc:
+            candidate.action = "failed"
+            candidate.reason = str(exc)
+        return candidate
+
+    def _canonical(self, market: str) -> str:
+        market_data = self._router.market_data
+        canonical = market_data.canonical_for(market)
+        if canonical is None:
+            market_data.register_symbol(market, market)
+            canonical = market.upper()
+        return canonical
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="dust-sweeper")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.sweep()
+            except Exception as exc:
+                self._logger.info("dust_sweep_error", extra={"venue": self._connector.venue, "error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = ["DustConfig", "DustCandidate", "DustSweeper", "available_balances"]
@@ -0,0 +1,205 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Any, Dict, List, Optional, Protocol, Sequence
+
+from xbot.connector.history import CurrentFunding, FundingRate
+
+from .market_data_service import MarketDataService
+from ..core.backoff import AdaptiveInterval, BackoffConfig
+from ..storage.base import FUNDING_RATES, StorageWriter
+from ..utils.logging import get_logger
+
+
+class FundingRateSource(Protocol):
+    venue: str
+
+    async def get_funding_rates(self, symbol: str, limit: int = 100) -> List[FundingRate]:  # pragma: no cover
+        ...
+
+
+class FundingPredictor(Protocol):
+    """Forecasts the next interval's funding rate from past rates (oldest first)."""
+
+    def predict(self, history: Sequence[FundingRate]) -> Optional[Decimal]:  # pragma: no cover - protocol
+        ...
+
+
+class EwmaFundingPredictor:
+    """Exponentially weighted mean of past rates; `alpha` is the weight of the newest one."""
+
+    def __init__(self, alpha: Decimal | float = Decimal("0.3")) -> None:
+        self._alpha = Decimal(str(alpha))
+        if not (0 < self._alpha <= 1):
+            raise ValueError("alpha must be in (0, 1]")
+
+    def predict(self, history: Sequence[FundingRate]) -> Optional[Decimal]:
+        estimate: Optional[Decimal] = None
+        for entry in history:
+            estimate = entry.rate if estimate is None else self._alpha * entry.rate + (1 - self._alpha) * estimate
+        return estimate
+
+
+@dataclass(slots=True)
+class FundingForecast:
+    symbol: str  # canonical
+    current: Optional[Decimal]  # latest settled rate
+    predicted: Optional[Decimal]  # next interval
+    samples: int
+    ts: float
+
+
+def pays_funding(rate: Decimal, is_long: bool) -> bool:
+    """Longs pay positive funding, shorts pay negative funding."""
+    return rate > 0 if is_long else rate < 0
+
+
+def funding_entry_delay(
+    funding: Optional[CurrentFunding],
+    *,
+    is_long: bool,
+    window_secs: float,
+    settle_buffer_secs: float = 5.0,
+    now: Optional[float] = None,
+) -> float:
+    """Seconds to hold off a new position so it does not pay a whole interval for minutes of exposure.
+
+    Returns 0 unless the position would pay at the next accrual and that
+    accrual is at most `window_secs` away. In that case it returns the time
+    until just after the accrual, plus `settle_buffer_secs` to absorb clock
+    skew. A position that would receive funding is never delayed; opening it
+    just before the accrual is the cheap side of the same timing.
+    """
+    if funding is None or not pays_funding(funding.rate, is_long):
+        return 0.0
+    remaining = funding.secs_to_next(now)
+    if remaining > window_secs:
+        return 0.0
+    return remaining + settle_buffer_secs
+
+
+class FundingService:
+    """Collects funding-rate history per symbol and serves next-interval forecasts.
+
+    `refresh` pulls recent rates from the connector, merges them into the
+    in-memory history (deduplicated by interval) and, when a writer is given,
+    persists new intervals to `funding_rates`. Forecasts come from a pluggable
+    `FundingPredictor`; EWMA is the default.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: FundingRateSource,
+        market_data: MarketDataService,
+        symbols: Sequence[str],
+        predictor: Optional[FundingPredictor] = None,
+        writer: Optional[StorageWriter] = None,
+        history_limit: int = 200,
+        interval_secs: float = 900.0,
+        backoff: Optional[BackoffConfig] = None,
+    ) -> None:
+        self._connector = connector
+        self._market_data = market_data
+        self._symbols = [s.upper() for s in symbols]
+        self._predictor: FundingPredictor = predictor or EwmaFundingPredictor()
+        self._writer = writer
+        self._history_limit = history_limit
+        self._interval = AdaptiveInterval(connector.venue, "funding", interval_secs, backoff)
+        self._history: Dict[str, List[FundingRate]] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def set_predictor(self, predictor: FundingPredictor) -> None:
+        self._predictor = predictor
+
+    def history(self, symbol: str) -> List[FundingRate]:
+        return list(self._history.get(symbol.upper(), []))
+
+    async def refresh(self, symbol: str) -> int:
+        """Fetch recent rates for a canonical symbol; returns how many intervals were new."""
+        key = symbol.upper()
+        venue_symbol = self._market_data.resolve_symbol(key)
+        fetched = await self._connector.get_funding_rates(venue_symbol, limit=self._history_limit)
+        known = {entry.ts_ms for entry in self._history.get(key, [])}
+        new = [entry for entry in fetched if entry.ts_ms not in known]
+        if not new:
+            return 0
+        merged = sorted(self._history.get(key, []) + new, key=lambda r: r.ts_ms)
+        self._history[key] = merged[-self._history_limit:]
+        if self._writer is not None:
+            for entry in new:
+                row: Dict[str, Any] = {
+                    "ts": entry.ts_ms / 1000,
+                    "venue": self._connector.venue,
+                    "symbol": key,
+                    "rate": str(entry.rate),
+                }
+                self._writer.enqueue(FUNDING_RATES, row)
+        return len(new)
+
+    def forecast(self, symbol: str) -> FundingForecast:
+        key = symbol.upper()
+        history = self._history.get(key, [])
+        return FundingForecast(
+            symbol=key,
+            current=history[-1].rate if history else None,
+            predicted=self._predictor.predict(history) if history else None,
+            samples=len(history),
+            ts=time.time(),
+        )
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="funding-service")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            error: Optional[Exception] = None
+            for symbol in self._symbols:
+                try:
+                    added = await self.refresh(symbol)
+                except Exception as exc:
+                    self._logger.info("funding_refresh_error", extra={"symbol": symbol, "error": str(exc)})
+                    error = exc
+                    continue
+                if added:
+                    forecast = self.forecast(symbol)
+                    self._logger.info(
+                        "funding_forecast",
+                        extra={
+                            "venue": self._connector.venue,
+                            "symbol": symbol,
+                            "current": None if forecast.current is None else str(forecast.current),
+                            "predicted": None if forecast.predicted is None else str(forecast.predicted),
+                            "samples": forecast.samples,
+                        },
+                    )
+            if error is None:
+                self._interval.success()
+            else:
+                self._interval.failure(error)
+            await self._interval.sleep()
+
+
+__all__ = [
+    "EwmaFundingPredictor",
+    "FundingForecast",
+    "FundingPredictor",
+    "FundingRateSource",
+    "FundingService",
+    "funding_entry_delay",
+    "pays_funding",
+]
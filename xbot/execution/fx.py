@@ -0,0 +1,185 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from collections import deque
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Callable, Dict, Iterable, List, Optional, Sequence, Tuple
+
+from .market_data_service import MarketDataService, UnknownSymbolError
+from .position_service import PositionSnapshot
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class FxConfig:
+    reporting_currency: str = "USD"
+    # 1:1 anchors assumed rather than observed; a market quote between two assets takes precedence
+    pegs: Dict[str, str] = field(default_factory=lambda: {"USDC": "USD", "USDT": "USD"})
+    symbols: List[str] = field(default_factory=list)  # canonical markets priced from mids, e.g. BTC_USDC
+    venue_currencies: Dict[str, str] = field(default_factory=dict)  # venue -> currency of its margin equity
+    interval_secs: float = 30.0
+    max_age_secs: float = 120.0  # market rates older than this are not used
+
+
+@dataclass(slots=True)
+class _Edge:
+    rate: Decimal
+    ts: Optional[float]  # None for pegs, which never go stale
+
+
+class CurrencyConverter:
+    """Converts amounts between assets over pegs and observed market rates.
+
+    Rates form a graph; a conversion takes the path with the fewest hops,
+    so `BTC -> USD` goes through a `BTC/USDC` mid and the `USDC = USD` peg,
+    while a direct `USDT/USDC` quote beats routing both legs through USD.
+    A conversion with no fresh path returns None instead of guessing.
+    """
+
+    def __init__(
+        self,
+        reporting_currency: str = "USD",
+        *,
+        pegs: Optional[Dict[str, str]] = None,
+        max_age_secs: Optional[float] = 120.0,
+        clock: Callable[[], float] = time.time,
+    ) -> None:
+        self.reporting_currency = reporting_currency.upper()
+        self._max_age = max_age_secs
+        self._clock = clock
+        self._edges: Dict[str, Dict[str, _Edge]] = {}
+        for asset, anchor in (pegs or {}).items():
+            self.peg(asset, anchor)
+
+    def _link(self, base: str, quote: str, rate: Decimal, ts: Optional[float]) -> None:
+        self._edges.setdefault(base, {})[quote] = _Edge(rate, ts)
+        self._edges.setdefault(quote, {})[base] = _Edge(1 / rate, ts)
+
+    def peg(self, asset: str, anchor: str, rate: Decimal = Decimal(1)) -> None:
+        asset, anchor = asset.upper(), anchor.upper()
+        if asset != anchor:
+            self._link(asset, anchor, rate, None)
+
+    def set_rate(self, base: str, quote: str, price: Decimal, ts: Optional[float] = None) -> None:
+        """Record that one `base` is worth `price` of `quote`."""
+        base, quote = base.upper(), quote.upper()
+        if price <= 0 or base == quote:
+            return
+        self._link(base, quote, price, self._clock() if ts is None else ts)
+
+    def _fresh(self, edge: _Edge, now: float) -> bool:
+        return edge.ts is None or self._max_age is None or now - edge.ts <= self._max_age
+
+    def rate(self, source: str, target: Optional[str] = None, *, now: Optional[float] = None) -> Optional[Decimal]:
+        """Units of `target` (default: the reporting currency) per unit of `source`."""
+        source, target = source.upper(), (target or self.reporting_currency).upper()
+        if source == target:
+            return Decimal(1)
+        now = self._clock() if now is None else now
+        seen = {source}
+        queue: deque[Tuple[str, Decimal]] = deque([(source, Decimal(1))])
+        while queue:
+            asset, acc = queue.popleft()
+            for other, edge in self._edges.get(asset, {}).items():
+                if other in seen or not self._fresh(edge, now):
+                    continue
+                if other == target:
+                    return acc * edge.rate
+                seen.add(other)
+                queue.append((other, acc * edge.rate))
+        return None
+
+    def convert(self, amount: Decimal, source: str, target: Optional[str] = None) -> Optional[Decimal]:
+        rate = self.rate(source, target)
+        return None if rate is None else amount * rate
+
+    def rates(self) -> Dict[str, Optional[Decimal]]:
+        """Every known asset valued in the reporting currency; None where no fresh path exists."""
+        return {asset: self.rate(asset) for asset in sorted(self._edges)}
+
+
+async def exposure(
+    positions: Iterable[PositionSnapshot], market_data: MarketDataService, converter: CurrencyConverter
+) -> Optional[Decimal]:
+    """Gross position notional in the reporting currency; None if any position cannot be converted."""
+    total = Decimal(0)
+    for position in positions:
+        if not position.notional:
+            continue
+        try:
+            quote = market_data.instrument(position.symbol).quote
+        except UnknownSymbolError:
+            return None
+        converted = converter.convert(abs(position.notional), quote) if quote else None
+        if converted is None:
+            return None
+        total += converted
+    return total
+
+
+class FxService:
+    """Keeps a converter's market rates current from the top-of-book mids of configured markets."""
+
+    def __init__(
+        self,
+        *,
+        converter: CurrencyConverter,
+        market_data: Sequence[MarketDataService],
+        symbols: Sequence[str],
+        interval_secs: float = 30.0,
+    ) -> None:
+        self._converter = converter
+        self._market_data = list(market_data)
+        self._symbols = [symbol.upper() for symbol in symbols]
+        self._interval = interval_secs
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def converter(self) -> CurrencyConverter:
+        return self._converter
+
+    async def refresh(self) -> int:
+        """Poll every configured market once; returns how many rates were updated."""
+        updated = 0
+        for market_data in self._market_data:
+            listed = market_data.symbols()
+            for symbol in self._symbols:
+                if symbol not in listed:
+                    continue
+                try:
+                    bid_i, ask_i, scale = await market_data.get_top_of_book(symbol)
+                    instrument = market_data.instrument(symbol)
+                except Exception as exc:
+                    self._logger.info("fx_rate_error", extra={"symbol": symbol, "error": str(exc)})
+                    continue
+                if not bid_i or not ask_i or not instrument.quote:
+                    continue
+                mid = (Decimal(bid_i) + Decimal(ask_i)) / 2 / Decimal(scale)
+                self._converter.set_rate(instrument.base, instrument.quote, mid)
+                updated += 1
+        return updated
+
+    async def start(self) -> None:
+        if self._task is None:
+            await self.refresh()
+            self._task = asyncio.create_task(self._run(), name="fx-service")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            await asyncio.sleep(self._interval)
+            await self.refresh()
+
+
+__all__ = ["CurrencyConverter", "FxConfig", "FxService", "exposure"]
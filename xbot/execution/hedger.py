@@ -0,0 +1,246 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Awaitable, Callable, Dict, List, Mapping, Optional
+
+from .portfolio_executor import TargetPositionExecutor
+from .position_service import PositionService
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.metrics import METRICS
+from ..core.symbology import base_asset
+from ..utils.logging import get_logger
+
+
+def default_underlying(symbol: str) -> str:
+    """`SOL`, `SOL_USDC_PERP` and `SOL-PERP` all hedge as `SOL`."""
+    return base_asset(symbol)
+
+
+@dataclass(slots=True)
+class HedgeTarget:
+    underlying: str
+    hedge_symbol: str  # canonical symbol on the hedge venue
+    band: Decimal  # tolerated |residual delta| in base units before rebalancing
+    ratio: Decimal = Decimal(1)  # hedge size per unit of net delta
+    fallback_symbol: Optional[str] = None  # canonical symbol on the fallback venue; None: no failover
+
+
+@dataclass(slots=True)
+class HedgeConfig:
+    targets: Dict[str, HedgeTarget] = field(default_factory=dict)  # keyed by underlying
+    underlyings: Dict[str, str] = field(default_factory=dict)  # symbol -> underlying overrides
+    interval_secs: float = 10.0
+    failover_after: int = 3  # consecutive failed probes of the hedge venue before hedging on the fallback
+
+
+@dataclass(slots=True)
+class HedgeLeg:
+    """A fallback hedge venue: its positions and the executor that trades there."""
+
+    name: str
+    positions: PositionService
+    executor: TargetPositionExecutor
+
+
+@dataclass(slots=True)
+class HedgeDecision:
+    underlying: str
+    net_delta: Decimal  # across the exposure sources, hedge excluded
+    hedge_position: Decimal
+    desired_hedge: Decimal
+    residual: Decimal  # net delta left after the current hedge
+    rebalanced: bool
+    fallback_position: Decimal = Decimal(0)
+    failed_over: bool = False  # this pass re-targeted the fallback venue
+
+
+class PortfolioHedger:
+    """Keeps an offsetting position on one hedge venue for the summed delta of all others.
+
+    Net delta per underlying is the sum of base quantities across every exposure
+    `PositionService` (one per venue/account). The hedge target is
+    ``-net_delta * ratio``; while the residual stays inside the band nothing
+    happens, and once it breaches the band the hedge venue's
+    `TargetPositionExecutor` is re-targeted to the fully hedged size.
+
+    With a `fallback` leg and a `probe` of the hedge venue (any cheap
+    authenticated call), `failover_after` failed probes in a row mark the
+    hedge venue down. The primary target is then frozen at its last known
+    position and the residual is hedged on the fallback venue under each
+    target's `fallback_symbol`, so the book does not sit naked until the
+    venue recovers. Every underlying hedged there is flagged for manual
+    review (`review`, an alert, the `hedge_failover` log). The fallback hedge
+    keeps counting towards the residual: once the hedge venue is back, it
+    hedges only what the fallback does not cover. Unwinding the fallback
+    position is left to the operator, who clears the flag with
+    `resolve_review`.
+    """
+
+    def __init__(
+        self,
+        *,
+        sources: Mapping[str, PositionService],
+        hedge_positions: PositionService,
+        executor: TargetPositionExecutor,
+        config: HedgeConfig,
+        fallback: Optional[HedgeLeg] = None,
+        probe: Optional[Callable[[], Awaitable[Any]]] = None,
+        alerts: Optional[AlertManager] = None,
+    ) -> None:
+        self._sources = dict(sources)
+        self._hedge_positions = hedge_positions
+        self._executor = executor
+        self._config = config
+        self._fallback = fallback
+        self._probe = probe
+        self._alerts = alerts
+        self._probe_failures = 0
+        self._review: Dict[str, Dict[str, Any]] = {}  # underlying -> why it is hedged on the fallback
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def underlying_for(self, symbol: str) -> str:
+        return self._config.underlyings.get(symbol.upper()) or default_underlying(symbol)
+
+    async def net_deltas(self) -> Dict[str, Decimal]:
+        totals: Dict[str, Decimal] = {}
+        for positions in self._sources.values():
+            for snapshot in await positions.all_positions():
+                underlying = self.underlying_for(snapshot.symbol)
+                totals[underlying] = totals.get(underlying, Decimal(0)) + snapshot.base_qty
+        return totals
+
+    @property
+    def primary_down(self) -> bool:
+        return self._fallback is not None and self._probe_failures >= self._config.failover_after
+
+    @property
+    def review(self) -> Dict[str, Dict[str, Any]]:
+        """Underlyings hedged on the fallback venue, awaiting an operator."""
+        return dict(self._review)
+
+    def resolve_review(self, underlying: str) -> bool:
+        return self._review.pop(underlying.upper(), None) is not None
+
+    async def check_primary(self) -> bool:
+        """Probe the hedge venue; returns True while it answers (or nothing probes it)."""
+        if self._probe is None:
+            return True
+        try:
+            await self._probe()
+        except Exception as exc:
+            self._probe_failures += 1
+            self._logger.info("hedge_venue_probe_failed", extra={"failures": self._probe_failures, "error": str(exc)})
+            return False
+        if self._probe_failures >= self._config.failover_after:
+            self._logger.info("hedge_venue_recovered", extra={"failures": self._probe_failures})
+        self._probe_failures = 0
+        return True
+
+    async def _fallback_position(self, target: HedgeTarget) -> Decimal:
+        if self._fallback is None or not target.fallback_symbol:
+            return Decimal(0)
+        snapshot = await self._fallback.positions.get_position(target.fallback_symbol)
+        return snapshot.base_qty if snapshot else Decimal(0)
+
+    async def _fail_over(self, underlying: str, target: HedgeTarget, hedge: Decimal, desired: Decimal) -> bool:
+        """Freeze the primary hedge and put the rest of `desired` on the fallback venue."""
+        if self._fallback is None or not target.fallback_symbol:
+            return False
+        await self._executor.set_target(target.hedge_symbol, hedge)
+        fallback_target = desired - hedge
+        await self._fallback.executor.set_target(target.fallback_symbol, fallback_target)
+        flagged = underlying not in self._review
+        self._review[underlying] = {
+            "underlying": underlying,
+            "hedge_symbol": target.hedge_symbol,
+            "frozen_hedge": str(hedge),
+            "fallback": self._fallback.name,
+            "fallback_symbol": target.fallback_symbol,
+            "fallback_target": str(fallback_target),
+        }
+        METRICS.inc("hedge_failovers", underlying=underlying, fallback=self._fallback.name)
+        self._logger.info("hedge_failover", extra=self._review[underlying])
+        if flagged and self._alerts is not None:
+            await self._alerts.notify(
+                AlertLevel.CRITICAL,
+                "Hedge failover",
+                f"{underlying} hedged on {self._fallback.name} ({target.fallback_symbol} {fallback_target});"
+                " review and unwind the pair manually",
+                **self._review[underlying],
+            )
+        return True
+
+    async def step(self) -> List[HedgeDecision]:
+        deltas = await self.net_deltas()
+        down = self._fallback is not None and not await self.check_primary() and self.primary_down
+        decisions: List[HedgeDecision] = []
+        for underlying, target in self._config.targets.items():
+            net = deltas.get(underlying, Decimal(0))
+            snapshot = await self._hedge_positions.get_position(target.hedge_symbol)
+            hedge = snapshot.base_qty if snapshot else Decimal(0)
+            fallback = await self._fallback_position(target)
+            desired = -net * target.ratio
+            residual = net + ((hedge + fallback) / target.ratio if target.ratio else Decimal(0))
+            rebalance = abs(residual) > target.band
+            failed_over = False
+            if rebalance and down:
+                failed_over = await self._fail_over(underlying, target, hedge, desired)
+            if rebalance and not failed_over:
+                # Whatever the fallback venue holds stays hedged there until the operator unwinds it
+                desired -= fallback
+                await self._executor.set_target(target.hedge_symbol, desired)
+                self._logger.info(
+                    "hedge_rebalance",
+                    extra={
+                        "underlying": underlying,
+                        "hedge_symbol": target.hedge_symbol,
+                        "net_delta": str(net),
+                        "hedge_position": str(hedge),
+                        "fallback_position": str(fallback),
+                        "desired_hedge": str(desired),
+                        "residual": str(residual),
+                        "band": str(target.band),
+                    },
+                )
+            METRICS.set("hedge_net_delta", float(net), underlying=underlying)
+            METRICS.set("hedge_residual_delta", float(residual), underlying=underlying)
+            decisions.append(
+                HedgeDecision(
+                    underlying=underlying,
+                    net_delta=net,
+                    hedge_position=hedge,
+                    desired_hedge=desired,
+                    residual=residual,
+                    rebalanced=rebalance,
+                    fallback_position=fallback,
+                    failed_over=failed_over,
+                )
+            )
+        return decisions
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="portfolio-hedger")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.step()
+            except Exception:
+                self._logger.exception("hedge_step_failed")
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = ["PortfolioHedger", "HedgeConfig", "HedgeLeg", "HedgeTarget", "HedgeDecision", "default_underlying"]
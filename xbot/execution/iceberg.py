@@ -0,0 +1,198 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import secrets
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Tuple, TYPE_CHECKING
+
+from .models import FINAL_STATES, Order, OrderState
+from .order_expiry import filled_size_i
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .router import ExecutionRouter
+
+ICEBERG = "iceberg"
+
+
+def iceberg_trace(iceberg_id: str, total_i: int, clip_i: int, done_i: int, reduce_only: int) -> str:
+    """Trace of one clip: the iceberg, its sizes, what earlier clips filled and reduce-only, enough to resume it."""
+    return f"{ICEBERG}:{iceberg_id}:{total_i}:{clip_i}:{done_i}:{reduce_only}"
+
+
+def parse_iceberg_trace(trace_id: Optional[str]) -> Optional[Tuple[str, int, int, int, int]]:
+    parts = (trace_id or "").split(":")
+    if len(parts) != 6 or parts[0] != ICEBERG:
+        return None
+    try:
+        return parts[1], int(parts[2]), int(parts[3]), int(parts[4]), int(parts[5])
+    except ValueError:
+        return None
+
+
+def _price_i(order: Order) -> Optional[int]:
+    return next((int(e.info["price_i"]) for e in order.history if e.info.get("price_i") is not None), None)
+
+
+@dataclass(slots=True)
+class Iceberg:
+    iceberg_id: str
+    symbol: str
+    is_ask: bool
+    price_i: int
+    total_i: int
+    clip_i: int
+    reduce_only: int = 0
+    done_i: int = 0  # filled by clips that have finished
+    clip: Optional[Order] = None
+    finished: bool = False
+
+    @property
+    def filled_i(self) -> int:
+        return self.done_i + (filled_size_i(self.clip) if self.clip is not None else 0)
+
+    @property
+    def remaining_i(self) -> int:
+        return max(self.total_i - self.filled_i, 0)
+
+
+class IcebergManager:
+    """Works a large limit order as a series of clips, at most `clip_i` of it resting at a time.
+
+    `place` rests the first clip at `price_i`; each sweep replaces a clip
+    that filled with the next one until `total_i` is done. A clip
+    cancelled or refused before it filled ends the iceberg, as does
+    `cancel`. Each clip's trace_id carries the iceberg's sizes and the
+    fills of earlier clips (`iceberg_trace`), so after a restart `adopt`
+    resumes it from the surviving clip, or from one that filled while the
+    bot was down.
+    """
+
+    def __init__(self, *, router: "ExecutionRouter", sweep_secs: float = 0.5) -> None:
+        self._router = router
+        self._sweep_secs = sweep_secs
+        self._icebergs: Dict[str, Iceberg] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def icebergs(self) -> List[Iceberg]:
+        return list(self._icebergs.values())
+
+    async def place(
+        self, *, symbol: str, is_ask: bool, size_i: int, price_i: int, clip_i: int, reduce_only: int = 0
+    ) -> Iceberg:
+        if clip_i <= 0 or size_i <= 0:
+            raise ValueError("iceberg size and clip size must be positive")
+        iceberg = Iceberg(
+            iceberg_id=secrets.token_hex(4),
+            symbol=symbol,
+            is_ask=is_ask,
+            price_i=price_i,
+            total_i=size_i,
+            clip_i=min(clip_i, size_i),
+            reduce_only=reduce_only,
+        )
+        await self._next_clip(iceberg)
+        self._icebergs[iceberg.iceberg_id] = iceberg
+        return iceberg
+
+    async def adopt(self, order: Order) -> None:
+        """Resume the iceberg a clip restored after a restart belongs to."""
+        parsed = parse_iceberg_trace(order.trace_id)
+        price_i = _price_i(order)
+        if parsed is None or price_i is None:
+            return
+        iceberg_id, total_i, clip_i, done_i, reduce_only = parsed
+        iceberg = Iceberg(
+            iceberg_id, order.symbol, order.is_ask, price_i, total_i, clip_i, reduce_only, done_i=done_i, clip=order
+        )
+        self._icebergs[iceberg_id] = iceberg
+        self._logger.info("iceberg_adopted", extra=self._extra(iceberg))
+
+    async def cancel(self, iceberg_id: str) -> None:
+        iceberg = self._icebergs.pop(iceberg_id)
+        iceberg.finished = True
+        clip = iceberg.clip
+        if clip is not None and clip.state not in FINAL_STATES:
+            await self._router.cancel(iceberg.symbol, clip.client_order_index)
+
+    async def sweep(self) -> int:
+        """Send the next clip of every iceberg whose clip filled; returns how many were sent."""
+        sent = 0
+        for iceberg in list(self._icebergs.values()):
+            clip = iceberg.clip
+            if clip is None or clip.state not in FINAL_STATES:
+                continue
+            iceberg.done_i += filled_size_i(clip)
+            iceberg.clip = None
+            if clip.state != OrderState.FILLED:
+                self._finish(iceberg, f"clip {clip.state.value}")
+                continue
+            if iceberg.remaining_i <= 0:
+                self._finish(iceberg, "filled")
+                continue
+            if await self._next_clip(iceberg):
+                sent += 1
+        return sent
+
+    async def _next_clip(self, iceberg: Iceberg) -> bool:
+        size_i = min(iceberg.clip_i, iceberg.remaining_i)
+        try:
+            iceberg.clip = await self._router.submit_limit(
+                symbol=iceberg.symbol,
+                is_ask=iceberg.is_ask,
+                size_i=size_i,
+                price_i=iceberg.price_i,
+                reduce_only=iceberg.reduce_only,
+                trace_id=iceberg_trace(
+                    iceberg.iceberg_id, iceberg.total_i, iceberg.clip_i, iceberg.done_i, iceberg.reduce_only
+                ),
+            )
+        except Exception as exc:
+            if iceberg.iceberg_id not in self._icebergs:
+                raise  # the first clip, from `place`: the caller learns the iceberg was refused
+            self._finish(iceberg, f"clip refused: {exc}")
+            return False
+        METRICS.inc("iceberg_clips", venue=self._router.orders.venue, symbol=iceberg.symbol)
+        return True
+
+    def _finish(self, iceberg: Iceberg, reason: str) -> None:
+        iceberg.finished = True
+        self._icebergs.pop(iceberg.iceberg_id, None)
+        self._logger.info("iceberg_finished", extra={**self._extra(iceberg), "reason": reason})
+
+    def _extra(self, iceberg: Iceberg) -> Dict[str, object]:
+        return {
+            "iceberg_id": iceberg.iceberg_id,
+            "symbol": iceberg.symbol,
+            "total_i": iceberg.total_i,
+            "filled_i": iceberg.filled_i,
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="iceberg")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            await asyncio.sleep(self._sweep_secs)
+            if not self._icebergs:
+                continue
+            try:
+                await self.sweep()
+            except Exception as exc:
+                self._logger.info("iceberg_sweep_error", extra={"error": str(exc)})
+
+
+__all__ = ["ICEBERG", "Iceberg", "IcebergManager", "iceberg_trace", "parse_iceberg_trace"]
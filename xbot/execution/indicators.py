@@ -0,0 +1,335 @@
+from __future__ import annotations
+
+from collections import deque
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Any, Callable, Deque, Dict, Hashable, List, Optional, Protocol, Tuple, TYPE_CHECKING
+
+from .candles import Candle
+
+if TYPE_CHECKING:
+    from .candles import CandleService
+
+
+class Indicator(Protocol):
+    @property
+    def value(self) -> Any:  # pragma: no cover - protocol
+        ...
+
+    def update(self, candle: Candle) -> None:  # pragma: no cover - protocol
+        ...
+
+
+class Ema:
+    """Exponential moving average of closes, seeded with the simple mean of the first `period`."""
+
+    def __init__(self, period: int) -> None:
+        if period <= 0:
+            raise ValueError("period must be positive")
+        self._period = period
+        self._alpha = Decimal(2) / (period + 1)
+        self._seed: List[Decimal] = []
+        self.value: Optional[Decimal] = None
+
+    def update(self, candle: Candle) -> None:
+        if self.value is None:
+            self._seed.append(candle.close)
+            if len(self._seed) == self._period:
+                self.value = sum(self._seed, Decimal(0)) / self._period
+                self._seed = []
+            return
+        self.value += self._alpha * (candle.close - self.value)
+
+
+class Rsi:
+    """Wilder's RSI of closes, 0-100; None until `period` changes have been seen."""
+
+    def __init__(self, period: int = 14) -> None:
+        if period <= 0:
+            raise ValueError("period must be positive")
+        self._period = period
+        self._prev: Optional[Decimal] = None
+        self._gains: List[Decimal] = []
+        self._losses: List[Decimal] = []
+        self._avg_gain: Optional[Decimal] = None
+        self._avg_loss = Decimal(0)
+
+    @property
+    def value(self) -> Optional[Decimal]:
+        if self._avg_gain is None:
+            return None
+        if self._avg_loss == 0:
+            return Decimal(100) if self._avg_gain > 0 else Decimal(50)
+        return 100 - 100 / (1 + self._avg_gain / self._avg_loss)
+
+    def update(self, candle: Candle) -> None:
+        prev, self._prev = self._prev, candle.close
+        if prev is None:
+            return
+        change = candle.close - prev
+        gain, loss = max(change, Decimal(0)), max(-change, Decimal(0))
+        if self._avg_gain is None:
+            self._gains.append(gain)
+            self._losses.append(loss)
+            if len(self._gains) == self._period:
+                self._avg_gain = sum(self._gains, Decimal(0)) / self._period
+                self._avg_loss = sum(self._losses, Decimal(0)) / self._period
+                self._gains, self._losses = [], []
+            return
+        self._avg_gain = (self._avg_gain * (self._period - 1) + gain) / self._period
+        self._avg_loss = (self._avg_loss * (self._period - 1) + loss) / self._period
+
+
+class Atr:
+    """Wilder's ATR, the incremental form of `candles.average_true_range`."""
+
+    def __init__(self, period: int = 14) -> None:
+        if period <= 0:
+            raise ValueError("period must be positive")
+        self._period = period
+        self._prev_close: Optional[Decimal] = None
+        self._seed: List[Decimal] = []
+        self.value: Optional[Decimal] = None
+
+    def update(self, candle: Candle) -> None:
+        prev, self._prev_close = self._prev_close, candle.close
+        if prev is None:
+            return
+        true_range = max(candle.high - candle.low, abs(candle.high - prev), abs(candle.low - prev))
+        if self.value is None:
+            self._seed.append(true_range)
+            if len(self._seed) == self._period:
+                self.value = sum(self._seed, Decimal(0)) / self._period
+                self._seed = []
+            return
+        self.value = (self.value * (self._period - 1) + true_range) / self._period
+
+
+@dataclass(slots=True, frozen=True)
+class Bands:
+    mid: Decimal
+    upper: Decimal
+    lower: Decimal
+
+    @property
+    def width(self) -> Decimal:
+        """Band width as a fraction of the mid."""
+        return (self.upper - self.lower) / self.mid if self.mid else Decimal(0)
+
+
+class Bollinger:
+    """Simple moving average of the last `period` closes, plus and minus `k` population stdevs."""
+
+    def __init__(self, period: int = 20, k: Decimal | float | str = 2) -> None:
+        if period <= 0:
+            raise ValueError("period must be positive")
+        self._period = period
+        self._k = Decimal(str(k))
+        self._window: Deque[Decimal] = deque()
+        self._sum = Decimal(0)
+        self._sum_sq = Decimal(0)
+
+    @property
+    def value(self) -> Optional[Bands]:
+        if len(self._window) < self._period:
+            return None
+        mean = self._sum / self._period
+        variance = max(self._sum_sq / self._period - mean * mean, Decimal(0))
+        spread = self._k * variance.sqrt()
+        return Bands(mid=mean, upper=mean + spread, lower=mean - spread)
+
+    def update(self, candle: Candle) -> None:
+        self._window.append(candle.close)
+        self._sum += candle.close
+        self._sum_sq += candle.close * candle.close
+        if len(self._window) > self._period:
+            old = self._window.popleft()
+            self._sum -= old
+            self._sum_sq -= old * old
+
+
+class Vwap:
+    """Volume-weighted typical price ((high + low + close) / 3) over the last `window` candles, or all of them."""
+
+    def __init__(self, window: Optional[int] = None) -> None:
+        if window is not None and window <= 0:
+            raise ValueError("window must be positive")
+        self._window: Optional[Deque[Tuple[Decimal, Decimal]]] = deque() if window else None
+        self._size = window
+        self._value_sum = Decimal(0)
+        self._volume = Decimal(0)
+
+    @property
+    def value(self) -> Optional[Decimal]:
+        return self._value_sum / self._volume if self._volume > 0 else None
+
+    @property
+    def volume(self) -> Decimal:
+        return self._volume
+
+    def update(self, candle: Candle) -> None:
+        typical = (candle.high + candle.low + candle.close) / 3
+        weighted = typical * candle.volume
+        self._value_sum += weighted
+        self._volume += candle.volume
+        if self._window is not None:
+            self._window.append((weighted, candle.volume))
+            if len(self._window) > (self._size or 0):
+                old_value, old_volume = self._window.popleft()
+                self._value_sum -= old_value
+                self._volume -= old_volume
+
+
+@dataclass(slots=True, frozen=True)
+class Session:
+    """Repeating session boundaries: every `length_secs`, starting `offset_secs` after the epoch (UTC)."""
+
+    length_secs: float
+    offset_secs: float = 0.0
+
+    def start(self, ts: float) -> float:
+        return ts - (ts - self.offset_secs) % self.length_secs
+
+    def end(self, ts: float) -> float:
+        return self.start(ts) + self.length_secs
+
+
+UTC_DAY = Session(86400.0)
+
+
+def funding_session(interval_hours: float = 8.0, offset_hours: float = 0.0) -> Session:
+    """Sessions between funding timestamps; venues fund on UTC interval boundaries unless offset."""
+    if interval_hours <= 0:
+        raise ValueError("interval_hours must be positive")
+    return Session(interval_hours * 3600.0, offset_hours * 3600.0)
+
+
+@dataclass(slots=True, frozen=True)
+class SessionStats:
+    anchor: float  # session start
+    open: Decimal
+    high: Decimal
+    low: Decimal
+    close: Decimal
+    volume: Decimal
+    vwap: Optional[Decimal]  # None while the session has no volume
+
+
+class AnchoredVwap:
+    """VWAP (typical price, like `Vwap`) anchored at the start of the current session, plus the session's OHLCV.
+
+    A candle belongs to the session its start falls in; the first candle
+    of a new session resets everything.
+    """
+
+    def __init__(self, session: Session = UTC_DAY) -> None:
+        self._session = session
+        self._anchor: Optional[float] = None
+        self._vwap = Vwap()
+        self._open = self._high = self._low = self._close = Decimal(0)
+
+    @property
+    def value(self) -> Optional[Decimal]:
+        return self._vwap.value
+
+    @property
+    def stats(self) -> Optional[SessionStats]:
+        if self._anchor is None:
+            return None
+        return SessionStats(
+            anchor=self._anchor,
+            open=self._open,
+            high=self._high,
+            low=self._low,
+            close=self._close,
+            volume=self._vwap.volume,
+            vwap=self._vwap.value,
+        )
+
+    def update(self, candle: Candle) -> None:
+        anchor = self._session.start(candle.start)
+        if anchor != self._anchor:
+            self._anchor, self._vwap = anchor, Vwap()
+            self._open, self._high, self._low = candle.open, candle.high, candle.low
+        self._high, self._low = max(self._high, candle.high), min(self._low, candle.low)
+        self._close = candle.close
+        self._vwap.update(candle)
+
+
+class Indicators:
+    """Incremental indicators over one symbol's candles at one timeframe.
+
+    Get one from `CandleService.indicators(symbol, interval_secs=...)`.
+    Each accessor registers its indicator on first use, warms it over the
+    candle history, and from then on feeds it only the candles closed since
+    the last read, so nothing is recomputed from scratch. Values are None
+    until an indicator has seen enough candles. Timeframes follow
+    `CandleService.candles`.
+    """
+
+    def __init__(self, candles: "CandleService", symbol: str, interval_secs: Optional[float] = None) -> None:
+        self._candles = candles
+        self._symbol = symbol
+        self._interval = interval_secs
+        self._indicators: Dict[Hashable, Tuple[Indicator, Optional[float]]] = {}  # key -> (indicator, last start fed)
+
+    @property
+    def symbol(self) -> str:
+        return self._symbol
+
+    @property
+    def interval_secs(self) -> float:
+        return self._interval if self._interval is not None else self._candles.interval_secs
+
+    def _get(self, key: Hashable, factory: Callable[[], Indicator], now: Optional[float]) -> Indicator:
+        indicator, fed = self._indicators.get(key) or (factory(), None)
+        for candle in self._candles.candles(self._symbol, now=now, interval_secs=self._interval):
+            if fed is None or candle.start > fed:
+                indicator.update(candle)
+                fed = candle.start
+        self._indicators[key] = (indicator, fed)
+        return indicator
+
+    def ema(self, period: int, *, now: Optional[float] = None) -> Optional[Decimal]:
+        return self._get(("ema", period), lambda: Ema(period), now).value
+
+    def rsi(self, period: int = 14, *, now: Optional[float] = None) -> Optional[Decimal]:
+        return self._get(("rsi", period), lambda: Rsi(period), now).value
+
+    def atr(self, period: int = 14, *, now: Optional[float] = None) -> Optional[Decimal]:
+        return self._get(("atr", period), lambda: Atr(period), now).value
+
+    def bollinger(
+        self, period: int = 20, k: Decimal | float | str = 2, *, now: Optional[float] = None
+    ) -> Optional[Bands]:
+        key = ("bollinger", period, Decimal(str(k)))
+        return self._get(key, lambda: Bollinger(period, k), now).value
+
+    def vwap(self, window: Optional[int] = None, *, now: Optional[float] = None) -> Optional[Decimal]:
+        return self._get(("vwap", window), lambda: Vwap(window), now).value
+
+    def anchored_vwap(self, session: Session = UTC_DAY, *, now: Optional[float] = None) -> Optional[Decimal]:
+        """VWAP since the start of the current `session` (default: the UTC day)."""
+        return self._get(("anchored_vwap", session), lambda: AnchoredVwap(session), now).value
+
+    def session(self, session: Session = UTC_DAY, *, now: Optional[float] = None) -> Optional[SessionStats]:
+        """OHLCV and anchored VWAP of the latest session with a closed candle."""
+        indicator = self._get(("anchored_vwap", session), lambda: AnchoredVwap(session), now)
+        return indicator.stats  # type: ignore[attr-defined]
+
+
+__all__ = [
+    "AnchoredVwap",
+    "Atr",
+    "Bands",
+    "Bollinger",
+    "Ema",
+    "Indicator",
+    "Indicators",
+    "Rsi",
+    "Session",
+    "SessionStats",
+    "UTC_DAY",
+    "Vwap",
+    "funding_session",
+]
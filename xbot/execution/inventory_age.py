@@ -0,0 +1,210 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Dict, Iterable, List, Optional, Tuple, TYPE_CHECKING
+
+from .arbitration import strategy_of
+from .maker_first import MAKER_FIRST, MakerFirstPolicy
+from .models import Order, OrderState
+from .order_expiry import ordered_size_i, size_decimals, to_steps
+from .order_service import fill_increase
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .router import ExecutionRouter
+
+MARKET = "market"
+
+_FILL_STATES = (OrderState.PARTIALLY_FILLED, OrderState.FILLED, OrderState.CANCELLED)  # a polled cancel can carry fills
+
+
+@dataclass(slots=True)
+class InventoryAgeConfig:
+    max_hold_secs: Dict[str, float] = field(default_factory=dict)  # strategy -> max age; "*" for the rest
+    interval_secs: float = 30.0
+    tactic: str = MAKER_FIRST  # maker_first | market
+    policy: MakerFirstPolicy = field(default_factory=MakerFirstPolicy)
+    retry_secs: float = 60.0  # after a failed exit, before trying that slice again
+    dry_run: bool = False  # log and alert only
+
+    def limit(self, strategy: str) -> Optional[float]:
+        return self.max_hold_secs.get(strategy, self.max_hold_secs.get("*"))
+
+
+@dataclass(slots=True)
+class InventorySlice:
+    """Net position one strategy built on one symbol through its own orders."""
+
+    strategy: str
+    symbol: str
+    net_i: int = 0  # signed, + long
+    opened: Optional[float] = None  # when the slice last went from flat (or the other side) to this side
+
+    def age_secs(self, now: Optional[float] = None) -> float:
+        if self.opened is None:
+            return 0.0
+        return max(0.0, (time.time() if now is None else now) - self.opened)
+
+    def to_dict(self, now: Optional[float] = None) -> Dict[str, Any]:
+        return {
+            "strategy": self.strategy,
+            "symbol": self.symbol,
+            "net_i": self.net_i,
+            "opened": self.opened,
+            "age_secs": round(self.age_secs(now), 1),
+        }
+
+
+def _fills(order: Order) -> List[Tuple[float, int]]:
+    """(ts, signed size) of each increase in the order's filled size, counted the way OrderService counts fills."""
+    sign = -1 if order.is_ask else 1
+    decimals = size_decimals(order)
+    found: List[Tuple[float, int]] = []
+    base, filled = Decimal(0), 0
+    for event in order.history:
+        if event.state not in _FILL_STATES:
+            continue
+        base += fill_increase(base, event.info) or 0
+        if event.state == OrderState.FILLED:
+            total = ordered_size_i(order)
+        else:
+            total = to_steps(base, decimals) if decimals is not None else 0
+        if total > filled:
+            found.append((event.ts, (total - filled) * sign))
+            filled = total
+    return found
+
+
+def inventory_slices(orders: Iterable[Order]) -> Dict[Tuple[str, str], InventorySlice]:
+    """Slices per (strategy, symbol), replaying every fill in time order; strategies come from `trace_id`."""
+    fills: Dict[Tuple[str, str], List[Tuple[float, int]]] = {}
+    for order in orders:
+        fills.setdefault((strategy_of(order), order.symbol), []).extend(_fills(order))
+    slices: Dict[Tuple[str, str], InventorySlice] = {}
+    for key, entries in fills.items():
+        inventory = InventorySlice(strategy=key[0], symbol=key[1])
+        for ts, delta in sorted(entries):
+            before = inventory.net_i
+            inventory.net_i += delta
+            if inventory.net_i == 0:
+                inventory.opened = None
+            elif before == 0 or (before > 0) != (inventory.net_i > 0):
+                inventory.opened = ts
+        slices[key] = inventory
+    return slices
+
+
+class InventoryAgeMonitor:
+    """Forces strategies out of positions they have held past their maximum holding time.
+
+    Every `interval_secs` it rebuilds each strategy's slice of every symbol
+    from the router's orders (`inventory_slices`): net filled size and when
+    the slice was opened, reset whenever it goes flat or changes side. A
+    slice older than the strategy's `max_hold_secs` is closed with a
+    reduce-only exit through the maker-first tactic (or a market order),
+    traced `<strategy>:inventory_age` so the exit nets against the same
+    slice. A strategy with no limit of its own and no `"*"` default is
+    never forced. In `dry_run` it only logs and alerts. A failed exit (or
+    a dry-run alert) is repeated after `retry_secs`. Ages cover orders
+    placed since the process started.
+    """
+
+    def __init__(
+        self,
+        *,
+        router: "ExecutionRouter",
+        config: InventoryAgeConfig,
+        alerts: Optional[AlertManager] = None,
+    ) -> None:
+        self._router = router
+        self._config = config
+        self._alerts = alerts
+        self._failed: Dict[Tuple[str, str], float] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> InventoryAgeConfig:
+        return self._config
+
+    async def slices(self) -> List[InventorySlice]:
+        found = inventory_slices(await self._router.orders.orders())
+        return [found[key] for key in sorted(found) if found[key].net_i]
+
+    async def sweep(self, now: Optional[float] = None) -> List[InventorySlice]:
+        """Exit every slice past its limit; returns the slices an exit was sent for."""
+        now = time.time() if now is None else now
+        forced: List[InventorySlice] = []
+        for inventory in await self.slices():
+            limit, age = self._config.limit(inventory.strategy), inventory.age_secs(now)
+            METRICS.set("inventory_age_secs", age, strategy=inventory.strategy, symbol=inventory.symbol)
+            if limit is None or age <= limit:
+                continue
+            key = (inventory.strategy, inventory.symbol)
+            if now - self._failed.get(key, float("-inf")) < self._config.retry_secs:
+                continue
+            if await self._exit(inventory, limit, now):
+                forced.append(inventory)
+        return forced
+
+    async def _exit(self, inventory: InventorySlice, limit: float, now: float) -> bool:
+        extra = {**inventory.to_dict(now), "max_hold_secs": limit, "tactic": self._config.tactic}
+        self._logger.warning("inventory_age_exit", extra={**extra, "dry_run": self._config.dry_run})
+        if self._alerts is not None:
+            await self._alerts.notify(
+                AlertLevel.WARNING,
+                f"Forced exit of {inventory.strategy} on {inventory.symbol}",
+                f"held {int(inventory.age_secs(now))}s, over the {int(limit)}s limit",
+                **extra,
+            )
+        if self._config.dry_run:
+            self._failed[(inventory.strategy, inventory.symbol)] = now
+            return False
+        kwargs: Dict[str, Any] = {
+            "symbol": inventory.symbol,
+            "is_ask": inventory.net_i > 0,
+            "size_i": abs(inventory.net_i),
+            "reduce_only": 1,
+            "trace_id": f"{inventory.strategy}:inventory_age",
+        }
+        try:
+            if self._config.tactic == MARKET:
+                await self._router.submit_market(**kwargs)
+            else:
+                await self._router.maker_first(**kwargs, policy=self._config.policy)
+        except Exception as exc:
+            self._failed[(inventory.strategy, inventory.symbol)] = now
+            self._logger.info("inventory_age_exit_error", extra={**extra, "error": str(exc)})
+            return False
+        self._failed.pop((inventory.strategy, inventory.symbol), None)
+        METRICS.inc("inventory_forced_exits", strategy=inventory.strategy, symbol=inventory.symbol)
+        return True
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="inventory-age")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.sweep()
+            except Exception as exc:
+                self._logger.info("inventory_age_error", extra={"error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = ["InventoryAgeConfig", "InventoryAgeMonitor", "InventorySlice", "MARKET", "inventory_slices"]
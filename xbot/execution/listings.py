@@ -0,0 +1,176 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+from xbot.connector.interface import IConnector
+
+from .market_data_service import MarketDataService
+from .watchlist import Watchlist
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.events import EventKind, EventStream
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class ListingConfig:
+    interval_secs: float = 60.0
+    market_types: List[str] = field(default_factory=lambda: ["PERP"])  # empty announces every market type
+    subscribe: bool = False  # add new listings to the watchlist, so market data streams straight away
+
+
+@dataclass(slots=True)
+class NewListing:
+    symbol: str  # canonical symbol registered in MarketDataService
+    venue_symbol: str
+    market_type: str
+    price_decimals: int
+    size_decimals: int
+    min_size_i: int
+    subscribed: bool
+    ts: float = field(default_factory=time.time)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "symbol": self.symbol,
+            "venue_symbol": self.venue_symbol,
+            "market_type": self.market_type,
+            "price_decimals": self.price_decimals,
+            "size_decimals": self.size_decimals,
+            "min_size_i": self.min_size_i,
+            "subscribed": self.subscribed,
+            "ts": self.ts,
+        }
+
+
+class ListingDetector:
+    """Onboards markets the venue lists while the bot is running.
+
+    Every `interval_secs` the connector reloads its market list
+    (`refresh_markets()`, which also loads the new filters); markets are new
+    when they were not listed at `start()` or on an earlier cycle. Each new market
+    of a configured type is registered in `MarketDataService` under its venue
+    symbol, its decimals and minimum size are loaded, and, with `subscribe`,
+    it joins the watchlist. It is then announced as a `NEW_LISTING` event and
+    an INFO alert. A listing that fails to onboard is retried next cycle.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        market_data: MarketDataService,
+        events: EventStream,
+        alerts: Optional[AlertManager] = None,
+        watchlist: Optional[Watchlist] = None,
+        config: Optional[ListingConfig] = None,
+    ) -> None:
+        if not hasattr(connector, "refresh_markets"):
+            raise ValueError(f"listing detection is not supported on {connector.venue}")
+        self._connector = connector
+        self._market_data = market_data
+        self._events = events
+        self._alerts = alerts
+        self._watchlist = watchlist
+        self._config = config or ListingConfig()
+        self._known: Optional[set[str]] = None  # markets seen so far; seeded with those listed at startup
+        self._pending: List[str] = []
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def _market_type(self, venue_symbol: str) -> str:
+        getter = getattr(self._connector, "market_info", None)
+        info = getter(venue_symbol) if getter is not None else None
+        return str((info or {}).get("marketType") or "SPOT").upper()
+
+    async def check(self) -> List[NewListing]:
+        """Reload the market list once; returns the listings onboarded."""
+        connector: Any = self._connector
+        listed = await connector.refresh_markets()
+        if self._known is None:
+            self._known = set(listed)
+        for venue_symbol in listed:
+            if venue_symbol not in self._known:
+                self._known.add(venue_symbol)
+                self._pending.append(venue_symbol)
+        listings: List[NewListing] = []
+        types = [t.upper() for t in self._config.market_types]
+        for venue_symbol in list(self._pending):
+            market_type = self._market_type(venue_symbol)
+            if types and market_type not in types:
+                self._pending.remove(venue_symbol)
+                continue
+            try:
+                listing = await self._onboard(venue_symbol, market_type)
+            except Exception as exc:
+                self._logger.info("listing_onboard_error", extra={"venue_symbol": venue_symbol, "error": str(exc)})
+                continue
+            self._pending.remove(venue_symbol)
+            listings.append(listing)
+            await self._announce(listing)
+        return listings
+
+    async def _onboard(self, venue_symbol: str, market_type: str) -> NewListing:
+        symbol = venue_symbol.upper()
+        if symbol not in self._market_data.symbols():
+            self._market_data.register_symbol(symbol, venue_symbol)
+        price_decimals, size_decimals = await self._market_data.get_price_size_decimals(symbol)
+        min_size_i = await self._market_data.get_min_size_i(symbol)
+        subscribed = False
+        if self._config.subscribe and self._watchlist is not None:
+            await self._watchlist.add(symbol)
+            subscribed = True
+        return NewListing(
+            symbol=symbol,
+            venue_symbol=venue_symbol,
+            market_type=market_type,
+            price_decimals=price_decimals,
+            size_decimals=size_decimals,
+            min_size_i=min_size_i,
+            subscribed=subscribed,
+        )
+
+    async def _announce(self, listing: NewListing) -> None:
+        venue = self._connector.venue
+        METRICS.inc("new_listings", venue=venue, market_type=listing.market_type)
+        self._logger.info("new_listing", extra={"venue": venue, **listing.to_dict()})
+        self._events.emit(EventKind.NEW_LISTING, venue, listing.to_dict(), symbol=listing.symbol)
+        if self._alerts is not None:
+            await self._alerts.notify(
+                AlertLevel.INFO,
+                f"new listing {listing.venue_symbol}",
+                f"{venue} listed {listing.market_type} market {listing.venue_symbol}"
+                + (", market data subscribed" if listing.subscribed else ""),
+                venue=venue,
+                **listing.to_dict(),
+            )
+
+    async def start(self) -> None:
+        if self._known is None:
+            listed = getattr(self._connector, "market_symbols", None)
+            self._known = set(listed()) if listed is not None else None
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="listing-detector")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            await asyncio.sleep(self._config.interval_secs)
+            try:
+                await self.check()
+            except Exception as exc:
+                self._logger.info("listing_check_error", extra={"venue": self._connector.venue, "error": str(exc)})
+
+
+__all__ = ["ListingConfig", "ListingDetector", "NewListing"]
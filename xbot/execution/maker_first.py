@@ -0,0 +1,255 @@
+from __future__ import annotations
+
+import asyncio
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, List, Mapping, Optional, TYPE_CHECKING
+
+from .market_data_service import MarketDataService
+from .models import Order, OrderEvent, OrderState
+from .risk_service import RiskViolationError
+from ..core.locks import ReentrantLock
+from ..core.metrics import METRICS
+from ..utils.idgen import client_order_index_for
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .order_service import OrderService
+
+MAKER_FIRST = "maker_first"
+
+
+@dataclass(slots=True)
+class MakerFirstPolicy:
+    """Post passively, reprice as the touch moves, cross the spread as a last resort."""
+
+    max_reprices: int = 3
+    timeout_secs: float = 30.0  # total passive budget before crossing
+    poll_secs: float = 1.0  # how often a resting order checks the touch
+    adverse_move_bps: Optional[Decimal] = None  # cross once the touch runs this far from arrival
+    cancel_wait_secs: float = 2.0
+
+    @classmethod
+    def from_mapping(cls, raw: Mapping[str, Any]) -> "MakerFirstPolicy":
+        adverse = raw.get("adverse_move_bps")
+        return cls(
+            max_reprices=int(raw.get("max_reprices", 3)),
+            timeout_secs=float(raw.get("timeout_secs", 30.0)),
+            poll_secs=float(raw.get("poll_secs", 1.0)),
+            adverse_move_bps=None if adverse is None else Decimal(str(adverse)),
+            cancel_wait_secs=float(raw.get("cancel_wait_secs", 2.0)),
+        )
+
+
+@dataclass(slots=True)
+class MakerFirstResult:
+    symbol: str
+    is_ask: bool
+    size_i: int
+    maker_filled_i: int = 0
+    taker_size_i: int = 0  # sent as a market order after crossing
+    reprices: int = 0
+    # timeout | adverse_move | post_only_rejected | no_touch | replayed; None when filled passively
+    crossed: Optional[str] = None
+    orders: List[Order] = field(default_factory=list)  # passive attempts, in order
+    taker_order: Optional[Order] = None
+
+
+class MakerFirstExecutor:
+    """Maker-first tactic: PostOnly at the passive touch with a taker fallback.
+
+    Each attempt joins the passive touch (bid for buys, ask for sells) with a
+    PostOnly limit. While it rests, the touch is polled. When the touch has
+    moved, the order is cancelled and re-posted, up to `max_reprices` times; a
+    PostOnly rejection counts as a reprice too. The remainder crosses the
+    spread as a market order once `timeout_secs` is spent, or once the touch
+    has moved `adverse_move_bps` against the arrival touch.
+
+    With a `client_order_key` every order gets a client index derived from
+    it (`<key>:1`, `<key>:2`, ..., `<key>:taker`), so running the same key
+    again replays the orders already placed: a resting attempt is picked up,
+    and once the taker leg exists no new passive attempt is sent.
+    """
+
+    def __init__(self, *, market_data: MarketDataService) -> None:
+        self._market_data = market_data
+        self._logger = get_logger(__name__)
+
+    def _touch(self, bid_i: Optional[int], ask_i: Optional[int], is_ask: bool) -> Optional[int]:
+        return ask_i if is_ask else bid_i
+
+    def _adverse(self, policy: MakerFirstPolicy, arrival: int, touch: int, is_ask: bool) -> bool:
+        if policy.adverse_move_bps is None:
+            return False
+        moved = Decimal(arrival - touch if is_ask else touch - arrival) / Decimal(arrival) * 10_000
+        return moved >= policy.adverse_move_bps
+
+    async def execute(
+        self,
+        *,
+        order_service: "OrderService",
+        symbol: str,
+        is_ask: bool,
+        size_i: int,
+        policy: Optional[MakerFirstPolicy] = None,
+        reduce_only: int = 0,
+        trace_id: Optional[str] = None,
+        client_order_key: Optional[str] = None,
+    ) -> MakerFirstResult:
+        policy = policy or MakerFirstPolicy()
+        taker_index = _index(client_order_key, "taker")
+        sent = 0
+        venue = order_service.venue
+        deadline = time.monotonic() + policy.timeout_secs
+        result = MakerFirstResult(symbol=symbol, is_ask=is_ask, size_i=size_i)
+        remaining = size_i
+        arrival: Optional[int] = None
+        # Held from cancelling a resting attempt until the next order is
+        # submitted, so a cancel-repost is atomic for the symbol
+        held: Optional[ReentrantLock] = None
+        try:
+            while remaining > 0:
+                bid_i, ask_i, _scale = await self._market_data.get_top_of_book(symbol)
+                touch = self._touch(bid_i, ask_i, is_ask)
+                if touch is None:
+                    result.crossed = "no_touch"
+                    break
+                arrival = arrival or touch
+                if self._adverse(policy, arrival, touch, is_ask):
+                    result.crossed = "adverse_move"
+                    break
+                if time.monotonic() >= deadline:
+                    result.crossed = "timeout"
+                    break
+                sent += 1
+                index = _index(client_order_key, str(sent))
+                if index is not None and not await _placed(order_service, symbol, index):
+                    if await _placed(order_service, symbol, taker_index):
+                        # An earlier run with this key already crossed; the taker below replays it
+                        result.crossed = "replayed"
+                        break
+                try:
+                    order = await order_service.submit_limit(
+                        symbol=symbol,
+                        is_ask=is_ask,
+                        size_i=remaining,
+                        price_i=touch,
+                        post_only=True,
+                        reduce_only=reduce_only,
+                        trace_id=trace_id,
+                        client_order_index=index,
+                    )
+                except RiskViolationError:
+                    raise
+                except Exception as exc:
+                    _release(held)
+                    held = None
+                    # Typically a PostOnly rejection because the touch moved while submitting
+                    self._logger.info("maker_first_post_rejected", extra={"symbol": symbol, "error": str(exc)})
+                    outcome, filled = "rejected", 0
+                else:
+                    _release(held)
+                    held = None
+                    result.orders.append(order)
+                    outcome, update = await self._rest(order, policy, arrival, touch, deadline, result.reprices)
+                    if outcome != "final":
+                        held = order_service.symbol_lock(symbol)
+                        await held.acquire()
+                        update = await self._cancel(order_service, order, policy)
+                    # The cancel's update carries no fill; OrderService has counted every fill into `order.filled`
+                    if update.state == OrderState.FILLED:
+                        filled = remaining
+                    else:
+                        filled = min(remaining, await self._market_data.to_size_i(symbol, order.filled))
+                    if update.state == OrderState.FAILED and not filled:
+                        outcome = "rejected"
+                result.maker_filled_i += filled
+                remaining -= filled
+                if outcome in ("timeout", "adverse_move"):
+                    result.crossed = outcome
+                    break
+                if outcome in ("reprice", "rejected"):
+                    result.reprices += 1
+                    if outcome == "rejected" and result.reprices > policy.max_reprices:
+                        result.crossed = "post_only_rejected"
+                        break
+            METRICS.inc("maker_first_executions", venue=venue)
+            if remaining > 0 and result.crossed is not None:
+                result.taker_size_i = remaining
+                self._logger.info(
+                    "maker_first_cross",
+                    extra={
+                        "symbol": symbol,
+                        "reason": result.crossed,
+                        "remaining_i": remaining,
+                        "maker_filled_i": result.maker_filled_i,
+                        "reprices": result.reprices,
+                    },
+                )
+                METRICS.inc("maker_first_crossed", venue=venue, reason=result.crossed)
+                result.taker_order = await order_service.submit_market(
+                    symbol=symbol,
+                    is_ask=is_ask,
+                    size_i=remaining,
+                    reduce_only=reduce_only,
+                    trace_id=trace_id,
+                    client_order_index=taker_index,
+                )
+        finally:
+            _release(held)
+        return result
+
+    async def _rest(
+        self,
+        order: Order,
+        policy: MakerFirstPolicy,
+        arrival: int,
+        price_i: int,
+        deadline: float,
+        reprices: int,
+    ) -> tuple[str, OrderEvent]:
+        """Wait on a resting order; returns why it should stop, and its final update if it already has."""
+        while True:
+            budget = max(0.0, min(policy.poll_secs, deadline - time.monotonic()))
+            try:
+                return "final", await asyncio.wait_for(order.wait_final(), timeout=budget)
+            except asyncio.TimeoutError:
+                pass
+            reason: Optional[str] = None
+            if time.monotonic() >= deadline:
+                reason = "timeout"
+            else:
+                bid_i, ask_i, _scale = await self._market_data.get_top_of_book(order.symbol)
+                touch = self._touch(bid_i, ask_i, order.is_ask)
+                if touch is not None and self._adverse(policy, arrival, touch, order.is_ask):
+                    reason = "adverse_move"
+                elif touch is not None and touch != price_i and reprices < policy.max_reprices:
+                    reason = "reprice"
+            if reason is not None:
+                return reason, order.snapshot()
+
+    async def _cancel(self, order_service: "OrderService", order: Order, policy: MakerFirstPolicy) -> OrderEvent:
+        await order_service.cancel(order.symbol, order.client_order_index)
+        try:
+            return await asyncio.wait_for(order.wait_final(), timeout=policy.cancel_wait_secs)
+        except asyncio.TimeoutError:
+            return order.snapshot()
+
+
+def _index(key: Optional[str], leg: str) -> Optional[int]:
+    return client_order_index_for(f"{key}:{leg}") if key else None
+
+
+async def _placed(order_service: "OrderService", symbol: str, index: Optional[int]) -> bool:
+    if index is None:
+        return False
+    return any(order.client_order_index == index for order in await order_service.orders(symbol))
+
+
+def _release(lock: Optional[ReentrantLock]) -> None:
+    if lock is not None:
+        lock.release()
+
+
+__all__ = ["MAKER_FIRST", "MakerFirstExecutor", "MakerFirstPolicy", "MakerFirstResult"]
@@ -1,21 +1,64 @@
 from __future__ import annotations
 
 import asyncio
+import sys
+import time
 from dataclasses import dataclass
-from decimal import Decimal, ROUND_DOWN, getcontext
+from enum import Enum
+from decimal import Decimal, ROUND_CEILING, ROUND_DOWN, ROUND_FLOOR, getcontext
 from typing import Dict, Mapping, Optional, Tuple
 
+from xbot.connector.history import CurrentFunding
 from xbot.connector.interface import IConnector
+from xbot.core.cache import MarketCache
+from xbot.core.orderbook import BookMetrics, OrderBook, SlippageEstimate, estimate_slippage
+from xbot.core.symbology import SYMBOLOGY, Instrument
+from xbot.core.symbols import SYMBOLS
 
 getcontext().prec = 28
 
 
+class MarketState(str, Enum):
+    OPEN = "open"
+    POST_ONLY = "post_only"  # only resting limit orders accepted
+    LIMIT_ONLY = "limit_only"  # no market orders
+    REDUCE_ONLY = "reduce_only"  # no orders that open or grow a position
+    CLOSED = "closed"  # no new orders (delisted, maintenance, cancel-only)
+
+
+_MARKET_STATES = {
+    "open": MarketState.OPEN,
+    "postonly": MarketState.POST_ONLY,
+    "limitonly": MarketState.LIMIT_ONLY,
+    "reduceonly": MarketState.REDUCE_ONLY,
+    "cancelonly": MarketState.CLOSED,
+    "closed": MarketState.CLOSED,
+}
+
+
+def parse_market_state(raw: object) -> MarketState:
+    """Venue order-book state (e.g. Backpack `orderBookState`) -> MarketState; unknown or missing reads as open."""
+    key = str(raw or "open").replace("_", "").replace("-", "").lower()
+    return _MARKET_STATES.get(key, MarketState.OPEN)
+
+
 @dataclass(slots=True)
 class SymbolSpec:
     canonical: str
     venue_symbol: str
 
 
+@dataclass(slots=True)
+class MarketData:
+    symbol: str
+    bid_i: Optional[int]
+    ask_i: Optional[int]
+    scale: int
+    ts: float
+    stale: bool
+    funding: Optional[CurrentFunding] = None  # perps on venues exposing `get_current_funding`
+
+
 class UnknownSymbolError(KeyError):
     """Raised when a canonical symbol is not configured for the active venue."""
 
@@ -28,26 +71,66 @@ class MarketDataService:
         *,
         connector: IConnector,
         symbol_map: Mapping[str, str],
+        stale_after_secs: Optional[float] = None,
+        funding_ttl_secs: float = 60.0,
     ) -> None:
         self._connector = connector
-        self._symbol_map: Dict[str, SymbolSpec] = {
-            canonical.upper(): SymbolSpec(canonical=canonical.upper(), venue_symbol=venue)
-            for canonical, venue in symbol_map.items()
-        }
+        self._stale_after = stale_after_secs
+        self._funding_ttl = funding_ttl_secs
+        self._funding: Dict[str, Tuple[Optional[CurrentFunding], float]] = {}
+        self._cache: Optional[MarketCache] = None
+        self._rest_updates: Dict[str, float] = {}
+        self._symbol_map: Dict[str, SymbolSpec] = {}
+        for canonical, venue in symbol_map.items():
+            self.register_symbol(canonical, venue)
         self._decimal_cache: Dict[str, Tuple[int, int]] = {}
         self._min_size_cache: Dict[str, int] = {}
         self._locks: Dict[str, asyncio.Lock] = {}
 
     def _canonical_key(self, symbol: str) -> str:
-        key = symbol.upper()
+        # Registered spellings resolve to the shared interned key without building a new string
+        symbol_id = SYMBOLS.get(symbol)
+        key = SYMBOLS.name(symbol_id) if symbol_id is not None else symbol.upper()
         if key not in self._symbol_map:
             raise UnknownSymbolError(symbol)
         return key
 
+    def register_symbol(self, symbol: str, venue_symbol: str) -> None:
+        """Map a canonical symbol at runtime, e.g. for a new listing added to the watchlist."""
+        key = SYMBOLS.intern(symbol)
+        current = self._symbol_map.get(key)
+        if current is not None and current.venue_symbol != venue_symbol:
+            self._decimal_cache.pop(key, None)
+            self._min_size_cache.pop(key, None)
+        # Venue symbols keep their spelling (some venues are case sensitive) but share one object with WS keys
+        self._symbol_map[key] = SymbolSpec(canonical=key, venue_symbol=sys.intern(venue_symbol))
+
+    def symbols(self) -> Dict[str, str]:
+        """Canonical -> venue symbol for every configured symbol."""
+        return {key: spec.venue_symbol for key, spec in self._symbol_map.items()}
+
+    def market_state(self, symbol: str) -> MarketState:
+        """Order-book state from the connector's last market load; connectors without one always read open."""
+        getter = getattr(self._connector, "market_info", None)
+        if getter is None:
+            return MarketState.OPEN
+        info = getter(self.resolve_symbol(symbol)) or {}
+        return parse_market_state(info.get("orderBookState"))
+
     def resolve_symbol(self, symbol: str) -> str:
         key = self._canonical_key(symbol)
         return self._symbol_map[key].venue_symbol
 
+    def instrument(self, symbol: str) -> Instrument:
+        """Contract spec (currencies, multiplier, inverse) of a canonical symbol on this venue."""
+        return SYMBOLOGY.instrument(getattr(self._connector, "venue", ""), self.resolve_symbol(symbol))
+
+    def canonical_for(self, venue_symbol: str) -> Optional[str]:
+        for spec in self._symbol_map.values():
+            if spec.venue_symbol == venue_symbol:
+                return spec.canonical
+        return None
+
     async def get_price_size_decimals(self, symbol: str) -> Tuple[int, int]:
         key = self._canonical_key(symbol)
         if key in self._decimal_cache:
@@ -74,13 +157,21 @@ class MarketDataService:
             self._min_size_cache[key] = minimum
             return minimum
 
-    async def to_price_i(self, symbol: str, price: Decimal | float | str) -> int:
+    async def to_price_i(self, symbol: str, price: Decimal | float | str, *, is_ask: Optional[bool] = None) -> int:
+        """Price in integer ticks, never worse than `price` for the order's side.
+
+        Bids round down and asks round up. A price between two ticks
+        therefore never pays more or sells for less than requested, and a
+        post-only price never moves toward the spread. With no side given
+        the price rounds toward negative infinity.
+        """
         price_decimals, _ = await self.get_price_size_decimals(symbol)
         scale = Decimal(10) ** price_decimals
         value = Decimal(str(price)) * scale
-        return int(value.to_integral_value(rounding=ROUND_DOWN))
+        return int(value.to_integral_value(rounding=ROUND_CEILING if is_ask else ROUND_FLOOR))
 
     async def to_size_i(self, symbol: str, size: Decimal | float | str) -> int:
+        """Size in integer steps, truncated so an order never exceeds the requested quantity."""
         _, size_decimals = await self.get_price_size_decimals(symbol)
         scale = Decimal(10) ** size_decimals
         value = Decimal(str(size)) * scale
@@ -94,7 +185,126 @@ class MarketDataService:
     async def get_top_of_book(self, symbol: str) -> Tuple[Optional[int], Optional[int], int]:
         venue_symbol = self.resolve_symbol(symbol)
         bid_i, ask_i, scale = await self._connector.get_top_of_book(venue_symbol)
+        if bid_i is not None or ask_i is not None:
+            self._rest_updates[venue_symbol] = time.time()
         return bid_i, ask_i, scale
 
+    async def get_market_data(self, symbol: str) -> MarketData:
+        bid_i, ask_i, scale = await self.get_top_of_book(symbol)
+        return MarketData(
+            symbol=symbol.upper(),
+            bid_i=bid_i,
+            ask_i=ask_i,
+            scale=scale,
+            ts=self.last_update(symbol) or time.time(),
+            stale=self.is_stale(symbol),
+            funding=await self.get_funding(symbol),
+        )
+
+    async def get_funding(self, symbol: str) -> Optional[CurrentFunding]:
+        """Current funding for a canonical symbol, cached for `funding_ttl_secs`.
+
+        The cache is also dropped once the cached accrual time passes, so the
+        next interval's rate and timestamp are picked up straight away.
+        Markets without funding (spot, or venues without the method) return
+        None; a failed refresh keeps the previous value until the next TTL.
+        """
+        venue_symbol = self.resolve_symbol(symbol)
+        fetch = getattr(self._connector, "get_current_funding", None)
+        if fetch is None:
+            return None
+        now = time.time()
+        cached = self._funding.get(venue_symbol)
+        if cached is not None:
+            info, fetched_at = cached
+            fresh = now - fetched_at < self._funding_ttl
+            if fresh and (info is None or now * 1000 < info.next_funding_at_ms(fetched_at)):
+                return info
+        previous = cached[0] if cached else None
+        try:
+            rows = await fetch(venue_symbol)
+        except Exception:
+            self._funding[venue_symbol] = (previous, now)
+            return previous
+        venue = getattr(self._connector, "venue", None)
+        found = next((r for r in rows if r.symbol == venue_symbol and (venue is None or r.venue == venue)), None)
+        self._funding[venue_symbol] = (found, now)
+        return found
+
+    async def get_order_book(self, symbol: str) -> Optional[OrderBook]:
+        """Local book from the WS-fed cache while fresh, else a REST depth snapshot.
+
+        Returns None when neither is available (no streamed book and the
+        connector has no `get_order_book`).
+        """
+        venue_symbol = self.resolve_symbol(symbol)
+        if self._cache is not None:
+            book = self._cache.books.get(venue_symbol)
+            if book is not None and (self._stale_after is None or time.time() - book.ts <= self._stale_after):
+                return book
+        fetch = getattr(self._connector, "get_order_book", None)
+        if fetch is None:
+            return None
+        book = await fetch(venue_symbol)
+        self._rest_updates[venue_symbol] = book.ts
+        return book
+
+    def book_metrics(self, symbol: str) -> Optional[BookMetrics]:
+        """Microprice, depth-weighted mid and imbalances of the streamed book.
+
+        Maintained by the cache as deltas arrive, so this is a read, not a
+        computation. None when the feed keeps no local book for `symbol`.
+        """
+        if self._cache is None:
+            return None
+        book = self._cache.books.get(self.resolve_symbol(symbol))
+        return book.metrics() if book is not None else None
+
+    async def slippage_estimate(
+        self, symbol: str, is_ask: bool, size: Decimal | float | str
+    ) -> Optional[SlippageEstimate]:
+        """Expected average fill price and impact (bps from mid) of a market order of `size` base units."""
+        book = await self.get_order_book(symbol)
+        if book is None:
+            return None
+        return estimate_slippage(book, is_ask=is_ask, size=float(size))
+
+    def attach_cache(self, cache: MarketCache) -> None:
+        """Use WS-fed book updates in the cache as an additional freshness source."""
+        self._cache = cache
+
+    @property
+    def stale_after_secs(self) -> Optional[float]:
+        return self._stale_after
+
+    def last_update(self, symbol: str) -> Optional[float]:
+        venue_symbol = self.resolve_symbol(symbol)
+        stamps = [self._rest_updates.get(venue_symbol)]
+        if self._cache is not None:
+            book = self._cache.orderbooks.get(venue_symbol)
+            stamps.append(book[2] if book else None)
+        seen = [ts for ts in stamps if ts is not None]
+        return max(seen) if seen else None
+
+    def is_stale(self, symbol: str, now: Optional[float] = None) -> bool:
+        """True once a symbol that has been quoted goes silent for `stale_after_secs`.
+
+        Symbols with no update yet are not reported stale, so venues without a
+        streaming feed are unaffected until their first REST quote.
+        """
+        if self._stale_after is None:
+            return False
+        last = self.last_update(symbol)
+        if last is None:
+            return False
+        return (now if now is not None else time.time()) - last > self._stale_after
+
 
-__all__ = ["MarketDataService", "SymbolSpec", "UnknownSymbolError"]
+__all__ = [
+    "MarketData",
+    "MarketDataService",
+    "MarketState",
+    "SymbolSpec",
+    "UnknownSymbolError",
+    "parse_market_state",
+]
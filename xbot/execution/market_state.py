@@ -0,0 +1,108 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+from typing import Any, Dict, Optional
+
+from xbot.connector.interface import IConnector
+
+from .market_data_service import MarketDataService, MarketState
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.events import EventKind, EventStream
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+
+class MarketStateMonitor:
+    """Keeps the venue's order-book states fresh and reports changes.
+
+    Every `interval_secs` the connector reloads its markets (`refresh_markets()`),
+    which is where `MarketDataService.market_state()` reads from, so the risk
+    layer sees post-only, reduce-only or closed books within one interval.
+    Each change of a configured symbol is published as a `MARKET_STATE` event
+    (`symbol`, `state`, `previous`) and alerted: WARNING when the book leaves
+    `open`, INFO when it comes back.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        market_data: MarketDataService,
+        events: Optional[EventStream] = None,
+        alerts: Optional[AlertManager] = None,
+        interval_secs: float = 60.0,
+    ) -> None:
+        if not hasattr(connector, "refresh_markets"):
+            raise ValueError(f"market states are not supported on {connector.venue}")
+        self._connector = connector
+        self._market_data = market_data
+        self._events = events
+        self._alerts = alerts
+        self._interval = interval_secs
+        self._states: Dict[str, MarketState] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def states(self) -> Dict[str, MarketState]:
+        return dict(self._states)
+
+    async def check(self) -> Dict[str, MarketState]:
+        """Reload markets once; returns the symbols whose state changed."""
+        connector: Any = self._connector
+        await connector.refresh_markets()
+        changed: Dict[str, MarketState] = {}
+        for symbol in self._market_data.symbols():
+            state = self._market_data.market_state(symbol)
+            previous = self._states.get(symbol)
+            self._states[symbol] = state
+            METRICS.set("market_open", 1.0 if state == MarketState.OPEN else 0.0, venue=connector.venue, symbol=symbol)
+            # The first reading of a symbol only reports books that are not open
+            if state != (previous or MarketState.OPEN):
+                changed[symbol] = state
+                await self._report(symbol, state, previous or MarketState.OPEN)
+        return changed
+
+    async def _report(self, symbol: str, state: MarketState, previous: MarketState) -> None:
+        venue = self._connector.venue
+        self._logger.warning(
+            "market_state_changed", extra={"symbol": symbol, "state": state.value, "previous": previous.value}
+        )
+        if self._events is not None:
+            self._events.emit(
+                EventKind.MARKET_STATE,
+                venue,
+                {"symbol": symbol, "state": state.value, "previous": previous.value},
+                symbol=symbol,
+            )
+        if self._alerts is not None:
+            await self._alerts.notify(
+                AlertLevel.INFO if state == MarketState.OPEN else AlertLevel.WARNING,
+                f"{symbol} market {state.value}",
+                f"{venue} order book for {symbol} went from {previous.value} to {state.value}",
+                venue=venue,
+                symbol=symbol,
+            )
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="market-state")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.check()
+            except Exception as exc:
+                self._logger.info("market_state_error", extra={"venue": self._connector.venue, "error": str(exc)})
+            await asyncio.sleep(self._interval)
+
+
+__all__ = ["MarketStateMonitor"]
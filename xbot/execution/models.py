@@ -4,9 +4,13 @@ import asyncio
 import json
 import time
 from dataclasses import dataclass, field
+from decimal import Decimal
 from enum import Enum
 from pathlib import Path
-from typing import Any, Dict, List, Optional
+from typing import TYPE_CHECKING, Any, Callable, Dict, List, Optional
+
+if TYPE_CHECKING:
+    from .queue_position import QueuePosition
 
 
 class OrderState(str, Enum):
@@ -46,6 +50,7 @@ class Order:
         is_ask: bool,
         log_dir: Optional[Path] = None,
         trace_id: Optional[str] = None,
+        sink: Optional[Callable[["Order", OrderEvent], None]] = None,
     ) -> None:
         self.venue = venue
         self.symbol = symbol
@@ -53,6 +58,8 @@ class Order:
         self.is_ask = is_ask
         self.trace_id = trace_id
         self.exchange_order_id: Optional[str] = None
+        self.queue_position: Optional[QueuePosition] = None  # set while a QueuePositionTracker follows it
+        self.filled = Decimal(0)  # cumulative base quantity, advanced by OrderService as fills are recorded
         self._state = OrderState.SUBMITTING
         self._history: List[OrderEvent] = []
         self._loop = asyncio.get_event_loop()
@@ -60,6 +67,7 @@ class Order:
         self._update_waiters: List[asyncio.Future[OrderEvent]] = []
         self._lock = asyncio.Lock()
         self._log_dir = log_dir
+        self._sink = sink
 
     @property
     def state(self) -> OrderState:
@@ -101,8 +109,23 @@ class Order:
                 self._final_future.set_result(event)
             if self._log_dir:
                 self._persist_event(event)
+            if self._sink is not None:
+                try:
+                    self._sink(self, event)
+                except Exception:
+                    pass
         return event
 
+    def restore(self, events: List[OrderEvent], *, exchange_order_id: Optional[str] = None) -> None:
+        """Rehydrate history from persisted events without re-persisting them."""
+        self._history = list(events)
+        if exchange_order_id:
+            self.exchange_order_id = exchange_order_id
+        if self._history:
+            self._state = self._history[-1].state
+            if self._state in FINAL_STATES and not self._final_future.done():
+                self._final_future.set_result(self._history[-1])
+
     def _persist_event(self, event: OrderEvent) -> None:
         try:
             self._log_dir.mkdir(parents=True, exist_ok=True)
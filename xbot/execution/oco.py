@@ -0,0 +1,136 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import secrets
+from dataclasses import dataclass
+from typing import List, Optional, Sequence, Set, TYPE_CHECKING
+
+from .models import FINAL_STATES, Order, OrderState
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .router import ExecutionRouter
+
+OCO = "oco"
+
+
+@dataclass(slots=True, frozen=True)
+class OcoLeg:
+    is_ask: bool
+    price_i: int
+    size_i: int
+    post_only: bool = False
+    reduce_only: int = 0
+
+
+def _filled(order: Order) -> bool:
+    return order.state == OrderState.FILLED or order.filled > 0
+
+
+class OcoManager:
+    """One-cancels-the-other groups of resting limit orders.
+
+    `place(symbol, legs)` submits every leg under the trace `oco:<group>`;
+    once any leg fills, even partly, every sweep cancels the legs still
+    resting. A leg cancelled without a fill leaves the others working. The
+    group is its trace_id, so after a restart the legs RecoveryService
+    hands to `adopt` (closed ones included) re-form it, and a leg that
+    filled while the bot was down cancels its siblings on the next sweep.
+    """
+
+    def __init__(self, *, router: "ExecutionRouter", sweep_secs: float = 0.5) -> None:
+        self._router = router
+        self._sweep_secs = sweep_secs
+        self._groups: Set[str] = set()  # trace ids
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def groups(self) -> List[str]:
+        return sorted(self._groups)
+
+    async def place(self, symbol: str, legs: Sequence[OcoLeg], *, group_id: Optional[str] = None) -> List[Order]:
+        """Submit every leg; when one is refused the legs already placed are cancelled and the error raised."""
+        if len(legs) < 2:
+            raise ValueError("an OCO group needs at least two legs")
+        trace_id = f"{OCO}:{group_id or secrets.token_hex(4)}"
+        placed: List[Order] = []
+        for leg in legs:
+            try:
+                order = await self._router.submit_limit(
+                    symbol=symbol,
+                    is_ask=leg.is_ask,
+                    size_i=leg.size_i,
+                    price_i=leg.price_i,
+                    post_only=leg.post_only,
+                    reduce_only=leg.reduce_only,
+                    trace_id=trace_id,
+                )
+            except Exception:
+                for order in placed:
+                    await self._cancel(order)
+                raise
+            placed.append(order)
+        self._groups.add(trace_id)
+        self._logger.info("oco_placed", extra={"symbol": symbol, "trace_id": trace_id, "legs": len(placed)})
+        return placed
+
+    async def adopt(self, order: Order) -> None:
+        """Rejoin a leg restored after a restart to its group."""
+        if order.trace_id and order.trace_id.startswith(f"{OCO}:"):
+            self._groups.add(order.trace_id)
+
+    async def sweep(self) -> int:
+        """Cancel the resting legs of every group that has a fill; returns how many were cancelled."""
+        cancelled = 0
+        orders = await self._router.orders.orders()
+        for trace_id in list(self._groups):
+            legs = [order for order in orders if order.trace_id == trace_id]
+            if legs and all(order.state in FINAL_STATES for order in legs):
+                self._groups.discard(trace_id)
+                continue
+            if not any(_filled(order) for order in legs):
+                continue
+            for order in legs:
+                if order.state not in FINAL_STATES and not _filled(order) and await self._cancel(order):
+                    cancelled += 1
+                    METRICS.inc("oco_cancels", venue=self._router.orders.venue)
+        return cancelled
+
+    async def _cancel(self, order: Order) -> bool:
+        try:
+            await self._router.cancel(order.symbol, order.client_order_index)
+        except Exception as exc:
+            self._logger.info(
+                "oco_cancel_error",
+                extra={"symbol": order.symbol, "client_order_index": order.client_order_index, "error": str(exc)},
+            )
+            return False
+        return True
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="oco")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            await asyncio.sleep(self._sweep_secs)
+            if not self._groups:
+                continue
+            try:
+                await self.sweep()
+            except Exception as exc:
+                self._logger.info("oco_sweep_error", extra={"error": str(exc)})
+
+
+__all__ = ["OCO", "OcoLeg", "OcoManager"]
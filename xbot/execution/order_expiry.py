@@ -0,0 +1,159 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from decimal import ROUND_DOWN, Decimal
+from typing import Dict, Optional, TYPE_CHECKING
+
+from .models import FINAL_STATES, Order, OrderState
+from ..core.command_bus import TradingResult
+from ..core.events import EventKind, EventStream
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .order_service import OrderService
+
+
+@dataclass(slots=True)
+class _Expiring:
+    order: Order
+    deadline: float  # monotonic
+    command_id: Optional[str]
+
+
+def ordered_size_i(order: Order) -> int:
+    """Size the order was placed with, or the latest size it was amended to."""
+    history = order.history
+    amended = next((int(e.info["size_i"]) for e in reversed(history) if e.info.get("amended")), None)
+    if amended is not None:
+        return amended
+    return next((int(e.info["size_i"]) for e in history if e.info.get("size_i")), 0)
+
+
+def filled_size_i(order: Order) -> int:
+    """Filled size so far in size steps, from the base quantity OrderService has counted into `order.filled`.
+
+    Orders journaled without their size decimals report 0 until they fill.
+    """
+    if order.state == OrderState.FILLED:
+        return ordered_size_i(order)
+    decimals = size_decimals(order)
+    if decimals is None:
+        return 0
+    return to_steps(order.filled, decimals)
+
+
+def size_decimals(order: Order) -> Optional[int]:
+    """Size decimals OrderService recorded when it placed the order."""
+    return next((int(e.info["size_decimals"]) for e in order.history if "size_decimals" in e.info), None)
+
+
+def to_steps(base: Decimal, decimals: int) -> int:
+    return int((base * Decimal(10) ** decimals).to_integral_value(rounding=ROUND_DOWN))
+
+
+class OrderExpiryService:
+    """Cancels resting orders still unfilled when their time in force runs out.
+
+    `track(order, time_in_force_ms)` arms a deadline; a sweep every
+    `sweep_secs` cancels live orders past it and reports a `TradingResult`
+    (status `expired`, with the filled and remaining size) as a
+    `COMMAND_RESULT` event. Orders that finish before their deadline are
+    dropped silently. A failed cancel is retried on the next sweep.
+    """
+
+    def __init__(
+        self,
+        *,
+        order_service: "OrderService",
+        events: Optional[EventStream] = None,
+        sweep_secs: float = 0.5,
+    ) -> None:
+        self._orders = order_service
+        self._events = events
+        self._sweep_secs = sweep_secs
+        self._tracked: Dict[int, _Expiring] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def track(self, order: Order, time_in_force_ms: int, *, command_id: Optional[str] = None) -> None:
+        if time_in_force_ms <= 0:
+            raise ValueError("time_in_force_ms must be positive")
+        if order.client_order_index in self._tracked:
+            return  # a replayed command keeps the original deadline
+        deadline = time.monotonic() + time_in_force_ms / 1000
+        self._tracked[order.client_order_index] = _Expiring(order=order, deadline=deadline, command_id=command_id)
+
+    def untrack(self, client_order_index: int) -> None:
+        self._tracked.pop(client_order_index, None)
+
+    def pending(self) -> int:
+        return len(self._tracked)
+
+    async def sweep(self) -> list[TradingResult]:
+        """Cancel every tracked order past its deadline; returns the results reported."""
+        now = time.monotonic()
+        results: list[TradingResult] = []
+        for coi, entry in list(self._tracked.items()):
+            order = entry.order
+            if order.state in FINAL_STATES:
+                self._tracked.pop(coi, None)
+                continue
+            if now < entry.deadline:
+                continue
+            try:
+                await self._orders.cancel(order.symbol, coi)
+            except Exception as exc:
+                self._logger.info(
+                    "order_expiry_cancel_error",
+                    extra={"symbol": order.symbol, "client_order_index": coi, "error": str(exc)},
+                )
+                continue
+            self._tracked.pop(coi, None)
+            results.append(self._report(entry))
+        return results
+
+    def _report(self, entry: _Expiring) -> TradingResult:
+        order = entry.order
+        result = TradingResult(
+            command_id=entry.command_id,
+            venue=order.venue,
+            symbol=order.symbol,
+            status="expired",
+            client_order_index=order.client_order_index,
+            size_i=ordered_size_i(order),
+            filled_i=filled_size_i(order),
+        )
+        METRICS.inc("orders_expired", venue=order.venue, partial=str(result.partial).lower())
+        self._logger.info("order_expired", extra=result.to_dict())
+        if self._events is not None:
+            self._events.emit(EventKind.COMMAND_RESULT, order.venue, result.to_dict(), symbol=order.symbol)
+        return result
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="order-expiry")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            await asyncio.sleep(self._sweep_secs)
+            if not self._tracked:
+                continue
+            try:
+                await self.sweep()
+            except Exception as exc:
+                self._logger.info("order_expiry_sweep_error", extra={"error": str(exc)})
+
+
+__all__ = ["OrderExpiryService", "filled_size_i", "ordered_size_i", "size_decimals", "to_steps"]
@@ -0,0 +1,166 @@
+from __future__ import annotations
+
+import asyncio
+import time
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import AsyncIterator, Dict, List, Optional, Set
+
+from .models import FINAL_STATES, Order, OrderEvent, OrderState
+from .order_expiry import filled_size_i, ordered_size_i
+
+
+class ProgressStage(str, Enum):
+    ACKNOWLEDGED = "acknowledged"
+    PARTIALLY_FILLED = "partially_filled"
+    FILLED = "filled"
+    CANCELLED = "cancelled"
+    FAILED = "failed"
+
+
+_STAGES = {
+    OrderState.OPEN: ProgressStage.ACKNOWLEDGED,
+    OrderState.PARTIALLY_FILLED: ProgressStage.PARTIALLY_FILLED,
+    OrderState.FILLED: ProgressStage.FILLED,
+    OrderState.CANCELLED: ProgressStage.CANCELLED,
+    OrderState.FAILED: ProgressStage.FAILED,
+}
+
+
+@dataclass(slots=True)
+class OrderProgress:
+    key: str  # command_id for bus commands, otherwise the orders' trace_id
+    venue: str
+    symbol: str
+    client_order_index: int
+    stage: ProgressStage
+    size_i: int
+    filled_i: int  # cumulative for this order
+    ts: float = field(default_factory=time.time)
+
+    @property
+    def remaining_i(self) -> int:
+        return max(0, self.size_i - self.filled_i)
+
+    @property
+    def final(self) -> bool:
+        return self.stage in (ProgressStage.FILLED, ProgressStage.CANCELLED, ProgressStage.FAILED)
+
+    def to_dict(self) -> Dict[str, object]:
+        return {
+            "key": self.key,
+            "venue": self.venue,
+            "symbol": self.symbol,
+            "client_order_index": self.client_order_index,
+            "stage": self.stage.value,
+            "size_i": self.size_i,
+            "filled_i": self.filled_i,
+            "remaining_i": self.remaining_i,
+            "ts": self.ts,
+        }
+
+
+@dataclass(slots=True)
+class _Command:
+    updates: List[OrderProgress] = field(default_factory=list)
+    live: Set[int] = field(default_factory=set)  # client order indices not yet final
+    complete: bool = False  # the command will place no further orders
+    subscribers: List["asyncio.Queue[Optional[OrderProgress]]"] = field(default_factory=list)
+    done_at: Optional[float] = None
+
+    @property
+    def done(self) -> bool:
+        return self.complete and not self.live
+
+
+class OrderProgressChannel:
+    """Streams per-order progress grouped by command.
+
+    Orders are grouped by `trace_id`; `RouterCommandHandler` sets it to the
+    `command_id`, and in-process callers may pass their own key as
+    `trace_id`. Every order update becomes an `OrderProgress`:
+    acknowledged, partially filled (with the cumulative filled size), then
+    filled, cancelled or failed. `stream(key)` replays what happened so far
+    and ends once `complete(key)` was called and every order of the key is
+    final. Finished keys are kept for `retain_secs` for late subscribers.
+    """
+
+    def __init__(self, *, retain_secs: float = 300.0) -> None:
+        self._retain_secs = retain_secs
+        self._commands: Dict[str, _Command] = {}
+
+    def on_order_event(self, order: Order, event: OrderEvent) -> None:
+        key = order.trace_id
+        stage = _STAGES.get(event.state)
+        if key is None or stage is None:
+            return
+        entry = self._commands.setdefault(key, _Command())
+        progress = OrderProgress(
+            key=key,
+            venue=order.venue,
+            symbol=order.symbol,
+            client_order_index=order.client_order_index,
+            stage=stage,
+            size_i=ordered_size_i(order),
+            filled_i=filled_size_i(order),
+            ts=event.ts,
+        )
+        if event.state in FINAL_STATES:
+            entry.live.discard(order.client_order_index)
+        else:
+            entry.live.add(order.client_order_index)
+        entry.updates.append(progress)
+        for queue in entry.subscribers:
+            queue.put_nowait(progress)
+        self._settle(key, entry)
+
+    def complete(self, key: str) -> None:
+        """Mark that no further orders will be placed under `key`."""
+        entry = self._commands.setdefault(key, _Command())
+        entry.complete = True
+        self._settle(key, entry)
+
+    def updates(self, key: str) -> List[OrderProgress]:
+        entry = self._commands.get(key)
+        return list(entry.updates) if entry else []
+
+    def latest(self, key: str) -> Optional[OrderProgress]:
+        entry = self._commands.get(key)
+        return entry.updates[-1] if entry and entry.updates else None
+
+    async def stream(self, key: str) -> AsyncIterator[OrderProgress]:
+        """Updates for `key`, past ones first; subscribe before publishing to see every one of them live."""
+        entry = self._commands.setdefault(key, _Command())
+        queue: "asyncio.Queue[Optional[OrderProgress]]" = asyncio.Queue()
+        for progress in entry.updates:
+            queue.put_nowait(progress)
+        if entry.done:
+            queue.put_nowait(None)
+        else:
+            entry.subscribers.append(queue)
+        try:
+            while True:
+                progress = await queue.get()
+                if progress is None:
+                    return
+                yield progress
+        finally:
+            if queue in entry.subscribers:
+                entry.subscribers.remove(queue)
+
+    def _settle(self, key: str, entry: _Command) -> None:
+        if entry.done and entry.done_at is None:
+            entry.done_at = time.monotonic()
+            for queue in entry.subscribers:
+                queue.put_nowait(None)
+            entry.subscribers.clear()
+        now = time.monotonic()
+        expired = [
+            k for k, e in self._commands.items()
+            if e.done_at is not None and now - e.done_at >= self._retain_secs and k != key
+        ]
+        for k in expired:
+            self._commands.pop(k, None)
+
+
+__all__ = ["OrderProgress", "OrderProgressChannel", "ProgressStage"]
@@ -1,18 +1,69 @@
 from __future__ import annotations
 
 import asyncio
-from dataclasses import dataclass, field
+from dataclasses import asdict, dataclass, field
 from decimal import Decimal
 from pathlib import Path
-from typing import Dict, Optional
+from typing import Any, Dict, List, Optional
 
 from xbot.connector.interface import IConnector
 
-from .market_data_service import MarketDataService
-from .models import Order, OrderEvent, OrderState
-from .risk_service import RiskService
+from .market_data_service import MarketDataService, MarketState
+from .models import FINAL_STATES, Order, OrderEvent, OrderState
+from .order_expiry import OrderExpiryService
+from .order_progress import OrderProgressChannel
+from .queue_position import QueuePositionTracker
+from .rejections import RejectionTracker, classify, parse_rejection
+from .risk_service import RiskService, RiskViolationError
+from .self_trade import SelfTradeGuard
+from .arbitration import PositionArbiter
+from .maker_first import MakerFirstExecutor, MakerFirstPolicy, MakerFirstResult
 from .tracking_limit import TrackingLimitEngine, TrackingLimitOrder
+from ..core.audit import AUDIT, CANCEL_SENT, EXCHANGE_RESPONSE, ORDER_REPLAYED, ORDER_SENT, RISK_ACCEPTED, RISK_REJECTED
+from ..core.events import EventKind, EventStream
+from ..core.latency import LATENCY
+from ..core.locks import KeyedLocks, ReentrantLock
+from ..core.rate_shaper import MessagePriority, RateShapedError, RateShaper
+from ..core.metrics import METRICS
+from ..storage.base import FILLS, ORDER_EVENTS, StorageWriter
+from ..storage.recorder import FillSnapshotRecorder
 from ..utils.idgen import ClientOrderIdGenerator
+from ..utils.logging import get_logger
+
+
+# Connector/exchange-specific status strings -> OrderState
+_STATUS_MAP = {
+    "new": OrderState.OPEN,
+    "accepted": OrderState.OPEN,
+    "active": OrderState.OPEN,
+    "open": OrderState.OPEN,
+    "partially_filled": OrderState.PARTIALLY_FILLED,
+    "partial": OrderState.PARTIALLY_FILLED,
+    "filled": OrderState.FILLED,
+    "done": OrderState.FILLED,
+    "closed": OrderState.FILLED,
+    "cancel": OrderState.CANCELLED,
+    "canceled": OrderState.CANCELLED,
+    "cancelled": OrderState.CANCELLED,
+    "rejected": OrderState.FAILED,
+    "failed": OrderState.FAILED,
+    "error": OrderState.FAILED,
+    "pending": OrderState.SUBMITTING,
+    "queued": OrderState.SUBMITTING,
+    "expired": OrderState.CANCELLED,
+    "partiallyfilled": OrderState.PARTIALLY_FILLED,
+}
+
+
+def normalize_order_state(status: str) -> Optional[OrderState]:
+    """Map a venue status to OrderState; None when unrecognized.
+
+    Variants such as Lighter's ``canceled-post-only`` match on their prefix.
+    """
+    status = status.lower()
+    if status in _STATUS_MAP:
+        return _STATUS_MAP[status]
+    return _STATUS_MAP.get(status.split("-", 1)[0])
 
 
 @dataclass(slots=True)
@@ -23,10 +74,50 @@ class OrderUpdatePayload:
     info: Dict[str, object] = field(default_factory=dict)
 
 
+def fill_increase(filled: Decimal, info: Dict[str, Any]) -> Optional[Decimal]:
+    """Base quantity an update filled on top of `filled`; None when it filled nothing new.
+
+    `filled_size`/`z`/`executedQuantity` are cumulative, so an update
+    carrying one (REST polls, replays, WS fills) counts only the increase
+    over what the order had already filled. `l`, the per-fill quantity,
+    is used when no cumulative figure came with it.
+    """
+    cumulative = info.get("filled_size") or info.get("z") or info.get("executedQuantity")
+    last = info.get("l")
+    try:
+        if cumulative is not None:
+            size = Decimal(str(cumulative)) - filled
+        elif last is not None:
+            size = Decimal(str(last))
+        else:
+            return None
+    except ArithmeticError:
+        return None
+    return size if size > 0 else None
+
+
+def new_fill_size(order: Order, info: Dict[str, Any]) -> Optional[Decimal]:
+    """Quantity this update filled, advancing `order.filled`; see `fill_increase`."""
+    size = fill_increase(order.filled, info)
+    if size is not None:
+        order.filled += size
+    return size
+
+
 class UnknownOrderError(KeyError):
     pass
 
 
+class OrderNotFoundError(LookupError):
+    """The venue answered that it has no order under the client id."""
+
+
+def _not_found(data: Dict[str, Any]) -> bool:
+    if not data or (set(data) == {"raw"} and not data["raw"]):
+        return True
+    return classify(*parse_rejection(data)) == "unknown_order"
+
+
 class OrderService:
     """Centralised order flow coordination for a single venue."""
 
@@ -38,15 +129,159 @@ class OrderService:
         risk_service: RiskService,
         tracking_engine: TrackingLimitEngine,
         log_root: Path | None = None,
+        storage: Optional[StorageWriter] = None,
+        events: Optional[EventStream] = None,
     ) -> None:
         self._connector = connector
         self._market_data = market_data
         self._risk = risk_service
         self._tracking = tracking_engine
+        self._maker_first = MakerFirstExecutor(market_data=market_data)
+        self._queue: Optional[QueuePositionTracker] = None
+        self._expiry: Optional[OrderExpiryService] = None
+        self._progress: Optional[OrderProgressChannel] = None
+        self._self_trade: Optional[SelfTradeGuard] = None
+        self._arbiter: Optional[PositionArbiter] = None
+        self._shaper: Optional[RateShaper] = None
+        self._fill_snapshots: Optional[FillSnapshotRecorder] = None
+        self._rejections: Optional[RejectionTracker] = None
+        self._symbol_locks = KeyedLocks()
         self._log_root = log_root or Path("logs/orders")
+        self._storage = storage
+        self._events = events
         self._generator = ClientOrderIdGenerator()
         self._orders: Dict[int, Order] = {}
         self._lock = asyncio.Lock()
+        self._logger = get_logger(__name__)
+
+    def symbol_lock(self, symbol: str) -> ReentrantLock:
+        """Serializes order actions per symbol.
+
+        Submits and cancels each hold it, so sizing against the current
+        position cannot interleave with another action on the symbol. Hold it
+        across a sequence (e.g. cancel, wait, re-post) to make the sequence
+        atomic; it is re-entrant for the holding task.
+        """
+        return self._symbol_locks(symbol.upper())
+
+    def attach_queue_tracker(self, tracker: QueuePositionTracker) -> None:
+        """Estimate queue position for every resting limit order placed from now on."""
+        self._queue = tracker
+
+    def attach_progress(self, progress: OrderProgressChannel) -> None:
+        """Report every order update to `progress`, grouped by the order's trace_id."""
+        self._progress = progress
+
+    @property
+    def progress(self) -> Optional[OrderProgressChannel]:
+        return self._progress
+
+    def attach_self_trade(self, guard: SelfTradeGuard) -> None:
+        """Check every new or amended order against our own resting orders; use `guard.register`."""
+        self._self_trade = guard
+
+    def attach_arbiter(self, arbiter: PositionArbiter) -> None:
+        """Arbitrate every opening order between strategies; use `arbiter.register`."""
+        self._arbiter = arbiter
+
+    def attach_rate_shaper(self, shaper: RateShaper) -> None:
+        """Pace every venue message through `shaper`, cancels first; share one shaper across services.
+
+        Each action waits for its token before taking the symbol lock, so a
+        cancel is not stuck behind the lock of a new order still queued.
+        """
+        self._shaper = shaper
+
+    async def _shape(self, priority: MessagePriority) -> None:
+        if self._shaper is not None:
+            await self._shaper.acquire(self.venue, priority)
+
+    def attach_fill_snapshots(self, recorder: FillSnapshotRecorder) -> None:
+        """Persist the book and recent trades around every fill."""
+        self._fill_snapshots = recorder
+
+    def attach_rejections(self, tracker: RejectionTracker) -> None:
+        """Count every submit, amend and cancel the venue refuses, by reason."""
+        self._rejections = tracker
+
+    @property
+    def rejections(self) -> Optional[RejectionTracker]:
+        return self._rejections
+
+    def attach_expiry(self, expiry: OrderExpiryService) -> None:
+        self._expiry = expiry
+
+    def expire_after(self, order: Order, time_in_force_ms: int, *, command_id: Optional[str] = None) -> None:
+        """Cancel `order` if it is still resting after `time_in_force_ms`."""
+        if self._expiry is None:
+            raise RuntimeError("time in force needs an OrderExpiryService attached")
+        self._expiry.track(order, time_in_force_ms, command_id=command_id)
+
+    @property
+    def venue(self) -> str:
+        return self._connector.venue
+
+    @property
+    def market_data(self) -> MarketDataService:
+        return self._market_data
+
+    @property
+    def log_root(self) -> Path:
+        return self._log_root
+
+    def record_event(self, order: Order, event: OrderEvent) -> None:
+        row = {
+            "ts": event.ts,
+            "venue": order.venue,
+            "symbol": order.symbol,
+            "client_order_index": order.client_order_index,
+            "exchange_order_id": order.exchange_order_id,
+            "trace_id": order.trace_id,
+            "state": event.state.value,
+            "info": event.info,
+        }
+        size: Optional[Decimal] = None
+        # A cancel polled over REST can still carry the quantity filled before it.
+        # Counted before progress is reported, so the update carries its own fill.
+        if event.state in (OrderState.FILLED, OrderState.PARTIALLY_FILLED, OrderState.CANCELLED):
+            size = new_fill_size(order, event.info)
+        if self._events is not None:
+            self._events.emit(EventKind.ORDER_UPDATE, order.venue, row, symbol=order.symbol)
+        if self._progress is not None:
+            self._progress.on_order_event(order, event)
+        if self._storage is not None:
+            self._storage.enqueue(ORDER_EVENTS, row)
+        if size is None:
+            return
+        info = event.info
+        fill = {
+            "ts": event.ts,
+            "venue": order.venue,
+            "symbol": order.symbol,
+            "venue_symbol": self._market_data.resolve_symbol(order.symbol),
+            "client_order_index": order.client_order_index,
+            "side": "sell" if order.is_ask else "buy",
+            "price": info.get("L") or info.get("price") or info.get("p"),
+            "size": str(size),
+            "fee": info.get("n") or info.get("fee"),
+            "fee_asset": info.get("N"),
+            "maker": info.get("m"),
+            "trace_id": order.trace_id,
+        }
+        if self._events is not None:
+            self._events.emit(EventKind.FILL, order.venue, fill, symbol=order.symbol)
+        if self._storage is not None:
+            self._storage.enqueue(FILLS, fill)
+        if self._fill_snapshots is not None:
+            queue = order.queue_position
+            try:
+                self._fill_snapshots.capture(
+                    fill,
+                    venue_symbol=fill["venue_symbol"],
+                    queue=asdict(queue) if queue is not None else None,
+                )
+            except Exception as exc:
+                self._logger.info("fill_snapshot_error", extra={"symbol": order.symbol, "error": str(exc)})
 
     async def _register(self, order: Order) -> None:
         async with self._lock:
@@ -58,6 +293,162 @@ class OrderService:
                 raise UnknownOrderError(client_order_index)
             return self._orders[client_order_index]
 
+    async def _replayed(self, client_order_index: Optional[int]) -> Optional[Order]:
+        """The order already placed under this index, so a retry gets it back instead of trading again.
+
+        Failed orders are not replayed; their retry goes to the venue.
+        """
+        if client_order_index is None:
+            return None
+        async with self._lock:
+            order = self._orders.get(client_order_index)
+        if order is None or order.state == OrderState.FAILED:
+            return None
+        METRICS.inc("orders_replayed", venue=self._connector.venue)
+        AUDIT.record(
+            ORDER_REPLAYED,
+            venue=self.venue,
+            symbol=order.symbol,
+            client_order_index=client_order_index,
+            trace_id=order.trace_id,
+            state=order.state.value,
+        )
+        self._logger.info(
+            "order_replayed",
+            extra={"symbol": order.symbol, "client_order_index": client_order_index, "state": order.state.value},
+        )
+        return order
+
+    async def adopt(self, order: Order) -> None:
+        """Register an order created outside this service (e.g. restored after restart)."""
+        await self._register(order)
+
+    async def orders(self, symbol: Optional[str] = None) -> List[Order]:
+        """Every order this service has handled, final ones included."""
+        async with self._lock:
+            orders = list(self._orders.values())
+        return [o for o in orders if symbol is None or o.symbol == symbol]
+
+    async def open_orders(self, symbol: Optional[str] = None) -> List[Order]:
+        async with self._lock:
+            orders = list(self._orders.values())
+        return [
+            o for o in orders
+            if o.state not in FINAL_STATES and (symbol is None or o.symbol == symbol)
+        ]
+
+    async def _validate(
+        self, *, trace_id: Optional[str], client_order_index: Optional[int] = None, **order: object
+    ) -> None:
+        """Risk-check an order and audit the decision either way."""
+        fields = {k: v for k, v in order.items() if v is not None}
+        try:
+            await self._risk.validate_order(**order)  # type: ignore[arg-type]
+        except (RiskViolationError, ValueError) as exc:
+            AUDIT.record(
+                RISK_REJECTED,
+                venue=self.venue,
+                trace_id=trace_id,
+                client_order_index=client_order_index,
+                risk_mode=self._risk.mode.value,
+                reason=str(exc),
+                **fields,
+            )
+            raise
+        AUDIT.record(
+            RISK_ACCEPTED,
+            venue=self.venue,
+            trace_id=trace_id,
+            client_order_index=client_order_index,
+            risk_mode=self._risk.mode.value,
+            **fields,
+        )
+
+    async def _prevent_self_trade(
+        self,
+        *,
+        trace_id: Optional[str],
+        client_order_index: int,
+        symbol: str,
+        is_ask: bool,
+        price_i: Optional[int],
+        exclude: Optional[Order] = None,
+    ) -> Optional[int]:
+        """The price the order may go out at after the self-trade check; rejections are audited."""
+        if self._self_trade is None:
+            return price_i
+        try:
+            return await self._self_trade.check(
+                venue=self.venue, symbol=symbol, is_ask=is_ask, price_i=price_i, exclude=exclude
+            )
+        except RiskViolationError as exc:
+            self._audit_rejection(
+                exc,
+                trace_id=trace_id,
+                client_order_index=client_order_index,
+                symbol=symbol,
+                is_ask=is_ask,
+                price_i=price_i,
+            )
+            raise
+
+    async def _arbitrate(
+        self,
+        *,
+        trace_id: Optional[str],
+        client_order_index: int,
+        symbol: str,
+        is_ask: bool,
+        size_i: int,
+        reduce_only: int,
+    ) -> None:
+        """Check an opening order against the other strategies' exposure; rejections are audited."""
+        if self._arbiter is None or reduce_only:
+            return
+        try:
+            await self._arbiter.check(service=self, symbol=symbol, is_ask=is_ask, size_i=size_i, trace_id=trace_id)
+        except RiskViolationError as exc:
+            self._audit_rejection(
+                exc,
+                trace_id=trace_id,
+                client_order_index=client_order_index,
+                symbol=symbol,
+                is_ask=is_ask,
+                size_i=size_i,
+            )
+            raise
+
+    def _audit_rejection(
+        self, exc: Exception, *, trace_id: Optional[str], client_order_index: Optional[int], **fields: object
+    ) -> None:
+        AUDIT.record(
+            RISK_REJECTED,
+            venue=self.venue,
+            trace_id=trace_id,
+            client_order_index=client_order_index,
+            risk_mode=self._risk.mode.value,
+            reason=str(exc),
+            **{k: v for k, v in fields.items() if v is not None},
+        )
+
+    def _audit_response(self, order: Order, *, error: Optional[Exception] = None, **fields: object) -> None:
+        AUDIT.record(
+            EXCHANGE_RESPONSE,
+            venue=self.venue,
+            symbol=order.symbol,
+            client_order_index=order.client_order_index,
+            trace_id=order.trace_id,
+            ok=error is None,
+            error=str(error) if error is not None else None,
+            **fields,
+        )
+
+    def _rejected(self, order: Order, exc: Exception, action: str) -> None:
+        # Dropped by our own rate shaper or lost on the wire: the venue never answered
+        if self._rejections is None or isinstance(exc, (RateShapedError, ConnectionError, asyncio.TimeoutError)):
+            return
+        self._rejections.record(order, exc, action=action)
+
     async def submit_limit(
         self,
         *,
@@ -72,67 +463,123 @@ class OrderService:
         reduce_only: int = 0,
         trace_id: Optional[str] = None,
     ) -> Order:
-        if size_i is None and size is None:
-            raise ValueError("size_i or size must be provided")
-        if price_i is None and price is None:
-            raise ValueError("price_i or price must be provided")
-        if size_i is None:
-            size_i = await self._market_data.to_size_i(symbol, size)
-        if price_i is None:
-            price_i = await self._market_data.to_price_i(symbol, price)
-        await self._risk.validate_order(symbol=symbol, size_i=size_i, is_ask=is_ask, price_i=price_i)
-        coi = client_order_index or self._generator.next()
-        venue_symbol = self._market_data.resolve_symbol(symbol)
-        order = Order(
-            venue=self._connector.venue,
-            symbol=symbol,
-            client_order_index=coi,
-            is_ask=is_ask,
-            log_dir=self._log_root,
-            trace_id=trace_id,
-        )
-        await self._register(order)
-        await order.apply_update(
-            OrderEvent(
-                state=OrderState.SUBMITTING,
-                info={
-                    "size_i": size_i,
-                    "price_i": price_i,
-                    "is_ask": is_ask,
-                    "symbol": symbol,
-                },
+        # The token first: waiting for it under the lock would hold a cancel for the symbol behind this order
+        await self._shape(MessagePriority.REDUCE if reduce_only else MessagePriority.NEW)
+        async with self.symbol_lock(symbol):
+            if size_i is None and size is None:
+                raise ValueError("size_i or size must be provided")
+            if price_i is None and price is None:
+                raise ValueError("price_i or price must be provided")
+            replayed = await self._replayed(client_order_index)
+            if replayed is not None:
+                return replayed
+            if size_i is None:
+                size_i = await self._market_data.to_size_i(symbol, size)
+            if price_i is None:
+                price_i = await self._market_data.to_price_i(symbol, price, is_ask=is_ask)
+            if not post_only and self._market_data.market_state(symbol) == MarketState.POST_ONLY:
+                # Anything that could take would be rejected by the venue
+                post_only = True
+                METRICS.inc("orders_adapted", venue=self.venue, symbol=symbol, reason="post_only")
+                self._logger.info("order_adapted_post_only", extra={"symbol": symbol})
+            coi = client_order_index or self._generator.next()
+            price_i = await self._prevent_self_trade(
+                trace_id=trace_id, client_order_index=coi, symbol=symbol, is_ask=is_ask, price_i=price_i
             )
-        )
-        try:
-            exchange_order_id = await self._connector.submit_limit_order(
-                symbol=venue_symbol,
+            await self._arbitrate(
+                trace_id=trace_id,
                 client_order_index=coi,
-                base_amount=size_i,
-                price=price_i,
+                symbol=symbol,
                 is_ask=is_ask,
-                post_only=post_only,
+                size_i=size_i,
+                reduce_only=reduce_only,
+            )
+            await self._validate(
+                trace_id=trace_id,
+                client_order_index=coi,
+                symbol=symbol,
+                size_i=size_i,
+                is_ask=is_ask,
+                price_i=price_i,
                 reduce_only=reduce_only,
             )
-        except Exception as exc:
+            venue_symbol = self._market_data.resolve_symbol(symbol)
+            order = Order(
+                venue=self._connector.venue,
+                symbol=symbol,
+                client_order_index=coi,
+                is_ask=is_ask,
+                log_dir=self._log_root,
+                trace_id=trace_id,
+                sink=self.record_event,
+            )
+            _, size_decimals = await self._market_data.get_price_size_decimals(symbol)
+            await self._register(order)
             await order.apply_update(
                 OrderEvent(
-                    state=OrderState.FAILED,
-                    info={"error": str(exc)}
+                    state=OrderState.SUBMITTING,
+                    info={
+                        "size_i": size_i,
+                        "size_decimals": size_decimals,
+                        "price_i": price_i,
+                        "is_ask": is_ask,
+                        "symbol": symbol,
+                    },
                 )
             )
-            raise
-        await order.apply_update(
-            OrderEvent(
-                state=OrderState.OPEN,
-                info={
-                    "exchange_order_id": exchange_order_id,
-                    "size_i": size_i,
-                    "price_i": price_i,
-                },
-            ),
-            exchange_order_id=exchange_order_id,
-        )
-        return order
+            AUDIT.record(
+                ORDER_SENT,
+                venue=self.venue,
+                symbol=symbol,
+                venue_symbol=venue_symbol,
+                client_order_index=coi,
+                trace_id=trace_id,
+                order_type="limit",
+                is_ask=is_ask,
+                size_i=size_i,
+                price_i=price_i,
+                post_only=post_only,
+                reduce_only=reduce_only,
+            )
+            try:
+                with LATENCY.timed(self.venue):
+                    exchange_order_id = await self._connector.submit_limit_order(
+                        symbol=venue_symbol,
+                        client_order_index=coi,
+                        base_amount=size_i,
+                        price=price_i,
+                        is_ask=is_ask,
+                        post_only=post_only,
+                        reduce_only=reduce_only,
+                    )
+            except Exception as exc:
+                self._audit_response(order, error=exc)
+                self._rejected(order, exc, "limit")
+                await order.apply_update(
+                    OrderEvent(
+                        state=OrderState.FAILED,
+                        info={"error": str(exc)}
+                    )
+                )
+                raise
+            self._audit_response(order, exchange_order_id=exchange_order_id)
+            await order.apply_update(
+                OrderEvent(
+                    state=OrderState.OPEN,
+                    info={
+                        "exchange_order_id": exchange_order_id,
+                        "size_i": size_i,
+                        "price_i": price_i,
+                    },
+                ),
+                exchange_order_id=exchange_order_id,
+            )
+            if self._queue is not None and order.state not in FINAL_STATES:
+                try:
+                    await self._queue.track(order, price_i=price_i, size_i=size_i)
+                except Exception as exc:
+                    self._logger.info("queue_track_error", extra={"symbol": symbol, "error": str(exc)})
+            return order
 
     async def submit_market(
         self,
@@ -145,75 +592,251 @@ class OrderService:
         reduce_only: int = 0,
         trace_id: Optional[str] = None,
     ) -> Order:
-        if size_i is None and size is None:
-            raise ValueError("size_i or size must be provided")
-        if size_i is None:
-            size_i = await self._market_data.to_size_i(symbol, size)
-        await self._risk.validate_order(symbol=symbol, size_i=size_i, is_ask=is_ask)
-        coi = client_order_index or self._generator.next()
-        venue_symbol = self._market_data.resolve_symbol(symbol)
-        order = Order(
-            venue=self._connector.venue,
-            symbol=symbol,
-            client_order_index=coi,
-            is_ask=is_ask,
-            log_dir=self._log_root,
-            trace_id=trace_id,
-        )
-        await self._register(order)
-        await order.apply_update(
-            OrderEvent(
-                state=OrderState.SUBMITTING,
-                info={"size_i": size_i, "is_ask": is_ask, "symbol": symbol},
+        # The token first: waiting for it under the lock would hold a cancel for the symbol behind this order
+        await self._shape(MessagePriority.REDUCE if reduce_only else MessagePriority.NEW)
+        async with self.symbol_lock(symbol):
+            if size_i is None and size is None:
+                raise ValueError("size_i or size must be provided")
+            replayed = await self._replayed(client_order_index)
+            if replayed is not None:
+                return replayed
+            if size_i is None:
+                size_i = await self._market_data.to_size_i(symbol, size)
+            coi = client_order_index or self._generator.next()
+            await self._prevent_self_trade(
+                trace_id=trace_id, client_order_index=coi, symbol=symbol, is_ask=is_ask, price_i=None
             )
-        )
-        try:
-            exchange_order_id = await self._connector.submit_market_order(
-                symbol=venue_symbol,
+            await self._arbitrate(
+                trace_id=trace_id,
                 client_order_index=coi,
+                symbol=symbol,
+                is_ask=is_ask,
+                size_i=size_i,
+                reduce_only=reduce_only,
+            )
+            await self._validate(
+                trace_id=trace_id,
+                client_order_index=coi,
+                symbol=symbol,
                 size_i=size_i,
                 is_ask=is_ask,
                 reduce_only=reduce_only,
+                market=True,
             )
-        except Exception as exc:
+            venue_symbol = self._market_data.resolve_symbol(symbol)
+            order = Order(
+                venue=self._connector.venue,
+                symbol=symbol,
+                client_order_index=coi,
+                is_ask=is_ask,
+                log_dir=self._log_root,
+                trace_id=trace_id,
+                sink=self.record_event,
+            )
+            _, size_decimals = await self._market_data.get_price_size_decimals(symbol)
+            await self._register(order)
             await order.apply_update(
                 OrderEvent(
-                    state=OrderState.FAILED,
-                    info={"error": str(exc)},
+                    state=OrderState.SUBMITTING,
+                    info={"size_i": size_i, "size_decimals": size_decimals, "is_ask": is_ask, "symbol": symbol},
                 )
             )
-            raise
-        await order.apply_update(
-            OrderEvent(
-                state=OrderState.OPEN,
-                info={
-                    "exchange_order_id": exchange_order_id,
-                    "size_i": size_i,
-                },
-            ),
-            exchange_order_id=exchange_order_id,
-        )
-        return order
+            AUDIT.record(
+                ORDER_SENT,
+                venue=self.venue,
+                symbol=symbol,
+                venue_symbol=venue_symbol,
+                client_order_index=coi,
+                trace_id=trace_id,
+                order_type="market",
+                is_ask=is_ask,
+                size_i=size_i,
+                reduce_only=reduce_only,
+            )
+            try:
+                with LATENCY.timed(self.venue):
+                    exchange_order_id = await self._connector.submit_market_order(
+                        symbol=venue_symbol,
+                        client_order_index=coi,
+                        size_i=size_i,
+                        is_ask=is_ask,
+                        reduce_only=reduce_only,
+                    )
+            except Exception as exc:
+                self._audit_response(order, error=exc)
+                self._rejected(order, exc, "market")
+                await order.apply_update(
+                    OrderEvent(
+                        state=OrderState.FAILED,
+                        info={"error": str(exc)},
+                    )
+                )
+                raise
+            self._audit_response(order, exchange_order_id=exchange_order_id)
+            await order.apply_update(
+                OrderEvent(
+                    state=OrderState.OPEN,
+                    info={
+                        "exchange_order_id": exchange_order_id,
+                        "size_i": size_i,
+                    },
+                ),
+                exchange_order_id=exchange_order_id,
+            )
+            return order
 
     async def cancel(self, symbol: str, client_order_index: int) -> None:
-        order = await self._get(client_order_index)
-        venue_symbol = self._market_data.resolve_symbol(symbol)
-        resp: Dict[str, object]
-        if order.exchange_order_id:
-            resp = await self._connector.cancel_by_order_id(venue_symbol, order.exchange_order_id)  # type: ignore[attr-defined]
-        else:
-            resp = await self._connector.cancel_by_client_id(venue_symbol, client_order_index)
-        await order.apply_update(
-            OrderEvent(
-                state=OrderState.CANCELLED,
-                info={
-                    "symbol": symbol,
-                    "client_order_index": client_order_index,
-                    "exchange_order_id": order.exchange_order_id,
-                    "cancel_response": resp,
-                },
+        await self._shape(MessagePriority.CANCEL)
+        async with self.symbol_lock(symbol):
+            order = await self._get(client_order_index)
+            venue_symbol = self._market_data.resolve_symbol(symbol)
+            resp: Dict[str, object]
+            AUDIT.record(
+                CANCEL_SENT,
+                venue=self.venue,
+                symbol=symbol,
+                client_order_index=client_order_index,
+                trace_id=order.trace_id,
+                exchange_order_id=order.exchange_order_id,
+            )
+            try:
+                with LATENCY.timed(self.venue):
+                    if order.exchange_order_id:
+                        resp = await self._connector.cancel_by_order_id(venue_symbol, order.exchange_order_id)  # type: ignore[attr-defined]
+                    else:
+                        resp = await self._connector.cancel_by_client_id(venue_symbol, client_order_index)
+            except Exception as exc:
+                self._audit_response(order, error=exc, action="cancel")
+                self._rejected(order, exc, "cancel")
+                raise
+            self._audit_response(order, action="cancel", response=resp)
+            await order.apply_update(
+                OrderEvent(
+                    state=OrderState.CANCELLED,
+                    info={
+                        "symbol": symbol,
+                        "client_order_index": client_order_index,
+                        "exchange_order_id": order.exchange_order_id,
+                        "cancel_response": resp,
+                    },
+                )
+            )
+
+    @property
+    def supports_amend(self) -> bool:
+        """True when the venue can change a resting order's price and size in place."""
+        return hasattr(self._connector, "amend_order")
+
+    async def amend(self, symbol: str, client_order_index: int, *, size_i: int, price_i: int) -> Order:
+        """Move a resting limit order to `price_i`/`size_i` in one venue message instead of cancel and replace.
+
+        The new order is risk-checked like a fresh one. On success an update
+        in the order's current state records the new `size_i`/`price_i`.
+        """
+        if not self.supports_amend:
+            raise NotImplementedError(f"{self.venue} cannot amend orders")
+        await self._shape(MessagePriority.NEW)
+        async with self.symbol_lock(symbol):
+            order = await self._get(client_order_index)
+            if order.state in FINAL_STATES or not order.exchange_order_id:
+                raise ValueError(f"order {client_order_index} is not resting and cannot be amended")
+            price_i = await self._prevent_self_trade(
+                trace_id=order.trace_id,
+                client_order_index=client_order_index,
+                symbol=symbol,
+                is_ask=order.is_ask,
+                price_i=price_i,
+                exclude=order,
+            )
+            await self._validate(
+                trace_id=order.trace_id,
+                client_order_index=client_order_index,
+                symbol=symbol,
+                size_i=size_i,
+                is_ask=order.is_ask,
+                price_i=price_i,
+            )
+            venue_symbol = self._market_data.resolve_symbol(symbol)
+            AUDIT.record(
+                ORDER_SENT,
+                venue=self.venue,
+                symbol=symbol,
+                venue_symbol=venue_symbol,
+                client_order_index=client_order_index,
+                trace_id=order.trace_id,
+                exchange_order_id=order.exchange_order_id,
+                order_type="amend",
+                is_ask=order.is_ask,
+                size_i=size_i,
+                price_i=price_i,
+            )
+            try:
+                with LATENCY.timed(self.venue):
+                    resp = await self._connector.amend_order(  # type: ignore[attr-defined]
+                        symbol=venue_symbol, order_id=order.exchange_order_id, base_amount=size_i, price=price_i
+                    )
+                if isinstance(resp, dict) and resp.get("error"):
+                    raise RuntimeError(f"amend rejected: {resp['error']}")
+            except Exception as exc:
+                self._audit_response(order, error=exc, action="amend")
+                self._rejected(order, exc, "amend")
+                raise
+            self._audit_response(order, action="amend", response=resp)
+            await order.apply_update(
+                OrderEvent(state=order.state, info={"amended": True, "size_i": size_i, "price_i": price_i})
+            )
+            METRICS.inc("orders_amended", venue=self.venue, symbol=symbol)
+            return order
+
+    async def cancel_all(self, symbol: Optional[str] = None) -> Dict[str, int]:
+        """Cancel every resting order this service knows about; failures are counted, not raised."""
+        cancelled = failed = 0
+        for order in await self.open_orders(symbol):
+            if order.state == OrderState.SUBMITTING and not order.exchange_order_id:
+                continue
+            try:
+                await self.cancel(order.symbol, order.client_order_index)
+                cancelled += 1
+            except Exception as exc:
+                failed += 1
+                self._logger.info(
+                    "cancel_all_error",
+                    extra={"symbol": order.symbol, "client_order_index": order.client_order_index, "error": str(exc)},
+                )
+        return {"cancelled": cancelled, "failed": failed}
+
+    async def cancel_replace(
+        self,
+        symbol: str,
+        client_order_index: int,
+        *,
+        size_i: Optional[int] = None,
+        size: Optional[Decimal | float | str] = None,
+        price_i: Optional[int] = None,
+        price: Optional[Decimal | float | str] = None,
+        post_only: bool = False,
+        reduce_only: int = 0,
+        trace_id: Optional[str] = None,
+    ) -> Order:
+        """Cancel a resting order and post its replacement on the same side.
+
+        Both steps run under the symbol lock, so no other submit or flatten for
+        the symbol can land between the cancel and the new order.
+        """
+        async with self.symbol_lock(symbol):
+            old = await self._get(client_order_index)
+            await self.cancel(symbol, client_order_index)
+            return await self.submit_limit(
+                symbol=symbol,
+                is_ask=old.is_ask,
+                size_i=size_i,
+                size=size,
+                price_i=price_i,
+                price=price,
+                post_only=post_only,
+                reduce_only=reduce_only,
+                trace_id=trace_id,
             )
-        )
 
     async def place_tracking_limit(
         self,
@@ -223,7 +846,13 @@ class OrderService:
         is_ask: bool,
         **kwargs: object,
     ) -> TrackingLimitOrder:
-        await self._risk.validate_order(symbol=symbol, size_i=base_amount_i, is_ask=is_ask)
+        await self._validate(
+            trace_id=kwargs.get("trace_id"),  # type: ignore[arg-type]
+            symbol=symbol,
+            size_i=base_amount_i,
+            is_ask=is_ask,
+            reduce_only=int(kwargs.get("reduce_only") or 0),  # type: ignore[arg-type]
+        )
         return await self._tracking.place(
             order_service=self,
             connector=self._connector,
@@ -233,6 +862,34 @@ class OrderService:
             **kwargs,
         )
 
+    async def place_maker_first(
+        self,
+        *,
+        symbol: str,
+        is_ask: bool,
+        size_i: Optional[int] = None,
+        size: Optional[Decimal | float | str] = None,
+        policy: Optional[MakerFirstPolicy] = None,
+        reduce_only: int = 0,
+        trace_id: Optional[str] = None,
+        client_order_key: Optional[str] = None,
+    ) -> MakerFirstResult:
+        if size_i is None and size is None:
+            raise ValueError("size_i or size must be provided")
+        if size_i is None:
+            size_i = await self._market_data.to_size_i(symbol, size)
+        await self._validate(trace_id=trace_id, symbol=symbol, size_i=size_i, is_ask=is_ask, reduce_only=reduce_only)
+        return await self._maker_first.execute(
+            order_service=self,
+            symbol=symbol,
+            is_ask=is_ask,
+            size_i=size_i,
+            policy=policy,
+            reduce_only=reduce_only,
+            trace_id=trace_id,
+            client_order_key=client_order_key,
+        )
+
     async def ingest_update(self, payload: OrderUpdatePayload) -> Order:
         # Primary: by client_order_index
         try:
@@ -269,29 +926,11 @@ class OrderService:
         data = await self._connector.get_order(venue_symbol, client_order_index)
         state_str = (data.get("state") or data.get("status") or "").lower()
         if not state_str:
+            if _not_found(data):
+                raise OrderNotFoundError(f"{venue_symbol} order {client_order_index} not found: {data}")
             raise ValueError("connector get_order response missing state/status")
 
-        # Normalize various connector/exchange-specific status strings to our enum
-        normalized_map = {
-            "new": OrderState.OPEN,
-            "accepted": OrderState.OPEN,
-            "active": OrderState.OPEN,
-            "open": OrderState.OPEN,
-            "partially_filled": OrderState.PARTIALLY_FILLED,
-            "partial": OrderState.PARTIALLY_FILLED,
-            "filled": OrderState.FILLED,
-            "done": OrderState.FILLED,
-            "closed": OrderState.FILLED,
-            "cancel": OrderState.CANCELLED,
-            "canceled": OrderState.CANCELLED,
-            "cancelled": OrderState.CANCELLED,
-            "rejected": OrderState.FAILED,
-            "failed": OrderState.FAILED,
-            "error": OrderState.FAILED,
-            "pending": OrderState.SUBMITTING,
-            "queued": OrderState.SUBMITTING,
-        }
-        state = normalized_map.get(state_str)
+        state = normalize_order_state(state_str)
         if state is None:
             # Fallback to direct enum conversion if it already matches
             state = OrderState(state_str)
@@ -304,4 +943,12 @@ class OrderService:
         return await self.ingest_update(payload)
 
 
-__all__ = ["OrderService", "OrderUpdatePayload", "UnknownOrderError"]
+__all__ = [
+    "OrderService",
+    "OrderNotFoundError",
+    "OrderUpdatePayload",
+    "UnknownOrderError",
+    "fill_increase",
+    "new_fill_size",
+    "normalize_order_state",
+]
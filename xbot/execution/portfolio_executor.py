@@ -0,0 +1,250 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Dict, List, Optional
+
+from .market_data_service import MarketDataService
+from .models import FINAL_STATES, Order
+from .order_service import OrderService
+from .position_service import PositionService
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class TargetPosition:
+    symbol: str
+    target: Decimal
+    max_slice: Optional[Decimal] = None
+
+
+@dataclass(slots=True)
+class _Pending:
+    order: Order
+    sent_at: float  # monotonic
+    base: Decimal  # position the order was sized against
+
+
+@dataclass(slots=True)
+class DeltaOrder:
+    symbol: str
+    is_ask: bool
+    size_i: int
+    reduce_only: int
+    current: Decimal
+    target: Decimal
+
+
+class TargetPositionExecutor:
+    """Continuously converges actual positions toward desired per-symbol targets.
+
+    Each pass compares the target against `PositionService` and sends at most one
+    market order per symbol. Orders are quantized to the venue step size, skipped
+    below the minimum quantity, capped by the slice size, and flagged reduce-only
+    whenever they only shrink the position. Crossing through zero is done in two
+    legs: a reduce-only close followed by an opening order on a later pass.
+
+    Nothing re-plans a symbol while its last order is live. Once it is final,
+    the position it was sized against plus what it filled is what the next
+    pass plans from, until `PositionService` reports a position newer than
+    that fill. An order still unresolved after `pending_timeout_secs` is
+    looked up on the venue rather than assumed gone.
+    """
+
+    def __init__(
+        self,
+        *,
+        order_service: OrderService,
+        position_service: PositionService,
+        market_data: MarketDataService,
+        interval_secs: float = 5.0,
+        default_max_slice: Optional[Decimal] = None,
+        pending_timeout_secs: float = 30.0,
+        trace_prefix: str = "target",
+    ) -> None:
+        self._orders = order_service
+        self._positions = position_service
+        self._market_data = market_data
+        self._interval = interval_secs
+        self._default_max_slice = default_max_slice
+        self._pending_timeout = pending_timeout_secs
+        self._trace_prefix = trace_prefix
+        self._targets: Dict[str, TargetPosition] = {}
+        self._inflight: Dict[str, _Pending] = {}
+        self._expected: Dict[str, tuple[Decimal, float]] = {}  # symbol -> (position, wall time of the fill)
+        self._lock = asyncio.Lock()
+        self._task: Optional[asyncio.Task] = None
+        self._running = asyncio.Event()
+        self._logger = get_logger(__name__)
+
+    @property
+    def trace_prefix(self) -> str:
+        """Tags this executor's orders, `<prefix>:<symbol>`, so fills and restored orders find their way back."""
+        return self._trace_prefix
+
+    async def adopt(self, order: Order) -> None:
+        """Take back an order this executor sent before a restart; its symbol is not re-planned until it is final."""
+        snapshot = await self._positions.get_position(order.symbol)
+        held = snapshot.base_qty if snapshot else Decimal(0)
+        filled = -order.filled if order.is_ask else order.filled
+        self._inflight[order.symbol] = _Pending(order=order, sent_at=time.monotonic(), base=held - filled)
+        self._logger.info(
+            "portfolio_order_adopted",
+            extra={"symbol": order.symbol, "coi": order.client_order_index, "filled": str(order.filled)},
+        )
+
+    async def set_target(self, symbol: str, target: Decimal | float | str, *, max_slice: Optional[Decimal] = None) -> None:
+        key = symbol.upper()
+        async with self._lock:
+            self._targets[key] = TargetPosition(symbol=key, target=Decimal(str(target)), max_slice=max_slice)
+        self._logger.info("target_set", extra={"symbol": key, "target": str(target)})
+
+    async def clear_target(self, symbol: str) -> None:
+        async with self._lock:
+            self._targets.pop(symbol.upper(), None)
+
+    async def targets(self) -> Dict[str, TargetPosition]:
+        async with self._lock:
+            return dict(self._targets)
+
+    async def start(self) -> None:
+        if self._task is not None:
+            return
+        self._running.set()
+        self._task = asyncio.create_task(self._run(), name="portfolio-executor")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._running.clear()
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while self._running.is_set():
+            try:
+                await self.step()
+            except Exception:
+                self._logger.exception("portfolio_step_failed")
+            await asyncio.sleep(self._interval)
+
+    async def step(self) -> List[Order]:
+        """Run one convergence pass and return the orders submitted."""
+        submitted: List[Order] = []
+        for target in (await self.targets()).values():
+            if not await self._settled(target.symbol):
+                continue
+            # Planned and sent under the symbol lock, so the delta is sized against
+            # a position no concurrent order on the symbol is changing
+            async with self._orders.symbol_lock(target.symbol):
+                try:
+                    delta = await self.plan(target)
+                except Exception as exc:
+                    self._logger.info("portfolio_plan_error", extra={"symbol": target.symbol, "error": str(exc)})
+                    continue
+                if delta is None:
+                    continue
+                try:
+                    order = await self._orders.submit_market(
+                        symbol=delta.symbol,
+                        is_ask=delta.is_ask,
+                        size_i=delta.size_i,
+                        reduce_only=delta.reduce_only,
+                        trace_id=f"{self._trace_prefix}:{delta.symbol}",
+                    )
+                except Exception as exc:
+                    self._logger.info("portfolio_submit_error", extra={"symbol": delta.symbol, "error": str(exc)})
+                    continue
+            self._inflight[delta.symbol] = _Pending(order=order, sent_at=time.monotonic(), base=delta.current)
+            self._logger.info(
+                "portfolio_delta_submitted",
+                extra={
+                    "symbol": delta.symbol,
+                    "current": str(delta.current),
+                    "target": str(delta.target),
+                    "size_i": delta.size_i,
+                    "is_ask": delta.is_ask,
+                    "reduce_only": delta.reduce_only,
+                    "coi": order.client_order_index,
+                },
+            )
+            submitted.append(order)
+        return submitted
+
+    async def _settled(self, symbol: str) -> bool:
+        """Whether the symbol's last order is final, recording the position it left behind."""
+        pending = self._inflight.get(symbol)
+        if pending is None:
+            return True
+        order = pending.order
+        if order.state not in FINAL_STATES and time.monotonic() - pending.sent_at >= self._pending_timeout:
+            try:
+                order = await self._orders.fetch_order(symbol, order.client_order_index)
+            except Exception as exc:
+                self._logger.info(
+                    "portfolio_pending_lookup_error",
+                    extra={"symbol": symbol, "coi": order.client_order_index, "error": str(exc)},
+                )
+        if order.state not in FINAL_STATES:
+            return False
+        self._inflight.pop(symbol, None)
+        filled = -order.filled if order.is_ask else order.filled
+        self._expected[symbol] = (pending.base + filled, order.snapshot().ts)
+        self._logger.info(
+            "portfolio_delta_settled",
+            extra={
+                "symbol": symbol,
+                "coi": order.client_order_index,
+                "state": order.state.value,
+                "filled": str(order.filled),
+                "expected": str(pending.base + filled),
+            },
+        )
+        return True
+
+    async def current_position(self, symbol: str) -> Decimal:
+        """Position to plan from: our own last fill until the position feed catches up with it."""
+        snapshot = await self._positions.get_position(symbol)
+        expected = self._expected.get(symbol)
+        if expected is not None:
+            qty, as_of = expected
+            if snapshot is None or snapshot.ts <= as_of:
+                return qty
+            self._expected.pop(symbol, None)
+        return snapshot.base_qty if snapshot else Decimal(0)
+
+    async def plan(self, target: TargetPosition) -> Optional[DeltaOrder]:
+        """Compute the next delta order for a target, or None when converged."""
+        current = await self.current_position(target.symbol)
+        diff = target.target - current
+        if diff == 0:
+            return None
+        is_ask = diff < 0
+        size = abs(diff)
+        reducing = current != 0 and (current > 0) == is_ask
+        if reducing and size > abs(current):
+            # Crossing zero: close first so the reduce-only flag stays truthful
+            size = abs(current)
+        max_slice = target.max_slice if target.max_slice is not None else self._default_max_slice
+        if max_slice is not None and size > max_slice:
+            size = max_slice
+        size_i = await self._market_data.to_size_i(target.symbol, size)
+        min_size_i = await self._market_data.get_min_size_i(target.symbol)
+        if size_i <= 0 or size_i < min_size_i:
+            return None
+        return DeltaOrder(
+            symbol=target.symbol,
+            is_ask=is_ask,
+            size_i=size_i,
+            reduce_only=1 if reducing else 0,
+            current=current,
+            target=target.target,
+        )
+
+
+__all__ = ["TargetPositionExecutor", "TargetPosition", "DeltaOrder"]
@@ -0,0 +1,90 @@
+from __future__ import annotations
+
+import asyncio
+from dataclasses import dataclass, field
+from typing import Any, List, Optional, Protocol
+
+from xbot.connector.history import READ, TRADE, KeyPermissions
+from xbot.connector.interface import IConnector
+
+from ..core.alerts import AlertLevel, AlertManager
+from ..utils.logging import get_logger
+
+
+class WsAuthCheck(Protocol):
+    async def check_auth(self, timeout_secs: float = 3.0) -> Optional[Exception]:  # pragma: no cover - protocol
+        ...
+
+
+@dataclass(slots=True)
+class PreflightConfig:
+    require: List[str] = field(default_factory=lambda: [READ, TRADE])  # withdraw is added when withdrawals are on
+    ws_auth: bool = True
+    timeout_secs: float = 10.0  # per probe: the REST permission check, then the WS handshake
+
+
+class PreflightError(RuntimeError):
+    def __init__(self, venue: str, missing: List[str], details: List[str]) -> None:
+        self.venue = venue
+        self.missing = missing
+        self.details = details
+        super().__init__(f"{venue} api key is missing {', '.join(missing)}: {'; '.join(details)}")
+
+
+class PermissionPreflight:
+    """Fails startup when the API key cannot do what the bot is configured to do.
+
+    Runs once before recovery and any strategy. The connector's
+    `check_permissions(symbol)` probes each scope (read, trade, withdraw)
+    with side-effect-free signed calls, and the WS client, when given,
+    signs a private subscribe so a key the stream would reject is caught
+    now rather than discovered as silence. Every required scope that is
+    denied, plus a rejected WS handshake (reported as `ws_auth`), goes into
+    one `PreflightError`, after a CRITICAL alert. Connectors without
+    `check_permissions` are skipped with a warning.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        ws: Optional[WsAuthCheck] = None,
+        alerts: Optional[AlertManager] = None,
+        config: Optional[PreflightConfig] = None,
+    ) -> None:
+        self._connector = connector
+        self._ws = ws
+        self._alerts = alerts
+        self._config = config or PreflightConfig()
+        self._logger = get_logger(__name__)
+
+    async def run(self, symbol: str) -> Optional[KeyPermissions]:
+        venue = self._connector.venue
+        check = getattr(self._connector, "check_permissions", None)
+        if check is None:
+            self._logger.warning("preflight_unsupported", extra={"venue": venue})
+            return None
+        permissions: KeyPermissions = await asyncio.wait_for(check(symbol), self._config.timeout_secs)
+        missing = permissions.missing(self._config.require)
+        details = [f"{scope}: {permissions.errors.get(scope, 'denied')}" for scope in missing]
+        if self._ws is not None and self._config.ws_auth:
+            ws: Any = self._ws
+            error = await asyncio.wait_for(ws.check_auth(), self._config.timeout_secs + 5)
+            if error is not None:
+                missing.append("ws_auth")
+                details.append(f"ws_auth: {error}")
+        self._logger.info(
+            "preflight",
+            extra={"venue": venue, "granted": permissions.granted, "required": self._config.require, "missing": missing},
+        )
+        if missing:
+            error = PreflightError(venue, missing, details)
+            if self._alerts is not None:
+                await self._alerts.notify(
+                    AlertLevel.CRITICAL, f"{venue} api key check failed", str(error), venue=venue, missing=missing
+                )
+            raise error
+        return permissions
+
+
+__all__ = ["PermissionPreflight", "PreflightConfig", "PreflightError", "WsAuthCheck"]
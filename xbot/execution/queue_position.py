@@ -0,0 +1,180 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Dict, Optional
+
+from .market_data_service import MarketDataService
+from .models import FINAL_STATES, Order
+from ..core.cache import MarketCache
+from ..core.events import Event, EventKind, EventsLagged, EventStream
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class QueuePosition:
+    price: float
+    ahead: float  # estimated size queued before us at our price
+    own: float  # our estimated remaining size
+    level: Optional[float] = None  # last observed size of the whole level, when a depth feed shows it
+    ts: float = 0.0
+
+    @property
+    def behind(self) -> Optional[float]:
+        if self.level is None:
+            return None
+        return max(0.0, self.level - self.ahead - self.own)
+
+
+@dataclass(slots=True)
+class _Tracked:
+    order: Order
+    venue_symbol: str
+    position: QueuePosition
+    pending_traded: float = 0.0  # printed at our price but not yet seen leaving the level
+
+
+class QueuePositionTracker:
+    """Estimates how much size is queued ahead of each resting limit order.
+
+    At placement, everything resting at our price counts as ahead. The level
+    comes from `market_data.get_order_book`, so it may already include our
+    own order; the estimate errs towards more size ahead. After that:
+
+    - A trade print at our price consumes the queue front. Size beyond what
+      is ahead is our own fill.
+    - A print through our price empties the queue ahead.
+    - When a streamed book (`cache.books`) shows the level shrinking by more
+      than the printed size, the rest counts as cancellations, spread
+      proportionally over the size ahead and behind us.
+    - Growth of the level joins behind us.
+
+    The estimate is kept on `order.queue_position` and dropped once the order
+    reaches a final state.
+    """
+
+    def __init__(self, *, market_data: MarketDataService, cache: MarketCache, events: EventStream) -> None:
+        self._market_data = market_data
+        self._cache = cache
+        self._events = events
+        self._tracked: Dict[int, _Tracked] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def position(self, client_order_index: int) -> Optional[QueuePosition]:
+        entry = self._tracked.get(client_order_index)
+        return entry.position if entry else None
+
+    async def track(self, order: Order, *, price_i: int, size_i: int) -> QueuePosition:
+        price_decimals, size_decimals = await self._market_data.get_price_size_decimals(order.symbol)
+        price = float(Decimal(price_i) / (Decimal(10) ** price_decimals))
+        own = float(Decimal(size_i) / (Decimal(10) ** size_decimals))
+        venue_symbol = self._market_data.resolve_symbol(order.symbol)
+        level: Optional[float] = None
+        try:
+            book = await self._market_data.get_order_book(order.symbol)
+        except Exception as exc:
+            self._logger.info("queue_book_unavailable", extra={"symbol": order.symbol, "error": str(exc)})
+            book = None
+        if book is not None:
+            level = (book.asks if order.is_ask else book.bids).get(price, 0.0)
+        position = QueuePosition(price=price, ahead=level or 0.0, own=own, level=level, ts=time.time())
+        self._tracked[order.client_order_index] = _Tracked(order=order, venue_symbol=venue_symbol, position=position)
+        order.queue_position = position
+        return position
+
+    def untrack(self, client_order_index: int) -> None:
+        self._tracked.pop(client_order_index, None)
+
+    def on_trade(self, venue_symbol: str, price: float, size: float) -> None:
+        for entry in self._live(venue_symbol):
+            pos = entry.position
+            through = price > pos.price if entry.order.is_ask else price < pos.price
+            if through:
+                pos.ahead = 0.0
+            elif price == pos.price:
+                consumed = min(size, pos.ahead)
+                pos.ahead -= consumed
+                pos.own = max(0.0, pos.own - (size - consumed))
+                entry.pending_traded += size
+            else:
+                continue
+            pos.ts = time.time()
+
+    def on_level(self, venue_symbol: str) -> None:
+        book = self._cache.books.get(venue_symbol)
+        if book is None:
+            return
+        for entry in self._live(venue_symbol):
+            pos = entry.position
+            level = (book.asks if entry.order.is_ask else book.bids).get(pos.price, 0.0)
+            if pos.level is not None and level < pos.level:
+                shrink = pos.level - level
+                traded = min(shrink, entry.pending_traded)
+                entry.pending_traded -= traded
+                cancelled = shrink - traded
+                others = max(0.0, pos.level - pos.own)
+                if cancelled > 0 and others > 0:
+                    pos.ahead -= cancelled * min(1.0, pos.ahead / others)
+            # Capped by the whole level: our own order may not be visible in it yet
+            pos.ahead = max(0.0, min(pos.ahead, level))
+            pos.level = level
+            pos.ts = time.time()
+
+    def resync(self) -> None:
+        """Re-anchor every tracked order on the current book after events were missed."""
+        for venue_symbol in {entry.venue_symbol for entry in self._tracked.values()}:
+            self.on_level(venue_symbol)
+
+    def _live(self, venue_symbol: str) -> list[_Tracked]:
+        done = [coi for coi, entry in self._tracked.items() if entry.order.state in FINAL_STATES]
+        for coi in done:
+            self._tracked.pop(coi, None)
+        return [entry for entry in self._tracked.values() if entry.venue_symbol == venue_symbol]
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="queue-position")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        sub = self._events.subscribe(
+            kinds=[EventKind.MARKET_DATA, EventKind.TRADE], name="queue-position", report_lag=True
+        )
+        try:
+            while True:
+                try:
+                    event = await sub.get()
+                except EventsLagged as exc:
+                    self._logger.info("queue_events_lagged", extra={"missed": exc.missed})
+                    self.resync()
+                    continue
+                if not self._tracked or event.symbol is None:
+                    continue
+                try:
+                    self._apply(event)
+                except Exception as exc:
+                    self._logger.info("queue_update_error", extra={"symbol": event.symbol, "error": str(exc)})
+        finally:
+            sub.close()
+
+    def _apply(self, event: Event) -> None:
+        if event.kind == EventKind.TRADE:
+            price, size = event.data.get("p"), event.data.get("q")
+            if price is not None and size is not None:
+                self.on_trade(event.symbol or "", float(price), float(size))
+        else:
+            self.on_level(event.symbol or "")
+
+
+__all__ = ["QueuePosition", "QueuePositionTracker"]
@@ -0,0 +1,219 @@
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional, Sequence, Tuple, TYPE_CHECKING
+
+from .models import FINAL_STATES, Order
+from .order_expiry import filled_size_i
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .router import ExecutionRouter
+
+
+@dataclass(slots=True, frozen=True)
+class QuoteLevel:
+    """One order a quoting strategy wants resting."""
+
+    is_ask: bool
+    price_i: int
+    size_i: int
+
+
+@dataclass(slots=True)
+class RestingQuote:
+    order: Order
+    is_ask: bool
+    price_i: int
+    size_i: int  # as placed or last amended
+    filled_at_amend_i: int = 0  # fills before the last amend, which the amended size no longer includes
+
+    @property
+    def remaining_i(self) -> int:
+        return max(self.size_i - (filled_size_i(self.order) - self.filled_at_amend_i), 0)
+
+    def level(self) -> QuoteLevel:
+        return QuoteLevel(self.is_ask, self.price_i, self.remaining_i)
+
+
+@dataclass(slots=True)
+class QuoteDiff:
+    keep: List[RestingQuote] = field(default_factory=list)
+    cancels: List[RestingQuote] = field(default_factory=list)
+    amends: List[Tuple[RestingQuote, QuoteLevel]] = field(default_factory=list)
+    places: List[QuoteLevel] = field(default_factory=list)
+
+    @property
+    def messages(self) -> int:
+        return len(self.cancels) + len(self.amends) + len(self.places)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"keep": len(self.keep), "cancels": len(self.cancels), "amends": len(self.amends), "places": len(self.places)}
+
+
+def _matches(resting: QuoteLevel, desired: QuoteLevel, price_tolerance_i: int, size_tolerance_i: int) -> bool:
+    return (
+        abs(resting.price_i - desired.price_i) <= price_tolerance_i
+        and abs(resting.size_i - desired.size_i) <= size_tolerance_i
+    )
+
+
+def diff_quotes(
+    desired: Sequence[QuoteLevel],
+    resting: Sequence[RestingQuote],
+    *,
+    price_tolerance_i: int = 0,
+    size_tolerance_i: int = 0,
+    amend: bool = True,
+) -> QuoteDiff:
+    """The fewest venue messages that turn `resting` into `desired`, side by side.
+
+    A resting order within the tolerances of a desired level is kept as is.
+    The leftovers are paired best price first on each side: with `amend`,
+    each pair becomes one amend; without it, a cancel and a place. Whatever
+    has no partner is cancelled or placed. Levels with no size are ignored.
+    """
+    diff = QuoteDiff()
+    for is_ask in (False, True):
+        wanted = sorted((d for d in desired if d.is_ask == is_ask and d.size_i > 0), key=lambda d: d.price_i, reverse=not is_ask)
+        current = sorted((r for r in resting if r.is_ask == is_ask), key=lambda r: r.price_i, reverse=not is_ask)
+        unmatched: List[QuoteLevel] = []
+        for level in wanted:
+            match = next(
+                (r for r in current if _matches(r.level(), level, price_tolerance_i, size_tolerance_i)), None
+            )
+            if match is None:
+                unmatched.append(level)
+                continue
+            current.remove(match)
+            diff.keep.append(match)
+        if amend:
+            while unmatched and current:
+                diff.amends.append((current.pop(0), unmatched.pop(0)))
+        diff.cancels.extend(current)
+        diff.places.extend(unmatched)
+    return diff
+
+
+class QuoteManager:
+    """Declarative quoting: a strategy states the levels it wants and only the difference is sent.
+
+    `update(desired)` drops orders that have finished, diffs the rest
+    against `desired` (`diff_quotes`) and sends cancels first, so the book
+    never holds both the old and the new quote, then amends, then post-only
+    places. Amends are used when the venue supports them
+    (`OrderService.supports_amend`); a failed amend falls back to cancel
+    and place. A level whose old quote could not be cancelled is not
+    placed; the next update tries again. Compared with cancelling and re-placing every level on each
+    update, the saving shows in the `quote_messages_saved` metric.
+    """
+
+    def __init__(
+        self,
+        *,
+        router: "ExecutionRouter",
+        symbol: str,
+        trace_prefix: str = "quotes",
+        price_tolerance_i: int = 0,
+        size_tolerance_i: int = 0,
+        post_only: bool = True,
+    ) -> None:
+        self._router = router
+        self._symbol = symbol
+        self._trace_prefix = trace_prefix
+        self._price_tolerance_i = price_tolerance_i
+        self._size_tolerance_i = size_tolerance_i
+        self._post_only = post_only
+        self._resting: Dict[int, RestingQuote] = {}
+        self._logger = get_logger(__name__)
+
+    @property
+    def resting(self) -> List[RestingQuote]:
+        return list(self._resting.values())
+
+    def _prune(self) -> None:
+        for coi, quote in list(self._resting.items()):
+            if quote.order.state in FINAL_STATES or quote.remaining_i <= 0:
+                del self._resting[coi]
+
+    def _trace_id(self, trace_id: Optional[str]) -> str:
+        return trace_id or f"{self._trace_prefix}:{len(self._resting)}"
+
+    async def update(self, desired: Sequence[QuoteLevel], *, trace_id: Optional[str] = None) -> QuoteDiff:
+        self._prune()
+        diff = diff_quotes(
+            desired,
+            self.resting,
+            price_tolerance_i=self._price_tolerance_i,
+            size_tolerance_i=self._size_tolerance_i,
+            amend=self._router.orders.supports_amend,
+        )
+        # A quote whose cancel failed may still rest, so its side places one level fewer
+        uncancelled = {False: 0, True: 0}
+        for quote in diff.cancels:
+            if not await self._cancel(quote):
+                uncancelled[quote.is_ask] += 1
+        for quote, level in diff.amends:
+            if not await self._amend(quote, level) and await self._cancel(quote):
+                await self._place(level, trace_id)
+        for level in diff.places:
+            if uncancelled[level.is_ask]:
+                uncancelled[level.is_ask] -= 1
+                self._logger.info("quote_place_skipped", extra={"symbol": self._symbol, "price_i": level.price_i})
+                continue
+            await self._place(level, trace_id)
+        venue = self._router.orders.venue
+        # what cancelling every resting order and placing every level would have cost
+        naive = len(diff.keep) + len(diff.cancels) + len(diff.amends) + len([d for d in desired if d.size_i > 0])
+        METRICS.inc("quote_messages", diff.messages, venue=venue, symbol=self._symbol)
+        METRICS.inc("quote_messages_saved", max(naive - diff.messages, 0), venue=venue, symbol=self._symbol)
+        if diff.messages:
+            self._logger.info("quotes_updated", extra={"symbol": self._symbol, **diff.to_dict()})
+        return diff
+
+    async def _cancel(self, quote: RestingQuote) -> bool:
+        coi = quote.order.client_order_index
+        try:
+            await self._router.cancel(self._symbol, coi)
+        except Exception as exc:
+            self._logger.info("quote_cancel_error", extra={"symbol": self._symbol, "client_order_index": coi, "error": str(exc)})
+            return False
+        self._resting.pop(coi, None)
+        return True
+
+    async def _amend(self, quote: RestingQuote, level: QuoteLevel) -> bool:
+        coi = quote.order.client_order_index
+        try:
+            await self._router.amend(symbol=self._symbol, client_order_index=coi, size_i=level.size_i, price_i=level.price_i)
+        except Exception as exc:
+            self._logger.info("quote_amend_error", extra={"symbol": self._symbol, "client_order_index": coi, "error": str(exc)})
+            return False
+        quote.filled_at_amend_i = filled_size_i(quote.order)
+        quote.price_i, quote.size_i = level.price_i, level.size_i
+        return True
+
+    async def _place(self, level: QuoteLevel, trace_id: Optional[str]) -> None:
+        try:
+            order = await self._router.submit_limit(
+                symbol=self._symbol,
+                is_ask=level.is_ask,
+                size_i=level.size_i,
+                price_i=level.price_i,
+                post_only=self._post_only,
+                trace_id=self._trace_id(trace_id),
+            )
+        except Exception as exc:
+            self._logger.info("quote_place_error", extra={"symbol": self._symbol, "price_i": level.price_i, "error": str(exc)})
+            return
+        if order.state not in FINAL_STATES:
+            self._resting[order.client_order_index] = RestingQuote(order, level.is_ask, level.price_i, level.size_i)
+
+    async def cancel_all(self) -> int:
+        self._prune()
+        for quote in self.resting:
+            await self._cancel(quote)
+        return len(self._resting)
+
+
+__all__ = ["QuoteDiff", "QuoteLevel", "QuoteManager", "RestingQuote", "diff_quotes"]
@@ -0,0 +1,221 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Dict, List, Optional
+
+from xbot.connector.interface import IConnector
+
+from .market_data_service import MarketDataService
+from .position_service import PositionService, PositionSnapshot
+from .recovery import _position_qty
+from .risk_service import RiskMode, RiskService
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.backoff import AdaptiveInterval, BackoffConfig
+from ..core.events import EventKind, EventsLagged, EventStream
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class ReconcileConfig:
+    interval_secs: float = 30.0
+    tolerance: Decimal = Decimal("0")  # absolute base-quantity difference tolerated per symbol
+    confirm_cycles: int = 2  # consecutive diverged cycles before alerting; absorbs fills still in flight
+    halt: bool = False  # also switch the risk layer to HALTED on divergence
+    symbols: List[str] = field(default_factory=list)  # canonical symbols to check; empty checks every known one
+
+
+@dataclass(slots=True)
+class Divergence:
+    symbol: str
+    virtual: Decimal
+    exchange: Decimal
+
+    @property
+    def diff(self) -> Decimal:
+        return self.exchange - self.virtual
+
+
+class PositionReconciler:
+    """Compares positions implied by our own fills with what the venue reports.
+
+    The virtual book starts from the venue positions on the first cycle and
+    then moves only with FILL events. Every `interval_secs` the venue
+    positions are fetched (and fed to `PositionService`); a symbol whose
+    difference exceeds `tolerance` for `confirm_cycles` cycles in a row raises
+    a CRITICAL alert and, with `halt`, switches the risk layer to HALTED. The
+    alert fires once per divergence; `resync()` adopts the venue positions as
+    the new baseline after an operator has checked them.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        market_data: MarketDataService,
+        positions: PositionService,
+        events: EventStream,
+        risk: RiskService,
+        alerts: AlertManager,
+        config: Optional[ReconcileConfig] = None,
+        backoff: Optional[BackoffConfig] = None,
+    ) -> None:
+        self._connector = connector
+        self._market_data = market_data
+        self._positions = positions
+        self._events = events
+        self._risk = risk
+        self._alerts = alerts
+        self._config = config or ReconcileConfig()
+        self._interval = AdaptiveInterval(connector.venue, "reconcile", self._config.interval_secs, backoff)
+        self._virtual: Dict[str, Decimal] = {}
+        self._strikes: Dict[str, int] = {}
+        self._alerted: set[str] = set()
+        self._seeded = False
+        self._tasks: List[asyncio.Task] = []
+        self._logger = get_logger(__name__)
+
+    def virtual(self, symbol: str) -> Decimal:
+        return self._virtual.get(symbol, Decimal(0))
+
+    def on_fill(self, symbol: str, side: str, size: Decimal) -> None:
+        signed = -size if side == "sell" else size
+        self._virtual[symbol] = self._virtual.get(symbol, Decimal(0)) + signed
+
+    async def exchange_positions(self) -> Dict[str, Decimal]:
+        positions: Dict[str, Decimal] = {}
+        raw_positions = await self._connector.get_positions()
+        self._events.state.mark_fresh(self._connector.venue, "positions")
+        for raw in raw_positions:
+            venue_symbol = raw.get("symbol")
+            qty = _position_qty(raw)
+            if not venue_symbol or qty is None:
+                continue
+            symbol = self._market_data.canonical_for(str(venue_symbol))
+            if symbol is None:
+                continue
+            positions[symbol] = qty
+            notional = Decimal(str(raw.get("netExposureNotional") or 0))
+            await self._positions.ingest(
+                PositionSnapshot(symbol=symbol, base_qty=qty, quote_value=notional, notional=notional, raw=raw)
+            )
+        # Venues list open positions only, so a position closed since the last cycle just disappears
+        configured = self._market_data.symbols()
+        for snapshot in await self._positions.all_positions():
+            if snapshot.symbol in positions or snapshot.symbol not in configured or not snapshot.base_qty:
+                continue
+            positions[snapshot.symbol] = Decimal(0)
+            await self._positions.ingest(
+                PositionSnapshot(symbol=snapshot.symbol, base_qty=Decimal(0), quote_value=Decimal(0), notional=Decimal(0))
+            )
+        return positions
+
+    async def resync(self) -> Dict[str, Decimal]:
+        """Adopt the venue positions as the virtual baseline and clear any divergence."""
+        exchange = await self.exchange_positions()
+        self._virtual = dict(exchange)
+        self._strikes.clear()
+        self._alerted.clear()
+        self._seeded = True
+        self._logger.info("position_resync", extra={"positions": {k: str(v) for k, v in exchange.items()}})
+        return exchange
+
+    async def check(self) -> List[Divergence]:
+        """Run one cycle; returns the symbols currently outside tolerance."""
+        if not self._seeded:
+            await self.resync()
+            return []
+        exchange = await self.exchange_positions()
+        symbols = self._config.symbols or sorted(set(exchange) | set(self._virtual))
+        diverged: List[Divergence] = []
+        venue = self._connector.venue
+        for symbol in symbols:
+            item = Divergence(symbol, self.virtual(symbol), exchange.get(symbol, Decimal(0)))
+            METRICS.set("position_divergence", float(item.diff), venue=venue, symbol=symbol)
+            if abs(item.diff) <= self._config.tolerance:
+                if symbol in self._alerted:
+                    self._logger.info("position_reconciled", extra={"symbol": symbol, "qty": str(item.exchange)})
+                self._strikes.pop(symbol, None)
+                self._alerted.discard(symbol)
+                continue
+            diverged.append(item)
+            self._strikes[symbol] = self._strikes.get(symbol, 0) + 1
+            if self._strikes[symbol] >= self._config.confirm_cycles and symbol not in self._alerted:
+                await self._raise(item)
+        return diverged
+
+    async def _raise(self, item: Divergence) -> None:
+        self._alerted.add(item.symbol)
+        venue = self._connector.venue
+        detail = f"{item.symbol}: tracked {item.virtual}, {venue} reports {item.exchange} (diff {item.diff})"
+        METRICS.inc("position_divergence_alerts", venue=venue, symbol=item.symbol)
+        self._logger.warning(
+            "position_divergence",
+            extra={"symbol": item.symbol, "virtual": str(item.virtual), "exchange": str(item.exchange), "diff": str(item.diff)},
+        )
+        if self._config.halt:
+            self._risk.set_mode(RiskMode.HALTED, f"position divergence {detail}")
+        await self._alerts.notify(
+            AlertLevel.CRITICAL,
+            "position divergence",
+            detail,
+            venue=venue,
+            halted=self._config.halt,
+        )
+
+    async def start(self) -> None:
+        if not self._tasks:
+            self._tasks = [
+                asyncio.create_task(self._consume_fills(), name="reconcile-fills"),
+                asyncio.create_task(self._run(), name="reconcile"),
+            ]
+
+    async def stop(self) -> None:
+        for task in self._tasks:
+            task.cancel()
+            with contextlib.suppress(asyncio.CancelledError):
+                await task
+        self._tasks = []
+
+    async def _consume_fills(self) -> None:
+        sub = self._events.subscribe(kinds=[EventKind.FILL], name="reconcile", report_lag=True)
+        try:
+            while True:
+                try:
+                    event = await sub.get()
+                except EventsLagged as exc:
+                    # Missed fills would surface as a false divergence; rebase on the venue instead
+                    self._logger.warning("reconcile_fills_lagged", extra={"missed": exc.missed})
+                    await self._resync_after_lag()
+                    continue
+                try:
+                    self.on_fill(str(event.data["symbol"]), str(event.data.get("side")), Decimal(str(event.data["size"])))
+                except Exception as exc:
+                    self._logger.info("reconcile_fill_error", extra={"error": str(exc), "fill": event.data})
+        finally:
+            sub.close()
+
+    async def _resync_after_lag(self) -> None:
+        try:
+            await self.resync()
+        except Exception as exc:
+            # The next cycle seeds again from the venue
+            self._seeded = False
+            self._logger.info("reconcile_resync_error", extra={"error": str(exc)})
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.check()
+            except Exception as exc:
+                self._logger.info("reconcile_error", extra={"error": str(exc)})
+                self._interval.failure(exc)
+            else:
+                self._interval.success()
+            await self._interval.sleep()
+
+
+__all__ = ["Divergence", "PositionReconciler", "ReconcileConfig"]
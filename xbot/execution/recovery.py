@@ -0,0 +1,335 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import json
+from dataclasses import dataclass, field
+from decimal import Decimal
+from pathlib import Path
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Tuple
+
+from xbot.connector.history import OrderHistory
+from xbot.connector.interface import IConnector
+
+from .market_data_service import MarketDataService
+from .models import FINAL_STATES, Order, OrderEvent, OrderState
+from .order_service import OrderNotFoundError, OrderService, new_fill_size, normalize_order_state
+from .position_service import PositionService, PositionSnapshot
+from ..utils.logging import get_logger
+
+ReattachHandler = Callable[[Order], Awaitable[None]]
+
+
+@dataclass(slots=True)
+class PersistedOrder:
+    symbol: str
+    client_order_index: int
+    is_ask: bool
+    trace_id: Optional[str]
+    exchange_order_id: Optional[str]
+    events: List[OrderEvent]
+
+    @property
+    def state(self) -> OrderState:
+        return self.events[-1].state if self.events else OrderState.SUBMITTING
+
+
+@dataclass(slots=True)
+class RecoveryReport:
+    restored: List[int] = field(default_factory=list)
+    closed_while_down: List[int] = field(default_factory=list)
+    reattached: List[int] = field(default_factory=list)
+    unresolved: List[int] = field(default_factory=list)  # venue lookup failed; kept open and retried
+    positions: Dict[str, Decimal] = field(default_factory=dict)
+    errors: List[str] = field(default_factory=list)
+
+
+def load_persisted_orders(log_root: Path, venue: str) -> List[PersistedOrder]:
+    """Read `<venue>-<symbol>-<coi>.jsonl` order logs and return each order's history."""
+    if not log_root.exists():
+        return []
+    prefix = f"{venue}-"
+    orders: List[PersistedOrder] = []
+    for path in sorted(log_root.glob(f"{prefix}*.jsonl")):
+        try:
+            symbol, coi_raw = path.stem[len(prefix):].rsplit("-", 1)
+            coi = int(coi_raw)
+        except ValueError:
+            continue
+        events: List[OrderEvent] = []
+        trace_id: Optional[str] = None
+        exchange_order_id: Optional[str] = None
+        is_ask = False
+        for line in path.read_text(encoding="utf-8").splitlines():
+            try:
+                row = json.loads(line)
+                state = OrderState(row["state"])
+            except Exception:
+                continue
+            info = row.get("info") or {}
+            events.append(OrderEvent(state=state, ts=float(row.get("ts") or 0.0), info=info))
+            trace_id = row.get("trace_id") or trace_id
+            exchange_order_id = row.get("exchange_order_id") or exchange_order_id
+            if "is_ask" in info:
+                is_ask = bool(info["is_ask"])
+        if events:
+            orders.append(
+                PersistedOrder(
+                    symbol=symbol,
+                    client_order_index=coi,
+                    is_ask=is_ask,
+                    trace_id=trace_id,
+                    exchange_order_id=exchange_order_id,
+                    events=events,
+                )
+            )
+    return orders
+
+
+def _position_qty(raw: Dict[str, Any]) -> Optional[Decimal]:
+    for key in ("netQuantity", "net_size", "position", "quantity", "q"):
+        value = raw.get(key)
+        if value is None:
+            continue
+        try:
+            return Decimal(str(value))
+        except Exception:
+            continue
+    return None
+
+
+class RecoveryService:
+    """Restores in-flight orders and positions after a restart, before strategies run.
+
+    Non-final orders found in the order logs are re-registered with `OrderService`,
+    reconciled against the venue REST state, and handed to any manager registered
+    for their `trace_id` prefix (e.g. ``"oco:"``) so it can resume supervising them;
+    managers registered with `closed` also get the ones that finished meanwhile.
+    Orders the venue reports as not found are looked up in the venue order history
+    to learn whether they filled or were cancelled while the bot was down. A lookup
+    that fails (timeout, 5xx, rate limit) proves nothing, so the order stays open and
+    supervised, and is looked up again every `retry_secs` until the venue answers.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        order_service: OrderService,
+        position_service: PositionService,
+        market_data: MarketDataService,
+        history_limit: int = 1000,
+        retry_secs: float = 5.0,
+    ) -> None:
+        self._connector = connector
+        self._orders = order_service
+        self._positions = position_service
+        self._market_data = market_data
+        self._handlers: Dict[str, Tuple[ReattachHandler, bool]] = {}
+        self._history_limit = history_limit
+        self._history: Dict[str, Optional[OrderHistory]] = {}
+        self._retry_secs = retry_secs
+        self._unresolved: Dict[int, Tuple[PersistedOrder, Order]] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def register_reattach(self, trace_prefix: str, handler: ReattachHandler, *, closed: bool = False) -> None:
+        """Hand restored orders traced `trace_prefix...` to `handler`.
+
+        With `closed`, orders that finished while the bot was down are handed
+        over too, for managers that act on how a sibling order ended.
+        """
+        self._handlers[trace_prefix] = (handler, closed)
+
+    @property
+    def unresolved(self) -> List[int]:
+        """Restored orders the venue has not yet answered for."""
+        return list(self._unresolved)
+
+    async def run(self) -> RecoveryReport:
+        report = RecoveryReport()
+        # Positions first, so a manager resuming an order sizes it against the venue's position
+        await self._restore_positions(report)
+        await self._restore_orders(report)
+        self._logger.info(
+            "recovery_done",
+            extra={
+                "venue": self._connector.venue,
+                "restored": report.restored,
+                "closed_while_down": report.closed_while_down,
+                "reattached": report.reattached,
+                "unresolved": report.unresolved,
+                "positions": {k: str(v) for k, v in report.positions.items()},
+                "errors": report.errors,
+            },
+        )
+        if self._unresolved and self._task is None:
+            self._task = asyncio.create_task(self._retry_loop(), name="recovery-retry")
+        return report
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _retry_loop(self) -> None:
+        while self._unresolved:
+            await asyncio.sleep(self._retry_secs)
+            try:
+                await self.retry_unresolved()
+            except Exception:
+                self._logger.exception("recovery_retry_failed")
+
+    async def retry_unresolved(self) -> RecoveryReport:
+        """Look up again the restored orders the venue could not answer for."""
+        report = RecoveryReport()
+        self._history.clear()
+        for entry, order in list(self._unresolved.values()):
+            if order.state in FINAL_STATES:
+                # The order's own updates settled it in the meantime
+                self._unresolved.pop(entry.client_order_index, None)
+                continue
+            await self._resolve(entry, order, report)
+        self._logger.info(
+            "recovery_retry",
+            extra={
+                "venue": self._connector.venue,
+                "restored": report.restored,
+                "closed_while_down": report.closed_while_down,
+                "unresolved": report.unresolved,
+                "errors": report.errors,
+            },
+        )
+        return report
+
+    async def _restore_orders(self, report: RecoveryReport) -> None:
+        persisted = load_persisted_orders(self._orders.log_root, self._connector.venue)
+        for entry in persisted:
+            if entry.state in FINAL_STATES:
+                continue
+            order = Order(
+                venue=self._connector.venue,
+                symbol=entry.symbol,
+                client_order_index=entry.client_order_index,
+                is_ask=entry.is_ask,
+                log_dir=self._orders.log_root,
+                trace_id=entry.trace_id,
+                sink=self._orders.record_event,
+            )
+            order.restore(entry.events, exchange_order_id=entry.exchange_order_id)
+            for event in entry.events:
+                # Fills recorded before the restart, so the venue's cumulative figure only adds what is new
+                if event.state in (OrderState.FILLED, OrderState.PARTIALLY_FILLED):
+                    new_fill_size(order, event.info)
+            await self._orders.adopt(order)
+            await self._resolve(entry, order, report)
+            # Unresolved ones too: until the venue says otherwise they may be working
+            await self._reattach(order, report)
+
+    async def _resolve(self, entry: PersistedOrder, order: Order, report: RecoveryReport) -> None:
+        """Reconcile a restored order with the venue; only a definite answer changes its state."""
+        coi = entry.client_order_index
+        try:
+            await self._orders.fetch_order(entry.symbol, coi)
+        except OrderNotFoundError as exc:
+            # Not open on the venue anymore: it filled or was cancelled while we were down
+            event = await self._closed_event(entry, str(exc), report)
+            if event is None:
+                self._unresolve(entry, order, report)
+                return
+            await order.apply_update(event)
+        except Exception as exc:
+            # Timeout, 5xx, rate limit: says nothing about the order
+            report.errors.append(f"order {coi}: {exc}")
+            self._unresolve(entry, order, report)
+            return
+        self._unresolved.pop(coi, None)
+        if order.state in FINAL_STATES:
+            report.closed_while_down.append(coi)
+        else:
+            report.restored.append(coi)
+
+    def _unresolve(self, entry: PersistedOrder, order: Order, report: RecoveryReport) -> None:
+        self._unresolved[entry.client_order_index] = (entry, order)
+        report.unresolved.append(entry.client_order_index)
+
+    async def _closed_event(self, entry: PersistedOrder, error: str, report: RecoveryReport) -> Optional[OrderEvent]:
+        """How an order no longer open ended; None when the order history could not be read."""
+        try:
+            history = await self._history_for(entry.symbol)
+        except Exception as exc:
+            report.errors.append(f"order history {entry.symbol}: {exc}")
+            return None
+        found = history.find(client_order_index=entry.client_order_index, order_id=entry.exchange_order_id) if history else None
+        state = normalize_order_state(found.status) if found else None
+        if found is None or state not in FINAL_STATES:
+            return OrderEvent(state=OrderState.CANCELLED, info={"recovery": "missing_on_exchange", "error": error})
+        info: Dict[str, Any] = {
+            "recovery": "order_history",
+            "status": found.status,
+            "order_id": found.order_id,
+            # Cumulative, so OrderService records only what filled after the last fill it saw
+            "executedQuantity": str(found.filled_quantity),
+        }
+        if found.price is not None:
+            info["price"] = str(found.price)
+        return OrderEvent(state=state, info=info)
+
+    async def _history_for(self, symbol: str) -> Optional[OrderHistory]:
+        """The venue's recent orders on `symbol`, None when it keeps no history; failures raise and are not cached."""
+        if symbol in self._history:
+            return self._history[symbol]
+        history: Optional[OrderHistory] = None
+        try:
+            venue_symbol = self._market_data.resolve_symbol(symbol)
+            history = await self._connector.get_order_history(venue_symbol, limit=self._history_limit)
+        except NotImplementedError:
+            pass
+        self._history[symbol] = history
+        return history
+
+    async def _reattach(self, order: Order, report: RecoveryReport) -> None:
+        if not order.trace_id:
+            return
+        for prefix, (handler, closed) in self._handlers.items():
+            if not order.trace_id.startswith(prefix):
+                continue
+            if order.state in FINAL_STATES and not closed:
+                return
+            try:
+                await handler(order)
+                report.reattached.append(order.client_order_index)
+            except Exception as exc:
+                report.errors.append(f"reattach {order.client_order_index}: {exc}")
+            return
+
+    async def _restore_positions(self, report: RecoveryReport) -> None:
+        try:
+            positions = await self._connector.get_positions()
+        except Exception as exc:
+            report.errors.append(f"positions: {exc}")
+            return
+        for raw in positions:
+            venue_symbol = raw.get("symbol")
+            qty = _position_qty(raw)
+            if not venue_symbol or qty is None:
+                continue
+            symbol = self._market_data.canonical_for(str(venue_symbol))
+            if symbol is None:
+                continue
+            await self._positions.ingest(
+                PositionSnapshot(
+                    symbol=symbol,
+                    base_qty=qty,
+                    quote_value=Decimal(str(raw.get("netExposureNotional") or 0)),
+                    notional=Decimal(str(raw.get("netExposureNotional") or 0)),
+                    raw=raw,
+                )
+            )
+            report.positions[symbol] = qty
+
+
+__all__ = ["RecoveryService", "RecoveryReport", "PersistedOrder", "load_persisted_orders"]
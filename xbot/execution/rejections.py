@@ -0,0 +1,150 @@
+from __future__ import annotations
+
+import re
+import time
+from collections import Counter, deque
+from dataclasses import dataclass, field
+from typing import Any, Deque, Dict, List, Optional, Tuple
+
+from .arbitration import strategy_of
+from .models import Order
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+# Checked in order; the first category with a matching marker wins
+CATEGORIES: List[Tuple[str, Tuple[str, ...]]] = [
+    ("rate_limit", ("rate limit", "too many requests", "429", "throttl")),
+    ("auth", ("unauthorized", "forbidden", "signature", "api key", "permission")),
+    ("insufficient_margin", ("insufficient", "margin", "balance", "not enough", "funds")),
+    ("post_only", ("post only", "post-only", "would cross", "immediately match", "would take")),
+    ("reduce_only", ("reduce only", "reduce-only", "would increase")),
+    ("tick_size", ("tick", "price decimal", "price precision", "invalid price")),
+    ("lot_size", ("step size", "lot size", "quantity decimal", "quantity precision", "invalid quantity")),
+    ("min_size", ("minimum", "too small", "min notional", "min quantity")),
+    ("max_size", ("maximum", "too large", "exceeds max")),
+    ("price_band", ("price band", "out of range", "deviat", "too far")),
+    ("market_closed", ("market closed", "not open", "halted", "suspended", "maintenance")),
+    ("unknown_order", ("not found", "unknown order", "does not exist")),
+    ("timeout", ("timeout", "timed out")),
+]
+OTHER = "other"
+
+_CODE = re.compile(r"""["']?code["']?\s*[:=]\s*["']?([\w.-]+)""", re.IGNORECASE)
+_MESSAGE = re.compile(r"""["']?(?:message|msg)["']?\s*[:=]\s*["']([^"']*)["']""", re.IGNORECASE)
+
+
+def parse_rejection(error: Any) -> Tuple[str, str]:
+    """(code, message) from a venue error, e.g. `limit order failed: {'code': ..., 'message': ...}`."""
+    if isinstance(error, dict):
+        return str(error.get("code") or ""), str(error.get("message") or error.get("msg") or "")
+    text = str(error)
+    code, message = _CODE.search(text), _MESSAGE.search(text)
+    if message is not None:
+        return (code.group(1) if code else ""), message.group(1)
+    # No structured payload: drop the "<action> failed: " prefix the connectors add
+    head, sep, tail = text.partition(": ")
+    prefixed = sep and ("failed" in head or "rejected" in head)
+    return (code.group(1) if code else ""), (tail if prefixed else text)
+
+
+def classify(code: str, message: str) -> str:
+    text = f"{code} {message}".replace("_", " ").lower()
+    for category, markers in CATEGORIES:
+        if any(marker in text for marker in markers):
+            return category
+    return OTHER
+
+
+@dataclass(slots=True)
+class Rejection:
+    venue: str
+    symbol: str
+    action: str  # limit | market | amend | cancel
+    category: str
+    code: str
+    message: str
+    strategy: str
+    client_order_index: Optional[int] = None
+    ts: float = field(default_factory=time.time)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "venue": self.venue,
+            "symbol": self.symbol,
+            "action": self.action,
+            "category": self.category,
+            "code": self.code,
+            "message": self.message,
+            "strategy": self.strategy,
+            "client_order_index": self.client_order_index,
+            "ts": self.ts,
+        }
+
+
+class RejectionTracker:
+    """Counts venue rejections by reason and keeps the latest `size` of them.
+
+    `OrderService` reports every submit, amend and cancel the venue refused.
+    The error is parsed into a code and message (`parse_rejection`) and
+    sorted into a category such as `tick_size` or `insufficient_margin`
+    (`classify`), so a systematic problem shows up as one growing counter
+    rather than scattered log lines. Counts run since the process started.
+    """
+
+    def __init__(self, *, size: int = 200) -> None:
+        self._recent: Deque[Rejection] = deque(maxlen=size)
+        self._by_category: Counter[str] = Counter()
+        self._by_code: Counter[str] = Counter()
+        self._by_symbol: Counter[Tuple[str, str]] = Counter()  # (symbol, category)
+        self._by_strategy: Counter[Tuple[str, str]] = Counter()  # (strategy, category)
+        self._logger = get_logger(__name__)
+
+    def record(self, order: Order, error: Any, *, action: str) -> Rejection:
+        code, message = parse_rejection(error)
+        rejection = Rejection(
+            venue=order.venue,
+            symbol=order.symbol,
+            action=action,
+            category=classify(code, message),
+            code=code,
+            message=message,
+            strategy=strategy_of(order),
+            client_order_index=order.client_order_index,
+        )
+        self._recent.append(rejection)
+        self._by_category[rejection.category] += 1
+        self._by_code[code or rejection.category] += 1
+        self._by_symbol[(rejection.symbol, rejection.category)] += 1
+        self._by_strategy[(rejection.strategy, rejection.category)] += 1
+        METRICS.inc(
+            "order_rejections", venue=rejection.venue, symbol=rejection.symbol, action=action, category=rejection.category
+        )
+        self._logger.info("order_rejected", extra=rejection.to_dict())
+        return rejection
+
+    def recent(self, limit: Optional[int] = None, *, category: Optional[str] = None) -> List[Rejection]:
+        """Newest first."""
+        found = [r for r in reversed(self._recent) if category is None or r.category == category]
+        return found[:limit] if limit is not None else found
+
+    def counts(self) -> Dict[str, int]:
+        return dict(self._by_category.most_common())
+
+    def snapshot(self, *, limit: int = 50, category: Optional[str] = None) -> Dict[str, Any]:
+        def nested(counter: Counter[Tuple[str, str]]) -> Dict[str, Dict[str, int]]:
+            grouped: Dict[str, Dict[str, int]] = {}
+            for (key, name), count in counter.most_common():
+                grouped.setdefault(key, {})[name] = count
+            return grouped
+
+        return {
+            "total": sum(self._by_category.values()),
+            "by_category": self.counts(),
+            "by_code": dict(self._by_code.most_common()),
+            "by_symbol": nested(self._by_symbol),
+            "by_strategy": nested(self._by_strategy),
+            "recent": [r.to_dict() for r in self.recent(limit, category=category)],
+        }
+
+
+__all__ = ["CATEGORIES", "OTHER", "Rejection", "RejectionTracker", "classify", "parse_rejection"]
@@ -1,21 +1,61 @@
 from __future__ import annotations
 
-from dataclasses import dataclass
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
 from decimal import Decimal
-from typing import Optional
+from enum import Enum
+from fnmatch import fnmatchcase
+from typing import List, Mapping, Optional, Protocol, Sequence
 
-from .market_data_service import MarketDataService
+from .fx import CurrencyConverter
+from .market_data_service import MarketDataService, MarketState
 from .position_service import PositionService
+from .trading_windows import TradingWindow, blocking_window
 
 
 class RiskViolationError(Exception):
     """Raised when requested action would violate a risk constraint."""
 
 
+class RiskMode(str, Enum):
+    NORMAL = "normal"
+    REDUCE_ONLY = "reduce_only"
+    HALTED = "halted"
+
+
 @dataclass(slots=True)
 class RiskLimits:
-    max_position: Optional[Decimal] = None
-    max_notional: Optional[Decimal] = None
+    max_position: Optional[Decimal] = None  # in contracts, as the venue sizes orders
+    max_notional: Optional[Decimal] = None  # quote currency (reporting currency with fx), contract multiplier applied
+    max_drawdown_pct: Optional[Decimal] = None
+    max_impact_bps: Optional[Decimal] = None  # market orders only, estimated from book depth
+    max_correlated_notional: Optional[Decimal] = None  # net notional across correlated symbols, needs correlations
+
+
+@dataclass(slots=True)
+class SymbolLists:
+    """Symbols orders may open positions on; entries are canonical or venue symbols, `*` globs allowed."""
+
+    allow: List[str] = field(default_factory=list)  # empty allows every symbol not denied
+    deny: List[str] = field(default_factory=list)  # wins over allow
+
+    def __post_init__(self) -> None:
+        self.allow = [entry.upper() for entry in self.allow]
+        self.deny = [entry.upper() for entry in self.deny]
+
+    def blocked(self, *names: str) -> Optional[str]:
+        """Why an order on a symbol known by `names` is refused; None when it is permitted."""
+        keys = [name.upper() for name in names if name]
+        if any(fnmatchcase(key, entry) for key in keys for entry in self.deny):
+            return "denied"
+        if self.allow and not any(fnmatchcase(key, entry) for key in keys for entry in self.allow):
+            return "not allowed"
+        return None
+
+
+class CorrelationSource(Protocol):
+    def correlated(self, symbol: str) -> Mapping[str, float]: ...
 
 
 class RiskService:
@@ -25,10 +65,182 @@ class RiskService:
         market_data: MarketDataService,
         position_service: PositionService,
         limits: Optional[RiskLimits] = None,
+        fx: Optional[CurrencyConverter] = None,
+        symbols: Optional[SymbolLists] = None,
+        windows: Sequence[TradingWindow] = (),
     ) -> None:
         self._market_data = market_data
         self._position_service = position_service
         self._limits = limits or RiskLimits()
+        self._symbols = symbols or SymbolLists()
+        self._windows = list(windows)
+        self._fx = fx
+        self._correlations: Optional[CorrelationSource] = None
+        self._mode = RiskMode.NORMAL
+        self._mode_reason = ""
+
+    @property
+    def limits(self) -> RiskLimits:
+        return self._limits
+
+    def set_limits(self, limits: RiskLimits) -> None:
+        self._limits = limits
+
+    @property
+    def symbol_lists(self) -> SymbolLists:
+        return self._symbols
+
+    def set_symbol_lists(self, symbols: SymbolLists) -> None:
+        self._symbols = symbols
+
+    @property
+    def windows(self) -> List[TradingWindow]:
+        return list(self._windows)
+
+    def blocking_window(self, symbol: str, now: Optional[float] = None) -> Optional[TradingWindow]:
+        """The no-trade window `symbol` is in right now, if any."""
+        found = blocking_window(self._windows, (symbol, self._market_data.resolve_symbol(symbol)), now)
+        return found[0] if found else None
+
+    def attach_correlations(self, source: Optional[CorrelationSource]) -> None:
+        self._correlations = source
+
+    @property
+    def mode(self) -> RiskMode:
+        return self._mode
+
+    @property
+    def mode_reason(self) -> str:
+        return self._mode_reason
+
+    def set_mode(self, mode: RiskMode, reason: str = "") -> None:
+        self._mode = mode
+        self._mode_reason = reason
+
+    async def _is_reducing(self, symbol: str, size: Decimal, is_ask: bool) -> bool:
+        existing = await self._position_service.get_position(symbol)
+        net_base = existing.base_qty if existing else Decimal(0)
+        future_base = net_base - size if is_ask else net_base + size
+        return abs(future_base) < abs(net_base) and (future_base == 0 or (future_base > 0) == (net_base > 0))
+
+    async def _check_symbol_lists(self, symbol: str, size: Decimal, is_ask: bool, *, reduce_only: int) -> None:
+        # Positions left on a symbol that was denied since can still be closed
+        reason = self._symbols.blocked(symbol, self._market_data.resolve_symbol(symbol))
+        if reason is not None and not reduce_only and not await self._is_reducing(symbol, size, is_ask):
+            raise RiskViolationError(f"symbol {symbol} is {reason} by the symbol lists")
+
+    async def _check_windows(self, symbol: str, size: Decimal, is_ask: bool, *, reduce_only: int) -> None:
+        found = blocking_window(self._windows, (symbol, self._market_data.resolve_symbol(symbol)), time.time())
+        if found is None or reduce_only or await self._is_reducing(symbol, size, is_ask):
+            return
+        window, until = found
+        reopens = datetime.fromtimestamp(until, tz=timezone.utc).strftime("%H:%M:%SZ")
+        raise RiskViolationError(f"no-trade window {window.name} on {symbol}: opening orders refused until {reopens}")
+
+    async def _check_market_state(
+        self, symbol: str, size: Decimal, is_ask: bool, *, reduce_only: int, market: bool
+    ) -> None:
+        # Post-only books are handled by OrderService turning limits into post-only orders
+        state = self._market_data.market_state(symbol)
+        if state == MarketState.CLOSED:
+            raise RiskViolationError(f"market {symbol} is closed for new orders")
+        if market and state in (MarketState.POST_ONLY, MarketState.LIMIT_ONLY):
+            raise RiskViolationError(f"market {symbol} is {state.value}; market orders are not accepted")
+        if state == MarketState.REDUCE_ONLY and not reduce_only:
+            if not await self._is_reducing(symbol, size, is_ask):
+                raise RiskViolationError(f"market {symbol} is reduce-only; opening orders are not accepted")
+
+    async def _check_impact(self, symbol: str, size: Decimal, is_ask: bool) -> None:
+        limit = self._limits.max_impact_bps
+        try:
+            estimate = await self._market_data.slippage_estimate(symbol, is_ask, size)
+        except Exception as exc:
+            raise RiskViolationError(f"unable to estimate slippage for {symbol}: {exc}") from exc
+        if estimate is None or estimate.impact_bps is None:
+            raise RiskViolationError(f"no order book to estimate slippage for {symbol}")
+        if not estimate.complete:
+            raise RiskViolationError(
+                f"visible depth {estimate.filled} cannot absorb market order of {size} on {symbol}"
+            )
+        if Decimal(str(estimate.impact_bps)) > limit:
+            raise RiskViolationError(
+                f"estimated impact {estimate.impact_bps:.1f} bps (avg {estimate.avg_price}) exceeds limit {limit} bps"
+            )
+
+    async def max_order_size(self, symbol: str, *, is_ask: bool) -> Optional[Decimal]:
+        """Largest order, in contracts, the position and notional limits accept right now; None when uncapped.
+
+        Sizing code caps its suggestions with this; `validate_order` remains the check of record.
+        """
+        caps: List[Decimal] = []
+        if self._limits.max_position is not None:
+            existing = await self._position_service.get_position(symbol)
+            net_base = existing.base_qty if existing else Decimal(0)
+            headroom = self._limits.max_position + net_base if is_ask else self._limits.max_position - net_base
+            caps.append(max(Decimal(0), headroom))
+        if self._limits.max_notional is not None:
+            bid_i, ask_i, scale = await self._market_data.get_top_of_book(symbol)
+            reference = bid_i if is_ask else ask_i
+            if not reference:
+                return Decimal(0)
+            instrument = self._market_data.instrument(symbol)
+            per_contract = instrument.notional(Decimal(1), Decimal(reference) / Decimal(scale))
+            if self._fx is not None:
+                per_contract = self._fx.convert(per_contract, instrument.quote) if instrument.quote else None
+            if not per_contract:
+                return Decimal(0)
+            caps.append(self._limits.max_notional / per_contract)
+        return min(caps) if caps else None
+
+    def _reporting(self, notional: Decimal, symbol: str) -> Decimal:
+        if self._fx is None:
+            return notional
+        # Limits are set in the reporting currency; fail closed when the quote cannot be valued
+        quote = self._market_data.instrument(symbol).quote
+        converted = self._fx.convert(notional, quote) if quote else None
+        if converted is None:
+            raise RiskViolationError(
+                f"no rate from {quote or symbol} to {self._fx.reporting_currency} for notional check"
+            )
+        return converted
+
+    async def _order_notional(
+        self, symbol: str, size: Decimal, is_ask: bool, price_i: Optional[int], price_decimals: int
+    ) -> Decimal:
+        if price_i is None:
+            bid_i, ask_i, _scale = await self._market_data.get_top_of_book(symbol)
+            reference = ask_i if not is_ask else bid_i
+            if reference is None:
+                raise RiskViolationError("unable to determine reference price for notional risk check")
+            price_i = reference
+        price = Decimal(price_i) / (Decimal(10) ** price_decimals)
+        return self._reporting(self._market_data.instrument(symbol).notional(size, price), symbol)
+
+    async def _check_correlated(
+        self, symbol: str, size: Decimal, is_ask: bool, price_i: Optional[int], price_decimals: int
+    ) -> None:
+        """Net notional over symbols correlated with `symbol`, each weighted by its coefficient.
+
+        A long in one symbol and a long in a symbol moving with it add up; a
+        hedge in a negatively correlated one offsets. Orders that shrink the
+        correlated exposure always pass.
+        """
+        assert self._correlations is not None and self._limits.max_correlated_notional is not None
+        weights = self._correlations.correlated(symbol)
+        before = Decimal(0)
+        for position in await self._position_service.all_positions():
+            rho = weights.get(position.symbol.upper())
+            if rho is None or not position.base_qty:
+                continue
+            signed = abs(position.notional) if position.base_qty > 0 else -abs(position.notional)
+            before += Decimal(str(rho)) * self._reporting(signed, position.symbol)
+        order = await self._order_notional(symbol, size, is_ask, price_i, price_decimals)
+        after = before - order if is_ask else before + order
+        if abs(after) > self._limits.max_correlated_notional and abs(after) > abs(before):
+            raise RiskViolationError(
+                f"correlated notional {after:.2f} across {', '.join(sorted(weights))} "
+                f"exceeds limit {self._limits.max_correlated_notional}"
+            )
 
     async def validate_order(
         self,
@@ -37,12 +249,32 @@ class RiskService:
         size_i: int,
         is_ask: bool,
         price_i: Optional[int] = None,
+        reduce_only: int = 0,
+        market: bool = False,
     ) -> None:
         await self._market_data.ensure_min_size(symbol, size_i)
-        if self._limits.max_position is None and self._limits.max_notional is None:
-            return
+        if self._mode == RiskMode.HALTED:
+            raise RiskViolationError(f"trading halted: {self._mode_reason or 'no reason given'}")
         price_decimals, size_decimals = await self._market_data.get_price_size_decimals(symbol)
         size = Decimal(size_i) / (Decimal(10) ** size_decimals)
+        await self._check_symbol_lists(symbol, size, is_ask, reduce_only=reduce_only)
+        if self._windows:
+            await self._check_windows(symbol, size, is_ask, reduce_only=reduce_only)
+        await self._check_market_state(symbol, size, is_ask, reduce_only=reduce_only, market=market)
+        if self._market_data.is_stale(symbol) and not reduce_only:
+            if not await self._is_reducing(symbol, size, is_ask):
+                raise RiskViolationError(f"market data for {symbol} is stale; only reducing orders allowed")
+        if self._mode == RiskMode.REDUCE_ONLY and not reduce_only:
+            if not await self._is_reducing(symbol, size, is_ask):
+                raise RiskViolationError(
+                    f"reduce-only mode ({self._mode_reason or 'no reason given'}) blocks opening order on {symbol}"
+                )
+        if market and self._limits.max_impact_bps is not None and not reduce_only:
+            await self._check_impact(symbol, size, is_ask)
+        if self._limits.max_correlated_notional is not None and self._correlations is not None and not reduce_only:
+            await self._check_correlated(symbol, size, is_ask, price_i, price_decimals)
+        if self._limits.max_position is None and self._limits.max_notional is None:
+            return
         if self._limits.max_position is not None:
             existing = await self._position_service.get_position(symbol)
             net_base = existing.base_qty if existing else Decimal(0)
@@ -52,18 +284,11 @@ class RiskService:
                     f"net base {future_base} exceeds limit {self._limits.max_position} for {symbol}"
                 )
         if self._limits.max_notional is not None:
-            if price_i is None:
-                bid_i, ask_i, _scale = await self._market_data.get_top_of_book(symbol)
-                reference = ask_i if not is_ask else bid_i
-                if reference is None:
-                    raise RiskViolationError("unable to determine reference price for notional risk check")
-                price_i = reference
-            price = Decimal(price_i) / (Decimal(10) ** price_decimals)
-            notional = price * size
+            notional = await self._order_notional(symbol, size, is_ask, price_i, price_decimals)
             if notional > self._limits.max_notional:
                 raise RiskViolationError(
                     f"order notional {notional} exceeds limit {self._limits.max_notional}"
                 )
 
 
-__all__ = ["RiskService", "RiskLimits", "RiskViolationError"]
+__all__ = ["CorrelationSource", "RiskService", "RiskLimits", "RiskMode", "RiskViolationError", "SymbolLists"]
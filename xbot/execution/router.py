@@ -1,14 +1,20 @@
 from __future__ import annotations
 
-from typing import Optional
+import time
+from typing import Any, Awaitable, Callable, Dict, Optional
 
+from .decisions import DecisionLog
+from .iceberg import IcebergManager
+from .oco import OcoManager
 from .order_service import OrderService
 from .position_service import PositionService
 from .risk_service import RiskService
+from .maker_first import MakerFirstResult
 from .tracking_limit import TrackingLimitOrder
 from .models import Order
 from .market_data_service import MarketDataService
 from ..core.cache import MarketCache
+from ..core.events import EventStream
 
 
 class ExecutionRouter:
@@ -28,6 +34,15 @@ class ExecutionRouter:
         self._risk = risk_service
         self._market_data = market_data
         self._cache = cache
+        self._decisions: Optional[DecisionLog] = None
+        self._variant = ""
+        self._oco = OcoManager(router=self)
+        self._iceberg = IcebergManager(router=self)
+
+    def attach_decisions(self, decisions: DecisionLog, variant: str) -> None:
+        """Record every order this router is asked for under `variant` (see `DecisionLog`)."""
+        self._decisions = decisions
+        self._variant = variant
 
     @property
     def risk(self) -> RiskService:
@@ -49,21 +64,77 @@ class ExecutionRouter:
     def cache(self) -> MarketCache | None:
         return self._cache
 
+    @property
+    def oco(self) -> OcoManager:
+        return self._oco
+
+    @property
+    def iceberg(self) -> IcebergManager:
+        return self._iceberg
+
+    @property
+    def events(self) -> EventStream | None:
+        """Unified update stream; `router.events.subscribe(...)` yields ordered `Event`s."""
+        return self._cache.events if self._cache is not None else None
+
     async def submit_limit(self, **kwargs) -> Order:
-        return await self._orders.submit_limit(**kwargs)
+        return await self._decide("submit_limit", kwargs, self._orders.submit_limit)
 
     async def submit_market(self, **kwargs) -> Order:
-        return await self._orders.submit_market(**kwargs)
+        return await self._decide("submit_market", kwargs, self._orders.submit_market)
+
+    async def maker_first(self, **kwargs) -> MakerFirstResult:
+        return await self._decide("maker_first", kwargs, self._orders.place_maker_first)
 
     async def cancel(self, symbol: str, client_order_index: int) -> None:
         await self._orders.cancel(symbol, client_order_index)
 
+    async def amend(self, **kwargs) -> Order:
+        return await self._decide("amend", kwargs, self._orders.amend)
+
     async def tracking_limit(self, **kwargs) -> TrackingLimitOrder:
-        return await self._orders.place_tracking_limit(**kwargs)
+        return await self._decide("tracking_limit", kwargs, self._orders.place_tracking_limit)
+
+    async def _decide(self, action: str, kwargs: Dict[str, Any], call: Callable[..., Awaitable[Any]]) -> Any:
+        if self._decisions is None:
+            return await call(**kwargs)
+        ts = time.time()
+        try:
+            result = await call(**kwargs)
+        except Exception as exc:
+            self._decisions.record(self._variant, action, kwargs, f"error: {exc}", ts=ts)
+            raise
+        self._decisions.record(self._variant, action, kwargs, "sent", ts=ts)
+        return result
 
     async def fetch_order(self, symbol: str, client_order_index: int) -> object:
         return await self._orders.fetch_order(symbol, client_order_index)
 
+    async def flatten_all(self) -> Dict[str, Any]:
+        """Cancel every resting order, then close each open position with a reduce-only market order.
+
+        A position that fails to close is reported, not raised, so one bad
+        symbol does not leave the others open.
+        """
+        cancelled = await self._orders.cancel_all()
+        closed: Dict[str, str] = {}
+        errors: Dict[str, str] = {}
+        for position in await self._positions.all_positions():
+            if position.base_qty == 0:
+                continue
+            try:
+                await self._orders.submit_market(
+                    symbol=position.symbol,
+                    is_ask=position.base_qty > 0,
+                    size=abs(position.base_qty),
+                    reduce_only=1,
+                    trace_id="flatten_all",
+                )
+                closed[position.symbol] = str(position.base_qty)
+            except Exception as exc:
+                errors[position.symbol] = str(exc)
+        return {"cancel": cancelled, "closed": closed, "errors": errors}
+
     async def fetch_margin(self) -> dict:
         # Access underlying connector for margin snapshot when available
         return await self._orders._connector.get_margin()  # type: ignore[attr-defined]
@@ -0,0 +1,230 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Dict, List, Optional
+
+from xbot.analytics.funding_table import annualize
+from xbot.connector.interface import IConnector
+
+from .market_data_service import MarketDataService
+from .watchlist import Watchlist
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+_BPS = Decimal(10_000)
+
+
+@dataclass(slots=True)
+class ScreenerConfig:
+    interval_secs: float = 3600.0
+    market_types: List[str] = field(default_factory=lambda: ["PERP"])
+    min_volume_24h: Decimal = Decimal(0)  # quote
+    min_open_interest: Decimal = Decimal(0)  # quote notional, open interest x last price
+    max_spread_bps: Optional[Decimal] = None  # top of book, from a depth snapshot
+    min_funding_apr: Optional[Decimal] = None  # annualized current funding bounds, inclusive
+    max_funding_apr: Optional[Decimal] = None
+    max_symbols: Optional[int] = None  # keep the highest-volume passers
+
+
+@dataclass(slots=True)
+class ScreenResult:
+    venue_symbol: str
+    quote_volume: Optional[Decimal] = None
+    open_interest: Optional[Decimal] = None  # quote notional
+    spread_bps: Optional[Decimal] = None
+    funding_apr: Optional[Decimal] = None
+    reason: str = ""  # first filter failed; empty when selected
+
+    @property
+    def selected(self) -> bool:
+        return not self.reason
+
+    def to_dict(self) -> Dict[str, Any]:
+        def text(value: Optional[Decimal]) -> Optional[str]:
+            return str(value) if value is not None else None
+
+        return {
+            "venue_symbol": self.venue_symbol,
+            "quote_volume": text(self.quote_volume),
+            "open_interest": text(self.open_interest),
+            "spread_bps": text(round(self.spread_bps, 2) if self.spread_bps is not None else None),
+            "funding_apr": text(self.funding_apr),
+            "selected": self.selected,
+            "reason": self.reason,
+        }
+
+
+class UniverseScreener:
+    """Picks the markets worth streaming and keeps the watchlist on them.
+
+    Every `interval_secs` it screens each listed market of the configured
+    types: 24h quote volume (`get_tickers`), open interest notional
+    (`get_open_interest`), annualized current funding (`get_current_funding`)
+    and, for the markets still in, the top-of-book spread from a depth
+    snapshot. A filter whose venue data is missing for a market fails it.
+    With `max_symbols`, the highest-volume passers are kept. Selected
+    markets join the `Watchlist`; markets the screener added and that no
+    longer pass are removed. Symbols watched for other reasons (the
+    configured symbol, listings, the admin API) are never removed.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        market_data: MarketDataService,
+        watchlist: Watchlist,
+        config: Optional[ScreenerConfig] = None,
+    ) -> None:
+        if not hasattr(connector, "market_symbols") or not hasattr(connector, "get_tickers"):
+            raise ValueError(f"universe screening is not supported on {connector.venue}")
+        self._connector = connector
+        self._market_data = market_data
+        self._watchlist = watchlist
+        self._config = config or ScreenerConfig()
+        self._managed: Dict[str, str] = {}  # canonical -> venue symbol, added by the screener
+        self._results: List[ScreenResult] = []
+        self._updated: Optional[float] = None
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> ScreenerConfig:
+        return self._config
+
+    @property
+    def managed(self) -> Dict[str, str]:
+        return dict(self._managed)
+
+    def _market_type(self, venue_symbol: str) -> str:
+        getter = getattr(self._connector, "market_info", None)
+        info = getter(venue_symbol) if getter is not None else None
+        return str((info or {}).get("marketType") or "SPOT").upper()
+
+    async def _venue_data(self) -> tuple[Dict[str, Any], Dict[str, Decimal], Dict[str, Decimal]]:
+        connector: Any = self._connector
+        tickers = {ticker.symbol: ticker for ticker in await connector.get_tickers()}
+        open_interest: Dict[str, Decimal] = {}
+        if self._config.min_open_interest and hasattr(connector, "get_open_interest"):
+            open_interest = await connector.get_open_interest()
+        funding: Dict[str, Decimal] = {}
+        bounded = self._config.min_funding_apr is not None or self._config.max_funding_apr is not None
+        if bounded and hasattr(connector, "get_current_funding"):
+            for entry in await connector.get_current_funding():
+                if entry.venue == connector.venue:
+                    funding[entry.symbol] = annualize(entry.rate, entry.interval_hours)
+        return tickers, open_interest, funding
+
+    async def _spread_bps(self, venue_symbol: str) -> Optional[Decimal]:
+        book = await self._connector.get_order_book(venue_symbol)
+        if book.best_bid is None or book.best_ask is None or not book.mid:
+            return None
+        return (Decimal(str(book.best_ask)) - Decimal(str(book.best_bid))) / Decimal(str(book.mid)) * _BPS
+
+    async def screen(self) -> List[ScreenResult]:
+        """Screen every listed market once, without touching the watchlist."""
+        config, connector = self._config, self._connector
+        listed: Any = getattr(connector, "market_symbols")
+        tickers, open_interest, funding = await self._venue_data()
+        types = [t.upper() for t in config.market_types]
+        results: List[ScreenResult] = []
+        for venue_symbol in sorted(listed()):
+            if types and self._market_type(venue_symbol) not in types:
+                continue
+            ticker = tickers.get(venue_symbol)
+            result = ScreenResult(venue_symbol, quote_volume=ticker.quote_volume if ticker is not None else None)
+            if venue_symbol in open_interest and ticker is not None and ticker.last is not None:
+                result.open_interest = open_interest[venue_symbol] * ticker.last
+            result.funding_apr = funding.get(venue_symbol)
+            results.append(result)
+            if result.quote_volume is None or result.quote_volume < config.min_volume_24h:
+                result.reason = "volume"
+            elif config.min_open_interest and (result.open_interest or 0) < config.min_open_interest:
+                result.reason = "open_interest"
+            elif not self._funding_ok(result.funding_apr):
+                result.reason = "funding"
+            elif config.max_spread_bps is not None:
+                try:
+                    result.spread_bps = await self._spread_bps(venue_symbol)
+                except Exception as exc:
+                    self._logger.info("screener_depth_error", extra={"venue_symbol": venue_symbol, "error": str(exc)})
+                if result.spread_bps is None or result.spread_bps > config.max_spread_bps:
+                    result.reason = "spread"
+        if config.max_symbols is not None:
+            passed = sorted((r for r in results if r.selected), key=lambda r: r.quote_volume or 0, reverse=True)
+            for result in passed[config.max_symbols :]:
+                result.reason = "max_symbols"
+        return results
+
+    def _funding_ok(self, apr: Optional[Decimal]) -> bool:
+        low, high = self._config.min_funding_apr, self._config.max_funding_apr
+        if low is None and high is None:
+            return True
+        if apr is None:
+            return False
+        return (low is None or apr >= low) and (high is None or apr <= high)
+
+    async def refresh(self, now: Optional[float] = None) -> List[ScreenResult]:
+        """Screen, then move the watchlist onto the selection; returns every market's result."""
+        results = await self.screen()
+        selected = {r.venue_symbol for r in results if r.selected}
+        added: List[str] = []
+        removed: List[str] = []
+        for symbol, venue_symbol in list(self._managed.items()):
+            if venue_symbol not in selected:
+                await self._watchlist.remove(symbol)
+                self._managed.pop(symbol)
+                removed.append(symbol)
+        watched = set(self._watchlist.symbols().values())
+        for venue_symbol in sorted(selected - watched):
+            symbol = self._market_data.canonical_for(venue_symbol) or venue_symbol.upper()
+            try:
+                await self._watchlist.add(symbol, venue_symbol)
+            except Exception as exc:
+                self._logger.info("screener_subscribe_error", extra={"venue_symbol": venue_symbol, "error": str(exc)})
+                continue
+            self._managed[symbol] = venue_symbol
+            added.append(symbol)
+        self._results = results
+        self._updated = time.time() if now is None else now
+        venue = self._connector.venue
+        METRICS.set("universe_symbols", len(selected), venue=venue)
+        self._logger.info(
+            "universe_screened",
+            extra={"venue": venue, "screened": len(results), "selected": len(selected), "added": added, "removed": removed},
+        )
+        return results
+
+    def snapshot(self) -> Dict[str, Any]:
+        return {
+            "updated": self._updated,
+            "managed": dict(self._managed),
+            "results": [result.to_dict() for result in self._results],
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="universe-screener")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.refresh()
+            except Exception as exc:
+                self._logger.info("screener_error", extra={"venue": self._connector.venue, "error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = ["ScreenResult", "ScreenerConfig", "UniverseScreener"]
@@ -0,0 +1,138 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Tuple, TYPE_CHECKING
+
+from .models import Order
+from .risk_service import RiskViolationError
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+if TYPE_CHECKING:
+    from .order_service import OrderService
+
+REPRICE = "reprice"
+REJECT = "reject"
+
+
+class SelfTradeError(RiskViolationError):
+    """Raised when an order would trade against one of our own resting orders."""
+
+
+@dataclass(slots=True)
+class SelfTradeConfig:
+    action: str = REPRICE  # reprice: back a limit order off to one tick inside our own quote; reject: refuse it
+
+
+def order_price_i(order: Order) -> Optional[int]:
+    """Latest limit price of an order (as placed or amended); None for market orders."""
+    return next((int(e.info["price_i"]) for e in reversed(order.history) if e.info.get("price_i")), None)
+
+
+class SelfTradeGuard:
+    """Stops our orders from crossing our own resting orders, across every registered `OrderService`.
+
+    Several strategies share one `OrderService`; several accounts on the
+    same venue each have their own. Registering all of them with one guard
+    lets it see every resting order in the process. A buy at or above one
+    of our asks on the same venue and symbol (a sell at or below one of our
+    bids) would match against ourselves: wash-trading flags on the venue
+    and two fees for no position change. With `reprice` a limit order is
+    moved to one tick short of our best opposite quote, where it rests
+    instead of crossing; with `reject` it raises `SelfTradeError`. A market
+    order cannot be repriced and may sweep through any of our opposite
+    orders on the symbol, so those are cancelled before it is sent; it is
+    only refused when a cancel fails. Exits and flattens therefore still go
+    out.
+    """
+
+    def __init__(self, config: Optional[SelfTradeConfig] = None) -> None:
+        self._config = config or SelfTradeConfig()
+        self._services: List["OrderService"] = []
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> SelfTradeConfig:
+        return self._config
+
+    def register(self, service: "OrderService") -> None:
+        if service not in self._services:
+            self._services.append(service)
+            service.attach_self_trade(self)
+
+    async def conflicts(
+        self, *, venue: str, symbol: str, is_ask: bool, price_i: Optional[int], exclude: Optional[Order] = None
+    ) -> List[Order]:
+        """Our resting orders on the other side that an order at `price_i` (None: market) would trade with."""
+        return [order for _, order in await self._crossing(venue, symbol, is_ask, price_i, exclude)]
+
+    async def _crossing(
+        self, venue: str, symbol: str, is_ask: bool, price_i: Optional[int], exclude: Optional[Order]
+    ) -> List[Tuple["OrderService", Order]]:
+        found: List[Tuple["OrderService", Order]] = []
+        for service in self._services:
+            if service.venue != venue:
+                continue
+            for order in await service.open_orders(symbol):
+                if order is exclude or order.is_ask == is_ask:
+                    continue
+                resting = order_price_i(order)
+                if resting is None:
+                    continue
+                if price_i is None or (resting <= price_i if not is_ask else resting >= price_i):
+                    found.append((service, order))
+        return found
+
+    async def check(
+        self,
+        *,
+        venue: str,
+        symbol: str,
+        is_ask: bool,
+        price_i: Optional[int],
+        exclude: Optional[Order] = None,
+    ) -> Optional[int]:
+        """The price the order may go out at: `price_i`, or a repriced one; raises `SelfTradeError`."""
+        crossing = await self._crossing(venue, symbol, is_ask, price_i, exclude)
+        if not crossing:
+            return price_i
+        prices = [p for p in (order_price_i(o) for _, o in crossing) if p is not None]
+        best = max(prices) if is_ask else min(prices)
+        extra = {
+            "venue": venue,
+            "symbol": symbol,
+            "is_ask": is_ask,
+            "price_i": price_i,
+            "resting_price_i": best,
+            "resting": [o.client_order_index for _, o in crossing],
+        }
+        if price_i is None:
+            await self._cancel(crossing, extra)
+            return None
+        if self._config.action == REPRICE:
+            repriced = best + 1 if is_ask else best - 1
+            if repriced > 0:
+                METRICS.inc("self_trades_prevented", venue=venue, symbol=symbol, action=REPRICE)
+                self._logger.info("self_trade_repriced", extra={**extra, "repriced_i": repriced})
+                return repriced
+        METRICS.inc("self_trades_prevented", venue=venue, symbol=symbol, action=REJECT)
+        self._logger.info("self_trade_rejected", extra=extra)
+        side = "sell" if is_ask else "buy"
+        raise SelfTradeError(f"{side} {symbol} at {price_i} would cross our own order at {best}")
+
+    async def _cancel(self, crossing: List[Tuple["OrderService", Order]], extra: Dict[str, object]) -> None:
+        for service, order in crossing:
+            try:
+                await service.cancel(order.symbol, order.client_order_index)
+            except Exception as exc:
+                METRICS.inc("self_trades_prevented", venue=str(extra["venue"]), symbol=order.symbol, action=REJECT)
+                self._logger.info("self_trade_rejected", extra={**extra, "error": str(exc)})
+                raise SelfTradeError(
+                    f"market order on {order.symbol} would cross our order {order.client_order_index}, "
+                    f"which could not be cancelled: {exc}"
+                ) from exc
+        METRICS.inc("self_trades_prevented", venue=str(extra["venue"]), symbol=str(extra["symbol"]), action="cancel")
+        self._logger.info("self_trade_cancelled", extra=extra)
+
+
+__all__ = ["REJECT", "REPRICE", "SelfTradeConfig", "SelfTradeError", "SelfTradeGuard", "order_price_i"]
@@ -0,0 +1,244 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from collections import defaultdict, deque
+from dataclasses import dataclass, field
+from typing import Any, Deque, Dict, List, Optional, Tuple
+
+from ..core.events import Event, EventKind, EventsLagged, EventStream
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class SignalConfig:
+    window_secs: float = 30.0  # order-flow imbalance and aggressor ratio look back this far
+    vpin_buckets: int = 50  # volume buckets averaged by VPIN
+    bucket_volume: Dict[str, float] = field(default_factory=dict)  # venue symbol -> base volume per bucket
+    trades_per_bucket: int = 20  # without a configured size, a bucket holds this many average trades
+    toxic_vpin: float = 0.6  # VPIN at or above this flags the flow as toxic
+    publish_secs: float = 1.0  # at most one SIGNAL event per symbol this often
+
+
+@dataclass(slots=True)
+class Signal:
+    symbol: str
+    ofi: Optional[float]  # summed touch order-flow imbalance over the window, base units
+    ofi_norm: Optional[float]  # ofi over the summed absolute contributions, in [-1, 1]
+    aggressor_ratio: Optional[float]  # buyer-initiated share of traded volume over the window
+    vpin: Optional[float]  # mean |buy - sell| / bucket volume over the last buckets
+    toxic: bool
+    ts: float
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "ofi": self.ofi,
+            "ofi_norm": self.ofi_norm,
+            "aggressor_ratio": self.aggressor_ratio,
+            "vpin": self.vpin,
+            "toxic": self.toxic,
+        }
+
+
+def touch_flow(
+    prev: Tuple[float, float, float, float], cur: Tuple[float, float, float, float]
+) -> float:
+    """Order-flow imbalance of one touch change (Cont, Kukanov and Stoikov); tuples are (bid, bid_size, ask, ask_size).
+
+    A higher bid or a larger bid queue is buying pressure, a lower ask or a
+    larger ask queue selling pressure.
+    """
+    pb, pbs, pa, pas = prev
+    b, bs, a, as_ = cur
+    bid = (bs if b >= pb else 0.0) - (pbs if b <= pb else 0.0)
+    ask = (as_ if a <= pa else 0.0) - (pas if a >= pa else 0.0)
+    return bid - ask
+
+
+@dataclass(slots=True)
+class _FlowState:
+    touch: Optional[Tuple[float, float, float, float]] = None
+    flows: Deque[Tuple[float, float]] = field(default_factory=deque)  # (ts, ofi contribution)
+    trades: Deque[Tuple[float, float, bool]] = field(default_factory=deque)  # (ts, size, buyer initiated)
+    last_price: Optional[float] = None
+    last_buy: bool = True
+    seed: List[Tuple[float, bool]] = field(default_factory=list)  # trades seen before the bucket size is known
+    bucket_volume: Optional[float] = None
+    bucket_buy: float = 0.0
+    bucket_sell: float = 0.0
+    buckets: Deque[float] = field(default_factory=deque)  # |buy - sell| / volume per full bucket
+    published: float = 0.0
+
+
+class SignalService:
+    """Derives order-flow signals per symbol from book and trade events and broadcasts them as `SIGNAL` events.
+
+    - Order-flow imbalance sums `touch_flow` over every touch change within
+      `window_secs`. It needs touch sizes, which only feeds that keep a local
+      book publish; elsewhere it stays None.
+    - The aggressor ratio is the buyer-initiated share of traded volume in
+      the same window. A trade's side comes from its maker flag (`m`: the
+      buyer was the maker), else the tick rule.
+    - VPIN fills equal-volume buckets with classified trade volume and
+      averages `|buy - sell| / volume` over the last `vpin_buckets`. Bucket
+      size is configured per symbol, or fixed at `trades_per_bucket` times
+      the average size of the first trades seen.
+
+    Events carry the venue symbol and the source they were derived from.
+    """
+
+    def __init__(self, *, events: EventStream, config: Optional[SignalConfig] = None) -> None:
+        self._events = events
+        self._config = config or SignalConfig()
+        self._state: Dict[Tuple[str, str], _FlowState] = defaultdict(_FlowState)
+        self._latest: Dict[str, Signal] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> SignalConfig:
+        return self._config
+
+    def latest(self, symbol: str) -> Optional[Signal]:
+        return self._latest.get(symbol)
+
+    def on_book(self, source: str, symbol: str, data: Dict[str, Any], ts: Optional[float] = None) -> None:
+        values = [data.get(key) for key in ("bid", "bid_size", "ask", "ask_size")]
+        if any(value is None for value in values):
+            return
+        touch = tuple(float(value) for value in values)
+        state = self._state[(source, symbol)]
+        now = time.time() if ts is None else ts
+        if state.touch is not None and touch != state.touch:
+            state.flows.append((now, touch_flow(state.touch, touch)))  # type: ignore[arg-type]
+        state.touch = touch  # type: ignore[assignment]
+
+    def on_trade(self, source: str, symbol: str, price: float, size: float, maker: Any = None, ts: Optional[float] = None) -> None:
+        state = self._state[(source, symbol)]
+        now = time.time() if ts is None else ts
+        if isinstance(maker, bool):
+            buy = not maker
+        elif state.last_price is not None and price != state.last_price:
+            buy = price > state.last_price
+        else:
+            buy = state.last_buy
+        state.last_price, state.last_buy = price, buy
+        state.trades.append((now, size, buy))
+        self._fill_buckets(symbol, state, size, buy)
+
+    def _fill_buckets(self, symbol: str, state: _FlowState, size: float, buy: bool) -> None:
+        if state.bucket_volume is None:
+            configured = self._config.bucket_volume.get(symbol)
+            if not configured:
+                state.seed.append((size, buy))
+                if len(state.seed) < self._config.trades_per_bucket:
+                    return
+                # Average trade size times trades_per_bucket is simply their total
+                state.bucket_volume = sum(seeded for seeded, _ in state.seed)
+                seed, state.seed = state.seed, []
+                for seeded, seeded_buy in seed:
+                    self._add_volume(state, seeded, seeded_buy)
+                return
+            state.bucket_volume = configured
+        self._add_volume(state, size, buy)
+
+    def _add_volume(self, state: _FlowState, size: float, buy: bool) -> None:
+        volume = state.bucket_volume or 0.0
+        if volume <= 0:
+            return
+        while size > 0:
+            room = volume - state.bucket_buy - state.bucket_sell
+            take = min(size, room)
+            if buy:
+                state.bucket_buy += take
+            else:
+                state.bucket_sell += take
+            size -= take
+            if state.bucket_buy + state.bucket_sell >= volume:
+                state.buckets.append(abs(state.bucket_buy - state.bucket_sell) / volume)
+                while len(state.buckets) > self._config.vpin_buckets:
+                    state.buckets.popleft()
+                state.bucket_buy = state.bucket_sell = 0.0
+
+    def signal(self, source: str, symbol: str, now: Optional[float] = None) -> Signal:
+        state = self._state[(source, symbol)]
+        now = time.time() if now is None else now
+        cutoff = now - self._config.window_secs
+        while state.flows and state.flows[0][0] < cutoff:
+            state.flows.popleft()
+        while state.trades and state.trades[0][0] < cutoff:
+            state.trades.popleft()
+        ofi = ofi_norm = None
+        if state.touch is not None:
+            ofi = sum(flow for _, flow in state.flows)
+            gross = sum(abs(flow) for _, flow in state.flows)
+            ofi_norm = ofi / gross if gross > 0 else 0.0
+        volume = sum(size for _, size, _ in state.trades)
+        aggressor = sum(size for _, size, buy in state.trades if buy) / volume if volume > 0 else None
+        vpin = sum(state.buckets) / len(state.buckets) if state.buckets else None
+        return Signal(
+            symbol=symbol,
+            ofi=ofi,
+            ofi_norm=ofi_norm,
+            aggressor_ratio=aggressor,
+            vpin=vpin,
+            toxic=vpin is not None and vpin >= self._config.toxic_vpin,
+            ts=now,
+        )
+
+    def _maybe_publish(self, source: str, symbol: str, now: float) -> None:
+        state = self._state[(source, symbol)]
+        if now - state.published < self._config.publish_secs:
+            return
+        state.published = now
+        signal = self.signal(source, symbol, now)
+        self._latest[symbol] = signal
+        self._events.emit(EventKind.SIGNAL, source, signal.to_dict(), symbol=symbol)
+
+    def _apply(self, event: Event) -> None:
+        assert event.symbol is not None
+        if event.kind == EventKind.TRADE:
+            price, size = event.data.get("p"), event.data.get("q")
+            if price is None or size is None:
+                return
+            self.on_trade(event.source, event.symbol, float(price), float(size), event.data.get("m"), event.ts)
+        else:
+            self.on_book(event.source, event.symbol, event.data, event.ts)
+        self._maybe_publish(event.source, event.symbol, event.ts)
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="signals")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        sub = self._events.subscribe(kinds=[EventKind.MARKET_DATA, EventKind.TRADE], name="signals", report_lag=True)
+        try:
+            while True:
+                try:
+                    event = await sub.get()
+                except EventsLagged as exc:
+                    # Touch deltas across the gap are unknown; restart the flow from the next touch
+                    self._logger.info("signal_events_lagged", extra={"missed": exc.missed})
+                    for state in self._state.values():
+                        state.touch = None
+                    continue
+                if event.symbol is None or event.snapshot:
+                    continue
+                try:
+                    self._apply(event)
+                except Exception as exc:
+                    self._logger.info("signal_update_error", extra={"symbol": event.symbol, "error": str(exc)})
+        finally:
+            sub.close()
+
+
+__all__ = ["Signal", "SignalConfig", "SignalService", "touch_flow"]
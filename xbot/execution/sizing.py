@@ -0,0 +1,77 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Optional
+
+from .candles import CandleService
+from .router import ExecutionRouter
+from ..analytics.equity import equity_from_margin
+from ..utils.logging import get_logger
+
+FIXED = "fixed"
+VOLATILITY = "volatility"
+
+
+@dataclass(slots=True)
+class SizingConfig:
+    risk_fraction: Decimal = Decimal("0.01")  # share of equity lost if price moves the stop distance
+    atr_period: int = 14
+    atr_multiple: Decimal = Decimal(2)  # stop distance in ATRs
+    max_size: Optional[Decimal] = None  # cap in contracts
+
+
+class SizingUnavailable(ValueError):
+    pass
+
+
+class VolatilitySizer:
+    """Sizes positions so a stop `atr_multiple` ATRs away costs `risk_fraction` of equity.
+
+    `size = equity * risk_fraction / loss_per_contract`, where the loss per
+    contract follows the symbology's multiplier and, for inverse contracts,
+    is taken in coin and valued at the last close. Equity comes from the
+    venue margin snapshot and is assumed to be in the quote currency. When
+    there is no ATR yet or no equity, sizing raises `SizingUnavailable`
+    instead of falling back to a fixed size.
+    """
+
+    def __init__(self, *, router: ExecutionRouter, candles: CandleService, config: Optional[SizingConfig] = None) -> None:
+        self._router = router
+        self._candles = candles
+        self._config = config or SizingConfig()
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> SizingConfig:
+        return self._config
+
+    async def size(self, symbol: str) -> Decimal:
+        market_data = self._router.market_data
+        venue_symbol = market_data.resolve_symbol(symbol)
+        candles = self._candles.candles(venue_symbol)
+        atr = self._candles.atr(venue_symbol, self._config.atr_period)
+        if atr is None or not candles:
+            raise SizingUnavailable(f"not enough {self._candles.interval_secs:.0f}s candles for an ATR on {symbol}")
+        price = candles[-1].close
+        stop = atr * self._config.atr_multiple
+        if stop <= 0 or stop >= price:
+            raise SizingUnavailable(f"stop distance {stop} is unusable at price {price} on {symbol}")
+        instrument = market_data.instrument(symbol)
+        loss = abs(instrument.pnl(Decimal(1), price, price - stop))
+        if instrument.inverse:
+            loss *= price
+        equity = equity_from_margin(await self._router.fetch_margin())
+        if equity is None or equity <= 0:
+            raise SizingUnavailable(f"no account equity to size {symbol} from")
+        size = equity * self._config.risk_fraction / loss
+        if self._config.max_size is not None:
+            size = min(size, self._config.max_size)
+        self._logger.info(
+            "position_sized",
+            extra={"symbol": symbol, "size": str(size), "atr": str(atr), "price": str(price), "equity": str(equity)},
+        )
+        return size
+
+
+__all__ = ["FIXED", "SizingConfig", "SizingUnavailable", "VOLATILITY", "VolatilitySizer"]
@@ -0,0 +1,239 @@
+from __future__ import annotations
+
+import asyncio
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Dict, List, Mapping, Optional, Tuple
+
+from .models import Order
+from .router import ExecutionRouter
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+_BPS = Decimal(10_000)
+_Level = Tuple[Decimal, Decimal, Decimal]  # (all-in price, raw price, size)
+
+
+@dataclass(slots=True)
+class SmartRouterConfig:
+    primary: str  # venue used whenever no other venue has usable data
+    taker_fee_bps: Dict[str, Decimal] = field(default_factory=dict)  # venue -> taker fee
+    funding_horizon_hours: float = 0.0  # expected holding time charged at each venue's current funding; 0 ignores funding
+    split: bool = True  # False routes the whole order to the single best venue
+
+
+@dataclass(slots=True)
+class VenueQuote:
+    venue: str
+    available: Decimal  # visible depth on the side the order takes
+    avg_price: Optional[Decimal]  # raw average fill price for the full size (or what depth allows)
+    all_in_price: Optional[Decimal]  # avg price after taker fee and funding over the horizon
+    usable: bool
+    reason: str = ""
+
+
+@dataclass(slots=True)
+class RouteLeg:
+    venue: str
+    size: Decimal
+    expected_price: Optional[Decimal]  # all-in; None on a fallback leg
+
+
+@dataclass(slots=True)
+class RoutePlan:
+    symbol: str
+    is_ask: bool
+    size: Decimal
+    legs: List[RouteLeg]
+    quotes: List[VenueQuote]
+    fallback: bool = False  # routed to the primary venue without a usable comparison
+
+    @property
+    def expected_price(self) -> Optional[Decimal]:
+        if self.fallback or not self.legs:
+            return None
+        filled = sum((leg.size for leg in self.legs), Decimal(0))
+        return sum((leg.size * (leg.expected_price or 0) for leg in self.legs), Decimal(0)) / filled
+
+
+class SmartOrderRouter:
+    """Routes market orders for a canonical symbol across venues by expected all-in price.
+
+    Each venue's book comes from its `MarketDataService.get_order_book`; every
+    level is priced all-in as ``price * (1 ± taker fee) * (1 + funding rate *
+    intervals over the horizon)``, which makes paid funding a cost for longs
+    and received funding a credit for shorts without special cases. With
+    `split` the order is filled greedily from the best all-in levels across
+    venues; otherwise it goes whole to the best venue that can fill it.
+
+    Venues with stale market data, no book or a failing fetch are left out.
+    When none remain, the whole order goes to `primary` as a plain market order.
+    Legs below a venue's minimum size are folded into the largest leg.
+    """
+
+    def __init__(self, *, routers: Mapping[str, ExecutionRouter], config: SmartRouterConfig) -> None:
+        if config.primary not in routers:
+            raise ValueError(f"primary venue {config.primary} has no router")
+        self._routers = dict(routers)
+        self._config = config
+        self._logger = get_logger(__name__)
+
+    def _adjust(self, venue: str, price: Decimal, is_ask: bool, funding_factor: Decimal) -> Decimal:
+        fee = self._config.taker_fee_bps.get(venue, Decimal(0)) / _BPS
+        after_fee = price * (1 - fee) if is_ask else price * (1 + fee)
+        return after_fee * funding_factor
+
+    async def _funding_factor(self, router: ExecutionRouter, symbol: str) -> Decimal:
+        if self._config.funding_horizon_hours <= 0:
+            return Decimal(1)
+        funding = await router.market_data.get_funding(symbol)
+        if funding is None or funding.interval_hours <= 0:
+            return Decimal(1)
+        intervals = Decimal(str(self._config.funding_horizon_hours / funding.interval_hours))
+        return 1 + funding.rate * intervals
+
+    async def _venue_levels(
+        self, venue: str, symbol: str, is_ask: bool
+    ) -> Tuple[VenueQuote, List[_Level]]:
+        """(all-in price, raw price, size) levels on the side the order takes, best first."""
+        router = self._routers[venue]
+        if router.market_data.is_stale(symbol):
+            return VenueQuote(venue, Decimal(0), None, None, usable=False, reason="stale"), []
+        try:
+            book = await router.market_data.get_order_book(symbol)
+            factor = await self._funding_factor(router, symbol)
+        except Exception as exc:
+            return VenueQuote(venue, Decimal(0), None, None, usable=False, reason=str(exc)), []
+        if book is None:
+            return VenueQuote(venue, Decimal(0), None, None, usable=False, reason="no_book"), []
+        levels: List[_Level] = []
+        for price, size in book.levels("bids" if is_ask else "asks"):
+            raw = Decimal(str(price))
+            levels.append((self._adjust(venue, raw, is_ask, factor), raw, Decimal(str(size))))
+        if not levels:
+            return VenueQuote(venue, Decimal(0), None, None, usable=False, reason="empty_book"), []
+        available = sum((size for _, _, size in levels), Decimal(0))
+        return VenueQuote(venue, available, None, None, usable=True), levels
+
+    async def plan(self, symbol: str, is_ask: bool, size: Decimal | float | str) -> RoutePlan:
+        qty = Decimal(str(size))
+        venues = list(self._routers)
+        results = await asyncio.gather(*(self._venue_levels(v, symbol, is_ask) for v in venues))
+        quotes = [quote for quote, _ in results]
+        books = {quote.venue: levels for quote, levels in results if quote.usable}
+        for quote in quotes:
+            if quote.usable:
+                quote.avg_price, quote.all_in_price = _walk(books[quote.venue], qty)
+        if not books:
+            plan = RoutePlan(symbol, is_ask, qty, [RouteLeg(self._config.primary, qty, None)], quotes, fallback=True)
+        elif self._config.split:
+            plan = RoutePlan(symbol, is_ask, qty, _greedy(books, qty, is_ask), quotes)
+        else:
+            plan = RoutePlan(symbol, is_ask, qty, [_best_single(quotes, qty, is_ask)], quotes)
+        if not plan.fallback:
+            plan.legs = await self._fold_small_legs(symbol, plan.legs)
+        return plan
+
+    async def _fold_small_legs(self, symbol: str, legs: List[RouteLeg]) -> List[RouteLeg]:
+        if len(legs) <= 1:
+            return legs
+        kept: List[RouteLeg] = []
+        folded = Decimal(0)
+        for leg in legs:
+            md = self._routers[leg.venue].market_data
+            if await md.to_size_i(symbol, leg.size) < await md.get_min_size_i(symbol):
+                folded += leg.size
+            else:
+                kept.append(leg)
+        if not kept:
+            best = max(legs, key=lambda leg: leg.size)
+            return [RouteLeg(best.venue, sum((leg.size for leg in legs), Decimal(0)), best.expected_price)]
+        if folded:
+            largest = max(kept, key=lambda leg: leg.size)
+            largest.size += folded
+        return kept
+
+    async def execute(
+        self,
+        *,
+        symbol: str,
+        is_ask: bool,
+        size: Decimal | float | str,
+        reduce_only: int = 0,
+        trace_id: Optional[str] = None,
+    ) -> List[Order]:
+        plan = await self.plan(symbol, is_ask, size)
+        self._logger.info(
+            "smart_route",
+            extra={
+                "symbol": symbol,
+                "is_ask": is_ask,
+                "size": str(plan.size),
+                "fallback": plan.fallback,
+                "legs": [{"venue": leg.venue, "size": str(leg.size), "price": str(leg.expected_price)} for leg in plan.legs],
+                "skipped": {q.venue: q.reason for q in plan.quotes if not q.usable},
+            },
+        )
+        if plan.fallback:
+            METRICS.inc("smart_route_fallback", venue=self._config.primary)
+        for leg in plan.legs:
+            METRICS.inc("smart_route_legs", venue=leg.venue)
+        return list(
+            await asyncio.gather(
+                *(
+                    self._routers[leg.venue].submit_market(
+                        symbol=symbol, is_ask=is_ask, size=leg.size, reduce_only=reduce_only, trace_id=trace_id
+                    )
+                    for leg in plan.legs
+                )
+            )
+        )
+
+
+def _walk(levels: List[_Level], qty: Decimal) -> Tuple[Decimal, Decimal]:
+    """(raw avg, all-in avg) for up to `qty` from best-first levels."""
+    remaining, raw_cost, cost = qty, Decimal(0), Decimal(0)
+    for all_in, raw, available in levels:
+        take = min(remaining, available)
+        cost += take * all_in
+        raw_cost += take * raw
+        remaining -= take
+        if remaining <= 0:
+            break
+    filled = qty - max(remaining, Decimal(0))
+    return raw_cost / filled, cost / filled
+
+
+def _greedy(books: Mapping[str, List[_Level]], qty: Decimal, is_ask: bool) -> List[RouteLeg]:
+    merged = sorted(
+        ((all_in, size, venue) for venue, levels in books.items() for all_in, _raw, size in levels),
+        key=lambda row: row[0],
+        reverse=is_ask,  # sells take the highest all-in bids first
+    )
+    sizes: Dict[str, Decimal] = {}
+    costs: Dict[str, Decimal] = {}
+    remaining = qty
+    for all_in, available, venue in merged:
+        take = min(remaining, available)
+        sizes[venue] = sizes.get(venue, Decimal(0)) + take
+        costs[venue] = costs.get(venue, Decimal(0)) + take * all_in
+        remaining -= take
+        if remaining <= 0:
+            break
+    legs = [RouteLeg(venue, sizes[venue], costs[venue] / sizes[venue]) for venue in sizes if sizes[venue] > 0]
+    legs.sort(key=lambda leg: leg.size, reverse=True)
+    if remaining > 0:
+        # Visible depth is exhausted everywhere; the deepest leg takes the rest into unseen depth
+        legs[0].size += remaining
+    return legs
+
+
+def _best_single(quotes: List[VenueQuote], qty: Decimal, is_ask: bool) -> RouteLeg:
+    usable = [q for q in quotes if q.usable and q.all_in_price is not None]
+    complete = [q for q in usable if q.available >= qty] or usable
+    pick = max if is_ask else min
+    best = pick(complete, key=lambda q: q.all_in_price)  # type: ignore[arg-type, return-value]
+    return RouteLeg(best.venue, qty, best.all_in_price)
+
+
+__all__ = ["RouteLeg", "RoutePlan", "SmartOrderRouter", "SmartRouterConfig", "VenueQuote"]
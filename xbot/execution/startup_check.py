@@ -0,0 +1,165 @@
+from __future__ import annotations
+
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import Any, Dict, List, Optional, Sequence
+
+from xbot.connector.interface import IConnector
+
+from .recovery import _position_qty
+from ..analytics.equity import equity_from_margin
+from ..core.alerts import AlertLevel, AlertManager
+from ..storage.base import ACCOUNT_FINGERPRINTS, StorageWriter
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class AccountFingerprint:
+    venue: str
+    account_id: Optional[str]
+    equity: Optional[Decimal]
+    positions: Dict[str, Decimal]  # venue symbol -> net quantity, non-zero only
+    open_orders: Optional[int]  # None when the connector cannot list them
+    ts: float = field(default_factory=time.time)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "ts": self.ts,
+            "venue": self.venue,
+            "account_id": self.account_id,
+            "equity": None if self.equity is None else str(self.equity),
+            "positions": {symbol: str(qty) for symbol, qty in self.positions.items()},
+            "open_orders": self.open_orders,
+        }
+
+
+@dataclass(slots=True)
+class AccountExpectations:
+    account_id: Optional[str] = None  # the bot must run against this account
+    flat: bool = False  # no open positions
+    max_open_orders: Optional[int] = None
+    min_equity: Optional[Decimal] = None
+    positions: Dict[str, Decimal] = field(default_factory=dict)  # venue symbol -> expected net quantity
+    position_tolerance: Decimal = Decimal("0")
+
+    def violations(self, fingerprint: AccountFingerprint) -> List[str]:
+        found: List[str] = []
+        if self.account_id is not None and fingerprint.account_id != self.account_id:
+            found.append(f"account {fingerprint.account_id} is not the expected {self.account_id}")
+        if self.flat and fingerprint.positions:
+            held = ", ".join(f"{symbol} {qty}" for symbol, qty in sorted(fingerprint.positions.items()))
+            found.append(f"expected flat, holding {held}")
+        for symbol, expected in sorted(self.positions.items()):
+            actual = fingerprint.positions.get(symbol, Decimal(0))
+            if abs(actual - expected) > self.position_tolerance:
+                found.append(f"{symbol} position is {actual}, expected {expected}")
+        if self.max_open_orders is not None:
+            if fingerprint.open_orders is None:
+                found.append("open orders could not be listed")
+            elif fingerprint.open_orders > self.max_open_orders:
+                found.append(f"{fingerprint.open_orders} open orders, at most {self.max_open_orders} expected")
+        if self.min_equity is not None and (fingerprint.equity is None or fingerprint.equity < self.min_equity):
+            found.append(f"equity {fingerprint.equity} is below {self.min_equity}")
+        return found
+
+
+@dataclass(slots=True)
+class StartupCheckConfig:
+    expectations: Optional[AccountExpectations] = None  # shared by every venue unless overridden
+    venues: Dict[str, AccountExpectations] = field(default_factory=dict)
+
+    def for_venue(self, venue: str) -> Optional[AccountExpectations]:
+        return self.venues.get(venue.lower(), self.expectations)
+
+
+class StartupCheckError(RuntimeError):
+    def __init__(self, violations: Dict[str, List[str]]) -> None:
+        self.violations = violations
+        summary = "; ".join(f"{venue}: {', '.join(items)}" for venue, items in violations.items())
+        super().__init__(f"account does not match expectations: {summary}")
+
+
+class AccountStartupCheck:
+    """Fingerprints each account on startup and refuses to start on a mismatch.
+
+    For every connector it fetches the account identifier (`account_id()`),
+    equity (`get_margin()`), open positions (`get_positions()`) and, where
+    supported, open orders (`get_open_orders()`). The fingerprint is logged
+    as `account_fingerprint` and written to `account_fingerprints` when a
+    writer is given. It is then checked against the configured
+    expectations, e.g. "this bot must start flat". Any violation raises a
+    CRITICAL alert and `StartupCheckError`, before recovery or any
+    strategy touches the account.
+    """
+
+    def __init__(
+        self,
+        *,
+        connectors: Sequence[IConnector],
+        writer: Optional[StorageWriter] = None,
+        alerts: Optional[AlertManager] = None,
+        config: Optional[StartupCheckConfig] = None,
+    ) -> None:
+        self._connectors = list(connectors)
+        self._writer = writer
+        self._alerts = alerts
+        self._config = config or StartupCheckConfig()
+        self._logger = get_logger(__name__)
+
+    async def fingerprint(self, connector: IConnector) -> AccountFingerprint:
+        account_id = getattr(connector, "account_id", None)
+        positions: Dict[str, Decimal] = {}
+        for raw in await connector.get_positions():
+            qty = _position_qty(raw)
+            if raw.get("symbol") and qty:
+                positions[str(raw["symbol"])] = qty
+        return AccountFingerprint(
+            venue=connector.venue,
+            account_id=account_id() if account_id is not None else None,
+            equity=equity_from_margin(await connector.get_margin()),
+            positions=positions,
+            open_orders=await self._open_orders(connector),
+        )
+
+    async def _open_orders(self, connector: IConnector) -> Optional[int]:
+        list_orders = getattr(connector, "get_open_orders", None)
+        if list_orders is None:
+            return None
+        try:
+            return len(await list_orders())
+        except Exception as exc:
+            # Unknown rather than zero, so a max_open_orders expectation still fails closed
+            self._logger.info("startup_open_orders_error", extra={"venue": connector.venue, "error": str(exc)})
+            return None
+
+    async def run(self) -> List[AccountFingerprint]:
+        fingerprints: List[AccountFingerprint] = []
+        violations: Dict[str, List[str]] = {}
+        for connector in self._connectors:
+            fingerprint = await self.fingerprint(connector)
+            fingerprints.append(fingerprint)
+            row = fingerprint.to_dict()
+            self._logger.info("account_fingerprint", extra=row)
+            if self._writer is not None:
+                self._writer.enqueue(ACCOUNT_FINGERPRINTS, row)
+            expectations = self._config.for_venue(connector.venue)
+            found = expectations.violations(fingerprint) if expectations is not None else []
+            if found:
+                violations[connector.venue] = found
+        if violations:
+            error = StartupCheckError(violations)
+            self._logger.error("startup_check_failed", extra={"violations": violations})
+            if self._alerts is not None:
+                await self._alerts.notify(AlertLevel.CRITICAL, "startup check failed", str(error), violations=violations)
+            raise error
+        return fingerprints
+
+
+__all__ = [
+    "AccountExpectations",
+    "AccountFingerprint",
+    "AccountStartupCheck",
+    "StartupCheckConfig",
+    "StartupCheckError",
+]
@@ -0,0 +1,65 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Any, Dict, Optional
+
+
+@dataclass(slots=True)
+class StopOrder:
+    """A stop held by the bot rather than the venue, optionally trailing.
+
+    `is_ask` is the side of the exit order: True protects a long and fires
+    when the bid trades down to `trigger`, False protects a short and fires
+    when the ask trades up to it. With `trail` set, `update` ratchets the
+    trigger to stay `trail` behind the best price seen since entry; it never
+    moves against the position. The connectors take no trigger orders, so
+    the owner polls the touch and sends a reduce-only market order once
+    `triggered` is True. A stop only fires while the bot is running.
+    """
+
+    symbol: str
+    is_ask: bool
+    trigger: Decimal
+    trail: Optional[Decimal] = None
+    extreme: Optional[Decimal] = None  # best price seen since entry, for trailing
+
+    def update(self, price: Decimal) -> Decimal:
+        """Feed the latest price; returns the (possibly raised or lowered) trigger."""
+        if self.trail is None:
+            return self.trigger
+        if self.is_ask:
+            self.extreme = price if self.extreme is None else max(self.extreme, price)
+            self.trigger = max(self.trigger, self.extreme - self.trail)
+        else:
+            self.extreme = price if self.extreme is None else min(self.extreme, price)
+            self.trigger = min(self.trigger, self.extreme + self.trail)
+        return self.trigger
+
+    def triggered(self, bid: Optional[Decimal], ask: Optional[Decimal]) -> bool:
+        if self.is_ask:
+            return bid is not None and bid <= self.trigger
+        return ask is not None and ask >= self.trigger
+
+    @classmethod
+    def from_dict(cls, raw: Dict[str, Any]) -> "StopOrder":
+        trail, extreme = raw.get("trail"), raw.get("extreme")
+        return cls(
+            symbol=str(raw["symbol"]),
+            is_ask=bool(raw["is_ask"]),
+            trigger=Decimal(str(raw["trigger"])),
+            trail=Decimal(str(trail)) if trail is not None else None,
+            extreme=Decimal(str(extreme)) if extreme is not None else None,
+        )
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "symbol": self.symbol,
+            "is_ask": self.is_ask,
+            "trigger": str(self.trigger),
+            "trail": str(self.trail) if self.trail is not None else None,
+            "extreme": str(self.extreme) if self.extreme is not None else None,
+        }
+
+
+__all__ = ["StopOrder"]
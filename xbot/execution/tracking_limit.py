@@ -9,6 +9,8 @@ from xbot.connector.interface import IConnector
 
 from .market_data_service import MarketDataService
 from .models import Order, OrderState
+from ..core.locks import ReentrantLock
+from ..utils.idgen import client_order_index_for
 
 if TYPE_CHECKING:
     from .models import OrderEvent
@@ -52,7 +54,12 @@ class TrackingLimitOrder:
 
 
 class TrackingLimitEngine:
-    """Single implementation of the tracking-limit orchestration loop."""
+    """Single implementation of the tracking-limit orchestration loop.
+
+    With a `client_order_key`, attempt n is placed under the client index
+    derived from `<key>:<n>`, so placing the same key again replays the
+    attempts already sent instead of trading twice.
+    """
 
     def __init__(
         self,
@@ -83,6 +90,7 @@ class TrackingLimitEngine:
         reduce_only: int = 0,
         trace_id: Optional[str] = None,
         observer: Optional[Callable[[str, Dict[str, object]], Awaitable[None]]] = None,
+        client_order_key: Optional[str] = None,
     ) -> TrackingLimitOrder:
         interval = interval_secs or self._default_interval
         timeout = timeout_secs or self._default_timeout
@@ -92,50 +100,87 @@ class TrackingLimitEngine:
         remaining = base_amount_i
         records: List[TrackingAttempt] = []
 
-        while True:
-            attempt += 1
-            if max_attempts and attempt > max_attempts:
-                raise TrackingLimitTimeoutError("max attempts reached before fill")
-            now = time.monotonic()
-            if now >= deadline:
-                raise TrackingLimitTimeoutError("tracking limit timeout reached")
-            bid_i, ask_i, _scale = await self._market_data.get_top_of_book(symbol)
-            reference = ask_i if is_ask else bid_i
-            if reference is None:
-                raise RuntimeError("top of book unavailable for tracking limit")
-            price_i = reference + price_offset_ticks if is_ask else reference - price_offset_ticks
-            if price_i <= 0:
-                raise ValueError("price offset results in non-positive price")
-            if observer is not None:
-                await observer(
-                    "before_submit",
-                    {
-                        "attempt": attempt,
-                        "price_i": price_i,
-                        "remaining": remaining,
-                        "is_ask": is_ask,
-                        "symbol": symbol,
-                    },
+        # Held from a timed-out attempt's cancel until its replacement is
+        # submitted, so nothing else on the symbol lands in between
+        held: Optional[ReentrantLock] = None
+        try:
+            while True:
+                attempt += 1
+                if max_attempts and attempt > max_attempts:
+                    raise TrackingLimitTimeoutError("max attempts reached before fill")
+                now = time.monotonic()
+                if now >= deadline:
+                    raise TrackingLimitTimeoutError("tracking limit timeout reached")
+                bid_i, ask_i, _scale = await self._market_data.get_top_of_book(symbol)
+                reference = ask_i if is_ask else bid_i
+                if reference is None:
+                    raise RuntimeError("top of book unavailable for tracking limit")
+                price_i = reference + price_offset_ticks if is_ask else reference - price_offset_ticks
+                if price_i <= 0:
+                    raise ValueError("price offset results in non-positive price")
+                if observer is not None:
+                    await observer(
+                        "before_submit",
+                        {
+                            "attempt": attempt,
+                            "price_i": price_i,
+                            "remaining": remaining,
+                            "is_ask": is_ask,
+                            "symbol": symbol,
+                        },
+                    )
+                index = client_order_index_for(f"{client_order_key}:{attempt}") if client_order_key else None
+                order = await order_service.submit_limit(
+                    symbol=symbol,
+                    is_ask=is_ask,
+                    size_i=remaining,
+                    price_i=price_i,
+                    post_only=post_only,
+                    reduce_only=reduce_only,
+                    trace_id=trace_id,
+                    client_order_index=index,
                 )
-            order = await order_service.submit_limit(
-                symbol=symbol,
-                is_ask=is_ask,
-                size_i=remaining,
-                price_i=price_i,
-                post_only=post_only,
-                reduce_only=reduce_only,
-                trace_id=trace_id,
-            )
-            wait_budget = max(0.0, min(interval, deadline - time.monotonic()))
-            try:
-                update = await asyncio.wait_for(order.wait_final(), timeout=wait_budget)
-            except asyncio.TimeoutError:
-                await order_service.cancel(symbol, order.client_order_index)
+                if held is not None:
+                    held.release()
+                    held = None
+                wait_budget = max(0.0, min(interval, deadline - time.monotonic()))
                 try:
-                    update = await asyncio.wait_for(order.wait_final(), timeout=self._cancel_wait_secs)
+                    update = await asyncio.wait_for(order.wait_final(), timeout=wait_budget)
                 except asyncio.TimeoutError:
-                    update = order.snapshot()
-                    update.info = {**update.info, "cancel_wait_timeout": True}
+                    held = order_service.symbol_lock(symbol)
+                    await held.acquire()
+                    await order_service.cancel(symbol, order.client_order_index)
+                    try:
+                        update = await asyncio.wait_for(order.wait_final(), timeout=self._cancel_wait_secs)
+                    except asyncio.TimeoutError:
+                        update = order.snapshot()
+                        update.info = {**update.info, "cancel_wait_timeout": True}
+                    if observer is not None:
+                        await observer(
+                            "after_submit",
+                            {
+                                "attempt": attempt,
+                                "client_order_index": order.client_order_index,
+                                "price_i": price_i,
+                                "state": update.state.value,
+                                "info": update.info,
+                            },
+                        )
+                    records.append(
+                        TrackingAttempt(
+                            attempt=attempt,
+                            client_order_index=order.client_order_index,
+                            price_i=price_i,
+                            state=update.state,
+                            info={**update.info, "timeout": True},
+                        )
+                    )
+                    filled = self._extract_filled(update.info)
+                    cumulative_filled += filled
+                    remaining = base_amount_i - cumulative_filled
+                    if cumulative_filled > 0 and remaining <= max(1, int(base_amount_i * 0.0001)):
+                        return TrackingLimitOrder(order, records, cumulative_filled)
+                    continue
                 if observer is not None:
                     await observer(
                         "after_submit",
@@ -153,47 +198,24 @@ class TrackingLimitEngine:
                         client_order_index=order.client_order_index,
                         price_i=price_i,
                         state=update.state,
-                        info={**update.info, "timeout": True},
+                        info=update.info,
                     )
                 )
+                if update.state == OrderState.FILLED:
+                    cumulative_filled += remaining
+                    return TrackingLimitOrder(order, records, cumulative_filled)
+                if update.state == OrderState.FAILED:
+                    raise RuntimeError(f"tracking limit attempt failed: {update.info}")
                 filled = self._extract_filled(update.info)
                 cumulative_filled += filled
                 remaining = base_amount_i - cumulative_filled
-                if cumulative_filled > 0 and remaining <= max(1, int(base_amount_i * 0.0001)):
+                if remaining <= max(1, int(base_amount_i * 0.0001)):
                     return TrackingLimitOrder(order, records, cumulative_filled)
-                continue
-            if observer is not None:
-                await observer(
-                    "after_submit",
-                    {
-                        "attempt": attempt,
-                        "client_order_index": order.client_order_index,
-                        "price_i": price_i,
-                        "state": update.state.value,
-                        "info": update.info,
-                    },
-                )
-            records.append(
-                TrackingAttempt(
-                    attempt=attempt,
-                    client_order_index=order.client_order_index,
-                    price_i=price_i,
-                    state=update.state,
-                    info=update.info,
-                )
-            )
-            if update.state == OrderState.FILLED:
-                cumulative_filled += remaining
-                return TrackingLimitOrder(order, records, cumulative_filled)
-            if update.state == OrderState.FAILED:
-                raise RuntimeError(f"tracking limit attempt failed: {update.info}")
-            filled = self._extract_filled(update.info)
-            cumulative_filled += filled
-            remaining = base_amount_i - cumulative_filled
-            if remaining <= max(1, int(base_amount_i * 0.0001)):
-                return TrackingLimitOrder(order, records, cumulative_filled)
-            if remaining <= 0:
-                return TrackingLimitOrder(order, records, cumulative_filled)
+                if remaining <= 0:
+                    return TrackingLimitOrder(order, records, cumulative_filled)
+        finally:
+            if held is not None:
+                held.release()
 
     @staticmethod
     def _extract_filled(info: Dict[str, object]) -> int:
@@ -0,0 +1,110 @@
+from __future__ import annotations
+
+import math
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from fnmatch import fnmatchcase
+from typing import List, Optional, Sequence, Tuple
+
+DAY_SECS = 86400.0
+WEEKDAYS = ("mon", "tue", "wed", "thu", "fri", "sat", "sun")
+
+
+def parse_time_of_day(value: str) -> float:
+    """`HH:MM` or `HH:MM:SS` (UTC) -> seconds after midnight."""
+    parts = [int(part) for part in str(value).split(":")]
+    if not 2 <= len(parts) <= 3:
+        raise ValueError(f"time of day must be HH:MM[:SS], got {value!r}")
+    hours, minutes, seconds = (parts + [0])[:3]
+    if not (0 <= hours <= 24 and 0 <= minutes < 60 and 0 <= seconds < 60) or (hours == 24 and (minutes or seconds)):
+        raise ValueError(f"invalid time of day {value!r}")
+    return float(hours * 3600 + minutes * 60 + seconds)
+
+
+def parse_weekday(value: object) -> int:
+    """0 (Monday) to 6, or a name such as `sat`/`Saturday`."""
+    if isinstance(value, int):
+        if not 0 <= value <= 6:
+            raise ValueError(f"weekday must be 0-6, got {value}")
+        return value
+    name = str(value).strip().lower()[:3]
+    if name not in WEEKDAYS:
+        raise ValueError(f"unknown weekday {value!r}")
+    return WEEKDAYS.index(name)
+
+
+@dataclass(slots=True)
+class TradingWindow:
+    """A recurring UTC window in which opening orders are refused.
+
+    Either a daily window from `start` to `end` (seconds after midnight;
+    it wraps past midnight when `end` <= `start`), optionally only on
+    `weekdays` (the day it starts), or, with `every_hours`, a window from
+    `before_secs` ahead of each interval boundary to `after_secs` past it,
+    e.g. around funding timestamps.
+    """
+
+    name: str
+    symbols: List[str] = field(default_factory=list)  # canonical/venue symbols, `*` globs; empty: every symbol
+    start: Optional[float] = None
+    end: Optional[float] = None
+    weekdays: List[int] = field(default_factory=list)  # 0 = Monday; empty: every day
+    every_hours: Optional[float] = None
+    before_secs: float = 0.0
+    after_secs: float = 0.0
+
+    def __post_init__(self) -> None:
+        self.symbols = [entry.upper() for entry in self.symbols]
+        if self.every_hours is None and (self.start is None or self.end is None):
+            raise ValueError(f"trading window {self.name!r} needs start and end, or every_hours")
+        if self.every_hours is not None and self.every_hours <= 0:
+            raise ValueError(f"trading window {self.name!r}: every_hours must be positive")
+
+    def applies(self, *names: str) -> bool:
+        if not self.symbols:
+            return True
+        return any(fnmatchcase(name.upper(), entry) for name in names if name for entry in self.symbols)
+
+    def _on(self, day_start: float) -> bool:
+        return not self.weekdays or datetime.fromtimestamp(day_start, tz=timezone.utc).weekday() in self.weekdays
+
+    def active_until(self, now: Optional[float] = None) -> Optional[float]:
+        """When the window that contains `now` closes; None outside the window."""
+        now = time.time() if now is None else now
+        if self.every_hours is not None:
+            period = self.every_hours * 3600
+            previous = math.floor(now / period) * period
+            if now < previous + self.after_secs:
+                return previous + self.after_secs
+            if now >= previous + period - self.before_secs:
+                return previous + period + self.after_secs
+            return None
+        assert self.start is not None and self.end is not None
+        day_start = now - now % DAY_SECS
+        secs = now - day_start
+        if self.start < self.end:
+            return day_start + self.end if self.start <= secs < self.end and self._on(day_start) else None
+        if secs >= self.start and self._on(day_start):
+            return day_start + DAY_SECS + self.end
+        if secs < self.end and self._on(day_start - DAY_SECS):
+            return day_start + self.end
+        return None
+
+
+def blocking_window(
+    windows: Sequence[TradingWindow], names: Sequence[str], now: Optional[float] = None
+) -> Optional[Tuple[TradingWindow, float]]:
+    """The active window covering a symbol known by `names` that closes last, with its close time."""
+    now = time.time() if now is None else now
+    found: Optional[Tuple[TradingWindow, float]] = None
+    for window in windows:
+        if not window.applies(*names):
+            continue
+        until = window.active_until(now)
+        if until is not None and (found is None or until > found[1]):
+            found = (window, until)
+    return found
+
+
+__all__ = ["TradingWindow", "blocking_window", "parse_time_of_day", "parse_weekday"]
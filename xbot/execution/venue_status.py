@@ -0,0 +1,173 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from typing import Any, Dict, List, Optional
+
+import httpx
+
+from xbot.connector.history import VenueStatus, timestamp_ms
+from xbot.connector.interface import IConnector
+
+from .risk_service import RiskMode, RiskService
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.events import EventKind, EventStream
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+
+@dataclass(slots=True)
+class VenueStatusConfig:
+    interval_secs: float = 60.0
+    status_page_url: Optional[str] = None  # Statuspage-style page, e.g. https://status.example.com
+    lead_secs: float = 300.0  # enter the maintenance mode this long before an announced window
+    mode: RiskMode = RiskMode.REDUCE_ONLY
+
+
+def parse_status_page(venue: str, summary: Dict[str, Any], *, lead_secs: float, now_ms: int) -> VenueStatus:
+    """`/api/v2/summary.json` of a Statuspage-style page -> VenueStatus."""
+    indicator = str((summary.get("status") or {}).get("indicator") or "")
+    for item in summary.get("scheduled_maintenances") or []:
+        starts = timestamp_ms(item.get("scheduled_for"))
+        ends = timestamp_ms(item.get("scheduled_until"))
+        status = str(item.get("status") or "")
+        upcoming = status == "scheduled" and starts is not None and starts - now_ms <= lead_secs * 1000
+        if status in ("in_progress", "verifying") or upcoming:
+            return VenueStatus(venue, True, str(item.get("name") or "scheduled maintenance"), starts, ends)
+    return VenueStatus(venue, indicator == "maintenance", str((summary.get("status") or {}).get("description") or ""))
+
+
+class VenueStatusMonitor:
+    """Moves the risk layer into reduce-only while the venue is in maintenance.
+
+    Every `interval_secs` it asks the connector (`get_exchange_status()`) and,
+    when configured, a Statuspage-style status page whose announced
+    maintenance counts `lead_secs` before it starts. On maintenance the risk
+    mode switches to `mode` (REDUCE_ONLY by default) and a WARNING alert goes
+    out; when every source is clear again the previous NORMAL mode is
+    restored. A mode someone else set (the circuit breaker, an operator, a
+    halt on shutdown) is never entered over or lifted.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        risk: RiskService,
+        events: Optional[EventStream] = None,
+        alerts: Optional[AlertManager] = None,
+        config: Optional[VenueStatusConfig] = None,
+    ) -> None:
+        self._connector = connector
+        self._risk = risk
+        self._events = events
+        self._alerts = alerts
+        self._config = config or VenueStatusConfig()
+        if not hasattr(connector, "get_exchange_status") and not self._config.status_page_url:
+            raise ValueError(f"no status source for {connector.venue}; set status_page_url")
+        self._reason: Optional[str] = None  # set while the risk mode is ours
+        self._client: Optional[httpx.AsyncClient] = None
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def in_maintenance(self) -> bool:
+        return self._reason is not None
+
+    async def statuses(self) -> List[VenueStatus]:
+        statuses: List[VenueStatus] = []
+        fetch = getattr(self._connector, "get_exchange_status", None)
+        if fetch is not None:
+            statuses.append(await fetch())
+        if self._config.status_page_url:
+            if self._client is None:
+                self._client = httpx.AsyncClient(timeout=10.0)
+            resp = await self._client.get(self._config.status_page_url.rstrip("/") + "/api/v2/summary.json")
+            resp.raise_for_status()
+            statuses.append(
+                parse_status_page(
+                    self._connector.venue,
+                    resp.json(),
+                    lead_secs=self._config.lead_secs,
+                    now_ms=int(time.time() * 1000),
+                )
+            )
+        return statuses
+
+    async def check(self) -> Optional[VenueStatus]:
+        """Poll every source once; returns the maintenance status in effect, if any."""
+        active = next((s for s in await self.statuses() if s.maintenance), None)
+        METRICS.set("venue_maintenance", 1.0 if active else 0.0, venue=self._connector.venue)
+        if active is not None and self._reason is None:
+            await self._enter(active)
+        elif active is None and self._reason is not None:
+            await self._resume()
+        return active
+
+    async def _enter(self, status: VenueStatus) -> None:
+        venue = self._connector.venue
+        if self._risk.mode != RiskMode.NORMAL:
+            self._logger.info("venue_maintenance_mode_kept", extra={"venue": venue, "mode": self._risk.mode.value})
+            return
+        self._reason = f"{venue} maintenance: {status.message or 'announced'}"
+        self._risk.set_mode(self._config.mode, self._reason)
+        self._logger.warning(
+            "venue_maintenance", extra={"venue": venue, "detail": status.message, "mode": self._config.mode.value}
+        )
+        self._publish(True, status.message)
+        if self._alerts is not None:
+            await self._alerts.notify(
+                AlertLevel.WARNING,
+                f"{venue} maintenance",
+                f"{status.message or 'maintenance announced'}; risk mode {self._config.mode.value} until it ends",
+                venue=venue,
+                starts_ms=status.starts_ms,
+                ends_ms=status.ends_ms,
+            )
+
+    async def _resume(self) -> None:
+        venue = self._connector.venue
+        ours = self._risk.mode == self._config.mode and self._risk.mode_reason == self._reason
+        self._reason = None
+        if not ours:
+            return
+        self._risk.set_mode(RiskMode.NORMAL)
+        self._logger.info("venue_maintenance_over", extra={"venue": venue})
+        self._publish(False, "")
+        if self._alerts is not None:
+            await self._alerts.notify(AlertLevel.INFO, f"{venue} maintenance over", "trading resumed", venue=venue)
+
+    def _publish(self, maintenance: bool, message: str) -> None:
+        if self._events is not None:
+            self._events.emit(
+                EventKind.VENUE_STATUS,
+                self._connector.venue,
+                {"maintenance": maintenance, "message": message, "mode": self._risk.mode.value},
+            )
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="venue-status")
+
+    async def stop(self) -> None:
+        if self._task is not None:
+            self._task.cancel()
+            with contextlib.suppress(asyncio.CancelledError):
+                await self._task
+            self._task = None
+        if self._client is not None:
+            await self._client.aclose()
+            self._client = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.check()
+            except Exception as exc:
+                self._logger.info("venue_status_error", extra={"venue": self._connector.venue, "error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = ["VenueStatusConfig", "VenueStatusMonitor", "parse_status_page"]
@@ -0,0 +1,99 @@
+from __future__ import annotations
+
+import asyncio
+from typing import Dict, Iterable, List, Optional, Protocol
+
+from .market_data_service import MarketDataService
+from ..core.events import EventKind, EventStream
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+
+class SubscriptionFeed(Protocol):
+    """A market-data feed that can change its venue symbols while connected (e.g. `BackpackWsClient`)."""
+
+    async def add_symbols(self, symbols: Iterable[str]) -> List[str]:
+        ...
+
+    async def remove_symbols(self, symbols: Iterable[str]) -> List[str]:
+        ...
+
+
+class Watchlist:
+    """Symbols the bot streams market data for, changeable at runtime.
+
+    `add(symbol, venue_symbol)` maps the symbol in `MarketDataService`,
+    checks the venue knows it (by loading its decimals) and subscribes every
+    attached feed; `remove(symbol)` unsubscribes it again. The mapping itself
+    stays, so orders and positions on a removed symbol still resolve. Each
+    change is published as a `WATCHLIST` event with the symbols added,
+    removed and now watched, which is how strategies learn about a new listing.
+    """
+
+    def __init__(
+        self,
+        *,
+        market_data: MarketDataService,
+        events: Optional[EventStream] = None,
+        venue: str = "",
+        symbols: Iterable[str] = (),
+    ) -> None:
+        self._market_data = market_data
+        self._events = events
+        self._venue = venue
+        self._feeds: List[SubscriptionFeed] = []
+        self._symbols: Dict[str, str] = {s.upper(): market_data.resolve_symbol(s) for s in symbols}
+        self._lock = asyncio.Lock()
+        self._logger = get_logger(__name__)
+
+    def attach_feed(self, feed: SubscriptionFeed) -> None:
+        self._feeds.append(feed)
+
+    def symbols(self) -> Dict[str, str]:
+        """Canonical -> venue symbol for every watched symbol."""
+        return dict(self._symbols)
+
+    def __contains__(self, symbol: object) -> bool:
+        return isinstance(symbol, str) and symbol.upper() in self._symbols
+
+    async def add(self, symbol: str, venue_symbol: Optional[str] = None) -> bool:
+        """Start watching `symbol`; returns False if it was already watched."""
+        key = symbol.upper()
+        async with self._lock:
+            if key in self._symbols:
+                return False
+            if venue_symbol:
+                self._market_data.register_symbol(key, venue_symbol)
+            resolved = self._market_data.resolve_symbol(key)
+            # Raises for symbols the venue does not list, before anything subscribes
+            await self._market_data.get_price_size_decimals(key)
+            for feed in self._feeds:
+                await feed.add_symbols([resolved])
+            self._symbols[key] = resolved
+            self._changed(added=[key], removed=[])
+        return True
+
+    async def remove(self, symbol: str) -> bool:
+        """Stop watching `symbol`; returns False if it was not watched."""
+        key = symbol.upper()
+        async with self._lock:
+            resolved = self._symbols.pop(key, None)
+            if resolved is None:
+                return False
+            for feed in self._feeds:
+                await feed.remove_symbols([resolved])
+            self._changed(added=[], removed=[key])
+        return True
+
+    def _changed(self, *, added: List[str], removed: List[str]) -> None:
+        METRICS.set("watchlist_symbols", len(self._symbols), venue=self._venue)
+        self._logger.info("watchlist_changed", extra={"added": added, "removed": removed, "symbols": sorted(self._symbols)})
+        if self._events is not None:
+            self._events.emit(
+                EventKind.WATCHLIST,
+                self._venue,
+                {"added": added, "removed": removed, "symbols": sorted(self._symbols)},
+            )
+
+
+__all__ = ["SubscriptionFeed", "Watchlist"]
@@ -0,0 +1,234 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from collections import defaultdict, deque
+from dataclasses import dataclass, field
+from typing import Any, Deque, Dict, List, Optional, Tuple
+
+from ..core.alerts import AlertLevel, AlertManager
+from ..core.events import EventKind, EventsLagged, EventStream
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+LARGE_PRINT = "large_print"
+SWEEP = "sweep"
+
+
+@dataclass(slots=True)
+class WhaleConfig:
+    size_multiple: float = 10.0  # a print this many times the average trade size is large
+    min_size: Dict[str, float] = field(default_factory=dict)  # venue symbol -> base size that is always large
+    baseline_trades: int = 500  # trades in the rolling average size
+    warmup_trades: int = 50  # no relative detection before this many trades
+    sweep_window_secs: float = 2.0  # aggressive prints on one side within this window form a cluster
+    sweep_trades: int = 10  # prints in a cluster needed to flag a sweep
+    sweep_multiple: float = 20.0  # and the cluster's volume, in average trade sizes
+    cooldown_secs: float = 5.0  # per symbol and kind, between events
+    alert: bool = False  # also send an INFO alert per event
+
+
+@dataclass(slots=True)
+class WhaleActivity:
+    symbol: str
+    kind: str  # large_print | sweep
+    side: str  # buy | sell: the aggressor
+    size: float  # base; summed over the cluster for sweeps
+    notional: float
+    price: float  # of the (last) print
+    trades: int
+    multiple: Optional[float]  # size over the average trade size, when known
+    ts: float
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "kind": self.kind,
+            "side": self.side,
+            "size": self.size,
+            "notional": self.notional,
+            "price": self.price,
+            "trades": self.trades,
+            "multiple": self.multiple,
+        }
+
+
+@dataclass(slots=True)
+class _TradeState:
+    sizes: Deque[float] = field(default_factory=deque)
+    size_sum: float = 0.0
+    cluster: Deque[Tuple[float, float, float]] = field(default_factory=deque)  # (ts, size, price) same-side prints
+    cluster_buy: bool = True
+    last_price: Optional[float] = None
+    last_buy: bool = True
+    fired: Dict[str, float] = field(default_factory=dict)  # kind -> last event ts
+
+    @property
+    def average(self) -> Optional[float]:
+        return self.size_sum / len(self.sizes) if self.sizes else None
+
+
+class WhaleDetector:
+    """Flags unusually large trades and bursts of aggressive prints, as `WHALE_ACTIVITY` events.
+
+    - A large print is a trade at least `size_multiple` times the rolling
+      average trade size of the last `baseline_trades` (after
+      `warmup_trades`), or at least the symbol's `min_size`.
+    - A sweep is `sweep_trades` or more prints by the same aggressor within
+      `sweep_window_secs` that together reach `sweep_multiple` average
+      sizes: one taker walking the book, or a burst of them. The cluster
+      restarts when the aggressor side changes.
+
+    The aggressor comes from the maker flag (`m`, the buyer was the maker),
+    else the tick rule, as in `SignalService`. Each kind fires at most once
+    per `cooldown_secs` per symbol. `recent(symbol)` and `active(symbol)`
+    serve strategies that use whale activity as a filter.
+    """
+
+    def __init__(
+        self,
+        *,
+        events: EventStream,
+        config: Optional[WhaleConfig] = None,
+        alerts: Optional[AlertManager] = None,
+    ) -> None:
+        self._events = events
+        self._config = config or WhaleConfig()
+        self._alerts = alerts
+        self._state: Dict[Tuple[str, str], _TradeState] = defaultdict(_TradeState)
+        self._recent: Dict[str, Deque[WhaleActivity]] = defaultdict(lambda: deque(maxlen=100))
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def config(self) -> WhaleConfig:
+        return self._config
+
+    def recent(
+        self, symbol: str, within_secs: Optional[float] = None, *, now: Optional[float] = None
+    ) -> List[WhaleActivity]:
+        now = time.time() if now is None else now
+        return [a for a in self._recent.get(symbol, ()) if within_secs is None or now - a.ts <= within_secs]
+
+    def active(
+        self, symbol: str, within_secs: float = 60.0, *, side: Optional[str] = None, now: Optional[float] = None
+    ) -> bool:
+        """Whether whale activity (on `side`, if given) was seen on `symbol` in the last `within_secs`."""
+        return any(side is None or a.side == side for a in self.recent(symbol, within_secs, now=now))
+
+    def on_trade(
+        self, source: str, symbol: str, price: float, size: float, maker: Any = None, ts: Optional[float] = None
+    ) -> List[WhaleActivity]:
+        state = self._state[(source, symbol)]
+        now = time.time() if ts is None else ts
+        if isinstance(maker, bool):
+            buy = not maker
+        elif state.last_price is not None and price != state.last_price:
+            buy = price > state.last_price
+        else:
+            buy = state.last_buy
+        state.last_price, state.last_buy = price, buy
+        average = state.average if len(state.sizes) >= self._config.warmup_trades else None
+        found: List[WhaleActivity] = []
+        side = "buy" if buy else "sell"
+        multiple = size / average if average else None
+        floor = self._config.min_size.get(symbol)
+        if (multiple is not None and multiple >= self._config.size_multiple) or (floor is not None and size >= floor):
+            found.append(WhaleActivity(symbol, LARGE_PRINT, side, size, size * price, price, 1, multiple, now))
+        sweep = self._cluster(state, buy, size, price, now, average)
+        if sweep is not None:
+            volume, prints = sweep
+            notional = sum(s * p for _, s, p in prints)
+            ratio = volume / average if average else None
+            found.append(WhaleActivity(symbol, SWEEP, side, volume, notional, price, len(prints), ratio, now))
+        self._baseline(state, size)
+        published = [activity for activity in found if self._cooled(state, activity)]
+        for activity in published:
+            self._publish(source, activity)
+        return published
+
+    def _cluster(
+        self, state: _TradeState, buy: bool, size: float, price: float, now: float, average: Optional[float]
+    ) -> Optional[Tuple[float, List[Tuple[float, float, float]]]]:
+        if buy != state.cluster_buy:
+            state.cluster.clear()
+            state.cluster_buy = buy
+        state.cluster.append((now, size, price))
+        while state.cluster and state.cluster[0][0] < now - self._config.sweep_window_secs:
+            state.cluster.popleft()
+        if average is None or len(state.cluster) < self._config.sweep_trades:
+            return None
+        volume = sum(s for _, s, _ in state.cluster)
+        if volume < self._config.sweep_multiple * average:
+            return None
+        prints = list(state.cluster)
+        state.cluster.clear()
+        return volume, prints
+
+    def _baseline(self, state: _TradeState, size: float) -> None:
+        state.sizes.append(size)
+        state.size_sum += size
+        while len(state.sizes) > self._config.baseline_trades:
+            state.size_sum -= state.sizes.popleft()
+
+    def _cooled(self, state: _TradeState, activity: WhaleActivity) -> bool:
+        last = state.fired.get(activity.kind)
+        if last is not None and activity.ts - last < self._config.cooldown_secs:
+            return False
+        state.fired[activity.kind] = activity.ts
+        return True
+
+    def _publish(self, source: str, activity: WhaleActivity) -> None:
+        self._recent[activity.symbol].append(activity)
+        METRICS.inc("whale_activity", source=source, symbol=activity.symbol, kind=activity.kind, side=activity.side)
+        self._logger.info("whale_activity", extra={"source": source, "symbol": activity.symbol, **activity.to_dict()})
+        self._events.emit(EventKind.WHALE_ACTIVITY, source, activity.to_dict(), symbol=activity.symbol)
+
+    async def _alert(self, source: str, activity: WhaleActivity) -> None:
+        if not self._config.alert or self._alerts is None:
+            return
+        title = f"{activity.kind.replace('_', ' ').capitalize()} on {activity.symbol}"
+        body = f"{activity.side} {activity.size:g} ({activity.notional:,.0f} notional) over {activity.trades} prints"
+        await self._alerts.notify(AlertLevel.INFO, title, body, source=source, symbol=activity.symbol)
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="whales")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        sub = self._events.subscribe(kinds=[EventKind.TRADE], name="whales", report_lag=True)
+        try:
+            while True:
+                try:
+                    event = await sub.get()
+                except EventsLagged as exc:
+                    # Prints across the gap are unknown; a half-seen cluster would under-report
+                    self._logger.info("whale_events_lagged", extra={"missed": exc.missed})
+                    for state in self._state.values():
+                        state.cluster.clear()
+                    continue
+                if event.symbol is None or event.snapshot:
+                    continue
+                price, size = event.data.get("p"), event.data.get("q")
+                if price is None or size is None:
+                    continue
+                try:
+                    maker = event.data.get("m")
+                    found = self.on_trade(event.source, event.symbol, float(price), float(size), maker, event.ts)
+                    for activity in found:
+                        await self._alert(event.source, activity)
+                except Exception as exc:
+                    self._logger.info("whale_update_error", extra={"symbol": event.symbol, "error": str(exc)})
+        finally:
+            sub.close()
+
+
+__all__ = ["LARGE_PRINT", "SWEEP", "WhaleActivity", "WhaleConfig", "WhaleDetector"]
@@ -0,0 +1,241 @@
+from __future__ import annotations
+
+import asyncio
+import secrets
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from enum import Enum
+from typing import Any, Dict, List, Optional, Protocol, Set
+
+from ..analytics.equity import EquityTracker, flow_ref
+from ..core.alerts import AlertLevel, AlertManager
+from ..utils.logging import get_logger
+
+
+class WithdrawalVenue(Protocol):
+    venue: str
+
+    async def request_withdrawal(
+        self, *, symbol: str, blockchain: str, address: str, quantity: Decimal, client_id: Optional[int] = None
+    ) -> Dict[str, Any]:  # pragma: no cover - protocol
+        ...
+
+
+@dataclass(slots=True)
+class AllowedAddress:
+    symbol: str
+    blockchain: str
+    address: str
+    label: str = ""
+
+
+@dataclass(slots=True)
+class WithdrawalConfig:
+    enabled: bool = False
+    allowlist: List[AllowedAddress] = field(default_factory=list)
+    max_quantity: Dict[str, Decimal] = field(default_factory=dict)  # per asset, per request
+    confirm_ttl_secs: float = 300.0
+
+
+class WithdrawalStatus(str, Enum):
+    PENDING = "pending"
+    SUBMITTED = "submitted"
+    FAILED = "failed"
+    CANCELLED = "cancelled"
+    EXPIRED = "expired"
+
+
+class WithdrawalRejected(ValueError):
+    pass
+
+
+@dataclass(slots=True)
+class PendingWithdrawal:
+    withdrawal_id: str
+    symbol: str
+    blockchain: str
+    address: str
+    quantity: Decimal
+    label: str = ""
+    requested_by: str = ""
+    created_at: float = field(default_factory=time.time)
+    expires_at: float = 0.0
+    status: WithdrawalStatus = WithdrawalStatus.PENDING
+    response: Dict[str, Any] = field(default_factory=dict)
+    error: Optional[str] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "withdrawal_id": self.withdrawal_id,
+            "symbol": self.symbol,
+            "blockchain": self.blockchain,
+            "address": self.address,
+            "label": self.label,
+            "quantity": str(self.quantity),
+            "requested_by": self.requested_by,
+            "created_at": self.created_at,
+            "expires_at": self.expires_at,
+            "status": self.status.value,
+            "response": self.response,
+            "error": self.error,
+        }
+
+
+class WithdrawalGuard:
+    """Two-step withdrawals restricted to allowlisted addresses.
+
+    `request` validates against the allowlist and per-asset caps and parks the
+    withdrawal; nothing leaves the exchange until `confirm` is called with its id
+    before `confirm_ttl_secs` runs out. With an `equity` tracker a submitted
+    withdrawal is recorded as a capital flow straight away, under the venue's
+    withdrawal id so the tracker's history sync does not count it twice.
+    `submitted_ids` lists those venue ids, so `BalanceMonitor` can tell the
+    withdrawals sent from here from any made around the bot.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: WithdrawalVenue,
+        config: WithdrawalConfig,
+        alerts: Optional[AlertManager] = None,
+        equity: Optional[EquityTracker] = None,
+    ) -> None:
+        self._connector = connector
+        self._config = config
+        self._alerts = alerts
+        self._equity = equity
+        self._withdrawals: Dict[str, PendingWithdrawal] = {}
+        self._submitted: Set[str] = set()  # venue withdrawal ids
+        self._lock = asyncio.Lock()
+        self._logger = get_logger(__name__)
+
+    def _allowed(self, symbol: str, blockchain: str, address: str) -> Optional[AllowedAddress]:
+        for entry in self._config.allowlist:
+            if entry.symbol == symbol and entry.blockchain.lower() == blockchain.lower() and entry.address == address:
+                return entry
+        return None
+
+    def _reject(self, reason: str, **info: Any) -> WithdrawalRejected:
+        self._logger.warning("withdrawal_rejected", extra={"venue": self._connector.venue, "reason": reason, **info})
+        return WithdrawalRejected(reason)
+
+    async def request(
+        self, *, symbol: str, blockchain: str, address: str, quantity: Decimal, requested_by: str = ""
+    ) -> PendingWithdrawal:
+        symbol = symbol.upper()
+        info = {"symbol": symbol, "blockchain": blockchain, "address": address, "quantity": str(quantity)}
+        if not self._config.enabled:
+            raise self._reject("withdrawals disabled", **info)
+        if quantity <= 0:
+            raise self._reject("quantity must be positive", **info)
+        allowed = self._allowed(symbol, blockchain, address)
+        if allowed is None:
+            raise self._reject("address not in allowlist", **info)
+        cap = self._config.max_quantity.get(symbol)
+        if cap is not None and quantity > cap:
+            raise self._reject(f"quantity above max_quantity {cap}", **info)
+        now = time.time()
+        pending = PendingWithdrawal(
+            withdrawal_id=secrets.token_hex(8),
+            symbol=symbol,
+            blockchain=allowed.blockchain,
+            address=address,
+            quantity=quantity,
+            label=allowed.label,
+            requested_by=requested_by,
+            created_at=now,
+            expires_at=now + self._config.confirm_ttl_secs,
+        )
+        async with self._lock:
+            self._withdrawals[pending.withdrawal_id] = pending
+        self._logger.info("withdrawal_requested", extra={"venue": self._connector.venue, **pending.to_dict()})
+        await self._notify(AlertLevel.WARNING, "Withdrawal awaiting confirmation", pending)
+        return pending
+
+    async def confirm(self, withdrawal_id: str) -> PendingWithdrawal:
+        async with self._lock:
+            pending = self._withdrawals.get(withdrawal_id)
+            if pending is None:
+                raise KeyError(withdrawal_id)
+            self._expire(pending)
+            if pending.status != WithdrawalStatus.PENDING:
+                raise WithdrawalRejected(f"withdrawal {withdrawal_id} is {pending.status.value}")
+            pending.status = WithdrawalStatus.SUBMITTED
+        try:
+            pending.response = await self._connector.request_withdrawal(
+                symbol=pending.symbol,
+                blockchain=pending.blockchain,
+                address=pending.address,
+                quantity=pending.quantity,
+            )
+        except Exception as exc:
+            pending.status = WithdrawalStatus.FAILED
+            pending.error = str(exc)
+            self._logger.warning("withdrawal_failed", extra={"venue": self._connector.venue, **pending.to_dict()})
+            await self._notify(AlertLevel.CRITICAL, "Withdrawal failed", pending)
+            return pending
+        venue_id = pending.response.get("id") if isinstance(pending.response, dict) else None
+        if venue_id is not None:
+            self._submitted.add(str(venue_id))
+        self._logger.info("withdrawal_submitted", extra={"venue": self._connector.venue, **pending.to_dict()})
+        self._record_flow(pending)
+        await self._notify(AlertLevel.WARNING, "Withdrawal submitted", pending)
+        return pending
+
+    async def cancel(self, withdrawal_id: str) -> PendingWithdrawal:
+        async with self._lock:
+            pending = self._withdrawals.get(withdrawal_id)
+            if pending is None:
+                raise KeyError(withdrawal_id)
+            self._expire(pending)
+            if pending.status == WithdrawalStatus.PENDING:
+                pending.status = WithdrawalStatus.CANCELLED
+                self._logger.info("withdrawal_cancelled", extra={"venue": self._connector.venue, "withdrawal_id": withdrawal_id})
+            return pending
+
+    def withdrawals(self) -> List[PendingWithdrawal]:
+        for pending in self._withdrawals.values():
+            self._expire(pending)
+        return sorted(self._withdrawals.values(), key=lambda p: p.created_at, reverse=True)
+
+    def submitted_ids(self) -> Set[str]:
+        return set(self._submitted)
+
+    def _record_flow(self, pending: PendingWithdrawal) -> None:
+        # Without the venue's id the history sync could not match it; it records the flow once the venue lists it
+        venue_id = pending.response.get("id") if isinstance(pending.response, dict) else None
+        if self._equity is None or venue_id is None:
+            return
+        amount = self._equity.value_flow(pending.symbol, pending.quantity)
+        if amount is None:
+            return
+        self._equity.record_flow(
+            venue=self._connector.venue,
+            asset=pending.symbol,
+            amount=-amount,
+            kind="withdrawal",
+            ref=flow_ref(self._connector.venue, "withdrawal", venue_id),
+        )
+
+    def _expire(self, pending: PendingWithdrawal) -> None:
+        if pending.status == WithdrawalStatus.PENDING and time.time() > pending.expires_at:
+            pending.status = WithdrawalStatus.EXPIRED
+
+    async def _notify(self, level: AlertLevel, title: str, pending: PendingWithdrawal) -> None:
+        if self._alerts is None:
+            return
+        body = f"{pending.quantity} {pending.symbol} -> {pending.label or pending.address} ({pending.blockchain})"
+        await self._alerts.notify(level, title, body, venue=self._connector.venue, withdrawal_id=pending.withdrawal_id)
+
+
+__all__ = [
+    "AllowedAddress",
+    "PendingWithdrawal",
+    "WithdrawalConfig",
+    "WithdrawalGuard",
+    "WithdrawalRejected",
+    "WithdrawalStatus",
+    "WithdrawalVenue",
+]
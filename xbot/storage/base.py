@@ -0,0 +1,213 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass
+from typing import Any, Dict, List, Mapping, Optional, Protocol, Sequence
+
+from ..core.metrics import METRICS
+from ..utils.logging import get_logger
+
+# Well-known tables shared by every backend
+ORDER_EVENTS = "order_events"
+FILLS = "fills"
+MARKET_SNAPSHOTS = "market_snapshots"
+FUNDING_PAYMENTS = "funding_payments"
+FUNDING_RATES = "funding_rates"
+EQUITY_SNAPSHOTS = "equity_snapshots"
+CAPITAL_FLOWS = "capital_flows"
+ACCOUNT_FINGERPRINTS = "account_fingerprints"
+AUDIT_LOG = "audit_log"
+STRATEGY_DECISIONS = "strategy_decisions"
+FILL_SNAPSHOTS = "fill_snapshots"
+
+
+@dataclass(slots=True)
+class StorageConfig:
+    backend: str = "jsonl"
+    root: str = "logs/storage"
+    dsn: Optional[str] = None
+    bot_id: str = "default"
+    timescale: bool = True
+    batch_size: int = 500
+    flush_interval_secs: float = 1.0
+    snapshot_interval_secs: float = 0.0
+    fill_snapshot_depth: int = 0  # >0 snapshots this many book levels per side on every fill
+    fill_snapshot_trades: int = 20  # recent trades kept in each fill snapshot
+    max_buffered_rows: int = 100_000  # rows kept for retry while the backend is failing; oldest dropped beyond
+    funding_payments_interval_secs: float = 300.0  # >0 polls the venue's funding payments into funding_payments
+
+
+class IStorage(Protocol):
+    """Append-mostly persistence contract used by recorders, reports and recovery."""
+
+    async def start(self) -> None:
+        """Open connections and create schemas when needed."""
+
+    async def stop(self) -> None:
+        """Release connections; pending writes must already be flushed."""
+
+    async def write(self, table: str, rows: Sequence[Mapping[str, Any]]) -> None:
+        """Persist a batch of rows. Each row carries at least `ts` (epoch seconds)."""
+
+    async def query(
+        self,
+        table: str,
+        *,
+        start: Optional[float] = None,
+        end: Optional[float] = None,
+        filters: Optional[Mapping[str, Any]] = None,
+        limit: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
+        """Return rows ordered by `ts` within [start, end) matching equality filters."""
+
+
+class BotScopedStorage:
+    """Read view of a backend limited to one bot's rows.
+
+    Every query is filtered on `bot_id` unless the caller filters on it
+    explicitly, so bots sharing a database never read each other's fills,
+    equity or orders. Writes pass through unchanged.
+    """
+
+    def __init__(self, storage: IStorage, bot_id: str) -> None:
+        self._storage = storage
+        self.bot_id = bot_id
+
+    async def start(self) -> None:
+        await self._storage.start()
+
+    async def stop(self) -> None:
+        await self._storage.stop()
+
+    async def write(self, table: str, rows: Sequence[Mapping[str, Any]]) -> None:
+        await self._storage.write(table, rows)
+
+    async def query(
+        self,
+        table: str,
+        *,
+        start: Optional[float] = None,
+        end: Optional[float] = None,
+        filters: Optional[Mapping[str, Any]] = None,
+        limit: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
+        scoped = {"bot_id": self.bot_id, **(filters or {})}
+        return await self._storage.query(table, start=start, end=end, filters=scoped, limit=limit)
+
+
+class StorageWriter:
+    """Buffers rows in memory and flushes them to a backend in batches.
+
+    `enqueue` never blocks or raises so it is safe from hot paths such as
+    `Order.apply_update`; a background task drains the buffer every
+    `flush_interval_secs` or once `batch_size` rows are pending. A batch the
+    backend fails to write goes back to the buffer for the next flush; past
+    `max_buffered_rows` the oldest rows are dropped and counted in
+    `storage_rows_dropped`. `storage` reads only this writer's `bot_id`.
+    """
+
+    def __init__(
+        self,
+        storage: IStorage,
+        *,
+        bot_id: str = "default",
+        batch_size: int = 500,
+        flush_interval_secs: float = 1.0,
+        max_buffered_rows: int = 100_000,
+    ) -> None:
+        self._storage = storage
+        self._reader = BotScopedStorage(storage, bot_id)
+        self._bot_id = bot_id
+        self._batch_size = batch_size
+        self._max_buffered = max_buffered_rows
+        self._interval = flush_interval_secs
+        self._pending: Dict[str, List[Dict[str, Any]]] = {}
+        self._count = 0
+        self._wakeup = asyncio.Event()
+        self._flush_lock = asyncio.Lock()
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def storage(self) -> IStorage:
+        return self._reader
+
+    @property
+    def pending_rows(self) -> int:
+        return self._count
+
+    def enqueue(self, table: str, row: Mapping[str, Any]) -> None:
+        record = dict(row)
+        record.setdefault("ts", time.time())
+        record.setdefault("bot_id", self._bot_id)
+        self._pending.setdefault(table, []).append(record)
+        self._count += 1
+        if self._count >= self._batch_size:
+            self._wakeup.set()
+
+    async def flush(self) -> None:
+        async with self._flush_lock:
+            pending, self._pending, self._count = self._pending, {}, 0
+            for table, rows in pending.items():
+                try:
+                    await self._storage.write(table, rows)
+                except Exception as exc:
+                    self._requeue(table, rows)
+                    self._logger.warning(
+                        "storage_write_error",
+                        extra={"table": table, "rows": len(rows), "pending": self._count, "error": str(exc)},
+                    )
+
+    def _requeue(self, table: str, rows: List[Dict[str, Any]]) -> None:
+        # Ahead of rows enqueued during the failed write, so each table stays in enqueue order
+        queue = self._pending[table] = rows + self._pending.get(table, [])
+        self._count += len(rows)
+        excess = self._count - self._max_buffered
+        if excess > 0:
+            dropped = min(excess, len(queue))
+            del queue[:dropped]
+            self._count -= dropped
+            METRICS.inc("storage_rows_dropped", dropped, table=table)
+
+    async def start(self) -> None:
+        if self._task is not None:
+            return
+        await self._storage.start()
+        self._task = asyncio.create_task(self._run(), name="storage-writer")
+
+    async def stop(self) -> None:
+        if self._task is not None:
+            self._task.cancel()
+            with contextlib.suppress(asyncio.CancelledError):
+                await self._task
+            self._task = None
+        await self.flush()
+        await self._storage.stop()
+
+    async def _run(self) -> None:
+        while True:
+            with contextlib.suppress(asyncio.TimeoutError):
+                await asyncio.wait_for(self._wakeup.wait(), timeout=self._interval)
+            self._wakeup.clear()
+            await self.flush()
+
+
+__all__ = [
+    "BotScopedStorage",
+    "IStorage",
+    "StorageConfig",
+    "StorageWriter",
+    "ORDER_EVENTS",
+    "FILLS",
+    "MARKET_SNAPSHOTS",
+    "FUNDING_PAYMENTS",
+    "FUNDING_RATES",
+    "EQUITY_SNAPSHOTS",
+    "CAPITAL_FLOWS",
+    "ACCOUNT_FINGERPRINTS",
+    "AUDIT_LOG",
+    "STRATEGY_DECISIONS",
+    "FILL_SNAPSHOTS",
+]
@@ -0,0 +1,36 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+from .base import BotScopedStorage, IStorage, StorageConfig, StorageWriter
+
+
+def build_storage(config: StorageConfig) -> IStorage:
+    backend = config.backend.lower()
+    if backend == "jsonl":
+        from .jsonl import JsonlStorage
+        return JsonlStorage(Path(config.root))
+    if backend in {"postgres", "postgresql", "timescale"}:
+        if not config.dsn:
+            raise ValueError("storage.dsn is required for the postgres backend")
+        from .postgres import PostgresStorage
+        return PostgresStorage(config.dsn, timescale=config.timescale or backend == "timescale")
+    raise ValueError(f"unsupported storage backend {config.backend}")
+
+
+def build_reader(config: StorageConfig) -> IStorage:
+    """The backend scoped to `config.bot_id`, for tools that only read."""
+    return BotScopedStorage(build_storage(config), config.bot_id)
+
+
+def build_writer(config: StorageConfig) -> StorageWriter:
+    return StorageWriter(
+        build_storage(config),
+        bot_id=config.bot_id,
+        batch_size=config.batch_size,
+        flush_interval_secs=config.flush_interval_secs,
+        max_buffered_rows=config.max_buffered_rows,
+    )
+
+
+__all__ = ["build_reader", "build_storage", "build_writer"]
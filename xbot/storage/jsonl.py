@@ -0,0 +1,65 @@
+from __future__ import annotations
+
+import asyncio
+import json
+from pathlib import Path
+from typing import Any, Dict, List, Mapping, Optional, Sequence
+
+
+class JsonlStorage:
+    """Default single-bot backend: one append-only `<table>.jsonl` file per table."""
+
+    def __init__(self, root: Path) -> None:
+        self._root = root
+        self._lock = asyncio.Lock()
+
+    async def start(self) -> None:
+        self._root.mkdir(parents=True, exist_ok=True)
+
+    async def stop(self) -> None:
+        return None
+
+    def _path(self, table: str) -> Path:
+        return self._root / f"{table}.jsonl"
+
+    async def write(self, table: str, rows: Sequence[Mapping[str, Any]]) -> None:
+        if not rows:
+            return
+        lines = "".join(json.dumps(row, ensure_ascii=True, default=str) + "\n" for row in rows)
+        async with self._lock:
+            with self._path(table).open("a", encoding="utf-8") as handle:
+                handle.write(lines)
+
+    async def query(
+        self,
+        table: str,
+        *,
+        start: Optional[float] = None,
+        end: Optional[float] = None,
+        filters: Optional[Mapping[str, Any]] = None,
+        limit: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
+        path = self._path(table)
+        if not path.exists():
+            return []
+        rows: List[Dict[str, Any]] = []
+        async with self._lock:
+            text = path.read_text(encoding="utf-8")
+        for line in text.splitlines():
+            try:
+                row = json.loads(line)
+            except Exception:
+                continue
+            ts = float(row.get("ts") or 0.0)
+            if start is not None and ts < start:
+                continue
+            if end is not None and ts >= end:
+                continue
+            if filters and any(row.get(k) != v for k, v in filters.items()):
+                continue
+            rows.append(row)
+        rows.sort(key=lambda r: float(r.get("ts") or 0.0))
+        return rows[:limit] if limit is not None else rows
+
+
+__all__ = ["JsonlStorage"]
@@ -0,0 +1,202 @@
+from __future__ import annotations
+
+import json
+from datetime import datetime, timezone
+from decimal import Decimal
+from typing import Any, Dict, List, Mapping, Optional, Sequence, Tuple
+
+from .base import (
+    ACCOUNT_FINGERPRINTS,
+    AUDIT_LOG,
+    CAPITAL_FLOWS,
+    EQUITY_SNAPSHOTS,
+    FILLS,
+    FILL_SNAPSHOTS,
+    FUNDING_PAYMENTS,
+    FUNDING_RATES,
+    MARKET_SNAPSHOTS,
+    ORDER_EVENTS,
+    STRATEGY_DECISIONS,
+)
+from ..utils.logging import get_logger
+
+# Columns promoted out of the JSON payload so they can be indexed and aggregated.
+# `ts` leads every table and nothing is unique without it, which keeps the schema
+# compatible with TimescaleDB hypertables partitioned on `ts`.
+_COMMON: List[Tuple[str, str]] = [
+    ("ts", "TIMESTAMPTZ NOT NULL"),
+    ("bot_id", "TEXT NOT NULL"),
+    ("venue", "TEXT"),
+    ("symbol", "TEXT"),
+]
+_EXTRA: Dict[str, List[Tuple[str, str]]] = {
+    ORDER_EVENTS: [("client_order_index", "BIGINT"), ("state", "TEXT")],
+    FILLS: [
+        ("client_order_index", "BIGINT"),
+        ("side", "TEXT"),
+        ("price", "NUMERIC"),
+        ("size", "NUMERIC"),
+        ("fee", "NUMERIC"),
+    ],
+    MARKET_SNAPSHOTS: [("bid", "NUMERIC"), ("ask", "NUMERIC")],
+    FUNDING_PAYMENTS: [("amount", "NUMERIC")],
+    FUNDING_RATES: [("rate", "NUMERIC")],
+    EQUITY_SNAPSHOTS: [("equity", "NUMERIC")],
+    CAPITAL_FLOWS: [("amount", "NUMERIC")],
+    ACCOUNT_FINGERPRINTS: [("account_id", "TEXT"), ("equity", "NUMERIC")],
+    AUDIT_LOG: [("kind", "TEXT"), ("command_id", "TEXT"), ("trace_id", "TEXT"), ("client_order_index", "BIGINT")],
+    STRATEGY_DECISIONS: [("variant", "TEXT"), ("action", "TEXT"), ("result", "TEXT")],
+    FILL_SNAPSHOTS: [
+        ("client_order_index", "BIGINT"),
+        ("side", "TEXT"),
+        ("price", "NUMERIC"),
+        ("size", "NUMERIC"),
+        ("bid", "NUMERIC"),
+        ("ask", "NUMERIC"),
+    ],
+}
+_NUMERIC = {"price", "size", "fee", "bid", "ask", "amount", "equity", "rate"}
+_INTEGER = {"client_order_index"}
+
+
+def _columns(table: str) -> List[Tuple[str, str]]:
+    return _COMMON + _EXTRA.get(table, []) + [("payload", "JSONB")]
+
+
+def _to_column(name: str, value: Any) -> Any:
+    if value is None:
+        return None
+    if name == "ts":
+        return datetime.fromtimestamp(float(value), tz=timezone.utc)
+    if name in _NUMERIC:
+        return Decimal(str(value))
+    if name in _INTEGER:
+        return int(value)
+    return str(value)
+
+
+class PostgresStorage:
+    """PostgreSQL/TimescaleDB backend for deployments running several bots.
+
+    Rows are tagged with `bot_id`, written with COPY in batches, and every table
+    becomes a hypertable on `ts` when the timescaledb extension is available.
+    Requires the optional `asyncpg` package.
+    """
+
+    def __init__(self, dsn: str, *, timescale: bool = True, pool_size: int = 4) -> None:
+        self._dsn = dsn
+        self._timescale = timescale
+        self._pool_size = pool_size
+        self._pool: Any = None
+        self._ready: set[str] = set()
+        self._logger = get_logger(__name__)
+
+    async def start(self) -> None:
+        if self._pool is not None:
+            return
+        try:
+            import asyncpg  # type: ignore
+        except Exception as exc:  # pragma: no cover - optional dependency
+            raise RuntimeError("asyncpg not available; install asyncpg to use the postgres storage backend") from exc
+        self._pool = await asyncpg.create_pool(self._dsn, min_size=1, max_size=self._pool_size)
+        if self._timescale:
+            async with self._pool.acquire() as conn:
+                try:
+                    await conn.execute("CREATE EXTENSION IF NOT EXISTS timescaledb")
+                except Exception as exc:
+                    self._timescale = False
+                    self._logger.info("timescale_unavailable", extra={"error": str(exc)})
+        for table in _EXTRA:
+            await self._ensure_table(table)
+
+    async def stop(self) -> None:
+        if self._pool is not None:
+            await self._pool.close()
+            self._pool = None
+
+    async def _ensure_table(self, table: str) -> None:
+        if table in self._ready:
+            return
+        cols = ", ".join(f"{name} {kind}" for name, kind in _columns(table))
+        async with self._pool.acquire() as conn:
+            await conn.execute(f"CREATE TABLE IF NOT EXISTS {table} ({cols})")
+            if self._timescale:
+                await conn.execute(
+                    f"SELECT create_hypertable('{table}', 'ts', if_not_exists => TRUE, migrate_data => TRUE)"
+                )
+            await conn.execute(
+                f"CREATE INDEX IF NOT EXISTS {table}_symbol_ts_idx ON {table} (bot_id, symbol, ts DESC)"
+            )
+        self._ready.add(table)
+
+    async def write(self, table: str, rows: Sequence[Mapping[str, Any]]) -> None:
+        if not rows:
+            return
+        if self._pool is None:
+            raise RuntimeError("postgres storage not started")
+        await self._ensure_table(table)
+        columns = [name for name, _ in _columns(table)]
+        promoted = set(columns) - {"payload"}
+        records = []
+        for row in rows:
+            extra = {k: v for k, v in row.items() if k not in promoted}
+            values = [_to_column(name, row.get(name)) for name in columns[:-1]]
+            values.append(json.dumps(extra, ensure_ascii=True, default=str))
+            records.append(tuple(values))
+        async with self._pool.acquire() as conn:
+            await conn.copy_records_to_table(table, records=records, columns=columns)
+
+    async def query(
+        self,
+        table: str,
+        *,
+        start: Optional[float] = None,
+        end: Optional[float] = None,
+        filters: Optional[Mapping[str, Any]] = None,
+        limit: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
+        if self._pool is None:
+            raise RuntimeError("postgres storage not started")
+        await self._ensure_table(table)
+        columns = [name for name, _ in _columns(table)]
+        clauses: List[str] = []
+        args: List[Any] = []
+        if start is not None:
+            args.append(_to_column("ts", start))
+            clauses.append(f"ts >= ${len(args)}")
+        if end is not None:
+            args.append(_to_column("ts", end))
+            clauses.append(f"ts < ${len(args)}")
+        for key, value in (filters or {}).items():
+            if key in columns:
+                args.append(_to_column(key, value))
+                clauses.append(f"{key} = ${len(args)}")
+            else:
+                args.append(json.dumps({key: value}, default=str))
+                clauses.append(f"payload @> ${len(args)}::jsonb")
+        sql = f"SELECT {', '.join(columns)} FROM {table}"
+        if clauses:
+            sql += " WHERE " + " AND ".join(clauses)
+        sql += " ORDER BY ts"
+        if limit is not None:
+            sql += f" LIMIT {int(limit)}"
+        async with self._pool.acquire() as conn:
+            records = await conn.fetch(sql, *args)
+        rows: List[Dict[str, Any]] = []
+        for record in records:
+            payload = record["payload"]
+            row: Dict[str, Any] = json.loads(payload) if isinstance(payload, str) else dict(payload or {})
+            for name in columns[:-1]:
+                value = record[name]
+                if value is None:
+                    continue
+                if name == "ts":
+                    value = value.timestamp()
+                elif isinstance(value, Decimal):
+                    value = str(value)
+                row[name] = value
+            rows.append(row)
+        return rows
+
+
+__all__ = ["PostgresStorage"]
@@ -0,0 +1,190 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from typing import Any, Awaitable, Callable, Dict, List, Mapping, Optional, Set
+
+from xbot.connector.history import timestamp_ms
+from xbot.core.cache import MarketCache
+from xbot.utils.logging import get_logger
+
+from .base import FILL_SNAPSHOTS, FUNDING_PAYMENTS, MARKET_SNAPSHOTS, StorageWriter
+
+
+class MarketSnapshotRecorder:
+    """Periodically copies top-of-book from `MarketCache` into the market_snapshots table."""
+
+    def __init__(self, *, cache: MarketCache, writer: StorageWriter, venue: str, interval_secs: float = 5.0) -> None:
+        self._cache = cache
+        self._writer = writer
+        self._venue = venue
+        self._interval = interval_secs
+        self._task: Optional[asyncio.Task] = None
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="snapshot-recorder")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+        # Final snapshot so the tail of the session is not lost between ticks
+        await self.record_once()
+
+    async def record_once(self) -> None:
+        for symbol, (bid, ask, ts) in list(self._cache.orderbooks.items()):
+            self._writer.enqueue(
+                MARKET_SNAPSHOTS,
+                {"ts": ts, "venue": self._venue, "symbol": symbol, "bid": bid, "ask": ask},
+            )
+
+    async def _run(self) -> None:
+        while True:
+            await self.record_once()
+            await asyncio.sleep(self._interval)
+
+
+class FillSnapshotRecorder:
+    """Writes the book around every fill to fill_snapshots, for offline execution analysis.
+
+    `capture` runs as the fill update is handled, so the book is the one the
+    feed shows right after the fill: the top `depth` levels per side where
+    the venue streams full depth (`cache.books`), else the cached touch. It
+    also keeps the last `trades` prints and, when queue tracking follows
+    the order, the queue estimate at the moment of the fill, so both
+    "did we cross when we did not need to" and "was the queue estimate
+    right" can be answered afterwards.
+    """
+
+    def __init__(self, *, cache: MarketCache, writer: StorageWriter, depth: int = 5, trades: int = 20) -> None:
+        self._cache = cache
+        self._writer = writer
+        self._depth = depth
+        self._trades = trades
+
+    def snapshot(self, venue_symbol: str) -> Dict[str, Any]:
+        row: Dict[str, Any] = {"venue_symbol": venue_symbol}
+        book = self._cache.books.get(venue_symbol)
+        if book is not None:
+            row["bids"] = [list(level) for level in book.levels("bids", self._depth)]
+            row["asks"] = [list(level) for level in book.levels("asks", self._depth)]
+            row["bid"], row["ask"], row["book_ts"] = book.best_bid, book.best_ask, book.ts
+        else:
+            bid, ask, ts = self._cache.orderbooks.get(venue_symbol, (None, None, 0.0))
+            row["bid"], row["ask"], row["book_ts"] = bid, ask, ts or None
+        trades = list(self._cache.trades.get(venue_symbol, ()))
+        row["trades"] = trades[-self._trades :] if self._trades > 0 else []
+        return row
+
+    def capture(self, fill: Mapping[str, Any], *, venue_symbol: str, queue: Optional[Mapping[str, Any]] = None) -> None:
+        row = {**fill, **self.snapshot(venue_symbol)}
+        row.setdefault("ts", time.time())
+        if queue is not None:
+            row["queue"] = dict(queue)
+        self._writer.enqueue(FILL_SNAPSHOTS, row)
+
+
+def payment_id(row: Mapping[str, Any]) -> str:
+    """The venue's id for a funding payment, else symbol and interval end, which are unique per account."""
+    if row.get("id") is not None:
+        return str(row["id"])
+    return f"{row.get('symbol')}:{row.get('intervalEndTimestamp')}"
+
+
+class FundingPaymentRecorder:
+    """Polls the venue's funding payment history into the funding_payments table.
+
+    Pages are read newest first until one holds a payment that is already
+    stored (or `max_pages` is reached on a first run), and every payment is
+    written once, keyed on `payment_id`. Rows carry the canonical symbol
+    from `canonical` so the trade journal credits them to the matching fills.
+    """
+
+    def __init__(
+        self,
+        *,
+        fetch: Callable[..., Awaitable[List[Dict[str, Any]]]],
+        writer: StorageWriter,
+        venue: str,
+        canonical: Optional[Callable[[str], Optional[str]]] = None,
+        interval_secs: float = 300.0,
+        page_size: int = 100,
+        max_pages: int = 10,
+    ) -> None:
+        self._fetch = fetch
+        self._writer = writer
+        self._venue = venue
+        self._canonical = canonical
+        self._interval = interval_secs
+        self._page_size = page_size
+        self._max_pages = max_pages
+        self._known: Optional[Set[str]] = None
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    async def _known_ids(self) -> Set[str]:
+        if self._known is None:
+            rows = await self._writer.storage.query(FUNDING_PAYMENTS, filters={"venue": self._venue})
+            self._known = {str(row["payment_id"]) for row in rows if row.get("payment_id") is not None}
+        return self._known
+
+    async def record_once(self) -> int:
+        """Store payments not seen before; returns how many were written."""
+        known = await self._known_ids()
+        added = 0
+        for page_no in range(self._max_pages):
+            page = await self._fetch(limit=self._page_size, offset=page_no * self._page_size)
+            caught_up = False
+            for payment in page:
+                pid = payment_id(payment)
+                if pid in known:
+                    caught_up = True
+                    continue
+                ended = timestamp_ms(payment.get("intervalEndTimestamp"))
+                venue_symbol = str(payment.get("symbol") or "")
+                symbol = (self._canonical(venue_symbol) if self._canonical else None) or venue_symbol
+                self._writer.enqueue(
+                    FUNDING_PAYMENTS,
+                    {
+                        "ts": ended / 1000 if ended is not None else time.time(),
+                        "venue": self._venue,
+                        "symbol": symbol,
+                        "venue_symbol": venue_symbol,
+                        "amount": payment.get("quantity"),
+                        "rate": payment.get("fundingRate"),
+                        "payment_id": pid,
+                    },
+                )
+                known.add(pid)
+                added += 1
+            if caught_up or len(page) < self._page_size:
+                break
+        return added
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="funding-payment-recorder")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.record_once()
+            except Exception as exc:
+                self._logger.info("funding_payments_poll_error", extra={"venue": self._venue, "error": str(exc)})
+            await asyncio.sleep(self._interval)
+
+
+__all__ = ["FillSnapshotRecorder", "FundingPaymentRecorder", "MarketSnapshotRecorder", "payment_id"]
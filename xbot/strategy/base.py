@@ -1,10 +1,19 @@
 from __future__ import annotations
 
+import asyncio
+import contextlib
+import secrets
 from dataclasses import dataclass
-from typing import Optional
+from decimal import Decimal
+from typing import Dict, Optional, Set, Tuple
 
+from xbot.analytics.kelly import KellyService
 from xbot.core.clock import WallClock
+from xbot.core.latency import LATENCY, REST, WS
+from xbot.execution.models import Order
 from xbot.execution.router import ExecutionRouter
+from xbot.execution.sizing import FIXED, VOLATILITY, VolatilitySizer
+from xbot.utils.logging import get_logger
 
 
 @dataclass(slots=True)
@@ -17,14 +26,36 @@ class StrategyConfig:
     price_offset_ticks: int = 0
     interval_secs: float = 10.0
     timeout_secs: float = 120.0
+    max_latency_ms: Optional[float] = None  # p95 WS one-way or REST round trip; None disables the gate
+    name: str = ""  # tags this strategy's orders and fills (trace_id prefix); defaults to mode
+    sizing: str = FIXED  # fixed: qty; volatility: the VolatilitySizer picks the size
+
+
+class StrategyPaused(RuntimeError):
+    pass
 
 
 class Strategy:
-    def __init__(self, *, router: ExecutionRouter, clock: WallClock, config: StrategyConfig) -> None:
+    def __init__(
+        self,
+        *,
+        router: ExecutionRouter,
+        clock: WallClock,
+        config: StrategyConfig,
+        sizer: Optional[VolatilitySizer] = None,
+        kelly: Optional[KellyService] = None,
+    ) -> None:
+        if config.sizing == VOLATILITY and sizer is None:
+            raise ValueError("volatility sizing needs a VolatilitySizer")
         self._router = router
         self._clock = clock
         self._config = config
+        self._sizer = sizer
+        self._kelly = kelly
         self._running = False
+        self._pause_reason: Optional[str] = None
+        self._cooldowns: Dict[str, Tuple[float, str]] = {}  # symbol -> (until, reason)
+        self._adopted: Set[asyncio.Task] = set()
 
     @property
     def router(self) -> ExecutionRouter:
@@ -38,11 +69,131 @@ class Strategy:
     def config(self) -> StrategyConfig:
         return self._config
 
+    @property
+    def name(self) -> str:
+        return self._config.name or self._config.mode
+
+    @property
+    def paused(self) -> bool:
+        return self._pause_reason is not None
+
+    @property
+    def pause_reason(self) -> Optional[str]:
+        return self._pause_reason
+
+    def pause(self, reason: str) -> None:
+        self._pause_reason = reason
+
+    def resume(self) -> None:
+        self._pause_reason = None
+
+    def cool_down(self, symbol: str, until: float, reason: str) -> None:
+        """Block new entries on `symbol` until the wall-clock time `until`."""
+        self._cooldowns[symbol.upper()] = (until, reason)
+
+    def cooldown(self, symbol: str) -> Optional[Tuple[float, str]]:
+        entry = self._cooldowns.get(symbol.upper())
+        if entry is not None and entry[0] <= self._clock.now():
+            del self._cooldowns[symbol.upper()]
+            return None
+        return entry
+
+    def trace_id(self) -> str:
+        """Fresh trace for one order, prefixed with the strategy name so fills attribute back to it."""
+        return f"{self.name}:{secrets.token_hex(4)}"
+
+    def ensure_active(self, *, symbol: Optional[str] = None, reduce_only: int = 0) -> None:
+        """Raise `StrategyPaused` before an opening order while paused or while `symbol` cools down.
+
+        Reducing orders always pass.
+        """
+        if reduce_only:
+            return
+        if self._pause_reason is not None:
+            raise StrategyPaused(f"strategy {self.name} is paused: {self._pause_reason}")
+        cooldown = self.cooldown(symbol) if symbol else None
+        if cooldown is not None:
+            remaining = cooldown[0] - self._clock.now()
+            raise StrategyPaused(f"{symbol} is cooling down for {remaining:.0f}s: {cooldown[1]}")
+
+    async def order_qty(self) -> Decimal:
+        """Size of the next entry.
+
+        The base is `config.qty`, or the volatility-based size when the
+        strategy opted in. A `KellyService` scales it by this strategy's
+        fractional-Kelly multiplier, and the result is capped at what the
+        risk limits still accept.
+        """
+        if self._config.sizing == VOLATILITY and self._sizer is not None:
+            qty = await self._sizer.size(self._config.symbol)
+        else:
+            qty = Decimal(str(self._config.qty))
+        if self._kelly is None:
+            return qty
+        multiplier = self._kelly.multiplier(self.name)
+        if multiplier <= 0:
+            raise StrategyPaused(f"strategy {self.name} shows no edge over its recent trades; Kelly size is 0")
+        qty *= multiplier
+        cap = await self._router.risk.max_order_size(self._config.symbol, is_ask=self._config.side == "sell")
+        return qty if cap is None else min(qty, cap)
+
+    def latency_ok(self) -> bool:
+        """False while the venue's p95 WS or REST latency is above `config.max_latency_ms`."""
+        limit = self._config.max_latency_ms
+        if limit is None:
+            return True
+        venue = self._router.orders.venue
+        return not any(LATENCY.exceeds(venue, kind, limit) for kind in (WS, REST))
+
+    async def wait_for_latency(self, poll_secs: float = 1.0) -> None:
+        """Block until `latency_ok()`; latency-sensitive strategies call this before placing orders."""
+        if self.latency_ok():
+            return
+        get_logger(__name__).info(
+            "strategy_latency_gated",
+            extra={"symbol": self._config.symbol, "max_latency_ms": self._config.max_latency_ms},
+        )
+        while not self.latency_ok():
+            await self._clock.sleep(poll_secs)
+
+    async def adopt(self, order: Order) -> None:
+        """Resume an order this strategy placed before a restart.
+
+        The strategy lost its place in the order's lifecycle, so the order
+        gets `timeout_secs` to finish, as a fresh one would, and is cancelled
+        if still open after that.
+        """
+        task = asyncio.create_task(self._expire_adopted(order), name=f"{self.name}-adopted-{order.client_order_index}")
+        self._adopted.add(task)
+        task.add_done_callback(self._adopted.discard)
+
+    async def _expire_adopted(self, order: Order) -> None:
+        try:
+            await order.wait_final(self._config.timeout_secs)
+            return
+        except asyncio.TimeoutError:
+            pass
+        get_logger(__name__).info(
+            "strategy_adopted_order_expired",
+            extra={"strategy": self.name, "symbol": order.symbol, "coi": order.client_order_index},
+        )
+        try:
+            await self._router.orders.cancel(order.symbol, order.client_order_index)
+        except Exception as exc:
+            get_logger(__name__).info(
+                "strategy_adopted_cancel_error",
+                extra={"strategy": self.name, "coi": order.client_order_index, "error": str(exc)},
+            )
+
     async def start(self) -> None:
         self._running = True
 
     async def stop(self) -> None:
         self._running = False
+        for task in list(self._adopted):
+            task.cancel()
+            with contextlib.suppress(asyncio.CancelledError):
+                await task
 
 
-__all__ = ["Strategy", "StrategyConfig"]
+__all__ = ["Strategy", "StrategyConfig", "StrategyPaused"]
@@ -0,0 +1,252 @@
+from __future__ import annotations
+
+import json
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Any, Dict, List, Optional, Sequence, Tuple
+
+from xbot.analytics.kelly import KellyService
+from xbot.core.clock import WallClock
+from xbot.execution.candles import Candle, CandleService, average_true_range
+from xbot.execution.risk_service import RiskViolationError
+from xbot.execution.router import ExecutionRouter
+from xbot.execution.sizing import SizingUnavailable, VolatilitySizer
+from xbot.execution.stops import StopOrder
+from xbot.utils.logging import get_logger
+from .base import Strategy, StrategyConfig, StrategyPaused
+
+
+@dataclass(slots=True)
+class BreakoutConfig:
+    channel: int = 20  # candles in the Donchian entry channel, before the breakout candle
+    exit_channel: Optional[int] = 10  # close when price breaks the opposite side of this shorter channel
+    atr_period: int = 14
+    stop_atr: Optional[Decimal] = None  # initial stop distance in ATRs; None: the sizer's atr_multiple, else 2
+    trail_atr: Optional[Decimal] = Decimal(3)  # trailing distance in ATRs; None keeps the initial stop fixed
+    allow_short: bool = True
+
+
+def donchian(candles: Sequence[Candle], channel: int) -> Optional[Tuple[Decimal, Decimal]]:
+    """(high, low) of the `channel` candles before the last one; None until there are enough."""
+    if channel <= 0 or len(candles) < channel + 1:
+        return None
+    window = candles[-channel - 1 : -1]
+    return max(c.high for c in window), min(c.low for c in window)
+
+
+class BreakoutStrategy(Strategy):
+    """Second reference strategy: Donchian channel breakout with ATR stops.
+
+    A close above the high of the previous `channel` candles enters long,
+    a close below their low enters short (unless `allow_short` is off).
+    Entries are market orders, since a breakout that has to wait for a
+    passive fill is usually gone. Every entry gets a `StopOrder` `stop_atr`
+    ATRs away, trailing by `trail_atr` ATRs as price moves in its favour,
+    and the trade also closes on a break of the shorter `exit_channel`.
+
+    Sizing goes through `order_qty()`. With `sizing.strategy` on, the
+    `VolatilitySizer` sizes for a stop `atr_multiple` ATRs away, and the
+    strategy places its stop at that same distance, so the loss at the
+    stop is the configured share of equity. Stops are held by the bot
+    (see `StopOrder`) and checked against the touch every
+    `config.interval_secs`; they fire as reduce-only market orders, which
+    pass while the strategy is paused. The stop is saved beside the order
+    logs as it moves, so a restart resumes it where it had trailed to; a
+    position found without one gets one from the current price.
+    """
+
+    def __init__(
+        self,
+        *,
+        router: ExecutionRouter,
+        clock: WallClock,
+        config: StrategyConfig,
+        candles: CandleService,
+        params: Optional[BreakoutConfig] = None,
+        sizer: Optional[VolatilitySizer] = None,
+        kelly: Optional[KellyService] = None,
+    ) -> None:
+        super().__init__(router=router, clock=clock, config=config, sizer=sizer, kelly=kelly)
+        self._candles = candles
+        self._params = params or BreakoutConfig()
+        self._stop: Optional[StopOrder] = None
+        self._stop_path = router.orders.log_root.parent / "stops" / f"{self.name}-{config.symbol}.json"
+        self._last_signal: Optional[float] = None  # start of the candle last acted on
+        self._logger = get_logger(__name__)
+
+    @property
+    def params(self) -> BreakoutConfig:
+        return self._params
+
+    @property
+    def stop(self) -> Optional[StopOrder]:
+        return self._stop
+
+    def _keep_stop(self, stop: Optional[StopOrder]) -> None:
+        """Set the stop and save it, so a restart does not reset a trailed trigger."""
+        self._stop = stop
+        try:
+            if stop is None:
+                self._stop_path.unlink(missing_ok=True)
+                return
+            self._stop_path.parent.mkdir(parents=True, exist_ok=True)
+            self._stop_path.write_text(json.dumps(stop.to_dict()), encoding="utf-8")
+        except OSError as exc:
+            self._logger.info("breakout_stop_save_error", extra={"symbol": self.config.symbol, "error": str(exc)})
+
+    def _load_stop(self) -> Optional[StopOrder]:
+        try:
+            stop = StopOrder.from_dict(json.loads(self._stop_path.read_text(encoding="utf-8")))
+        except FileNotFoundError:
+            return None
+        except (OSError, ValueError, KeyError, ArithmeticError) as exc:
+            self._logger.info("breakout_stop_load_error", extra={"symbol": self.config.symbol, "error": str(exc)})
+            return None
+        self._logger.info("breakout_stop_restored", extra=stop.to_dict())
+        return stop
+
+    def _closed(self) -> List[Candle]:
+        venue_symbol = self.router.market_data.resolve_symbol(self.config.symbol)
+        return self._candles.candles(venue_symbol)
+
+    def _stop_atr(self) -> Decimal:
+        if self._params.stop_atr is not None:
+            return self._params.stop_atr
+        return self._sizer.config.atr_multiple if self._sizer is not None else Decimal(2)
+
+    async def _touch(self) -> Tuple[Optional[Decimal], Optional[Decimal]]:
+        bid_i, ask_i, scale = await self.router.market_data.get_top_of_book(self.config.symbol)
+        bid = Decimal(bid_i) / Decimal(scale) if bid_i else None
+        ask = Decimal(ask_i) / Decimal(scale) if ask_i else None
+        return bid, ask
+
+    async def position(self) -> Decimal:
+        snapshot = await self.router.positions.get_position(self.config.symbol)
+        return snapshot.base_qty if snapshot else Decimal(0)
+
+    def _new_stop(self, is_long: bool, price: Decimal, atr: Decimal) -> StopOrder:
+        distance = atr * self._stop_atr()
+        trail = atr * self._params.trail_atr if self._params.trail_atr is not None else None
+        trigger = price - distance if is_long else price + distance
+        return StopOrder(symbol=self.config.symbol, is_ask=is_long, trigger=trigger, trail=trail, extreme=price)
+
+    def signal(self, candles: Sequence[Candle]) -> Optional[bool]:
+        """`is_ask` of the entry the last closed candle calls for, or None."""
+        channel = donchian(candles, self._params.channel)
+        if channel is None:
+            return None
+        close = candles[-1].close
+        if close > channel[0]:
+            return False
+        if close < channel[1] and self._params.allow_short:
+            return True
+        return None
+
+    def exit_signal(self, candles: Sequence[Candle], position: Decimal) -> bool:
+        if self._params.exit_channel is None:
+            return False
+        channel = donchian(candles, self._params.exit_channel)
+        if channel is None:
+            return False
+        close = candles[-1].close
+        return close < channel[1] if position > 0 else close > channel[0]
+
+    async def step(self) -> Optional[str]:
+        """One pass: check the stop, then the exit channel, then a new entry; returns the action taken."""
+        candles = self._closed()
+        atr = average_true_range(candles, self._params.atr_period)
+        position = await self.position()
+        bid, ask = await self._touch()
+        if position:
+            if self._stop is None or self._stop.is_ask != (position > 0):
+                price = bid if position > 0 else ask
+                if price is None or atr is None:
+                    return None
+                self._keep_stop(self._new_stop(position > 0, price, atr))
+                self._logger.info("breakout_stop_adopted", extra=self._stop.to_dict())
+            mark = bid if position > 0 else ask
+            if mark is not None:
+                trigger = self._stop.trigger
+                if self._stop.update(mark) != trigger:
+                    self._keep_stop(self._stop)
+            if self._stop.triggered(bid, ask):
+                await self._exit(position, "stop")
+                return "stop"
+            if candles and candles[-1].start != self._last_signal and self.exit_signal(candles, position):
+                self._last_signal = candles[-1].start
+                await self._exit(position, "exit_channel")
+                return "exit"
+            return None
+        if self._stop is not None:
+            self._keep_stop(None)
+        if not candles or candles[-1].start == self._last_signal or atr is None:
+            return None
+        is_ask = self.signal(candles)
+        if is_ask is None:
+            return None
+        self._last_signal = candles[-1].start
+        price = bid if is_ask else ask
+        if price is None:
+            return None
+        return await self._enter(is_ask, price, atr)
+
+    async def _enter(self, is_ask: bool, price: Decimal, atr: Decimal) -> Optional[str]:
+        symbol = self.config.symbol
+        try:
+            self.ensure_active(symbol=symbol)
+            qty = await self.order_qty()
+            cap = await self.router.risk.max_order_size(symbol, is_ask=is_ask)
+            if cap is not None:
+                qty = min(qty, cap)
+            size_i = await self.router.market_data.to_size_i(symbol, qty)
+            if size_i <= 0:
+                return None
+            await self.wait_for_latency()
+            await self.router.submit_market(symbol=symbol, is_ask=is_ask, size_i=size_i, trace_id=self.trace_id())
+        except (StrategyPaused, RiskViolationError, SizingUnavailable) as exc:
+            self._logger.info("breakout_entry_blocked", extra={"symbol": symbol, "reason": str(exc)})
+            return None
+        self._keep_stop(self._new_stop(not is_ask, price, atr))
+        action = "enter_short" if is_ask else "enter_long"
+        self._logger.info(
+            "breakout_entry",
+            extra={
+                "symbol": symbol,
+                "action": action,
+                "price": str(price),
+                "atr": str(atr),
+                "size_i": size_i,
+                "stop": str(self._stop.trigger),
+            },
+        )
+        return action
+
+    async def _exit(self, position: Decimal, reason: str) -> None:
+        symbol = self.config.symbol
+        size_i = await self.router.market_data.to_size_i(symbol, abs(position))
+        try:
+            await self.router.submit_market(
+                symbol=symbol, is_ask=position > 0, size_i=size_i, reduce_only=1, trace_id=self.trace_id()
+            )
+        except RiskViolationError as exc:
+            self._logger.info("breakout_exit_blocked", extra={"symbol": symbol, "reason": str(exc)})
+            return
+        stop = self._stop.to_dict() if self._stop else None
+        self._keep_stop(None)
+        self._logger.info("breakout_exit", extra={"symbol": symbol, "reason": reason, "size_i": size_i, "stop": stop})
+
+    def snapshot(self) -> Dict[str, Any]:
+        return {"stop": self._stop.to_dict() if self._stop else None, "last_signal": self._last_signal}
+
+    async def start(self) -> None:
+        await super().start()
+        self._stop = self._load_stop()
+        while self._running:
+            try:
+                await self.step()
+            except Exception as exc:
+                self._logger.info("breakout_error", extra={"symbol": self.config.symbol, "error": str(exc)})
+            await self.clock.sleep(self.config.interval_secs)
+
+
+__all__ = ["BreakoutConfig", "BreakoutStrategy", "donchian"]
@@ -0,0 +1,407 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from decimal import Decimal
+from typing import Any, Dict, List, Optional, Set, Tuple
+
+from xbot.analytics.balance_monitor import total_balances
+from xbot.analytics.funding_table import HOURS_PER_YEAR, annualize
+from xbot.connector.interface import IConnector
+from xbot.core.alerts import AlertLevel, AlertManager
+from xbot.core.symbology import FUTURE, SYMBOLOGY
+from xbot.execution.funding import funding_entry_delay
+from xbot.execution.market_data_service import MarketDataService
+from xbot.execution.models import Order
+from xbot.execution.order_service import OrderService
+from xbot.execution.portfolio_executor import TargetPositionExecutor
+from xbot.utils.logging import get_logger
+
+FLAT = "flat"
+OPEN = "open"
+UNWINDING = "unwinding"
+
+
+@dataclass(slots=True)
+class CashCarryConfig:
+    symbols: Dict[str, Optional[str]] = field(default_factory=dict)  # perp/future venue symbol -> spot (None: look up)
+    notional: Decimal = Decimal(1000)  # quote spent on spot per position
+    entry_apr: Decimal = Decimal("0.15")  # annualized basis + funding needed to open
+    exit_apr: Decimal = Decimal("0.03")  # below this the position is unwound
+    converge_bps: Decimal = Decimal(5)  # |basis| at or under this counts as converged
+    basis_horizon_hours: float = 168.0  # perps: the basis is assumed to close within this
+    unwind_before_hours: float = 24.0  # futures: unwind this long before expiry
+    entry_window_secs: float = 300.0  # an entry waits out an accrual this close that the short pays; 0 disables
+    expiries: Dict[str, str] = field(default_factory=dict)  # future venue symbol -> ISO expiry, overriding the label
+    interval_secs: float = 60.0
+    order_timeout_secs: float = 30.0
+    target_bps: Optional[Decimal] = None  # alert once the basis has converged this far from entry
+    stop_bps: Optional[Decimal] = None  # alert once the basis has widened this far from entry
+    close_on_basis: bool = False  # unwind at target_bps / stop_bps instead of only alerting
+    apply: bool = False  # trade; otherwise only evaluate and log
+
+
+@dataclass(slots=True)
+class BasisQuote:
+    symbol: str  # derivative venue symbol
+    spot: str
+    spot_mid: Decimal
+    perp_mid: Decimal
+    basis: Decimal  # (derivative - spot) / spot
+    funding_apr: Decimal  # annualized funding the short receives; 0 for futures
+    hours_to_expiry: Optional[float]
+    apr: Decimal  # basis annualized over the convergence horizon, plus funding
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "symbol": self.symbol,
+            "spot": self.spot,
+            "spot_mid": str(self.spot_mid),
+            "perp_mid": str(self.perp_mid),
+            "basis": str(self.basis),
+            "funding_apr": str(self.funding_apr),
+            "hours_to_expiry": self.hours_to_expiry,
+            "apr": str(self.apr),
+        }
+
+
+@dataclass(slots=True)
+class CarryPosition:
+    symbol: str
+    spot: str
+    state: str = FLAT
+    spot_qty: Decimal = Decimal(0)  # bought by this strategy: the spot balance above `baseline`
+    baseline: Decimal = Decimal(0)  # base asset held before entry, never sold
+    target_qty: Decimal = Decimal(0)  # spot quantity being worked toward
+    opened: Optional[float] = None
+    reason: str = ""
+    entry_basis: Optional[Decimal] = None
+    basis: Optional[Decimal] = None  # at the last refresh
+    alerted: Set[str] = field(default_factory=set)  # basis thresholds already alerted on
+
+    @property
+    def basis_change_bps(self) -> Optional[Decimal]:
+        """Convergence since entry in bps: positive when the basis narrowed, which the carry earns."""
+        if self.entry_basis is None or self.basis is None:
+            return None
+        return (self.entry_basis - self.basis) * 10000
+
+
+def carry_apr(
+    spot_mid: Decimal,
+    perp_mid: Decimal,
+    funding_apr: Decimal,
+    hours_to_expiry: Optional[float],
+    basis_horizon_hours: float,
+) -> Tuple[Decimal, Decimal]:
+    """(basis, annualized carry): the basis earned over its convergence window plus the funding received."""
+    basis = (perp_mid - spot_mid) / spot_mid
+    hours = hours_to_expiry if hours_to_expiry is not None else basis_horizon_hours
+    return basis, basis * HOURS_PER_YEAR / Decimal(str(max(hours, 1.0))) + funding_apr
+
+
+def parse_expiry(label: Optional[str]) -> Optional[float]:
+    """Epoch seconds of an ISO (`2025-06-27T08:00:00Z`) or `YYYYMMDD` expiry, UTC; None if unreadable."""
+    if not label:
+        return None
+    text = label.strip()
+    for parse in (
+        lambda t: datetime.fromisoformat(t.replace("Z", "+00:00")),
+        lambda t: datetime.strptime(t, "%Y%m%d"),
+        lambda t: datetime.strptime(t, "%y%m%d"),
+    ):
+        try:
+            moment = parse(text)
+        except ValueError:
+            continue
+        if moment.tzinfo is None:
+            moment = moment.replace(tzinfo=timezone.utc)
+        return moment.timestamp()
+    return None
+
+
+class CashAndCarryStrategy:
+    """Buys spot and shorts the perp (or dated future) on the same venue while basis plus funding pays.
+
+    Every `interval_secs` each derivative in `symbols` is priced against its
+    spot market and `carry_apr` gives the annualized return. A perp's basis
+    is assumed to close within `basis_horizon_hours` and a future's by
+    expiry; the short perp also receives funding. At `entry_apr` or more it
+    buys `notional` of spot, and the derivative short is targeted at the
+    spot quantity actually acquired, so the hedge follows fills. It unwinds
+    when the carry falls below `exit_apr`, the basis has converged within
+    `converge_bps`, or a future is within `unwind_before_hours` of expiry.
+    An entry whose short would pay funding at an accrual within
+    `entry_window_secs` waits until after it (`funding_entry_delay`).
+    Spot is sold back down to what was held before entry, never below.
+
+    Every open position's basis is tracked against its basis at entry. It
+    alerts once when the basis has converged by `target_bps` (the profit
+    target) and once when it has widened by `stop_bps`. With
+    `close_on_basis`, either threshold also unwinds the position.
+
+    Spot is tracked from the account balance: the spot leg sends one market
+    order at a time and waits for it before re-reading, so nothing is
+    bought twice. On Backpack the spot held counts as collateral for the
+    short, so the position needs little margin beyond the quote spent.
+    """
+
+    def __init__(
+        self,
+        *,
+        connector: IConnector,
+        order_service: OrderService,
+        market_data: MarketDataService,
+        executor: Optional[TargetPositionExecutor] = None,
+        config: Optional[CashCarryConfig] = None,
+        alerts: Optional[AlertManager] = None,
+    ) -> None:
+        self._connector = connector
+        self._alerts = alerts
+        self._orders = order_service
+        self._market_data = market_data
+        self._executor = executor
+        self._config = config or CashCarryConfig()
+        if self._config.apply and executor is None:
+            raise ValueError("cash-and-carry with apply needs a TargetPositionExecutor for the short leg")
+        self._positions: Dict[str, CarryPosition] = {}
+        self._quotes: Dict[str, BasisQuote] = {}
+        self._restored: Dict[str, Order] = {}  # symbol -> spot order sent before a restart
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    async def adopt(self, order: Order) -> None:
+        """Take back a spot order sent before a restart; its position waits for it before buying again."""
+        symbol = (order.trace_id or "").split(":", 1)[-1]
+        self._restored[symbol] = order
+
+    @property
+    def positions(self) -> Dict[str, CarryPosition]:
+        return dict(self._positions)
+
+    @property
+    def quotes(self) -> Dict[str, BasisQuote]:
+        return dict(self._quotes)
+
+    def _canonical(self, venue_symbol: str) -> str:
+        canonical = self._market_data.canonical_for(venue_symbol)
+        if canonical is None:
+            self._market_data.register_symbol(venue_symbol, venue_symbol)
+            canonical = venue_symbol.upper()
+        return canonical
+
+    def _spot_for(self, symbol: str) -> Optional[str]:
+        configured = self._config.symbols.get(symbol)
+        if configured:
+            return configured
+        resolver = getattr(self._connector, "spot_market", None)
+        instrument = SYMBOLOGY.instrument(self._connector.venue, symbol)
+        return resolver(instrument.base, instrument.quote) if resolver is not None else None
+
+    async def _mid(self, venue_symbol: str) -> Optional[Decimal]:
+        bid_i, ask_i, scale = await self._market_data.get_top_of_book(self._canonical(venue_symbol))
+        if not bid_i or not ask_i:
+            return None
+        return (Decimal(bid_i) + Decimal(ask_i)) / 2 / Decimal(scale)
+
+    def _expiry(self, symbol: str) -> Optional[float]:
+        if symbol in self._config.expiries:
+            return parse_expiry(self._config.expiries[symbol])
+        instrument = SYMBOLOGY.instrument(self._connector.venue, symbol)
+        return parse_expiry(instrument.expiry) if instrument.kind == FUTURE else None
+
+    async def quote(self, symbol: str, now: Optional[float] = None) -> Optional[BasisQuote]:
+        now = time.time() if now is None else now
+        spot = self._spot_for(symbol)
+        if spot is None:
+            return None
+        spot_mid, perp_mid = await self._mid(spot), await self._mid(symbol)
+        if spot_mid is None or perp_mid is None:
+            return None
+        expiry = self._expiry(symbol)
+        hours_to_expiry = max(0.0, (expiry - now) / 3600) if expiry is not None else None
+        funding_apr = Decimal(0)
+        funding = await self._market_data.get_funding(self._canonical(symbol))
+        if funding is not None and funding.interval_hours > 0:
+            funding_apr = annualize(funding.rate, funding.interval_hours)
+        basis, apr = carry_apr(spot_mid, perp_mid, funding_apr, hours_to_expiry, self._config.basis_horizon_hours)
+        return BasisQuote(symbol, spot, spot_mid, perp_mid, basis, funding_apr, hours_to_expiry, apr)
+
+    def _exit_reason(self, quote: BasisQuote) -> Optional[str]:
+        if quote.hours_to_expiry is not None and quote.hours_to_expiry <= self._config.unwind_before_hours:
+            return "expiry"
+        if abs(quote.basis) * 10000 <= self._config.converge_bps:
+            return "converged"
+        if quote.apr < self._config.exit_apr:
+            return "below_exit_apr"
+        return None
+
+    async def _base_balance(self, spot: str) -> Tuple[Decimal, Decimal]:
+        """(base asset, quote asset) totals of the spot market's currencies."""
+        instrument = SYMBOLOGY.instrument(self._connector.venue, spot)
+        balances = total_balances(await self._connector.get_margin())
+        return balances.get(instrument.base.upper(), Decimal(0)), balances.get(instrument.quote.upper(), Decimal(0))
+
+    async def refresh(self, now: Optional[float] = None) -> List[CarryPosition]:
+        now = time.time() if now is None else now
+        for symbol in self._config.symbols:
+            try:
+                quote = await self.quote(symbol, now)
+            except Exception as exc:
+                self._logger.info("cash_carry_quote_error", extra={"symbol": symbol, "error": str(exc)})
+                continue
+            if quote is None:
+                continue
+            self._quotes[symbol] = quote
+            position = self._positions.setdefault(symbol, CarryPosition(symbol=symbol, spot=quote.spot))
+            try:
+                await self._evaluate(position, quote, now)
+            except Exception as exc:
+                self._logger.info("cash_carry_trade_error", extra={"symbol": symbol, "error": str(exc)})
+        return list(self._positions.values())
+
+    async def _evaluate(self, position: CarryPosition, quote: BasisQuote, now: float) -> None:
+        if position.state == FLAT:
+            if quote.apr < self._config.entry_apr or self._exit_reason(quote) is not None:
+                return
+            delay = await self._entry_delay(position.symbol, now)
+            if delay:
+                position.reason = "funding_accrual"
+                self._logger.info("cash_carry_entry_delayed", extra={**quote.to_dict(), "delay_secs": round(delay, 1)})
+                return
+            position.reason = ""
+            self._logger.info("cash_carry_open", extra=quote.to_dict())
+            if not self._config.apply:
+                return
+            base, quote_balance = await self._base_balance(position.spot)
+            if quote_balance < self._config.notional:
+                self._logger.info(
+                    "cash_carry_insufficient_quote",
+                    extra={"symbol": position.symbol, "available": str(quote_balance), "needed": str(self._config.notional)},
+                )
+                return
+            position.state, position.baseline, position.opened = OPEN, base, now
+            position.target_qty = self._config.notional / quote.spot_mid
+            position.entry_basis = position.basis = quote.basis
+        elif position.state == OPEN:
+            position.basis = quote.basis
+            reason = self._exit_reason(quote) or await self._watch_basis(position, quote)
+            if reason is not None:
+                self._logger.info("cash_carry_unwind", extra={**quote.to_dict(), "reason": reason})
+                position.state, position.reason, position.target_qty = UNWINDING, reason, Decimal(0)
+        if self._config.apply and position.state != FLAT:
+            await self._converge(position)
+
+    async def _entry_delay(self, symbol: str, now: float) -> float:
+        if self._config.entry_window_secs <= 0:
+            return 0.0
+        funding = await self._market_data.get_funding(self._canonical(symbol))
+        return funding_entry_delay(funding, is_long=False, window_secs=self._config.entry_window_secs, now=now)
+
+    async def _watch_basis(self, position: CarryPosition, quote: BasisQuote) -> Optional[str]:
+        """Alert on the basis thresholds; the unwind reason when `close_on_basis` and one was crossed."""
+        change = position.basis_change_bps
+        if change is None:
+            return None
+        hit: Optional[str] = None
+        if self._config.target_bps is not None and change >= self._config.target_bps:
+            hit = "basis_target"
+        elif self._config.stop_bps is not None and -change >= self._config.stop_bps:
+            hit = "basis_stop"
+        if hit is None:
+            return None
+        extra = {**quote.to_dict(), "entry_basis": str(position.entry_basis), "change_bps": str(round(change, 2))}
+        if hit not in position.alerted:
+            position.alerted.add(hit)
+            self._logger.info(f"cash_carry_{hit}", extra=extra)
+            if self._alerts is not None:
+                closing = "; unwinding" if self._config.close_on_basis else ""
+                await self._alerts.notify(
+                    AlertLevel.INFO if hit == "basis_target" else AlertLevel.WARNING,
+                    f"Carry {hit.replace('_', ' ')} on {position.symbol}",
+                    f"basis {quote.basis * 10000:.1f}bps vs {position.entry_basis * 10000:.1f}bps at entry{closing}",
+                    **extra,
+                )
+        return hit if self._config.close_on_basis else None
+
+    async def _converge(self, position: CarryPosition) -> None:
+        """One spot order toward `target_qty`, then the short re-targeted at the spot actually held."""
+        assert self._executor is not None
+        spot = self._canonical(position.spot)
+        restored = self._restored.pop(position.symbol, None)
+        if restored is not None:
+            with contextlib.suppress(asyncio.TimeoutError):
+                await restored.wait_final(self._config.order_timeout_secs)
+        base, _ = await self._base_balance(position.spot)
+        position.spot_qty = max(Decimal(0), base - position.baseline)
+        diff = position.target_qty - position.spot_qty
+        size_i = await self._market_data.to_size_i(spot, abs(diff))
+        if size_i > 0 and size_i >= await self._market_data.get_min_size_i(spot):
+            order = await self._orders.submit_market(
+                symbol=spot, is_ask=diff < 0, size_i=size_i, trace_id=f"cash_carry:{position.symbol}"
+            )
+            with contextlib.suppress(asyncio.TimeoutError):
+                await order.wait_final(self._config.order_timeout_secs)
+            base, _ = await self._base_balance(position.spot)
+            position.spot_qty = max(Decimal(0), base - position.baseline)
+        elif position.state == UNWINDING:
+            # Spot is back at the baseline (or below one order's minimum): the position is closed
+            position.spot_qty = Decimal(0)
+        # Derivative sizes are contracts; spot is in base units
+        multiplier = SYMBOLOGY.instrument(self._connector.venue, position.symbol).multiplier
+        await self._executor.set_target(self._canonical(position.symbol), -position.spot_qty / multiplier)
+        if position.state == UNWINDING and position.spot_qty == 0:
+            self._logger.info("cash_carry_closed", extra={"symbol": position.symbol, "reason": position.reason})
+            self._positions[position.symbol] = CarryPosition(symbol=position.symbol, spot=position.spot)
+
+    def snapshot(self) -> Dict[str, Any]:
+        return {
+            "quotes": {symbol: quote.to_dict() for symbol, quote in self._quotes.items()},
+            "positions": {
+                symbol: {
+                    "state": p.state,
+                    "spot_qty": str(p.spot_qty),
+                    "target_qty": str(p.target_qty),
+                    "reason": p.reason,
+                    "entry_basis": str(p.entry_basis) if p.entry_basis is not None else None,
+                    "basis_change_bps": str(p.basis_change_bps) if p.basis_change_bps is not None else None,
+                }
+                for symbol, p in self._positions.items()
+            },
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="cash-carry")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.refresh()
+            except Exception as exc:
+                self._logger.info("cash_carry_error", extra={"error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = [
+    "BasisQuote",
+    "CashAndCarryStrategy",
+    "CashCarryConfig",
+    "CarryPosition",
+    "FLAT",
+    "OPEN",
+    "UNWINDING",
+    "carry_apr",
+    "parse_expiry",
+]
@@ -0,0 +1,274 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import json
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Mapping, Optional, Sequence
+
+import httpx
+
+from xbot.core.alerts import AlertLevel, AlertManager
+from xbot.execution.risk_service import RiskMode, RiskService
+from xbot.utils.logging import get_logger
+from .base import Strategy
+
+try:  # pragma: no cover - optional dependency
+    import yaml  # type: ignore
+except Exception:  # pragma: no cover
+    yaml = None
+
+EVENT_PREFIX = "event:"  # pause/risk-mode reasons set by the calendar start with this
+
+
+@dataclass(slots=True, frozen=True)
+class ScheduledEvent:
+    name: str
+    at: float  # epoch seconds
+    kind: str = ""  # e.g. cpi, fomc, unlock; selects per-kind windows
+    before_secs: Optional[float] = None  # overrides the kind's or the default window
+    after_secs: Optional[float] = None
+    symbols: tuple[str, ...] = ()  # informational; the halt covers every designated strategy
+
+
+@dataclass(slots=True)
+class EventWindow:
+    before_secs: float = 900.0
+    after_secs: float = 1800.0
+
+
+@dataclass(slots=True)
+class EventCalendarConfig:
+    path: Optional[str] = None  # YAML/JSON file with an `events` list
+    url: Optional[str] = None  # endpoint returning the same list as JSON
+    refresh_secs: float = 3600.0  # reload the file/URL this often
+    interval_secs: float = 10.0  # check the windows this often
+    default_window: EventWindow = field(default_factory=EventWindow)
+    windows: Dict[str, EventWindow] = field(default_factory=dict)  # kind -> window
+    strategies: List[str] = field(default_factory=list)  # strategy names to halt; empty: every registered one
+    account_wide: bool = False  # also put the whole account into reduce-only
+    events: List[ScheduledEvent] = field(default_factory=list)  # inline events, merged with the file/URL
+
+
+def parse_time(value: Any) -> float:
+    """Epoch seconds from a number (seconds or milliseconds) or an ISO timestamp (UTC when naive)."""
+    if isinstance(value, (int, float)):
+        return float(value) / 1000 if value > 1e11 else float(value)
+    if isinstance(value, datetime):
+        moment = value
+    else:
+        moment = datetime.fromisoformat(str(value).strip().replace("Z", "+00:00"))
+    if moment.tzinfo is None:
+        moment = moment.replace(tzinfo=timezone.utc)
+    return moment.timestamp()
+
+
+def parse_events(rows: Iterable[Mapping[str, Any]]) -> List[ScheduledEvent]:
+    events: List[ScheduledEvent] = []
+    for row in rows:
+        when = row.get("at", row.get("time"))
+        if when is None:
+            raise ValueError(f"calendar event {row.get('name')!r} has no time")
+        events.append(
+            ScheduledEvent(
+                name=str(row.get("name") or row.get("kind") or "event"),
+                at=parse_time(when),
+                kind=str(row.get("kind") or "").lower(),
+                before_secs=float(row["before_secs"]) if row.get("before_secs") is not None else None,
+                after_secs=float(row["after_secs"]) if row.get("after_secs") is not None else None,
+                symbols=tuple(str(s).upper() for s in row.get("symbols") or ()),
+            )
+        )
+    return events
+
+
+def load_events_file(path: Path) -> List[ScheduledEvent]:
+    text = path.read_text(encoding="utf-8")
+    if path.suffix in {".yaml", ".yml"}:
+        if yaml is None:
+            raise RuntimeError("PyYAML not available; use a JSON calendar")
+        payload = yaml.safe_load(text) or {}
+    else:
+        payload = json.loads(text)
+    rows = payload.get("events", []) if isinstance(payload, Mapping) else payload
+    return parse_events(rows)
+
+
+class EventCalendar:
+    """Scheduled market-moving events (CPI, FOMC, token unlocks) with a halt window around each."""
+
+    def __init__(self, config: EventCalendarConfig) -> None:
+        self._config = config
+        self._events: List[ScheduledEvent] = list(config.events)
+
+    @property
+    def events(self) -> List[ScheduledEvent]:
+        return list(self._events)
+
+    def set_events(self, events: Sequence[ScheduledEvent]) -> None:
+        self._events = sorted({(e.at, e.name): e for e in events}.values(), key=lambda e: e.at)
+
+    def window(self, event: ScheduledEvent) -> tuple[float, float]:
+        """(start, end) epoch seconds of the halt around `event`."""
+        base = self._config.windows.get(event.kind, self._config.default_window)
+        before = event.before_secs if event.before_secs is not None else base.before_secs
+        after = event.after_secs if event.after_secs is not None else base.after_secs
+        return event.at - before, event.at + after
+
+    def active(self, now: Optional[float] = None) -> List[ScheduledEvent]:
+        now = time.time() if now is None else now
+        return [e for e in self._events if self.window(e)[0] <= now < self.window(e)[1]]
+
+    def upcoming(self, now: Optional[float] = None, limit: int = 10) -> List[ScheduledEvent]:
+        now = time.time() if now is None else now
+        return [e for e in self._events if self.window(e)[1] > now][:limit]
+
+    async def reload(self, client: Optional[httpx.AsyncClient] = None) -> List[ScheduledEvent]:
+        events = list(self._config.events)
+        if self._config.path:
+            events += load_events_file(Path(self._config.path))
+        if self._config.url:
+            async with contextlib.AsyncExitStack() as stack:
+                http = client or await stack.enter_async_context(httpx.AsyncClient(timeout=10.0))
+                resp = await http.get(self._config.url)
+                resp.raise_for_status()
+                payload = resp.json()
+            events += parse_events(payload.get("events", []) if isinstance(payload, Mapping) else payload)
+        self.set_events(events)
+        return self.events
+
+
+class EventHaltService:
+    """Puts designated strategies into reduce-only around calendar events and lifts it afterwards.
+
+    Inside an event's window each designated strategy is paused with reason
+    `event:<name>`, which blocks opening orders while reducing ones still
+    pass. With `account_wide` the risk mode goes to `REDUCE_ONLY` too. When
+    no window is active the calendar lifts only what it set: a strategy paused
+    for another reason (session guardrails, an operator) stays paused, and a
+    risk mode changed since is left alone. A failed reload keeps the last
+    good calendar.
+    """
+
+    def __init__(
+        self,
+        *,
+        calendar: EventCalendar,
+        config: EventCalendarConfig,
+        risk: Optional[RiskService] = None,
+        alerts: Optional[AlertManager] = None,
+    ) -> None:
+        self._calendar = calendar
+        self._config = config
+        self._risk = risk
+        self._alerts = alerts
+        self._strategies: Dict[str, Strategy] = {}
+        self._active: Optional[str] = None  # reason currently applied
+        self._loaded_at = 0.0
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def calendar(self) -> EventCalendar:
+        return self._calendar
+
+    @property
+    def active_reason(self) -> Optional[str]:
+        return self._active
+
+    def register(self, strategy: Strategy) -> None:
+        if not self._config.strategies or strategy.name in self._config.strategies:
+            self._strategies[strategy.name] = strategy
+
+    async def _reload(self, now: float) -> None:
+        if now - self._loaded_at < self._config.refresh_secs:
+            return
+        self._loaded_at = now
+        try:
+            events = await self._calendar.reload()
+        except Exception as exc:
+            self._logger.info("event_calendar_reload_error", extra={"error": str(exc)})
+            return
+        self._logger.info("event_calendar_loaded", extra={"events": len(events)})
+
+    async def check(self, now: Optional[float] = None) -> Optional[str]:
+        now = time.time() if now is None else now
+        await self._reload(now)
+        active = self._calendar.active(now)
+        reason = EVENT_PREFIX + ",".join(e.name for e in active) if active else None
+        if reason == self._active:
+            return reason
+        if reason is not None:
+            await self._halt(reason, active)
+        else:
+            await self._lift()
+        self._active = reason
+        return reason
+
+    async def _halt(self, reason: str, events: List[ScheduledEvent]) -> None:
+        for strategy in self._strategies.values():
+            if strategy.pause_reason is None or strategy.pause_reason.startswith(EVENT_PREFIX):
+                strategy.pause(reason)
+        if self._config.account_wide and self._risk is not None:
+            if self._risk.mode == RiskMode.NORMAL or self._risk.mode_reason.startswith(EVENT_PREFIX):
+                self._risk.set_mode(RiskMode.REDUCE_ONLY, reason)
+        names = [e.name for e in events]
+        self._logger.info("event_halt_start", extra={"events": names, "strategies": sorted(self._strategies)})
+        if self._alerts is not None and self._active is None:
+            await self._alerts.notify(AlertLevel.WARNING, "Event halt", f"reduce-only for {', '.join(names)}", events=names)
+
+    async def _lift(self) -> None:
+        for strategy in self._strategies.values():
+            if strategy.pause_reason is not None and strategy.pause_reason.startswith(EVENT_PREFIX):
+                strategy.resume()
+        if self._config.account_wide and self._risk is not None:
+            if self._risk.mode == RiskMode.REDUCE_ONLY and self._risk.mode_reason.startswith(EVENT_PREFIX):
+                self._risk.set_mode(RiskMode.NORMAL, "")
+        self._logger.info("event_halt_end", extra={"strategies": sorted(self._strategies)})
+        if self._alerts is not None:
+            await self._alerts.notify(AlertLevel.INFO, "Event halt lifted", "strategies re-enabled")
+
+    def snapshot(self, now: Optional[float] = None) -> Dict[str, Any]:
+        return {
+            "active": self._active,
+            "upcoming": [
+                {"name": e.name, "kind": e.kind, "at": e.at, "window": list(self._calendar.window(e))}
+                for e in self._calendar.upcoming(now)
+            ],
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="event-halt")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.check()
+            except Exception as exc:
+                self._logger.info("event_halt_error", extra={"error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = [
+    "EVENT_PREFIX",
+    "EventCalendar",
+    "EventCalendarConfig",
+    "EventHaltService",
+    "EventWindow",
+    "ScheduledEvent",
+    "load_events_file",
+    "parse_events",
+    "parse_time",
+]
@@ -0,0 +1,425 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field, replace
+from decimal import Decimal
+from typing import Any, Dict, List, Mapping, Optional, Sequence, Tuple
+
+from xbot.analytics.funding_table import HOURS_PER_YEAR, FundingTable, FundingTableRow, annualize
+from xbot.analytics.ticker_stats import TickerStats
+from xbot.connector.history import CurrentFunding
+from xbot.execution.funding import FundingService, funding_entry_delay
+from xbot.execution.market_data_service import MarketDataService
+from xbot.execution.portfolio_executor import TargetPositionExecutor
+from xbot.utils.logging import get_logger
+
+HOLD = "hold"
+OPEN = "open"
+ROTATE = "rotate"
+FLIP = "flip"
+CLOSE = "close"
+
+BPS = Decimal(10000)
+
+
+@dataclass(slots=True)
+class RotationConfig:
+    notional: Decimal = Decimal(1000)  # quote notional per leg of each underlying's pair
+    underlyings: List[str] = field(default_factory=list)  # empty: every underlying on two tradable venues
+    venues: List[str] = field(default_factory=list)  # venues legs may use; empty: those with an executor
+    interval_secs: float = 300.0
+    taker_fee_bps: Dict[str, Decimal] = field(default_factory=dict)  # venue -> fee per trade
+    default_fee_bps: Decimal = Decimal(5)
+    slippage_bps: Decimal = Decimal(2)  # per trade, on top of the fee
+    horizon_hours: float = 72.0  # trading costs are spread over this expected holding period
+    min_spread_apr: Decimal = Decimal("0.05")  # net of costs, needed to open a pair
+    hysteresis_apr: Decimal = Decimal("0.03")  # net of costs, a new pair must beat the held one by this
+    exit_spread_apr: Decimal = Decimal(0)  # the held pair is closed when its spread falls below this
+    min_hold_secs: float = 4 * 3600.0  # no rotation (but still closing) before a pair is this old
+    flip: bool = False  # reverse the legs in place when the paying side flips, instead of closing
+    min_volume_24h: Decimal = Decimal(0)  # quote volume a symbol needs to get a new leg; held legs are kept
+    entry_window_secs: float = 300.0  # a new pair waits out an accrual this close that it would pay; 0 disables
+    apply: bool = False  # send targets to the executors; otherwise only decide and log
+
+
+@dataclass(slots=True, frozen=True)
+class FundingPair:
+    underlying: str
+    short: Tuple[str, str]  # (venue, venue symbol) receiving the higher funding
+    long: Tuple[str, str]
+
+    def reversed(self) -> "FundingPair":
+        return FundingPair(underlying=self.underlying, short=self.long, long=self.short)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"underlying": self.underlying, "short": list(self.short), "long": list(self.long)}
+
+
+@dataclass(slots=True)
+class HeldPair:
+    pair: FundingPair
+    opened: float
+
+
+@dataclass(slots=True)
+class RotationDecision:
+    action: str
+    underlying: str
+    pair: Optional[FundingPair]  # the pair held after the decision
+    spread_apr: Optional[Decimal]  # of that pair
+    held_spread_apr: Optional[Decimal] = None  # of the pair held before
+    cost_apr: Decimal = Decimal(0)  # trading cost of the action, annualized over the horizon
+    reason: str = ""
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "action": self.action,
+            "underlying": self.underlying,
+            "pair": self.pair.to_dict() if self.pair else None,
+            "spread_apr": str(self.spread_apr) if self.spread_apr is not None else None,
+            "held_spread_apr": str(self.held_spread_apr) if self.held_spread_apr is not None else None,
+            "cost_apr": str(self.cost_apr),
+            "reason": self.reason,
+        }
+
+
+def _trade_cost(venue: str, config: RotationConfig) -> Decimal:
+    return (config.taker_fee_bps.get(venue, config.default_fee_bps) + config.slippage_bps) / BPS
+
+
+def turnover_cost(held: Optional[FundingPair], target: Optional[FundingPair], config: RotationConfig) -> Decimal:
+    """Cost of moving from `held` to `target` as a fraction of one leg's notional.
+
+    A leg that stays on the same venue trades nothing; a leg that moves is
+    closed on the old venue and opened on the new one.
+    """
+    cost = Decimal(0)
+    for side in ("short", "long"):
+        before = getattr(held, side) if held else None
+        after = getattr(target, side) if target else None
+        if before == after:
+            continue
+        if before is not None:
+            cost += _trade_cost(before[0], config)
+        if after is not None:
+            cost += _trade_cost(after[0], config)
+    return cost
+
+
+def annualized_cost(cost: Decimal, config: RotationConfig) -> Decimal:
+    return cost * HOURS_PER_YEAR / Decimal(str(config.horizon_hours))
+
+
+def spread_of(pair: FundingPair, rows: Sequence[FundingTableRow]) -> Optional[Decimal]:
+    by_venue = {row.venue: row for row in rows}
+    short, long = by_venue.get(pair.short[0]), by_venue.get(pair.long[0])
+    if short is None or long is None:
+        return None
+    return short.annualized - long.annualized
+
+
+def best_pair(underlying: str, rows: Sequence[FundingTableRow]) -> Optional[Tuple[FundingPair, Decimal]]:
+    """Short the highest annualized funding, long the lowest; rows need two distinct venues."""
+    ranked = sorted(rows, key=lambda row: row.annualized, reverse=True)
+    if len({row.venue for row in ranked}) < 2:
+        return None
+    short, long = ranked[0], ranked[-1]
+    pair = FundingPair(underlying=underlying, short=(short.venue, short.symbol), long=(long.venue, long.symbol))
+    return pair, short.annualized - long.annualized
+
+
+def entry_delay(
+    pair: FundingPair, rows: Sequence[FundingTableRow], window_secs: float, now: Optional[float] = None
+) -> float:
+    """Seconds to hold off opening `pair`; 0 when it can open now.
+
+    A leg that would pay at an accrual at most `window_secs` away holds the
+    pair off until just after it (`funding_entry_delay`), unless the other
+    leg receives more at its own accrual inside the same window.
+    """
+    if window_secs <= 0:
+        return 0.0
+    by_venue = {row.venue: row for row in rows}
+    delay, paid, received = 0.0, Decimal(0), Decimal(0)
+    for (venue, _), is_long in ((pair.short, False), (pair.long, True)):
+        row = by_venue.get(venue)
+        if row is None or (row.next_funding_ms is None and row.interval_hours <= 0):
+            continue
+        funding = CurrentFunding(row.venue, row.symbol, row.rate, row.interval_hours, row.next_funding_ms)
+        leg_delay = funding_entry_delay(funding, is_long=is_long, window_secs=window_secs, now=now)
+        if leg_delay:
+            delay, paid = max(delay, leg_delay), paid + abs(row.rate)
+        elif funding.secs_to_next(now) <= window_secs:
+            received += abs(row.rate)
+    return delay if paid > received else 0.0
+
+
+def _flip(
+    held: HeldPair,
+    held_spread: Optional[Decimal],
+    rows: Sequence[FundingTableRow],
+    forecast: Sequence[FundingTableRow],
+    config: RotationConfig,
+) -> Optional[RotationDecision]:
+    """Reverse the held pair when it pays now or is forecast to; None when that does not cover its cost."""
+    forecast_spread = spread_of(held.pair, forecast) if forecast else None
+    expected = forecast_spread if forecast_spread is not None else held_spread
+    if held_spread is None or expected is None or expected >= 0:
+        return None
+    reason = "funding_flipped" if held_spread < 0 else "funding_flip_forecast"
+    options: List[Tuple[FundingPair, Decimal]] = [(held.pair.reversed(), -expected)]
+    best = best_pair(held.pair.underlying, forecast or rows)
+    if best is not None and best[0] not in (held.pair, options[0][0]):
+        options.append(best)
+    scored = [
+        (pair, spread, annualized_cost(turnover_cost(held.pair, pair, config), config)) for pair, spread in options
+    ]
+    pair, spread, cost = max(scored, key=lambda option: option[1] - option[2])
+    if spread - cost < config.min_spread_apr:
+        return None
+    action = FLIP if pair == options[0][0] else ROTATE
+    return RotationDecision(action, held.pair.underlying, pair, spread, held_spread, cost, reason)
+
+
+def decide(
+    underlying: str,
+    rows: Sequence[FundingTableRow],
+    held: Optional[HeldPair],
+    config: RotationConfig,
+    now: Optional[float] = None,
+    forecast: Sequence[FundingTableRow] = (),
+) -> RotationDecision:
+    """What to hold on `underlying` given fresh `rows` (restricted to tradable venues).
+
+    Every comparison is net of the turnover cost of getting there, annualized
+    over `horizon_hours`, so a pair must out-earn its own trading costs. A
+    rotation must also beat the held pair by `hysteresis_apr` and wait for
+    `min_hold_secs`, which keeps noise from churning the legs.
+
+    With `config.flip`, a held pair whose spread has turned negative, or
+    whose `forecast` rows (the same rows at predicted rates) say it will,
+    is reversed in place when the reversed spread, net of trading both legs
+    twice over, still clears `min_spread_apr`. A better third venue wins
+    over the reversal when it nets more. Flips skip `min_hold_secs`, since
+    the held pair is paying.
+
+    A new pair is not opened while `entry_delay` says a leg would pay an
+    accrual within `entry_window_secs`; the next refresh after it opens it.
+    """
+    now = time.time() if now is None else now
+    best = best_pair(underlying, rows)
+    if held is None:
+        if best is None:
+            return RotationDecision(HOLD, underlying, None, None, reason="no_pair")
+        pair, spread = best
+        cost = annualized_cost(turnover_cost(None, pair, config), config)
+        if spread - cost < config.min_spread_apr:
+            return RotationDecision(HOLD, underlying, None, None, cost_apr=cost, reason="below_min_spread")
+        if entry_delay(pair, rows, config.entry_window_secs, now):
+            return RotationDecision(HOLD, underlying, None, None, cost_apr=cost, reason="funding_accrual")
+        return RotationDecision(OPEN, underlying, pair, spread, cost_apr=cost)
+    held_spread = spread_of(held.pair, rows)
+    if config.flip:
+        flipped = _flip(held, held_spread, rows, forecast, config)
+        if flipped is not None:
+            return flipped
+    if held_spread is None or held_spread < config.exit_spread_apr:
+        cost = annualized_cost(turnover_cost(held.pair, None, config), config)
+        reason = "leg_unquoted" if held_spread is None else "below_exit_spread"
+        return RotationDecision(CLOSE, underlying, None, None, held_spread, cost, reason)
+    if best is None or best[0] == held.pair:
+        return RotationDecision(HOLD, underlying, held.pair, held_spread, held_spread)
+    pair, spread = best
+    cost = annualized_cost(turnover_cost(held.pair, pair, config), config)
+    if now - held.opened < config.min_hold_secs:
+        return RotationDecision(HOLD, underlying, held.pair, held_spread, held_spread, cost, "min_hold")
+    if spread - cost < held_spread + config.hysteresis_apr:
+        return RotationDecision(HOLD, underlying, held.pair, held_spread, held_spread, cost, "within_hysteresis")
+    return RotationDecision(ROTATE, underlying, pair, spread, held_spread, cost)
+
+
+class FundingRotationStrategy:
+    """Keeps each underlying's delta-neutral pair on the best-paying venues among three or more.
+
+    Every `interval_secs` it reads the `FundingTable`, restricted to venues
+    it can trade, and runs `decide` per underlying. With `apply`, the legs
+    become targets of each venue's `TargetPositionExecutor`: `notional / mid`
+    short where funding is highest and long where it is lowest. A rotation
+    moves only the leg that changes venue. A pair is opened, moved and
+    closed as a whole; when a leg cannot be priced nothing is targeted.
+    Held pairs live in memory, so a restart starts from flat targets.
+    With `funding` services per venue, their forecasts let `flip` act
+    before the paying side has actually changed. With `tickers`, a venue
+    symbol whose 24h quote volume is under `min_volume_24h` is not
+    considered for new legs; the legs of a held pair stay eligible so a
+    quiet day does not force a close.
+    """
+
+    def __init__(
+        self,
+        *,
+        funding_table: FundingTable,
+        executors: Optional[Mapping[str, TargetPositionExecutor]] = None,
+        market_data: Optional[Mapping[str, MarketDataService]] = None,
+        config: Optional[RotationConfig] = None,
+        funding: Optional[Mapping[str, FundingService]] = None,
+        tickers: Optional[TickerStats] = None,
+    ) -> None:
+        self._funding_table = funding_table
+        self._tickers = tickers
+        self._executors = dict(executors or {})
+        self._market_data = dict(market_data or {})
+        self._funding = dict(funding or {})
+        self._config = config or RotationConfig()
+        self._held: Dict[str, HeldPair] = {}
+        self._decisions: Dict[str, RotationDecision] = {}
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def held(self) -> Dict[str, HeldPair]:
+        return dict(self._held)
+
+    @property
+    def decisions(self) -> Dict[str, RotationDecision]:
+        return dict(self._decisions)
+
+    def _tradable(self) -> List[str]:
+        return [venue.lower() for venue in self._config.venues] or sorted(self._executors)
+
+    def _underlyings(self) -> List[str]:
+        if self._config.underlyings:
+            return [u.upper() for u in self._config.underlyings]
+        return sorted({row.underlying for row in self._funding_table.rows()} | set(self._held))
+
+    def _forecast(self, rows: Sequence[FundingTableRow]) -> List[FundingTableRow]:
+        """`rows` at predicted rates; empty unless every venue has a forecast."""
+        forecast: List[FundingTableRow] = []
+        for row in rows:
+            service, market_data = self._funding.get(row.venue), self._market_data.get(row.venue)
+            symbol = market_data.canonical_for(row.symbol) if market_data is not None else None
+            predicted = service.forecast(symbol).predicted if service is not None and symbol is not None else None
+            if predicted is None:
+                return []
+            forecast.append(replace(row, rate=predicted, annualized=annualize(predicted, row.interval_hours)))
+        return forecast
+
+    def _screened(self, row: FundingTableRow, held: Optional[HeldPair]) -> bool:
+        if self._tickers is None or not self._config.min_volume_24h:
+            return True
+        if held is not None and (row.venue, row.symbol) in (held.pair.short, held.pair.long):
+            return True
+        return self._tickers.liquid(row.venue, row.symbol, self._config.min_volume_24h)
+
+    async def refresh(self, now: Optional[float] = None) -> List[RotationDecision]:
+        now = time.time() if now is None else now
+        tradable = set(self._tradable())
+        decisions: List[RotationDecision] = []
+        for underlying in self._underlyings():
+            held = self._held.get(underlying)
+            rows = [
+                row
+                for row in self._funding_table.rows(underlying)
+                if row.venue in tradable and self._screened(row, held)
+            ]
+            forecast = self._forecast(rows) if self._config.flip and held is not None and self._funding else []
+            decision = decide(underlying, rows, held, self._config, now, forecast)
+            if decision.action != HOLD:
+                held_pair = held.pair if held else None
+                if self._config.apply and not await self._apply(held_pair, decision.pair):
+                    spread = decision.held_spread_apr
+                    decision = RotationDecision(HOLD, underlying, held_pair, spread, spread, decision.cost_apr, "untradable")
+                else:
+                    self._logger.info("funding_rotation", extra=decision.to_dict())
+                    if decision.pair is None:
+                        self._held.pop(underlying, None)
+                    else:
+                        self._held[underlying] = HeldPair(decision.pair, now)
+            self._decisions[underlying] = decision
+            decisions.append(decision)
+        return decisions
+
+    async def _qty(self, venue: str, venue_symbol: str) -> Optional[Tuple[str, Decimal]]:
+        market_data = self._market_data.get(venue)
+        if market_data is None or venue not in self._executors:
+            return None
+        symbol = market_data.canonical_for(venue_symbol)
+        if symbol is None:
+            return None
+        bid_i, ask_i, scale = await market_data.get_top_of_book(symbol)
+        if not bid_i or not ask_i:
+            return None
+        mid = (Decimal(bid_i) + Decimal(ask_i)) / 2 / Decimal(scale)
+        return symbol, self._config.notional / mid
+
+    async def _apply(self, held: Optional[FundingPair], target: Optional[FundingPair]) -> bool:
+        """Target the legs that change; False (and nothing sent) when any of them cannot be priced."""
+        changes: Dict[Tuple[str, str], Decimal] = {}
+        for side, sign in (("short", -1), ("long", 1)):
+            before = getattr(held, side) if held else None
+            after = getattr(target, side) if target else None
+            if before == after:
+                continue
+            for leg, qty_sign in ((before, 0), (after, sign)):
+                if leg is None:
+                    continue
+                sized = await self._qty(*leg)
+                if sized is None:
+                    self._logger.info("funding_rotation_untradable", extra={"venue": leg[0], "symbol": leg[1]})
+                    return False
+                key = (leg[0], sized[0])
+                # A flip reuses both venues: the new leg's target replaces the old leg's flatten
+                if qty_sign or key not in changes:
+                    changes[key] = sized[1] * qty_sign
+        # New legs go first, so the hedge is never missing while a leg moves
+        for (venue, symbol), qty in sorted(changes.items(), key=lambda change: change[1] == 0):
+            await self._executors[venue].set_target(symbol, qty)
+        return True
+
+    def snapshot(self) -> Dict[str, Any]:
+        return {
+            "held": {u: {**h.pair.to_dict(), "opened": h.opened} for u, h in self._held.items()},
+            "decisions": {u: d.to_dict() for u, d in self._decisions.items()},
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="funding-rotation")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def _run(self) -> None:
+        while True:
+            try:
+                await self.refresh()
+            except Exception as exc:
+                self._logger.info("funding_rotation_error", extra={"error": str(exc)})
+            await asyncio.sleep(self._config.interval_secs)
+
+
+__all__ = [
+    "CLOSE",
+    "FLIP",
+    "FundingPair",
+    "FundingRotationStrategy",
+    "HOLD",
+    "HeldPair",
+    "OPEN",
+    "ROTATE",
+    "RotationConfig",
+    "RotationDecision",
+    "annualized_cost",
+    "best_pair",
+    "decide",
+    "entry_delay",
+    "spread_of",
+    "turnover_cost",
+]
@@ -0,0 +1,253 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timedelta, timezone
+from decimal import Decimal
+from typing import Any, Dict, List, Mapping, Optional, Set, Tuple
+
+from xbot.analytics.journal import TradeRecord, build_journal, strategy_tag
+from xbot.core.alerts import AlertLevel, AlertManager
+from xbot.core.events import EventKind, EventsLagged, EventStream
+from xbot.execution.router import ExecutionRouter
+from xbot.storage.base import FILLS, StorageWriter
+from xbot.utils.logging import get_logger
+from .base import Strategy
+
+
+@dataclass(slots=True)
+class GuardrailConfig:
+    max_daily_loss: Optional[Decimal] = None  # realized net PnL of closed round trips, in their PnL currency
+    max_trades_per_day: Optional[int] = None  # orders with at least one fill
+    reset_hour_utc: int = 0  # the session day starts at this UTC hour
+    loss_streak: Optional[int] = None  # consecutive losing round trips on one symbol before it cools down
+    cooldown_secs: float = 3600.0  # how long new entries on that symbol are blocked
+
+
+@dataclass(slots=True)
+class _Session:
+    day: str
+    fills: List[Mapping[str, Any]] = field(default_factory=list)
+    orders: Set[int] = field(default_factory=set)
+    trades: List[TradeRecord] = field(default_factory=list)
+    breached: Optional[str] = None
+    seen: Set[Tuple[Any, ...]] = field(default_factory=set)
+
+
+def _fill_key(fill: Mapping[str, Any]) -> Tuple[Any, ...]:
+    # Millisecond ts: backends store timestamps at different precisions
+    return (fill.get("client_order_index"), str(fill.get("size")), round(float(fill.get("ts") or 0.0), 3))
+
+
+class SessionGuardrails:
+    """Per-strategy daily loss and trade-count limits within one account.
+
+    Fills are attributed by `strategy_tag`, i.e. the `trace_id` prefix a
+    strategy puts on its orders. When a strategy breaches one of its limits
+    it alone is paused and its own resting orders are cancelled; other
+    strategies trading the same account keep running. The pause lifts when
+    the next session day starts. A run of losing round trips on one symbol
+    blocks new entries on just that symbol for a cooldown instead. Counters
+    live in memory, so a restart starts the day afresh. If the fill feed
+    drops events they are rebuilt from the day's stored fills, when a
+    `writer` is given.
+    """
+
+    def __init__(
+        self,
+        *,
+        router: ExecutionRouter,
+        events: EventStream,
+        alerts: Optional[AlertManager] = None,
+        writer: Optional[StorageWriter] = None,
+    ) -> None:
+        self._router = router
+        self._events = events
+        self._alerts = alerts
+        self._writer = writer
+        self._strategies: Dict[str, Strategy] = {}
+        self._configs: Dict[str, GuardrailConfig] = {}
+        self._sessions: Dict[str, _Session] = {}
+        self._streaks: Dict[Tuple[str, str], int] = {}  # (strategy, symbol) -> consecutive losing round trips
+        self._task: Optional[asyncio.Task] = None
+        self._logger = get_logger(__name__)
+
+    def register(self, strategy: Strategy, config: GuardrailConfig) -> None:
+        self._strategies[strategy.name] = strategy
+        self._configs[strategy.name] = config
+
+    def session(self, name: str) -> Dict[str, Any]:
+        """Current counters for one strategy, for logs and the admin API."""
+        session = self._session(name)
+        return {
+            "strategy": name,
+            "day": session.day,
+            "trades": len(session.orders),
+            "realized_pnl": str(self._realized(session)),
+            "loss_streaks": {symbol: n for (owner, symbol), n in self._streaks.items() if owner == name and n},
+            "breached": session.breached,
+        }
+
+    async def start(self) -> None:
+        if self._task is None:
+            self._task = asyncio.create_task(self._run(), name="strategy-guardrails")
+
+    async def stop(self) -> None:
+        if self._task is None:
+            return
+        self._task.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await self._task
+        self._task = None
+
+    async def on_fill(self, fill: Mapping[str, Any]) -> None:
+        name = strategy_tag(fill)
+        if name not in self._strategies:
+            return
+        session = self._session(name, float(fill.get("ts") or time.time()))
+        key = _fill_key(fill)
+        if key in session.seen:
+            return  # already rebuilt from storage by a resync
+        session.seen.add(key)
+        session.fills.append(fill)
+        if fill.get("client_order_index") is not None:
+            session.orders.add(int(fill["client_order_index"]))
+        await self._evaluate(name, session)
+
+    async def resync(self) -> None:
+        """Rebuild today's counters from stored fills, e.g. after the fill feed dropped events."""
+        if self._writer is None:
+            self._logger.warning("strategy_guardrail_resync_unavailable", extra={"reason": "no storage configured"})
+            return
+        await self._writer.flush()
+        sessions = {name: self._session(name) for name in self._strategies}
+        if not sessions:
+            return
+        start = min(self._day_start(name, session.day) for name, session in sessions.items())
+        rows = await self._writer.storage.query(FILLS, start=start)
+        for name, session in sessions.items():
+            day_start = self._day_start(name, session.day)
+            session.fills = [row for row in rows if strategy_tag(row) == name and float(row.get("ts") or 0.0) >= day_start]
+            session.orders = {int(row["client_order_index"]) for row in session.fills if row.get("client_order_index") is not None}
+            session.seen = {_fill_key(row) for row in session.fills}
+            await self._evaluate(name, session)
+        self._logger.info(
+            "strategy_guardrail_resync",
+            extra={"trades": {name: len(session.orders) for name, session in sessions.items()}},
+        )
+
+    async def _evaluate(self, name: str, session: _Session) -> None:
+        closed = len(session.trades)
+        session.trades = build_journal(session.fills)
+        # Round trips already counted keep their place, so only new ones move the loss streaks
+        for trade in session.trades[closed:]:
+            await self._on_round_trip(name, trade)
+        if session.breached is None:
+            reason = self._breach(self._configs[name], session)
+            if reason is not None:
+                session.breached = reason
+                await self._halt(name, reason)
+
+    def _day(self, name: str, ts: float) -> str:
+        shift = timedelta(hours=self._configs[name].reset_hour_utc)
+        return (datetime.fromtimestamp(ts, tz=timezone.utc) - shift).date().isoformat()
+
+    def _day_start(self, name: str, day: str) -> float:
+        start = datetime.fromisoformat(day).replace(tzinfo=timezone.utc)
+        return (start + timedelta(hours=self._configs[name].reset_hour_utc)).timestamp()
+
+    def _session(self, name: str, ts: Optional[float] = None) -> _Session:
+        day = self._day(name, time.time() if ts is None else ts)
+        session = self._sessions.get(name)
+        if session is None or session.day != day:
+            if session is not None and session.breached is not None:
+                self._strategies[name].resume()
+                self._logger.info("strategy_guardrail_reset", extra={"strategy": name, "day": day})
+            session = _Session(day=day)
+            self._sessions[name] = session
+        return session
+
+    @staticmethod
+    def _realized(session: _Session) -> Decimal:
+        return sum((trade.net_pnl for trade in session.trades), Decimal(0))
+
+    async def _on_round_trip(self, name: str, trade: TradeRecord) -> None:
+        config = self._configs[name]
+        key = (name, trade.symbol.upper())
+        self._streaks[key] = self._streaks.get(key, 0) + 1 if trade.net_pnl < 0 else 0
+        if config.loss_streak is None or self._streaks[key] < config.loss_streak:
+            return
+        self._streaks[key] = 0
+        reason = f"{config.loss_streak} consecutive losing round trips"
+        self._strategies[name].cool_down(trade.symbol, time.time() + config.cooldown_secs, reason)
+        self._logger.warning(
+            "strategy_loss_streak_cooldown",
+            extra={"strategy": name, "symbol": trade.symbol, "reason": reason, "cooldown_secs": config.cooldown_secs},
+        )
+        if self._alerts is not None:
+            await self._alerts.notify(
+                AlertLevel.WARNING,
+                f"Strategy {name} cooling down on {trade.symbol}",
+                f"{reason}; new entries blocked for {int(config.cooldown_secs)}s",
+                strategy=name,
+                symbol=trade.symbol,
+            )
+
+    def _breach(self, config: GuardrailConfig, session: _Session) -> Optional[str]:
+        if config.max_trades_per_day is not None and len(session.orders) >= config.max_trades_per_day:
+            return f"{len(session.orders)} trades reached the daily limit of {config.max_trades_per_day}"
+        if config.max_daily_loss is not None:
+            realized = self._realized(session)
+            if realized <= -config.max_daily_loss:
+                return f"realized PnL {realized} breached the daily loss limit of {config.max_daily_loss}"
+        return None
+
+    async def _halt(self, name: str, reason: str) -> None:
+        self._strategies[name].pause(reason)
+        cancelled = failed = 0
+        for order in await self._router.orders.open_orders():
+            if strategy_tag({"trace_id": order.trace_id}) != name:
+                continue
+            try:
+                await self._router.cancel(order.symbol, order.client_order_index)
+                cancelled += 1
+            except Exception as exc:
+                failed += 1
+                self._logger.info(
+                    "strategy_guardrail_cancel_error",
+                    extra={"strategy": name, "client_order_index": order.client_order_index, "error": str(exc)},
+                )
+        self._logger.warning(
+            "strategy_guardrail_breached",
+            extra={"strategy": name, "reason": reason, "cancelled": cancelled, "failed": failed},
+        )
+        if self._alerts is not None:
+            await self._alerts.notify(
+                AlertLevel.WARNING, f"Strategy {name} paused", reason, strategy=name, cancelled=cancelled
+            )
+
+    async def _run(self) -> None:
+        sub = self._events.subscribe(kinds=[EventKind.FILL], name="strategy-guardrails", report_lag=True)
+        try:
+            while True:
+                try:
+                    event = await sub.get()
+                except EventsLagged as exc:
+                    # Dropped fills would undercount trades and losses against the limits
+                    self._logger.warning("strategy_guardrail_fills_lagged", extra={"missed": exc.missed})
+                    try:
+                        await self.resync()
+                    except Exception as resync_exc:
+                        self._logger.info("strategy_guardrail_resync_error", extra={"error": str(resync_exc)})
+                    continue
+                try:
+                    await self.on_fill(event.data)
+                except Exception as exc:
+                    self._logger.info("strategy_guardrail_error", extra={"error": str(exc), "fill": event.data})
+        finally:
+            sub.close()
+
+
+__all__ = ["GuardrailConfig", "SessionGuardrails"]
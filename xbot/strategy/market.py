@@ -1,7 +1,5 @@
 from __future__ import annotations
 
-from decimal import Decimal
-
 from xbot.core.clock import WallClock
 from xbot.execution.router import ExecutionRouter
 from .base import Strategy, StrategyConfig
@@ -12,12 +10,15 @@ class MarketOrderStrategy(Strategy):
 
     async def start(self) -> None:
         await super().start()
-        size_i = await self.router.market_data.to_size_i(self.config.symbol, Decimal(str(self.config.qty)))
+        size_i = await self.router.market_data.to_size_i(self.config.symbol, await self.order_qty())
+        await self.wait_for_latency()
+        self.ensure_active(symbol=self.config.symbol, reduce_only=self.config.reduce_only)
         await self.router.submit_market(
             symbol=self.config.symbol,
             is_ask=self.config.side == "sell",
             size_i=size_i,
             reduce_only=self.config.reduce_only,
+            trace_id=self.trace_id(),
         )
 
 
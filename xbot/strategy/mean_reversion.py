@@ -0,0 +1,212 @@
+from __future__ import annotations
+
+import time
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import List, Optional, Sequence
+
+from xbot.analytics.kelly import KellyService
+from xbot.core.clock import WallClock
+from xbot.execution.candles import CandleService
+from xbot.execution.maker_first import MakerFirstPolicy
+from xbot.execution.risk_service import RiskViolationError
+from xbot.execution.router import ExecutionRouter
+from xbot.execution.sizing import VolatilitySizer
+from xbot.utils.logging import get_logger
+from .base import Strategy, StrategyConfig, StrategyPaused
+
+
+@dataclass(slots=True)
+class MeanReversionConfig:
+    window: int = 20  # closed candles in the mean and band
+    entry_z: Decimal = Decimal(2)  # enter against a close this many deviations from the mean
+    exit_z: Decimal = Decimal("0.5")  # take profit once back within this many
+    stop_z: Optional[Decimal] = Decimal(4)  # cut the trade if the move keeps going this far
+    max_hold_secs: Optional[float] = 3600.0  # close a trade that has not reverted by then
+    policy: MakerFirstPolicy = field(default_factory=MakerFirstPolicy)  # entries and exits
+
+
+@dataclass(slots=True)
+class Band:
+    mean: Decimal
+    std: Decimal
+    close: Decimal
+
+    @property
+    def z(self) -> Decimal:
+        return (self.close - self.mean) / self.std if self.std else Decimal(0)
+
+    def upper(self, k: Decimal) -> Decimal:
+        return self.mean + k * self.std
+
+    def lower(self, k: Decimal) -> Decimal:
+        return self.mean - k * self.std
+
+
+def bollinger(closes: Sequence[Decimal], window: int) -> Optional[Band]:
+    """Mean and population deviation of the last `window` closes; None until that many exist."""
+    if window < 2 or len(closes) < window:
+        return None
+    recent = list(closes[-window:])
+    mean = sum(recent, Decimal(0)) / window
+    variance = sum(((c - mean) ** 2 for c in recent), Decimal(0)) / window
+    return Band(mean=mean, std=variance.sqrt(), close=recent[-1])
+
+
+class MeanReversionStrategy(Strategy):
+    """Reference scalper: fade closes outside a Bollinger band, exit on reversion.
+
+    Every `config.interval_secs` it reads the candle store for the
+    strategy's symbol and computes the z-score of the last close against the
+    mean of the last `window` closes. Flat, a z beyond `entry_z` enters
+    against the move: long below the lower band, short above the upper.
+    Holding, the trade is closed once z is back within `exit_z`, when it
+    extends past `stop_z`, or after `max_hold_secs`.
+
+    It exercises the strategy API end to end, which makes it the template
+    for new strategies:
+    - entries are sized by `order_qty()` (fixed, volatility or Kelly) and
+      capped at `router.risk.max_order_size` for the side actually traded;
+    - `ensure_active()` gates entries, so guardrails and event halts stop
+      new trades while exits still go out;
+    - orders go through `router.maker_first`, which posts PostOnly at the
+      touch and crosses only as a last resort;
+    - a `RiskViolationError` from the risk checks is logged and the signal
+      re-evaluated next bar, never retried in a loop.
+    The position is read back from `router.positions`, so fills from a
+    crossed remainder or a manual flatten are picked up.
+    """
+
+    def __init__(
+        self,
+        *,
+        router: ExecutionRouter,
+        clock: WallClock,
+        config: StrategyConfig,
+        candles: CandleService,
+        params: Optional[MeanReversionConfig] = None,
+        sizer: Optional[VolatilitySizer] = None,
+        kelly: Optional[KellyService] = None,
+    ) -> None:
+        super().__init__(router=router, clock=clock, config=config, sizer=sizer, kelly=kelly)
+        self._candles = candles
+        self._params = params or MeanReversionConfig()
+        self._entered_at: Optional[float] = None
+        self._logger = get_logger(__name__)
+
+    @property
+    def params(self) -> MeanReversionConfig:
+        return self._params
+
+    def band(self) -> Optional[Band]:
+        venue_symbol = self.router.market_data.resolve_symbol(self.config.symbol)
+        closes: List[Decimal] = [c.close for c in self._candles.candles(venue_symbol, self._params.window)]
+        return bollinger(closes, self._params.window)
+
+    async def position(self) -> Decimal:
+        snapshot = await self.router.positions.get_position(self.config.symbol)
+        return snapshot.base_qty if snapshot else Decimal(0)
+
+    def entry_side(self, band: Band) -> Optional[bool]:
+        """`is_ask` of the entry `band` calls for, or None."""
+        if band.z >= self._params.entry_z:
+            return True
+        if band.z <= -self._params.entry_z:
+            return False
+        return None
+
+    def exit_reason(self, band: Band, position: Decimal, now: float) -> Optional[str]:
+        # z in the direction of the original move: it shrinks as the price reverts toward the mean
+        adverse = -band.z if position > 0 else band.z
+        if adverse <= self._params.exit_z:
+            return "reverted"
+        if self._params.stop_z is not None and adverse >= self._params.stop_z:
+            return "stop"
+        if self._params.max_hold_secs is not None and self._entered_at is not None:
+            if now - self._entered_at >= self._params.max_hold_secs:
+                return "max_hold"
+        return None
+
+    async def step(self, now: Optional[float] = None) -> Optional[str]:
+        """Evaluate the latest bar once; returns the action taken (enter_long, enter_short, exit) or None."""
+        now = time.time() if now is None else now
+        band = self.band()
+        if band is None:
+            return None
+        position = await self.position()
+        if position:
+            reason = self.exit_reason(band, position, now)
+            if reason is None:
+                return None
+            await self._exit(position, band, reason)
+            return "exit"
+        self._entered_at = None
+        is_ask = self.entry_side(band)
+        if is_ask is None:
+            return None
+        return await self._enter(is_ask, band, now)
+
+    async def _enter(self, is_ask: bool, band: Band, now: float) -> Optional[str]:
+        symbol = self.config.symbol
+        try:
+            self.ensure_active(symbol=symbol)
+            qty = await self.order_qty()
+            cap = await self.router.risk.max_order_size(symbol, is_ask=is_ask)
+            if cap is not None:
+                qty = min(qty, cap)
+            size_i = await self.router.market_data.to_size_i(symbol, qty)
+            if size_i <= 0:
+                return None
+            await self.wait_for_latency()
+            result = await self.router.maker_first(
+                symbol=symbol, is_ask=is_ask, size_i=size_i, policy=self._params.policy, trace_id=self.trace_id()
+            )
+        except (StrategyPaused, RiskViolationError) as exc:
+            self._logger.info("mean_reversion_entry_blocked", extra={"symbol": symbol, "reason": str(exc)})
+            return None
+        self._entered_at = now
+        action = "enter_short" if is_ask else "enter_long"
+        self._logger.info(
+            "mean_reversion_entry",
+            extra={
+                "symbol": symbol,
+                "action": action,
+                "z": str(band.z),
+                "mean": str(band.mean),
+                "size_i": size_i,
+                "crossed": result.crossed,
+            },
+        )
+        return action
+
+    async def _exit(self, position: Decimal, band: Band, reason: str) -> None:
+        symbol = self.config.symbol
+        size_i = await self.router.market_data.to_size_i(symbol, abs(position))
+        try:
+            result = await self.router.maker_first(
+                symbol=symbol,
+                is_ask=position > 0,
+                size_i=size_i,
+                policy=self._params.policy,
+                reduce_only=1,
+                trace_id=self.trace_id(),
+            )
+        except RiskViolationError as exc:
+            self._logger.info("mean_reversion_exit_blocked", extra={"symbol": symbol, "reason": str(exc)})
+            return
+        self._logger.info(
+            "mean_reversion_exit",
+            extra={"symbol": symbol, "reason": reason, "z": str(band.z), "size_i": size_i, "crossed": result.crossed},
+        )
+
+    async def start(self) -> None:
+        await super().start()
+        while self._running:
+            try:
+                await self.step()
+            except Exception as exc:
+                self._logger.info("mean_reversion_error", extra={"symbol": self.config.symbol, "error": str(exc)})
+            await self.clock.sleep(self.config.interval_secs)
+
+
+__all__ = ["Band", "MeanReversionConfig", "MeanReversionStrategy", "bollinger"]
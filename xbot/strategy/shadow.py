@@ -0,0 +1,233 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+from dataclasses import dataclass
+from decimal import Decimal
+from pathlib import Path
+from typing import Any, Callable, Dict, Optional, Tuple
+
+from xbot.connector.simulated import SimMarket, SimulatedConnector
+from xbot.core.cache import MarketCache
+from xbot.execution.decisions import DecisionLog
+from xbot.execution.market_data_service import MarketDataService
+from xbot.execution.order_service import OrderService, OrderUpdatePayload, normalize_order_state
+from xbot.execution.position_service import PositionService, PositionSnapshot
+from xbot.execution.recovery import _position_qty
+from xbot.execution.risk_service import RiskLimits, RiskService
+from xbot.execution.router import ExecutionRouter
+from xbot.execution.tracking_limit import TrackingLimitEngine
+from xbot.utils.logging import get_logger
+from .base import Strategy
+
+
+@dataclass(slots=True)
+class ShadowConfig:
+    mode: str  # the candidate's strategy mode; may equal the live one with other parameters
+    name: str  # trace_id prefix and decision `variant` of the candidate
+    qty: Optional[float] = None  # overrides the live qty
+    interval_secs: Optional[float] = None  # overrides the live interval
+    collateral: Decimal = Decimal(10_000)  # paper account balance
+    mirror_secs: float = 1.0  # how often the paper book follows the live touch
+    log_root: str = "logs/shadow"
+    params: Optional[Any] = None  # MeanReversionConfig/BreakoutConfig for the candidate's mode
+
+
+class ShadowRunner:
+    """Runs a candidate strategy on paper next to the live one, on the same market data.
+
+    The candidate gets its own execution stack (orders, positions, risk
+    with the live limits, router) over a `SimulatedConnector` whose book
+    follows the live top of book (the WS-fed cache when there is one)
+    every `mirror_secs`. It reads the live
+    candle store, so both versions see the same bars. Its orders fill on
+    paper at the live touch and never reach the venue. Both routers write
+    to one `DecisionLog`: the live one as `live`, the candidate under its
+    name, so the two decision streams can be compared before promotion.
+    Paper fills are not written to `fills`, which keeps the journal,
+    guardrails and Kelly sizing on real trades only. Paper PnL is in
+    `snapshot()`.
+    """
+
+    def __init__(
+        self,
+        *,
+        market_data: MarketDataService,
+        cache: Optional[MarketCache] = None,
+        symbol: str,
+        venue: str,
+        limits: RiskLimits,
+        decisions: DecisionLog,
+        config: ShadowConfig,
+        factory: Callable[[ExecutionRouter], Strategy],
+    ) -> None:
+        self._live = market_data
+        self._live_cache = cache
+        self._symbol = symbol
+        self._venue = f"{venue}-shadow"
+        self._limits = limits
+        self._decisions = decisions
+        self._config = config
+        self._factory = factory
+        self._market: Optional[SimMarket] = None
+        self._connector: Optional[SimulatedConnector] = None
+        self._cache: Optional[MarketCache] = None
+        self._market_data: Optional[MarketDataService] = None
+        self._positions: Optional[PositionService] = None
+        self._router: Optional[ExecutionRouter] = None
+        self._strategy: Optional[Strategy] = None
+        self._tasks: list[asyncio.Task] = []
+        self._logger = get_logger(__name__)
+
+    @property
+    def router(self) -> Optional[ExecutionRouter]:
+        return self._router
+
+    @property
+    def strategy(self) -> Optional[Strategy]:
+        return self._strategy
+
+    async def _build(self) -> ExecutionRouter:
+        venue_symbol = self._live.resolve_symbol(self._symbol)
+        price_decimals, size_decimals = await self._live.get_price_size_decimals(self._symbol)
+        touch = await self._live_touch()
+        if touch is None:
+            raise RuntimeError(f"no live book for {self._symbol}; the shadow needs one to start")
+        self._market = SimMarket(
+            venue_symbol,
+            mark=(touch[0] + touch[1]) / 2,
+            price_decimals=price_decimals,
+            size_decimals=size_decimals,
+            min_size_i=await self._live.get_min_size_i(self._symbol),
+            funding_rate=Decimal(0),
+        )
+        self._connector = SimulatedConnector(
+            markets=[self._market], collateral=self._config.collateral, venue=self._venue
+        )
+        self._market_data = MarketDataService(connector=self._connector, symbol_map={self._symbol: venue_symbol})
+        self._cache = MarketCache(source=self._venue)
+        self._market_data.attach_cache(self._cache)
+        self._positions = PositionService()
+        risk = RiskService(market_data=self._market_data, position_service=self._positions, limits=self._limits)
+        orders = OrderService(
+            connector=self._connector,
+            market_data=self._market_data,
+            risk_service=risk,
+            tracking_engine=TrackingLimitEngine(market_data=self._market_data),
+            log_root=Path(self._config.log_root) / "orders",
+        )
+
+        async def on_order_update(client_order_index: int, status: str, info: Dict[str, Any]) -> None:
+            state = normalize_order_state(status)
+            if state is None:
+                return
+            payload = OrderUpdatePayload(client_order_index, state, str(info.get("exchange_order_id") or ""), info)
+            with contextlib.suppress(Exception):
+                await orders.ingest_update(payload)
+
+        self._connector.attach_order_updates(on_order_update)
+        router = ExecutionRouter(
+            order_service=orders,
+            position_service=self._positions,
+            risk_service=risk,
+            market_data=self._market_data,
+            cache=self._cache,
+        )
+        router.attach_decisions(self._decisions, self._config.name)
+        return router
+
+    async def _live_touch(self) -> Optional[Tuple[Decimal, Decimal]]:
+        """Live bid/ask from the WS-fed cache, or from the venue when the cache has none."""
+        venue_symbol = self._live.resolve_symbol(self._symbol)
+        if self._live_cache is not None:
+            bid, ask, _ = self._live_cache.orderbooks.get(venue_symbol, (None, None, 0.0))
+            if bid and ask:
+                return Decimal(str(bid)), Decimal(str(ask))
+        bid_i, ask_i, scale = await self._live.get_top_of_book(self._symbol)
+        if not bid_i or not ask_i:
+            return None
+        return Decimal(bid_i) / Decimal(scale), Decimal(ask_i) / Decimal(scale)
+
+    async def mirror(self) -> None:
+        """Move the paper book to the live touch, deliver the resulting fills and refresh paper positions."""
+        assert self._market is not None and self._connector is not None and self._cache is not None
+        touch = await self._live_touch()
+        if touch is None:
+            return
+        bid, ask = touch
+        mid = (bid + ask) / 2
+        self._market.spread_bps = (ask - bid) / mid * Decimal(10_000)
+        self._connector.set_mark(self._market.symbol, mid)
+        await self._cache.set_top(self._market.symbol, float(self._market.bid), float(self._market.ask))
+        await self._connector.flush_updates()
+        await self._sync_positions()
+
+    async def _sync_positions(self) -> None:
+        assert self._connector is not None and self._market_data is not None and self._positions is not None
+        await self._positions.reset()
+        for raw in await self._connector.get_positions():
+            symbol = self._market_data.canonical_for(str(raw.get("symbol")))
+            qty = _position_qty(raw)
+            if symbol is None or qty is None:
+                continue
+            notional = Decimal(str(raw.get("netExposureNotional") or 0))
+            await self._positions.ingest(
+                PositionSnapshot(symbol=symbol, base_qty=qty, quote_value=notional, notional=notional, raw=raw)
+            )
+
+    def snapshot(self) -> Dict[str, Any]:
+        if self._connector is None:
+            return {"variant": self._config.name, "running": False}
+        return {
+            "variant": self._config.name,
+            "mode": self._config.mode,
+            "running": bool(self._tasks),
+            "pnl": str(self._connector.equity() - self._config.collateral),
+            "fees": str(self._connector.fees_paid),
+            "exposure": str(self._connector.exposure()),
+            "decisions": len(self._decisions.recent(self._config.name, limit=10_000)),
+        }
+
+    async def start(self) -> None:
+        if self._tasks:
+            return
+        self._router = await self._build()
+        await self.mirror()
+        self._strategy = self._factory(self._router)
+        self._tasks = [
+            asyncio.create_task(self._run_mirror(), name="shadow-mirror"),
+            asyncio.create_task(self._run_strategy(self._strategy), name="shadow-strategy"),
+        ]
+        self._logger.info(
+            "shadow_start", extra={"variant": self._config.name, "mode": self._config.mode, "symbol": self._symbol}
+        )
+
+    async def stop(self) -> None:
+        if not self._tasks:
+            return
+        if self._strategy is not None:
+            await self._strategy.stop()
+        for task in self._tasks:
+            task.cancel()
+        for task in self._tasks:
+            with contextlib.suppress(asyncio.CancelledError):
+                await task
+        self._tasks = []
+        self._logger.info("shadow_stop", extra=self.snapshot())
+
+    async def _run_mirror(self) -> None:
+        while True:
+            try:
+                await self.mirror()
+            except Exception as exc:
+                self._logger.info("shadow_mirror_error", extra={"variant": self._config.name, "error": str(exc)})
+            await asyncio.sleep(self._config.mirror_secs)
+
+    async def _run_strategy(self, strategy: Strategy) -> None:
+        try:
+            await strategy.start()
+        except Exception as exc:
+            self._logger.info("shadow_strategy_error", extra={"variant": self._config.name, "error": str(exc)})
+
+
+__all__ = ["ShadowConfig", "ShadowRunner"]
@@ -1,10 +1,11 @@
 from __future__ import annotations
 
-from decimal import Decimal
-from typing import Optional
+from typing import Dict, Optional
 
+from xbot.analytics.kelly import KellyService
 from xbot.core.clock import WallClock
 from xbot.execution.router import ExecutionRouter
+from xbot.execution.sizing import VolatilitySizer
 from .base import Strategy, StrategyConfig
 
 
@@ -18,16 +19,20 @@ class TrackingLimitStrategy(Strategy):
         clock: WallClock,
         config: StrategyConfig,
         wait_after_fill: float = 5.0,
+        sizer: Optional[VolatilitySizer] = None,
+        kelly: Optional[KellyService] = None,
     ) -> None:
-        super().__init__(router=router, clock=clock, config=config)
+        super().__init__(router=router, clock=clock, config=config, sizer=sizer, kelly=kelly)
         self._wait_after_fill = wait_after_fill
 
     async def start(self) -> None:
         await super().start()
         symbol = self.config.symbol
-        qty = Decimal(str(self.config.qty))
+        qty = await self.order_qty()
         is_ask_open = self.config.side == "sell"
         size_i = await self.router.market_data.to_size_i(symbol, qty)
+        await self.wait_for_latency()
+        self.ensure_active(symbol=symbol)
         tracking = await self.router.tracking_limit(
             symbol=symbol,
             base_amount_i=size_i,
@@ -36,6 +41,8 @@ class TrackingLimitStrategy(Strategy):
             interval_secs=self.config.interval_secs,
             timeout_secs=self.config.timeout_secs,
             post_only=True,
+            trace_id=self.trace_id(),
+            observer=self._stop_when_paused,
         )
         await tracking.wait_final()
         await self.clock.sleep(self._wait_after_fill)
@@ -44,7 +51,13 @@ class TrackingLimitStrategy(Strategy):
             is_ask=not is_ask_open,
             size_i=size_i,
             reduce_only=1,
+            trace_id=self.trace_id(),
         )
 
+    async def _stop_when_paused(self, stage: str, info: Dict[str, object]) -> None:
+        # A pause cancels the resting attempt; this keeps the tracker from replacing it
+        if stage == "before_submit":
+            self.ensure_active(symbol=str(info["symbol"]))
+
 
 __all__ = ["TrackingLimitStrategy"]
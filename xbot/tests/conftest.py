@@ -0,0 +1,81 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+from decimal import Decimal
+from pathlib import Path
+from typing import Any, Callable, Dict, Optional
+
+import pytest
+
+from xbot.connector.simulated import SimMarket, SimulatedConnector
+from xbot.core.cache import MarketCache
+from xbot.execution.market_data_service import MarketDataService
+from xbot.execution.order_service import OrderService, OrderUpdatePayload, normalize_order_state
+from xbot.execution.position_service import PositionService
+from xbot.execution.risk_service import RiskLimits, RiskService
+from xbot.execution.tracking_limit import TrackingLimitEngine
+from xbot.storage.base import StorageWriter
+
+SYMBOL = "SOL"
+VENUE_SYMBOL = "SOL_USD_PERP"
+
+
+@dataclass
+class SimStack:
+    """An OrderService wired to a `SimulatedConnector`, the way `stress.py` runs one."""
+
+    connector: SimulatedConnector
+    market_data: MarketDataService
+    positions: PositionService
+    risk: RiskService
+    orders: OrderService
+    cache: MarketCache
+
+    @property
+    def market(self) -> SimMarket:
+        return self.connector.markets[VENUE_SYMBOL]
+
+    async def quote(self) -> None:
+        await self.cache.set_top(VENUE_SYMBOL, float(self.market.bid), float(self.market.ask))
+
+
+@pytest.fixture
+def sim_stack(tmp_path: Path) -> Callable[..., SimStack]:
+    """Factory for a `SimStack`; call it inside the test's event loop."""
+
+    def build(
+        *,
+        mark: Decimal = Decimal("100"),
+        collateral: Decimal = Decimal("10000"),
+        limits: Optional[RiskLimits] = None,
+        storage: Optional[StorageWriter] = None,
+        connector: Optional[SimulatedConnector] = None,
+        **risk_kwargs: Any,
+    ) -> SimStack:
+        """`connector` reuses a venue, e.g. to restart the bot against the orders it left there."""
+        if connector is None:
+            connector = SimulatedConnector(markets=[SimMarket(VENUE_SYMBOL, mark=mark)], collateral=collateral)
+        market_data = MarketDataService(connector=connector, symbol_map={SYMBOL: VENUE_SYMBOL})
+        cache = MarketCache(source=connector.venue)
+        market_data.attach_cache(cache)
+        positions = PositionService()
+        risk = RiskService(market_data=market_data, position_service=positions, limits=limits, **risk_kwargs)
+        orders = OrderService(
+            connector=connector,
+            market_data=market_data,
+            risk_service=risk,
+            tracking_engine=TrackingLimitEngine(market_data=market_data),
+            log_root=tmp_path / "orders",
+            storage=storage,
+        )
+
+        async def on_order_update(client_order_index: int, status: str, info: Dict[str, Any]) -> None:
+            state = normalize_order_state(status)
+            if state is not None:
+                payload = OrderUpdatePayload(client_order_index, state, str(info.get("exchange_order_id") or ""), info)
+                await orders.ingest_update(payload)
+
+        connector.attach_order_updates(on_order_update)
+        return SimStack(connector, market_data, positions, risk, orders, cache)
+
+    return build
@@ -0,0 +1,44 @@
+from __future__ import annotations
+
+from typing import Any, List
+
+import pytest
+
+from xbot.core.alerts import AlertLevel, AlertManager, WebhookAlertSink
+from xbot.core.metrics import METRICS
+
+
+class FakeResponse:
+    def __init__(self, status_code: int) -> None:
+        self.status_code = status_code
+
+
+class FakeClient:
+    """Stands in for the sink's `httpx.AsyncClient`, answering with queued status codes."""
+
+    def __init__(self, *statuses: int) -> None:
+        self.statuses = list(statuses)
+        self.posted: List[Any] = []
+
+    async def post(self, url: str, **kwargs: Any) -> FakeResponse:
+        self.posted.append(kwargs["json"])
+        return FakeResponse(self.statuses.pop(0))
+
+
+def _sink(client: FakeClient) -> WebhookAlertSink:
+    sink = WebhookAlertSink(url="https://hooks.example.com/xbot")
+    sink._client = client  # type: ignore[assignment]
+    return sink
+
+
+@pytest.mark.asyncio
+async def test_rejected_webhook_deliveries_are_counted():
+    client = FakeClient(200, 500, 429)
+    manager = AlertManager([_sink(client)])
+    before = METRICS.get("alert_failures", sink="WebhookAlertSink", level="critical")
+
+    for _ in range(3):
+        await manager.notify(AlertLevel.CRITICAL, "Withdrawal failed")
+
+    assert len(client.posted) == 3
+    assert METRICS.get("alert_failures", sink="WebhookAlertSink", level="critical") == before + 2
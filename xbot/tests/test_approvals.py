@@ -0,0 +1,138 @@
+from __future__ import annotations
+
+import asyncio
+import json
+import re
+from decimal import Decimal
+from typing import Any, Dict, List
+
+import pytest
+
+from xbot.app.admin_api import AdminApi, AdminApiConfig
+from xbot.app.config import load_config
+from xbot.core.alerts import Alert, AlertManager
+from xbot.core.approvals import (
+    CONFIRMATION_CODE,
+    FLATTEN_ALL,
+    WITHDRAW,
+    ApprovalConfig,
+    ApprovalDenied,
+    ApprovalGate,
+    ApprovalStatus,
+)
+from xbot.execution.risk_service import RiskLimits
+
+
+class ListSink:
+    def __init__(self) -> None:
+        self.alerts: List[Alert] = []
+
+    async def send(self, alert: Alert) -> None:
+        self.alerts.append(alert)
+
+
+class FakeRequest:
+    """What the admin handlers read from a request: the JSON body and the authenticated operator."""
+
+    def __init__(self, body: Dict[str, Any], operator: str) -> None:
+        self._body = body
+        self._operator = operator
+
+    async def json(self) -> Dict[str, Any]:
+        return self._body
+
+    def get(self, key: str, default: Any = None) -> Any:
+        return self._operator if key == "operator" else default
+
+
+class Action:
+    """Counts how often the gated action actually ran."""
+
+    def __init__(self) -> None:
+        self.runs = 0
+
+    async def __call__(self) -> str:
+        self.runs += 1
+        await asyncio.sleep(0)
+        return "done"
+
+
+@pytest.mark.asyncio
+async def test_second_operator_must_approve_and_the_action_runs_once():
+    gate, action = ApprovalGate(), Action()
+    pending = await gate.submit(WITHDRAW, "withdraw 100 USDC", action, requested_by="alice")
+    assert pending.status == ApprovalStatus.PENDING and action.runs == 0
+
+    with pytest.raises(ApprovalDenied):
+        await gate.approve(pending.approval_id, operator="alice")
+    results = await asyncio.gather(
+        gate.approve(pending.approval_id, operator="bob"),
+        gate.approve(pending.approval_id, operator="carol"),
+        return_exceptions=True,
+    )
+
+    assert action.runs == 1
+    assert sum(isinstance(result, ApprovalDenied) for result in results) == 1
+    assert pending.status == ApprovalStatus.EXECUTED and pending.decided_by == "bob" and pending.result == "done"
+
+
+@pytest.mark.asyncio
+async def test_confirmation_code_only_goes_to_the_alert_sinks():
+    sink, action = ListSink(), Action()
+    gate = ApprovalGate(ApprovalConfig(mode=CONFIRMATION_CODE), alerts=AlertManager([sink]))
+    pending = await gate.submit(FLATTEN_ALL, "flatten every position", action, requested_by="alice")
+
+    assert "code" not in pending.to_dict()
+    (alert,) = sink.alerts
+    code = re.search(r"code (\d{6})", alert.body).group(1)
+    with pytest.raises(ApprovalDenied):
+        await gate.approve(pending.approval_id, operator="alice", code="not-it")
+    await gate.approve(pending.approval_id, operator="alice", code=code)
+    assert action.runs == 1
+
+
+@pytest.mark.asyncio
+async def test_ungated_actions_run_at_once_and_expired_ones_never():
+    action = Action()
+    gate = ApprovalGate(ApprovalConfig(actions=[WITHDRAW], ttl_secs=0.01))
+    ran = await gate.submit(FLATTEN_ALL, "flatten", action, requested_by="alice")
+    assert ran.status == ApprovalStatus.EXECUTED and action.runs == 1
+
+    pending = await gate.submit(WITHDRAW, "withdraw", action, requested_by="alice")
+    await asyncio.sleep(0.02)
+    with pytest.raises(ApprovalDenied, match="expired"):
+        await gate.approve(pending.approval_id, operator="bob")
+    assert (await gate.reject(pending.approval_id, operator="bob")).status == ApprovalStatus.EXPIRED
+    assert action.runs == 1
+
+
+@pytest.mark.asyncio
+async def test_approved_raise_keeps_a_limit_tightened_while_it_was_pending(sim_stack, monkeypatch):
+    risk = sim_stack().risk
+    configured = RiskLimits(max_position=Decimal("10"), max_notional=Decimal("1000"))
+    risk.set_limits(configured)
+    routes = {}
+    monkeypatch.setattr(AdminApi, "add_route", lambda self, method, path, handler: routes.update({(method, path): handler}))
+    gate = ApprovalGate()
+    AdminApi(AdminApiConfig(token="t")).mount_risk_limits(risk, gate, maxima=configured)
+    update = routes[("POST", "/risk/limits")]
+
+    raised = await update(FakeRequest({"max_position": 20}, "alice"))
+    assert raised.status == 202 and risk.limits.max_position == Decimal("10")
+    assert (await update(FakeRequest({"max_notional": 500}, "alice"))).status == 200
+    await gate.approve(json.loads(raised.text)["approval_id"], operator="bob")
+
+    assert (risk.limits.max_position, risk.limits.max_notional) == (Decimal("20"), Decimal("500"))
+
+
+def test_approvals_are_on_unless_turned_off(tmp_path):
+    def load(payload: Dict[str, Any]):
+        path = tmp_path / "config.json"
+        path.write_text(json.dumps(payload), encoding="utf-8")
+        return load_config(venue="backpack", symbol="SOL", qty=1, config_path=str(path))
+
+    assert load({}).approval_config.enabled
+    with pytest.raises(ValueError, match="two admin operators"):
+        load({"admin": {"token": "t"}})  # one operator cannot approve their own flatten
+    assert load({"admin": {"token": "t"}, "approvals": {"mode": CONFIRMATION_CODE}}).approval_config.enabled
+    assert not load({"admin": {"token": "t"}, "approvals": {"enabled": False}}).approval_config.enabled
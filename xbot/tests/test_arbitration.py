@@ -0,0 +1,30 @@
+from __future__ import annotations
+
+from decimal import Decimal
+
+import pytest
+
+from xbot.execution.arbitration import ArbitrationConfig, ArbitrationError, PositionArbiter
+from xbot.execution.models import OrderState
+from xbot.execution.order_service import OrderUpdatePayload
+
+from .conftest import SYMBOL
+
+
+@pytest.mark.asyncio
+async def test_partial_fills_count_toward_combined_exposure(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    arbiter = PositionArbiter(ArbitrationConfig(max_combined={SYMBOL: Decimal("1")}))
+    arbiter.register(stack.orders)
+
+    order = await stack.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="1", price="90", trace_id="a:1")
+    payload = OrderUpdatePayload(order.client_order_index, OrderState.PARTIALLY_FILLED, info={"executedQuantity": "0.4"})
+    await stack.orders.ingest_update(payload)
+    exposure = (await arbiter.exposures(SYMBOL))["a"]
+    assert (exposure.filled_i, exposure.pending_long_i) == (400, 600)
+
+    await stack.orders.cancel(SYMBOL, order.client_order_index)
+    with pytest.raises(ArbitrationError):
+        await stack.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="0.7", price="90", trace_id="b:1")
+    await stack.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="0.6", price="90", trace_id="b:2")
@@ -0,0 +1,79 @@
+from __future__ import annotations
+
+import asyncio
+from typing import Any, Dict, List, Optional
+
+import pytest
+
+from xbot.connector.backpack import BackpackConnector
+from xbot.connector.base import ConnectorConfig
+
+MARKET = "SOL_USD_PERP"
+
+
+class FakeAccount:
+    """An account whose order POSTs time out; `lands` decides whether the venue still took the order."""
+
+    def __init__(self, *, lands: bool = False, lookup_fails: bool = False) -> None:
+        self.lands = lands
+        self.lookup_fails = lookup_fails
+        self.sent: List[Dict[str, Any]] = []
+        self.orders: List[Dict[str, Any]] = []
+
+    async def execute_order(self, **order: Any) -> Dict[str, Any]:
+        self.sent.append(order)
+        if self.lands:
+            self.orders.append({"id": f"ex-{len(self.sent)}", "symbol": MARKET, "clientId": order["client_id"], "status": "New"})
+        raise asyncio.TimeoutError()
+
+    async def get_open_order(self, *, symbol: str, client_id: int) -> Dict[str, Any]:
+        if self.lookup_fails:
+            raise RuntimeError("venue unavailable")
+        for row in self.orders:
+            if row["clientId"] == client_id:
+                return row
+        return {"code": "RESOURCE_NOT_FOUND", "message": "Order not found"}
+
+    async def get_order_history(self, *, symbol: Optional[str], limit: int, offset: int) -> List[Dict[str, Any]]:
+        return []
+
+
+def _connector(tmp_path, account: FakeAccount, retries: int = 1) -> BackpackConnector:
+    config = ConnectorConfig(order_retries=retries, order_lookup_delay_secs=0)
+    connector = BackpackConnector(key_path=tmp_path / "keys.json", config=config)
+    connector._account = account  # type: ignore[assignment]
+    connector._markets[MARKET] = {
+        "symbol": MARKET,
+        "filters": {"price": {"tickSize": "0.01"}, "quantity": {"stepSize": "0.01", "minQuantity": "0.01"}},
+    }
+    return connector
+
+
+async def _submit(connector: BackpackConnector) -> str:
+    return await connector.submit_limit_order(
+        symbol=MARKET, client_order_index=7, base_amount=100, price=10000, is_ask=False
+    )
+
+
+@pytest.mark.asyncio
+async def test_timed_out_order_that_landed_is_not_resent(tmp_path):
+    account = FakeAccount(lands=True)
+    assert await _submit(_connector(tmp_path, account, retries=3)) == "ex-1"
+    assert len(account.sent) == 1
+
+
+@pytest.mark.asyncio
+async def test_timed_out_order_the_venue_never_saw_is_resent_up_to_the_limit(tmp_path):
+    account = FakeAccount()
+    with pytest.raises(asyncio.TimeoutError):
+        await _submit(_connector(tmp_path, account, retries=2))
+    assert len(account.sent) == 3
+    assert {order["client_id"] for order in account.sent} == {7}  # resends reuse the idempotency key
+
+
+@pytest.mark.asyncio
+async def test_failed_lookup_raises_the_timeout_instead_of_resending(tmp_path):
+    account = FakeAccount(lookup_fails=True)
+    with pytest.raises(asyncio.TimeoutError):
+        await _submit(_connector(tmp_path, account, retries=3))
+    assert len(account.sent) == 1
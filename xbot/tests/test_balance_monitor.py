@@ -0,0 +1,77 @@
+from __future__ import annotations
+
+import asyncio
+import time
+from decimal import Decimal
+from typing import Any, Dict, List, Optional
+
+import pytest
+
+from xbot.analytics.balance_monitor import BalanceMonitor
+from xbot.core.alerts import AlertManager
+from xbot.execution.withdrawals import AllowedAddress, WithdrawalConfig, WithdrawalGuard
+
+ADDRESS = "So1anaTreasury"
+
+
+class FakeVenue:
+    """A USDC balance with Backpack's history endpoints; only withdrawals move it."""
+
+    venue = "fake"
+
+    def __init__(self) -> None:
+        self.usdc = Decimal("1000")
+        self.withdrawals: List[Dict[str, Any]] = []
+
+    def withdraw(self, quantity: Decimal) -> Dict[str, Any]:
+        """What the venue records for any withdrawal, whoever sent it."""
+        self.usdc -= quantity + Decimal(1)
+        row = {
+            "id": str(len(self.withdrawals) + 1),
+            "symbol": "USDC",
+            "quantity": str(quantity),
+            "fee": "1",
+            "status": "confirmed",
+            "createdAt": int(time.time() * 1000),
+        }
+        self.withdrawals.append(row)
+        return row
+
+    async def request_withdrawal(
+        self, *, symbol: str, blockchain: str, address: str, quantity: Decimal, client_id: Optional[int] = None
+    ) -> Dict[str, Any]:
+        return self.withdraw(quantity)
+
+    async def get_margin(self) -> Dict[str, Any]:
+        return {"balances": {"USDC": {"available": str(self.usdc)}}}
+
+    async def get_fill_history(self, **_: Any) -> List[Dict[str, Any]]:
+        return []
+
+    async def get_funding_payments(self, **_: Any) -> List[Dict[str, Any]]:
+        return []
+
+    async def get_deposits(self, **_: Any) -> List[Dict[str, Any]]:
+        return []
+
+    async def get_withdrawals(self, *, limit: int, offset: int, **_: Any) -> List[Dict[str, Any]]:
+        return self.withdrawals[offset : offset + limit]
+
+
+@pytest.mark.asyncio
+async def test_only_withdrawals_sent_through_the_guard_are_explained():
+    venue = FakeVenue()
+    allowed = AllowedAddress(symbol="USDC", blockchain="Solana", address=ADDRESS)
+    guard = WithdrawalGuard(connector=venue, config=WithdrawalConfig(enabled=True, allowlist=[allowed]))
+    monitor = BalanceMonitor(connector=venue, alerts=AlertManager([]), withdrawals=guard)  # type: ignore[arg-type]
+    assert await monitor.check() == []
+
+    pending = await guard.request(symbol="USDC", blockchain="Solana", address=ADDRESS, quantity=Decimal("100"))
+    await guard.confirm(pending.withdrawal_id)
+    await asyncio.sleep(0.01)
+    assert await monitor.check() == []
+
+    venue.withdraw(Decimal("50"))  # made around the bot
+    await asyncio.sleep(0.01)
+    (anomaly,) = await monitor.check()
+    assert (anomaly.asset, anomaly.explained, anomaly.residual) == ("USDC", Decimal(0), Decimal("-51"))
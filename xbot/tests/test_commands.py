@@ -0,0 +1,72 @@
+from __future__ import annotations
+
+import asyncio
+import contextlib
+from decimal import Decimal
+
+import pytest
+
+from xbot.core.command_bus import TradingCommand
+from xbot.execution.commands import RouterCommandHandler
+from xbot.execution.maker_first import MAKER_FIRST
+from xbot.execution.models import OrderState
+from xbot.execution.router import ExecutionRouter
+
+from .conftest import SYMBOL
+
+FAST = {"tactic": MAKER_FIRST, "timeout_secs": 0.2, "poll_secs": 0.05, "cancel_wait_secs": 0.1}
+
+
+def _handler(stack) -> RouterCommandHandler:
+    router = ExecutionRouter(
+        order_service=stack.orders, position_service=stack.positions, risk_service=stack.risk, market_data=stack.market_data
+    )
+    return RouterCommandHandler(router)
+
+
+@pytest.mark.asyncio
+async def test_maker_first_command_handled_twice_trades_once(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    handler = _handler(stack)
+    command = TradingCommand(
+        venue=stack.connector.venue, kind=MAKER_FIRST, payload={"symbol": SYMBOL, "is_ask": False, "size": "1"}, policy=FAST
+    )
+
+    first = await handler(command)
+    await stack.connector.flush_updates()
+    again = await handler(command)
+
+    assert first.crossed == "timeout" and again.crossed == "replayed"
+    assert again.taker_order is first.taker_order and again.orders == first.orders
+    assert len(await stack.orders.orders(SYMBOL)) == 2  # one passive attempt, one taker leg
+
+
+@pytest.mark.asyncio
+async def test_tracking_limit_command_handled_twice_trades_once(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    handler = _handler(stack)
+    # Priced through the ask, so the first attempt fills at once
+    payload = {"symbol": SYMBOL, "is_ask": False, "base_amount_i": 1000, "price_offset_ticks": -100}
+    command = TradingCommand(venue=stack.connector.venue, kind="tracking_limit", payload=payload)
+
+    async def deliver() -> None:
+        while True:
+            await stack.connector.flush_updates()
+            await asyncio.sleep(0.01)
+
+    delivery = asyncio.create_task(deliver())
+    try:
+        first = await handler(command)
+        again = await handler(command)
+    finally:
+        delivery.cancel()
+        with contextlib.suppress(asyncio.CancelledError):
+            await delivery
+
+    assert first.order.state == OrderState.FILLED
+    assert again.order is first.order
+    assert len(await stack.orders.orders(SYMBOL)) == 1
+    [position] = await stack.connector.get_positions()
+    assert Decimal(position["netQuantity"]) == 1
@@ -0,0 +1,50 @@
+from __future__ import annotations
+
+from decimal import Decimal
+
+import pytest
+
+from xbot.connector.simulated import SimMarket, SimulatedConnector
+from xbot.execution.dust import DustConfig, DustSweeper
+from xbot.execution.risk_service import RiskMode
+from xbot.execution.router import ExecutionRouter
+
+from .conftest import VENUE_SYMBOL
+
+SPOT = "JTO_USDC"
+
+
+@pytest.mark.asyncio
+async def test_dust_is_sold_through_the_order_service_as_reduce_only(sim_stack, monkeypatch):
+    markets = [SimMarket(VENUE_SYMBOL, mark=Decimal("100")), SimMarket(SPOT, mark=Decimal("2"))]
+    stack = sim_stack(connector=SimulatedConnector(markets=markets, collateral=Decimal("10000")))
+    router = ExecutionRouter(
+        order_service=stack.orders, position_service=stack.positions, risk_service=stack.risk, market_data=stack.market_data
+    )
+    sent = []
+
+    async def margin():
+        return {"balances": {"USDC": {"available": "500"}, "JTO": {"available": "1.2345"}}}
+
+    async def market_order(**kwargs):
+        sent.append(kwargs)
+        return "sim-dust"
+
+    monkeypatch.setattr(stack.connector, "get_margin", margin)
+    def spot_market(asset: str, quote: str):
+        return SPOT if asset == "JTO" else None
+
+    monkeypatch.setattr(stack.connector, "spot_market", spot_market, raising=False)
+    monkeypatch.setattr(stack.connector, "submit_market_order", market_order)
+    sweeper = DustSweeper(connector=stack.connector, router=router, config=DustConfig(dry_run=False))
+
+    stack.risk.set_mode(RiskMode.REDUCE_ONLY, "drawdown")
+    assert await sweeper.sweep() == []  # paused
+    stack.risk.set_mode(RiskMode.NORMAL)
+    (candidate,) = await sweeper.sweep()
+
+    assert candidate.action == "converted" and candidate.order_id == "sim-dust"
+    (kwargs,) = sent
+    assert (kwargs["symbol"], kwargs["size_i"], kwargs["is_ask"], kwargs["reduce_only"]) == (SPOT, 1234, True, 1)
+    [order] = await stack.orders.orders(SPOT)
+    assert order.client_order_index == candidate.client_order_index and order.trace_id == "dust:JTO"
@@ -0,0 +1,131 @@
+from __future__ import annotations
+
+import asyncio
+import time
+from dataclasses import replace
+from decimal import Decimal
+from typing import Any, Dict, List, Optional
+
+import pytest
+
+from xbot.analytics.equity import EquityTracker
+from xbot.core.alerts import AlertManager
+from xbot.execution.circuit_breaker import DrawdownCircuitBreaker
+from xbot.execution.risk_service import RiskMode
+from xbot.execution.withdrawals import AllowedAddress, WithdrawalConfig, WithdrawalGuard
+from xbot.storage.base import CAPITAL_FLOWS, StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+
+ADDRESS = "0xabc"
+
+
+class FakeVenue:
+    """Margin equity that moves with withdrawals, plus the venue's transfer history."""
+
+    venue = "backpack"
+
+    def __init__(self, equity: Decimal) -> None:
+        self.equity = equity
+        self.deposits: List[Dict[str, Any]] = []
+        self.withdrawals: List[Dict[str, Any]] = []
+
+    async def get_margin(self) -> Dict[str, Any]:
+        return {"netEquity": str(self.equity)}
+
+    async def get_deposits(self, *, limit: int, offset: int, **_: Any) -> List[Dict[str, Any]]:
+        return self.deposits[offset : offset + limit]
+
+    async def get_withdrawals(self, *, limit: int, offset: int, **_: Any) -> List[Dict[str, Any]]:
+        return self.withdrawals[offset : offset + limit]
+
+    async def request_withdrawal(
+        self, *, symbol: str, blockchain: str, address: str, quantity: Decimal, client_id: Optional[int] = None
+    ) -> Dict[str, Any]:
+        self.equity -= quantity
+        row = {
+            "id": len(self.withdrawals) + 1,
+            "symbol": symbol,
+            "quantity": str(quantity),
+            "fee": "0",
+            "status": "confirmed",
+            "createdAt": int(time.time() * 1000),
+        }
+        self.withdrawals.append(row)
+        return dict(row)
+
+
+async def _tracker(tmp_path, venue: FakeVenue) -> EquityTracker:
+    storage = JsonlStorage(tmp_path / "storage")
+    await storage.start()
+    return EquityTracker(connectors=[venue], writer=StorageWriter(storage))
+
+
+def _guard(venue: FakeVenue, equity: EquityTracker) -> WithdrawalGuard:
+    config = WithdrawalConfig(enabled=True, allowlist=[AllowedAddress("USDC", "Solana", ADDRESS)])
+    return WithdrawalGuard(connector=venue, config=config, equity=equity)
+
+
+@pytest.mark.asyncio
+async def test_confirmed_withdrawal_is_not_drawdown(tmp_path):
+    venue = FakeVenue(Decimal("10000"))
+    equity = await _tracker(tmp_path, venue)
+    guard = _guard(venue, equity)
+    await equity.snapshot()
+
+    pending = await guard.request(symbol="USDC", blockchain="Solana", address=ADDRESS, quantity=Decimal("2500"))
+    await guard.confirm(pending.withdrawal_id)
+    await equity.snapshot()
+
+    assert await equity.drawdown() == 0
+    # The venue lists the same withdrawal on the next sync; it is not counted again
+    assert await equity.sync_flows() == 0
+    flows = await equity.storage.query(CAPITAL_FLOWS)
+    assert [Decimal(row["amount"]) for row in flows] == [Decimal("-2500")]
+
+
+@pytest.mark.asyncio
+async def test_transfers_made_outside_the_bot_are_synced_from_history(tmp_path):
+    venue = FakeVenue(Decimal("10000"))
+    equity = await _tracker(tmp_path, venue)
+    await equity.snapshot()
+    await asyncio.sleep(0.01)  # venue timestamps are whole milliseconds
+
+    await venue.request_withdrawal(symbol="USDC", blockchain="Solana", address=ADDRESS, quantity=Decimal("1000"))
+    venue.withdrawals[-1]["fee"] = "1"
+    venue.equity -= Decimal("1")
+    venue.deposits.append({"id": "d1", "symbol": "USDC", "quantity": "50", "status": "pending", "createdAt": int(time.time() * 1000)})
+
+    assert await equity.sync_flows() == 1  # the pending deposit has not moved funds yet
+    await equity.snapshot()
+    assert await equity.drawdown() == 0
+
+    venue.equity -= Decimal("900")  # a real loss is still drawdown
+    await equity.snapshot()
+    assert await equity.drawdown() == Decimal("900") / Decimal("10000")
+
+
+@pytest.mark.asyncio
+async def test_breaker_follows_the_live_limit_and_rearms_on_resume(tmp_path, sim_stack):
+    risk = sim_stack().risk
+    venue = FakeVenue(Decimal("10000"))
+    equity = await _tracker(tmp_path, venue)
+    breaker = DrawdownCircuitBreaker(equity=equity, risk=risk, alerts=AlertManager())
+    await equity.snapshot()
+    venue.equity -= Decimal("2000")
+    await equity.snapshot()
+
+    assert await breaker.check() == Decimal("0.2") and not breaker.tripped  # no limit set
+    risk.set_limits(replace(risk.limits, max_drawdown_pct=Decimal("0.1")))
+    await breaker.check()
+    assert breaker.tripped and risk.mode == RiskMode.REDUCE_ONLY
+
+    risk.set_mode(RiskMode.NORMAL, "operator resumed")
+    assert await breaker.check() == 0  # the loss it already acted on is behind it
+    assert not breaker.tripped and risk.mode == RiskMode.NORMAL
+
+    await asyncio.sleep(0.01)
+    await equity.snapshot()
+    venue.equity -= Decimal("900")  # a new 11% loss since the resume trips it again
+    await equity.snapshot()
+    await breaker.check()
+    assert breaker.tripped and risk.mode == RiskMode.REDUCE_ONLY
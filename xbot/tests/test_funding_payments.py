@@ -0,0 +1,80 @@
+from __future__ import annotations
+
+from decimal import Decimal
+from typing import Any, Dict, List
+
+import pytest
+
+from xbot.analytics.journal import load_journal
+from xbot.storage.base import FILLS, FUNDING_PAYMENTS, StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+from xbot.storage.recorder import FundingPaymentRecorder
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+HOUR_MS = 3_600_000
+
+
+class FakeFundingHistory:
+    """`get_funding_payments`, newest first, counting the pages read."""
+
+    def __init__(self) -> None:
+        self.payments: List[Dict[str, Any]] = []
+        self.pages = 0
+
+    def settle(self, quantity: str, end_ms: int) -> None:
+        row = {"symbol": VENUE_SYMBOL, "quantity": quantity, "fundingRate": "0.0001", "intervalEndTimestamp": end_ms}
+        self.payments.insert(0, row)
+
+    async def fetch(self, *, limit: int, offset: int) -> List[Dict[str, Any]]:
+        self.pages += 1
+        return self.payments[offset : offset + limit]
+
+
+async def _writer(tmp_path) -> StorageWriter:
+    storage = JsonlStorage(tmp_path / "storage")
+    await storage.start()
+    return StorageWriter(storage)
+
+
+def _recorder(history: FakeFundingHistory, writer: StorageWriter, **kwargs: Any) -> FundingPaymentRecorder:
+    canonical = {VENUE_SYMBOL: SYMBOL}.get
+    return FundingPaymentRecorder(fetch=history.fetch, writer=writer, venue="backpack", canonical=canonical, **kwargs)
+
+
+@pytest.mark.asyncio
+async def test_payments_are_stored_once_across_polls_and_restarts(tmp_path):
+    history = FakeFundingHistory()
+    writer = await _writer(tmp_path)
+    for hour in range(5):
+        history.settle("0.5", 1_700_000_000_000 + hour * HOUR_MS)
+
+    assert await _recorder(history, writer, page_size=2).record_once() == 5
+    await writer.flush()
+    history.settle("-0.25", 1_700_000_000_000 + 5 * HOUR_MS)
+    # A restarted recorder reads the stored ids back and stops at the first page it already has
+    history.pages = 0
+    assert await _recorder(history, writer, page_size=2).record_once() == 1
+    assert history.pages == 1
+    await writer.flush()
+
+    rows = await writer.storage.query(FUNDING_PAYMENTS)
+    assert len({row["payment_id"] for row in rows}) == len(rows) == 6
+    assert {row["symbol"] for row in rows} == {SYMBOL}
+    assert rows[0]["ts"] == 1_700_000_000.0
+
+
+@pytest.mark.asyncio
+async def test_journal_credits_recorded_funding_to_the_open_trade(tmp_path):
+    history = FakeFundingHistory()
+    writer = await _writer(tmp_path)
+    start = 1_700_000_000.0
+    writer.enqueue(FILLS, {"ts": start, "venue": "backpack", "symbol": SYMBOL, "side": "sell", "price": "100", "size": "1", "fee": "0"})
+    history.settle("0.75", int((start + 3600) * 1000))
+    writer.enqueue(FILLS, {"ts": start + 7200, "venue": "backpack", "symbol": SYMBOL, "side": "buy", "price": "100", "size": "1", "fee": "0"})
+    await _recorder(history, writer).record_once()
+    await writer.flush()
+
+    (trade,) = await load_journal(writer.storage)
+    assert trade.funding == Decimal("0.75")
+    assert trade.net_pnl == Decimal("0.75")
@@ -0,0 +1,73 @@
+from __future__ import annotations
+
+from decimal import Decimal
+from typing import Optional
+
+import pytest
+
+from xbot.analytics.funding_table import FundingTableRow, annualize
+from xbot.connector.simulated import SimMarket, SimulatedConnector
+from xbot.execution.market_data_service import MarketDataService
+from xbot.execution.order_service import OrderService
+from xbot.execution.position_service import PositionService
+from xbot.execution.risk_service import RiskService
+from xbot.execution.tracking_limit import TrackingLimitEngine
+from xbot.strategy.cash_carry import FLAT, CashAndCarryStrategy, CashCarryConfig
+from xbot.strategy.funding_rotation import HOLD, OPEN, RotationConfig, decide
+
+from .conftest import VENUE_SYMBOL
+
+ACCRUAL = 1_800_000_000.0  # an 8h boundary, in seconds
+SPOT = "SOL_USD"
+
+
+def _row(venue: str, rate: str, interval_hours: float = 8.0, next_funding: Optional[float] = ACCRUAL) -> FundingTableRow:
+    annualized = annualize(Decimal(rate), interval_hours)
+    next_ms = int(next_funding * 1000) if next_funding is not None else None
+    return FundingTableRow("SOL", venue, "SOL_PERP", Decimal(rate), interval_hours, annualized, next_ms)
+
+
+def test_new_pair_waits_out_an_accrual_its_long_leg_would_pay():
+    # The long leg pays 0.003% every hour; the short receives 0.1% every 8h, half an hour later
+    rows = [_row("a", "0.001", next_funding=ACCRUAL + 1800), _row("b", "0.00003", 1.0, ACCRUAL)]
+    config = RotationConfig(venues=["a", "b"])
+
+    waiting = decide("SOL", rows, None, config, now=ACCRUAL - 60)
+    assert (waiting.action, waiting.reason) == (HOLD, "funding_accrual")
+    assert decide("SOL", rows, None, config, now=ACCRUAL - 3600).action == OPEN
+    assert decide("SOL", rows, None, RotationConfig(entry_window_secs=0), now=ACCRUAL - 60).action == OPEN
+
+    # The short's accrual falls inside the window too and it receives more than the long pays
+    together = [_row("a", "0.001"), _row("b", "0.00003", 1.0)]
+    assert decide("SOL", together, None, config, now=ACCRUAL - 60).action == OPEN
+
+
+def _carry(rate: str) -> CashAndCarryStrategy:
+    perp = SimMarket(VENUE_SYMBOL, mark=Decimal("101"), funding_rate=Decimal(rate))
+    connector = SimulatedConnector(markets=[perp, SimMarket(SPOT, mark=Decimal("100"))], collateral=Decimal("10000"))
+    market_data = MarketDataService(connector=connector, symbol_map={"SOL": VENUE_SYMBOL})
+    risk = RiskService(market_data=market_data, position_service=PositionService())
+    order_service = OrderService(
+        connector=connector,
+        market_data=market_data,
+        risk_service=risk,
+        tracking_engine=TrackingLimitEngine(market_data=market_data),
+    )
+    return CashAndCarryStrategy(
+        connector=connector,
+        order_service=order_service,
+        market_data=market_data,
+        config=CashCarryConfig(symbols={VENUE_SYMBOL: SPOT}),
+    )
+
+
+@pytest.mark.asyncio
+async def test_cash_carry_entry_waits_out_an_accrual_the_short_would_pay():
+    paying = _carry("-0.0001")
+    [position] = await paying.refresh(now=ACCRUAL - 60)
+    assert (position.state, position.reason) == (FLAT, "funding_accrual")
+    [position] = await paying.refresh(now=ACCRUAL - 3600)
+    assert position.reason == ""
+
+    [position] = await _carry("0.0001").refresh(now=ACCRUAL - 60)  # the short receives: no reason to wait
+    assert position.reason == ""
@@ -0,0 +1,78 @@
+from __future__ import annotations
+
+import asyncio
+import time
+from types import SimpleNamespace
+from typing import Any, Dict, List, Optional
+
+import pytest
+
+from xbot.core.events import EventKind, EventStream, EventStreamConfig
+from xbot.storage.base import FILLS, StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+from xbot.strategy.guardrails import GuardrailConfig, SessionGuardrails
+
+
+class FakeStrategy:
+    name = "mm"
+
+    def __init__(self) -> None:
+        self.paused: Optional[str] = None
+
+    def pause(self, reason: str) -> None:
+        self.paused = reason
+
+    def resume(self) -> None:
+        self.paused = None
+
+    def cool_down(self, symbol: str, until: float, reason: str) -> None:
+        pass
+
+
+class NoOrders:
+    async def open_orders(self) -> List[Any]:
+        return []
+
+
+async def _writer(tmp_path) -> StorageWriter:
+    storage = JsonlStorage(tmp_path / "storage")
+    await storage.start()
+    return StorageWriter(storage)
+
+
+def _fill(index: int) -> Dict[str, Any]:
+    return {
+        "ts": time.time(),
+        "venue": "backpack",
+        "symbol": "SOL",
+        "client_order_index": index,
+        "side": "buy",
+        "price": "100",
+        "size": "1",
+        "fee": "0",
+        "trace_id": f"mm:{index}",
+    }
+
+
+@pytest.mark.asyncio
+async def test_counters_are_rebuilt_from_storage_after_the_fill_feed_lags(tmp_path):
+    writer = await _writer(tmp_path)
+    events = EventStream(config=EventStreamConfig(capacities={"strategy-guardrails": 2}))
+    strategy = FakeStrategy()
+    guardrails = SessionGuardrails(router=SimpleNamespace(orders=NoOrders()), events=events, writer=writer)  # type: ignore[arg-type]
+    guardrails.register(strategy, GuardrailConfig(max_trades_per_day=5))  # type: ignore[arg-type]
+    await guardrails.start()
+    await asyncio.sleep(0)
+
+    # A burst the subscriber cannot keep up with; OrderService stores every fill as it emits it
+    for index in range(5):
+        fill = _fill(index)
+        writer.enqueue(FILLS, fill)
+        events.emit(EventKind.FILL, "backpack", fill, symbol="SOL")
+    for _ in range(5):
+        await asyncio.sleep(0)
+    await guardrails.stop()
+
+    # The two fills still queued after the drop were part of the rebuild and are not counted twice
+    assert guardrails.session("mm")["trades"] == 5
+    assert strategy.paused is not None and "daily limit of 5" in strategy.paused
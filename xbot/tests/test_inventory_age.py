@@ -0,0 +1,24 @@
+from __future__ import annotations
+
+import pytest
+
+from xbot.execution.inventory_age import inventory_slices
+from xbot.execution.models import OrderState
+from xbot.execution.order_service import OrderUpdatePayload
+
+from .conftest import SYMBOL
+
+
+@pytest.mark.asyncio
+async def test_partial_fills_build_the_slice_from_their_increments(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    order = await stack.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="1", price="90", trace_id="mm:1")
+    coi = order.client_order_index
+    for info in ({"l": "0.3"}, {"z": "0.5"}, {"executedQuantity": "0.5"}):  # the last one is a replay
+        await stack.orders.ingest_update(OrderUpdatePayload(coi, OrderState.PARTIALLY_FILLED, info=info))
+
+    inventory = inventory_slices(await stack.orders.orders())[("mm", SYMBOL)]
+    first_fill = next(e.ts for e in order.history if e.state == OrderState.PARTIALLY_FILLED)
+    assert inventory.net_i == 500
+    assert inventory.opened == first_fill
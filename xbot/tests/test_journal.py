@@ -0,0 +1,48 @@
+from __future__ import annotations
+
+from decimal import Decimal
+
+import pytest
+
+from xbot.analytics.journal import build_journal
+from xbot.core.symbology import SYMBOLOGY
+from xbot.storage.base import FILLS, StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+
+def _fill(ts: float, side: str, size: str, price: str) -> dict:
+    return {
+        "ts": ts,
+        "venue": "inverse-test",
+        "symbol": "BTC",
+        "venue_symbol": "BTC_USD_PERP",
+        "side": side,
+        "size": size,
+        "price": price,
+    }
+
+
+def test_inverse_contract_pnl_is_taken_in_coin():
+    SYMBOLOGY.override("inverse-test", "BTC_USD_PERP", inverse=True)
+    # 1000 one-dollar contracts bought at 100 and sold at 125
+    (trade,) = build_journal([_fill(1.0, "buy", "1000", "100"), _fill(2.0, "sell", "1000", "125")])
+
+    assert trade.symbol == "BTC" and trade.currency == "BTC"
+    assert trade.gross_pnl == Decimal(1000) * (Decimal(1) / 100 - Decimal(1) / 125)  # 2 BTC, not 25000 USD
+
+
+@pytest.mark.asyncio
+async def test_fill_rows_carry_the_venue_symbol(sim_stack, tmp_path):
+    storage = JsonlStorage(tmp_path / "storage")
+    await storage.start()
+    writer = StorageWriter(storage)
+    stack = sim_stack(storage=writer)
+    await stack.quote()
+    await stack.orders.submit_market(symbol=SYMBOL, is_ask=False, size="1")
+    await stack.connector.flush_updates()
+    await writer.flush()
+
+    [fill] = await storage.query(FILLS)
+    assert (fill["symbol"], fill["venue_symbol"]) == (SYMBOL, VENUE_SYMBOL)
@@ -0,0 +1,34 @@
+from __future__ import annotations
+
+import asyncio
+
+import pytest
+
+from xbot.execution.maker_first import MakerFirstExecutor, MakerFirstPolicy
+from xbot.execution.models import OrderState
+from xbot.execution.order_expiry import ordered_size_i
+from xbot.execution.order_service import OrderUpdatePayload
+
+from .conftest import SYMBOL
+
+
+@pytest.mark.asyncio
+async def test_taker_leg_sends_only_what_the_maker_leg_left(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    executor = MakerFirstExecutor(market_data=stack.market_data)
+    policy = MakerFirstPolicy(timeout_secs=0.2, poll_secs=0.05, cancel_wait_secs=0.1)
+
+    async def partial_fill() -> None:
+        while not (orders := await stack.orders.orders(SYMBOL)):
+            await asyncio.sleep(0.01)
+        payload = OrderUpdatePayload(orders[0].client_order_index, OrderState.PARTIALLY_FILLED, info={"executedQuantity": "0.4"})
+        await stack.orders.ingest_update(payload)
+
+    filler = asyncio.create_task(partial_fill())
+    result = await executor.execute(order_service=stack.orders, symbol=SYMBOL, is_ask=False, size_i=1000, policy=policy)
+    await filler
+
+    assert result.crossed == "timeout"
+    assert (result.maker_filled_i, result.taker_size_i) == (400, 600)
+    assert result.taker_order is not None and ordered_size_i(result.taker_order) == 600
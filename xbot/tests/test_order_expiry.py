@@ -0,0 +1,30 @@
+from __future__ import annotations
+
+from decimal import Decimal
+
+import pytest
+
+from xbot.execution.models import OrderState
+from xbot.execution.order_expiry import OrderExpiryService, filled_size_i
+from xbot.execution.order_service import OrderUpdatePayload
+
+from .conftest import SYMBOL
+
+
+@pytest.mark.asyncio
+async def test_expired_partial_reports_the_base_quantity_filled(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    order = await stack.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="1", price="90")
+    coi = order.client_order_index
+    await stack.orders.ingest_update(OrderUpdatePayload(coi, OrderState.PARTIALLY_FILLED, info={"executedQuantity": "0.4"}))
+    await stack.orders.ingest_update(OrderUpdatePayload(coi, OrderState.PARTIALLY_FILLED, info={"l": "0.25"}))
+    assert order.filled == Decimal("0.65")
+    assert filled_size_i(order) == 650
+
+    expiry = OrderExpiryService(order_service=stack.orders)
+    expiry.track(order, 1)
+    expiry._tracked[coi].deadline = 0  # already past
+    (result,) = await expiry.sweep()
+
+    assert (result.size_i, result.filled_i, result.remaining_i, result.partial) == (1000, 650, 350, True)
@@ -0,0 +1,134 @@
+from __future__ import annotations
+
+from decimal import Decimal
+
+import pytest
+
+from xbot.core.clock import WallClock
+from xbot.execution.candles import CandleService
+from xbot.execution.models import OrderState
+from xbot.execution.oco import OcoLeg
+from xbot.execution.recovery import RecoveryService
+from xbot.execution.router import ExecutionRouter
+from xbot.execution.stops import StopOrder
+from xbot.strategy.base import StrategyConfig
+from xbot.strategy.breakout import BreakoutStrategy
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+
+def _router(stack) -> ExecutionRouter:
+    return ExecutionRouter(
+        order_service=stack.orders, position_service=stack.positions, risk_service=stack.risk, market_data=stack.market_data
+    )
+
+
+async def _recover(stack, router: ExecutionRouter) -> None:
+    recovery = RecoveryService(
+        connector=stack.connector,
+        order_service=stack.orders,
+        position_service=stack.positions,
+        market_data=stack.market_data,
+        retry_secs=60.0,
+    )
+    recovery.register_reattach("oco:", router.oco.adopt, closed=True)
+    recovery.register_reattach("iceberg:", router.iceberg.adopt, closed=True)
+    await recovery.run()
+
+
+BRACKET = [OcoLeg(is_ask=False, price_i=9000, size_i=1000), OcoLeg(is_ask=True, price_i=11000, size_i=1000)]
+
+
+@pytest.mark.asyncio
+async def test_oco_fill_cancels_the_other_leg(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    router = _router(stack)
+    bid, ask = await router.oco.place(SYMBOL, BRACKET)
+
+    assert await router.oco.sweep() == 0  # nothing filled yet
+    stack.connector.set_mark(VENUE_SYMBOL, Decimal("85"))
+    await stack.connector.flush_updates()
+    assert await router.oco.sweep() == 1
+    await stack.connector.flush_updates()
+
+    assert bid.state == OrderState.FILLED and ask.state == OrderState.CANCELLED
+    assert await router.oco.sweep() == 0 and router.oco.groups == []
+
+
+@pytest.mark.asyncio
+async def test_oco_leg_that_filled_while_down_cancels_its_sibling(sim_stack):
+    before = sim_stack()
+    await before.quote()
+    await _router(before).oco.place(SYMBOL, BRACKET, group_id="g1")
+    stack = sim_stack(connector=before.connector)
+    stack.connector.set_mark(VENUE_SYMBOL, Decimal("85"))  # the bid fills before recovery runs
+    router = _router(stack)
+
+    await _recover(stack, router)
+    assert router.oco.groups == ["oco:g1"]
+    assert await router.oco.sweep() == 1
+    await stack.connector.flush_updates()
+
+    assert await stack.connector.get_open_orders(VENUE_SYMBOL) == []
+    states = sorted(order.state.value for order in await stack.orders.orders(SYMBOL))
+    assert states == sorted([OrderState.FILLED.value, OrderState.CANCELLED.value])
+
+
+@pytest.mark.asyncio
+async def test_iceberg_sends_the_next_clip_after_each_fill(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    router = _router(stack)
+    iceberg = await router.iceberg.place(symbol=SYMBOL, is_ask=False, size_i=2500, price_i=9000, clip_i=1000)
+
+    sizes = []
+    for _ in range(3):
+        sizes.append(iceberg.remaining_i)
+        stack.connector.set_mark(VENUE_SYMBOL, Decimal("85"))
+        await stack.connector.flush_updates()
+        stack.connector.set_mark(VENUE_SYMBOL, Decimal("100"))
+        await router.iceberg.sweep()
+
+    assert sizes == [2500, 1500, 500]
+    assert iceberg.finished and iceberg.filled_i == 2500
+    [position] = await stack.connector.get_positions()
+    assert Decimal(position["netQuantity"]) == Decimal("2.5")
+
+
+@pytest.mark.asyncio
+async def test_iceberg_resumes_from_a_clip_that_filled_while_down(sim_stack):
+    before = sim_stack()
+    await before.quote()
+    await _router(before).iceberg.place(symbol=SYMBOL, is_ask=False, size_i=2500, price_i=9000, clip_i=1000)
+    stack = sim_stack(connector=before.connector)
+    await stack.quote()
+    stack.connector.set_mark(VENUE_SYMBOL, Decimal("85"))
+    stack.connector.set_mark(VENUE_SYMBOL, Decimal("100"))
+    router = _router(stack)
+
+    await _recover(stack, router)
+    [iceberg] = router.iceberg.icebergs
+    assert iceberg.filled_i == 1000
+    assert await router.iceberg.sweep() == 1
+
+    [resting] = await stack.connector.get_open_orders(VENUE_SYMBOL)
+    assert iceberg.clip.client_order_index == resting["clientId"]
+    assert iceberg.remaining_i == 1500 and iceberg.done_i == 1000
+
+
+@pytest.mark.asyncio
+async def test_breakout_trailed_stop_survives_a_restart(sim_stack):
+    stack = sim_stack()
+
+    def breakout() -> BreakoutStrategy:
+        config = StrategyConfig(symbol=SYMBOL, mode="breakout")
+        return BreakoutStrategy(router=_router(stack), clock=WallClock(), config=config, candles=CandleService())
+
+    stop = StopOrder(symbol=SYMBOL, is_ask=True, trigger=Decimal("90"), trail=Decimal("5"), extreme=Decimal("95"))
+    stop.update(Decimal("104"))
+    breakout()._keep_stop(stop)
+
+    restored = breakout()._load_stop()
+    assert restored is not None and restored.to_dict() == stop.to_dict()
+    assert restored.trigger == Decimal("99")
@@ -0,0 +1,25 @@
+from __future__ import annotations
+
+import pytest
+
+from xbot.execution.models import OrderState
+from xbot.execution.order_progress import OrderProgressChannel, ProgressStage
+from xbot.execution.order_service import OrderUpdatePayload
+
+from .conftest import SYMBOL
+
+
+@pytest.mark.asyncio
+async def test_partial_fill_progress_carries_the_fill_it_reports(sim_stack):
+    stack = sim_stack()
+    progress = OrderProgressChannel()
+    stack.orders.attach_progress(progress)
+    await stack.quote()
+    order = await stack.orders.submit_limit(symbol=SYMBOL, is_ask=True, size="1", price="110", trace_id="cmd-1")
+
+    payload = OrderUpdatePayload(order.client_order_index, OrderState.PARTIALLY_FILLED, info={"executedQuantity": "0.4"})
+    await stack.orders.ingest_update(payload)
+
+    update = progress.updates("cmd-1")[-1]
+    assert update.stage == ProgressStage.PARTIALLY_FILLED
+    assert (update.size_i, update.filled_i, update.remaining_i) == (1000, 400, 600)
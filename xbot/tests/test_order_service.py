@@ -0,0 +1,112 @@
+from __future__ import annotations
+
+import asyncio
+from decimal import Decimal
+
+import pytest
+
+from xbot.core.rate_shaper import MessagePriority, RateShaper, RateShaperConfig
+from xbot.execution.models import OrderState
+from xbot.execution.order_service import OrderUpdatePayload
+from xbot.storage.base import FILLS, StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+
+from .conftest import SYMBOL
+
+
+async def _writer(tmp_path) -> StorageWriter:
+    storage = JsonlStorage(tmp_path / "storage")
+    await storage.start()
+    return StorageWriter(storage)
+
+
+async def _fills(writer: StorageWriter) -> list:
+    await writer.flush()
+    return await writer.storage.query(FILLS)
+
+
+@pytest.mark.asyncio
+async def test_ws_fill_records_per_fill_size(sim_stack, tmp_path):
+    writer = await _writer(tmp_path)
+    stack = sim_stack(storage=writer)
+    await stack.quote()
+    order = await stack.orders.submit_market(symbol=SYMBOL, is_ask=False, size=Decimal("1.5"))
+    await stack.connector.flush_updates()
+
+    assert order.state == OrderState.FILLED
+    assert order.filled == Decimal("1.5")
+    assert [Decimal(row["size"]) for row in await _fills(writer)] == [Decimal("1.5")]
+
+
+@pytest.mark.asyncio
+async def test_cumulative_updates_count_only_the_increase(sim_stack, tmp_path):
+    writer = await _writer(tmp_path)
+    stack = sim_stack(storage=writer)
+    await stack.quote()
+    order = await stack.orders.submit_market(symbol=SYMBOL, is_ask=True, size=Decimal("3"))
+    coi = order.client_order_index
+
+    # REST polls report the executed quantity so far, not the last fill
+    for status, executed in ((OrderState.PARTIALLY_FILLED, "1"), (OrderState.PARTIALLY_FILLED, "1"), (OrderState.FILLED, "3")):
+        await stack.orders.ingest_update(OrderUpdatePayload(coi, status, info={"executedQuantity": executed}))
+    # A WS fill replayed after the polls already counted it adds nothing
+    await stack.connector.flush_updates()
+
+    assert order.filled == Decimal("3")
+    assert [Decimal(row["size"]) for row in await _fills(writer)] == [Decimal("1"), Decimal("2")]
+
+
+@pytest.mark.asyncio
+async def test_last_fill_used_without_cumulative(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    order = await stack.orders.submit_market(symbol=SYMBOL, is_ask=False, size=Decimal("2"))
+    coi = order.client_order_index
+
+    await stack.orders.ingest_update(OrderUpdatePayload(coi, OrderState.PARTIALLY_FILLED, info={"l": "0.5"}))
+    await stack.orders.ingest_update(OrderUpdatePayload(coi, OrderState.PARTIALLY_FILLED, info={"l": "0.5"}))
+
+    assert order.filled == Decimal("1")
+
+
+@pytest.mark.asyncio
+async def test_fill_reported_with_the_cancel_is_recorded(sim_stack, tmp_path):
+    writer = await _writer(tmp_path)
+    stack = sim_stack(storage=writer)
+    await stack.quote()
+    order = await stack.orders.submit_market(symbol=SYMBOL, is_ask=False, size=Decimal("2"))
+
+    payload = OrderUpdatePayload(order.client_order_index, OrderState.CANCELLED, info={"executedQuantity": "0.5"})
+    await stack.orders.ingest_update(payload)
+
+    assert order.filled == Decimal("0.5")
+    assert [Decimal(row["size"]) for row in await _fills(writer)] == [Decimal("0.5")]
+
+
+@pytest.mark.asyncio
+async def test_cancel_is_not_held_behind_a_shaped_new_order(sim_stack, monkeypatch):
+    stack = sim_stack()
+    await stack.quote()
+    resting = await stack.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="1", price="90")
+    venue = stack.connector.venue
+    shaper = RateShaper(RateShaperConfig(venues={venue: 20}, burst_secs=0.05))  # one message per 50ms
+    stack.orders.attach_rate_shaper(shaper)
+    sent = []
+    place, cancel = stack.connector.submit_limit_order, stack.connector.cancel_by_order_id
+
+    async def placed(**kwargs):
+        sent.append("place")
+        return await place(**kwargs)
+
+    async def cancelled(*args):
+        sent.append("cancel")
+        return await cancel(*args)
+
+    monkeypatch.setattr(stack.connector, "submit_limit_order", placed)
+    monkeypatch.setattr(stack.connector, "cancel_by_order_id", cancelled)
+    await shaper.acquire(venue, MessagePriority.NEW)  # the bucket is dry
+    new = asyncio.create_task(stack.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="1", price="91"))
+    await asyncio.sleep(0)  # queued first
+    await asyncio.gather(new, stack.orders.cancel(SYMBOL, resting.client_order_index))
+
+    assert sent == ["cancel", "place"]
@@ -0,0 +1,102 @@
+from __future__ import annotations
+
+import time
+from decimal import Decimal
+
+import pytest
+
+from xbot.execution.models import OrderState
+from xbot.execution.order_service import OrderUpdatePayload
+from xbot.execution.portfolio_executor import TargetPositionExecutor
+from xbot.execution.position_service import PositionSnapshot
+
+from .conftest import SYMBOL
+
+
+def _executor(stack, **kwargs) -> TargetPositionExecutor:
+    return TargetPositionExecutor(
+        order_service=stack.orders, position_service=stack.positions, market_data=stack.market_data, **kwargs
+    )
+
+
+@pytest.mark.asyncio
+async def test_filled_delta_is_not_sent_again(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    executor = _executor(stack)
+    await executor.set_target(SYMBOL, "2")
+
+    first = await executor.step()
+    assert len(first) == 1
+    await stack.connector.flush_updates()
+    assert first[0].state == OrderState.FILLED
+
+    # PositionService has not seen the fill yet; the executor's own fill says we are at target
+    assert await executor.step() == []
+    assert await executor.step() == []
+    assert len(await stack.orders.orders(SYMBOL)) == 1
+    assert await executor.current_position(SYMBOL) == Decimal("2")
+
+
+@pytest.mark.asyncio
+async def test_timed_out_order_is_looked_up_not_resent(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    executor = _executor(stack, pending_timeout_secs=0.0)
+    await executor.set_target(SYMBOL, "-1")
+
+    [order] = await executor.step()
+    # The fill update never arrives; the venue lookup finds the order filled
+    assert order.state == OrderState.OPEN
+    assert await executor.step() == []
+    assert order.state == OrderState.FILLED
+    assert await executor.current_position(SYMBOL) == Decimal("-1")
+    # A late WS fill for the same order does not move the expected position
+    await stack.connector.flush_updates()
+    assert await executor.step() == []
+    assert len(await stack.orders.orders(SYMBOL)) == 1
+
+
+@pytest.mark.asyncio
+async def test_live_order_blocks_replanning(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    executor = _executor(stack)
+    await executor.set_target(SYMBOL, "1")
+
+    assert len(await executor.step()) == 1
+    assert await executor.step() == []
+    assert len(await stack.orders.orders(SYMBOL)) == 1
+
+
+@pytest.mark.asyncio
+async def test_partial_fill_plans_only_the_remainder(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    executor = _executor(stack)
+    await executor.set_target(SYMBOL, "2")
+
+    [order] = await executor.step()
+    # Venue fills half and cancels the rest
+    payload = OrderUpdatePayload(order.client_order_index, OrderState.CANCELLED, info={"executedQuantity": "0.5"})
+    await stack.orders.ingest_update(payload)
+    [second] = await executor.step()
+    assert await stack.market_data.to_size_i(SYMBOL, Decimal("1.5")) == second.history[0].info["size_i"]
+
+
+@pytest.mark.asyncio
+async def test_newer_position_feed_wins_over_expected(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    executor = _executor(stack)
+    await executor.set_target(SYMBOL, "1")
+
+    await executor.step()
+    await stack.connector.flush_updates()
+    assert await executor.step() == []
+    # Reconciliation later finds the position closed on the venue, e.g. liquidated
+    flat = Decimal(0)
+    await stack.positions.ingest(
+        PositionSnapshot(symbol=SYMBOL, base_qty=flat, quote_value=flat, notional=flat, ts=time.time() + 1)
+    )
+    assert len(await executor.step()) == 1
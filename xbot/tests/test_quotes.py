@@ -0,0 +1,57 @@
+from __future__ import annotations
+
+import pytest
+
+from xbot.execution.models import OrderState
+from xbot.execution.order_service import OrderUpdatePayload
+from xbot.execution.quotes import QuoteLevel, QuoteManager
+from xbot.execution.router import ExecutionRouter
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+
+def _quotes(stack) -> QuoteManager:
+    router = ExecutionRouter(
+        order_service=stack.orders, position_service=stack.positions, risk_service=stack.risk, market_data=stack.market_data
+    )
+    return QuoteManager(router=router, symbol=SYMBOL)
+
+
+async def _resting_prices(stack) -> list:
+    return sorted(order["price"] for order in await stack.connector.get_open_orders(VENUE_SYMBOL))
+
+
+@pytest.mark.asyncio
+async def test_failed_cancel_holds_back_the_replacement_quote(sim_stack, monkeypatch):
+    stack = sim_stack()
+    await stack.quote()
+    quotes = _quotes(stack)
+    await quotes.update([QuoteLevel(is_ask=False, price_i=9000, size_i=1000)])
+    before = await _resting_prices(stack)
+
+    async def refuse(*args, **kwargs):
+        raise RuntimeError("venue busy")
+
+    connector_cancels = (stack.connector.cancel_by_order_id, stack.connector.cancel_by_client_id)
+    monkeypatch.setattr(stack.connector, "cancel_by_order_id", refuse)
+    monkeypatch.setattr(stack.connector, "cancel_by_client_id", refuse)
+    await quotes.update([QuoteLevel(is_ask=False, price_i=9100, size_i=1000)])
+    assert await _resting_prices(stack) == before  # not both the old and the new quote
+
+    monkeypatch.setattr(stack.connector, "cancel_by_order_id", connector_cancels[0])
+    monkeypatch.setattr(stack.connector, "cancel_by_client_id", connector_cancels[1])
+    await quotes.update([QuoteLevel(is_ask=False, price_i=9100, size_i=1000)])
+    assert len(await _resting_prices(stack)) == 1 and await _resting_prices(stack) != before
+
+
+@pytest.mark.asyncio
+async def test_partly_filled_quote_reports_what_is_left(sim_stack):
+    stack = sim_stack()
+    await stack.quote()
+    quotes = _quotes(stack)
+    await quotes.update([QuoteLevel(is_ask=True, price_i=11000, size_i=1000)])
+    (quote,) = quotes.resting
+    payload = OrderUpdatePayload(quote.order.client_order_index, OrderState.PARTIALLY_FILLED, info={"executedQuantity": "0.4"})
+    await stack.orders.ingest_update(payload)
+    assert quote.remaining_i == 600
+    assert quote.level() == QuoteLevel(is_ask=True, price_i=11000, size_i=600)
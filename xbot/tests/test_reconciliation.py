@@ -0,0 +1,80 @@
+from __future__ import annotations
+
+from decimal import Decimal
+from typing import List
+
+import pytest
+
+from xbot.core.alerts import Alert, AlertLevel, AlertManager
+from xbot.core.events import EventStream
+from xbot.execution.reconciliation import PositionReconciler, ReconcileConfig
+from xbot.execution.risk_service import RiskMode
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+
+class ListSink:
+    def __init__(self) -> None:
+        self.alerts: List[Alert] = []
+
+    async def send(self, alert: Alert) -> None:
+        self.alerts.append(alert)
+
+
+def _reconciler(stack, sink: ListSink, **config) -> PositionReconciler:
+    return PositionReconciler(
+        connector=stack.connector,
+        market_data=stack.market_data,
+        positions=stack.positions,
+        events=EventStream(),
+        risk=stack.risk,
+        alerts=AlertManager([sink]),
+        config=ReconcileConfig(**config),
+    )
+
+
+async def _held(stack) -> Decimal:
+    snapshot = await stack.positions.get_position(SYMBOL)
+    return snapshot.base_qty if snapshot is not None else Decimal(0)
+
+
+@pytest.mark.asyncio
+async def test_closed_positions_are_zeroed_when_the_venue_stops_listing_them(sim_stack):
+    stack = sim_stack()
+    sink = ListSink()
+    reconciler = _reconciler(stack, sink)
+    await stack.quote()
+    await stack.orders.submit_market(symbol=SYMBOL, is_ask=False, size=Decimal("2"))
+    await reconciler.check()  # seeds from the venue
+    assert await _held(stack) == Decimal("2")
+
+    await stack.orders.submit_market(symbol=SYMBOL, is_ask=True, size=Decimal("2"))
+    reconciler.on_fill(SYMBOL, "sell", Decimal("2"))
+    assert await stack.connector.get_positions() == []
+
+    assert await reconciler.check() == []
+    assert await _held(stack) == 0
+    assert sink.alerts == []
+
+
+@pytest.mark.asyncio
+async def test_position_closed_behind_the_bots_back_alerts_and_halts(sim_stack):
+    stack = sim_stack()
+    sink = ListSink()
+    reconciler = _reconciler(stack, sink, confirm_cycles=2, halt=True)
+    await stack.quote()
+    await stack.orders.submit_market(symbol=SYMBOL, is_ask=False, size=Decimal("1"))
+    await reconciler.check()
+
+    # Closed by hand on the venue, so no fill reaches the reconciler
+    await stack.connector.submit_market_order(symbol=VENUE_SYMBOL, client_order_index=999, size_i=1000, is_ask=True)
+    assert await stack.connector.get_positions() == []
+    (first,) = await reconciler.check()
+    assert (first.virtual, first.exchange) == (Decimal("1"), Decimal(0))
+    assert await _held(stack) == 0
+    assert sink.alerts == []  # one diverged cycle is still within confirm_cycles
+
+    await reconciler.check()
+    (alert,) = sink.alerts
+    assert alert.level == AlertLevel.CRITICAL
+    assert stack.risk.mode == RiskMode.HALTED
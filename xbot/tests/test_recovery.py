@@ -0,0 +1,149 @@
+from __future__ import annotations
+
+from decimal import Decimal
+from typing import List
+
+import pytest
+
+from xbot.connector.history import HistoricalOrder, OrderHistory
+from xbot.execution.models import Order, OrderState
+from xbot.execution.portfolio_executor import TargetPositionExecutor
+from xbot.execution.recovery import RecoveryService
+from xbot.storage.base import FILLS, StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+
+def _recovery(stack) -> RecoveryService:
+    return RecoveryService(
+        connector=stack.connector,
+        order_service=stack.orders,
+        position_service=stack.positions,
+        market_data=stack.market_data,
+        retry_secs=60.0,
+    )
+
+
+async def _resting_then_restart(sim_stack, *, trace_id: str = "target:SOL"):
+    """Leave a resting bid on the venue, then bring up a fresh stack on the same venue and order logs."""
+    before = sim_stack()
+    await before.quote()
+    order = await before.orders.submit_limit(symbol=SYMBOL, is_ask=False, size="1", price="90", trace_id=trace_id)
+    assert order.state == OrderState.OPEN
+    return order.client_order_index, sim_stack(connector=before.connector)
+
+
+@pytest.mark.asyncio
+async def test_live_order_is_restored(sim_stack):
+    coi, stack = await _resting_then_restart(sim_stack)
+    recovery = _recovery(stack)
+
+    report = await recovery.run()
+
+    assert report.restored == [coi]
+    assert report.unresolved == []
+    [order] = await stack.orders.open_orders(SYMBOL)
+    assert order.client_order_index == coi
+
+
+@pytest.mark.asyncio
+async def test_transient_lookup_failure_keeps_order_open(sim_stack):
+    coi, stack = await _resting_then_restart(sim_stack)
+    recovery = _recovery(stack)
+    stack.connector.down = True
+
+    report = await recovery.run()
+    await recovery.stop()
+
+    assert report.unresolved == [coi]
+    assert report.closed_while_down == []
+    [order] = await stack.orders.open_orders(SYMBOL)
+    assert order.state == OrderState.OPEN
+
+    # Still down: nothing changes; back up: the venue has it open
+    assert (await recovery.retry_unresolved()).unresolved == [coi]
+    stack.connector.down = False
+    retried = await recovery.retry_unresolved()
+    assert retried.restored == [coi]
+    assert recovery.unresolved == []
+    assert order.state == OrderState.OPEN
+
+
+@pytest.mark.asyncio
+async def test_not_found_order_is_closed(sim_stack):
+    coi, _ = await _resting_then_restart(sim_stack)
+    # Same order logs, but a venue that has never seen the order
+    stack = sim_stack()
+    recovery = _recovery(stack)
+
+    report = await recovery.run()
+
+    assert report.closed_while_down == [coi]
+    assert report.unresolved == []
+    [order] = await stack.orders.orders(SYMBOL)
+    assert order.state == OrderState.CANCELLED
+    assert order.snapshot().info["recovery"] == "missing_on_exchange"
+
+
+@pytest.mark.asyncio
+async def test_restored_order_goes_back_to_its_executor(sim_stack):
+    coi, stack = await _resting_then_restart(sim_stack, trace_id="carry:SOL")
+    executor = TargetPositionExecutor(
+        order_service=stack.orders,
+        position_service=stack.positions,
+        market_data=stack.market_data,
+        trace_prefix="carry",
+    )
+    seen: List[Order] = []
+
+    async def other(order: Order) -> None:
+        seen.append(order)
+
+    recovery = _recovery(stack)
+    recovery.register_reattach("rotation:", other)
+    recovery.register_reattach(f"{executor.trace_prefix}:", executor.adopt)
+
+    report = await recovery.run()
+
+    assert report.reattached == [coi]
+    assert seen == []
+    await stack.quote()
+    await executor.set_target(SYMBOL, Decimal("1"))
+    # The restored bid is still working toward the target, so nothing new is sent
+    assert await executor.step() == []
+    assert len(await stack.orders.orders(SYMBOL)) == 1
+
+
+@pytest.mark.asyncio
+async def test_order_that_filled_while_down_records_its_fill(sim_stack, tmp_path, monkeypatch):
+    coi, _ = await _resting_then_restart(sim_stack)
+    storage = JsonlStorage(tmp_path / "storage")
+    await storage.start()
+    writer = StorageWriter(storage)
+    stack = sim_stack(storage=writer)  # a venue that no longer lists the order as open
+    filled = HistoricalOrder(
+        venue=stack.connector.venue,
+        symbol=VENUE_SYMBOL,
+        order_id="x1",
+        client_order_index=coi,
+        is_ask=False,
+        order_type="Limit",
+        price=Decimal("90"),
+        quantity=Decimal("1"),
+        filled_quantity=Decimal("1"),
+        status="Filled",
+    )
+
+    async def history(symbol=None, cursor=None, limit=None) -> OrderHistory:
+        return OrderHistory([filled])
+
+    monkeypatch.setattr(stack.connector, "get_order_history", history)
+    report = await _recovery(stack).run()
+
+    assert report.closed_while_down == [coi]
+    [order] = await stack.orders.orders(SYMBOL)
+    assert order.state == OrderState.FILLED and order.filled == Decimal("1")
+    await writer.flush()
+    [fill] = await writer.storage.query(FILLS)
+    assert (Decimal(fill["size"]), Decimal(fill["price"])) == (Decimal("1"), Decimal("90"))
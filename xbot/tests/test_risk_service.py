@@ -0,0 +1,95 @@
+from __future__ import annotations
+
+from decimal import Decimal
+
+import pytest
+
+from xbot.execution.position_service import PositionSnapshot
+from xbot.execution.risk_service import RiskViolationError, SymbolLists
+from xbot.execution.trading_windows import TradingWindow, blocking_window, parse_time_of_day
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+SATURDAY = 1704499200.0  # 2024-01-06 00:00 UTC
+HOUR = 3600.0
+
+
+async def _validate(stack, size: str, *, is_ask: bool = False, reduce_only: int = 0) -> None:
+    _, size_decimals = await stack.market_data.get_price_size_decimals(SYMBOL)
+    size_i = int(Decimal(size) * Decimal(10) ** size_decimals)
+    await stack.risk.validate_order(symbol=SYMBOL, size_i=size_i, is_ask=is_ask, reduce_only=reduce_only)
+
+
+async def _hold(stack, base_qty: str) -> None:
+    qty = Decimal(base_qty)
+    await stack.positions.ingest(PositionSnapshot(SYMBOL, qty, qty * 100, abs(qty) * 100))
+
+
+@pytest.mark.asyncio
+async def test_symbol_lists_match_canonical_and_venue_names(sim_stack):
+    stack = sim_stack(symbols=SymbolLists(allow=["BTC", "ETH*"]))
+    await stack.quote()
+    with pytest.raises(RiskViolationError, match="not allowed"):
+        await _validate(stack, "1")
+
+    stack.risk.set_symbol_lists(SymbolLists(allow=["sol_*"]))  # globs are case-insensitive, venue names count
+    await _validate(stack, "1")
+
+    stack.risk.set_symbol_lists(SymbolLists(allow=["SOL"], deny=[VENUE_SYMBOL]))
+    with pytest.raises(RiskViolationError, match="denied"):
+        await _validate(stack, "1")
+
+
+@pytest.mark.asyncio
+async def test_denied_symbol_positions_can_still_be_closed(sim_stack):
+    stack = sim_stack(symbols=SymbolLists(deny=[SYMBOL]))
+    await stack.quote()
+    await _hold(stack, "2")
+
+    await _validate(stack, "1", is_ask=True)  # reduces the long
+    await _validate(stack, "5", reduce_only=1)
+    with pytest.raises(RiskViolationError, match="denied"):
+        await _validate(stack, "1")  # adds to it
+    with pytest.raises(RiskViolationError, match="denied"):
+        await _validate(stack, "3", is_ask=True)  # flips it short
+
+
+def test_daily_window_wraps_past_midnight_on_its_start_day_only():
+    window = TradingWindow("weekend", start=parse_time_of_day("22:00"), end=parse_time_of_day("02:00"), weekdays=[5])
+    assert window.active_until(SATURDAY + 23 * HOUR) == SATURDAY + 26 * HOUR
+    assert window.active_until(SATURDAY + 25 * HOUR) == SATURDAY + 26 * HOUR  # Sunday 01:00, opened Saturday
+    assert window.active_until(SATURDAY + 1 * HOUR) is None  # Saturday 01:00, Friday has no window
+    assert window.active_until(SATURDAY + 12 * HOUR) is None
+
+
+def test_interval_window_straddles_each_boundary():
+    window = TradingWindow("funding", every_hours=8, before_secs=60, after_secs=30)
+    assert window.active_until(SATURDAY + 8 * HOUR - 60) == SATURDAY + 8 * HOUR + 30
+    assert window.active_until(SATURDAY + 8 * HOUR + 29) == SATURDAY + 8 * HOUR + 30
+    assert window.active_until(SATURDAY + 8 * HOUR + 30) is None
+    assert window.active_until(SATURDAY + 8 * HOUR - 61) is None
+
+
+def test_blocking_window_picks_the_one_closing_last_for_the_symbol():
+    windows = [
+        TradingWindow("short", start=0, end=2 * HOUR),
+        TradingWindow("long", symbols=["sol_*"], start=0, end=4 * HOUR),
+        TradingWindow("other", symbols=["BTC"], start=0, end=6 * HOUR),
+    ]
+    found = blocking_window(windows, (SYMBOL, VENUE_SYMBOL), SATURDAY + HOUR)
+    assert found is not None and found[0].name == "long" and found[1] == SATURDAY + 4 * HOUR
+    assert blocking_window(windows, ("BTC",), SATURDAY + 5 * HOUR)[0].name == "other"
+
+
+@pytest.mark.asyncio
+async def test_no_trade_window_refuses_only_opening_orders(sim_stack):
+    always = TradingWindow("maintenance", symbols=[SYMBOL], start=0, end=parse_time_of_day("24:00"))
+    stack = sim_stack(windows=[always])
+    await stack.quote()
+    await _hold(stack, "2")
+
+    with pytest.raises(RiskViolationError, match="no-trade window maintenance"):
+        await _validate(stack, "1")
+    await _validate(stack, "1", is_ask=True)
+    await _validate(stack, "1", reduce_only=1)
+    assert stack.risk.blocking_window(SYMBOL) is always
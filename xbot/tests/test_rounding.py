@@ -0,0 +1,104 @@
+from __future__ import annotations
+
+import random
+from decimal import Decimal
+from typing import Tuple
+
+import pytest
+
+from xbot.connector.simulated import SimMarket, SimulatedConnector
+from xbot.execution.models import OrderState
+
+from .conftest import SYMBOL, VENUE_SYMBOL
+
+# Each seed draws one market's filters and CASES values against them; a failure names its seed and value
+SEEDS = range(12)
+CASES = 200
+
+
+def _venue(price_decimals: int, size_decimals: int, *, min_size_i: int = 1) -> SimulatedConnector:
+    market = SimMarket(
+        VENUE_SYMBOL, mark=Decimal("100"), price_decimals=price_decimals, size_decimals=size_decimals, min_size_i=min_size_i
+    )
+    return SimulatedConnector(markets=[market], collateral=Decimal("10000"))
+
+
+def _filters(rng: random.Random) -> Tuple[int, int, int]:
+    """(price_decimals, size_decimals, min_size_i) of a random market."""
+    return rng.randint(0, 8), rng.randint(0, 8), rng.choice([1, rng.randint(1, 10), rng.randint(1, 100_000)])
+
+
+def _value(rng: random.Random, decimals: int) -> Tuple[Decimal, int, int]:
+    """(value, whole steps, remainder): a random number of steps plus up to six digits past the step, exactly."""
+    steps = rng.choice([rng.randint(0, 10), rng.randint(0, 10**4), rng.randint(0, 10**9)])
+    extra = rng.randint(1, 6)
+    remainder = rng.choice([0, 1, 10**extra - 1, rng.randrange(10**extra)])
+    return Decimal(steps * 10**extra + remainder).scaleb(-(decimals + extra)), steps, remainder
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("seed", SEEDS)
+async def test_prices_land_on_a_tick_no_worse_than_requested(sim_stack, seed):
+    rng = random.Random(seed)
+    price_decimals, size_decimals, _ = _filters(rng)
+    stack = sim_stack(connector=_venue(price_decimals, size_decimals))
+    tick = Decimal(10) ** -price_decimals
+    for _ in range(CASES):
+        price, ticks, remainder = _value(rng, price_decimals)
+        is_ask = rng.random() < 0.5
+        price_i = await stack.market_data.to_price_i(SYMBOL, price, is_ask=is_ask)
+        rounded = Decimal(price_i) * tick
+        case = (seed, price, is_ask, rounded)
+        assert abs(rounded - price) < tick, case
+        if remainder == 0:
+            assert price_i == ticks, case
+        elif is_ask:
+            assert price_i == ticks + 1, case  # an ask never sells for less
+        else:
+            assert price_i == ticks, case  # a bid never pays more
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("seed", SEEDS)
+async def test_sizes_truncate_to_a_step_and_never_exceed_the_request(sim_stack, seed):
+    rng = random.Random(seed)
+    price_decimals, size_decimals, min_size_i = _filters(rng)
+    stack = sim_stack(connector=_venue(price_decimals, size_decimals, min_size_i=min_size_i))
+    step = Decimal(10) ** -size_decimals
+    for _ in range(CASES):
+        size, steps, _ = _value(rng, size_decimals)
+        size_i = await stack.market_data.to_size_i(SYMBOL, size)
+        rounded = Decimal(size_i) * step
+        assert size_i == steps and rounded <= size and size - rounded < step, (seed, size, rounded)
+        # A size truncated under the venue minimum is refused, never rounded up to it
+        if size_i < min_size_i:
+            with pytest.raises(ValueError):
+                await stack.market_data.ensure_min_size(SYMBOL, size_i)
+        else:
+            await stack.market_data.ensure_min_size(SYMBOL, size_i)
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("min_size_i", [1, 10, 250])
+async def test_sizes_below_the_venue_minimum_are_rejected_not_rounded_up(sim_stack, min_size_i):
+    stack = sim_stack(connector=_venue(2, 3, min_size_i=min_size_i))
+    for size_i in range(max(0, min_size_i - 3), min_size_i + 3):
+        if size_i < min_size_i:
+            with pytest.raises(ValueError):
+                await stack.market_data.ensure_min_size(SYMBOL, size_i)
+        else:
+            await stack.market_data.ensure_min_size(SYMBOL, size_i)
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("is_ask", [False, True])
+async def test_post_only_price_between_ticks_rests_instead_of_crossing(sim_stack, is_ask):
+    # Touch at 99.99 / 100.01; a price a fraction of a tick off the far touch must not round onto it
+    stack = sim_stack()
+    await stack.quote()
+    price = Decimal("99.9901") if is_ask else Decimal("100.0099")
+    order = await stack.orders.submit_limit(symbol=SYMBOL, is_ask=is_ask, size=Decimal("1"), price=price, post_only=True)
+
+    assert order.state not in (OrderState.CANCELLED, OrderState.FILLED)
+    (resting,) = await stack.connector.get_open_orders(VENUE_SYMBOL)
+    assert Decimal(resting["price"]) == Decimal("100.00")
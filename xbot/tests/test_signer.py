@@ -0,0 +1,105 @@
+from __future__ import annotations
+
+import asyncio
+import base64
+import contextlib
+import json
+from typing import Any, Dict, List
+
+import pytest
+from cryptography.hazmat.primitives.asymmetric import ed25519
+from cryptography.hazmat.primitives.serialization import Encoding, PublicFormat
+
+from xbot.connector.backpack import Account, AsyncHttpClient, DeferredSignature, SigningHttpClient
+from xbot.connector.signer import SignerError, UnixSocketSigner
+
+
+class FakeSigningService:
+    """A signing service on a Unix socket, answering each JSON line after `delay` seconds."""
+
+    def __init__(self, path: str, *, delay: float = 0.0, tamper: bool = False) -> None:
+        self.path = path
+        self.delay = delay
+        self.tamper = tamper
+        self.key = ed25519.Ed25519PrivateKey.generate()
+        raw = self.key.public_key().public_bytes(Encoding.Raw, PublicFormat.Raw)
+        self.public_key = base64.b64encode(raw).decode()
+        self.requests: List[Dict[str, Any]] = []
+        self._server: Any = None
+
+    async def __aenter__(self) -> "FakeSigningService":
+        self._server = await asyncio.start_unix_server(self._handle, path=self.path)
+        return self
+
+    async def __aexit__(self, *exc: Any) -> None:
+        self._server.close()
+        await self._server.wait_closed()
+
+    async def _handle(self, reader: asyncio.StreamReader, writer: asyncio.StreamWriter) -> None:
+        request = json.loads(await reader.readline())
+        self.requests.append(request)
+        await asyncio.sleep(self.delay)
+        message = base64.b64decode(request["message"])
+        signature = self.key.sign(b"tampered" if self.tamper else message)
+        with contextlib.suppress(ConnectionError):  # the client may have given up already
+            writer.write(json.dumps({"signature": base64.b64encode(signature).decode()}).encode() + b"\n")
+            await writer.drain()
+        writer.close()
+
+
+def _signer(service: FakeSigningService, timeout_secs: float = 2.0) -> UnixSocketSigner:
+    return UnixSocketSigner(socket_path=service.path, public_key=service.public_key, timeout_secs=timeout_secs)
+
+
+@pytest.mark.asyncio
+async def test_signing_does_not_block_the_event_loop(tmp_path):
+    async with FakeSigningService(str(tmp_path / "signer.sock"), delay=0.2) as service:
+        ticks = 0
+
+        async def tick() -> None:
+            nonlocal ticks
+            while True:
+                ticks += 1
+                await asyncio.sleep(0.01)
+
+        ticker = asyncio.create_task(tick())
+        signature = await _signer(service).sign("instruction=orderExecute&timestamp=1")
+        ticker.cancel()
+
+    service.key.public_key().verify(base64.b64decode(signature), b"instruction=orderExecute&timestamp=1")
+    assert ticks >= 10  # the loop kept running while the service took 200ms
+
+
+@pytest.mark.asyncio
+async def test_bad_and_slow_signers_raise_signer_error(tmp_path):
+    async with FakeSigningService(str(tmp_path / "bad.sock"), tamper=True) as service:
+        with pytest.raises(SignerError):
+            await _signer(service).sign("message")
+    async with FakeSigningService(str(tmp_path / "slow.sock"), delay=0.1) as service:
+        with pytest.raises(SignerError):
+            await _signer(service, timeout_secs=0.02).sign("message")
+        await asyncio.sleep(0.15)  # let the service finish its late reply
+    with pytest.raises(SignerError):
+        await _signer(service).sign("message")  # nothing listening any more
+
+
+@pytest.mark.asyncio
+async def test_rest_requests_are_signed_by_the_async_signer(tmp_path, monkeypatch):
+    sent: List[Dict[str, Any]] = []
+
+    async def fake_get(self, url, headers=None, params=None, timeout=None):
+        sent.append(headers)
+        return {}
+
+    monkeypatch.setattr(AsyncHttpClient, "get", fake_get)
+    async with FakeSigningService(str(tmp_path / "signer.sock")) as service:
+        signer = _signer(service)
+        account = Account(public_key=signer.public_key, secret_key=None, http_client=SigningHttpClient(signer, timeout=1.0))
+        account.signer = DeferredSignature
+        await account.get_balances()
+
+    (headers,) = sent
+    assert not isinstance(headers["X-Signature"], DeferredSignature)
+    message = base64.b64decode(service.requests[0]["message"])
+    service.key.public_key().verify(base64.b64decode(headers["X-Signature"]), message)
+    assert message.startswith(b"instruction=balanceQuery")
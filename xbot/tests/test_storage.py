@@ -0,0 +1,74 @@
+from __future__ import annotations
+
+from typing import Any, Mapping, Sequence
+
+import pytest
+
+from xbot.core.metrics import METRICS
+from xbot.storage.base import FILLS, StorageWriter
+from xbot.storage.jsonl import JsonlStorage
+
+
+class FlakyStorage(JsonlStorage):
+    """Fails every write while `down` is set."""
+
+    down = False
+
+    async def write(self, table: str, rows: Sequence[Mapping[str, Any]]) -> None:
+        if self.down:
+            raise ConnectionError("database unavailable")
+        await super().write(table, rows)
+
+
+async def _storage(tmp_path) -> FlakyStorage:
+    storage = FlakyStorage(tmp_path / "storage")
+    await storage.start()
+    return storage
+
+
+@pytest.mark.asyncio
+async def test_reads_only_return_the_writers_bot(tmp_path):
+    storage = await _storage(tmp_path)
+    ours, theirs = StorageWriter(storage, bot_id="sol-mm-1"), StorageWriter(storage, bot_id="sol-mm-2")
+    ours.enqueue(FILLS, {"symbol": "SOL", "size": "1"})
+    theirs.enqueue(FILLS, {"symbol": "SOL", "size": "2"})
+    await ours.flush()
+    await theirs.flush()
+
+    assert [row["size"] for row in await ours.storage.query(FILLS)] == ["1"]
+    assert [row["size"] for row in await ours.storage.query(FILLS, filters={"symbol": "SOL"})] == ["1"]
+    # An explicit bot_id filter reads another bot on purpose
+    assert [row["size"] for row in await ours.storage.query(FILLS, filters={"bot_id": "sol-mm-2"})] == ["2"]
+
+
+@pytest.mark.asyncio
+async def test_failed_batches_are_retried_in_order(tmp_path):
+    storage = await _storage(tmp_path)
+    writer = StorageWriter(storage)
+    storage.down = True
+    writer.enqueue(FILLS, {"ts": 1.0, "size": "1"})
+    await writer.flush()
+    writer.enqueue(FILLS, {"ts": 2.0, "size": "2"})
+    await writer.flush()
+    assert writer.pending_rows == 2
+
+    storage.down = False
+    await writer.flush()
+    assert writer.pending_rows == 0
+    assert [row["size"] for row in await writer.storage.query(FILLS)] == ["1", "2"]
+
+
+@pytest.mark.asyncio
+async def test_retry_buffer_drops_the_oldest_rows_past_its_bound(tmp_path):
+    storage = await _storage(tmp_path)
+    writer = StorageWriter(storage, max_buffered_rows=3)
+    before = METRICS.get("storage_rows_dropped", table=FILLS)
+    storage.down = True
+    for ts in range(5):
+        writer.enqueue(FILLS, {"ts": float(ts), "size": str(ts)})
+        await writer.flush()
+
+    storage.down = False
+    await writer.flush()
+    assert [row["size"] for row in await writer.storage.query(FILLS)] == ["2", "3", "4"]
+    assert METRICS.get("storage_rows_dropped", table=FILLS) == before + 2
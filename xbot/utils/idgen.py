@@ -1,5 +1,6 @@
 from __future__ import annotations
 
+import hashlib
 import itertools
 import secrets
 from typing import Iterable
@@ -24,4 +25,20 @@ class ClientOrderIdGenerator:
             yield self.next()
 
 
-__all__ = ["ClientOrderIdGenerator"]
+# Derived indices sit above the generator's default range and fit in 31 bits,
+# the narrowest client id the connectors accept
+_DERIVED_BASE = 1_000_000
+_DERIVED_SPAN = 2**31 - 1 - _DERIVED_BASE
+
+
+def client_order_index_for(key: str) -> int:
+    """Stable client order index for an idempotency key such as a command id.
+
+    Retrying with the same key reuses the same index, so the order service (and
+    the venue) can recognise the retry instead of placing a second order.
+    """
+    digest = hashlib.blake2b(key.encode("utf-8"), digest_size=8).digest()
+    return _DERIVED_BASE + int.from_bytes(digest, "big") % _DERIVED_SPAN
+
+
+__all__ = ["ClientOrderIdGenerator", "client_order_index_for"]
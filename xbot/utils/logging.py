@@ -8,6 +8,7 @@ import time
 from pathlib import Path
 from typing import Any, Dict, Optional
 
+from .secret import RedactingFilter
 
 DEFAULT_EXCLUDE = {
     "name",
@@ -59,7 +60,7 @@ class JsonFormatter(logging.Formatter):
         if extras:
             payload["extra"] = extras
         if record.exc_info:
-            payload["exc_info"] = self.formatException(record.exc_info)
+            payload["exc_info"] = record.exc_text or self.formatException(record.exc_info)
         return json.dumps(payload, ensure_ascii=True)
 
 
@@ -156,6 +157,11 @@ def setup_logging(level: str = "INFO") -> None:
     file_handler = logging.FileHandler(file_path, encoding="utf-8")
     file_handler.setFormatter(JsonFormatter())
 
+    # Registered secrets (API keys) are masked before either handler formats a record
+    redacting = RedactingFilter()
+    console.addFilter(redacting)
+    file_handler.addFilter(redacting)
+
     root.handlers.clear()
     root.addHandler(console)
     root.addHandler(file_handler)
@@ -0,0 +1,115 @@
+from __future__ import annotations
+
+import hmac
+import logging
+import threading
+from typing import Any, Optional, Set
+
+REDACTED = "***"
+
+# Every secret value loaded by this process; log records are scrubbed of them
+_registered: Set[str] = set()
+_lock = threading.Lock()
+_RECORD_FIELDS = frozenset(vars(logging.makeLogRecord({})))  # everything but `extra` keys
+
+
+def register_secret(value: Optional[str]) -> None:
+    """Have `redact` (and so every log line) mask `value` from now on."""
+    if value and len(value) >= 8:  # shorter values would mask ordinary text
+        with _lock:
+            _registered.add(value)
+
+
+def redact(text: str) -> str:
+    """`text` with every registered secret replaced by `REDACTED`."""
+    for value in sorted(_registered, key=len, reverse=True):
+        if value in text:
+            text = text.replace(value, REDACTED)
+    return text
+
+
+class Secret:
+    """A key held in a mutable buffer that is overwritten with zeros on `wipe()` or collection.
+
+    `repr`/`str` never show the value, copies share the one buffer instead of
+    duplicating the key, and the value is registered for log redaction. Only
+    `reveal()` returns it, for handing to a signer. Python cannot promise
+    the bytes leave memory entirely (the revealed `str` and any key object a
+    library builds from it are immutable copies), but the process keeps no
+    long-lived plain copy of its own.
+    """
+
+    __slots__ = ("_buf", "__weakref__")
+
+    def __init__(self, value: str) -> None:
+        self._buf = bytearray(value.encode("utf-8"))
+        register_secret(value)
+
+    def reveal(self) -> str:
+        if not self._buf:
+            raise ValueError("secret was wiped")
+        return self._buf.decode("utf-8")
+
+    def wipe(self) -> None:
+        for i in range(len(self._buf)):
+            self._buf[i] = 0
+        self._buf = bytearray()
+
+    def __bool__(self) -> bool:
+        return bool(self._buf)
+
+    def __eq__(self, other: object) -> bool:
+        if not isinstance(other, Secret):
+            return NotImplemented
+        return hmac.compare_digest(bytes(self._buf), bytes(other._buf))
+
+    def __hash__(self) -> int:
+        return id(self)
+
+    def __copy__(self) -> "Secret":
+        return self
+
+    def __deepcopy__(self, memo: Any) -> "Secret":
+        return self
+
+    def __reduce__(self) -> Any:
+        raise TypeError("secrets cannot be pickled")
+
+    def __repr__(self) -> str:
+        return f"Secret({REDACTED})"
+
+    __str__ = __repr__
+
+    def __del__(self) -> None:
+        self.wipe()
+
+
+class RedactingFilter(logging.Filter):
+    """Masks registered secrets in a record's message, extras and traceback before any handler formats it."""
+
+    def __init__(self, formatter: Optional[logging.Formatter] = None) -> None:
+        super().__init__()
+        self._formatter = formatter or logging.Formatter()
+
+    def filter(self, record: logging.LogRecord) -> bool:
+        if not _registered:
+            return True
+        record.msg = redact(record.getMessage())
+        record.args = None
+        for key, value in list(record.__dict__.items()):
+            if key in _RECORD_FIELDS or key.startswith("_"):
+                continue
+            if isinstance(value, str):
+                record.__dict__[key] = redact(value)
+            elif isinstance(value, (dict, list, tuple)):
+                text = repr(value)
+                if redact(text) != text:
+                    record.__dict__[key] = redact(text)
+        if record.exc_info and not record.exc_text:
+            record.exc_text = self._formatter.formatException(record.exc_info)
+        if record.exc_text:
+            record.exc_text = redact(record.exc_text)
+        return True
+
+
+__all__ = ["REDACTED", "RedactingFilter", "Secret", "redact", "register_secret"]